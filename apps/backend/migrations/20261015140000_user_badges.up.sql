@@ -0,0 +1,11 @@
+-- Badges awarded to users per season by the badge engine
+CREATE TABLE user_badges (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    season_id INT NOT NULL REFERENCES seasons(id) ON DELETE CASCADE,
+    badge_id TEXT NOT NULL,
+    awarded_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    UNIQUE(user_id, season_id, badge_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_badges_user_id ON user_badges(user_id);
@@ -0,0 +1,6 @@
+ALTER TABLE users DROP COLUMN IF EXISTS banned_at;
+
+DROP TABLE IF EXISTS reports;
+DROP TYPE IF EXISTS report_action;
+DROP TYPE IF EXISTS report_status;
+DROP TYPE IF EXISTS report_category;
@@ -0,0 +1,28 @@
+-- Player reports and the admin moderation queue
+CREATE TYPE report_category AS ENUM ('cheating', 'abuse', 'spam', 'other');
+CREATE TYPE report_status AS ENUM ('open', 'assigned', 'resolved', 'dismissed');
+CREATE TYPE report_action AS ENUM ('dismiss', 'warn', 'ban');
+
+CREATE TABLE reports (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    reporter_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    reported_user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    lobby_id UUID REFERENCES lobbies(id) ON DELETE SET NULL,
+    category report_category NOT NULL,
+    description TEXT,
+    status report_status NOT NULL DEFAULT 'open',
+    assigned_to UUID REFERENCES users(id) ON DELETE SET NULL,
+    action report_action,
+    resolution_note TEXT,
+    resolved_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    CHECK (reporter_id <> reported_user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_reports_status ON reports(status, created_at);
+CREATE INDEX IF NOT EXISTS idx_reports_reporter ON reports(reporter_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_reports_reported_user ON reports(reported_user_id);
+
+-- Set when a moderator bans the user; banned users can't sign in
+ALTER TABLE users ADD COLUMN IF NOT EXISTS banned_at TIMESTAMP;
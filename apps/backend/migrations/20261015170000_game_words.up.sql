@@ -0,0 +1,12 @@
+-- Valid words played in lexi_wars games, in turn order
+CREATE TABLE game_words (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    lobby_id UUID NOT NULL REFERENCES lobbies(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    word TEXT NOT NULL,
+    turn INT NOT NULL,
+    played_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    UNIQUE (lobby_id, turn)
+);
+
+CREATE INDEX IF NOT EXISTS idx_game_words_user ON game_words(user_id);
@@ -0,0 +1,2 @@
+-- First-place finishes per game, for win rates on profiles
+ALTER TABLE skill_ratings ADD COLUMN wins INT NOT NULL DEFAULT 0;
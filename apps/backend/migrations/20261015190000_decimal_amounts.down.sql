@@ -0,0 +1,6 @@
+ALTER TABLE lobby_refunds
+    ALTER COLUMN amount TYPE DOUBLE PRECISION;
+
+ALTER TABLE lobbies
+    ALTER COLUMN entry_amount TYPE DOUBLE PRECISION,
+    ALTER COLUMN current_amount TYPE DOUBLE PRECISION;
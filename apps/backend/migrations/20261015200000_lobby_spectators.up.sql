@@ -0,0 +1,2 @@
+-- Lobby creators can keep spectators out of competitive lobbies
+ALTER TABLE lobbies ADD COLUMN spectators_allowed BOOLEAN NOT NULL DEFAULT TRUE;
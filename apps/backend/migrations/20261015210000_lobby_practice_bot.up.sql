@@ -0,0 +1,3 @@
+-- Practice lobbies seat a bot opponent at the given difficulty
+CREATE TYPE bot_difficulty AS ENUM ('easy', 'medium', 'hard');
+ALTER TABLE lobbies ADD COLUMN practice_bot bot_difficulty;
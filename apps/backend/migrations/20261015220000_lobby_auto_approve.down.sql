@@ -0,0 +1 @@
+ALTER TABLE lobbies DROP COLUMN IF EXISTS auto_approve_trust_threshold;
@@ -0,0 +1,2 @@
+-- Private lobbies can admit join requests from users at or above this trust rating
+ALTER TABLE lobbies ADD COLUMN auto_approve_trust_threshold DOUBLE PRECISION;
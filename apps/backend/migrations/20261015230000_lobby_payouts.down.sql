@@ -0,0 +1,3 @@
+ALTER TABLE lobbies DROP COLUMN IF EXISTS payout_underfill;
+ALTER TABLE lobbies DROP COLUMN IF EXISTS payout_percents;
+DROP TYPE IF EXISTS payout_underfill;
@@ -0,0 +1,2 @@
+-- Set once a season has ended and been closed out by the rollover job
+ALTER TABLE seasons ADD COLUMN finalized_at TIMESTAMP;
@@ -0,0 +1,12 @@
+-- Periodic record of each user's leaderboard rank within a season
+CREATE TABLE rank_snapshots (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    season_id INT NOT NULL REFERENCES seasons(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    rank INT NOT NULL,
+    points DOUBLE PRECISION NOT NULL,
+    taken_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_rank_snapshots_season_user
+    ON rank_snapshots(season_id, user_id, taken_at);
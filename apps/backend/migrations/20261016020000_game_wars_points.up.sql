@@ -0,0 +1,12 @@
+-- Wars points each user earned per game within a season, for per-game leaderboards
+CREATE TABLE user_game_wars_points (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    season_id INT NOT NULL REFERENCES seasons(id) ON DELETE CASCADE,
+    game_id UUID NOT NULL REFERENCES games(id) ON DELETE CASCADE,
+    points DOUBLE PRECISION NOT NULL DEFAULT 0,
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, season_id, game_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_game_wars_points_leaderboard
+    ON user_game_wars_points(season_id, game_id, points DESC);
@@ -0,0 +1 @@
+ALTER TABLE lobbies DROP COLUMN IF EXISTS anonymize_players;
@@ -0,0 +1,2 @@
+-- Hosts can hide player identities from spectators until the game finishes
+ALTER TABLE lobbies ADD COLUMN anonymize_players BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,2 @@
+-- Seasons track their last change like the other editable tables
+ALTER TABLE seasons ADD COLUMN updated_at TIMESTAMP NOT NULL DEFAULT NOW();
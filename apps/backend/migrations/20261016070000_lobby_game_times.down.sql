@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_lobbies_finished_at;
+ALTER TABLE lobbies DROP COLUMN IF EXISTS finished_at;
+ALTER TABLE lobbies DROP COLUMN IF EXISTS started_at;
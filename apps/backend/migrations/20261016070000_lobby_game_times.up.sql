@@ -0,0 +1,5 @@
+-- When each lobby's game started and finished, for platform stats
+ALTER TABLE lobbies ADD COLUMN started_at TIMESTAMP;
+ALTER TABLE lobbies ADD COLUMN finished_at TIMESTAMP;
+
+CREATE INDEX IF NOT EXISTS idx_lobbies_finished_at ON lobbies(finished_at) WHERE finished_at IS NOT NULL;
@@ -0,0 +1,19 @@
+-- Contract calls submitted from the server account, and the ones that failed
+-- after every retry
+CREATE TABLE server_transactions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- Contract function called
+    function TEXT NOT NULL,
+    -- Nonce of the last attempt; NULL when the node never gave one
+    nonce BIGINT,
+    -- Set once the node accepted the transaction
+    tx_id TEXT,
+    status TEXT NOT NULL CHECK (status IN ('submitted', 'failed')),
+    attempts INT NOT NULL,
+    -- Why the last attempt failed
+    error TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_server_transactions_failed ON server_transactions(created_at)
+    WHERE status = 'failed';
@@ -0,0 +1 @@
+ALTER TABLE wars_point_awards DROP COLUMN IF EXISTS active_players;
@@ -0,0 +1,4 @@
+-- Players still in the game when an award was made, which a sponsor's bonus
+-- is counted from, so recomputing an award uses the same count. NULL for
+-- awards recorded before it was kept.
+ALTER TABLE wars_point_awards ADD COLUMN active_players INT;
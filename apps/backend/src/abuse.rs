@@ -0,0 +1,115 @@
+// IP abuse tracking: sliding-window violation counts and temporary bans (Redis)
+
+use chrono::Utc;
+use redis::AsyncCommands;
+use uuid::Uuid;
+
+use crate::{
+    errors::AppError,
+    models::RedisKey,
+    state::{AbuseConfig, RedisClient},
+};
+
+/// How long past bans are remembered for backoff after the last one ends
+const OFFENSE_MEMORY_SECS: u64 = 24 * 60 * 60;
+
+/// What an IP did to earn a violation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Violation {
+    /// Request rejected for missing, invalid or revoked credentials
+    FailedAuth,
+    /// Request rejected by the rate limiter
+    RateLimited,
+}
+
+/// Redis-backed tracker of violations and temporary bans per IP.
+#[derive(Clone)]
+pub struct AbuseTracker {
+    redis: RedisClient,
+    config: AbuseConfig,
+}
+
+impl AbuseTracker {
+    pub fn new(redis: RedisClient, config: AbuseConfig) -> Self {
+        Self { redis, config }
+    }
+
+    /// Seconds left on the IP's ban, or `None` if it isn't banned.
+    pub async fn ban_remaining(&self, ip: &str) -> Result<Option<u64>, AppError> {
+        let mut conn = self.redis.get().await?;
+
+        let ttl: i64 = conn
+            .ttl(RedisKey::abuse_ban(ip))
+            .await
+            .map_err(AppError::RedisCommandError)?;
+
+        // -2: no ban; -1 shouldn't happen, but never ban forever
+        Ok((ttl > 0).then_some(ttl as u64))
+    }
+
+    /// Record a violation. Returns the ban length if this one crossed the threshold.
+    ///
+    /// Violations are counted over a sliding window; a ban clears the window
+    /// and each further ban within `OFFENSE_MEMORY_SECS` doubles in length.
+    pub async fn record_violation(
+        &self,
+        ip: &str,
+        violation: Violation,
+    ) -> Result<Option<u64>, AppError> {
+        let mut conn = self.redis.get().await?;
+
+        let key = RedisKey::abuse_violations(ip);
+        let now_ms = Utc::now().timestamp_millis();
+        let window_start = now_ms - (self.config.window_secs * 1000) as i64;
+
+        // Unique member so concurrent violations in the same millisecond all count
+        let member = format!("{}:{}", now_ms, Uuid::new_v4());
+        let (count,): (u32,) = redis::pipe()
+            .atomic()
+            .zrembyscore(&key, "-inf", window_start)
+            .ignore()
+            .zadd(&key, member, now_ms)
+            .ignore()
+            .expire(&key, self.config.window_secs as i64)
+            .ignore()
+            .zcard(&key)
+            .query_async(&mut *conn)
+            .await
+            .map_err(AppError::RedisCommandError)?;
+
+        tracing::debug!("abuse: {:?} from {} ({} in window)", violation, ip, count);
+
+        if count < self.config.threshold {
+            return Ok(None);
+        }
+
+        let offenses_key = RedisKey::abuse_offenses(ip);
+        let offense: u32 = conn
+            .incr(&offenses_key, 1)
+            .await
+            .map_err(AppError::RedisCommandError)?;
+        let ban_secs = self.config.ban_duration(offense);
+
+        let _: () = redis::pipe()
+            .atomic()
+            .set_ex(RedisKey::abuse_ban(ip), offense, ban_secs)
+            .ignore()
+            .expire(&offenses_key, (ban_secs + OFFENSE_MEMORY_SECS) as i64)
+            .ignore()
+            .del(&key)
+            .ignore()
+            .query_async(&mut *conn)
+            .await
+            .map_err(AppError::RedisCommandError)?;
+
+        tracing::warn!(
+            "abuse: banned {} for {}s (offense {}, last violation {:?})",
+            ip,
+            ban_secs,
+            offense,
+            violation
+        );
+
+        Ok(Some(ban_secs))
+    }
+}
@@ -0,0 +1,183 @@
+// Announcements: platform-wide notices pushed to every connected client
+//
+// An announcement is fanned out to every socket, lobby list and room alike,
+// when it is posted. It is also kept in Redis until it expires, so clients
+// connecting later are sent whatever is still active.
+
+use chrono::{DateTime, Utc};
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    errors::AppError, models::RedisKey, state::AppState, state::RedisClient, ws::broadcast,
+    ws::lobby::LobbyServerMessage,
+};
+
+/// Longest announcement text accepted
+pub const MAX_ANNOUNCEMENT_LENGTH: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnnouncementLevel {
+    Info,
+    Warning,
+    Critical,
+}
+
+/// A notice shown to every client until `expires_at`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Announcement {
+    pub id: Uuid,
+    pub level: AnnouncementLevel,
+    pub message: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl Announcement {
+    /// Validate and build a new announcement.
+    pub fn new(
+        level: AnnouncementLevel,
+        message: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<Self, AppError> {
+        let message = message.trim();
+        if message.is_empty() {
+            return Err(AppError::BadRequest(
+                "Announcement message cannot be empty".into(),
+            ));
+        }
+        if message.chars().count() > MAX_ANNOUNCEMENT_LENGTH {
+            return Err(AppError::BadRequest(format!(
+                "Announcement message cannot exceed {} characters",
+                MAX_ANNOUNCEMENT_LENGTH
+            )));
+        }
+
+        let now = Utc::now();
+        if expires_at <= now {
+            return Err(AppError::BadRequest(
+                "Announcement must expire in the future".into(),
+            ));
+        }
+
+        Ok(Self {
+            id: Uuid::new_v4(),
+            level,
+            message: message.to_string(),
+            created_at: now,
+            expires_at,
+        })
+    }
+}
+
+/// Redis-backed store of active announcements, shared by every instance.
+#[derive(Clone)]
+pub struct Announcements {
+    redis: RedisClient,
+}
+
+impl Announcements {
+    pub fn new(redis: RedisClient) -> Self {
+        Self { redis }
+    }
+
+    /// Store an announcement until it expires.
+    pub async fn save(&self, announcement: &Announcement) -> Result<(), AppError> {
+        let raw = serde_json::to_string(announcement).map_err(|e| {
+            AppError::Serialization(format!("Failed to encode announcement: {}", e))
+        })?;
+
+        let mut conn = self.redis.get().await?;
+        let _: () = conn
+            .zadd(
+                RedisKey::announcements(),
+                raw,
+                announcement.expires_at.timestamp_millis(),
+            )
+            .await
+            .map_err(AppError::RedisCommandError)?;
+        Ok(())
+    }
+
+    /// Announcements that haven't expired, oldest first. Expired ones are dropped.
+    pub async fn active(&self) -> Result<Vec<Announcement>, AppError> {
+        let key = RedisKey::announcements();
+        let now = Utc::now().timestamp_millis();
+        let mut conn = self.redis.get().await?;
+
+        let _: () = conn
+            .zrembyscore(&key, "-inf", now)
+            .await
+            .map_err(AppError::RedisCommandError)?;
+        let raw: Vec<String> = conn
+            .zrange(&key, 0, -1)
+            .await
+            .map_err(AppError::RedisCommandError)?;
+
+        let mut active: Vec<Announcement> = raw
+            .iter()
+            .filter_map(|raw| match serde_json::from_str(raw) {
+                Ok(announcement) => Some(announcement),
+                Err(e) => {
+                    tracing::warn!("Skipping unreadable announcement: {}", e);
+                    None
+                }
+            })
+            .collect();
+        active.sort_by_key(|a| a.created_at);
+        Ok(active)
+    }
+}
+
+/// Store an announcement and push it to every open connection.
+pub async fn announce(state: &AppState, announcement: Announcement) -> Result<(), AppError> {
+    Announcements::new(state.redis.clone())
+        .save(&announcement)
+        .await?;
+
+    // Room sockets take the same shape, so one message serves both
+    broadcast::broadcast_all(state, &LobbyServerMessage::Announcement(announcement)).await;
+    Ok(())
+}
+
+/// Active announcements to send a connection that just opened; empty if
+/// they can't be read.
+pub async fn active_for_new_connection(state: &AppState) -> Vec<Announcement> {
+    Announcements::new(state.redis.clone())
+        .active()
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("Failed to load announcements: {}", e);
+            Vec::new()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_announcement_is_trimmed() {
+        let expires_at = Utc::now() + Duration::hours(1);
+        let announcement = Announcement::new(
+            AnnouncementLevel::Info,
+            "  Tournament tonight  ",
+            expires_at,
+        )
+        .unwrap();
+        assert_eq!(announcement.message, "Tournament tonight");
+    }
+
+    #[test]
+    fn test_announcement_rejects_empty_or_expired() {
+        let later = Utc::now() + Duration::hours(1);
+        assert!(Announcement::new(AnnouncementLevel::Info, "   ", later).is_err());
+
+        let earlier = Utc::now() - Duration::seconds(1);
+        assert!(Announcement::new(AnnouncementLevel::Warning, "Late", earlier).is_err());
+    }
+}
@@ -0,0 +1,84 @@
+// Sign-in challenges: single-use nonces a wallet signs to prove it's theirs
+//
+// `issue` stores a random nonce against the wallet for `CHALLENGE_TTL_SECS`
+// and returns the exact message to sign. `consume` takes it back out with
+// GETDEL, so a nonce answers at most one verify call, right or wrong.
+
+use chrono::Utc;
+use rand::RngCore;
+use redis::AsyncCommands;
+use serde::Serialize;
+
+use crate::{
+    errors::AppError,
+    models::{RedisKey, WalletAddress},
+    state::RedisClient,
+};
+
+/// How long a challenge can be answered
+pub const CHALLENGE_TTL_SECS: u64 = 5 * 60;
+
+/// A challenge as handed to the client.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthChallenge {
+    pub nonce: String,
+    /// The text the wallet signs
+    pub message: String,
+    pub expires_at: i64,
+}
+
+/// The text a wallet signs to answer `nonce`.
+pub fn challenge_message(wallet: &WalletAddress, nonce: &str) -> String {
+    format!(
+        "Sign in to Stacks Wars\n\nWallet: {}\nNonce: {}",
+        wallet.as_str(),
+        nonce
+    )
+}
+
+/// Issue a fresh challenge for `wallet`.
+pub async fn issue(redis: &RedisClient, wallet: &WalletAddress) -> Result<AuthChallenge, AppError> {
+    let mut bytes = [0u8; 32];
+    rand::rng().fill_bytes(&mut bytes);
+    let nonce = hex::encode(bytes);
+
+    let mut conn = redis.get().await?;
+    let _: () = conn
+        .set_ex(
+            RedisKey::auth_challenge(&nonce),
+            wallet.as_str(),
+            CHALLENGE_TTL_SECS,
+        )
+        .await
+        .map_err(AppError::RedisCommandError)?;
+
+    Ok(AuthChallenge {
+        message: challenge_message(wallet, &nonce),
+        expires_at: Utc::now().timestamp() + CHALLENGE_TTL_SECS as i64,
+        nonce,
+    })
+}
+
+/// Use up `nonce`, failing unless it's outstanding and was issued to `wallet`.
+pub async fn consume(
+    redis: &RedisClient,
+    wallet: &WalletAddress,
+    nonce: &str,
+) -> Result<(), AppError> {
+    let mut conn = redis.get().await?;
+    let issued_to: Option<String> = conn
+        .get_del(RedisKey::auth_challenge(nonce))
+        .await
+        .map_err(AppError::RedisCommandError)?;
+
+    match issued_to {
+        Some(issued_to) if issued_to == wallet.as_str() => Ok(()),
+        Some(_) => Err(AppError::Unauthorized(
+            "Challenge was issued to another wallet".into(),
+        )),
+        None => Err(AppError::Unauthorized(
+            "Challenge is expired or already used".into(),
+        )),
+    }
+}
@@ -3,11 +3,13 @@ use axum::{
     http::{StatusCode, request::Parts},
 };
 use axum_extra::extract::cookie::CookieJar;
-use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode};
-use redis::AsyncCommands;
 
-use super::jwt::Claims;
-use crate::{models::keys::RedisKey, state::RedisClient};
+use super::jwt::{Claims, JwtKeys, decode_jwt};
+use crate::{
+    models::keys::RedisKey,
+    state::{AppState, RedisClient},
+    wallet_access::WalletAccess,
+};
 
 /// WebSocket auth extractor: optional.
 pub struct WsAuth(pub Option<AuthClaims>);
@@ -26,10 +28,11 @@ impl FromRequestParts<crate::state::AppState> for WsAuth {
         };
         if let Some(cookie) = jar.get("auth_token") {
             let token = cookie.value();
-            let secret = state.config.jwt_secret.clone();
             if let Ok(claims) =
-                AuthClaims::from_token_with_secret(token, &secret, &state.redis).await
+                AuthClaims::from_token_with_keys(token, &state.config.jwt_keys, &state.redis).await
             {
+                // A denied wallet is refused rather than downgraded to anonymous
+                check_wallet_access(state, &claims).await?;
                 return Ok(WsAuth(Some(claims)));
             }
         }
@@ -65,11 +68,24 @@ impl FromRequestParts<crate::state::AppState> for AuthClaims {
         ))?;
 
         let token = cookie.value();
-        let secret = state.config.jwt_secret.clone();
-        AuthClaims::from_token_with_secret(token, &secret, &state.redis).await
+        let claims =
+            AuthClaims::from_token_with_keys(token, &state.config.jwt_keys, &state.redis).await?;
+        check_wallet_access(state, &claims).await?;
+        Ok(claims)
     }
 }
 
+/// Refuse wallets the access lists keep out.
+async fn check_wallet_access(
+    state: &AppState,
+    claims: &AuthClaims,
+) -> Result<(), (StatusCode, String)> {
+    WalletAccess::from_state(state)
+        .check(claims.wallet_address())
+        .await
+        .map_err(|e| e.to_response())
+}
+
 impl AuthClaims {
     /// Create AuthClaims from a JWT token string
     pub async fn from_token(
@@ -83,26 +99,22 @@ impl AuthClaims {
             )
         })?;
 
-        AuthClaims::from_token_with_secret(token, &secret, redis).await
+        let keys =
+            JwtKeys::from_env(&secret).map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+        AuthClaims::from_token_with_keys(token, &keys, redis).await
     }
 
-    pub async fn from_token_with_secret(
+    pub async fn from_token_with_keys(
         token: &str,
-        secret: &str,
+        keys: &JwtKeys,
         redis: &RedisClient,
     ) -> Result<Self, (StatusCode, String)> {
-        let token_data = decode::<Claims>(
-            token,
-            &DecodingKey::from_secret(secret.as_bytes()),
-            &Validation::new(Algorithm::HS256),
-        )
-        .map_err(|e| {
+        let claims = decode_jwt(token, keys).map_err(|e| {
             tracing::warn!("JWT validation failed: {}", e);
             (StatusCode::UNAUTHORIZED, "Invalid or expired token".into())
         })?;
 
-        let claims = token_data.claims;
-
         // Check if token is revoked
         let jti = claims.jti();
         let key = RedisKey::revoked_token(jti);
@@ -115,19 +127,31 @@ impl AuthClaims {
             )
         })?;
 
-        let is_revoked: Option<bool> = conn.get(&key).await.map_err(|e| {
-            tracing::error!("Failed to check token revocation status: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Authentication check failed".to_string(),
-            )
-        })?;
+        let sessions_key = RedisKey::user_sessions_revoked(claims.sub.as_str());
+        let (is_revoked, revoked_at): (Option<bool>, Option<i64>) = redis::pipe()
+            .get(&key)
+            .get(&sessions_key)
+            .query_async(&mut *conn)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to check token revocation status: {}", e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Authentication check failed".to_string(),
+                )
+            })?;
 
         if is_revoked.is_some() {
             tracing::warn!("Attempted use of revoked token: {}", jti);
             return Err((StatusCode::UNAUTHORIZED, "Token has been revoked".into()));
         }
 
+        // All of the user's sessions were revoked (e.g. account deleted)
+        if revoked_at.is_some_and(|cutoff| claims.iat <= cutoff) {
+            tracing::warn!("Attempted use of token from revoked session: {}", jti);
+            return Err((StatusCode::UNAUTHORIZED, "Token has been revoked".into()));
+        }
+
         Ok(Self(claims))
     }
 
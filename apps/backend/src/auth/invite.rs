@@ -0,0 +1,113 @@
+// Lobby invite tokens: signed (HS256) bearer tokens pointing at an invite record
+//
+// Invites are signed with their own secret (`INVITE_SECRET`), not the auth
+// keys, so rotating the auth keys leaves outstanding invites valid and a leaked
+// invite key can't forge sessions.
+
+use chrono::Utc;
+use jsonwebtoken::{
+    Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, encode, errors::ErrorKind,
+};
+use uuid::Uuid;
+
+use crate::{
+    errors::AppError,
+    models::{InviteError, LobbyInvite},
+};
+
+/// Invite token claims.
+///
+/// Uses/revocation are not encoded here; they are checked against the Redis
+/// record on redemption.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct InviteClaims {
+    /// Lobby the invite grants access to
+    pub lobby_id: Uuid,
+    /// Invite record ID
+    pub invite_id: Uuid,
+    /// Issued at timestamp (seconds since Unix epoch)
+    pub iat: i64,
+    /// Expiration timestamp, omitted for invites without expiry
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exp: Option<i64>,
+}
+
+/// Read the invite signing secret from `INVITE_SECRET`.
+pub fn invite_secret_from_env() -> Result<String, String> {
+    let secret = std::env::var("INVITE_SECRET").map_err(|_| "INVITE_SECRET is not set")?;
+    if secret.len() < 32 {
+        return Err("INVITE_SECRET must be at least 32 characters for security".to_string());
+    }
+    Ok(secret)
+}
+
+/// Sign an invite token for the given invite record
+pub fn generate_invite_token(invite: &LobbyInvite, secret: &str) -> Result<String, AppError> {
+    let claims = InviteClaims {
+        lobby_id: invite.lobby_id,
+        invite_id: invite.invite_id,
+        iat: Utc::now().timestamp(),
+        exp: invite.expires_at,
+    };
+
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(secret.as_bytes()),
+    )
+    .map_err(AppError::JwtError)
+}
+
+/// Verify an invite token's signature (and expiry, when present)
+pub fn decode_invite_token(token: &str, secret: &str) -> Result<InviteClaims, InviteError> {
+    let mut validation = Validation::new(Algorithm::HS256);
+    validation.required_spec_claims.clear();
+
+    decode::<InviteClaims>(
+        token,
+        &DecodingKey::from_secret(secret.as_bytes()),
+        &validation,
+    )
+    .map(|data| data.claims)
+    .map_err(|e| match e.kind() {
+        ErrorKind::ExpiredSignature => InviteError::Expired,
+        _ => InviteError::InvalidToken,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "stacks_wars_deep_and_hidden_secret";
+
+    #[test]
+    fn test_invite_token_roundtrip() {
+        let invite = LobbyInvite::new(Uuid::new_v4(), Uuid::new_v4(), Some(1), None);
+        let token = generate_invite_token(&invite, SECRET).unwrap();
+
+        let claims = decode_invite_token(&token, SECRET).unwrap();
+        assert_eq!(claims.lobby_id, invite.lobby_id);
+        assert_eq!(claims.invite_id, invite.invite_id);
+        assert!(claims.exp.is_none());
+    }
+
+    #[test]
+    fn test_invite_token_wrong_secret_rejected() {
+        let invite = LobbyInvite::new(Uuid::new_v4(), Uuid::new_v4(), None, Some(600));
+        let token = generate_invite_token(&invite, SECRET).unwrap();
+
+        let err = decode_invite_token(&token, "another_secret_that_is_long_enough__").unwrap_err();
+        assert_eq!(err, InviteError::InvalidToken);
+    }
+
+    #[test]
+    fn test_expired_invite_token_rejected() {
+        let mut invite = LobbyInvite::new(Uuid::new_v4(), Uuid::new_v4(), None, None);
+        invite.expires_at = Some(Utc::now().timestamp() - 3600);
+        let token = generate_invite_token(&invite, SECRET).unwrap();
+
+        let err = decode_invite_token(&token, SECRET).unwrap_err();
+        assert_eq!(err, InviteError::Expired);
+    }
+}
@@ -1,10 +1,19 @@
-// JWT utilities: token generation and validation (HS256, claims, expiry)
+// JWT utilities: token generation and validation (HS256/RS256 keys with rotation, claims, expiry)
+
+use std::fmt;
 
 use chrono::{Duration, Utc};
-use jsonwebtoken::{encode, EncodingKey, Header};
+use jsonwebtoken::{
+    Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, decode_header, encode,
+};
+use redis::AsyncCommands;
 use uuid::Uuid;
 
-use crate::{errors::AppError, models::User};
+use crate::{
+    errors::AppError,
+    models::{RedisKey, User},
+    state::RedisClient,
+};
 
 /// JWT Claims structure
 ///
@@ -53,16 +62,10 @@ impl Claims {
     }
 }
 
-/// Generate JWT token for user authentication
-pub fn generate_jwt(user: &User, secret: &str) -> Result<String, AppError> {
-    // Validate provided secret meets requirements
-    validate_jwt_secret(secret)?;
-
+/// Generate JWT token for user authentication, signed with the current key
+pub fn generate_jwt(user: &User, keys: &JwtKeys) -> Result<String, AppError> {
     let now = Utc::now();
-    let expiry_days = std::env::var("TOKEN_EXPIRY_DAYS")
-        .ok()
-        .and_then(|s| s.parse::<i64>().ok())
-        .unwrap_or(7);
+    let expiry_days = token_expiry_days();
 
     let claims = Claims {
         sub: user.id().to_string(),
@@ -72,12 +75,228 @@ pub fn generate_jwt(user: &User, secret: &str) -> Result<String, AppError> {
         jti: Uuid::new_v4().to_string(),
     };
 
-    encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(secret.as_bytes()),
-    )
-    .map_err(AppError::JwtError)
+    keys.sign(&claims)
+}
+
+/// Verify a token's signature and expiry and return its claims.
+///
+/// Does not check revocation; use `AuthClaims` for that.
+pub fn decode_jwt(token: &str, keys: &JwtKeys) -> Result<Claims, AppError> {
+    keys.verify(token)
+}
+
+/// A key tokens verify against.
+#[derive(Clone)]
+struct VerifyingKey {
+    kid: String,
+    algorithm: Algorithm,
+    key: DecodingKey,
+}
+
+impl VerifyingKey {
+    fn decode(&self, token: &str) -> Result<Claims, AppError> {
+        decode::<Claims>(token, &self.key, &Validation::new(self.algorithm))
+            .map(|data| data.claims)
+            .map_err(AppError::JwtError)
+    }
+}
+
+/// The keys auth tokens are signed and verified with.
+///
+/// Tokens are signed with the current key and carry its `kid` in the header.
+/// Verification picks the key by `kid`, so tokens signed with a previous key
+/// keep working until they expire or that key is dropped from config. Tokens
+/// without a `kid` predate key ids and may have been signed with any key still
+/// accepted, so each is tried, current first.
+#[derive(Clone)]
+pub struct JwtKeys {
+    current: VerifyingKey,
+    signing: EncodingKey,
+    previous: Vec<VerifyingKey>,
+}
+
+impl fmt::Debug for JwtKeys {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JwtKeys")
+            .field("current", &self.current.kid)
+            .field("algorithm", &self.current.algorithm)
+            .field(
+                "previous",
+                &self.previous.iter().map(|k| &k.kid).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl JwtKeys {
+    /// Sign with a shared HS256 secret of at least 32 characters.
+    pub fn hs256(kid: &str, secret: &str) -> Result<Self, AppError> {
+        validate_jwt_secret(secret)?;
+        Ok(Self {
+            current: VerifyingKey {
+                kid: kid.to_string(),
+                algorithm: Algorithm::HS256,
+                key: DecodingKey::from_secret(secret.as_bytes()),
+            },
+            signing: EncodingKey::from_secret(secret.as_bytes()),
+            previous: Vec::new(),
+        })
+    }
+
+    /// Sign with an RSA private key; other services verify with the public key.
+    pub fn rs256(kid: &str, private_pem: &[u8], public_pem: &[u8]) -> Result<Self, AppError> {
+        Ok(Self {
+            current: VerifyingKey {
+                kid: kid.to_string(),
+                algorithm: Algorithm::RS256,
+                key: DecodingKey::from_rsa_pem(public_pem)?,
+            },
+            signing: EncodingKey::from_rsa_pem(private_pem)?,
+            previous: Vec::new(),
+        })
+    }
+
+    /// Keep accepting tokens signed with a retired HS256 secret.
+    pub fn with_previous_hs256(self, kid: &str, secret: &str) -> Result<Self, AppError> {
+        validate_jwt_secret(secret)?;
+        self.with_previous(VerifyingKey {
+            kid: kid.to_string(),
+            algorithm: Algorithm::HS256,
+            key: DecodingKey::from_secret(secret.as_bytes()),
+        })
+    }
+
+    /// Keep accepting tokens signed with a retired RSA key.
+    pub fn with_previous_rs256(self, kid: &str, public_pem: &[u8]) -> Result<Self, AppError> {
+        self.with_previous(VerifyingKey {
+            kid: kid.to_string(),
+            algorithm: Algorithm::RS256,
+            key: DecodingKey::from_rsa_pem(public_pem)?,
+        })
+    }
+
+    fn with_previous(mut self, key: VerifyingKey) -> Result<Self, AppError> {
+        if self.key(&key.kid).is_some() {
+            return Err(AppError::EnvError(format!(
+                "JWT key id '{}' is used twice",
+                key.kid
+            )));
+        }
+        self.previous.push(key);
+        Ok(self)
+    }
+
+    /// Keys from config.
+    ///
+    /// `JWT_ALGORITHM` picks HS256 (default, signing with `jwt_secret`) or
+    /// RS256 (the PEM files in `JWT_RSA_PRIVATE_KEY_FILE` and
+    /// `JWT_RSA_PUBLIC_KEY_FILE`); `JWT_KEY_ID` names the key. Retired keys
+    /// still accepted are listed as `kid=secret` in `JWT_PREVIOUS_KEYS` and
+    /// `kid=public-key-file` in `JWT_PREVIOUS_RSA_KEYS`.
+    pub fn from_env(jwt_secret: &str) -> Result<Self, String> {
+        let kid = std::env::var("JWT_KEY_ID").unwrap_or_else(|_| "primary".to_string());
+        let read_pem = |var: &str, path: &str| {
+            std::fs::read(path).map_err(|e| format!("{}: can't read '{}': {}", var, path, e))
+        };
+        let env_file = |var: &str| {
+            let path = std::env::var(var).map_err(|_| format!("{} must be set for RS256", var))?;
+            read_pem(var, &path)
+        };
+
+        let algorithm = std::env::var("JWT_ALGORITHM").unwrap_or_else(|_| "HS256".to_string());
+        let mut keys = match algorithm.trim() {
+            "HS256" => Self::hs256(&kid, jwt_secret),
+            "RS256" => Self::rs256(
+                &kid,
+                &env_file("JWT_RSA_PRIVATE_KEY_FILE")?,
+                &env_file("JWT_RSA_PUBLIC_KEY_FILE")?,
+            ),
+            other => return Err(format!("JWT_ALGORITHM: unsupported algorithm '{}'", other)),
+        }
+        .map_err(|e| e.to_string())?;
+
+        for (var, rsa) in [
+            ("JWT_PREVIOUS_KEYS", false),
+            ("JWT_PREVIOUS_RSA_KEYS", true),
+        ] {
+            let spec = std::env::var(var).unwrap_or_default();
+            for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+                let (kid, value) = entry
+                    .split_once('=')
+                    .ok_or_else(|| format!("{}: expected kid=value, got '{}'", var, entry))?;
+                keys = if rsa {
+                    keys.with_previous_rs256(kid, &read_pem(var, value)?)
+                } else {
+                    keys.with_previous_hs256(kid, value)
+                }
+                .map_err(|e| format!("{}: {}", var, e))?;
+            }
+        }
+        Ok(keys)
+    }
+
+    /// `kid` of the key new tokens are signed with
+    pub fn current_kid(&self) -> &str {
+        &self.current.kid
+    }
+
+    fn key(&self, kid: &str) -> Option<&VerifyingKey> {
+        std::iter::once(&self.current)
+            .chain(&self.previous)
+            .find(|key| key.kid == kid)
+    }
+
+    fn sign(&self, claims: &Claims) -> Result<String, AppError> {
+        let mut header = Header::new(self.current.algorithm);
+        header.kid = Some(self.current.kid.clone());
+        encode(&header, claims, &self.signing).map_err(AppError::JwtError)
+    }
+
+    fn verify(&self, token: &str) -> Result<Claims, AppError> {
+        let header = decode_header(token)?;
+        match &header.kid {
+            Some(kid) => self
+                .key(kid)
+                .ok_or_else(|| AppError::Unauthorized(format!("Unknown signing key '{}'", kid)))?
+                .decode(token),
+            // The current key's error stands if no key verifies it
+            None => self
+                .previous
+                .iter()
+                .fold(self.current.decode(token), |result, key| {
+                    result.or_else(|e| key.decode(token).map_err(|_| e))
+                }),
+        }
+    }
+}
+
+/// Token lifetime in days (`TOKEN_EXPIRY_DAYS`, default 7)
+pub fn token_expiry_days() -> i64 {
+    std::env::var("TOKEN_EXPIRY_DAYS")
+        .ok()
+        .and_then(|s| s.parse::<i64>().ok())
+        .unwrap_or(7)
+}
+
+/// Revoke every token issued to a user up to now.
+///
+/// Tokens carry no server-side session list, so this records a cutoff that the
+/// auth extractor compares against each token's `iat`. The marker lives as
+/// long as the longest-lived token could.
+pub async fn revoke_user_tokens(redis: &RedisClient, user_id: Uuid) -> Result<(), AppError> {
+    let mut conn = redis.get().await?;
+
+    let ttl = Duration::days(token_expiry_days()).num_seconds().max(1) as u64;
+    let _: () = conn
+        .set_ex(
+            RedisKey::user_sessions_revoked(user_id),
+            Utc::now().timestamp(),
+            ttl,
+        )
+        .await
+        .map_err(AppError::RedisCommandError)?;
+
+    Ok(())
 }
 
 /// Validate JWT_SECRET meets security requirements
@@ -95,3 +314,92 @@ fn validate_jwt_secret(secret: &str) -> Result<(), AppError> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "current-secret-current-secret-0123";
+    const OLD_SECRET: &str = "previous-secret-previous-secret-01";
+
+    fn claims() -> Claims {
+        let now = Utc::now().timestamp();
+        Claims {
+            sub: Uuid::new_v4().to_string(),
+            wallet: "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7".to_string(),
+            iat: now,
+            exp: now + 60,
+            jti: Uuid::new_v4().to_string(),
+        }
+    }
+
+    #[test]
+    fn test_tokens_from_a_rotated_key_still_verify() {
+        let old = JwtKeys::hs256("2025", OLD_SECRET).unwrap();
+        let token = old.sign(&claims()).unwrap();
+
+        let rotated = JwtKeys::hs256("2026", SECRET)
+            .unwrap()
+            .with_previous_hs256("2025", OLD_SECRET)
+            .unwrap();
+        assert!(rotated.verify(&token).is_ok());
+        assert!(rotated.verify(&rotated.sign(&claims()).unwrap()).is_ok());
+
+        // Once the old key leaves config its tokens stop working
+        let retired = JwtKeys::hs256("2026", SECRET).unwrap();
+        assert!(matches!(
+            retired.verify(&token),
+            Err(AppError::Unauthorized(_))
+        ));
+        assert!(
+            JwtKeys::hs256("2026", SECRET)
+                .unwrap()
+                .with_previous_hs256("2026", OLD_SECRET)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_tokens_without_a_kid_try_every_key() {
+        let unlabelled = |secret: &str| {
+            encode(
+                &Header::default(),
+                &claims(),
+                &EncodingKey::from_secret(secret.as_bytes()),
+            )
+            .unwrap()
+        };
+        let rotated = JwtKeys::hs256("2026", SECRET)
+            .unwrap()
+            .with_previous_hs256("2025", OLD_SECRET)
+            .unwrap();
+
+        assert!(rotated.verify(&unlabelled(SECRET)).is_ok());
+        assert!(rotated.verify(&unlabelled(OLD_SECRET)).is_ok());
+        assert!(
+            rotated
+                .verify(&unlabelled("unknown-secret-unknown-secret-0123"))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_rs256_tokens_verify_with_the_public_key() {
+        let keys = JwtKeys::rs256(
+            "rsa",
+            include_bytes!("../../tests/fixtures/jwt_rs256.pem"),
+            include_bytes!("../../tests/fixtures/jwt_rs256.pub.pem"),
+        )
+        .unwrap();
+        let token = keys.sign(&claims()).unwrap();
+        assert_eq!(decode_header(&token).unwrap().alg, Algorithm::RS256);
+        assert!(keys.verify(&token).is_ok());
+
+        // An HS256 token can't pass itself off as the RSA key
+        let forged = JwtKeys::hs256("rsa", SECRET)
+            .unwrap()
+            .sign(&claims())
+            .unwrap();
+        assert!(keys.verify(&forged).is_err());
+    }
+}
@@ -1,7 +1,11 @@
 // Authentication module: extractors and JWT helpers
 
+pub mod challenge;
 pub mod extractors;
+pub mod invite;
 pub mod jwt;
+pub mod roles;
+pub mod wallet_signature;
 
 pub use extractors::AuthClaims;
 pub use jwt::generate_jwt;
@@ -0,0 +1,63 @@
+// Role guards: `RequireRole<R>` admits callers holding at least `R::ROLE`
+//
+// A caller's role is their `users.role`, except that wallets listed as admins
+// in config are always `Admin` so the platform can't be left without one.
+// Roles are looked up per request rather than carried in the token, so a
+// change applies immediately.
+
+use std::marker::PhantomData;
+
+use axum::{extract::FromRequestParts, http::request::Parts};
+
+use crate::{
+    auth::AuthClaims, db::user::UserRepository, errors::AppError, models::Role, state::AppState,
+};
+
+/// The least role a `RequireRole` guard admits.
+pub trait RequiredRole {
+    const ROLE: Role;
+}
+
+/// Moderators and admins
+pub struct Moderators;
+
+impl RequiredRole for Moderators {
+    const ROLE: Role = Role::Moderator;
+}
+
+/// Admins only
+pub struct Admins;
+
+impl RequiredRole for Admins {
+    const ROLE: Role = Role::Admin;
+}
+
+/// Extractor for authenticated callers holding `R::ROLE` or higher.
+pub struct RequireRole<R>(pub AuthClaims, pub PhantomData<R>);
+
+impl<R: RequiredRole> FromRequestParts<AppState> for RequireRole<R> {
+    type Rejection = (axum::http::StatusCode, String);
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let auth = AuthClaims::from_request_parts(parts, state).await?;
+        if role_of(state, &auth).await.map_err(|e| e.to_response())? < R::ROLE {
+            return Err(
+                AppError::Forbidden(format!("{} access required", R::ROLE.label())).to_response(),
+            );
+        }
+        Ok(RequireRole(auth, PhantomData))
+    }
+}
+
+/// The role `auth`'s caller acts with.
+pub async fn role_of(state: &AppState, auth: &AuthClaims) -> Result<Role, AppError> {
+    if state.config.is_admin(auth.wallet_address()) {
+        return Ok(Role::Admin);
+    }
+    UserRepository::new(state.postgres.clone())
+        .find_role(auth.0.user_id()?)
+        .await
+}
@@ -0,0 +1,164 @@
+// Stacks wallet message signatures: proving a sign-in comes from the wallet's owner
+//
+// Wallets sign `sha256("\x17Stacks Signed Message:\n" || varint(len) || message)`
+// with the account's secp256k1 key and hand back the signature (RSV hex) and
+// the public key. A signature counts for a wallet when it verifies under that
+// public key and the key hashes to the wallet's address (hash160, c32check).
+
+use k256::ecdsa::{Signature, SigningKey, VerifyingKey, signature::hazmat::PrehashVerifier};
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256};
+
+use crate::errors::AppError;
+use crate::models::WalletAddress;
+
+const MESSAGE_PREFIX: &[u8] = b"\x17Stacks Signed Message:\n";
+/// Address versions of single-signature accounts
+const MAINNET_VERSION: u8 = 22;
+const TESTNET_VERSION: u8 = 26;
+
+/// The digest a Stacks wallet signs for `message`.
+pub fn message_hash(message: &str) -> [u8; 32] {
+    let len = message.len();
+    let mut encoded = MESSAGE_PREFIX.to_vec();
+    // Bitcoin-style varint length
+    match len {
+        0..0xfd => encoded.push(len as u8),
+        0xfd..=0xffff => {
+            encoded.push(0xfd);
+            encoded.extend_from_slice(&(len as u16).to_le_bytes());
+        }
+        _ => {
+            encoded.push(0xfe);
+            encoded.extend_from_slice(&(len as u32).to_le_bytes());
+        }
+    }
+    encoded.extend_from_slice(message.as_bytes());
+    Sha256::digest(&encoded).into()
+}
+
+/// Check that `signature` over `message` was made by `wallet`'s key.
+///
+/// `signature` is hex: 65-byte RSV as wallets return it, or the bare 64-byte
+/// `r || s`. `public_key` is the hex SEC1 key the wallet reported.
+pub fn verify_message(
+    wallet: &WalletAddress,
+    message: &str,
+    signature: &str,
+    public_key: &str,
+) -> Result<(), AppError> {
+    let invalid = |reason: &str| AppError::Unauthorized(format!("Invalid signature: {}", reason));
+
+    let public_key = hex::decode(public_key).map_err(|_| invalid("public key is not hex"))?;
+    let version = match wallet.as_str().as_bytes().get(1) {
+        Some(b'P') => MAINNET_VERSION,
+        Some(b'T') => TESTNET_VERSION,
+        _ => return Err(invalid("wallet is not a single-signature account")),
+    };
+    if stacks_address(&public_key, version) != wallet.as_str() {
+        return Err(invalid("public key does not belong to this wallet"));
+    }
+
+    let key =
+        VerifyingKey::from_sec1_bytes(&public_key).map_err(|_| invalid("malformed public key"))?;
+    let signature = hex::decode(signature).map_err(|_| invalid("signature is not hex"))?;
+    if signature.len() != 64 && signature.len() != 65 {
+        return Err(invalid("signature has the wrong length"));
+    }
+    let signature =
+        Signature::from_slice(&signature[..64]).map_err(|_| invalid("signature out of range"))?;
+    // k256 only accepts low-s signatures; wallets that emit high-s are still honest
+    let signature = signature.normalize_s().unwrap_or(signature);
+
+    key.verify_prehash(&message_hash(message), &signature)
+        .map_err(|_| invalid("signature does not match"))
+}
+
+/// Sign `message` the way a Stacks wallet does, returning RSV hex.
+///
+/// The nonce follows RFC 6979, so signing is deterministic. Meant for tooling
+/// and tests; real users sign in their wallet.
+pub fn sign_message(secret_key: &[u8; 32], message: &str) -> String {
+    let key = SigningKey::from_bytes(secret_key.into()).expect("secret key is in range");
+    let (signature, recovery_id) = key
+        .sign_prehash_recoverable(&message_hash(message))
+        .expect("a 32-byte digest can be signed");
+    let mut bytes = signature.to_bytes().to_vec();
+    bytes.push(recovery_id.to_byte());
+    hex::encode(bytes)
+}
+
+/// Compressed public key for `secret_key`, hex encoded.
+pub fn public_key(secret_key: &[u8; 32]) -> String {
+    let key = SigningKey::from_bytes(secret_key.into()).expect("secret key is in range");
+    hex::encode(key.verifying_key().to_encoded_point(true).as_bytes())
+}
+
+/// The single-signature address of `public_key` for address `version`.
+pub fn stacks_address(public_key: &[u8], version: u8) -> String {
+    let hash160 = Ripemd160::digest(Sha256::digest(public_key));
+    c32::encode_check_prefixed(hash160, 'S', version).expect("address versions are below 32")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_addresses_match_c32check() {
+        let key = public_key(&[42u8; 32]);
+        let key = hex::decode(key).unwrap();
+        let mainnet = stacks_address(&key, MAINNET_VERSION);
+        let testnet = stacks_address(&key, TESTNET_VERSION);
+        assert!(mainnet.starts_with("SP"), "{}", mainnet);
+        assert!(testnet.starts_with("ST"), "{}", testnet);
+        assert!(WalletAddress::new(&mainnet).is_ok());
+
+        let known = hex::decode("a46ff88886c2ef9762d970b4d2c63678835bd39d").unwrap();
+        assert_eq!(
+            c32::encode_check_prefixed(known, 'S', MAINNET_VERSION).unwrap(),
+            "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7"
+        );
+
+        let (hash160, version) = c32::decode_check_prefixed(&mainnet, 'S').unwrap();
+        assert_eq!(version, MAINNET_VERSION);
+        assert_eq!(hash160, Ripemd160::digest(Sha256::digest(&key)).to_vec());
+    }
+
+    #[test]
+    fn test_public_keys_are_curve_multiples() {
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        assert_eq!(
+            public_key(&one),
+            "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"
+        );
+        let mut two = [0u8; 32];
+        two[31] = 2;
+        assert_eq!(
+            public_key(&two),
+            "02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5"
+        );
+    }
+
+    #[test]
+    fn test_signed_messages_verify_only_for_their_wallet() {
+        let secret = [7u8; 32];
+        let key = public_key(&secret);
+        let wallet =
+            WalletAddress::new(stacks_address(&hex::decode(&key).unwrap(), MAINNET_VERSION))
+                .unwrap();
+        let signature = sign_message(&secret, "hello");
+
+        assert!(verify_message(&wallet, "hello", &signature, &key).is_ok());
+        assert!(verify_message(&wallet, "hello!", &signature, &key).is_err());
+
+        let other = public_key(&[8u8; 32]);
+        assert!(verify_message(&wallet, "hello", &signature, &other).is_err());
+        let testnet =
+            WalletAddress::new(stacks_address(&hex::decode(&key).unwrap(), TESTNET_VERSION))
+                .unwrap();
+        assert!(testnet.as_str().starts_with("ST"));
+        assert!(verify_message(&testnet, "hello", &signature, &key).is_ok());
+    }
+}
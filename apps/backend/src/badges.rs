@@ -0,0 +1,257 @@
+// Badge engine: data-driven badge criteria evaluated after each game
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{db::user_badge::UserBadgeRepository, errors::AppError};
+
+/// Condition a player must meet to earn a badge.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum BadgeCriterion {
+    /// Season wars points reached `points`
+    SeasonPoints { points: f64 },
+    /// Finished a game at `rank` or better
+    Placement { rank: usize },
+    /// Won `wins` games in a row
+    WinStreak { wins: u32 },
+}
+
+/// A badge and the criterion that awards it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BadgeDefinition {
+    /// Stable identifier stored on awarded badges
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub criterion: BadgeCriterion,
+}
+
+impl BadgeDefinition {
+    pub fn new(id: &str, name: &str, description: &str, criterion: BadgeCriterion) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: description.to_string(),
+            criterion,
+        }
+    }
+}
+
+/// What a player achieved, as of the game that just finished.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BadgeContext {
+    /// Season wars points after the game
+    pub season_points: f64,
+    /// Placement in the game (1 = winner)
+    pub rank: usize,
+    /// Current win streak, when known
+    pub win_streak: Option<u32>,
+}
+
+impl BadgeCriterion {
+    /// Whether the context satisfies this criterion.
+    pub fn is_met(&self, ctx: &BadgeContext) -> bool {
+        match self {
+            BadgeCriterion::SeasonPoints { points } => ctx.season_points >= *points,
+            BadgeCriterion::Placement { rank } => ctx.rank >= 1 && ctx.rank <= *rank,
+            BadgeCriterion::WinStreak { wins } => ctx.win_streak.is_some_and(|s| s >= *wins),
+        }
+    }
+}
+
+/// Registry of badge definitions.
+///
+/// `BadgeEngine::default()` holds the built-in badges; more can be registered
+/// or loaded from JSON without touching the evaluation code.
+#[derive(Debug, Clone)]
+pub struct BadgeEngine {
+    badges: Vec<BadgeDefinition>,
+}
+
+impl Default for BadgeEngine {
+    fn default() -> Self {
+        let mut engine = Self::new();
+        for badge in default_badges() {
+            engine.register(badge);
+        }
+        engine
+    }
+}
+
+impl BadgeEngine {
+    /// Create an engine with no badges registered.
+    pub fn new() -> Self {
+        Self { badges: Vec::new() }
+    }
+
+    /// Load badge definitions from a JSON array.
+    pub fn from_json(json: &str) -> Result<Self, AppError> {
+        let badges: Vec<BadgeDefinition> = serde_json::from_str(json)
+            .map_err(|e| AppError::Deserialization(format!("Invalid badge definitions: {}", e)))?;
+
+        let mut engine = Self::new();
+        for badge in badges {
+            engine.register(badge);
+        }
+        Ok(engine)
+    }
+
+    /// Register a badge, replacing any existing definition with the same id.
+    pub fn register(&mut self, badge: BadgeDefinition) {
+        self.badges.retain(|b| b.id != badge.id);
+        self.badges.push(badge);
+    }
+
+    /// All registered badges.
+    pub fn badges(&self) -> &[BadgeDefinition] {
+        &self.badges
+    }
+
+    /// Badges whose criteria the context meets.
+    pub fn evaluate(&self, ctx: &BadgeContext) -> Vec<&BadgeDefinition> {
+        self.badges
+            .iter()
+            .filter(|b| b.criterion.is_met(ctx))
+            .collect()
+    }
+
+    /// Evaluate and grant badges for a user's season.
+    ///
+    /// Safe to call repeatedly: badges already held are not awarded again.
+    /// Returns the ids of newly awarded badges.
+    pub async fn award(
+        &self,
+        repo: &UserBadgeRepository,
+        user_id: Uuid,
+        season_id: i32,
+        ctx: &BadgeContext,
+    ) -> Result<Vec<String>, AppError> {
+        let earned: Vec<String> = self.evaluate(ctx).iter().map(|b| b.id.clone()).collect();
+        repo.award(user_id, season_id, &earned).await
+    }
+}
+
+/// Built-in badges.
+pub fn default_badges() -> Vec<BadgeDefinition> {
+    vec![
+        BadgeDefinition::new(
+            "first-win",
+            "First Blood",
+            "Win a game",
+            BadgeCriterion::Placement { rank: 1 },
+        ),
+        BadgeDefinition::new(
+            "podium",
+            "Podium",
+            "Finish a game in the top 3",
+            BadgeCriterion::Placement { rank: 3 },
+        ),
+        BadgeDefinition::new(
+            "points-100",
+            "Contender",
+            "Reach 100 wars points in a season",
+            BadgeCriterion::SeasonPoints { points: 100.0 },
+        ),
+        BadgeDefinition::new(
+            "points-500",
+            "Veteran",
+            "Reach 500 wars points in a season",
+            BadgeCriterion::SeasonPoints { points: 500.0 },
+        ),
+        BadgeDefinition::new(
+            "points-1000",
+            "Warlord",
+            "Reach 1000 wars points in a season",
+            BadgeCriterion::SeasonPoints { points: 1000.0 },
+        ),
+        BadgeDefinition::new(
+            "streak-3",
+            "Hot Streak",
+            "Win 3 games in a row",
+            BadgeCriterion::WinStreak { wins: 3 },
+        ),
+        BadgeDefinition::new(
+            "streak-5",
+            "Unstoppable",
+            "Win 5 games in a row",
+            BadgeCriterion::WinStreak { wins: 5 },
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx(season_points: f64, rank: usize) -> BadgeContext {
+        BadgeContext {
+            season_points,
+            rank,
+            win_streak: None,
+        }
+    }
+
+    fn ids(engine: &BadgeEngine, ctx: &BadgeContext) -> Vec<String> {
+        engine.evaluate(ctx).iter().map(|b| b.id.clone()).collect()
+    }
+
+    #[test]
+    fn test_points_threshold() {
+        let engine = BadgeEngine::default();
+
+        assert!(!ids(&engine, &ctx(99.9, 4)).contains(&"points-100".to_string()));
+        assert!(ids(&engine, &ctx(100.0, 4)).contains(&"points-100".to_string()));
+        assert!(!ids(&engine, &ctx(100.0, 4)).contains(&"points-500".to_string()));
+    }
+
+    #[test]
+    fn test_placement_and_streak() {
+        let engine = BadgeEngine::default();
+
+        let winner = ids(&engine, &ctx(0.0, 1));
+        assert!(winner.contains(&"first-win".to_string()));
+        assert!(winner.contains(&"podium".to_string()));
+        assert_eq!(ids(&engine, &ctx(0.0, 3)), vec!["podium".to_string()]);
+
+        let streaking = BadgeContext {
+            win_streak: Some(3),
+            ..ctx(0.0, 1)
+        };
+        let earned = ids(&engine, &streaking);
+        assert!(earned.contains(&"streak-3".to_string()));
+        assert!(!earned.contains(&"streak-5".to_string()));
+    }
+
+    #[test]
+    fn test_badges_loaded_from_json() {
+        let engine = BadgeEngine::from_json(
+            r#"[{"id":"big","name":"Big","description":"d","criterion":{"type":"seasonPoints","points":50}}]"#,
+        )
+        .unwrap();
+
+        assert_eq!(engine.badges().len(), 1);
+        assert_eq!(ids(&engine, &ctx(50.0, 9)), vec!["big".to_string()]);
+    }
+
+    #[test]
+    fn test_register_replaces_same_id() {
+        let mut engine = BadgeEngine::new();
+        engine.register(BadgeDefinition::new(
+            "b",
+            "B",
+            "d",
+            BadgeCriterion::Placement { rank: 1 },
+        ));
+        engine.register(BadgeDefinition::new(
+            "b",
+            "B",
+            "d",
+            BadgeCriterion::Placement { rank: 2 },
+        ));
+
+        assert_eq!(engine.badges().len(), 1);
+        assert_eq!(ids(&engine, &ctx(0.0, 2)), vec!["b".to_string()]);
+    }
+}
@@ -0,0 +1,36 @@
+use stacks_wars_be::db::consistency;
+use stacks_wars_be::state::AppState;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Load environment variables
+    dotenvy::dotenv().ok();
+
+    tracing_subscriber::fmt::init();
+
+    // `--repair` rewrites drifted lobby config in Redis from Postgres
+    let repair = std::env::args().any(|arg| arg == "--repair");
+
+    let state = AppState::new().await?;
+    let report = consistency::check_lobbies(&state.redis, &state.postgres, repair).await?;
+
+    println!("\n🔍 Checked {} live lobbies", report.checked);
+    for mismatch in &report.mismatches {
+        let status = if mismatch.repaired {
+            "repaired"
+        } else {
+            "open"
+        };
+        println!("  [{}] {}: {:?}", status, mismatch.key, mismatch.drift);
+    }
+
+    let open = report.unrepaired().count();
+    if open == 0 {
+        println!("\n✅ Redis and PostgreSQL agree");
+    } else {
+        println!("\n⚠️  {} mismatches need attention", open);
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use stacks_wars_be::db::hydration;
+use stacks_wars_be::db::hydration::{self, HydrationOptions};
 use stacks_wars_be::state::AppState;
 
 #[tokio::main]
@@ -6,6 +6,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Load environment variables
     dotenvy::dotenv().ok();
 
+    // Progress is reported through tracing
+    tracing_subscriber::fmt::init();
+
+    // `--dry-run` reports what would be written without touching PostgreSQL
+    let options = HydrationOptions {
+        dry_run: std::env::args().any(|arg| arg == "--dry-run"),
+        ..Default::default()
+    };
+
     println!("\n🚀 Initializing application state...");
 
     // Initialize app state (connects to both PostgreSQL and Redis)
@@ -13,10 +22,50 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     println!("✅ Connected to PostgreSQL and Redis\n");
 
-    // Run hydration from Redis to PostgreSQL
-    hydration::hydrate_all_from_redis(&state.redis, &state.postgres).await?;
+    // Run hydration from Redis to PostgreSQL (resumes from any checkpoint)
+    let summary =
+        hydration::hydrate_all_with_options(&state.redis, &state.postgres, &options).await?;
+
+    let (title, inserted) = if summary.dry_run {
+        ("Hydration Dry Run (nothing written)", "planned")
+    } else {
+        ("Hydration Summary", "hydrated")
+    };
+
+    println!("\n╔═══════════════════════════════════════════════╗");
+    println!("║  {:<45}║", title);
+    println!("╠═══════════════════════════════════════════════╣");
+    for phase in &summary.phases {
+        println!(
+            "║  {:<8} {:<8} {:>5}  existing {:>5}  failed {:>5}",
+            phase.phase.as_str(),
+            inserted,
+            phase.hydrated,
+            phase.existing,
+            phase.failures.len()
+        );
+    }
+    println!("╚═══════════════════════════════════════════════╝");
+
+    if !summary.invalid.is_empty() {
+        println!(
+            "\n🔍 Validation found {} invalid records:",
+            summary.invalid.len()
+        );
+        for issue in &summary.invalid {
+            println!("   {}: {}", issue.key, issue.reason);
+        }
+    }
+
+    for failure in summary.failures() {
+        println!("⚠️  {}: {}", failure.key, failure.reason);
+    }
 
-    println!("\n✨ Hydration script completed successfully!");
+    if summary.dry_run {
+        println!("\n✨ Dry run completed; re-run without --dry-run to write");
+    } else {
+        println!("\n✨ Hydration script completed successfully!");
+    }
 
     Ok(())
 }
@@ -6,6 +6,7 @@ use stacks_wars_be::db::{
     hydration::redis::migrate_all_redis_state, lobby_state::LobbyStateRepository,
     player_state::PlayerStateRepository,
 };
+use stacks_wars_be::state::RedisClient;
 use std::{env, time::Duration};
 
 #[tokio::main]
@@ -45,17 +46,17 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .connection_timeout(Duration::from_secs(10))
         .build(manager)
         .await?;
+    let redis = RedisClient::new(redis_pool);
 
     println!("✅ Connected!\n");
 
     // Create repositories
-    let lobby_state_repo = LobbyStateRepository::new(redis_pool.clone());
-    let player_state_repo = PlayerStateRepository::new(redis_pool.clone());
+    let lobby_state_repo = LobbyStateRepository::new(redis.clone());
+    let player_state_repo = PlayerStateRepository::new(redis.clone());
 
     // Run migration
     let (lobbies, players) =
-        migrate_all_redis_state(&redis_pool, &lobby_state_repo, &player_state_repo, dry_run)
-            .await?;
+        migrate_all_redis_state(&redis, &lobby_state_repo, &player_state_repo, dry_run).await?;
 
     println!("\n╔═══════════════════════════════════════════════╗");
     println!("║  Migration Summary                            ║");
@@ -0,0 +1,117 @@
+// Chat retention: prunes lobby chat past its retention policy
+//
+// Chat keys already expire a day after the last message, but a busy lobby
+// keeps pushing that out and its history grows without bound. Every
+// CHAT_RETENTION_INTERVAL_SECS (0 turns it off) a background task deletes the
+// oldest messages of every lobby with chat once they are past the lobby's
+// policy (see `ChatRetentionConfig`). Deletes go out a batch at a time so no single
+// Redis call runs long. Only the instance that takes the interval's Redis
+// lock runs the pass; the lock is left to expire.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+use crate::{
+    db::{lobby::LobbyRepository, lobby_chat::LobbyChatRepository},
+    errors::AppError,
+    models::{LobbyStatus, RedisKey},
+    redis_lock::RedisLock,
+    state::{AppState, ChatRetentionConfig, RetentionPolicy},
+};
+
+/// Messages deleted per Redis round trip
+const DELETE_BATCH: usize = 100;
+
+/// Lobbies whose status is looked up per query
+const STATUS_BATCH: usize = 500;
+
+/// Spawn the background task that prunes old chat, if enabled.
+pub fn spawn_chat_retention(state: AppState) {
+    let retention = state.config.chat_retention.clone();
+    if !retention.is_enabled() {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let period = Duration::from_secs(retention.interval_secs);
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            // Slightly shorter than the period, so the next tick finds it free
+            let hold = period.mul_f64(0.9);
+            match RedisLock::try_acquire(&state.redis, &RedisKey::lock("chat_retention"), hold)
+                .await
+            {
+                Ok(Some(_)) => {}
+                Ok(None) => continue,
+                Err(e) => {
+                    tracing::error!("Chat retention lock failed: {}", e);
+                    continue;
+                }
+            }
+            match prune_chat(&state, &retention, Utc::now()).await {
+                Ok(0) => {}
+                Ok(deleted) => tracing::info!("Pruned {} chat messages", deleted),
+                Err(e) => tracing::error!("Chat retention pass failed: {}", e),
+            }
+        }
+    });
+}
+
+/// Delete every lobby's chat messages that are past its policy as of `now`.
+/// Returns the number of messages deleted.
+///
+/// Lobbies that are finished or cancelled, or no longer exist, follow the
+/// `finished` policy; the rest follow `active`.
+pub async fn prune_chat(
+    state: &AppState,
+    retention: &ChatRetentionConfig,
+    now: DateTime<Utc>,
+) -> Result<usize, AppError> {
+    let chat_repo = LobbyChatRepository::new(state.redis.clone());
+    let lobby_repo = LobbyRepository::new(state.postgres.clone());
+
+    let lobby_ids = chat_repo
+        .lobbies_with_chat()
+        .await
+        .map_err(AppError::RedisError)?;
+
+    let mut deleted = 0;
+    for chunk in lobby_ids.chunks(STATUS_BATCH) {
+        let statuses = lobby_repo.find_statuses(chunk).await?;
+        for &lobby_id in chunk {
+            let policy = match statuses.get(&lobby_id) {
+                Some(LobbyStatus::Finished | LobbyStatus::Cancelled) | None => &retention.finished,
+                Some(_) => &retention.active,
+            };
+            match prune_lobby(&chat_repo, lobby_id, policy, now).await {
+                Ok(count) => deleted += count,
+                Err(e) => tracing::error!("Failed to prune chat in lobby {}: {}", lobby_id, e),
+            }
+        }
+    }
+
+    Ok(deleted)
+}
+
+async fn prune_lobby(
+    chat_repo: &LobbyChatRepository,
+    lobby_id: uuid::Uuid,
+    policy: &RetentionPolicy,
+    now: DateTime<Utc>,
+) -> Result<usize, String> {
+    let mut deleted = 0;
+    if policy.max_age_secs > 0 {
+        let cutoff = now.timestamp() - policy.max_age_secs as i64;
+        deleted += chat_repo
+            .delete_older_than(lobby_id, cutoff, DELETE_BATCH)
+            .await?;
+    }
+    if policy.max_messages > 0 {
+        deleted += chat_repo
+            .trim_to_latest(lobby_id, policy.max_messages, DELETE_BATCH)
+            .await?;
+    }
+    Ok(deleted)
+}
@@ -0,0 +1,405 @@
+// Contract calls from the server account, one nonce at a time
+//
+// Every transaction the server account sends needs the account's next nonce.
+// Two calls built from the same nonce conflict, and the node rejects the one
+// that comes second, so a `NonceManager` hands nonces out itself:
+//
+// - it asks the node for the account's next nonce once, then counts up from
+//   there as its transactions are accepted
+// - submissions go through one lock, so a nonce is only handed out after the
+//   call before it was accepted or gave up
+// - when the node turns a nonce down (too low, or already used by a pending
+//   transaction) the nonce is refreshed from the node and the call is built
+//   again, up to MAX_SUBMIT_ATTEMPTS with a doubling backoff. An unreachable
+//   node is retried with the same nonce.
+//
+// The caller builds and signs the transaction for the nonce it is given.
+// Accepted calls and calls that failed after every retry are recorded in
+// `server_transactions` through the `TxRepository`.
+
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+use tokio::sync::Mutex;
+
+use crate::{db::tx::TxRepository, errors::AppError, state::AppState};
+
+/// Most times one call is built and broadcast before it gives up
+pub const MAX_SUBMIT_ATTEMPTS: u32 = 4;
+
+/// Wait before the first retry; doubled after every retry
+pub const DEFAULT_RETRY_BACKOFF: Duration = Duration::from_millis(200);
+
+/// Why the node didn't take a transaction.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum BroadcastError {
+    /// The nonce is too low or already taken by a pending transaction
+    #[error("Nonce rejected: {0}")]
+    NonceRejected(String),
+
+    /// The transaction itself is invalid; retrying won't help
+    #[error("Transaction rejected: {0}")]
+    Rejected(String),
+
+    /// The node couldn't be reached or failed to answer
+    #[error("Node unavailable: {0}")]
+    Unavailable(String),
+}
+
+/// A contract call that was not accepted.
+#[derive(Debug, thiserror::Error)]
+pub enum ContractCallError {
+    #[error("Nonce still conflicting after {attempts} attempts: {reason}")]
+    NonceConflict { attempts: u32, reason: String },
+
+    #[error("Transaction rejected: {0}")]
+    Rejected(String),
+
+    #[error("Stacks node unavailable: {0}")]
+    Unavailable(String),
+
+    #[error("Failed to build transaction: {0}")]
+    Build(AppError),
+}
+
+impl From<ContractCallError> for AppError {
+    fn from(error: ContractCallError) -> Self {
+        match error {
+            ContractCallError::Build(e) => e,
+            ContractCallError::Rejected(_) => AppError::BadRequest(error.to_string()),
+            ContractCallError::NonceConflict { .. } | ContractCallError::Unavailable(_) => {
+                AppError::ServiceUnavailable(error.to_string())
+            }
+        }
+    }
+}
+
+/// The node transactions are sent to.
+#[async_trait]
+pub trait StacksNode: Send + Sync {
+    /// The next nonce the node expects from `address`, counting its pending
+    /// transactions.
+    async fn account_nonce(&self, address: &str) -> Result<u64, AppError>;
+
+    /// Broadcast a signed transaction; returns its id.
+    async fn broadcast(&self, raw_tx: &[u8]) -> Result<String, BroadcastError>;
+}
+
+/// Stacks node reached through the Hiro API.
+pub struct HiroNode {
+    client: Client,
+    base_url: String,
+    api_key: String,
+}
+
+impl HiroNode {
+    pub fn from_state(state: &AppState) -> Self {
+        let network = if state.config.network.is_mainnet() {
+            "mainnet"
+        } else {
+            "testnet"
+        };
+        Self {
+            client: Client::new(),
+            base_url: format!("https://api.{}.hiro.so", network),
+            api_key: state.config.hiro_api_key.clone(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct HiroNonces {
+    possible_next_nonce: u64,
+}
+
+#[derive(Deserialize)]
+struct BroadcastRejection {
+    error: Option<String>,
+    reason: Option<String>,
+}
+
+/// The next nonce in a Hiro nonces response.
+fn parse_account_nonce(body: &str) -> Result<u64, AppError> {
+    serde_json::from_str::<HiroNonces>(body)
+        .map(|nonces| nonces.possible_next_nonce)
+        .map_err(|e| AppError::Deserialization(e.to_string()))
+}
+
+/// The transaction id in a broadcast response, or why it was turned down.
+///
+/// An accepted transaction's body is its id as a JSON string. A rejection
+/// names a `reason`; `BadNonce` and `ConflictingNonceInMempool` are nonce
+/// problems, anything else is the transaction's own.
+fn parse_broadcast_response(status: u16, body: &str) -> Result<String, BroadcastError> {
+    if (200..300).contains(&status) {
+        return serde_json::from_str::<String>(body)
+            .map_err(|e| BroadcastError::Unavailable(format!("Unreadable txid: {}", e)));
+    }
+    if status >= 500 {
+        return Err(BroadcastError::Unavailable(format!(
+            "Node returned {}: {}",
+            status, body
+        )));
+    }
+
+    let rejection: BroadcastRejection = serde_json::from_str(body)
+        .map_err(|_| BroadcastError::Rejected(format!("Node returned {}: {}", status, body)))?;
+    let reason = rejection
+        .reason
+        .or(rejection.error)
+        .unwrap_or_else(|| format!("Node returned {}", status));
+
+    match reason.as_str() {
+        "BadNonce" | "ConflictingNonceInMempool" => Err(BroadcastError::NonceRejected(reason)),
+        _ => Err(BroadcastError::Rejected(reason)),
+    }
+}
+
+#[async_trait]
+impl StacksNode for HiroNode {
+    async fn account_nonce(&self, address: &str) -> Result<u64, AppError> {
+        let response = self
+            .client
+            .get(format!(
+                "{}/extended/v1/address/{}/nonces",
+                self.base_url, address
+            ))
+            .header("x-api-key", &self.api_key)
+            .send()
+            .await
+            .map_err(|e| AppError::FetchError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(AppError::FetchError(format!(
+                "Hiro API returned {} for {} nonces",
+                response.status(),
+                address
+            )));
+        }
+
+        let body = response
+            .text()
+            .await
+            .map_err(|e| AppError::FetchError(e.to_string()))?;
+        parse_account_nonce(&body)
+    }
+
+    async fn broadcast(&self, raw_tx: &[u8]) -> Result<String, BroadcastError> {
+        let response = self
+            .client
+            .post(format!("{}/v2/transactions", self.base_url))
+            .header("x-api-key", &self.api_key)
+            .header("content-type", "application/octet-stream")
+            .body(raw_tx.to_vec())
+            .send()
+            .await
+            .map_err(|e| BroadcastError::Unavailable(e.to_string()))?;
+
+        let status = response.status().as_u16();
+        let body = response
+            .text()
+            .await
+            .map_err(|e| BroadcastError::Unavailable(e.to_string()))?;
+        parse_broadcast_response(status, &body)
+    }
+}
+
+/// Hands out the server account's nonces and submits its contract calls.
+pub struct NonceManager {
+    node: Arc<dyn StacksNode>,
+    address: String,
+    /// Next nonce to use; None until read from the node, or after a call
+    /// gave up on conflicts
+    next_nonce: Mutex<Option<u64>>,
+    txs: TxRepository,
+    backoff: Duration,
+}
+
+impl NonceManager {
+    pub fn new(node: Arc<dyn StacksNode>, address: impl Into<String>, txs: TxRepository) -> Self {
+        Self {
+            node,
+            address: address.into(),
+            next_nonce: Mutex::new(None),
+            txs,
+            backoff: DEFAULT_RETRY_BACKOFF,
+        }
+    }
+
+    /// Use a different wait before the first retry
+    pub fn with_backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Build and broadcast a call to `function`; returns its transaction id.
+    ///
+    /// `build` signs the transaction for the nonce it is given, and is called
+    /// again with a fresh nonce when the node turns one down.
+    pub async fn submit<F>(&self, function: &str, mut build: F) -> Result<String, ContractCallError>
+    where
+        F: FnMut(u64) -> Result<Vec<u8>, AppError> + Send,
+    {
+        let mut next_nonce = self.next_nonce.lock().await;
+        let mut nonce = *next_nonce;
+        let mut last_nonce = None;
+        let mut backoff = self.backoff;
+        let mut attempts = 0;
+
+        let failure = loop {
+            attempts += 1;
+
+            let current = match nonce {
+                Some(current) => current,
+                None => match self.node.account_nonce(&self.address).await {
+                    Ok(current) => current,
+                    Err(e) if attempts < MAX_SUBMIT_ATTEMPTS => {
+                        tracing::warn!("Failed to read the server account nonce: {}", e);
+                        tokio::time::sleep(backoff).await;
+                        backoff *= 2;
+                        continue;
+                    }
+                    Err(e) => break ContractCallError::Unavailable(e.to_string()),
+                },
+            };
+            nonce = Some(current);
+            last_nonce = Some(current);
+
+            let raw_tx = match build(current) {
+                Ok(raw_tx) => raw_tx,
+                Err(e) => break ContractCallError::Build(e),
+            };
+
+            match self.node.broadcast(&raw_tx).await {
+                Ok(tx_id) => {
+                    *next_nonce = Some(current + 1);
+                    drop(next_nonce);
+
+                    if let Err(e) = self
+                        .txs
+                        .record_submitted(function, current, &tx_id, attempts)
+                        .await
+                    {
+                        tracing::error!(
+                            "Failed to record {} transaction {}: {}",
+                            function,
+                            tx_id,
+                            e
+                        );
+                    }
+                    return Ok(tx_id);
+                }
+                Err(BroadcastError::NonceRejected(reason)) => {
+                    tracing::warn!(
+                        "{} rejected nonce {} (attempt {}): {}",
+                        function,
+                        current,
+                        attempts,
+                        reason
+                    );
+                    if attempts >= MAX_SUBMIT_ATTEMPTS {
+                        // Nothing we hold is trusted any more; the next call
+                        // starts from the node's count
+                        nonce = None;
+                        break ContractCallError::NonceConflict { attempts, reason };
+                    }
+                    // The node may not have seen its own pending transaction
+                    // yet, so never go back to the nonce that was turned down
+                    nonce = Some(match self.node.account_nonce(&self.address).await {
+                        Ok(fresh) => fresh.max(current + 1),
+                        Err(_) => current + 1,
+                    });
+                }
+                Err(BroadcastError::Unavailable(reason)) => {
+                    tracing::warn!(
+                        "{} broadcast failed (attempt {}): {}",
+                        function,
+                        attempts,
+                        reason
+                    );
+                    if attempts >= MAX_SUBMIT_ATTEMPTS {
+                        break ContractCallError::Unavailable(reason);
+                    }
+                }
+                Err(BroadcastError::Rejected(reason)) => {
+                    break ContractCallError::Rejected(reason);
+                }
+            }
+
+            tokio::time::sleep(backoff).await;
+            backoff *= 2;
+        };
+
+        // A call that wasn't accepted didn't use its nonce
+        *next_nonce = nonce;
+        drop(next_nonce);
+
+        tracing::error!(
+            "{} failed after {} attempts: {}",
+            function,
+            attempts,
+            failure
+        );
+        if let Err(e) = self
+            .txs
+            .record_failed(function, last_nonce, &failure.to_string(), attempts)
+            .await
+        {
+            tracing::error!("Failed to record failed {} call: {}", function, e);
+        }
+        Err(failure)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_broadcast_response() {
+        assert_eq!(
+            parse_broadcast_response(200, "\"0xabc\""),
+            Ok("0xabc".to_string())
+        );
+        assert_eq!(
+            parse_broadcast_response(
+                400,
+                r#"{"error":"transaction rejected","reason":"BadNonce","txid":"0xabc"}"#
+            ),
+            Err(BroadcastError::NonceRejected("BadNonce".to_string()))
+        );
+        assert_eq!(
+            parse_broadcast_response(
+                400,
+                r#"{"error":"transaction rejected","reason":"ConflictingNonceInMempool"}"#
+            ),
+            Err(BroadcastError::NonceRejected(
+                "ConflictingNonceInMempool".to_string()
+            ))
+        );
+        assert_eq!(
+            parse_broadcast_response(
+                400,
+                r#"{"error":"transaction rejected","reason":"NotEnoughFunds"}"#
+            ),
+            Err(BroadcastError::Rejected("NotEnoughFunds".to_string()))
+        );
+        assert!(matches!(
+            parse_broadcast_response(503, "busy"),
+            Err(BroadcastError::Unavailable(_))
+        ));
+        assert!(matches!(
+            parse_broadcast_response(400, "not json"),
+            Err(BroadcastError::Rejected(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_account_nonce() {
+        let body = r#"{"last_mempool_tx_nonce":6,"last_executed_tx_nonce":4,"possible_next_nonce":7,"detected_missing_nonces":[]}"#;
+        assert_eq!(parse_account_nonce(body).unwrap(), 7);
+        assert!(parse_account_nonce("{}").is_err());
+    }
+}
@@ -0,0 +1,537 @@
+// Contract event indexing: deposits and claims read off the vault contracts
+//
+// Every CONTRACT_EVENT_INTERVAL_SECS (0 turns it off) a background task reads
+// the asset transfer events of every lobby vault that can still move funds
+// (lobbies not yet finished, and finished ones for CLAIM_INDEX_WINDOW) and
+// applies them:
+//
+// - a transfer into the vault of at least the entry amount is a player's
+//   entry deposit. It is indexed under the lobby, so a room join finds it
+//   without asking the node, and stamped on the player's state if they
+//   already joined. Smaller transfers are not deposits.
+// - a transfer out of a finished lobby's vault to a player with an unclaimed
+//   prize is their claim, marked as `ClaimReward` would mark it.
+// - a transfer out of a vault before the game finished is a refund (leave or
+//   kick), which drops the player's indexed deposit.
+//
+// Events are identified by `{tx_id}:{event_index}`. Each contract's applied
+// ids are kept in a Redis set. A pass reads back to the newest applied event
+// and applies what it found oldest first, up to MAX_EVENTS_PER_PASS and
+// stopping at the first that fails, so the set always covers everything up to
+// the newest applied event and the next pass can stop reading there.
+
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use chrono::Utc;
+use redis::AsyncCommands;
+use reqwest::Client;
+use serde::Deserialize;
+
+use crate::{
+    db::{
+        expiry::apply_expiry, lobby::LobbyRepository, lobby_state::LobbyStateRepository,
+        player_state::PlayerStateRepository,
+    },
+    errors::AppError,
+    http::handlers::stacks::has_joined,
+    models::{
+        Lobby, LobbyStatus, PlayerState, RedisKey, WalletAddress, money::to_micro_units,
+        player_state::ClaimState,
+    },
+    redis_lock::RedisLock,
+    state::{AppState, RedisClient},
+};
+
+/// Default time between indexing passes
+pub const DEFAULT_CONTRACT_EVENT_INTERVAL_SECS: u64 = 15;
+
+/// How long after finishing a lobby's vault is still watched for claims
+pub const CLAIM_INDEX_WINDOW: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Events requested per page
+const EVENT_PAGE_SIZE: usize = 50;
+
+/// Most events applied to one contract in a pass. The oldest go first; newer
+/// ones stay unapplied, so the next pass reads back to them.
+pub const MAX_EVENTS_PER_PASS: usize = 500;
+
+/// How long applied event ids are remembered after a contract's last event
+const SEEN_TTL_SECS: i64 = 30 * 24 * 60 * 60;
+
+/// An asset transfer emitted by a contract call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractEvent {
+    /// `{tx_id}:{event_index}`, unique across the chain
+    pub id: String,
+    pub tx_id: String,
+    pub sender: String,
+    pub recipient: String,
+    /// Fungible token moved, as `{contract}::{token}`; None for STX
+    pub asset_id: Option<String>,
+    /// Micro-units moved
+    pub amount: u128,
+}
+
+/// Where contract events are read from.
+#[async_trait]
+pub trait ContractEventSource: Send + Sync {
+    /// Transfer events touching `contract`, newest first, skipping `offset`.
+    ///
+    /// A page shorter than `limit` means there are no older events.
+    async fn events(
+        &self,
+        contract: &str,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<ContractEvent>, AppError>;
+}
+
+/// Contract events from the Hiro API.
+pub struct HiroEvents {
+    client: Client,
+    base_url: String,
+    api_key: String,
+}
+
+impl HiroEvents {
+    pub fn from_state(state: &AppState) -> Self {
+        let network = if state.config.network.is_mainnet() {
+            "mainnet"
+        } else {
+            "testnet"
+        };
+        Self {
+            client: Client::new(),
+            base_url: format!("https://api.{}.hiro.so", network),
+            api_key: state.config.hiro_api_key.clone(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct HiroEventPage {
+    events: Vec<HiroEvent>,
+}
+
+#[derive(Deserialize)]
+struct HiroEvent {
+    event_index: u64,
+    event_type: String,
+    tx_id: String,
+    asset: Option<HiroAsset>,
+}
+
+#[derive(Deserialize)]
+struct HiroAsset {
+    asset_event_type: Option<String>,
+    asset_id: Option<String>,
+    sender: Option<String>,
+    recipient: Option<String>,
+    amount: Option<String>,
+}
+
+/// The transfers in a page of Hiro events; other event types are dropped.
+fn parse_hiro_events(body: &str) -> Result<Vec<ContractEvent>, AppError> {
+    let page: HiroEventPage =
+        serde_json::from_str(body).map_err(|e| AppError::Deserialization(e.to_string()))?;
+
+    Ok(page
+        .events
+        .into_iter()
+        .filter(|event| {
+            matches!(
+                event.event_type.as_str(),
+                "stx_asset" | "fungible_token_asset"
+            )
+        })
+        .filter_map(|event| {
+            let asset = event.asset?;
+            if asset.asset_event_type.as_deref() != Some("transfer") {
+                return None;
+            }
+            Some(ContractEvent {
+                id: format!("{}:{}", event.tx_id, event.event_index),
+                tx_id: event.tx_id,
+                sender: asset.sender?,
+                recipient: asset.recipient?,
+                asset_id: asset.asset_id,
+                amount: asset.amount?.parse().ok()?,
+            })
+        })
+        .collect())
+}
+
+#[async_trait]
+impl ContractEventSource for HiroEvents {
+    async fn events(
+        &self,
+        contract: &str,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<ContractEvent>, AppError> {
+        let response = self
+            .client
+            .get(format!("{}/extended/v1/tx/events", self.base_url))
+            .query(&[
+                ("address", contract.to_string()),
+                ("offset", offset.to_string()),
+                ("limit", limit.to_string()),
+            ])
+            .header("x-api-key", &self.api_key)
+            .send()
+            .await
+            .map_err(|e| AppError::FetchError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(AppError::FetchError(format!(
+                "Hiro API returned {} for {} events",
+                response.status(),
+                contract
+            )));
+        }
+
+        let body = response
+            .text()
+            .await
+            .map_err(|e| AppError::FetchError(e.to_string()))?;
+        parse_hiro_events(&body)
+    }
+}
+
+/// Spawn the background task that indexes vault contract events, if enabled.
+pub fn spawn_contract_indexer(state: AppState) {
+    let interval_secs = state.config.contract_event_interval_secs;
+    if interval_secs == 0 {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let period = Duration::from_secs(interval_secs);
+        let indexer = ContractIndexer::new(state.clone(), Arc::new(HiroEvents::from_state(&state)));
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            // One instance indexes per interval; the lock lapses before the next
+            match RedisLock::try_acquire(
+                &state.redis,
+                &RedisKey::lock("contract_events"),
+                period.mul_f64(0.9),
+            )
+            .await
+            {
+                Ok(Some(_)) => match indexer.index_once().await {
+                    Ok(0) => {}
+                    Ok(count) => tracing::debug!("Applied {} contract events", count),
+                    Err(e) => tracing::error!("Contract event indexing failed: {}", e),
+                },
+                Ok(None) => {}
+                Err(e) => tracing::error!("Contract event lock failed: {}", e),
+            }
+        }
+    });
+}
+
+/// Applies vault contract events to lobby payment and claim state.
+#[derive(Clone)]
+pub struct ContractIndexer {
+    state: AppState,
+    source: Arc<dyn ContractEventSource>,
+}
+
+impl ContractIndexer {
+    pub fn new(state: AppState, source: Arc<dyn ContractEventSource>) -> Self {
+        Self { state, source }
+    }
+
+    /// Index every watched lobby's vault; returns the number of events applied.
+    ///
+    /// A lobby that fails is logged and left for the next pass.
+    pub async fn index_once(&self) -> Result<usize, AppError> {
+        let window = chrono::Duration::from_std(CLAIM_INDEX_WINDOW).unwrap_or_default();
+        let lobbies = LobbyRepository::new(self.state.postgres.clone())
+            .find_with_open_contracts((Utc::now() - window).naive_utc())
+            .await?;
+
+        let mut applied = 0;
+        for lobby in lobbies {
+            match self.index_lobby(&lobby).await {
+                Ok(count) => applied += count,
+                Err(e) => tracing::warn!("Failed to index events for lobby {}: {}", lobby.id(), e),
+            }
+        }
+        Ok(applied)
+    }
+
+    /// Apply the lobby vault's events not applied yet; returns how many were.
+    pub async fn index_lobby(&self, lobby: &Lobby) -> Result<usize, AppError> {
+        let Some(contract) = lobby.contract_address.as_ref() else {
+            return Ok(0);
+        };
+        let contract = contract.as_str();
+        let seen_key = RedisKey::contract_events_seen(contract);
+
+        let fresh = self.unseen_events(contract, &seen_key).await?;
+        let mut applied = 0;
+        for event in fresh.iter().rev().take(MAX_EVENTS_PER_PASS) {
+            self.apply(lobby, contract, event).await?;
+
+            let mut conn = self.state.redis.get().await?;
+            let _: () = redis::pipe()
+                .sadd(&seen_key, &event.id)
+                .ignore()
+                .expire(&seen_key, SEEN_TTL_SECS)
+                .ignore()
+                .query_async(&mut *conn)
+                .await
+                .map_err(AppError::RedisCommandError)?;
+            applied += 1;
+        }
+        Ok(applied)
+    }
+
+    /// Events newer than the newest applied one, newest first.
+    async fn unseen_events(
+        &self,
+        contract: &str,
+        seen_key: &str,
+    ) -> Result<Vec<ContractEvent>, AppError> {
+        let mut fresh = Vec::new();
+        let mut offset = 0;
+
+        loop {
+            let page = self
+                .source
+                .events(contract, offset, EVENT_PAGE_SIZE)
+                .await?;
+            if page.is_empty() {
+                break;
+            }
+
+            let mut pipe = redis::pipe();
+            for event in &page {
+                pipe.sismember(seen_key, &event.id);
+            }
+            let mut conn = self.state.redis.get().await?;
+            let seen: Vec<bool> = pipe
+                .query_async(&mut *conn)
+                .await
+                .map_err(AppError::RedisCommandError)?;
+
+            let page_len = page.len();
+            let mut reached_seen = false;
+            for (event, seen) in page.into_iter().zip(seen) {
+                if seen {
+                    reached_seen = true;
+                    break;
+                }
+                fresh.push(event);
+            }
+            if reached_seen || page_len < EVENT_PAGE_SIZE {
+                break;
+            }
+            offset += page_len;
+        }
+
+        Ok(fresh)
+    }
+
+    async fn apply(
+        &self,
+        lobby: &Lobby,
+        contract: &str,
+        event: &ContractEvent,
+    ) -> Result<(), AppError> {
+        // Fungible token vaults only move the lobby's token
+        if let (Some(token), Some(asset_id)) = (&lobby.token_contract_id, &event.asset_id)
+            && !asset_id.starts_with(&format!("{}::", token.as_str()))
+        {
+            return Ok(());
+        }
+
+        if event.recipient == contract {
+            self.deposited(lobby, event).await
+        } else if event.sender == contract {
+            if lobby.status == LobbyStatus::Finished {
+                self.claimed(lobby, &event.recipient, &event.tx_id).await
+            } else {
+                self.refunded(lobby, &event.recipient).await
+            }
+        } else {
+            Ok(())
+        }
+    }
+
+    async fn deposited(&self, lobby: &Lobby, event: &ContractEvent) -> Result<(), AppError> {
+        // Only a transfer covering the entry fee pays for a seat
+        let entry = lobby
+            .entry_amount
+            .and_then(to_micro_units)
+            .filter(|entry| *entry > 0);
+        let Some(entry) = entry else {
+            return Ok(());
+        };
+        if event.amount < entry as u128 {
+            tracing::debug!(
+                "Ignoring a {} micro-unit transfer into lobby {}'s vault; entry is {}",
+                event.amount,
+                lobby.id(),
+                entry
+            );
+            return Ok(());
+        }
+
+        let (wallet, tx_id) = (event.sender.as_str(), event.tx_id.as_str());
+        let key = RedisKey::lobby_deposits(lobby.id());
+        let mut conn = self.state.redis.get().await?;
+        let _: () = conn
+            .hset(&key, wallet, tx_id)
+            .await
+            .map_err(AppError::RedisCommandError)?;
+        apply_expiry(&mut conn, &key).await?;
+
+        if let Some(player) = self.player_by_wallet(lobby, wallet).await?
+            && player.tx_id.is_none()
+        {
+            PlayerStateRepository::new(self.state.redis.clone())
+                .set_tx_id(lobby.id(), player.user_id, tx_id)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn refunded(&self, lobby: &Lobby, wallet: &str) -> Result<(), AppError> {
+        let mut conn = self.state.redis.get().await?;
+        let _: () = conn
+            .hdel(RedisKey::lobby_deposits(lobby.id()), wallet)
+            .await
+            .map_err(AppError::RedisCommandError)?;
+        Ok(())
+    }
+
+    async fn claimed(&self, lobby: &Lobby, wallet: &str, tx_id: &str) -> Result<(), AppError> {
+        // Transfers to anyone else (the fee wallet) and repeat claims are ignored
+        let Some(player) = self.player_by_wallet(lobby, wallet).await? else {
+            return Ok(());
+        };
+        let Some(prize) = player
+            .prize
+            .filter(|_| player.has_prize() && !player.has_claimed())
+        else {
+            return Ok(());
+        };
+
+        PlayerStateRepository::new(self.state.redis.clone())
+            .update_claim_state(
+                lobby.id(),
+                player.user_id,
+                ClaimState::Claimed {
+                    tx_id: tx_id.to_string(),
+                },
+            )
+            .await?;
+        LobbyStateRepository::new(self.state.redis.clone())
+            .subtract_current_amount(lobby.id(), prize)
+            .await?;
+        Ok(())
+    }
+
+    async fn player_by_wallet(
+        &self,
+        lobby: &Lobby,
+        wallet: &str,
+    ) -> Result<Option<PlayerState>, AppError> {
+        let players = PlayerStateRepository::new(self.state.redis.clone())
+            .list_players(lobby.id())
+            .await?;
+        Ok(players
+            .into_iter()
+            .find(|player| player.wallet_address.eq_ignore_ascii_case(wallet)))
+    }
+}
+
+/// Whether `wallet` paid into the lobby's vault, from the indexed deposits
+/// when the indexer has seen it and from the node otherwise.
+pub async fn has_deposited(
+    state: &AppState,
+    lobby_id: uuid::Uuid,
+    contract: &WalletAddress,
+    wallet: &WalletAddress,
+) -> Result<bool, AppError> {
+    if indexed_deposit(&state.redis, lobby_id, wallet.as_str())
+        .await?
+        .is_some()
+    {
+        return Ok(true);
+    }
+    has_joined(contract, wallet, state).await
+}
+
+/// Transaction of `wallet`'s indexed deposit into the lobby's vault.
+pub async fn indexed_deposit(
+    redis: &RedisClient,
+    lobby_id: uuid::Uuid,
+    wallet: &str,
+) -> Result<Option<String>, AppError> {
+    let mut conn = redis.get().await?;
+    conn.hget(RedisKey::lobby_deposits(lobby_id), wallet)
+        .await
+        .map_err(AppError::RedisCommandError)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hiro_transfers_are_parsed_and_other_events_dropped() {
+        let body = r#"{
+            "limit": 50,
+            "offset": 0,
+            "events": [
+                {
+                    "event_index": 2,
+                    "event_type": "stx_asset",
+                    "tx_id": "0xabc",
+                    "asset": {
+                        "asset_event_type": "transfer",
+                        "sender": "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7",
+                        "recipient": "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7.vault",
+                        "amount": "5000000"
+                    }
+                },
+                {
+                    "event_index": 0,
+                    "event_type": "smart_contract_log",
+                    "tx_id": "0xabc",
+                    "contract_log": { "topic": "print" }
+                },
+                {
+                    "event_index": 1,
+                    "event_type": "fungible_token_asset",
+                    "tx_id": "0xdef",
+                    "asset": {
+                        "asset_event_type": "mint",
+                        "asset_id": "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7.token::wars",
+                        "recipient": "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7",
+                        "amount": "1"
+                    }
+                }
+            ]
+        }"#;
+
+        let events = parse_hiro_events(body).unwrap();
+        assert_eq!(
+            events,
+            vec![ContractEvent {
+                id: "0xabc:2".to_string(),
+                tx_id: "0xabc".to_string(),
+                sender: "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7".to_string(),
+                recipient: "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7.vault".to_string(),
+                asset_id: None,
+                amount: 5_000_000,
+            }]
+        );
+        assert!(parse_hiro_events("not json").is_err());
+    }
+}
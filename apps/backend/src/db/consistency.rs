@@ -0,0 +1,342 @@
+// Consistency check: compares Postgres lobbies against what Redis holds for them
+//
+// Three sources are walked:
+// - live Postgres lobbies, each of which should have a runtime hash mirroring
+//   its status, name and amounts;
+// - runtime hashes, each of which should belong to a Postgres lobby;
+// - legacy `lobbies:{id}:info` hashes, which hydration should have copied.
+//
+// Diverging config fields on a live lobby are trivial drift: `repair` rewrites
+// them from Postgres. Everything else is reported for someone to look at,
+// since the right fix depends on which store is behind.
+
+use std::collections::{HashMap, HashSet};
+
+use redis::AsyncCommands;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::db::hydration::types::LobbyInfo;
+use crate::db::lobby::LobbyRepository;
+use crate::db::lobby_state::LobbyStateRepository;
+use crate::errors::AppError;
+use crate::models::keys::{KeyPart, RedisKey};
+use crate::models::{Lobby, LobbyStatus};
+use crate::state::RedisClient;
+
+/// How a lobby's Redis data disagrees with Postgres.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum LobbyDrift {
+    /// A live lobby has no runtime hash
+    MissingInRedis,
+    /// A Redis hash names a lobby Postgres doesn't have
+    MissingInPostgres,
+    /// Both stores have the field but disagree
+    FieldMismatch {
+        field: String,
+        postgres: Option<String>,
+        redis: Option<String>,
+    },
+    /// The Redis hash couldn't be parsed
+    Unparseable { reason: String },
+}
+
+/// One problem found by a check.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LobbyMismatch {
+    /// None when the key doesn't carry a valid lobby id
+    pub lobby_id: Option<Uuid>,
+    pub key: String,
+    pub drift: LobbyDrift,
+    /// Whether the check fixed it
+    pub repaired: bool,
+}
+
+/// Everything a consistency check found.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsistencyReport {
+    /// Live Postgres lobbies compared
+    pub checked: usize,
+    pub mismatches: Vec<LobbyMismatch>,
+}
+
+impl ConsistencyReport {
+    pub fn is_consistent(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+
+    /// Mismatches still outstanding after any repair
+    pub fn unrepaired(&self) -> impl Iterator<Item = &LobbyMismatch> {
+        self.mismatches.iter().filter(|m| !m.repaired)
+    }
+
+    fn push(&mut self, lobby_id: Option<Uuid>, key: &str, drift: LobbyDrift) {
+        self.mismatches.push(LobbyMismatch {
+            lobby_id,
+            key: key.to_string(),
+            drift,
+            repaired: false,
+        });
+    }
+}
+
+/// Lobby id from a `lobbies:{id}:...` key
+fn lobby_id_from_key(key: &str) -> Option<Uuid> {
+    key.split(':')
+        .nth(1)
+        .and_then(|id| Uuid::parse_str(id).ok())
+}
+
+fn mismatch(field: &str, postgres: Option<String>, redis: Option<String>) -> LobbyDrift {
+    LobbyDrift::FieldMismatch {
+        field: field.to_string(),
+        postgres,
+        redis,
+    }
+}
+
+/// Amounts compare by value, so "5" and "5.00" agree
+fn amount_drift(
+    field: &str,
+    postgres: Option<Decimal>,
+    redis: Option<&String>,
+) -> Option<LobbyDrift> {
+    let parsed = redis.and_then(|s| s.parse::<Decimal>().ok());
+    if parsed == postgres && (redis.is_none() || parsed.is_some()) {
+        return None;
+    }
+    Some(mismatch(
+        field,
+        postgres.map(|a| a.to_string()),
+        redis.cloned(),
+    ))
+}
+
+/// Config fields of a live lobby whose runtime hash disagrees with its row.
+///
+/// These are the fields `LobbyStateRepository::sync_config` writes.
+fn config_drift(lobby: &Lobby, runtime: &HashMap<String, String>) -> Vec<LobbyDrift> {
+    let mut drift = Vec::new();
+    if runtime.get("name") != Some(&lobby.name) {
+        drift.push(mismatch(
+            "name",
+            Some(lobby.name.clone()),
+            runtime.get("name").cloned(),
+        ));
+    }
+    drift.extend(amount_drift(
+        "entry_amount",
+        lobby.entry_amount,
+        runtime.get("entry_amount"),
+    ));
+    drift.extend(amount_drift(
+        "current_amount",
+        lobby.current_amount,
+        runtime.get("current_amount"),
+    ));
+    drift
+}
+
+/// Compare Postgres lobbies with Redis, optionally repairing trivial drift.
+pub async fn check_lobbies(
+    redis: &RedisClient,
+    pool: &PgPool,
+    repair: bool,
+) -> Result<ConsistencyReport, AppError> {
+    let lobby_repo = LobbyRepository::new(pool.clone());
+    let state_repo = LobbyStateRepository::new(redis.clone());
+    let mut conn = redis.get().await?;
+    let mut report = ConsistencyReport::default();
+
+    // Live lobbies against their runtime hashes
+    let live = lobby_repo.get_active_lobbies().await?;
+    let live_ids: HashSet<Uuid> = live.iter().map(|l| l.id).collect();
+    report.checked = live.len();
+
+    for lobby in &live {
+        let key = RedisKey::lobby_state(lobby.id);
+        let runtime: HashMap<String, String> = conn
+            .hgetall(&key)
+            .await
+            .map_err(AppError::RedisCommandError)?;
+        if runtime.is_empty() {
+            report.push(Some(lobby.id), &key, LobbyDrift::MissingInRedis);
+            continue;
+        }
+
+        let status = runtime.get("status");
+        if status.and_then(|s| s.parse::<LobbyStatus>().ok()) != Some(lobby.status) {
+            report.push(
+                Some(lobby.id),
+                &key,
+                mismatch(
+                    "status",
+                    Some(lobby.status.as_key().to_string()),
+                    status.cloned(),
+                ),
+            );
+        }
+
+        let drift = config_drift(lobby, &runtime);
+        if drift.is_empty() {
+            continue;
+        }
+        let repaired = repair && state_repo.sync_config(lobby).await?;
+        for drift in drift {
+            report.mismatches.push(LobbyMismatch {
+                lobby_id: Some(lobby.id),
+                key: key.clone(),
+                drift,
+                repaired,
+            });
+        }
+    }
+
+    // Runtime hashes with no lobby behind them
+    let state_keys: Vec<String> = conn
+        .keys(RedisKey::lobby_state(KeyPart::Wildcard))
+        .await
+        .map_err(AppError::RedisCommandError)?;
+    for key in state_keys {
+        match lobby_id_from_key(&key) {
+            Some(id) if live_ids.contains(&id) => {}
+            Some(id) => {
+                if !lobby_repo.exists(id).await? {
+                    report.push(Some(id), &key, LobbyDrift::MissingInPostgres);
+                }
+            }
+            None => report.push(
+                None,
+                &key,
+                LobbyDrift::Unparseable {
+                    reason: "invalid lobby id in key".to_string(),
+                },
+            ),
+        }
+    }
+
+    // Legacy info hashes against what hydration wrote
+    let info_keys: Vec<String> = conn
+        .keys(RedisKey::lobby(KeyPart::Wildcard))
+        .await
+        .map_err(AppError::RedisCommandError)?;
+    for key in info_keys {
+        let lobby_id = lobby_id_from_key(&key);
+        let data: HashMap<String, String> = conn
+            .hgetall(&key)
+            .await
+            .map_err(AppError::RedisCommandError)?;
+        let (info, _creator_id, game_id) = match LobbyInfo::from_redis_hash_partial(&data) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                report.push(
+                    lobby_id,
+                    &key,
+                    LobbyDrift::Unparseable {
+                        reason: e.to_string(),
+                    },
+                );
+                continue;
+            }
+        };
+
+        let lobby = match lobby_repo.find_by_id(info.id).await {
+            Ok(lobby) => lobby,
+            Err(AppError::NotFound(_)) => {
+                report.push(Some(info.id), &key, LobbyDrift::MissingInPostgres);
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        if lobby.name != info.name {
+            report.push(
+                Some(info.id),
+                &key,
+                mismatch("name", Some(lobby.name.clone()), Some(info.name)),
+            );
+        }
+        if lobby.game_id != game_id {
+            report.push(
+                Some(info.id),
+                &key,
+                mismatch(
+                    "game_id",
+                    Some(lobby.game_id.to_string()),
+                    Some(game_id.to_string()),
+                ),
+            );
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn lobby() -> Lobby {
+        let now = Utc::now().naive_utc();
+        Lobby {
+            id: Uuid::new_v4(),
+            path: "drift".into(),
+            name: "Drift".into(),
+            description: None,
+            game_id: Uuid::new_v4(),
+            game_path: "lexi-wars".into(),
+            creator_id: Uuid::new_v4(),
+            entry_amount: Some(Decimal::from(5)),
+            current_amount: None,
+            token_symbol: None,
+            token_contract_id: None,
+            contract_address: None,
+            is_private: false,
+            is_sponsored: false,
+            spectators_allowed: true,
+            anonymize_players: false,
+            practice_bot: None,
+            auto_approve_trust_threshold: None,
+            payout_percents: None,
+            payout_underfill: Default::default(),
+            status: LobbyStatus::Waiting,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn runtime(fields: &[(&str, &str)]) -> HashMap<String, String> {
+        fields
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_matching_config_has_no_drift() {
+        let lobby = lobby();
+        let hash = runtime(&[("name", "Drift"), ("entry_amount", "5.00")]);
+        assert!(config_drift(&lobby, &hash).is_empty());
+    }
+
+    #[test]
+    fn test_config_drift_reports_each_field() {
+        let lobby = lobby();
+        let hash = runtime(&[("name", "Stale"), ("current_amount", "3")]);
+
+        let drift = config_drift(&lobby, &hash);
+        assert_eq!(
+            drift,
+            vec![
+                mismatch("name", Some("Drift".into()), Some("Stale".into())),
+                mismatch("entry_amount", Some("5".into()), None),
+                mismatch("current_amount", None, Some("3".into())),
+            ]
+        );
+    }
+}
@@ -0,0 +1,146 @@
+// Key expiry: applies `ExpiryPolicy` TTLs to keys as they are written and keeps
+// live lobbies' keys from expiring.
+//
+// A finished lobby whose winners haven't all claimed is held: its state and
+// player keys lose their TTL until the last prize is claimed, since those
+// keys are the only record of who is owed what.
+
+use redis::AsyncCommands;
+use uuid::Uuid;
+
+use crate::db::player_state::scan_player_keys;
+use crate::errors::AppError;
+use crate::models::{ExpiryPolicy, KeyCategory, RedisKey};
+use crate::redis_client::RedisConnection;
+use crate::state::RedisClient;
+
+/// Set the policy TTL on `key` after a write. Keys without a category, and
+/// the keys of a held lobby, are left alone.
+pub async fn apply_expiry(conn: &mut RedisConnection<'_>, key: &str) -> Result<(), AppError> {
+    if let Some(ttl) = ExpiryPolicy::ttl_for_key(key) {
+        if let Some(lobby_id) = held_lobby_key(key)
+            && is_held(conn, lobby_id).await?
+        {
+            return Ok(());
+        }
+        let _: () = conn
+            .expire(key, ttl)
+            .await
+            .map_err(AppError::RedisCommandError)?;
+    }
+    Ok(())
+}
+
+/// Queue the policy TTL for `key` on a pipeline.
+pub fn apply_expiry_pipe(pipe: &mut redis::Pipeline, key: &str) {
+    if let Some(ttl) = ExpiryPolicy::ttl_for_key(key) {
+        pipe.expire(key, ttl).ignore();
+    }
+}
+
+/// Push out the TTLs of a lobby's state, player and chat index keys.
+///
+/// Called on lobby activity. Only does the work once half the lobby TTL has
+/// gone since the last refresh; returns whether it did. Chat messages keep the
+/// TTL they were written with and join requests are meant to lapse.
+pub async fn refresh_lobby_expiry(redis: &RedisClient, lobby_id: Uuid) -> Result<bool, AppError> {
+    let mut conn = redis.get().await?;
+    let state_key = RedisKey::lobby_state(lobby_id);
+
+    let remaining: i64 = conn
+        .ttl(&state_key)
+        .await
+        .map_err(AppError::RedisCommandError)?;
+    // -2: no lobby state, so nothing is live
+    if remaining == -2 || !ExpiryPolicy::lobby_refresh_due(remaining) {
+        return Ok(false);
+    }
+    if is_held(&mut conn, lobby_id).await? {
+        return Ok(false);
+    }
+
+    let mut keys = vec![state_key, RedisKey::lobby_chat(lobby_id)];
+    keys.extend(scan_player_keys(&mut conn, lobby_id).await?);
+
+    let mut pipe = redis::pipe();
+    for key in &keys {
+        apply_expiry_pipe(&mut pipe, key);
+    }
+    let _: () = pipe
+        .query_async(&mut *conn)
+        .await
+        .map_err(AppError::RedisCommandError)?;
+
+    Ok(true)
+}
+
+/// Hold a finished lobby's keys until `user_id` has claimed their prize.
+pub async fn hold_for_claim(
+    conn: &mut RedisConnection<'_>,
+    lobby_id: Uuid,
+    user_id: Uuid,
+) -> Result<(), AppError> {
+    let mut keys = vec![RedisKey::lobby_state(lobby_id)];
+    keys.extend(scan_player_keys(conn, lobby_id).await?);
+
+    let mut pipe = redis::pipe();
+    pipe.sadd(RedisKey::lobby_unclaimed(lobby_id), user_id.to_string())
+        .ignore();
+    for key in &keys {
+        pipe.persist(key).ignore();
+    }
+    let _: () = pipe
+        .query_async(&mut **conn)
+        .await
+        .map_err(AppError::RedisCommandError)?;
+
+    Ok(())
+}
+
+/// Record `user_id`'s claim. Once nobody is left to claim, the lobby's keys
+/// get their policy TTL back.
+pub async fn release_claim(
+    conn: &mut RedisConnection<'_>,
+    lobby_id: Uuid,
+    user_id: Uuid,
+) -> Result<(), AppError> {
+    let unclaimed_key = RedisKey::lobby_unclaimed(lobby_id);
+    let (_, remaining): (i64, i64) = redis::pipe()
+        .srem(&unclaimed_key, user_id.to_string())
+        .scard(&unclaimed_key)
+        .query_async(&mut **conn)
+        .await
+        .map_err(AppError::RedisCommandError)?;
+    if remaining > 0 {
+        return Ok(());
+    }
+
+    let mut keys = vec![RedisKey::lobby_state(lobby_id)];
+    keys.extend(scan_player_keys(conn, lobby_id).await?);
+
+    let mut pipe = redis::pipe();
+    for key in &keys {
+        apply_expiry_pipe(&mut pipe, key);
+    }
+    let _: () = pipe
+        .query_async(&mut **conn)
+        .await
+        .map_err(AppError::RedisCommandError)?;
+
+    Ok(())
+}
+
+/// Whether a lobby still has prizes waiting to be claimed.
+async fn is_held(conn: &mut RedisConnection<'_>, lobby_id: Uuid) -> Result<bool, AppError> {
+    conn.exists(RedisKey::lobby_unclaimed(lobby_id))
+        .await
+        .map_err(AppError::RedisCommandError)
+}
+
+/// The lobby a state or player key belongs to; other keys are never held.
+fn held_lobby_key(key: &str) -> Option<Uuid> {
+    match RedisKey::category(key)? {
+        KeyCategory::LobbyState | KeyCategory::LobbyPlayer => key.split(':').nth(1)?.parse().ok(),
+        _ => None,
+    }
+}
@@ -1,7 +1,16 @@
-use crate::{errors::AppError, models::game::Game};
+use sqlx::PgConnection;
 use uuid::Uuid;
 
+use crate::{
+    errors::AppError,
+    models::{
+        game::Game,
+        seed::{SeedGame, SeedMode, SeedOutcome},
+    },
+};
+
 use super::GameRepository;
+use crate::db::timing::TimedQuery;
 
 impl GameRepository {
     /// Create a new game type.
@@ -19,21 +28,7 @@ impl GameRepository {
         // Validate player counts
         let (min_players, max_players) = Game::validate_player_count(min_players, max_players)?;
 
-        // Validate path format (lowercase, alphanumeric + hyphens)
-        if !path
-            .chars()
-            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
-        {
-            return Err(AppError::BadRequest(
-                "Game path must be lowercase alphanumeric with hyphens only".to_string(),
-            ));
-        }
-
-        if path.is_empty() || path.len() > 50 {
-            return Err(AppError::BadRequest(
-                "Game path must be between 1-50 characters".to_string(),
-            ));
-        }
+        Game::validate_path(path).map_err(|e| AppError::BadRequest(e.to_string()))?;
 
         let game = sqlx::query_as::<_, Game>(
             "INSERT INTO games (name, path, description, image_url, min_players, max_players, category, creator_id, is_active)
@@ -49,6 +44,7 @@ impl GameRepository {
         .bind(category)
         .bind(creator_id)
         .fetch_one(&self.pool)
+        .timed("GameRepository::create_game")
         .await
         .map_err(|e| {
             if let sqlx::Error::Database(db_err) = &e {
@@ -67,4 +63,66 @@ impl GameRepository {
         );
         Ok(game)
     }
+
+    /// Insert or update a game from a seed bundle inside the caller's transaction.
+    ///
+    /// Existing games are matched by path. A name clash with a different path
+    /// is reported as an error so the whole bundle rolls back.
+    pub async fn seed_game(
+        conn: &mut PgConnection,
+        game: &SeedGame,
+        mode: SeedMode,
+        creator_id: Uuid,
+    ) -> Result<SeedOutcome, AppError> {
+        let (min_players, max_players) =
+            Game::validate_player_count(game.min_players, game.max_players)?;
+        Game::validate_path(&game.path).map_err(|e| AppError::BadRequest(e.to_string()))?;
+
+        let on_conflict = match mode {
+            SeedMode::Skip => "DO NOTHING",
+            SeedMode::Update => {
+                "DO UPDATE SET name = EXCLUDED.name, description = EXCLUDED.description,
+                image_url = EXCLUDED.image_url, min_players = EXCLUDED.min_players,
+                max_players = EXCLUDED.max_players, category = EXCLUDED.category,
+                updated_at = NOW()"
+            }
+        };
+
+        // xmax = 0 only for freshly inserted rows
+        let row: Option<(bool,)> = sqlx::query_as(&format!(
+            "INSERT INTO games (name, path, description, image_url, min_players, max_players, category, creator_id, is_active)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, TRUE)
+            ON CONFLICT (path) {}
+            RETURNING (xmax = 0)",
+            on_conflict
+        ))
+        .bind(&game.name)
+        .bind(&game.path)
+        .bind(&game.description)
+        .bind(&game.image_url)
+        .bind(min_players)
+        .bind(max_players)
+        .bind(&game.category)
+        .bind(creator_id)
+        .fetch_optional(&mut *conn)
+        .timed("GameRepository::seed_game")
+        .await
+        .map_err(|e| {
+            if let sqlx::Error::Database(db_err) = &e
+                && db_err.is_unique_violation()
+            {
+                return AppError::BadRequest(format!(
+                    "Game with name '{}' already exists",
+                    game.name
+                ));
+            }
+            AppError::DatabaseError(format!("Failed to seed game: {}", e))
+        })?;
+
+        Ok(match row {
+            None => SeedOutcome::Skipped,
+            Some((true,)) => SeedOutcome::Inserted,
+            Some((false,)) => SeedOutcome::Updated,
+        })
+    }
 }
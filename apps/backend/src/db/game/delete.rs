@@ -2,6 +2,7 @@ use crate::errors::AppError;
 use uuid::Uuid;
 
 use super::GameRepository;
+use crate::db::timing::TimedQuery;
 
 impl GameRepository {
     /// Hard-delete a game (permanent). Prefer `deactivate_game` for soft-delete.
@@ -12,6 +13,7 @@ impl GameRepository {
         )
         .bind(game_id)
         .execute(&self.pool)
+        .timed("GameRepository::delete_game")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to delete game: {}", e)))?;
 
@@ -51,6 +53,7 @@ impl GameRepository {
         )
         .bind(&game_ids)
         .execute(&self.pool)
+        .timed("GameRepository::bulk_deactivate")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to bulk deactivate games: {}", e)))?;
 
@@ -68,6 +71,7 @@ impl GameRepository {
         )
         .bind(creator_id)
         .execute(&self.pool)
+        .timed("GameRepository::delete_by_creator")
         .await
         .map_err(|e| {
             AppError::DatabaseError(format!("Failed to delete games by creator: {}", e))
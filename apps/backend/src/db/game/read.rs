@@ -2,9 +2,11 @@ use crate::{
     errors::AppError,
     models::game::{Game, Order, Pagination},
 };
+use sqlx::PgConnection;
 use uuid::Uuid;
 
 use super::GameRepository;
+use crate::db::timing::TimedQuery;
 
 impl GameRepository {
     /// Find a game by UUID.
@@ -17,6 +19,7 @@ impl GameRepository {
         )
         .bind(game_id)
         .fetch_optional(&self.pool)
+        .timed("GameRepository::find_by_id")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to query game: {}", e)))?
         .ok_or_else(|| AppError::NotFound("Game not found".into()))?;
@@ -24,6 +27,43 @@ impl GameRepository {
         Ok(game)
     }
 
+    /// Load a game that new lobbies may be created for, inside the caller's transaction.
+    ///
+    /// The row is share-locked so it can't be deactivated before the caller
+    /// commits. Missing or inactive games, and games whose player limits can't
+    /// seat a lobby, are rejected with `AppError::BadRequest`.
+    pub async fn find_active_for_lobby(
+        conn: &mut PgConnection,
+        game_id: Uuid,
+    ) -> Result<Game, AppError> {
+        let game = sqlx::query_as::<_, Game>(
+            "SELECT id, name, path, description, image_url, min_players, max_players, category,
+                    creator_id, is_active, updated_at, created_at
+            FROM games
+            WHERE id = $1
+            FOR SHARE",
+        )
+        .bind(game_id)
+        .fetch_optional(&mut *conn)
+        .timed("GameRepository::find_active_for_lobby")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to query game: {}", e)))?
+        .ok_or_else(|| AppError::BadRequest(format!("Game {} does not exist", game_id)))?;
+
+        if !game.is_active {
+            return Err(AppError::BadRequest(format!(
+                "Game '{}' is not active",
+                game.name
+            )));
+        }
+
+        Game::validate_player_count(game.min_players, game.max_players).map_err(|e| {
+            AppError::BadRequest(format!("Game '{}' has invalid limits: {}", game.name, e))
+        })?;
+
+        Ok(game)
+    }
+
     /// Find a game by its path (URL-friendly identifier).
     pub async fn find_by_path(&self, path: &str) -> Result<Game, AppError> {
         let game = sqlx::query_as::<_, Game>(
@@ -34,6 +74,7 @@ impl GameRepository {
         )
         .bind(path)
         .fetch_optional(&self.pool)
+        .timed("GameRepository::find_by_path")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to query game by path: {}", e)))?
         .ok_or_else(|| AppError::NotFound("Game not found".into()))?;
@@ -53,6 +94,7 @@ impl GameRepository {
         )
         .bind(name)
         .fetch_optional(&self.pool)
+        .timed("GameRepository::find_by_name")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to query game by name: {}", e)))?
         .ok_or_else(|| AppError::NotFound("Game not found".into()))?;
@@ -83,6 +125,7 @@ impl GameRepository {
             .bind(limit)
             .bind(offset)
             .fetch_all(&self.pool)
+            .timed("GameRepository::get_all_games")
             .await
             .map_err(|e| AppError::DatabaseError(format!("Failed to fetch games: {}", e)))?;
 
@@ -105,6 +148,7 @@ impl GameRepository {
         .bind(limit)
         .bind(offset)
         .fetch_all(&self.pool)
+        .timed("GameRepository::get_active_games")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to fetch active games: {}", e)))?;
 
@@ -124,6 +168,7 @@ impl GameRepository {
         .bind(category)
         .bind(limit)
         .fetch_all(&self.pool)
+        .timed("GameRepository::get_by_category")
         .await
         .map_err(|e| {
             AppError::DatabaseError(format!("Failed to fetch games by category: {}", e))
@@ -149,6 +194,7 @@ impl GameRepository {
         .bind(creator_id)
         .bind(limit)
         .fetch_all(&self.pool)
+        .timed("GameRepository::get_by_creator")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to fetch games by creator: {}", e)))?;
 
@@ -167,6 +213,7 @@ impl GameRepository {
 
         let count = sqlx::query_scalar::<_, i64>(query)
             .fetch_one(&self.pool)
+            .timed("GameRepository::count_games")
             .await
             .map_err(|e| AppError::DatabaseError(format!("Failed to count games: {}", e)))?;
 
@@ -179,6 +226,7 @@ impl GameRepository {
             sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM games WHERE id = $1)")
                 .bind(game_id)
                 .fetch_one(&self.pool)
+                .timed("GameRepository::exists")
                 .await
                 .map_err(|e| {
                     AppError::DatabaseError(format!("Failed to check game existence: {}", e))
@@ -193,6 +241,7 @@ impl GameRepository {
             sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM games WHERE name = $1)")
                 .bind(name)
                 .fetch_one(&self.pool)
+                .timed("GameRepository::name_exists")
                 .await
                 .map_err(|e| {
                     AppError::DatabaseError(format!("Failed to check game name existence: {}", e))
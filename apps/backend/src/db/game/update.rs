@@ -2,6 +2,7 @@ use crate::{errors::AppError, models::game::Game};
 use uuid::Uuid;
 
 use super::GameRepository;
+use crate::db::timing::TimedQuery;
 
 impl GameRepository {
     /// Update a game's name (must be unique).
@@ -16,6 +17,7 @@ impl GameRepository {
         .bind(name)
         .bind(game_id)
         .fetch_optional(&self.pool)
+        .timed("GameRepository::update_name")
         .await
         .map_err(|e| {
             if let sqlx::Error::Database(db_err) = &e {
@@ -47,6 +49,7 @@ impl GameRepository {
         .bind(description)
         .bind(game_id)
         .fetch_optional(&self.pool)
+        .timed("GameRepository::update_description")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to update game description: {}", e)))?
         .ok_or_else(|| AppError::NotFound("Game not found".into()))?;
@@ -67,6 +70,7 @@ impl GameRepository {
         .bind(image_url)
         .bind(game_id)
         .fetch_optional(&self.pool)
+        .timed("GameRepository::update_image_url")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to update game image: {}", e)))?
         .ok_or_else(|| AppError::NotFound("Game not found".into()))?;
@@ -97,6 +101,7 @@ impl GameRepository {
         .bind(max_players)
         .bind(game_id)
         .fetch_optional(&self.pool)
+        .timed("GameRepository::update_player_limits")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to update player limits: {}", e)))?
         .ok_or_else(|| AppError::NotFound("Game not found".into()))?;
@@ -126,6 +131,7 @@ impl GameRepository {
         .bind(category)
         .bind(game_id)
         .fetch_optional(&self.pool)
+        .timed("GameRepository::update_category")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to update game category: {}", e)))?
         .ok_or_else(|| AppError::NotFound("Game not found".into()))?;
@@ -146,6 +152,7 @@ impl GameRepository {
         .bind(is_active)
         .bind(game_id)
         .fetch_optional(&self.pool)
+        .timed("GameRepository::set_active")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to update game status: {}", e)))?
         .ok_or_else(|| AppError::NotFound("Game not found".into()))?;
@@ -198,6 +205,7 @@ impl GameRepository {
         .bind(new_active)
         .bind(game_id)
         .fetch_one(&self.pool)
+        .timed("GameRepository::update_game")
         .await
         .map_err(|e| {
             if let sqlx::Error::Database(db_err) = &e {
@@ -0,0 +1,30 @@
+use uuid::Uuid;
+
+use crate::{db::timing::TimedQuery, errors::AppError, models::GameWord};
+
+use super::GameWordRepository;
+
+impl GameWordRepository {
+    /// Record a word accepted on the given turn.
+    pub async fn record_word(
+        &self,
+        lobby_id: Uuid,
+        user_id: Uuid,
+        word: &str,
+        turn: i32,
+    ) -> Result<GameWord, AppError> {
+        sqlx::query_as::<_, GameWord>(
+            "INSERT INTO game_words (lobby_id, user_id, word, turn)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, lobby_id, user_id, word, turn, played_at",
+        )
+        .bind(lobby_id)
+        .bind(user_id)
+        .bind(word)
+        .bind(turn)
+        .fetch_one(&self.pool)
+        .timed("GameWordRepository::record_word")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to record game word: {}", e)))
+    }
+}
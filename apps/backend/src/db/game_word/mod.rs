@@ -0,0 +1,17 @@
+use sqlx::PgPool;
+
+mod create;
+mod read;
+
+/// Repository for words played in lexi_wars games.
+#[derive(Clone)]
+pub struct GameWordRepository {
+    pub(crate) pool: PgPool,
+}
+
+impl GameWordRepository {
+    /// Create a new `GameWordRepository` with the given pool.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
@@ -0,0 +1,22 @@
+use uuid::Uuid;
+
+use crate::{db::timing::TimedQuery, errors::AppError, models::GameWord};
+
+use super::GameWordRepository;
+
+impl GameWordRepository {
+    /// All words played in a lobby, in turn order.
+    pub async fn find_by_lobby(&self, lobby_id: Uuid) -> Result<Vec<GameWord>, AppError> {
+        sqlx::query_as::<_, GameWord>(
+            "SELECT id, lobby_id, user_id, word, turn, played_at
+            FROM game_words
+            WHERE lobby_id = $1
+            ORDER BY turn",
+        )
+        .bind(lobby_id)
+        .fetch_all(&self.pool)
+        .timed("GameWordRepository::find_by_lobby")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch game words: {}", e)))
+    }
+}
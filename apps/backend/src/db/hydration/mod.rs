@@ -1,459 +1,225 @@
 // Hydration helpers: populate PostgreSQL from existing Redis state (one-time migrations)
-
-use crate::db::hydration::types::LobbyInfo;
+//
+// `hydrate_all_from_redis` runs the phases in `HydrationPhase::ALL` order. A
+// record that can't be hydrated (bad key, missing fields, rejected insert) is
+// collected in the summary and the run moves on; only Redis failures abort it.
+// Progress is checkpointed per key (see `progress`), so re-running after an
+// abort or a `max_records` stop resumes instead of starting over.
+//
+// Every run starts with a validation pass that parses all records and reports
+// the ones that won't hydrate before anything is written. A dry run stops
+// short of writing: it reports what each record would do, using reads only,
+// and leaves the checkpoint untouched.
+
+use crate::db::hydration::progress::{
+    HydrationFailure, HydrationPhase, HydrationSummary, PhaseSummary, clear_checkpoint,
+    load_checkpoint, save_checkpoint,
+};
+use crate::db::hydration::records::{RecordOutcome, parse_record, plan_record, write_record};
 use crate::errors::AppError;
-use crate::models::LobbyStatus;
 use crate::models::keys::{KeyPart, RedisKey};
+use crate::redis_client::RedisConnection;
 use crate::state::RedisClient;
 use ::redis::AsyncCommands;
 use sqlx::PgPool;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
+pub mod progress;
+mod records;
 pub mod redis;
 pub mod types;
 
-/// Default creator ID for games and lobbies when creator is missing in Redis
-/// This user must exist in the database before hydration
-const DEFAULT_CREATOR_ID: &str = "da8e9778-2e2f-4eb3-b50e-76be49f5ba38";
+/// Options for a hydration run.
+#[derive(Debug, Clone, Default)]
+pub struct HydrationOptions {
+    /// Stop after this many records; the next run resumes from the checkpoint
+    pub max_records: Option<usize>,
+    /// Report what would be written without touching PostgreSQL or the checkpoint
+    pub dry_run: bool,
+}
 
-/// Hydrate users from Redis into PostgreSQL (one-time migration).
-pub async fn hydrate_users_from_redis(
+/// Hydrate all tables from Redis into PostgreSQL (one-time migration).
+pub async fn hydrate_all_from_redis(
     redis: &RedisClient,
     pool: &PgPool,
-) -> Result<usize, AppError> {
-    let mut conn = redis
-        .get()
-        .await
-        .map_err(|e| AppError::RedisError(format!("Failed to get Redis connection: {}", e)))?;
-
-    // Get all user keys: users:data:*
-    let pattern = RedisKey::user(KeyPart::Wildcard);
-    let keys: Vec<String> = conn
-        .keys(&pattern)
-        .await
-        .map_err(AppError::RedisCommandError)?;
-
-    let mut hydrated_count = 0;
-
-    println!(
-        "Found {} user keys matching pattern: {}",
-        keys.len(),
-        pattern
-    );
-
-    for key in keys {
-        // Extract user_id from key "users:data:{uuid}"
-        let user_id = key
-            .strip_prefix("users:data:")
-            .and_then(|id| Uuid::parse_str(id).ok());
-
-        if user_id.is_none() {
-            println!("⚠️  Skipping invalid user key: {}", key);
-            continue;
-        }
-        let user_id = user_id.unwrap();
-
-        // Get user data from Redis hash
-        let user_data: HashMap<String, String> = conn
-            .hgetall(&key)
-            .await
-            .map_err(AppError::RedisCommandError)?;
-
-        if user_data.is_empty() {
-            println!("⚠️  Empty data for user {}, skipping", user_id);
-            continue;
-        }
-
-        // Parse into User struct (this contains all the fields we need)
-        // User struct has: id, wallet_address, wars_point, username, display_name
-        let wallet_address = match user_data.get("wallet_address") {
-            Some(addr) => addr.clone(),
-            None => {
-                println!("⚠️  Missing wallet_address for user {}, skipping", user_id);
-                continue;
-            }
-        };
-
-        let username = user_data.get("username").cloned();
-        let display_name = user_data.get("display_name").cloned();
-
-        // Insert into PostgreSQL
-        let result = sqlx::query(
-            r#"
-            INSERT INTO users (id, wallet_address, username, display_name, trust_rating, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $6)
-            ON CONFLICT (wallet_address) DO UPDATE SET
-                username = COALESCE(EXCLUDED.username, users.username),
-                display_name = COALESCE(EXCLUDED.display_name, users.display_name),
-                updated_at = EXCLUDED.updated_at
-            "#,
-        )
-        .bind(user_id)
-        .bind(&wallet_address)
-        .bind(&username)
-        .bind(&display_name)
-        .bind(10.0) // Default trust rating
-        .bind(chrono::Utc::now().naive_utc())
-        .execute(pool)
-        .await
-        .map_err(|e| {
-            AppError::DatabaseError(format!("Failed to insert user {}: {}", user_id, e))
-        })?;
-
-        hydrated_count += 1;
-        let action = if result.rows_affected() > 0 {
-            "✅ Hydrated"
-        } else {
-            "  Updated"
-        };
-        println!(
-            "{} user: {} ({})",
-            action,
-            username.as_deref().unwrap_or("unknown"),
-            user_id
-        );
-    }
-
-    Ok(hydrated_count)
+) -> Result<HydrationSummary, AppError> {
+    hydrate_all_with_options(redis, pool, &HydrationOptions::default()).await
 }
 
-/// Hydrate games from Redis into PostgreSQL (one-time migration).
-pub async fn hydrate_games_from_redis(
+/// Hydrate all tables, resuming from any checkpoint an earlier run left.
+pub async fn hydrate_all_with_options(
     redis: &RedisClient,
     pool: &PgPool,
-) -> Result<usize, AppError> {
-    let mut conn = redis
-        .get()
-        .await
-        .map_err(|e| AppError::RedisError(format!("Failed to get Redis connection: {}", e)))?;
-
-    // Get all game keys: games:*:data
-    let pattern = RedisKey::game(KeyPart::Wildcard);
-    let keys: Vec<String> = conn
-        .keys(&pattern)
-        .await
-        .map_err(AppError::RedisCommandError)?;
-
-    let mut hydrated_count = 0;
-
-    println!(
-        "Found {} game keys matching pattern: {}",
-        keys.len(),
-        pattern
-    );
+    options: &HydrationOptions,
+) -> Result<HydrationSummary, AppError> {
+    let invalid = validate_from_redis(redis).await?;
+    if !invalid.is_empty() {
+        tracing::warn!(
+            "Validation found {} records that will not hydrate",
+            invalid.len()
+        );
+    }
 
-    // Default creator ID
-    let default_creator_id =
-        Uuid::parse_str(DEFAULT_CREATOR_ID).expect("DEFAULT_CREATOR_ID must be a valid UUID");
+    let mut budget = options.max_records;
+    let mut planned = HashSet::new();
+    let mut phases = Vec::new();
+    let mut complete = true;
+
+    for phase in HydrationPhase::ALL {
+        let (summary, finished) = hydrate_phase(
+            redis,
+            pool,
+            phase,
+            &mut budget,
+            options.dry_run.then_some(&mut planned),
+        )
+        .await?;
+        tracing::info!(
+            dry_run = options.dry_run,
+            phase = phase.as_str(),
+            total = summary.total,
+            resumed_past = summary.resumed_past,
+            hydrated = summary.hydrated,
+            existing = summary.existing,
+            failed = summary.failures.len(),
+            "Hydration phase {}",
+            if finished { "finished" } else { "stopped" }
+        );
+        phases.push(summary);
 
-    for key in keys {
-        // Extract game_id from key "games:{uuid}:data"
-        let parts: Vec<&str> = key.split(':').collect();
-        if parts.len() != 3 || parts[2] != "data" {
-            println!("⚠️  Invalid game key format: {}", key);
-            continue;
+        if !finished {
+            complete = false;
+            break;
         }
+    }
 
-        let game_id = match Uuid::parse_str(parts[1]) {
-            Ok(id) => id,
-            Err(_) => {
-                println!("⚠️  Invalid game ID in key: {}", key);
-                continue;
-            }
-        };
+    if complete && !options.dry_run {
+        clear_checkpoint(redis).await?;
+    }
 
-        // Get game data from Redis hash
-        let game_data: HashMap<String, String> = conn
-            .hgetall(&key)
-            .await
-            .map_err(AppError::RedisCommandError)?;
+    Ok(HydrationSummary {
+        phases,
+        complete,
+        dry_run: options.dry_run,
+        invalid,
+    })
+}
 
-        if game_data.is_empty() {
-            println!("⚠️  Empty data for game {}, skipping", game_id);
-            continue;
-        }
+/// Parse every record without writing anything; returns the ones that fail.
+pub async fn validate_from_redis(redis: &RedisClient) -> Result<Vec<HydrationFailure>, AppError> {
+    let mut conn = redis.get().await?;
+    let mut invalid = Vec::new();
 
-        // Parse fields from Redis (using GameType structure)
-        let name = match game_data.get("name") {
-            Some(n) => n.clone(),
-            None => {
-                println!("⚠️  Missing name for game {}, skipping", game_id);
-                continue;
+    for phase in HydrationPhase::ALL {
+        for key in phase_keys(&mut conn, phase).await? {
+            let data: HashMap<String, String> = conn
+                .hgetall(&key)
+                .await
+                .map_err(AppError::RedisCommandError)?;
+            if let Err(reason) = parse_record(phase, &key, &data) {
+                tracing::warn!(phase = phase.as_str(), "Invalid record {}: {}", key, reason);
+                invalid.push(HydrationFailure { key, reason });
             }
-        };
-
-        let description = game_data
-            .get("description")
-            .cloned()
-            .unwrap_or_else(|| "No description available".to_string());
-
-        let image_url = game_data
-            .get("image_url")
-            .cloned()
-            .unwrap_or_else(|| "".to_string());
-
-        let min_players = game_data
-            .get("min_players")
-            .and_then(|s| s.parse::<i16>().ok())
-            .unwrap_or(2);
-
-        // Default values for fields not in Redis
-        let max_players = 16;
-        let category = Some("puzzle".to_string());
-        let creator_id = default_creator_id;
-        let is_active = true;
-
-        // Insert into PostgreSQL
-        let result = sqlx::query(
-            r#"
-            INSERT INTO games (
-                id, name, description, image_url,
-                min_players, max_players, category,
-                creator_id, is_active, created_at, updated_at, path
-            )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $10, $11)
-            ON CONFLICT (name) DO UPDATE SET
-                description = EXCLUDED.description,
-                image_url = EXCLUDED.image_url,
-                min_players = EXCLUDED.min_players,
-                max_players = EXCLUDED.max_players,
-                updated_at = EXCLUDED.updated_at
-            "#,
-        )
-        .bind(game_id)
-        .bind(&name)
-        .bind(&description)
-        .bind(&image_url)
-        .bind(min_players)
-        .bind(max_players)
-        .bind(&category)
-        .bind(creator_id)
-        .bind(is_active)
-        .bind(chrono::Utc::now().naive_utc())
-        .bind("lexi-wars")
-        .execute(pool)
-        .await
-        .map_err(|e| {
-            AppError::DatabaseError(format!("Failed to insert game {}: {}", game_id, e))
-        })?;
-
-        if result.rows_affected() > 0 {
-            hydrated_count += 1;
-            println!(
-                "✅ Hydrated game: {} ({}) - min_players={}, max_players={}",
-                name, game_id, min_players, max_players
-            );
-        } else {
-            println!("  Game {} already exists, updated", game_id);
         }
     }
 
-    Ok(hydrated_count)
+    Ok(invalid)
 }
 
-/// Hydrate lobbies from Redis into PostgreSQL (one-time migration).
-pub async fn hydrate_lobbies_from_redis(
-    redis: &RedisClient,
-    pool: &PgPool,
-) -> Result<usize, AppError> {
-    let mut conn = redis
-        .get()
-        .await
-        .map_err(|e| AppError::RedisError(format!("Failed to get Redis connection: {}", e)))?;
-
-    // Get all lobby info keys: lobbies:*:info
-    let pattern = RedisKey::lobby(KeyPart::Wildcard);
-    let keys: Vec<String> = conn
+/// A phase's keys in sorted order, so the checkpoint marks a position
+async fn phase_keys(
+    conn: &mut RedisConnection<'_>,
+    phase: HydrationPhase,
+) -> Result<Vec<String>, AppError> {
+    let pattern = match phase {
+        HydrationPhase::Users => RedisKey::user(KeyPart::Wildcard),
+        HydrationPhase::Games => RedisKey::game(KeyPart::Wildcard),
+        HydrationPhase::Lobbies => RedisKey::lobby(KeyPart::Wildcard),
+    };
+
+    let mut keys: Vec<String> = conn
         .keys(&pattern)
         .await
         .map_err(AppError::RedisCommandError)?;
+    keys.sort();
+    Ok(keys)
+}
 
-    let mut hydrated_count = 0;
-
-    println!(
-        "Found {} lobby keys matching pattern: {}",
-        keys.len(),
-        pattern
-    );
-
-    // Default creator ID
-    let default_creator_id =
-        Uuid::parse_str(DEFAULT_CREATOR_ID).expect("DEFAULT_CREATOR_ID must be a valid UUID");
+/// Hydrate one table, skipping keys an earlier run finished.
+///
+/// Takes records out of `budget` when one is set. With `dry_run_planned`,
+/// nothing is written: records are only planned, and the ids of rows that
+/// would be inserted are added to the set for later phases. Returns the phase
+/// summary and whether every key was handled.
+pub async fn hydrate_phase(
+    redis: &RedisClient,
+    pool: &PgPool,
+    phase: HydrationPhase,
+    budget: &mut Option<usize>,
+    mut dry_run_planned: Option<&mut HashSet<Uuid>>,
+) -> Result<(PhaseSummary, bool), AppError> {
+    let mut conn = redis.get().await?;
+    let keys = phase_keys(&mut conn, phase).await?;
+
+    let mut summary = PhaseSummary::new(phase);
+    summary.total = keys.len();
+
+    let checkpoint = load_checkpoint(redis, phase).await?;
+    if let Some(checkpoint) = &checkpoint {
+        summary.resumed_past = keys.iter().filter(|key| *key <= checkpoint).count();
+        tracing::info!(
+            phase = phase.as_str(),
+            "Resuming hydration after {} ({} of {} keys done)",
+            checkpoint,
+            summary.resumed_past,
+            summary.total
+        );
+    }
 
-    for key in keys {
-        // Extract lobby_id from key "lobbies:{uuid}:info"
-        let parts: Vec<&str> = key.split(':').collect();
-        if parts.len() != 3 || parts[2] != "info" {
-            println!("⚠️  Invalid lobby key format: {}", key);
-            continue;
+    for key in keys.iter().skip(summary.resumed_past) {
+        if *budget == Some(0) {
+            return Ok((summary, false));
         }
 
-        let lobby_id = match Uuid::parse_str(parts[1]) {
-            Ok(id) => id,
-            Err(_) => {
-                println!("⚠️  Invalid lobby ID in key: {}", key);
-                continue;
-            }
-        };
-
-        // Get lobby data from Redis hash
-        let lobby_data: HashMap<String, String> = conn
-            .hgetall(&key)
+        let data: HashMap<String, String> = conn
+            .hgetall(key)
             .await
             .map_err(AppError::RedisCommandError)?;
 
-        if lobby_data.is_empty() {
-            println!("⚠️  Empty data for lobby {}, skipping", lobby_id);
-            continue;
-        }
-
-        // Parse using LobbyInfo::from_redis_hash_partial
-        // This returns (LobbyInfo, creator_id, game_id)
-        let (lobby_info, creator_id, game_id) =
-            match LobbyInfo::from_redis_hash_partial(&lobby_data) {
-                Ok(data) => data,
-                Err(e) => {
-                    println!("⚠️  Failed to parse lobby {}: {}", lobby_id, e);
-                    continue;
+        let outcome = match (
+            parse_record(phase, key, &data),
+            dry_run_planned.as_deref_mut(),
+        ) {
+            (Err(reason), _) => Err(reason),
+            (Ok(record), Some(planned)) => {
+                let outcome = plan_record(pool, &record, planned).await;
+                if let Ok(RecordOutcome::Hydrated) = outcome {
+                    planned.insert(record.id());
                 }
-            };
-
-        // Check if creator exists in database, otherwise use default
-        let creator_exists =
-            sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM users WHERE id = $1)")
-                .bind(creator_id)
-                .fetch_one(pool)
-                .await
-                .unwrap_or(false);
-
-        let final_creator_id = if creator_exists {
-            creator_id
-        } else {
-            println!(
-                "⚠️  Creator {} not found for lobby {}, using default creator",
-                creator_id, lobby_id
-            );
-            default_creator_id
+                outcome
+            }
+            (Ok(record), None) => write_record(pool, record).await,
         };
-
-        // Check if game exists in database
-        let game_exists =
-            sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM games WHERE id = $1)")
-                .bind(game_id)
-                .fetch_one(pool)
-                .await
-                .unwrap_or(false);
-
-        if !game_exists {
-            println!(
-                "⚠️  Game {} not found for lobby {}, skipping lobby",
-                game_id, lobby_id
-            );
-            continue;
+        match outcome {
+            Ok(RecordOutcome::Hydrated) => summary.hydrated += 1,
+            Ok(RecordOutcome::Existing) => summary.existing += 1,
+            Err(reason) => {
+                tracing::warn!(phase = phase.as_str(), "Skipping {}: {}", key, reason);
+                summary.failures.push(HydrationFailure {
+                    key: key.clone(),
+                    reason,
+                });
+            }
         }
 
-        // Extract fields from LobbyInfo
-        let name = lobby_info.name;
-        let description = lobby_info.description;
-        let entry_amount = lobby_info.entry_amount.unwrap_or(0.0);
-        let current_amount = lobby_info.current_amount.unwrap_or(0.0);
-        let token_symbol = lobby_info.token_symbol;
-        let token_contract_id = lobby_info.token_id; // token_id in LobbyInfo
-        let contract_address = lobby_info.contract_address;
-
-        // Business rules per user's instructions:
-        // - is_private: Set to true for all (doesn't exist in LobbyInfo)
-        let is_private = true;
-
-        // - is_sponsored: true if entry_amount is 0 and current_amount > 0
-        let is_sponsored = entry_amount == 0.0 && current_amount > 0.0;
-
-        // Convert LobbyState enum to PostgreSQL enum string
-        let status = LobbyStatus::Finished; // Default to Finished
-
-        // Insert into PostgreSQL using raw SQL
-        let result = sqlx::query(
-            r#"
-            INSERT INTO lobbies (
-                id, name, description, creator_id, game_id,
-                entry_amount, current_amount, token_symbol, token_contract_id, contract_address,
-                is_private, is_sponsored, status, created_at, updated_at
-            )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $14)
-            ON CONFLICT (id) DO NOTHING
-            "#,
-        )
-        .bind(lobby_id)
-        .bind(&name)
-        .bind(description)
-        .bind(final_creator_id)
-        .bind(game_id)
-        .bind(entry_amount)
-        .bind(current_amount)
-        .bind(token_symbol)
-        .bind(token_contract_id)
-        .bind(contract_address)
-        .bind(is_private)
-        .bind(is_sponsored)
-        .bind(status)
-        .bind(chrono::Utc::now().naive_utc())
-        .execute(pool)
-        .await
-        .map_err(|e| {
-            AppError::DatabaseError(format!("Failed to insert lobby {}: {}", lobby_id, e))
-        })?;
-
-        if result.rows_affected() > 0 {
-            hydrated_count += 1;
-            println!(
-                "✅ Hydrated lobby: {} ({}) - private={}, sponsored={}",
-                name, lobby_id, is_private, is_sponsored
-            );
-        } else {
-            println!("  Lobby {} already exists, skipping", lobby_id);
+        if dry_run_planned.is_none() {
+            save_checkpoint(redis, phase, key).await?;
+        }
+        if let Some(remaining) = budget {
+            *remaining -= 1;
         }
     }
 
-    Ok(hydrated_count)
-}
-
-/// Hydrate all tables from Redis into PostgreSQL (one-time migration).
-pub async fn hydrate_all_from_redis(redis: &RedisClient, pool: &PgPool) -> Result<(), AppError> {
-    println!("╔═══════════════════════════════════════════════╗");
-    println!("║  Starting PostgreSQL Hydration from Redis   ║");
-    println!("╚═══════════════════════════════════════════════╝\n");
-
-    // Hydrate users first (lobbies depend on users)
-    println!("📊 Phase 1: Hydrating users table...");
-    let user_count = hydrate_users_from_redis(redis, pool).await?;
-    println!("   {} users migrated\n", user_count);
-
-    // Hydrate games second (lobbies depend on games)
-    println!("📊 Phase 2: Hydrating games table...");
-    let game_count = hydrate_games_from_redis(redis, pool).await?;
-    println!("   {} games migrated\n", game_count);
-
-    // Hydrate lobbies last (depends on both users and games)
-    println!("📊 Phase 3: Hydrating lobbies table...");
-    let lobby_count = hydrate_lobbies_from_redis(redis, pool).await?;
-    println!("   {} lobbies migrated\n", lobby_count);
-
-    println!("╔═══════════════════════════════════════════════╗");
-    println!("║  🎉 Hydration Complete!                      ║");
-    println!(
-        "║  ✅ {} users migrated                        ",
-        user_count
-    );
-    println!(
-        "║  ✅ {} games migrated                        ",
-        game_count
-    );
-    println!(
-        "║  ✅ {} lobbies migrated                      ",
-        lobby_count
-    );
-    println!("╚═══════════════════════════════════════════════╝");
-
-    Ok(())
+    Ok((summary, true))
 }
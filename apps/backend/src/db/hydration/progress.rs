@@ -0,0 +1,143 @@
+// Hydration progress: per-phase checkpoints in Redis and the run summary
+//
+// Each phase walks its Redis keys in sorted order and records the last key it
+// finished in the checkpoint hash (one field per phase). A re-run skips every
+// key up to the checkpoint, so an interrupted hydration picks up where it
+// stopped. A completed run clears the checkpoint.
+
+use ::redis::AsyncCommands;
+use serde::Serialize;
+
+use crate::errors::AppError;
+use crate::models::RedisKey;
+use crate::state::RedisClient;
+
+/// Tables hydrated, in dependency order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HydrationPhase {
+    Users,
+    Games,
+    Lobbies,
+}
+
+impl HydrationPhase {
+    /// Every phase, in the order they run (lobbies reference users and games)
+    pub const ALL: [HydrationPhase; 3] = [
+        HydrationPhase::Users,
+        HydrationPhase::Games,
+        HydrationPhase::Lobbies,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HydrationPhase::Users => "users",
+            HydrationPhase::Games => "games",
+            HydrationPhase::Lobbies => "lobbies",
+        }
+    }
+}
+
+/// A record that could not be hydrated.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HydrationFailure {
+    pub key: String,
+    pub reason: String,
+}
+
+/// What one phase did.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhaseSummary {
+    pub phase: HydrationPhase,
+    /// Keys found for this phase
+    pub total: usize,
+    /// Keys already handled by an earlier, interrupted run
+    pub resumed_past: usize,
+    /// Rows inserted (or, in a dry run, that would be)
+    pub hydrated: usize,
+    /// Rows that already existed (updated or left alone)
+    pub existing: usize,
+    pub failures: Vec<HydrationFailure>,
+}
+
+impl PhaseSummary {
+    pub fn new(phase: HydrationPhase) -> Self {
+        Self {
+            phase,
+            total: 0,
+            resumed_past: 0,
+            hydrated: 0,
+            existing: 0,
+            failures: Vec::new(),
+        }
+    }
+
+    /// Keys this run went through
+    pub fn processed(&self) -> usize {
+        self.hydrated + self.existing + self.failures.len()
+    }
+}
+
+/// What a hydration run did, phase by phase.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HydrationSummary {
+    pub phases: Vec<PhaseSummary>,
+    /// False when the run stopped early; the next run resumes from the checkpoint
+    pub complete: bool,
+    /// Nothing was written; `hydrated` counts planned inserts
+    pub dry_run: bool,
+    /// Records the validation pass found unparseable, before any writes
+    pub invalid: Vec<HydrationFailure>,
+}
+
+impl HydrationSummary {
+    pub fn phase(&self, phase: HydrationPhase) -> Option<&PhaseSummary> {
+        self.phases.iter().find(|p| p.phase == phase)
+    }
+
+    pub fn hydrated(&self) -> usize {
+        self.phases.iter().map(|p| p.hydrated).sum()
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &HydrationFailure> {
+        self.phases.iter().flat_map(|p| p.failures.iter())
+    }
+}
+
+/// Last key a phase finished, if an earlier run got that far.
+pub async fn load_checkpoint(
+    redis: &RedisClient,
+    phase: HydrationPhase,
+) -> Result<Option<String>, AppError> {
+    let mut conn = redis.get().await?;
+    conn.hget(RedisKey::hydration_checkpoint(), phase.as_str())
+        .await
+        .map_err(AppError::RedisCommandError)
+}
+
+/// Record `key` as the last one `phase` finished.
+pub async fn save_checkpoint(
+    redis: &RedisClient,
+    phase: HydrationPhase,
+    key: &str,
+) -> Result<(), AppError> {
+    let mut conn = redis.get().await?;
+    let _: () = conn
+        .hset(RedisKey::hydration_checkpoint(), phase.as_str(), key)
+        .await
+        .map_err(AppError::RedisCommandError)?;
+    Ok(())
+}
+
+/// Forget all progress so the next run starts from the beginning.
+pub async fn clear_checkpoint(redis: &RedisClient) -> Result<(), AppError> {
+    let mut conn = redis.get().await?;
+    let _: () = conn
+        .del(RedisKey::hydration_checkpoint())
+        .await
+        .map_err(AppError::RedisCommandError)?;
+    Ok(())
+}
@@ -0,0 +1,386 @@
+// Hydration records: parse legacy Redis hashes, then plan or write their rows
+//
+// Parsing needs nothing but the key and hash, so the validation pass can run
+// it over every record before anything is written. Planning answers what a
+// write would do using reads only; it backs dry runs.
+
+use std::collections::{HashMap, HashSet};
+
+use rust_decimal::Decimal;
+use sqlx::{PgPool, Postgres};
+use uuid::Uuid;
+
+use crate::db::hydration::progress::HydrationPhase;
+use crate::db::hydration::types::LobbyInfo;
+use crate::models::LobbyStatus;
+
+/// Default creator ID for games and lobbies when creator is missing in Redis
+/// This user must exist in the database before hydration
+const DEFAULT_CREATOR_ID: &str = "da8e9778-2e2f-4eb3-b50e-76be49f5ba38";
+
+/// How a record lands (or would land) in PostgreSQL
+pub(super) enum RecordOutcome {
+    Hydrated,
+    Existing,
+}
+
+/// A legacy record parsed out of Redis.
+pub(super) enum Record {
+    User {
+        user_id: Uuid,
+        wallet_address: String,
+        username: Option<String>,
+        display_name: Option<String>,
+    },
+    Game {
+        game_id: Uuid,
+        name: String,
+        description: String,
+        image_url: String,
+        min_players: i16,
+    },
+    Lobby {
+        lobby_id: Uuid,
+        info: Box<LobbyInfo>,
+        creator_id: Uuid,
+        game_id: Uuid,
+    },
+}
+
+impl Record {
+    /// Id of the row this record becomes
+    pub(super) fn id(&self) -> Uuid {
+        match self {
+            Record::User { user_id, .. } => *user_id,
+            Record::Game { game_id, .. } => *game_id,
+            Record::Lobby { lobby_id, .. } => *lobby_id,
+        }
+    }
+}
+
+fn default_creator_id() -> Uuid {
+    Uuid::parse_str(DEFAULT_CREATOR_ID).expect("DEFAULT_CREATOR_ID must be a valid UUID")
+}
+
+/// Parse the hash stored under `key` for `phase`.
+pub(super) fn parse_record(
+    phase: HydrationPhase,
+    key: &str,
+    data: &HashMap<String, String>,
+) -> Result<Record, String> {
+    match phase {
+        HydrationPhase::Users => parse_user(key, data),
+        HydrationPhase::Games => parse_game(key, data),
+        HydrationPhase::Lobbies => parse_lobby(key, data),
+    }
+}
+
+fn parse_user(key: &str, user_data: &HashMap<String, String>) -> Result<Record, String> {
+    // Extract user_id from key "users:data:{uuid}"
+    let user_id = key
+        .strip_prefix("users:data:")
+        .and_then(|id| Uuid::parse_str(id).ok())
+        .ok_or_else(|| "invalid user key".to_string())?;
+
+    if user_data.is_empty() {
+        return Err(format!("empty data for user {}", user_id));
+    }
+
+    // User struct has: id, wallet_address, wars_point, username, display_name
+    let wallet_address = user_data
+        .get("wallet_address")
+        .cloned()
+        .ok_or_else(|| format!("missing wallet_address for user {}", user_id))?;
+
+    Ok(Record::User {
+        user_id,
+        wallet_address,
+        username: user_data.get("username").cloned(),
+        display_name: user_data.get("display_name").cloned(),
+    })
+}
+
+fn parse_game(key: &str, game_data: &HashMap<String, String>) -> Result<Record, String> {
+    // Extract game_id from key "games:{uuid}:data"
+    let parts: Vec<&str> = key.split(':').collect();
+    if parts.len() != 3 || parts[2] != "data" {
+        return Err("invalid game key format".to_string());
+    }
+    let game_id = Uuid::parse_str(parts[1]).map_err(|_| "invalid game ID in key".to_string())?;
+
+    if game_data.is_empty() {
+        return Err(format!("empty data for game {}", game_id));
+    }
+
+    // Parse fields from Redis (using GameType structure)
+    let name = game_data
+        .get("name")
+        .cloned()
+        .ok_or_else(|| format!("missing name for game {}", game_id))?;
+
+    Ok(Record::Game {
+        game_id,
+        name,
+        description: game_data
+            .get("description")
+            .cloned()
+            .unwrap_or_else(|| "No description available".to_string()),
+        image_url: game_data.get("image_url").cloned().unwrap_or_default(),
+        min_players: game_data
+            .get("min_players")
+            .and_then(|s| s.parse::<i16>().ok())
+            .unwrap_or(2),
+    })
+}
+
+fn parse_lobby(key: &str, lobby_data: &HashMap<String, String>) -> Result<Record, String> {
+    // Extract lobby_id from key "lobbies:{uuid}:info"
+    let parts: Vec<&str> = key.split(':').collect();
+    if parts.len() != 3 || parts[2] != "info" {
+        return Err("invalid lobby key format".to_string());
+    }
+    let lobby_id = Uuid::parse_str(parts[1]).map_err(|_| "invalid lobby ID in key".to_string())?;
+
+    if lobby_data.is_empty() {
+        return Err(format!("empty data for lobby {}", lobby_id));
+    }
+
+    // This returns (LobbyInfo, creator_id, game_id)
+    let (info, creator_id, game_id) = LobbyInfo::from_redis_hash_partial(lobby_data)
+        .map_err(|e| format!("failed to parse lobby {}: {}", lobby_id, e))?;
+
+    Ok(Record::Lobby {
+        lobby_id,
+        info: Box::new(info),
+        creator_id,
+        game_id,
+    })
+}
+
+async fn row_exists<'q, T>(pool: &PgPool, query: &'q str, value: T) -> bool
+where
+    T: 'q + Send + sqlx::Encode<'q, Postgres> + sqlx::Type<Postgres>,
+{
+    sqlx::query_scalar::<_, bool>(query)
+        .bind(value)
+        .fetch_one(pool)
+        .await
+        .unwrap_or(false)
+}
+
+async fn user_exists(pool: &PgPool, user_id: Uuid) -> bool {
+    row_exists(
+        pool,
+        "SELECT EXISTS(SELECT 1 FROM users WHERE id = $1)",
+        user_id,
+    )
+    .await
+}
+
+async fn game_exists(pool: &PgPool, game_id: Uuid) -> bool {
+    row_exists(
+        pool,
+        "SELECT EXISTS(SELECT 1 FROM games WHERE id = $1)",
+        game_id,
+    )
+    .await
+}
+
+/// What writing `record` would do, using reads only.
+///
+/// `planned` holds rows earlier phases of the same dry run would have
+/// inserted, so lobbies whose game is still only in Redis aren't rejected.
+pub(super) async fn plan_record(
+    pool: &PgPool,
+    record: &Record,
+    planned: &HashSet<Uuid>,
+) -> Result<RecordOutcome, String> {
+    let exists = match record {
+        Record::User { wallet_address, .. } => {
+            row_exists(
+                pool,
+                "SELECT EXISTS(SELECT 1 FROM users WHERE wallet_address = $1)",
+                wallet_address,
+            )
+            .await
+        }
+        Record::Game { name, .. } => {
+            row_exists(
+                pool,
+                "SELECT EXISTS(SELECT 1 FROM games WHERE name = $1)",
+                name,
+            )
+            .await
+        }
+        Record::Lobby {
+            lobby_id, game_id, ..
+        } => {
+            if !planned.contains(game_id) && !game_exists(pool, *game_id).await {
+                return Err(format!("game {} not found for lobby {}", game_id, lobby_id));
+            }
+            row_exists(
+                pool,
+                "SELECT EXISTS(SELECT 1 FROM lobbies WHERE id = $1)",
+                lobby_id,
+            )
+            .await
+        }
+    };
+
+    Ok(if exists {
+        RecordOutcome::Existing
+    } else {
+        RecordOutcome::Hydrated
+    })
+}
+
+/// Write `record` to PostgreSQL.
+pub(super) async fn write_record(pool: &PgPool, record: Record) -> Result<RecordOutcome, String> {
+    let rows_affected = match record {
+        Record::User {
+            user_id,
+            wallet_address,
+            username,
+            display_name,
+        } => sqlx::query(
+            r#"
+            INSERT INTO users (id, wallet_address, username, display_name, trust_rating, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $6)
+            ON CONFLICT (wallet_address) DO UPDATE SET
+                username = COALESCE(EXCLUDED.username, users.username),
+                display_name = COALESCE(EXCLUDED.display_name, users.display_name),
+                updated_at = EXCLUDED.updated_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(&wallet_address)
+        .bind(&username)
+        .bind(&display_name)
+        .bind(10.0) // Default trust rating
+        .bind(chrono::Utc::now().naive_utc())
+        .execute(pool)
+        .await
+        .map_err(|e| format!("failed to insert user {}: {}", user_id, e))?
+        .rows_affected(),
+
+        Record::Game {
+            game_id,
+            name,
+            description,
+            image_url,
+            min_players,
+        } => {
+            // Default values for fields not in Redis
+            let max_players: i16 = 16;
+            let category = Some("puzzle".to_string());
+            let is_active = true;
+
+            sqlx::query(
+                r#"
+                INSERT INTO games (
+                    id, name, description, image_url,
+                    min_players, max_players, category,
+                    creator_id, is_active, created_at, updated_at, path
+                )
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $10, $11)
+                ON CONFLICT (name) DO UPDATE SET
+                    description = EXCLUDED.description,
+                    image_url = EXCLUDED.image_url,
+                    min_players = EXCLUDED.min_players,
+                    max_players = EXCLUDED.max_players,
+                    updated_at = EXCLUDED.updated_at
+                "#,
+            )
+            .bind(game_id)
+            .bind(&name)
+            .bind(&description)
+            .bind(&image_url)
+            .bind(min_players)
+            .bind(max_players)
+            .bind(&category)
+            .bind(default_creator_id())
+            .bind(is_active)
+            .bind(chrono::Utc::now().naive_utc())
+            .bind("lexi-wars")
+            .execute(pool)
+            .await
+            .map_err(|e| format!("failed to insert game {}: {}", game_id, e))?
+            .rows_affected()
+        }
+
+        Record::Lobby {
+            lobby_id,
+            info,
+            creator_id,
+            game_id,
+        } => {
+            // Check if creator exists in database, otherwise use default
+            let creator_id = if user_exists(pool, creator_id).await {
+                creator_id
+            } else {
+                tracing::warn!(
+                    "Creator {} not found for lobby {}, using default creator",
+                    creator_id,
+                    lobby_id
+                );
+                default_creator_id()
+            };
+
+            if !game_exists(pool, game_id).await {
+                return Err(format!(
+                    "game {} not found for lobby {}",
+                    game_id, lobby_id
+                ));
+            }
+
+            let info = *info;
+            let entry_amount = info.entry_amount.unwrap_or_default();
+            let current_amount = info.current_amount.unwrap_or_default();
+
+            // Business rules per user's instructions:
+            // - is_private: Set to true for all (doesn't exist in LobbyInfo)
+            let is_private = true;
+
+            // - is_sponsored: true if entry_amount is 0 and current_amount > 0
+            let is_sponsored = entry_amount.is_zero() && current_amount > Decimal::ZERO;
+
+            let status = LobbyStatus::Finished; // Default to Finished
+
+            sqlx::query(
+                r#"
+                INSERT INTO lobbies (
+                    id, name, description, creator_id, game_id,
+                    entry_amount, current_amount, token_symbol, token_contract_id, contract_address,
+                    is_private, is_sponsored, status, created_at, updated_at
+                )
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $14)
+                ON CONFLICT (id) DO NOTHING
+                "#,
+            )
+            .bind(lobby_id)
+            .bind(&info.name)
+            .bind(info.description)
+            .bind(creator_id)
+            .bind(game_id)
+            .bind(entry_amount)
+            .bind(current_amount)
+            .bind(info.token_symbol)
+            .bind(info.token_id) // token_id in LobbyInfo
+            .bind(info.contract_address)
+            .bind(is_private)
+            .bind(is_sponsored)
+            .bind(status)
+            .bind(chrono::Utc::now().naive_utc())
+            .execute(pool)
+            .await
+            .map_err(|e| format!("failed to insert lobby {}: {}", lobby_id, e))?
+            .rows_affected()
+        }
+    };
+
+    Ok(if rows_affected > 0 {
+        RecordOutcome::Hydrated
+    } else {
+        RecordOutcome::Existing
+    })
+}
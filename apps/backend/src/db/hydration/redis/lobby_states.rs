@@ -16,10 +16,7 @@ pub async fn migrate_lobby_states(
     lobby_state_repo: &LobbyStateRepository,
     dry_run: bool,
 ) -> Result<usize, AppError> {
-    let mut conn = redis
-        .get()
-        .await
-        .map_err(|e| AppError::RedisError(format!("Failed to get Redis connection: {}", e)))?;
+    let mut conn = redis.get().await?;
 
     // Scan all lobby info keys directly
     let pattern = "lobbies:*:info";
@@ -17,10 +17,7 @@ pub async fn migrate_player_states(
     player_state_repo: &PlayerStateRepository,
     dry_run: bool,
 ) -> Result<usize, AppError> {
-    let mut conn = redis
-        .get()
-        .await
-        .map_err(|e| AppError::RedisError(format!("Failed to get Redis connection: {}", e)))?;
+    let mut conn = redis.get().await?;
 
     // Scan all player keys directly
     let pattern = "lobbies:*:player:*";
@@ -1,6 +1,7 @@
 use std::{collections::HashMap, str::FromStr};
 
 use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -56,7 +57,8 @@ pub struct Player {
     pub rank: Option<usize>,
     pub tx_id: Option<String>,
     pub claim: Option<ClaimState>,
-    pub prize: Option<f64>,
+    #[serde(with = "rust_decimal::serde::float_option")]
+    pub prize: Option<Decimal>,
     pub last_ping: Option<u64>,
 
     // Game-specific fields only
@@ -93,7 +95,7 @@ impl Player {
             .get("claim")
             .and_then(|v| serde_json::from_str::<ClaimState>(v).ok());
 
-        let prize = data.get("prize").and_then(|v| v.parse::<f64>().ok());
+        let prize = data.get("prize").and_then(|v| v.parse::<Decimal>().ok());
 
         let last_ping = data.get("last_ping").and_then(|v| v.parse::<u64>().ok());
 
@@ -162,8 +164,10 @@ pub struct LobbyInfo {
 
     pub description: Option<String>,
     pub contract_address: Option<String>,
-    pub entry_amount: Option<f64>,
-    pub current_amount: Option<f64>,
+    #[serde(with = "rust_decimal::serde::float_option")]
+    pub entry_amount: Option<Decimal>,
+    #[serde(with = "rust_decimal::serde::float_option")]
+    pub current_amount: Option<Decimal>,
     pub token_symbol: Option<String>,
     pub token_id: Option<String>,
     pub creator_last_ping: Option<u64>,
@@ -1,4 +1,8 @@
-use crate::db::join_request::{JoinRequest, JoinRequestRepository, JoinRequestState};
+use crate::db::expiry::apply_expiry;
+use crate::db::join_request::{
+    JoinRequest, JoinRequestReason, JoinRequestRepository, JoinRequestState,
+};
+use crate::models::User;
 use crate::models::keys::RedisKey;
 use chrono::Utc;
 use redis::AsyncCommands;
@@ -14,28 +18,58 @@ impl JoinRequestRepository {
         username: Option<String>,
         display_name: Option<String>,
         trust_rating: f64,
-        ttl_seconds: usize,
     ) -> redis::RedisResult<()> {
+        let jr = JoinRequest {
+            user_id,
+            state: JoinRequestState::Pending,
+            wallet_address,
+            username,
+            display_name,
+            trust_rating,
+            is_creator: false,
+            created_at: Utc::now().timestamp(),
+            reason: None,
+        };
+        self.store(lobby_id, &jr).await
+    }
+
+    /// Record a join request accepted because the user's trust rating met
+    /// the lobby's `threshold`.
+    pub async fn create_auto_approved(
+        &self,
+        lobby_id: Uuid,
+        user: &User,
+        threshold: f64,
+    ) -> redis::RedisResult<()> {
+        let jr = JoinRequest {
+            user_id: user.id,
+            state: JoinRequestState::Accepted,
+            wallet_address: user.wallet_address.to_string(),
+            username: user.username.clone(),
+            display_name: user.display_name.clone(),
+            trust_rating: user.trust_rating,
+            is_creator: false,
+            created_at: Utc::now().timestamp(),
+            reason: Some(JoinRequestReason::TrustThreshold {
+                trust_rating: user.trust_rating,
+                threshold,
+            }),
+        };
+        self.store(lobby_id, &jr).await
+    }
+
+    async fn store(&self, lobby_id: Uuid, jr: &JoinRequest) -> redis::RedisResult<()> {
         if let Ok(mut conn) = self.redis.get().await {
             let key = RedisKey::lobby_join_requests(lobby_id);
-            let jr = JoinRequest {
-                user_id,
-                state: JoinRequestState::Pending,
-                wallet_address,
-                username,
-                display_name,
-                trust_rating,
-                is_creator: false,
-                created_at: Utc::now().timestamp(),
-            };
             let _: redis::RedisResult<i32> = conn
                 .hset(
                     &key,
-                    user_id.to_string(),
-                    serde_json::to_string(&jr).unwrap(),
+                    jr.user_id.to_string(),
+                    serde_json::to_string(jr).unwrap(),
                 )
                 .await;
-            let _: redis::RedisResult<bool> = conn.expire(&key, ttl_seconds as i64).await;
+            let _ = apply_expiry(&mut conn, &key).await;
+            Self::publish_change(&mut conn, lobby_id, jr.user_id, Some(&jr.state)).await;
         }
         Ok(())
     }
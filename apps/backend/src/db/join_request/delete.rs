@@ -9,6 +9,7 @@ impl JoinRequestRepository {
         if let Ok(mut conn) = self.redis.get().await {
             let key = RedisKey::lobby_join_requests(lobby_id);
             let _: redis::RedisResult<i32> = conn.hdel(&key, user_id.to_string()).await;
+            Self::publish_change(&mut conn, lobby_id, user_id, None).await;
         }
         Ok(())
     }
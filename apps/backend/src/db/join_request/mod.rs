@@ -1,15 +1,22 @@
 // JoinRequestRepository: runtime Redis helpers for join request lifecycle
+//
+// Every change to a request is also published on its channel (see
+// `RedisKey::join_request_channel`) for clients waiting on it over HTTP. They
+// all hear it through one shared subscription, `JoinRequestChanges`.
 
 mod create;
 mod delete;
 mod read;
 mod update;
+mod watch;
+
+pub use watch::{JoinRequestChanges, MAX_JOIN_REQUEST_WAITERS};
 
 use crate::state::RedisClient;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub enum JoinRequestState {
     Pending,
@@ -17,6 +24,15 @@ pub enum JoinRequestState {
     Rejected,
 }
 
+/// Why a join request was decided without the creator.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum JoinRequestReason {
+    /// The user's trust rating met the lobby's auto-approve threshold
+    #[serde(rename_all = "camelCase")]
+    TrustThreshold { trust_rating: f64, threshold: f64 },
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct JoinRequest {
@@ -28,6 +44,9 @@ pub struct JoinRequest {
     pub trust_rating: f64,
     pub is_creator: bool,
     pub created_at: i64,
+    /// Set when the request was decided automatically
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<JoinRequestReason>,
 }
 
 /// JoinRequestRepository (wraps the Redis client).
@@ -24,6 +24,7 @@ impl JoinRequestRepository {
                             serde_json::to_string(&jr).unwrap(),
                         )
                         .await;
+                    Self::publish_change(&mut conn, lobby_id, user_id, Some(&jr.state)).await;
                 }
             }
         }
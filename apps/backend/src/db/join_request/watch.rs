@@ -0,0 +1,197 @@
+use std::{sync::Arc, time::Duration};
+
+use crate::db::join_request::{JoinRequest, JoinRequestRepository, JoinRequestState};
+use crate::errors::AppError;
+use crate::models::keys::RedisKey;
+use crate::redis_client::RedisConnection;
+use futures::StreamExt;
+use redis::AsyncCommands;
+use tokio::sync::{
+    OnceCell, OwnedSemaphorePermit, Semaphore,
+    broadcast::{self, error::RecvError},
+};
+use uuid::Uuid;
+
+/// Most join requests waited on at once, across all lobbies
+pub const MAX_JOIN_REQUEST_WAITERS: usize = 256;
+
+/// A request that changed, as `(lobby_id, user_id)`, or `None` when any of
+/// them may have
+type Change = Option<(Uuid, Uuid)>;
+
+/// Changes to join requests, heard on one shared subscription.
+///
+/// The first wait opens a single pub/sub connection subscribed to every
+/// lobby's join request channels, and its messages fan out to the waiters.
+/// If that connection drops it is reopened, and every waiter re-reads its
+/// request since changes in between went unheard.
+#[derive(Clone)]
+pub struct JoinRequestChanges {
+    changes: broadcast::Sender<Change>,
+    subscriber: Arc<OnceCell<()>>,
+    waiters: Arc<Semaphore>,
+}
+
+impl Default for JoinRequestChanges {
+    fn default() -> Self {
+        Self {
+            changes: broadcast::channel(1024).0,
+            subscriber: Arc::new(OnceCell::new()),
+            waiters: Arc::new(Semaphore::new(MAX_JOIN_REQUEST_WAITERS)),
+        }
+    }
+}
+
+impl JoinRequestChanges {
+    /// A place among the waiters, `None` while all of them are taken
+    pub fn try_wait(&self) -> Option<OwnedSemaphorePermit> {
+        self.waiters.clone().try_acquire_owned().ok()
+    }
+
+    /// Changes from now on, subscribing on first use
+    pub async fn subscribe(
+        &self,
+        redis_url: &str,
+    ) -> Result<broadcast::Receiver<Change>, AppError> {
+        self.subscriber
+            .get_or_try_init(|| self.start(redis_url))
+            .await?;
+        Ok(self.changes.subscribe())
+    }
+
+    async fn start(&self, redis_url: &str) -> Result<(), AppError> {
+        let client = redis::Client::open(redis_url)
+            .map_err(|e| AppError::RedisError(format!("Invalid Redis URL: {}", e)))?;
+        let mut messages = connect(&client).await?.into_on_message();
+        let changes = self.changes.clone();
+
+        tokio::spawn(async move {
+            loop {
+                while let Some(msg) = messages.next().await {
+                    let _ = changes.send(parse_channel(msg.get_channel_name()));
+                }
+                tracing::warn!("Join request subscription dropped, reconnecting");
+                messages = loop {
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    match connect(&client).await {
+                        Ok(pubsub) => break pubsub.into_on_message(),
+                        Err(e) => tracing::warn!("{}", e),
+                    }
+                };
+                let _ = changes.send(None);
+            }
+        });
+        Ok(())
+    }
+}
+
+async fn connect(client: &redis::Client) -> Result<redis::aio::PubSub, AppError> {
+    let pattern = RedisKey::join_request_channel("*", "*");
+    let mut pubsub = client
+        .get_async_pubsub()
+        .await
+        .map_err(|e| AppError::RedisError(format!("Failed to open pub/sub connection: {}", e)))?;
+    pubsub
+        .psubscribe(&pattern)
+        .await
+        .map_err(|e| AppError::RedisError(format!("Failed to subscribe to {}: {}", pattern, e)))?;
+    Ok(pubsub)
+}
+
+/// The lobby and user a join request channel is for
+fn parse_channel(channel: &str) -> Change {
+    let mut parts = channel.split(':');
+    let lobby_id = parts.nth(1)?.parse().ok()?;
+    let user_id = parts.nth(1)?.parse().ok()?;
+    Some((lobby_id, user_id))
+}
+
+/// Wait for the next change that may concern the request; false once no
+/// more changes will come.
+async fn next_change(changes: &mut broadcast::Receiver<Change>, request: (Uuid, Uuid)) -> bool {
+    loop {
+        match changes.recv().await {
+            Ok(Some(changed)) if changed != request => continue,
+            Ok(_) | Err(RecvError::Lagged(_)) => return true,
+            Err(RecvError::Closed) => return false,
+        }
+    }
+}
+
+impl JoinRequestRepository {
+    /// Announce a request's new state (`None` once it's removed) to anyone
+    /// waiting on it. Best effort: waiters also give up at their timeout.
+    pub(super) async fn publish_change(
+        conn: &mut RedisConnection<'_>,
+        lobby_id: Uuid,
+        user_id: Uuid,
+        state: Option<&JoinRequestState>,
+    ) {
+        let channel = RedisKey::join_request_channel(lobby_id, user_id);
+        let payload = serde_json::to_string(&state).unwrap_or_default();
+        if let Err(e) = conn.publish::<_, _, i64>(&channel, payload).await {
+            tracing::warn!(
+                "Failed to publish join request change on {}: {}",
+                channel,
+                e
+            );
+        }
+    }
+
+    /// Wait up to `timeout` for a user's join request to leave state `from`,
+    /// then return it as it is (`None` if it was removed).
+    ///
+    /// `changes` must be subscribed before the wait starts: the request is
+    /// read after that, so a change made in between isn't missed.
+    pub async fn wait_for_change(
+        &self,
+        mut changes: broadcast::Receiver<Change>,
+        lobby_id: Uuid,
+        user_id: Uuid,
+        from: &JoinRequestState,
+        timeout: Duration,
+    ) -> Result<Option<JoinRequest>, AppError> {
+        let deadline = tokio::time::Instant::now() + timeout;
+
+        loop {
+            let current = self.get(lobby_id, user_id).await;
+            if current.as_ref().map(|jr| &jr.state) != Some(from) {
+                return Ok(current);
+            }
+            match tokio::time::timeout_at(deadline, next_change(&mut changes, (lobby_id, user_id)))
+                .await
+            {
+                Ok(true) => continue,
+                // Timed out, or the subscription is gone
+                Ok(false) | Err(_) => return Ok(current),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_channel_reads_lobby_and_user() {
+        let (lobby_id, user_id) = (Uuid::new_v4(), Uuid::new_v4());
+        assert_eq!(
+            parse_channel(&RedisKey::join_request_channel(lobby_id, user_id)),
+            Some((lobby_id, user_id))
+        );
+        assert_eq!(parse_channel("lobbies:nope:join_requests:nope"), None);
+    }
+
+    #[test]
+    fn test_waiters_are_capped() {
+        let changes = JoinRequestChanges::default();
+        let waiting: Vec<_> = (0..MAX_JOIN_REQUEST_WAITERS)
+            .map(|_| changes.try_wait().expect("room for a waiter"))
+            .collect();
+        assert!(changes.try_wait().is_none());
+
+        drop(waiting);
+        assert!(changes.try_wait().is_some());
+    }
+}
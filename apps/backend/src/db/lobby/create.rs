@@ -1,19 +1,35 @@
+use rust_decimal::Decimal;
 use sqlx::query_as;
 use uuid::Uuid;
 
 use crate::{
     errors::AppError,
-    models::{Lobby, LobbyState, LobbyStatus, PlayerState, WalletAddress},
+    maintenance::MaintenanceMode,
+    models::{
+        BotDifficulty, Lobby, LobbyState, LobbyStatus, PayoutTable, PlayerState, WalletAddress,
+    },
     state::{AppState, RedisClient},
 };
 
 use super::LobbyRepository;
 use crate::db::{
-    lobby_state::LobbyStateRepository, player_state::PlayerStateRepository, user::UserRepository,
+    game::GameRepository, lobby_state::LobbyStateRepository, player_state::PlayerStateRepository,
+    timing::TimedQuery, user::UserRepository,
 };
 
 impl LobbyRepository {
     /// Create a new lobby and return the created `Lobby`.
+    ///
+    /// Fails with `AppError::BadRequest` unless `game_id` names an active game
+    /// whose path is `game_path`, and with `AppError::ServiceUnavailable` while
+    /// maintenance mode is on. The name is cleaned up and checked by the
+    /// configured `LobbyNamePolicy`; with `unique_per_creator` on, a name the
+    /// creator already uses for an unfinished lobby is an `AppError::Conflict`.
+    /// The token and entry amount must pass the
+    /// configured `TokenPolicy`. Practice lobbies (`practice_bot`) can't be
+    /// staked, sponsored or backed by a vault contract. An `auto_approve_trust_threshold` must be a
+    /// non-negative trust rating, on a private lobby. Without `payouts` the pot is split by the
+    /// default table for the player count.
     pub async fn create_lobby(
         &self,
         name: &str,
@@ -21,20 +37,57 @@ impl LobbyRepository {
         creator_id: Uuid,
         game_id: Uuid,
         game_path: &str,
-        entry_amount: Option<f64>,
-        current_amount: Option<f64>,
+        entry_amount: Option<Decimal>,
+        current_amount: Option<Decimal>,
         token_symbol: Option<&str>,
         token_contract_id: Option<&str>,
         contract_address: Option<&str>,
         is_private: bool,
         is_sponsored: bool,
+        spectators_allowed: bool,
+        anonymize_players: bool,
+        practice_bot: Option<BotDifficulty>,
+        auto_approve_trust_threshold: Option<f64>,
+        payouts: Option<PayoutTable>,
         redis: RedisClient,
         state: AppState,
     ) -> Result<Lobby, AppError> {
+        MaintenanceMode::new(redis.clone())
+            .ensure_accepting_games()
+            .await?;
+
+        let name = state
+            .config
+            .lobby_names
+            .sanitize(name, &state.config.chat_moderation)?;
+        let name = name.as_str();
+
         // Validate amounts based on sponsor status
         let (entry_amount, current_amount) =
             Lobby::validate_creation_amounts(entry_amount, current_amount, is_sponsored)?;
 
+        // Practice games pay nothing out, so there's nothing to escrow
+        if practice_bot.is_some()
+            && (is_sponsored
+                || contract_address.is_some()
+                || entry_amount.is_some_and(|amount| amount > Decimal::ZERO))
+        {
+            return Err(AppError::BadRequest(
+                "Practice lobbies can't have a stake".into(),
+            ));
+        }
+
+        if auto_approve_trust_threshold.is_some_and(|t| !t.is_finite() || t < 0.0) {
+            return Err(AppError::BadRequest(
+                "Auto-approve trust threshold must be a non-negative number".into(),
+            ));
+        }
+        if auto_approve_trust_threshold.is_some() && !is_private {
+            return Err(AppError::BadRequest(
+                "Auto-approve trust threshold only applies to private lobbies".into(),
+            ));
+        }
+
         // Validate and parse contract addresses
         let token_contract_id = if let Some(addr) = token_contract_id {
             Some(WalletAddress::new(addr)?)
@@ -42,24 +95,83 @@ impl LobbyRepository {
             None
         };
 
+        state.config.lobby_tokens.check(
+            token_symbol,
+            token_contract_id.as_ref(),
+            entry_amount,
+            is_sponsored,
+        )?;
+
         let contract_address = if let Some(addr) = contract_address {
             Some(WalletAddress::new(addr)?)
         } else {
             None
         };
-        let lobby_future = query_as::<_, Lobby>(
+
+        let creator = UserRepository::new(self.pool.clone())
+            .find_by_id(creator_id)
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to fetch creator user: {}", e)))?;
+
+        let mut transaction =
+            self.pool.begin().await.map_err(|e| {
+                AppError::DatabaseError(format!("Failed to begin transaction: {}", e))
+            })?;
+
+        // The game must exist and stay active until the lobby is committed
+        let game = GameRepository::find_active_for_lobby(&mut transaction, game_id).await?;
+        if game.path != game_path {
+            return Err(AppError::BadRequest(format!(
+                "Game path '{}' does not match game '{}'",
+                game_path, game.name
+            )));
+        }
+
+        if state.config.lobby_names.unique_per_creator {
+            // Serializes the creator's lobby creation so two can't both pass the check
+            sqlx::query("SELECT 1 FROM users WHERE id = $1 FOR UPDATE")
+                .bind(creator_id)
+                .execute(&mut *transaction)
+                .timed("LobbyRepository::create_lobby")
+                .await
+                .map_err(|e| AppError::DatabaseError(format!("Failed to lock creator: {}", e)))?;
+            let taken: bool = sqlx::query_scalar(
+                "SELECT EXISTS (
+                    SELECT 1 FROM lobbies
+                    WHERE creator_id = $1 AND lower(name) = lower($2)
+                      AND status NOT IN ('finished', 'cancelled')
+                )",
+            )
+            .bind(creator_id)
+            .bind(name)
+            .fetch_one(&mut *transaction)
+            .timed("LobbyRepository::create_lobby")
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to check lobby name: {}", e)))?;
+            if taken {
+                return Err(AppError::Conflict(format!(
+                    "You already have an open lobby named '{}'",
+                    name
+                )));
+            }
+        }
+
+        let lobby = query_as::<_, Lobby>(
             r#"
             INSERT INTO lobbies (
                 name, description, creator_id, game_id, game_path,
                 entry_amount, current_amount, token_symbol, token_contract_id,
-                contract_address, is_private, is_sponsored,
-                status
+                contract_address, is_private, is_sponsored, spectators_allowed,
+                anonymize_players, practice_bot, auto_approve_trust_threshold,
+                payout_percents, payout_underfill, status
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16,
+                    $17, $18, $19)
             RETURNING id, path, name, description, game_id, game_path, creator_id,
                       entry_amount, current_amount, token_symbol, token_contract_id,
-                      contract_address, is_private, is_sponsored, status,
-                      created_at, updated_at
+                      contract_address, is_private, is_sponsored, spectators_allowed,
+                      anonymize_players, practice_bot, auto_approve_trust_threshold,
+                      payout_percents, payout_underfill, status, created_at, updated_at
             "#,
         )
         .bind(name)
@@ -74,23 +186,35 @@ impl LobbyRepository {
         .bind(contract_address.as_ref())
         .bind(is_private)
         .bind(is_sponsored)
+        .bind(spectators_allowed)
+        .bind(anonymize_players)
+        .bind(practice_bot)
+        .bind(auto_approve_trust_threshold)
+        .bind(payouts.as_ref().map(|table| {
+            table
+                .percents()
+                .iter()
+                .map(|p| *p as i32)
+                .collect::<Vec<i32>>()
+        }))
+        .bind(
+            payouts
+                .as_ref()
+                .map(PayoutTable::underfill)
+                .unwrap_or_default(),
+        )
         .bind(LobbyStatus::Waiting)
-        .fetch_one(&self.pool);
-
-        let user_repo = UserRepository::new(self.pool.clone());
-        let user_future = user_repo.find_by_id(creator_id);
-
-        // Run both queries in parallel
-        let (lobby_result, creator_result) = tokio::join!(lobby_future, user_future);
-
-        let lobby = lobby_result.map_err(|e| {
+        .fetch_one(&mut *transaction)
+        .timed("LobbyRepository::create_lobby")
+        .await
+        .map_err(|e| {
             AppError::DatabaseError(format!("Failed to create lobby '{}': {}", name, e))
         })?;
 
-        let creator = creator_result.map_err(|e| {
-            let _ = self.delete_lobby(lobby.id(), None);
-            AppError::DatabaseError(format!("Failed to fetch creator user: {}", e))
-        })?;
+        transaction
+            .commit()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to commit lobby: {}", e)))?;
 
         let lobby_state_repo = LobbyStateRepository::new(redis.clone());
         let player_repo = PlayerStateRepository::new(redis.clone());
@@ -105,6 +229,11 @@ impl LobbyRepository {
             )));
         }
 
+        // Config is mirrored into the runtime hash from the start
+        if let Err(e) = lobby_state_repo.sync_config(&lobby).await {
+            tracing::warn!("Failed to sync lobby {} config to Redis: {}", lobby.id(), e);
+        }
+
         let creator_pstate = PlayerState::new(
             creator_id,
             lobby.id(),
@@ -127,8 +256,7 @@ impl LobbyRepository {
         tracing::info!("Created lobby: {} (path: {})", lobby.name, lobby.path);
 
         // Broadcast lobby creation to lobby list subscribers
-        crate::ws::broadcast::broadcast_lobby_creation(state, lobby.id(), game_id, creator_id)
-            .await;
+        crate::ws::broadcast::broadcast_lobby_creation(state, lobby.id()).await;
 
         Ok(lobby)
     }
@@ -4,6 +4,7 @@ use uuid::Uuid;
 use crate::{errors::AppError, models::LobbyStatus, state::AppState};
 
 use super::LobbyRepository;
+use crate::db::timing::TimedQuery;
 
 impl LobbyRepository {
     /// Delete a lobby by ID (returns number of rows deleted).
@@ -15,6 +16,7 @@ impl LobbyRepository {
         let result = query("DELETE FROM lobbies WHERE id = $1")
             .bind(lobby_id)
             .execute(&self.pool)
+            .timed("LobbyRepository::delete_lobby")
             .await
             .map_err(|e| AppError::DatabaseError(format!("Failed to delete lobby: {}", e)))?;
 
@@ -24,12 +26,7 @@ impl LobbyRepository {
             // Broadcast lobby removal to lobby list subscribers
             if let Some(state) = state {
                 tokio::spawn(async move {
-                    use crate::ws::{broadcast, lobby::LobbyServerMessage};
-                    let _ = broadcast::broadcast_lobby_list(
-                        &state,
-                        &LobbyServerMessage::LobbyRemoved { lobby_id },
-                    )
-                    .await;
+                    crate::ws::broadcast::broadcast_lobby_removal(&state, lobby_id).await;
                 });
             }
         }
@@ -42,6 +39,7 @@ impl LobbyRepository {
         let result = query("DELETE FROM lobbies WHERE creator_id = $1")
             .bind(creator_id)
             .execute(&self.pool)
+            .timed("LobbyRepository::delete_by_creator")
             .await
             .map_err(|e| {
                 AppError::DatabaseError(format!("Failed to delete lobbies by creator: {}", e))
@@ -61,6 +59,7 @@ impl LobbyRepository {
         let result = query("DELETE FROM lobbies WHERE game_id = $1")
             .bind(game_id)
             .execute(&self.pool)
+            .timed("LobbyRepository::delete_by_game")
             .await
             .map_err(|e| {
                 AppError::DatabaseError(format!("Failed to delete lobbies by game: {}", e))
@@ -80,6 +79,7 @@ impl LobbyRepository {
         let result = query("DELETE FROM lobbies WHERE status = $1")
             .bind(LobbyStatus::Finished)
             .execute(&self.pool)
+            .timed("LobbyRepository::delete_finished_lobbies")
             .await
             .map_err(|e| {
                 AppError::DatabaseError(format!("Failed to delete finished lobbies: {}", e))
@@ -95,6 +95,7 @@ impl LobbyRepository {
         let result = query("DELETE FROM lobbies WHERE created_at < NOW() - INTERVAL '1 day' * $1")
             .bind(days)
             .execute(&self.pool)
+            .timed("LobbyRepository::delete_old_lobbies")
             .await
             .map_err(|e| AppError::DatabaseError(format!("Failed to delete old lobbies: {}", e)))?;
 
@@ -116,6 +117,7 @@ impl LobbyRepository {
         let result = query("DELETE FROM lobbies WHERE id = ANY($1)")
             .bind(lobby_ids)
             .execute(&self.pool)
+            .timed("LobbyRepository::delete_bulk")
             .await
             .map_err(|e| {
                 AppError::DatabaseError(format!("Failed to bulk delete lobbies: {}", e))
@@ -131,6 +133,7 @@ impl LobbyRepository {
     pub async fn delete_all(&self) -> Result<u64, AppError> {
         let result = query("DELETE FROM lobbies")
             .execute(&self.pool)
+            .timed("LobbyRepository::delete_all")
             .await
             .map_err(|e| AppError::DatabaseError(format!("Failed to delete all lobbies: {}", e)))?;
 
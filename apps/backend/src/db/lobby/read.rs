@@ -1,12 +1,15 @@
+use chrono::NaiveDateTime;
 use sqlx::{FromRow, Row, query, query_as};
+use std::collections::HashMap;
 use uuid::Uuid;
 
 use crate::{
     errors::AppError,
-    models::{Lobby, LobbyStatus},
+    models::{Lobby, LobbyFilter, LobbyStatus, LobbyStatusCount},
 };
 
 use super::LobbyRepository;
+use crate::db::timing::TimedQuery;
 
 impl LobbyRepository {
     /// Find a lobby by its ID.
@@ -14,6 +17,7 @@ impl LobbyRepository {
         let lobby = query_as::<_, Lobby>("SELECT * FROM lobbies WHERE id = $1")
             .bind(lobby_id)
             .fetch_optional(&self.pool)
+            .timed("LobbyRepository::find_by_id")
             .await
             .map_err(|e| AppError::DatabaseError(format!("Failed to fetch lobby: {}", e)))?
             .ok_or_else(|| AppError::NotFound(format!("Lobby {} not found", lobby_id)))?;
@@ -21,11 +25,59 @@ impl LobbyRepository {
         Ok(lobby)
     }
 
+    /// Status of each of `lobby_ids` that exists.
+    pub async fn find_statuses(
+        &self,
+        lobby_ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, LobbyStatus>, AppError> {
+        let rows =
+            query_as::<_, (Uuid, LobbyStatus)>("SELECT id, status FROM lobbies WHERE id = ANY($1)")
+                .bind(lobby_ids)
+                .fetch_all(&self.pool)
+                .timed("LobbyRepository::find_statuses")
+                .await
+                .map_err(|e| {
+                    AppError::DatabaseError(format!("Failed to fetch lobby statuses: {}", e))
+                })?;
+
+        Ok(rows.into_iter().collect())
+    }
+
+    /// Seed the lobby's game was played with, once it has started.
+    pub async fn find_game_seed(&self, lobby_id: Uuid) -> Result<Option<u64>, AppError> {
+        let seed = query_as::<_, (Option<i64>,)>("SELECT game_seed FROM lobbies WHERE id = $1")
+            .bind(lobby_id)
+            .fetch_optional(&self.pool)
+            .timed("LobbyRepository::find_game_seed")
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to fetch game seed: {}", e)))?
+            .ok_or_else(|| AppError::NotFound(format!("Lobby {} not found", lobby_id)))?
+            .0;
+
+        Ok(seed.map(|seed| seed as u64))
+    }
+
+    /// When the lobby's game went in progress, once it has.
+    pub async fn find_started_at(&self, lobby_id: Uuid) -> Result<Option<NaiveDateTime>, AppError> {
+        let started_at =
+            query_as::<_, (Option<NaiveDateTime>,)>("SELECT started_at FROM lobbies WHERE id = $1")
+                .bind(lobby_id)
+                .fetch_optional(&self.pool)
+                .timed("LobbyRepository::find_started_at")
+                .await
+                .map_err(|e| AppError::DatabaseError(format!("Failed to fetch start time: {}", e)))?
+                .ok_or_else(|| AppError::NotFound(format!("Lobby {} not found", lobby_id)))?
+                .0;
+
+        Ok(started_at)
+    }
+
     /// Find a lobby by its path.
     pub async fn find_by_path(&self, path: &str) -> Result<Lobby, AppError> {
         let lobby = query_as::<_, Lobby>("SELECT * FROM lobbies WHERE path = $1")
             .bind(path)
             .fetch_optional(&self.pool)
+            .timed("LobbyRepository::find_by_path")
             .await
             .map_err(|e| AppError::DatabaseError(format!("Failed to fetch lobby by path: {}", e)))?
             .ok_or_else(|| AppError::NotFound(format!("Lobby with path '{}' not found", path)))?;
@@ -47,6 +99,7 @@ impl LobbyRepository {
         .bind(limit as i64)
         .bind(offset as i64)
         .fetch_all(&self.pool)
+        .timed("LobbyRepository::find_by_creator")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to fetch creator lobbies: {}", e)))?;
 
@@ -77,6 +130,7 @@ impl LobbyRepository {
         .bind(limit as i64)
         .bind(offset as i64)
         .fetch_all(&self.pool)
+        .timed("LobbyRepository::find_by_game_id")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to fetch game lobbies: {}", e)))?;
 
@@ -107,6 +161,7 @@ impl LobbyRepository {
         .bind(limit as i64)
         .bind(offset as i64)
         .fetch_all(&self.pool)
+        .timed("LobbyRepository::find_by_status")
         .await
         .map_err(|e| {
             AppError::DatabaseError(format!("Failed to fetch lobbies by status: {}", e))
@@ -137,6 +192,7 @@ impl LobbyRepository {
         .bind(limit)
         .bind(offset)
         .fetch_all(&self.pool)
+        .timed("LobbyRepository::get_all_lobbies")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to fetch all lobbies: {}", e)))?;
 
@@ -163,12 +219,37 @@ impl LobbyRepository {
             "#,
         )
         .fetch_all(&self.pool)
+        .timed("LobbyRepository::get_active_lobbies")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to fetch active lobbies: {}", e)))?;
 
         Ok(lobbies)
     }
 
+    /// Lobbies with a vault contract that may still see deposits or claims:
+    /// those not yet finished, and those finished since `finished_since`.
+    pub async fn find_with_open_contracts(
+        &self,
+        finished_since: NaiveDateTime,
+    ) -> Result<Vec<Lobby>, AppError> {
+        query_as::<_, Lobby>(
+            r#"
+            SELECT * FROM lobbies
+            WHERE contract_address IS NOT NULL
+              AND (status IN ('waiting', 'starting', 'in_progress')
+                OR (status = 'finished' AND updated_at >= $1))
+            ORDER BY created_at
+            "#,
+        )
+        .bind(finished_since)
+        .fetch_all(&self.pool)
+        .timed("LobbyRepository::find_with_open_contracts")
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to fetch lobbies with contracts: {}", e))
+        })
+    }
+
     /// Get public (non-private) lobbies.
     pub async fn get_public_lobbies(
         &self,
@@ -181,6 +262,7 @@ impl LobbyRepository {
         .bind(limit as i64)
         .bind(offset as i64)
         .fetch_all(&self.pool)
+        .timed("LobbyRepository::get_public_lobbies")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to fetch public lobbies: {}", e)))?;
 
@@ -209,6 +291,7 @@ impl LobbyRepository {
         .bind(limit as i64)
         .bind(offset as i64)
         .fetch_all(&self.pool)
+        .timed("LobbyRepository::get_sponsored_lobbies")
         .await
         .map_err(|e| {
             AppError::DatabaseError(format!("Failed to fetch sponsored lobbies: {}", e))
@@ -239,6 +322,7 @@ impl LobbyRepository {
         .bind(game_id)
         .bind(status)
         .fetch_all(&self.pool)
+        .timed("LobbyRepository::find_by_game_and_status")
         .await
         .map_err(|e| {
             AppError::DatabaseError(format!("Failed to fetch lobbies by game and status: {}", e))
@@ -251,6 +335,7 @@ impl LobbyRepository {
     pub async fn count_lobbies(&self) -> Result<i64, AppError> {
         let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM lobbies")
             .fetch_one(&self.pool)
+            .timed("LobbyRepository::count_lobbies")
             .await
             .map_err(|e| AppError::DatabaseError(format!("Failed to count lobbies: {}", e)))?;
 
@@ -262,6 +347,7 @@ impl LobbyRepository {
         let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM lobbies WHERE status = $1")
             .bind(status)
             .fetch_one(&self.pool)
+            .timed("LobbyRepository::count_by_status")
             .await
             .map_err(|e| {
                 AppError::DatabaseError(format!("Failed to count lobbies by status: {}", e))
@@ -275,6 +361,7 @@ impl LobbyRepository {
         let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM lobbies WHERE game_id = $1")
             .bind(game_id)
             .fetch_one(&self.pool)
+            .timed("LobbyRepository::count_by_game")
             .await
             .map_err(|e| {
                 AppError::DatabaseError(format!("Failed to count lobbies by game: {}", e))
@@ -283,11 +370,45 @@ impl LobbyRepository {
         Ok(count.0)
     }
 
+    /// Lobby counts per status and game, as Postgres has them.
+    pub async fn status_summary(&self) -> Result<Vec<LobbyStatusCount>, AppError> {
+        query_as::<_, LobbyStatusCount>(
+            r#"
+            SELECT status, game_id, COUNT(*) AS count
+            FROM lobbies
+            GROUP BY status, game_id
+            ORDER BY game_id, status
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .timed("LobbyRepository::status_summary")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to summarize lobbies: {}", e)))
+    }
+
+    /// ID, game and status of every lobby that hasn't finished or been cancelled.
+    pub async fn find_in_flight_statuses(
+        &self,
+    ) -> Result<Vec<(Uuid, Uuid, LobbyStatus)>, AppError> {
+        query_as::<_, (Uuid, Uuid, LobbyStatus)>(
+            r#"
+            SELECT id, game_id, status
+            FROM lobbies
+            WHERE status IN ('waiting', 'starting', 'in_progress')
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .timed("LobbyRepository::find_in_flight_statuses")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch in-flight lobbies: {}", e)))
+    }
+
     /// Check if a lobby exists by ID.
     pub async fn exists(&self, lobby_id: Uuid) -> Result<bool, AppError> {
-        let result = query("SELECT EXISTS(SELECT 1 FROM lobbies WHERE lobby_id = $1)")
+        let result = query("SELECT EXISTS(SELECT 1 FROM lobbies WHERE id = $1)")
             .bind(lobby_id)
             .fetch_one(&self.pool)
+            .timed("LobbyRepository::exists")
             .await
             .map_err(|e| {
                 AppError::DatabaseError(format!("Failed to check lobby existence: {}", e))
@@ -318,6 +439,7 @@ impl LobbyRepository {
         .bind(limit as i64)
         .bind(offset as i64)
         .fetch_all(&self.pool)
+        .timed("LobbyRepository::find_by_statuses")
         .await
         .map_err(|e| {
             AppError::DatabaseError(format!("Failed to fetch lobbies by statuses: {}", e))
@@ -336,6 +458,48 @@ impl LobbyRepository {
         Ok((lobbies, total))
     }
 
+    /// Get lobbies matching a lobby-list filter with pagination
+    pub async fn find_by_filter(
+        &self,
+        filter: &LobbyFilter,
+        offset: usize,
+        limit: usize,
+    ) -> Result<(Vec<Lobby>, i64), AppError> {
+        let rows = query(
+            "SELECT *, COUNT(*) OVER() as total FROM lobbies
+             WHERE (cardinality($1::lobby_status[]) = 0 OR status = ANY($1))
+               AND ($2::uuid IS NULL OR game_id = $2)
+               AND ($3::numeric IS NULL OR COALESCE(entry_amount, 0) >= $3)
+               AND ($4::numeric IS NULL OR COALESCE(entry_amount, 0) <= $4)
+             ORDER BY created_at DESC
+             LIMIT $5 OFFSET $6",
+        )
+        .bind(&filter.statuses)
+        .bind(filter.game_id)
+        .bind(filter.min_stake)
+        .bind(filter.max_stake)
+        .bind(limit as i64)
+        .bind(offset as i64)
+        .fetch_all(&self.pool)
+        .timed("LobbyRepository::find_by_filter")
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to fetch lobbies by filter: {}", e))
+        })?;
+
+        let total = rows
+            .first()
+            .map(|row| row.get::<i64, _>("total"))
+            .unwrap_or(0);
+        let lobbies = rows
+            .into_iter()
+            .map(|row| Lobby::from_row(&row))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::DatabaseError(format!("Failed to parse lobby: {}", e)))?;
+
+        Ok((lobbies, total))
+    }
+
     /// Get all lobbies with pagination (no status filter)
     pub async fn find_all(
         &self,
@@ -348,6 +512,7 @@ impl LobbyRepository {
         .bind(limit as i64)
         .bind(offset as i64)
         .fetch_all(&self.pool)
+        .timed("LobbyRepository::find_all")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to fetch all lobbies: {}", e)))?;
 
@@ -1,18 +1,49 @@
-use chrono::Utc;
+use rust_decimal::Decimal;
 use sqlx::query;
 use uuid::Uuid;
 
 use crate::{
+    db::{lobby_state::LobbyStateRepository, timing::TimedQuery},
     errors::AppError,
     models::{Lobby, LobbyStatus, WalletAddress},
-    state::AppState,
+    state::{AppState, RedisClient},
     ws::broadcast_lobby_update,
 };
 
 use super::LobbyRepository;
 
+/// Push config a write just changed to the lobby's runtime hash.
+///
+/// Postgres already holds the change, so a Redis failure is logged rather than
+/// failing the update; the next sync repairs it.
+async fn write_through(redis: &RedisClient, lobby: &Lobby) {
+    if let Err(e) = LobbyStateRepository::new(redis.clone())
+        .sync_config(lobby)
+        .await
+    {
+        tracing::warn!("Failed to sync lobby {} config to Redis: {}", lobby.id, e);
+    }
+}
+
 impl LobbyRepository {
+    /// Copy a lobby's config from Postgres into its Redis runtime hash.
+    ///
+    /// Returns false when the lobby isn't live in Redis.
+    pub async fn sync_lobby_to_redis(
+        &self,
+        lobby_id: Uuid,
+        redis: &RedisClient,
+    ) -> Result<bool, AppError> {
+        let lobby = self.find_by_id(lobby_id).await?;
+        LobbyStateRepository::new(redis.clone())
+            .sync_config(&lobby)
+            .await
+    }
+
     /// Update lobby status.
+    ///
+    /// The first move to `InProgress` stamps `started_at`, and the first to
+    /// `Finished` stamps `finished_at`.
     pub async fn update_status(
         &self,
         lobby_id: Uuid,
@@ -22,15 +53,20 @@ impl LobbyRepository {
         let lobby = sqlx::query_as::<_, Lobby>(
             r#"
             UPDATE lobbies
-            SET status = $1, updated_at = $2
-            WHERE id = $3
+            SET status = $1,
+                updated_at = NOW(),
+                started_at = CASE WHEN $1 = 'in_progress'::lobby_status
+                                  THEN COALESCE(started_at, NOW()) ELSE started_at END,
+                finished_at = CASE WHEN $1 = 'finished'::lobby_status
+                                   THEN COALESCE(finished_at, NOW()) ELSE finished_at END
+            WHERE id = $2
             RETURNING *
             "#,
         )
         .bind(status)
-        .bind(Utc::now().naive_utc())
         .bind(lobby_id)
         .fetch_one(&self.pool)
+        .timed("LobbyRepository::update_status")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to update lobby status: {}", e)))?;
 
@@ -41,28 +77,35 @@ impl LobbyRepository {
         Ok(lobby)
     }
 
-    /// Update lobby name.
+    /// Update lobby name. The name is cleaned up and checked by the configured
+    /// `LobbyNamePolicy`, as at creation.
     pub async fn update_name(
         &self,
         lobby_id: Uuid,
         name: &str,
         state: AppState,
     ) -> Result<Lobby, AppError> {
+        let name = state
+            .config
+            .lobby_names
+            .sanitize(name, &state.config.chat_moderation)?;
+
         let lobby = sqlx::query_as::<_, Lobby>(
             r#"
             UPDATE lobbies
-            SET name = $1, updated_at = $2
-            WHERE id = $3
+            SET name = $1, updated_at = NOW()
+            WHERE id = $2
             RETURNING *
             "#,
         )
         .bind(name)
-        .bind(Utc::now().naive_utc())
         .bind(lobby_id)
         .fetch_one(&self.pool)
+        .timed("LobbyRepository::update_name")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to update lobby name: {}", e)))?;
 
+        write_through(&state.redis, &lobby).await;
         broadcast_lobby_update(state, lobby_id).await;
 
         Ok(lobby)
@@ -78,15 +121,15 @@ impl LobbyRepository {
         let lobby = sqlx::query_as::<_, Lobby>(
             r#"
             UPDATE lobbies
-            SET description = $1, updated_at = $2
-            WHERE id = $3
+            SET description = $1, updated_at = NOW()
+            WHERE id = $2
             RETURNING *
             "#,
         )
         .bind(description)
-        .bind(Utc::now().naive_utc())
         .bind(lobby_id)
         .fetch_one(&self.pool)
+        .timed("LobbyRepository::update_description")
         .await
         .map_err(|e| {
             AppError::DatabaseError(format!("Failed to update lobby description: {}", e))
@@ -101,7 +144,7 @@ impl LobbyRepository {
     pub async fn update_entry_amount(
         &self,
         lobby_id: Uuid,
-        entry_amount: f64,
+        entry_amount: Decimal,
         state: AppState,
     ) -> Result<Lobby, AppError> {
         let entry_amount = Lobby::validate_amount(Some(entry_amount))?;
@@ -109,20 +152,21 @@ impl LobbyRepository {
         let lobby = sqlx::query_as::<_, Lobby>(
             r#"
             UPDATE lobbies
-            SET entry_amount = $1, updated_at = $2
-            WHERE id = $3
+            SET entry_amount = $1, updated_at = NOW()
+            WHERE id = $2
             RETURNING *
             "#,
         )
         .bind(entry_amount)
-        .bind(Utc::now().naive_utc())
         .bind(lobby_id)
         .fetch_one(&self.pool)
+        .timed("LobbyRepository::update_entry_amount")
         .await
         .map_err(|e| {
             AppError::DatabaseError(format!("Failed to update lobby entry amount: {}", e))
         })?;
 
+        write_through(&state.redis, &lobby).await;
         broadcast_lobby_update(state, lobby_id).await;
 
         Ok(lobby)
@@ -132,7 +176,7 @@ impl LobbyRepository {
     pub async fn update_current_amount(
         &self,
         lobby_id: Uuid,
-        current_amount: f64,
+        current_amount: Decimal,
         state: AppState,
     ) -> Result<Lobby, AppError> {
         let current_amount = Lobby::validate_amount(Some(current_amount))?;
@@ -140,20 +184,21 @@ impl LobbyRepository {
         let lobby = sqlx::query_as::<_, Lobby>(
             r#"
             UPDATE lobbies
-            SET current_amount = $1, updated_at = $2
-            WHERE id = $3
+            SET current_amount = $1, updated_at = NOW()
+            WHERE id = $2
             RETURNING *
             "#,
         )
         .bind(current_amount)
-        .bind(Utc::now().naive_utc())
         .bind(lobby_id)
         .fetch_one(&self.pool)
+        .timed("LobbyRepository::update_current_amount")
         .await
         .map_err(|e| {
             AppError::DatabaseError(format!("Failed to update lobby current amount: {}", e))
         })?;
 
+        write_through(&state.redis, &lobby).await;
         broadcast_lobby_update(state, lobby_id).await;
 
         Ok(lobby)
@@ -163,7 +208,7 @@ impl LobbyRepository {
     pub async fn increment_current_amount(
         &self,
         lobby_id: Uuid,
-        amount: f64,
+        amount: Decimal,
         state: AppState,
     ) -> Result<Lobby, AppError> {
         Lobby::validate_amount(Some(amount))?;
@@ -171,18 +216,19 @@ impl LobbyRepository {
         let lobby = sqlx::query_as::<_, Lobby>(
             r#"
             UPDATE lobbies
-            SET current_amount = COALESCE(current_amount, 0) + $1, updated_at = $2
-            WHERE id = $3
+            SET current_amount = COALESCE(current_amount, 0) + $1, updated_at = NOW()
+            WHERE id = $2
             RETURNING *
             "#,
         )
         .bind(amount)
-        .bind(Utc::now().naive_utc())
         .bind(lobby_id)
         .fetch_one(&self.pool)
+        .timed("LobbyRepository::increment_current_amount")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to increment lobby amount: {}", e)))?;
 
+        write_through(&state.redis, &lobby).await;
         broadcast_lobby_update(state, lobby_id).await;
 
         Ok(lobby)
@@ -208,16 +254,16 @@ impl LobbyRepository {
         let lobby = sqlx::query_as::<_, Lobby>(
             r#"
             UPDATE lobbies
-            SET token_symbol = $1, token_contract_id = $2, updated_at = $3
-            WHERE id = $4
+            SET token_symbol = $1, token_contract_id = $2, updated_at = NOW()
+            WHERE id = $3
             RETURNING *
             "#,
         )
         .bind(token_symbol)
         .bind(validated_contract.as_ref())
-        .bind(Utc::now().naive_utc())
         .bind(lobby_id)
         .fetch_optional(&self.pool)
+        .timed("LobbyRepository::update_token_info")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to update lobby token info: {}", e)))?
         .ok_or_else(|| AppError::NotFound(format!("Lobby {} not found", lobby_id)))?;
@@ -240,15 +286,15 @@ impl LobbyRepository {
         let lobby = sqlx::query_as::<_, Lobby>(
             r#"
             UPDATE lobbies
-            SET contract_address = $1, updated_at = $2
-            WHERE id = $3
+            SET contract_address = $1, updated_at = NOW()
+            WHERE id = $2
             RETURNING *
             "#,
         )
         .bind(contract_address.as_ref())
-        .bind(Utc::now().naive_utc())
         .bind(lobby_id)
         .fetch_one(&self.pool)
+        .timed("LobbyRepository::update_contract_address")
         .await
         .map_err(|e| {
             AppError::DatabaseError(format!("Failed to update lobby contract address: {}", e))
@@ -269,15 +315,15 @@ impl LobbyRepository {
         let lobby = sqlx::query_as::<_, Lobby>(
             r#"
             UPDATE lobbies
-            SET is_private = $1, updated_at = $2
-            WHERE id = $3
+            SET is_private = $1, updated_at = NOW()
+            WHERE id = $2
             RETURNING *
             "#,
         )
         .bind(is_private)
-        .bind(Utc::now().naive_utc())
         .bind(lobby_id)
         .fetch_one(&self.pool)
+        .timed("LobbyRepository::set_private")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to update lobby privacy: {}", e)))?;
 
@@ -296,15 +342,15 @@ impl LobbyRepository {
         let lobby = sqlx::query_as::<_, Lobby>(
             r#"
             UPDATE lobbies
-            SET is_sponsored = $1, updated_at = $2
-            WHERE id = $3
+            SET is_sponsored = $1, updated_at = NOW()
+            WHERE id = $2
             RETURNING *
             "#,
         )
         .bind(is_sponsored)
-        .bind(Utc::now().naive_utc())
         .bind(lobby_id)
         .fetch_one(&self.pool)
+        .timed("LobbyRepository::set_sponsored")
         .await
         .map_err(|e| {
             AppError::DatabaseError(format!("Failed to update lobby sponsored status: {}", e))
@@ -315,6 +361,181 @@ impl LobbyRepository {
         Ok(lobby)
     }
 
+    /// Allow or refuse spectators. Connected spectators stay until the game
+    /// starts.
+    pub async fn set_spectators_allowed(
+        &self,
+        lobby_id: Uuid,
+        spectators_allowed: bool,
+        state: AppState,
+    ) -> Result<Lobby, AppError> {
+        let lobby = sqlx::query_as::<_, Lobby>(
+            r#"
+            UPDATE lobbies
+            SET spectators_allowed = $1, updated_at = NOW()
+            WHERE id = $2
+            RETURNING *
+            "#,
+        )
+        .bind(spectators_allowed)
+        .bind(lobby_id)
+        .fetch_one(&self.pool)
+        .timed("LobbyRepository::set_spectators_allowed")
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to update lobby spectator setting: {}", e))
+        })?;
+
+        broadcast_lobby_update(state, lobby_id).await;
+
+        Ok(lobby)
+    }
+
+    /// Set or clear the trust rating at which join requests are auto-approved.
+    /// Only private lobbies take join requests, so only they can set one.
+    pub async fn set_auto_approve_trust_threshold(
+        &self,
+        lobby_id: Uuid,
+        threshold: Option<f64>,
+        state: AppState,
+    ) -> Result<Lobby, AppError> {
+        if threshold.is_some_and(|t| !t.is_finite() || t < 0.0) {
+            return Err(AppError::BadRequest(
+                "Auto-approve trust threshold must be a non-negative number".into(),
+            ));
+        }
+        if threshold.is_some() && !self.find_by_id(lobby_id).await?.is_private {
+            return Err(AppError::BadRequest(
+                "Auto-approve trust threshold only applies to private lobbies".into(),
+            ));
+        }
+
+        let lobby = sqlx::query_as::<_, Lobby>(
+            r#"
+            UPDATE lobbies
+            SET auto_approve_trust_threshold = $1, updated_at = NOW()
+            WHERE id = $2
+            RETURNING *
+            "#,
+        )
+        .bind(threshold)
+        .bind(lobby_id)
+        .fetch_one(&self.pool)
+        .timed("LobbyRepository::set_auto_approve_trust_threshold")
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!(
+                "Failed to update lobby auto-approve setting: {}",
+                e
+            ))
+        })?;
+
+        broadcast_lobby_update(state, lobby_id).await;
+
+        Ok(lobby)
+    }
+
+    /// Cancel a lobby, but only if it is still waiting.
+    ///
+    /// Returns `None` when the lobby has moved on (e.g. started) in the meantime.
+    /// Callers are responsible for notifying lobby list subscribers.
+    pub async fn cancel_if_waiting(&self, lobby_id: Uuid) -> Result<Option<Lobby>, AppError> {
+        let lobby = sqlx::query_as::<_, Lobby>(
+            r#"
+            UPDATE lobbies
+            SET status = $1, updated_at = NOW()
+            WHERE id = $2 AND status = $3
+            RETURNING *
+            "#,
+        )
+        .bind(LobbyStatus::Cancelled)
+        .bind(lobby_id)
+        .bind(LobbyStatus::Waiting)
+        .fetch_optional(&self.pool)
+        .timed("LobbyRepository::cancel_if_waiting")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to cancel lobby: {}", e)))?;
+
+        if lobby.is_some() {
+            tracing::info!("Cancelled lobby {}", lobby_id);
+        }
+
+        Ok(lobby)
+    }
+
+    /// Make `creator_id` the creator of a waiting lobby.
+    ///
+    /// Returns None if the lobby is no longer waiting.
+    pub async fn transfer_creator(
+        &self,
+        lobby_id: Uuid,
+        creator_id: Uuid,
+    ) -> Result<Option<Lobby>, AppError> {
+        let lobby = sqlx::query_as::<_, Lobby>(
+            r#"
+            UPDATE lobbies
+            SET creator_id = $1, updated_at = NOW()
+            WHERE id = $2 AND status = $3
+            RETURNING *
+            "#,
+        )
+        .bind(creator_id)
+        .bind(lobby_id)
+        .bind(LobbyStatus::Waiting)
+        .fetch_optional(&self.pool)
+        .timed("LobbyRepository::transfer_creator")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to transfer lobby: {}", e)))?;
+
+        if lobby.is_some() {
+            tracing::info!("Transferred lobby {} to creator {}", lobby_id, creator_id);
+        }
+
+        Ok(lobby)
+    }
+
+    /// Record that the lobby's game ran to the end, for platform stats.
+    ///
+    /// Only the first call stamps the time. The status is left to the lobby
+    /// lifecycle.
+    pub async fn record_finished(&self, lobby_id: Uuid) -> Result<(), AppError> {
+        query(
+            r#"
+            UPDATE lobbies
+            SET finished_at = COALESCE(finished_at, NOW()), updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(lobby_id)
+        .execute(&self.pool)
+        .timed("LobbyRepository::record_finished")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to record lobby finish: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Store the seed a lobby's game draws its randomness from.
+    ///
+    /// Stored as the seed's bits in a BIGINT; `find_game_seed` reads it back.
+    pub async fn record_game_seed(&self, lobby_id: Uuid, seed: u64) -> Result<(), AppError> {
+        query(
+            r#"
+            UPDATE lobbies
+            SET game_seed = $2, updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(lobby_id)
+        .bind(seed as i64)
+        .execute(&self.pool)
+        .timed("LobbyRepository::record_game_seed")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to record game seed: {}", e)))?;
+
+        Ok(())
+    }
+
     /// Bulk update lobbies to finished status.
     pub async fn mark_lobbies_as_finished(&self, lobby_ids: &[Uuid]) -> Result<u64, AppError> {
         if lobby_ids.is_empty() {
@@ -324,14 +545,14 @@ impl LobbyRepository {
         let result = query(
             r#"
             UPDATE lobbies
-            SET status = $1, updated_at = $2
-            WHERE id = ANY($3)
+            SET status = $1, updated_at = NOW(), finished_at = COALESCE(finished_at, NOW())
+            WHERE id = ANY($2)
             "#,
         )
         .bind(LobbyStatus::Finished)
-        .bind(Utc::now().naive_utc())
         .bind(lobby_ids)
         .execute(&self.pool)
+        .timed("LobbyRepository::mark_lobbies_as_finished")
         .await
         .map_err(|e| {
             AppError::DatabaseError(format!("Failed to bulk update lobby statuses: {}", e))
@@ -1,3 +1,4 @@
+use crate::db::expiry::apply_expiry_pipe;
 use crate::db::lobby_chat::LobbyChatRepository;
 use crate::models::{ChatMessage, RedisKey};
 use redis::AsyncCommands;
@@ -44,11 +45,14 @@ impl LobbyChatRepository {
             .await
             .map_err(|e| format!("Failed to store message data: {}", e))?;
 
-        // Set expiration to 24 hours (chat is temporary)
-        let _: () = conn
-            .expire(&message_key, 86400)
+        // Chat is temporary; both keys get the policy TTL
+        let mut pipe = redis::pipe();
+        apply_expiry_pipe(&mut pipe, &chat_key);
+        apply_expiry_pipe(&mut pipe, &message_key);
+        let _: () = pipe
+            .query_async(&mut *conn)
             .await
-            .map_err(|e| format!("Failed to set message expiration: {}", e))?;
+            .map_err(|e| format!("Failed to set chat expiration: {}", e))?;
 
         Ok(message)
     }
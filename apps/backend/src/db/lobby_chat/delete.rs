@@ -1,5 +1,6 @@
 use crate::db::lobby_chat::LobbyChatRepository;
 use crate::models::RedisKey;
+use crate::redis_client::RedisConnection;
 use redis::AsyncCommands;
 use uuid::Uuid;
 
@@ -34,7 +35,7 @@ impl LobbyChatRepository {
 
     /// Delete all chat messages for a lobby.
     ///
-    /// Removes the sorted set and all message data.
+    /// Removes the sorted set, all message data and read pointers.
     pub async fn cleanup_lobby(&self, lobby_id: Uuid) -> Result<usize, String> {
         let mut conn = self
             .redis
@@ -43,7 +44,7 @@ impl LobbyChatRepository {
             .map_err(|e| format!("Failed to get Redis connection: {}", e))?;
 
         let chat_key = RedisKey::lobby_chat(lobby_id);
-        
+
         // Get all message IDs from the sorted set
         let message_ids: Vec<String> = conn
             .zrange(&chat_key, 0, -1)
@@ -64,12 +65,108 @@ impl LobbyChatRepository {
             }
         }
 
-        // Delete the sorted set
+        // Delete the sorted set and read pointers
         let _: () = conn
-            .del(&chat_key)
+            .del(&[chat_key, RedisKey::lobby_chat_reads(lobby_id)])
             .await
             .map_err(|e| format!("Failed to delete chat sorted set: {}", e))?;
 
         Ok(deleted_count)
     }
+
+    /// Delete the lobby's messages sent before `cutoff` (unix seconds),
+    /// oldest first, `batch` at a time. Returns how many were deleted.
+    pub async fn delete_older_than(
+        &self,
+        lobby_id: Uuid,
+        cutoff: i64,
+        batch: usize,
+    ) -> Result<usize, String> {
+        let mut conn = self
+            .redis
+            .get()
+            .await
+            .map_err(|e| format!("Failed to get Redis connection: {}", e))?;
+
+        let chat_key = RedisKey::lobby_chat(lobby_id);
+        let mut deleted = 0;
+
+        loop {
+            // The cutoff itself is kept: `(` makes the bound exclusive
+            let message_ids: Vec<String> = conn
+                .zrangebyscore_limit(&chat_key, "-inf", format!("({}", cutoff), 0, batch as isize)
+                .await
+                .map_err(|e| format!("Failed to get old message IDs: {}", e))?;
+            deleted += delete_batch(&mut conn, lobby_id, &message_ids).await?;
+            if message_ids.len() < batch {
+                return Ok(deleted);
+            }
+        }
+    }
+
+    /// Delete the lobby's oldest messages until at most `keep` are left,
+    /// `batch` at a time. Returns how many were deleted.
+    pub async fn trim_to_latest(
+        &self,
+        lobby_id: Uuid,
+        keep: usize,
+        batch: usize,
+    ) -> Result<usize, String> {
+        let mut conn = self
+            .redis
+            .get()
+            .await
+            .map_err(|e| format!("Failed to get Redis connection: {}", e))?;
+
+        let chat_key = RedisKey::lobby_chat(lobby_id);
+        let mut deleted = 0;
+
+        loop {
+            let total: usize = conn
+                .zcard(&chat_key)
+                .await
+                .map_err(|e| format!("Failed to count messages: {}", e))?;
+            let excess = total.saturating_sub(keep).min(batch);
+            if excess == 0 {
+                return Ok(deleted);
+            }
+
+            let message_ids: Vec<String> = conn
+                .zrange(&chat_key, 0, excess as isize - 1)
+                .await
+                .map_err(|e| format!("Failed to get oldest message IDs: {}", e))?;
+            if message_ids.is_empty() {
+                return Ok(deleted);
+            }
+            deleted += delete_batch(&mut conn, lobby_id, &message_ids).await?;
+        }
+    }
+}
+
+/// Remove `message_ids` from the chat index and delete their data in one
+/// round trip, so a batch never holds Redis for long.
+async fn delete_batch(
+    conn: &mut RedisConnection<'_>,
+    lobby_id: Uuid,
+    message_ids: &[String],
+) -> Result<usize, String> {
+    if message_ids.is_empty() {
+        return Ok(0);
+    }
+
+    let mut pipe = redis::pipe();
+    pipe.zrem(RedisKey::lobby_chat(lobby_id), message_ids)
+        .ignore();
+    for message_id in message_ids {
+        if let Ok(message_id) = Uuid::parse_str(message_id) {
+            pipe.del(RedisKey::lobby_chat_message(lobby_id, message_id))
+                .ignore();
+        }
+    }
+    let _: () = pipe
+        .query_async(&mut **conn)
+        .await
+        .map_err(|e| format!("Failed to delete chat messages: {}", e))?;
+
+    Ok(message_ids.len())
 }
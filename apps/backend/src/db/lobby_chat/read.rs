@@ -1,9 +1,83 @@
 use crate::db::lobby_chat::LobbyChatRepository;
-use crate::models::{ChatMessage, RedisKey};
+use crate::db::pagination::{Page, Paginator};
+use crate::models::keys::KeyPart;
+use crate::models::{ChatCursor, ChatMessage, ChatMessageView, ChatPage, RedisKey};
 use redis::AsyncCommands;
+use std::collections::HashMap;
 use uuid::Uuid;
 
+/// Largest page `get_history_page` returns
+pub const MAX_CHAT_PAGE: usize = 100;
+
+/// Keys examined per SCAN call
+const SCAN_COUNT: usize = 500;
+
+/// Ranks `start..end` of the chat index to return, and whether more lie beyond.
+///
+/// `cursor_rank` is the cursor message's position in the index; without a
+/// cursor the newest messages are returned.
+fn page_bounds(
+    cursor: Option<(ChatCursor, usize)>,
+    total: usize,
+    limit: usize,
+) -> (usize, usize, bool) {
+    match cursor {
+        None => {
+            let start = total.saturating_sub(limit);
+            (start, total, start > 0)
+        }
+        Some((ChatCursor::Before(_), rank)) => {
+            let start = rank.saturating_sub(limit);
+            (start, rank, start > 0)
+        }
+        Some((ChatCursor::After(_), rank)) => {
+            let start = (rank + 1).min(total);
+            let end = (start + limit).min(total);
+            (start, end, end < total)
+        }
+    }
+}
+
 impl LobbyChatRepository {
+    /// Every lobby that has chat stored, found with an incremental SCAN.
+    pub async fn lobbies_with_chat(&self) -> Result<Vec<Uuid>, String> {
+        let mut conn = self
+            .redis
+            .get()
+            .await
+            .map_err(|e| format!("Failed to get Redis connection: {}", e))?;
+
+        let pattern = RedisKey::lobby_chat(KeyPart::Wildcard);
+        let mut lobby_ids = Vec::new();
+        let mut cursor: u64 = 0;
+
+        loop {
+            let (next, keys): (u64, Vec<String>) = redis::cmd("SCAN")
+                .cursor_arg(cursor)
+                .arg("MATCH")
+                .arg(&pattern)
+                .arg("COUNT")
+                .arg(SCAN_COUNT)
+                .query_async(&mut *conn)
+                .await
+                .map_err(|e| format!("Failed to scan chat keys: {}", e))?;
+            lobby_ids.extend(
+                keys.iter()
+                    .filter_map(|key| key.split(':').nth(1))
+                    .filter_map(|id| Uuid::parse_str(id).ok()),
+            );
+            if next == 0 {
+                break;
+            }
+            cursor = next;
+        }
+
+        // SCAN may return a key more than once
+        lobby_ids.sort_unstable();
+        lobby_ids.dedup();
+        Ok(lobby_ids)
+    }
+
     /// Gets the most recent chat messages from a lobby.
     ///
     /// Returns messages in reverse chronological order (newest first).
@@ -46,6 +120,145 @@ impl LobbyChatRepository {
         Ok(messages)
     }
 
+    /// Gets a page of chat messages in chronological order (oldest first),
+    /// continuing from `cursor`.
+    pub async fn get_page(
+        &self,
+        lobby_id: Uuid,
+        paginator: &Paginator,
+        cursor: Option<&str>,
+    ) -> Result<Page<ChatMessage>, String> {
+        let after: Option<Uuid> = Paginator::decode(cursor).map_err(|e| e.to_string())?;
+
+        let mut conn = self
+            .redis
+            .get()
+            .await
+            .map_err(|e| format!("Failed to get Redis connection: {}", e))?;
+
+        let chat_key = RedisKey::lobby_chat(lobby_id);
+        let start = match after {
+            Some(id) => {
+                let rank: Option<usize> = conn
+                    .zrank(&chat_key, id.to_string())
+                    .await
+                    .map_err(|e| format!("Failed to locate cursor: {}", e))?;
+                rank.ok_or_else(|| format!("Cursor message {} not found", id))? + 1
+            }
+            None => 0,
+        };
+
+        let message_ids: Vec<String> = conn
+            .zrange(
+                &chat_key,
+                start as isize,
+                start as isize + paginator.fetch_limit() as isize - 1,
+            )
+            .await
+            .map_err(|e| format!("Failed to get message IDs: {}", e))?;
+        drop(conn);
+
+        let message_ids = message_ids
+            .iter()
+            .map(|id| Uuid::parse_str(id).map_err(|e| format!("Invalid message ID: {}", e)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Keyed on the ids, so a message whose data is gone doesn't stall the cursor
+        let page = paginator.page(message_ids, |id| *id);
+        let mut messages = Vec::with_capacity(page.items.len());
+        for message_id in &page.items {
+            if let Ok(Some(message)) = self.get_message(lobby_id, *message_id).await {
+                messages.push(message);
+            }
+        }
+
+        Ok(Page {
+            items: messages,
+            next_cursor: page.next_cursor,
+            has_more: page.has_more,
+        })
+    }
+
+    /// Gets a page of chat history relative to `cursor`, oldest first.
+    ///
+    /// Messages are ordered by creation time (to the second), then by id, so
+    /// pages never overlap or skip. Without a cursor the newest page is
+    /// returned. `limit` is capped at `MAX_CHAT_PAGE`. Reactions are summarized
+    /// for `viewer`.
+    pub async fn get_history_page(
+        &self,
+        lobby_id: Uuid,
+        cursor: Option<ChatCursor>,
+        limit: usize,
+        viewer: Option<Uuid>,
+    ) -> Result<ChatPage, String> {
+        let limit = limit.clamp(1, MAX_CHAT_PAGE);
+
+        let mut conn = self
+            .redis
+            .get()
+            .await
+            .map_err(|e| format!("Failed to get Redis connection: {}", e))?;
+
+        let chat_key = RedisKey::lobby_chat(lobby_id);
+        let total: usize = conn
+            .zcard(&chat_key)
+            .await
+            .map_err(|e| format!("Failed to count messages: {}", e))?;
+
+        let cursor_rank = match cursor {
+            Some(c @ (ChatCursor::Before(id) | ChatCursor::After(id))) => {
+                let rank: Option<usize> = conn
+                    .zrank(&chat_key, id.to_string())
+                    .await
+                    .map_err(|e| format!("Failed to locate cursor: {}", e))?;
+                let rank = rank.ok_or_else(|| format!("Cursor message {} not found", id))?;
+                Some((c, rank))
+            }
+            None => None,
+        };
+
+        let (start, end, has_more) = page_bounds(cursor_rank, total, limit);
+        if start >= end {
+            return Ok(ChatPage {
+                messages: Vec::new(),
+                next_cursor: None,
+                has_more: false,
+            });
+        }
+
+        let message_ids: Vec<String> = conn
+            .zrange(&chat_key, start as isize, (end - 1) as isize)
+            .await
+            .map_err(|e| format!("Failed to get message IDs: {}", e))?;
+        drop(conn);
+
+        let message_ids = message_ids
+            .iter()
+            .map(|id| Uuid::parse_str(id).map_err(|e| format!("Invalid message ID: {}", e)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Continue from the page's far edge, even if that message's data is gone
+        let next_cursor = match cursor {
+            _ if !has_more => None,
+            Some(ChatCursor::After(_)) => message_ids.last().copied(),
+            _ => message_ids.first().copied(),
+        };
+
+        let mut messages = Vec::with_capacity(message_ids.len());
+        for message_id in message_ids {
+            if let Ok(Some(message)) = self.get_message(lobby_id, message_id).await {
+                messages.push(ChatMessageView::new(message, viewer));
+            }
+        }
+
+        Ok(ChatPage {
+            messages,
+            next_cursor,
+            has_more,
+        })
+    }
+
     /// Gets a specific chat message by ID.
     pub async fn get_message(
         &self,
@@ -74,4 +287,57 @@ impl LobbyChatRepository {
             None => Ok(None),
         }
     }
+
+    /// Gets each user's last-read message in a lobby, keyed by user id.
+    pub async fn get_read_receipts(&self, lobby_id: Uuid) -> Result<HashMap<Uuid, Uuid>, String> {
+        let mut conn = self
+            .redis
+            .get()
+            .await
+            .map_err(|e| format!("Failed to get Redis connection: {}", e))?;
+
+        let reads: HashMap<String, String> = conn
+            .hgetall(RedisKey::lobby_chat_reads(lobby_id))
+            .await
+            .map_err(|e| format!("Failed to get read receipts: {}", e))?;
+
+        Ok(reads
+            .into_iter()
+            .filter_map(|(user_id, message_id)| {
+                Some((
+                    Uuid::parse_str(&user_id).ok()?,
+                    Uuid::parse_str(&message_id).ok()?,
+                ))
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_page_is_newest() {
+        assert_eq!(page_bounds(None, 10, 4), (6, 10, true));
+        assert_eq!(page_bounds(None, 4, 4), (0, 4, false));
+        assert_eq!(page_bounds(None, 2, 4), (0, 2, false));
+    }
+
+    #[test]
+    fn test_paging_backward_stops_at_oldest() {
+        let cursor = ChatCursor::Before(Uuid::new_v4());
+        assert_eq!(page_bounds(Some((cursor, 6)), 10, 4), (2, 6, true));
+        // Exactly `limit` older messages left: this page has them all
+        assert_eq!(page_bounds(Some((cursor, 4)), 10, 4), (0, 4, false));
+        assert_eq!(page_bounds(Some((cursor, 0)), 10, 4), (0, 0, false));
+    }
+
+    #[test]
+    fn test_paging_forward_stops_at_newest() {
+        let cursor = ChatCursor::After(Uuid::new_v4());
+        assert_eq!(page_bounds(Some((cursor, 2)), 10, 4), (3, 7, true));
+        assert_eq!(page_bounds(Some((cursor, 5)), 10, 4), (6, 10, false));
+        assert_eq!(page_bounds(Some((cursor, 9)), 10, 4), (10, 10, false));
+    }
 }
@@ -1,17 +1,71 @@
 use crate::db::lobby_chat::LobbyChatRepository;
-use crate::models::{ChatMessage, RedisKey};
-use redis::AsyncCommands;
+use crate::models::{ChatMessage, ExpiryPolicy, RedisKey};
+use redis::{AsyncCommands, SetExpiry, SetOptions};
 use uuid::Uuid;
 
+/// Moves a user's read pointer to a message, but never back: the new message
+/// must rank after the current one in the chat index. A pointer to a message
+/// that has since left the index is replaced. Returns -1 for an unknown
+/// message, 0 when the pointer stays, 1 when it moved.
+const MARK_READ_SCRIPT: &str = r#"
+local new_rank = redis.call('ZRANK', KEYS[1], ARGV[2])
+if not new_rank then
+    return -1
+end
+local current = redis.call('HGET', KEYS[2], ARGV[1])
+if current then
+    local current_rank = redis.call('ZRANK', KEYS[1], current)
+    if current_rank and current_rank >= new_rank then
+        return 0
+    end
+end
+redis.call('HSET', KEYS[2], ARGV[1], ARGV[2])
+redis.call('EXPIRE', KEYS[2], ARGV[3])
+return 1
+"#;
+
 impl LobbyChatRepository {
     /// Adds a reaction to a chat message.
+    ///
+    /// Returns the message and whether it changed; re-adding a reaction the
+    /// user already has is a no-op.
     pub async fn add_reaction(
         &self,
         lobby_id: Uuid,
         message_id: Uuid,
         user_id: Uuid,
         emoji: &str,
-    ) -> Result<ChatMessage, String> {
+    ) -> Result<(ChatMessage, bool), String> {
+        self.update_reactions(lobby_id, message_id, |message| {
+            message.add_reaction(user_id, emoji)
+        })
+        .await
+    }
+
+    /// Removes a reaction from a chat message.
+    ///
+    /// Returns the message and whether it changed; removing a reaction that
+    /// isn't there is a no-op.
+    pub async fn remove_reaction(
+        &self,
+        lobby_id: Uuid,
+        message_id: Uuid,
+        user_id: Uuid,
+        emoji: &str,
+    ) -> Result<(ChatMessage, bool), String> {
+        self.update_reactions(lobby_id, message_id, |message| {
+            message.remove_reaction(user_id, emoji)
+        })
+        .await
+    }
+
+    /// Applies `change` to a message's reactions, saving only if it reports a change.
+    async fn update_reactions(
+        &self,
+        lobby_id: Uuid,
+        message_id: Uuid,
+        change: impl FnOnce(&mut ChatMessage) -> bool,
+    ) -> Result<(ChatMessage, bool), String> {
         let mut conn = self
             .redis
             .get()
@@ -31,60 +85,112 @@ impl LobbyChatRepository {
         let mut message: ChatMessage = serde_json::from_str(&message_json)
             .map_err(|e| format!("Failed to deserialize message: {}", e))?;
 
-        // Add reaction
-        message.add_reaction(user_id, emoji);
+        if !change(&mut message) {
+            return Ok((message, false));
+        }
 
         // Save updated message
         let updated_json = serde_json::to_string(&message)
             .map_err(|e| format!("Failed to serialize message: {}", e))?;
 
         let _: () = conn
-            .set(&message_key, updated_json)
+            .set_options(
+                &message_key,
+                updated_json,
+                SetOptions::default().with_expiration(SetExpiry::KEEPTTL),
+            )
             .await
             .map_err(|e| format!("Failed to update message: {}", e))?;
 
-        Ok(message)
+        Ok((message, true))
     }
 
-    /// Removes a reaction from a chat message.
-    pub async fn remove_reaction(
+    /// Records that `user_id` has read up to `message_id`.
+    ///
+    /// Applied atomically. Returns whether the pointer advanced; marking a
+    /// message older than (or the same as) the current one leaves it alone.
+    pub async fn mark_read(
         &self,
         lobby_id: Uuid,
-        message_id: Uuid,
         user_id: Uuid,
-        emoji: &str,
-    ) -> Result<ChatMessage, String> {
+        message_id: Uuid,
+    ) -> Result<bool, String> {
         let mut conn = self
             .redis
             .get()
             .await
             .map_err(|e| format!("Failed to get Redis connection: {}", e))?;
 
-        let message_key = RedisKey::lobby_chat_message(lobby_id, message_id);
-
-        // Get current message
-        let message_json: Option<String> = conn
-            .get(&message_key)
+        let moved: i64 = redis::Script::new(MARK_READ_SCRIPT)
+            .key(RedisKey::lobby_chat(lobby_id))
+            .key(RedisKey::lobby_chat_reads(lobby_id))
+            .arg(user_id.to_string())
+            .arg(message_id.to_string())
+            .arg(ExpiryPolicy::CHAT_TTL_SECS)
+            .invoke_async(&mut *conn)
             .await
-            .map_err(|e| format!("Failed to get message: {}", e))?;
+            .map_err(|e| format!("Failed to mark message read: {}", e))?;
 
-        let message_json = message_json.ok_or_else(|| "Message not found".to_string())?;
-
-        let mut message: ChatMessage = serde_json::from_str(&message_json)
-            .map_err(|e| format!("Failed to deserialize message: {}", e))?;
-
-        // Remove reaction
-        message.remove_reaction(user_id, emoji);
-
-        // Save updated message
-        let updated_json = serde_json::to_string(&message)
-            .map_err(|e| format!("Failed to serialize message: {}", e))?;
+        match moved {
+            -1 => Err("Message not found".to_string()),
+            moved => Ok(moved == 1),
+        }
+    }
 
-        let _: () = conn
-            .set(&message_key, updated_json)
+    /// Reattributes every message `from_user` sent in a lobby to `to_user`.
+    ///
+    /// Message expiry is preserved. Returns the number of messages changed.
+    pub async fn reattribute_messages(
+        &self,
+        lobby_id: Uuid,
+        from_user: Uuid,
+        to_user: Uuid,
+    ) -> Result<usize, String> {
+        let mut conn = self
+            .redis
+            .get()
             .await
-            .map_err(|e| format!("Failed to update message: {}", e))?;
+            .map_err(|e| format!("Failed to get Redis connection: {}", e))?;
 
-        Ok(message)
+        let message_ids: Vec<String> = conn
+            .zrange(RedisKey::lobby_chat(lobby_id), 0, -1)
+            .await
+            .map_err(|e| format!("Failed to get message IDs: {}", e))?;
+
+        let mut changed = 0;
+        for message_id in message_ids {
+            let message_id =
+                Uuid::parse_str(&message_id).map_err(|e| format!("Invalid message ID: {}", e))?;
+            let message_key = RedisKey::lobby_chat_message(lobby_id, message_id);
+
+            let message_json: Option<String> = conn
+                .get(&message_key)
+                .await
+                .map_err(|e| format!("Failed to get message: {}", e))?;
+            let Some(message_json) = message_json else {
+                continue;
+            };
+
+            let mut message: ChatMessage = serde_json::from_str(&message_json)
+                .map_err(|e| format!("Failed to deserialize message: {}", e))?;
+            if message.user_id != from_user {
+                continue;
+            }
+            message.user_id = to_user;
+
+            let updated_json = serde_json::to_string(&message)
+                .map_err(|e| format!("Failed to serialize message: {}", e))?;
+            let _: () = conn
+                .set_options(
+                    &message_key,
+                    updated_json,
+                    SetOptions::default().with_expiration(SetExpiry::KEEPTTL),
+                )
+                .await
+                .map_err(|e| format!("Failed to update message: {}", e))?;
+            changed += 1;
+        }
+
+        Ok(changed)
     }
 }
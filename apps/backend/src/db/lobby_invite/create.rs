@@ -0,0 +1,55 @@
+// Create operations for LobbyInvite (Redis)
+
+use crate::db::expiry::apply_expiry;
+use crate::db::lobby_invite::LobbyInviteRepository;
+use crate::errors::AppError;
+use crate::models::{LobbyInvite, RedisKey};
+use redis::AsyncCommands;
+use uuid::Uuid;
+
+impl LobbyInviteRepository {
+    /// Create a new invite for a lobby.
+    ///
+    /// When `expires_in_secs` is set the record expires with the invite;
+    /// otherwise it gets the policy TTL, so Redis does not accumulate dead invites.
+    pub async fn create_invite(
+        &self,
+        lobby_id: Uuid,
+        created_by: Uuid,
+        max_uses: Option<u32>,
+        expires_in_secs: Option<i64>,
+    ) -> Result<LobbyInvite, AppError> {
+        if let Some(0) = max_uses {
+            return Err(AppError::BadRequest("maxUses must be at least 1".into()));
+        }
+        if let Some(secs) = expires_in_secs
+            && secs <= 0
+        {
+            return Err(AppError::BadRequest(
+                "expiresInSecs must be positive".into(),
+            ));
+        }
+
+        let mut conn = self.redis.get().await?;
+
+        let invite = LobbyInvite::new(lobby_id, created_by, max_uses, expires_in_secs);
+        let key = RedisKey::lobby_invite(lobby_id, invite.invite_id);
+
+        let _: () = conn
+            .hset_multiple(&key, &invite.to_redis_hash())
+            .await
+            .map_err(AppError::RedisCommandError)?;
+
+        match expires_in_secs {
+            Some(secs) => {
+                let _: () = conn
+                    .expire(&key, secs)
+                    .await
+                    .map_err(AppError::RedisCommandError)?;
+            }
+            None => apply_expiry(&mut conn, &key).await?,
+        }
+
+        Ok(invite)
+    }
+}
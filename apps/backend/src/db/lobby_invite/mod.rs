@@ -0,0 +1,20 @@
+// LobbyInvite repository (Redis): invite records backing signed invite links
+
+mod create;
+mod read;
+mod update;
+
+use crate::state::RedisClient;
+
+/// Repository for lobby invite operations.
+#[derive(Clone)]
+pub struct LobbyInviteRepository {
+    pub(crate) redis: RedisClient,
+}
+
+impl LobbyInviteRepository {
+    /// Create a new `LobbyInviteRepository`.
+    pub fn new(redis: RedisClient) -> Self {
+        Self { redis }
+    }
+}
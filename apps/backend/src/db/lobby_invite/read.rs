@@ -0,0 +1,34 @@
+// Read operations for LobbyInvite (Redis)
+
+use crate::db::lobby_invite::LobbyInviteRepository;
+use crate::errors::AppError;
+use crate::models::{LobbyInvite, RedisKey};
+use redis::AsyncCommands;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+impl LobbyInviteRepository {
+    /// Get an invite record by lobby and invite ID.
+    pub async fn get_invite(
+        &self,
+        lobby_id: Uuid,
+        invite_id: Uuid,
+    ) -> Result<LobbyInvite, AppError> {
+        let mut conn = self.redis.get().await?;
+        let key = RedisKey::lobby_invite(lobby_id, invite_id);
+
+        let map: HashMap<String, String> = conn
+            .hgetall(&key)
+            .await
+            .map_err(AppError::RedisCommandError)?;
+
+        if map.is_empty() {
+            return Err(AppError::NotFound(format!(
+                "Invite {} not found",
+                invite_id
+            )));
+        }
+
+        LobbyInvite::from_redis_hash(&map)
+    }
+}
@@ -0,0 +1,54 @@
+// Update operations for LobbyInvite (Redis): redemption and revocation
+
+use crate::db::lobby_invite::LobbyInviteRepository;
+use crate::errors::AppError;
+use crate::models::{InviteError, LobbyInvite, RedisKey};
+use chrono::Utc;
+use redis::AsyncCommands;
+use uuid::Uuid;
+
+impl LobbyInviteRepository {
+    /// Redeem one use of an invite.
+    ///
+    /// The use counter is incremented atomically; if that overshoots `max_uses`
+    /// (concurrent redemptions) the increment is rolled back and the invite is
+    /// reported as exhausted.
+    pub async fn redeem(&self, lobby_id: Uuid, invite_id: Uuid) -> Result<LobbyInvite, AppError> {
+        let mut invite = self.get_invite(lobby_id, invite_id).await?;
+        invite.check_redeemable(Utc::now().timestamp())?;
+
+        let mut conn = self.redis.get().await?;
+        let key = RedisKey::lobby_invite(lobby_id, invite_id);
+
+        let uses: u32 = conn
+            .hincr(&key, "uses", 1)
+            .await
+            .map_err(AppError::RedisCommandError)?;
+
+        if let Some(max_uses) = invite.max_uses
+            && uses > max_uses
+        {
+            let _: redis::RedisResult<i64> = conn.hincr(&key, "uses", -1).await;
+            return Err(InviteError::Exhausted { max_uses }.into());
+        }
+
+        invite.uses = uses;
+        Ok(invite)
+    }
+
+    /// Revoke an invite; every outstanding token for it stops working.
+    pub async fn revoke(&self, lobby_id: Uuid, invite_id: Uuid) -> Result<(), AppError> {
+        // Ensure the invite exists so revoking doesn't create a partial record
+        self.get_invite(lobby_id, invite_id).await?;
+
+        let mut conn = self.redis.get().await?;
+        let key = RedisKey::lobby_invite(lobby_id, invite_id);
+
+        let _: () = conn
+            .hset(&key, "revoked", true.to_string())
+            .await
+            .map_err(AppError::RedisCommandError)?;
+
+        Ok(())
+    }
+}
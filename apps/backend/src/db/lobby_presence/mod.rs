@@ -0,0 +1,24 @@
+// LobbyPresence repository (Redis): room connections open to a lobby on any instance
+
+mod read;
+mod update;
+
+use crate::state::RedisClient;
+
+/// Repository for lobby presence.
+///
+/// Each lobby has a sorted set of its open room connections, scored by when
+/// each was last seen. Instances refresh their own connections periodically,
+/// so entries left behind by an instance that died go stale instead of
+/// keeping the lobby alive.
+#[derive(Clone)]
+pub struct LobbyPresenceRepository {
+    pub(crate) redis: RedisClient,
+}
+
+impl LobbyPresenceRepository {
+    /// Create a new `LobbyPresenceRepository`.
+    pub fn new(redis: RedisClient) -> Self {
+        Self { redis }
+    }
+}
@@ -0,0 +1,17 @@
+use redis::AsyncCommands;
+use uuid::Uuid;
+
+use crate::{db::lobby_presence::LobbyPresenceRepository, errors::AppError, models::RedisKey};
+
+impl LobbyPresenceRepository {
+    /// Whether any instance has seen a connection to the lobby at or after
+    /// `since` (unix seconds).
+    pub async fn has_connections(&self, lobby_id: Uuid, since: i64) -> Result<bool, AppError> {
+        let mut conn = self.redis.get().await?;
+        let count: i64 = conn
+            .zcount(RedisKey::lobby_connections(lobby_id), since, "+inf")
+            .await
+            .map_err(AppError::RedisCommandError)?;
+        Ok(count > 0)
+    }
+}
@@ -0,0 +1,51 @@
+use chrono::Utc;
+use redis::AsyncCommands;
+use uuid::Uuid;
+
+use crate::{
+    db::{expiry::apply_expiry_pipe, lobby_presence::LobbyPresenceRepository},
+    errors::AppError,
+    models::RedisKey,
+};
+
+impl LobbyPresenceRepository {
+    /// Mark connections to the lobby as seen now.
+    pub async fn touch(&self, lobby_id: Uuid, connection_ids: &[Uuid]) -> Result<(), AppError> {
+        if connection_ids.is_empty() {
+            return Ok(());
+        }
+
+        let key = RedisKey::lobby_connections(lobby_id);
+        let now = Utc::now().timestamp();
+        let members: Vec<(i64, String)> = connection_ids
+            .iter()
+            .map(|conn_id| (now, conn_id.to_string()))
+            .collect();
+
+        let mut pipe = redis::pipe();
+        pipe.atomic().zadd_multiple(&key, &members).ignore();
+        apply_expiry_pipe(&mut pipe, &key);
+
+        let mut conn = self.redis.get().await?;
+        let _: () = pipe
+            .query_async(&mut *conn)
+            .await
+            .map_err(AppError::RedisCommandError)?;
+        Ok(())
+    }
+
+    /// Forget connections to the lobby that have closed.
+    pub async fn remove(&self, lobby_id: Uuid, connection_ids: &[Uuid]) -> Result<(), AppError> {
+        if connection_ids.is_empty() {
+            return Ok(());
+        }
+
+        let members: Vec<String> = connection_ids.iter().map(Uuid::to_string).collect();
+        let mut conn = self.redis.get().await?;
+        let _: i64 = conn
+            .zrem(RedisKey::lobby_connections(lobby_id), members)
+            .await
+            .map_err(AppError::RedisCommandError)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,63 @@
+use crate::{
+    db::timing::TimedQuery,
+    errors::AppError,
+    models::{Lobby, LobbyRefund},
+};
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use super::LobbyRefundRepository;
+
+impl LobbyRefundRepository {
+    /// Record refunds for a cancelled lobby.
+    ///
+    /// `refunds` are `(user_id, wallet_address, amount)`. Recording is idempotent
+    /// per `(lobby_id, user_id)`, so a retried reap won't double a refund.
+    pub async fn create_refunds(
+        &self,
+        lobby: &Lobby,
+        refunds: &[(Uuid, String, Decimal)],
+    ) -> Result<Vec<LobbyRefund>, AppError> {
+        let mut transaction =
+            self.pool.begin().await.map_err(|e| {
+                AppError::DatabaseError(format!("Failed to start transaction: {}", e))
+            })?;
+
+        let mut created = Vec::with_capacity(refunds.len());
+
+        for (user_id, wallet_address, amount) in refunds {
+            let refund = sqlx::query_as::<_, LobbyRefund>(
+                "INSERT INTO lobby_refunds
+                    (lobby_id, user_id, wallet_address, amount, token_symbol, token_contract_id)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                ON CONFLICT (lobby_id, user_id) DO NOTHING
+                RETURNING *",
+            )
+            .bind(lobby.id())
+            .bind(user_id)
+            .bind(wallet_address)
+            .bind(amount)
+            .bind(&lobby.token_symbol)
+            .bind(lobby.token_contract_id.as_ref().map(|c| c.as_str()))
+            .fetch_optional(&mut *transaction)
+            .timed("LobbyRefundRepository::create_refunds")
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to record refund: {}", e)))?;
+
+            created.extend(refund);
+        }
+
+        transaction
+            .commit()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to commit transaction: {}", e)))?;
+
+        tracing::info!(
+            "Recorded {} refunds for lobby {}",
+            created.len(),
+            lobby.id()
+        );
+
+        Ok(created)
+    }
+}
@@ -0,0 +1,18 @@
+use sqlx::PgPool;
+
+mod create;
+mod read;
+mod update;
+
+/// Repository for refunds owed on cancelled lobbies.
+#[derive(Clone)]
+pub struct LobbyRefundRepository {
+    pub(crate) pool: PgPool,
+}
+
+impl LobbyRefundRepository {
+    /// Create a new `LobbyRefundRepository` with the given pool.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
@@ -0,0 +1,51 @@
+use crate::{db::timing::TimedQuery, errors::AppError, models::LobbyRefund};
+use uuid::Uuid;
+
+use super::LobbyRefundRepository;
+
+impl LobbyRefundRepository {
+    /// Get all refunds recorded for a lobby.
+    pub async fn find_by_lobby(&self, lobby_id: Uuid) -> Result<Vec<LobbyRefund>, AppError> {
+        let refunds = sqlx::query_as::<_, LobbyRefund>(
+            "SELECT * FROM lobby_refunds WHERE lobby_id = $1 ORDER BY created_at",
+        )
+        .bind(lobby_id)
+        .fetch_all(&self.pool)
+        .timed("LobbyRefundRepository::find_by_lobby")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch refunds: {}", e)))?;
+
+        Ok(refunds)
+    }
+
+    /// Get all refunds owed to a user, oldest first.
+    pub async fn find_by_user(&self, user_id: Uuid) -> Result<Vec<LobbyRefund>, AppError> {
+        let refunds = sqlx::query_as::<_, LobbyRefund>(
+            "SELECT * FROM lobby_refunds WHERE user_id = $1 ORDER BY created_at",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .timed("LobbyRefundRepository::find_by_user")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch refunds: {}", e)))?;
+
+        Ok(refunds)
+    }
+
+    /// Get refunds that haven't been paid out yet, oldest first.
+    pub async fn find_pending(&self, limit: i64) -> Result<Vec<LobbyRefund>, AppError> {
+        let refunds = sqlx::query_as::<_, LobbyRefund>(
+            "SELECT * FROM lobby_refunds
+            WHERE refund_tx_id IS NULL
+            ORDER BY created_at
+            LIMIT $1",
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .timed("LobbyRefundRepository::find_pending")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch pending refunds: {}", e)))?;
+
+        Ok(refunds)
+    }
+}
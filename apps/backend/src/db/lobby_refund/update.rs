@@ -0,0 +1,32 @@
+use crate::db::timing::TimedQuery;
+use crate::errors::AppError;
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use super::LobbyRefundRepository;
+
+impl LobbyRefundRepository {
+    /// Replace the wallet on a deleted user's paid-out refunds.
+    ///
+    /// Pending refunds keep the real wallet so they can still be paid out.
+    /// Runs inside the caller's transaction.
+    pub async fn anonymize_user(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        placeholder_wallet: &str,
+    ) -> Result<u64, AppError> {
+        let result = sqlx::query(
+            "UPDATE lobby_refunds
+            SET wallet_address = $2, updated_at = NOW()
+            WHERE user_id = $1 AND refund_tx_id IS NOT NULL",
+        )
+        .bind(user_id)
+        .bind(placeholder_wallet)
+        .execute(conn)
+        .timed("LobbyRefundRepository::anonymize_user")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to anonymize refunds: {}", e)))?;
+
+        Ok(result.rows_affected())
+    }
+}
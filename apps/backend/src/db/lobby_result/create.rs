@@ -0,0 +1,25 @@
+use uuid::Uuid;
+
+use crate::{db::timing::TimedQuery, errors::AppError, games::GameResults};
+
+use super::LobbyResultRepository;
+
+impl LobbyResultRepository {
+    /// Store a lobby's final results; a lobby keeps the first results stored for it.
+    pub async fn store(&self, lobby_id: Uuid, results: &GameResults) -> Result<(), AppError> {
+        let json =
+            serde_json::to_string(results).map_err(|e| AppError::Serialization(e.to_string()))?;
+        sqlx::query(
+            "INSERT INTO lobby_results (lobby_id, results)
+            VALUES ($1, $2)
+            ON CONFLICT (lobby_id) DO NOTHING",
+        )
+        .bind(lobby_id)
+        .bind(json)
+        .execute(&self.pool)
+        .timed("LobbyResultRepository::store")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to store lobby results: {}", e)))?;
+        Ok(())
+    }
+}
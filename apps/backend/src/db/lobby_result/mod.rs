@@ -0,0 +1,17 @@
+use sqlx::PgPool;
+
+mod create;
+mod read;
+
+/// Repository for lobbies' final game results.
+#[derive(Clone)]
+pub struct LobbyResultRepository {
+    pub(crate) pool: PgPool,
+}
+
+impl LobbyResultRepository {
+    /// Create a new `LobbyResultRepository` with the given pool.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
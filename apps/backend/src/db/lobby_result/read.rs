@@ -0,0 +1,25 @@
+use uuid::Uuid;
+
+use crate::{db::timing::TimedQuery, errors::AppError, games::GameResults};
+
+use super::LobbyResultRepository;
+
+impl LobbyResultRepository {
+    /// A lobby's final results, if its game finished ranked.
+    pub async fn find_by_lobby(&self, lobby_id: Uuid) -> Result<Option<GameResults>, AppError> {
+        let json: Option<String> =
+            sqlx::query_scalar("SELECT results FROM lobby_results WHERE lobby_id = $1")
+                .bind(lobby_id)
+                .fetch_optional(&self.pool)
+                .timed("LobbyResultRepository::find_by_lobby")
+                .await
+                .map_err(|e| {
+                    AppError::DatabaseError(format!("Failed to fetch lobby results: {}", e))
+                })?;
+
+        json.map(|json| {
+            serde_json::from_str(&json).map_err(|e| AppError::Deserialization(e.to_string()))
+        })
+        .transpose()
+    }
+}
@@ -1,5 +1,6 @@
 // Create operations for LobbyState (Redis)
 
+use crate::db::expiry::apply_expiry;
 use crate::db::lobby_state::LobbyStateRepository;
 use crate::errors::AppError;
 use crate::models::{LobbyState, RedisKey};
@@ -8,10 +9,7 @@ use redis::AsyncCommands;
 impl LobbyStateRepository {
     /// Create a new lobby state in Redis (fails if already exists).
     pub async fn create_state(&self, state: LobbyState) -> Result<(), AppError> {
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
+        let mut conn = self.redis.get().await?;
         let key = RedisKey::lobby_state(state.lobby_id);
 
         // Check if state already exists
@@ -33,16 +31,14 @@ impl LobbyStateRepository {
             .hset_multiple(&key, &hash)
             .await
             .map_err(AppError::RedisCommandError)?;
+        apply_expiry(&mut conn, &key).await?;
 
         Ok(())
     }
 
     /// Upsert (create or overwrite) a lobby state in Redis.
     pub async fn upsert_state(&self, state: LobbyState) -> Result<(), AppError> {
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
+        let mut conn = self.redis.get().await?;
         let key = RedisKey::lobby_state(state.lobby_id);
 
         // Convert to hash and store (overwrites if exists)
@@ -51,6 +47,7 @@ impl LobbyStateRepository {
             .hset_multiple(&key, &hash)
             .await
             .map_err(AppError::RedisCommandError)?;
+        apply_expiry(&mut conn, &key).await?;
 
         Ok(())
     }
@@ -9,10 +9,7 @@ use uuid::Uuid;
 impl LobbyStateRepository {
     /// Delete a lobby state; errors if not found.
     pub async fn delete_state(&self, lobby_id: Uuid) -> Result<(), AppError> {
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
+        let mut conn = self.redis.get().await?;
         let key = RedisKey::lobby_state(lobby_id);
 
         let deleted: usize = conn.del(&key).await.map_err(AppError::RedisCommandError)?;
@@ -27,12 +24,21 @@ impl LobbyStateRepository {
         Ok(())
     }
 
+    /// Forget who readied up, ahead of a new ready check.
+    pub async fn clear_ready(&self, lobby_id: Uuid) -> Result<(), AppError> {
+        let mut conn = self.redis.get().await?;
+
+        let _: () = conn
+            .del(RedisKey::lobby_ready(lobby_id))
+            .await
+            .map_err(AppError::RedisCommandError)?;
+
+        Ok(())
+    }
+
     /// Soft-delete a lobby state; returns `true` if removed.
     pub async fn delete_state_soft(&self, lobby_id: Uuid) -> Result<bool, AppError> {
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
+        let mut conn = self.redis.get().await?;
         let key = RedisKey::lobby_state(lobby_id);
 
         let deleted: usize = conn.del(&key).await.map_err(AppError::RedisCommandError)?;
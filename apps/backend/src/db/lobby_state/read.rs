@@ -5,16 +5,13 @@ use crate::errors::AppError;
 use crate::models::keys::{KeyPart, RedisKey};
 use crate::models::{LobbyState, LobbyStatus};
 use redis::AsyncCommands;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
 impl LobbyStateRepository {
     /// Get the lobby state by UUID from Redis.
     pub async fn get_state(&self, lobby_id: Uuid) -> Result<LobbyState, AppError> {
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
+        let mut conn = self.redis.get().await?;
         let key = RedisKey::lobby_state(lobby_id);
 
         let map: HashMap<String, String> = conn
@@ -32,12 +29,24 @@ impl LobbyStateRepository {
         LobbyState::from_redis_hash(&map)
     }
 
+    /// Players who have readied up for the lobby's start.
+    pub async fn get_ready(&self, lobby_id: Uuid) -> Result<HashSet<Uuid>, AppError> {
+        let mut conn = self.redis.get().await?;
+
+        let members: Vec<String> = conn
+            .smembers(RedisKey::lobby_ready(lobby_id))
+            .await
+            .map_err(AppError::RedisCommandError)?;
+
+        Ok(members
+            .iter()
+            .filter_map(|id| Uuid::parse_str(id).ok())
+            .collect())
+    }
+
     /// Check whether a lobby state exists in Redis.
     pub async fn exists(&self, lobby_id: Uuid) -> Result<bool, AppError> {
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
+        let mut conn = self.redis.get().await?;
         let key = RedisKey::lobby_state(lobby_id);
 
         conn.exists(&key).await.map_err(AppError::RedisCommandError)
@@ -45,10 +54,7 @@ impl LobbyStateRepository {
 
     /// Retrieve a lobby's current `LobbyStatus` from Redis.
     pub async fn get_status(&self, lobby_id: Uuid) -> Result<LobbyStatus, AppError> {
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
+        let mut conn = self.redis.get().await?;
         let key = RedisKey::lobby_state(lobby_id);
 
         let status: Option<String> = conn
@@ -69,10 +75,7 @@ impl LobbyStateRepository {
 
     /// Return the participant count for a lobby.
     pub async fn get_participant_count(&self, lobby_id: Uuid) -> Result<usize, AppError> {
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
+        let mut conn = self.redis.get().await?;
         let key = RedisKey::lobby_state(lobby_id);
 
         let count: Option<usize> = conn
@@ -85,10 +88,7 @@ impl LobbyStateRepository {
 
     /// Fetch all lobby states (optional `limit`).
     pub async fn get_all(&self, limit: Option<usize>) -> Result<Vec<LobbyState>, AppError> {
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
+        let mut conn = self.redis.get().await?;
         let pattern = RedisKey::lobby_state(KeyPart::Wildcard);
 
         let keys: Vec<String> = conn
@@ -139,10 +139,7 @@ impl LobbyStateRepository {
             return Ok(Vec::new());
         }
 
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
+        let mut conn = self.redis.get().await?;
 
         // Build pipeline with HGETALL commands for each lobby
         let mut pipe = redis::pipe();
@@ -1,20 +1,35 @@
 // Update operations for LobbyState (Redis)
 
+use crate::db::expiry::apply_expiry;
 use crate::db::lobby_state::LobbyStateRepository;
 use crate::errors::AppError;
-use crate::models::LobbyStatus;
 use crate::models::keys::RedisKey;
+use crate::models::{Lobby, LobbyStatus};
 use chrono::Utc;
 use redis::AsyncCommands;
+use rust_decimal::Decimal;
 use uuid::Uuid;
 
+/// Writes config fields into a lobby state hash, but only while it exists, so
+/// a lobby whose runtime expired isn't resurrected. Empty values delete.
+const SYNC_CONFIG_SCRIPT: &str = r#"
+if redis.call('EXISTS', KEYS[1]) == 0 then
+    return 0
+end
+for i = 1, #ARGV, 2 do
+    if ARGV[i + 1] == '' then
+        redis.call('HDEL', KEYS[1], ARGV[i])
+    else
+        redis.call('HSET', KEYS[1], ARGV[i], ARGV[i + 1])
+    end
+end
+return 1
+"#;
+
 impl LobbyStateRepository {
     /// Update lobby status.
     pub async fn update_status(&self, lobby_id: Uuid, status: LobbyStatus) -> Result<(), AppError> {
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
+        let mut conn = self.redis.get().await?;
         let key = RedisKey::lobby_state(lobby_id);
 
         // Check if exists
@@ -54,10 +69,7 @@ impl LobbyStateRepository {
         lobby_id: Uuid,
         count: usize,
     ) -> Result<(), AppError> {
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
+        let mut conn = self.redis.get().await?;
         let key = RedisKey::lobby_state(lobby_id);
 
         let now = Utc::now().timestamp();
@@ -78,10 +90,7 @@ impl LobbyStateRepository {
 
     /// Increment participant count by 1 and return the new count.
     pub async fn increment_participants(&self, lobby_id: Uuid) -> Result<usize, AppError> {
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
+        let mut conn = self.redis.get().await?;
         let key = RedisKey::lobby_state(lobby_id);
 
         let new_count: usize = conn
@@ -101,10 +110,7 @@ impl LobbyStateRepository {
 
     /// Decrement participant count by 1 (never negative) and return the new count.
     pub async fn decrement_participants(&self, lobby_id: Uuid) -> Result<usize, AppError> {
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
+        let mut conn = self.redis.get().await?;
         let key = RedisKey::lobby_state(lobby_id);
 
         let new_count: isize = conn
@@ -127,10 +133,7 @@ impl LobbyStateRepository {
 
     /// Mark the lobby as started and set `started_at`.
     pub async fn mark_started(&self, lobby_id: Uuid) -> Result<(), AppError> {
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
+        let mut conn = self.redis.get().await?;
         let key = RedisKey::lobby_state(lobby_id);
 
         let now = Utc::now().timestamp();
@@ -152,10 +155,7 @@ impl LobbyStateRepository {
 
     /// Mark the lobby as finished and set `finished_at`.
     pub async fn mark_finished(&self, lobby_id: Uuid) -> Result<(), AppError> {
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
+        let mut conn = self.redis.get().await?;
         let key = RedisKey::lobby_state(lobby_id);
 
         let now = Utc::now().timestamp();
@@ -177,10 +177,7 @@ impl LobbyStateRepository {
 
     /// Update the lobby creator's last ping timestamp.
     pub async fn update_creator_ping(&self, lobby_id: Uuid) -> Result<(), AppError> {
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
+        let mut conn = self.redis.get().await?;
         let key = RedisKey::lobby_state(lobby_id);
 
         let now_ms = Utc::now().timestamp_millis() as u64;
@@ -202,10 +199,7 @@ impl LobbyStateRepository {
 
     /// Update the Telegram message ID associated with the lobby.
     pub async fn update_tg_msg_id(&self, lobby_id: Uuid, msg_id: i32) -> Result<(), AppError> {
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
+        let mut conn = self.redis.get().await?;
         let key = RedisKey::lobby_state(lobby_id);
 
         let now = Utc::now().timestamp();
@@ -226,10 +220,7 @@ impl LobbyStateRepository {
 
     /// Touch the lobby (refresh `updated_at`).
     pub async fn touch(&self, lobby_id: Uuid) -> Result<(), AppError> {
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
+        let mut conn = self.redis.get().await?;
         let key = RedisKey::lobby_state(lobby_id);
 
         let now = Utc::now().timestamp();
@@ -242,40 +233,43 @@ impl LobbyStateRepository {
         Ok(())
     }
 
-    /// Persist the countdown seconds for a lobby (overwrites) and set a short expiry.
+    /// Persist the countdown seconds for a lobby (overwrites) with a short expiry.
     pub async fn set_countdown(
         &self,
         lobby_id: Uuid,
         seconds_remaining: u8,
     ) -> Result<(), AppError> {
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
+        let mut conn = self.redis.get().await?;
 
         let key = RedisKey::lobby_countdown(lobby_id);
 
-        // Store as integer and set a TTL so cancelled/finished countdowns expire.
+        // Store as integer; the policy TTL lets cancelled/finished countdowns expire.
         let _: () = conn
             .set(&key, seconds_remaining)
             .await
             .map_err(AppError::RedisCommandError)?;
+        apply_expiry(&mut conn, &key).await?;
+
+        Ok(())
+    }
+
+    /// Record that `user_id` is ready to start.
+    pub async fn mark_ready(&self, lobby_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        let mut conn = self.redis.get().await?;
+        let key = RedisKey::lobby_ready(lobby_id);
 
-        // Keep countdown around for a short period (e.g., 60s) so clients can pick it up.
         let _: () = conn
-            .expire(&key, 60)
+            .sadd(&key, user_id.to_string())
             .await
             .map_err(AppError::RedisCommandError)?;
+        apply_expiry(&mut conn, &key).await?;
 
         Ok(())
     }
 
     /// Remove the countdown key for a lobby.
     pub async fn clear_countdown(&self, lobby_id: Uuid) -> Result<(), AppError> {
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
+        let mut conn = self.redis.get().await?;
 
         let key = RedisKey::lobby_countdown(lobby_id);
 
@@ -285,18 +279,24 @@ impl LobbyStateRepository {
     }
 
     /// Subtract from current_amount.
-    pub async fn subtract_current_amount(&self, lobby_id: Uuid, amount: f64) -> Result<(), AppError> {
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
+    pub async fn subtract_current_amount(
+        &self,
+        lobby_id: Uuid,
+        amount: Decimal,
+    ) -> Result<(), AppError> {
+        let mut conn = self.redis.get().await?;
         let key = RedisKey::lobby_state(lobby_id);
 
         // Get current amount
-        let current: Option<String> = conn.hget(&key, "current_amount").await.map_err(AppError::RedisCommandError)?;
-        let current_amount = current.and_then(|s| s.parse::<f64>().ok()).unwrap_or(0.0);
+        let current: Option<String> = conn
+            .hget(&key, "current_amount")
+            .await
+            .map_err(AppError::RedisCommandError)?;
+        let current_amount = current
+            .and_then(|s| s.parse::<Decimal>().ok())
+            .unwrap_or_default();
 
-        let new_amount = (current_amount - amount).max(0.0);
+        let new_amount = (current_amount - amount).max(Decimal::ZERO);
 
         let now = Utc::now().timestamp();
 
@@ -313,4 +313,27 @@ impl LobbyStateRepository {
 
         Ok(())
     }
+
+    /// Mirror a lobby's durable config (name and amounts) into its runtime hash.
+    ///
+    /// Applied atomically. Returns false when the lobby has no runtime state,
+    /// in which case nothing is written.
+    pub async fn sync_config(&self, lobby: &Lobby) -> Result<bool, AppError> {
+        let mut conn = self.redis.get().await?;
+        let amount = |amount: Option<Decimal>| amount.map(|a| a.to_string()).unwrap_or_default();
+
+        let synced: i64 = redis::Script::new(SYNC_CONFIG_SCRIPT)
+            .key(RedisKey::lobby_state(lobby.id))
+            .arg("name")
+            .arg(&lobby.name)
+            .arg("entry_amount")
+            .arg(amount(lobby.entry_amount))
+            .arg("current_amount")
+            .arg(amount(lobby.current_amount))
+            .invoke_async(&mut *conn)
+            .await
+            .map_err(AppError::RedisCommandError)?;
+
+        Ok(synced == 1)
+    }
 }
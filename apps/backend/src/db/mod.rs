@@ -1,12 +1,33 @@
 // Database repositories and helpers
+pub mod consistency;
+pub mod expiry;
 pub mod game;
+pub mod game_word;
 pub mod hydration;
 pub mod join_request;
 pub mod lobby;
 pub mod lobby_chat;
+pub mod lobby_invite;
+pub mod lobby_presence;
+pub mod lobby_refund;
+pub mod lobby_result;
 pub mod lobby_state;
+pub mod pagination;
 pub mod platform_rating;
 pub mod player_state;
+pub mod prediction;
+pub mod pseudonym;
+pub mod rank_snapshot;
+pub mod report;
 pub mod season;
+pub mod signed_result;
+pub mod skill_rating;
+pub mod spectator;
+pub mod stats;
+pub mod streak;
+pub mod timing;
+pub mod token_info;
+pub mod tx;
 pub mod user;
+pub mod user_badge;
 pub mod user_wars_points;
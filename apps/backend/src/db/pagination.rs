@@ -0,0 +1,163 @@
+// Cursor pagination shared by the repositories
+//
+// A paged read asks for one row more than the page holds: if that extra row
+// comes back there's another page, and it's dropped before returning. The
+// cursor is the sort keys of the page's last row, JSON-encoded and base64'd
+// (URL-safe, unpadded) so clients treat it as opaque. The next read resumes
+// strictly past those keys, so rows inserted meanwhile never shift a page.
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use crate::errors::AppError;
+
+/// Page size when the caller doesn't ask for one
+pub const DEFAULT_PAGE_SIZE: usize = 20;
+
+/// Largest page a caller can ask for
+pub const MAX_PAGE_SIZE: usize = 100;
+
+/// One page of a paged read.
+///
+/// `next_cursor` fetches the following page and is set only while
+/// `has_more` is.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<String>,
+    pub has_more: bool,
+}
+
+impl<T> Page<T> {
+    /// The same page with each item converted by `f`
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            next_cursor: self.next_cursor,
+            has_more: self.has_more,
+        }
+    }
+}
+
+/// Page size plus cursor encoding for a paged read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Paginator {
+    limit: usize,
+}
+
+impl Paginator {
+    /// `limit` items per page, clamped to `1..=MAX_PAGE_SIZE`
+    pub fn new(limit: Option<usize>) -> Self {
+        Self {
+            limit: limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE),
+        }
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Rows to fetch: one past the page, to tell whether another follows
+    pub fn fetch_limit(&self) -> i64 {
+        self.limit as i64 + 1
+    }
+
+    /// Cursor pointing just past a row with sort keys `key`
+    pub fn encode<K: Serialize>(key: &K) -> String {
+        let json = serde_json::to_vec(key).expect("cursor keys serialize");
+        URL_SAFE_NO_PAD.encode(json)
+    }
+
+    /// Sort keys from a client's cursor; None without one
+    pub fn decode<K: DeserializeOwned>(cursor: Option<&str>) -> Result<Option<K>, AppError> {
+        let Some(cursor) = cursor else {
+            return Ok(None);
+        };
+        URL_SAFE_NO_PAD
+            .decode(cursor)
+            .ok()
+            .and_then(|json| serde_json::from_slice(&json).ok())
+            .map(Some)
+            .ok_or_else(|| AppError::InvalidInput("Invalid cursor".to_string()))
+    }
+
+    /// The page in `rows`, fetched with `fetch_limit`; `key` gives a row's sort keys
+    pub fn page<T, K: Serialize>(&self, mut rows: Vec<T>, key: impl Fn(&T) -> K) -> Page<T> {
+        let has_more = rows.len() > self.limit;
+        rows.truncate(self.limit);
+        let next_cursor = match rows.last() {
+            Some(last) if has_more => Some(Self::encode(&key(last))),
+            _ => None,
+        };
+
+        Page {
+            items: rows,
+            next_cursor,
+            has_more,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+    use uuid::Uuid;
+
+    use super::*;
+
+    #[test]
+    fn test_cursor_round_trips_sort_keys() {
+        let created_at =
+            NaiveDateTime::parse_from_str("2026-03-01 12:34:56.789012", "%Y-%m-%d %H:%M:%S%.f")
+                .unwrap();
+        let key = (created_at, Uuid::new_v4());
+
+        let cursor = Paginator::encode(&key);
+        assert!(
+            cursor
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        );
+        assert_eq!(Paginator::decode(Some(&cursor)).unwrap(), Some(key));
+        assert!(Paginator::decode::<Uuid>(None).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_garbled_cursor_is_rejected() {
+        let cursor = Paginator::encode(&("not", "a", "timestamp"));
+        assert!(matches!(
+            Paginator::decode::<(NaiveDateTime, Uuid)>(Some(&cursor)),
+            Err(AppError::InvalidInput(_))
+        ));
+        assert!(Paginator::decode::<Uuid>(Some("%%%")).is_err());
+    }
+
+    #[test]
+    fn test_extra_row_means_more_pages() {
+        let paginator = Paginator::new(Some(3));
+        assert_eq!(paginator.fetch_limit(), 4);
+
+        let page = paginator.page(vec![10, 20, 30, 40], |n| *n);
+        assert_eq!(page.items, vec![10, 20, 30]);
+        assert!(page.has_more);
+        assert_eq!(
+            Paginator::decode::<i32>(page.next_cursor.as_deref()).unwrap(),
+            Some(30)
+        );
+
+        let last = paginator.page(vec![10, 20, 30], |n| *n);
+        assert!(!last.has_more);
+        assert_eq!(last.next_cursor, None);
+
+        let empty = paginator.page(Vec::<i32>::new(), |n| *n);
+        assert!(empty.items.is_empty() && !empty.has_more);
+    }
+
+    #[test]
+    fn test_page_size_is_clamped() {
+        assert_eq!(Paginator::new(None).limit(), DEFAULT_PAGE_SIZE);
+        assert_eq!(Paginator::new(Some(0)).limit(), 1);
+        assert_eq!(Paginator::new(Some(10_000)).limit(), MAX_PAGE_SIZE);
+    }
+}
@@ -1,12 +1,12 @@
 use super::PlatformRatingRepository;
+use crate::db::timing::TimedQuery;
 use crate::errors::AppError;
 use crate::models::PlatformRating;
 
 impl PlatformRatingRepository {
     /// Create or replace a platform rating for the given user.
-    /// Since `platform_ratings.user_id` is unique, this will return an
-    /// error if the row already exists — callers can use `update_rating`
-    /// to change an existing rating.
+    /// `platform_ratings.user_id` is unique, so rating again overwrites the
+    /// user's existing rating instead of adding a second one.
     pub async fn create_rating(
         &self,
         user_id: uuid::Uuid,
@@ -16,16 +16,19 @@ impl PlatformRatingRepository {
         let rec = sqlx::query_as::<_, PlatformRating>(
             r#"INSERT INTO platform_ratings (user_id, rating, comment)
             VALUES ($1, $2, $3)
+            ON CONFLICT (user_id)
+            DO UPDATE SET rating = EXCLUDED.rating, comment = EXCLUDED.comment, updated_at = NOW()
             RETURNING id, user_id, rating, comment, created_at, updated_at"#,
         )
         .bind(user_id)
         .bind(rating)
         .bind(comment)
         .fetch_one(&self.pool)
+        .timed("PlatformRatingRepository::create_rating")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to create platform rating: {}", e)))?;
 
-        tracing::info!("Created platform rating for user {}", rec.user_id);
+        tracing::info!("Saved platform rating for user {}", rec.user_id);
         Ok(rec)
     }
 }
@@ -1,4 +1,5 @@
 use super::PlatformRatingRepository;
+use crate::db::timing::TimedQuery;
 use crate::errors::AppError;
 
 impl PlatformRatingRepository {
@@ -8,6 +9,7 @@ impl PlatformRatingRepository {
         sqlx::query("DELETE FROM platform_ratings WHERE user_id = $1")
             .bind(user_id)
             .execute(&self.pool)
+            .timed("PlatformRatingRepository::delete_by_user")
             .await
             .map_err(|e| {
                 AppError::DatabaseError(format!("Failed to delete platform rating: {}", e))
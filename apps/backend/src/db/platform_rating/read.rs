@@ -1,6 +1,13 @@
 use super::PlatformRatingRepository;
+use crate::db::timing::TimedQuery;
 use crate::errors::AppError;
-use crate::models::PlatformRating;
+use crate::models::{PlatformRating, PlatformRatingFilter, PlatformRatingSummary};
+
+/// WHERE clause shared by `list` and `summary`; binds $1-$4 from the filter
+const FILTER_CLAUSE: &str = "($1::SMALLINT IS NULL OR rating >= $1)
+    AND ($2::SMALLINT IS NULL OR rating <= $2)
+    AND ($3::TIMESTAMP IS NULL OR created_at >= $3)
+    AND ($4::TIMESTAMP IS NULL OR created_at <= $4)";
 
 impl PlatformRatingRepository {
     /// Get a platform rating by user id. Returns `Ok(None)` if not found.
@@ -13,23 +20,65 @@ impl PlatformRatingRepository {
         )
         .bind(user_id)
         .fetch_optional(&self.pool)
+        .timed("PlatformRatingRepository::get_by_user")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to query platform rating: {}", e)))?;
 
         Ok(rec)
     }
 
-    /// List platform ratings. If `rating_filter` is `Some(n)` returns only
-    /// ratings equal to `n` (1-5). If `None` returns all ratings.
-    pub async fn list(&self, rating_filter: Option<i16>) -> Result<Vec<PlatformRating>, AppError> {
-        let recs = sqlx::query_as::<_, PlatformRating>(
-            "SELECT id, user_id, rating, comment, created_at, updated_at FROM platform_ratings WHERE ($1 IS NULL OR rating = $1) ORDER BY created_at DESC",
-        )
-        .bind(rating_filter)
+    /// List platform ratings matching `filter`, newest first, one page at a time.
+    pub async fn list(
+        &self,
+        filter: &PlatformRatingFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<PlatformRating>, AppError> {
+        let recs = sqlx::query_as::<_, PlatformRating>(&format!(
+            "SELECT id, user_id, rating, comment, created_at, updated_at
+            FROM platform_ratings
+            WHERE {}
+            ORDER BY created_at DESC, id
+            LIMIT $5 OFFSET $6",
+            FILTER_CLAUSE
+        ))
+        .bind(filter.min_rating)
+        .bind(filter.max_rating)
+        .bind(filter.since)
+        .bind(filter.until)
+        .bind(limit)
+        .bind(offset)
         .fetch_all(&self.pool)
+        .timed("PlatformRatingRepository::list")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to list platform ratings: {}", e)))?;
 
         Ok(recs)
     }
+
+    /// Count, average and per-score distribution of ratings matching `filter`.
+    pub async fn summary(
+        &self,
+        filter: &PlatformRatingFilter,
+    ) -> Result<PlatformRatingSummary, AppError> {
+        let counts: Vec<(i16, i64)> = sqlx::query_as(&format!(
+            "SELECT rating, COUNT(*)
+            FROM platform_ratings
+            WHERE rating IS NOT NULL AND {}
+            GROUP BY rating",
+            FILTER_CLAUSE
+        ))
+        .bind(filter.min_rating)
+        .bind(filter.max_rating)
+        .bind(filter.since)
+        .bind(filter.until)
+        .fetch_all(&self.pool)
+        .timed("PlatformRatingRepository::summary")
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to summarize platform ratings: {}", e))
+        })?;
+
+        Ok(PlatformRatingSummary::from_counts(&counts))
+    }
 }
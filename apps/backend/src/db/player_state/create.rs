@@ -1,5 +1,6 @@
 // Create operations for PlayerState
 
+use crate::db::expiry::apply_expiry;
 use crate::db::player_state::PlayerStateRepository;
 use crate::errors::AppError;
 use crate::models::{PlayerState, RedisKey};
@@ -14,10 +15,7 @@ impl PlayerStateRepository {
         state: PlayerState,
         app_state: Option<AppState>,
     ) -> Result<(), AppError> {
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
+        let mut conn = self.redis.get().await?;
         let key = RedisKey::lobby_player(state.lobby_id, state.user_id);
         // Convert to hash and store (overwrites if exists)
         let hash = state.to_redis_hash();
@@ -26,6 +24,7 @@ impl PlayerStateRepository {
             .hset_multiple(&key, &hash_pairs)
             .await
             .map_err(AppError::RedisCommandError)?;
+        apply_expiry(&mut conn, &key).await?;
 
         // Broadcast lobby update if AppState provided
         if let Some(app_state) = app_state {
@@ -41,10 +40,7 @@ impl PlayerStateRepository {
         state: PlayerState,
         app_state: Option<AppState>,
     ) -> Result<(), AppError> {
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
+        let mut conn = self.redis.get().await?;
         let key = RedisKey::lobby_player(state.lobby_id, state.user_id);
 
         // Check if state already exists
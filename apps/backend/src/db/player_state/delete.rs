@@ -15,10 +15,7 @@ impl PlayerStateRepository {
         user_id: Uuid,
         app_state: Option<AppState>,
     ) -> Result<(), AppError> {
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
+        let mut conn = self.redis.get().await?;
         let key = RedisKey::lobby_player(lobby_id, user_id);
 
         let deleted: usize = conn.del(&key).await.map_err(AppError::RedisCommandError)?;
@@ -39,10 +36,7 @@ impl PlayerStateRepository {
 
     /// Delete all player states in a lobby; returns number deleted.
     pub async fn cleanup_lobby(&self, lobby_id: Uuid) -> Result<usize, AppError> {
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
+        let mut conn = self.redis.get().await?;
         let pattern = RedisKey::lobby_player(lobby_id, KeyPart::Wildcard);
 
         let keys: Vec<String> = conn
@@ -6,6 +6,8 @@ mod read;
 mod update;
 mod sync;
 
+pub(crate) use read::scan_player_keys;
+
 use crate::state::RedisClient;
 
 /// PlayerState repository (wraps the Redis client).
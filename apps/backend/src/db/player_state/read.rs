@@ -4,17 +4,18 @@ use crate::db::player_state::PlayerStateRepository;
 use crate::errors::AppError;
 use crate::models::PlayerState;
 use crate::models::keys::{KeyPart, RedisKey};
+use crate::redis_client::RedisConnection;
 use redis::AsyncCommands;
 use std::collections::HashMap;
 use uuid::Uuid;
 
+/// Keys examined per SCAN call
+const SCAN_COUNT: usize = 500;
+
 impl PlayerStateRepository {
     /// Get a player's state by lobby and user ID.
     pub async fn get_state(&self, lobby_id: Uuid, user_id: Uuid) -> Result<PlayerState, AppError> {
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
+        let mut conn = self.redis.get().await?;
         let key = RedisKey::lobby_player(lobby_id, user_id);
 
         let map: HashMap<String, String> = conn
@@ -34,60 +35,48 @@ impl PlayerStateRepository {
 
     /// Check if a player's state exists in Redis.
     pub async fn exists(&self, lobby_id: Uuid, user_id: Uuid) -> Result<bool, AppError> {
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
+        let mut conn = self.redis.get().await?;
         let key = RedisKey::lobby_player(lobby_id, user_id);
 
         conn.exists(&key).await.map_err(AppError::RedisCommandError)
     }
 
-    /// Get all player states in a lobby.
-    pub async fn get_all_in_lobby(&self, lobby_id: Uuid) -> Result<Vec<PlayerState>, AppError> {
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
-        let pattern = RedisKey::lobby_player(lobby_id, KeyPart::Wildcard);
+    /// All player states in a lobby, in join order.
+    ///
+    /// Keys are found with an incremental SCAN and every hash is read in one
+    /// pipeline, so a roster costs the SCAN pages plus a single round trip
+    /// however many players there are.
+    pub async fn list_players(&self, lobby_id: Uuid) -> Result<Vec<PlayerState>, AppError> {
+        let mut conn = self.redis.get().await?;
+        let keys = scan_player_keys(&mut conn, lobby_id).await?;
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
 
-        let keys: Vec<String> = conn
-            .keys(pattern)
+        let mut pipe = redis::pipe();
+        for key in &keys {
+            pipe.hgetall(key);
+        }
+        let maps: Vec<HashMap<String, String>> = pipe
+            .query_async(&mut *conn)
             .await
             .map_err(AppError::RedisCommandError)?;
 
-        let mut states = Vec::new();
-
-        for key in keys {
-            let map: HashMap<String, String> = conn
-                .hgetall(&key)
-                .await
-                .map_err(AppError::RedisCommandError)?;
-
-            if !map.is_empty() {
-                if let Ok(state) = PlayerState::from_redis_hash(&map) {
-                    states.push(state);
-                }
-            }
-        }
+        // Skip keys removed mid-scan and hashes that no longer parse
+        let mut states: Vec<PlayerState> = maps
+            .iter()
+            .filter(|map| !map.is_empty())
+            .filter_map(|map| PlayerState::from_redis_hash(map).ok())
+            .collect();
+        states.sort_by_key(|p| (p.joined_at, p.user_id));
 
         Ok(states)
     }
 
     /// Count players in a lobby.
     pub async fn count_players(&self, lobby_id: Uuid) -> Result<usize, AppError> {
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
-        let pattern = RedisKey::lobby_player(lobby_id, KeyPart::Wildcard);
-
-        let keys: Vec<String> = conn
-            .keys(pattern)
-            .await
-            .map_err(AppError::RedisCommandError)?;
-
-        Ok(keys.len())
+        let mut conn = self.redis.get().await?;
+        Ok(scan_player_keys(&mut conn, lobby_id).await?.len())
     }
 
     /// Check whether a given user is the creator in this lobby (reads `is_creator` flag).
@@ -101,14 +90,14 @@ impl PlayerStateRepository {
 
     /// Get players with prizes (winners) in a lobby.
     pub async fn get_winners(&self, lobby_id: Uuid) -> Result<Vec<PlayerState>, AppError> {
-        let all_players = self.get_all_in_lobby(lobby_id).await?;
+        let all_players = self.list_players(lobby_id).await?;
 
         Ok(all_players.into_iter().filter(|p| p.has_prize()).collect())
     }
 
     /// Get players sorted by rank for a lobby.
     pub async fn get_ranked_players(&self, lobby_id: Uuid) -> Result<Vec<PlayerState>, AppError> {
-        let mut all_players = self.get_all_in_lobby(lobby_id).await?;
+        let mut all_players = self.list_players(lobby_id).await?;
 
         // Sort by rank (None values go to the end)
         all_players.sort_by(|a, b| match (a.rank, b.rank) {
@@ -123,10 +112,7 @@ impl PlayerStateRepository {
 
     /// Get player IDs in a lobby (lightweight).
     pub async fn get_player_ids(&self, lobby_id: Uuid) -> Result<Vec<Uuid>, AppError> {
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
+        let mut conn = self.redis.get().await?;
         let pattern = RedisKey::lobby_player(lobby_id, KeyPart::Wildcard);
 
         let keys: Vec<String> = conn
@@ -148,4 +134,54 @@ impl PlayerStateRepository {
 
         Ok(player_ids)
     }
+
+    /// Get IDs of every lobby the user has player state in.
+    pub async fn get_lobby_ids_for_user(&self, user_id: Uuid) -> Result<Vec<Uuid>, AppError> {
+        let mut conn = self.redis.get().await?;
+        let pattern = RedisKey::lobby_player(KeyPart::Wildcard, user_id);
+
+        let keys: Vec<String> = conn
+            .keys(&pattern)
+            .await
+            .map_err(AppError::RedisCommandError)?;
+
+        // Extract lobby_id from key: lobbies:{lobby_id}:players:{user_id}
+        Ok(keys
+            .iter()
+            .filter_map(|key| key.split(':').nth(1))
+            .filter_map(|id| Uuid::parse_str(id).ok())
+            .collect())
+    }
+}
+
+/// Every player key in a lobby, via SCAN so large keyspaces aren't blocked.
+pub(crate) async fn scan_player_keys(
+    conn: &mut RedisConnection<'_>,
+    lobby_id: Uuid,
+) -> Result<Vec<String>, AppError> {
+    let pattern = RedisKey::lobby_player(lobby_id, KeyPart::Wildcard);
+    let mut keys = Vec::new();
+    let mut cursor: u64 = 0;
+
+    loop {
+        let (next, batch): (u64, Vec<String>) = redis::cmd("SCAN")
+            .cursor_arg(cursor)
+            .arg("MATCH")
+            .arg(&pattern)
+            .arg("COUNT")
+            .arg(SCAN_COUNT)
+            .query_async(&mut **conn)
+            .await
+            .map_err(AppError::RedisCommandError)?;
+        keys.extend(batch);
+        if next == 0 {
+            break;
+        }
+        cursor = next;
+    }
+
+    // SCAN may return a key more than once
+    keys.sort_unstable();
+    keys.dedup();
+    Ok(keys)
 }
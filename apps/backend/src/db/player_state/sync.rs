@@ -22,10 +22,7 @@ impl PlayerStateRepository {
         display_name: Option<&str>,
         trust_rating: Option<f64>,
     ) -> Result<usize, AppError> {
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
+        let mut conn = self.redis.get().await?;
 
         // Pattern to match all lobbies where this user is a player
         // lobbies:*:players:{user_id}
@@ -85,4 +82,49 @@ impl PlayerStateRepository {
 
         Ok(updated_count)
     }
+
+    /// Replace a deleted user's identity in every lobby they have state in.
+    ///
+    /// Wallet and display name are overwritten with placeholders and the
+    /// username is dropped; rank, prize and points are kept so past standings
+    /// still resolve. Returns the number of lobbies updated.
+    pub async fn anonymize_user_across_lobbies(
+        &self,
+        user_id: Uuid,
+        placeholder_wallet: &str,
+        placeholder_name: &str,
+    ) -> Result<usize, AppError> {
+        let mut conn = self.redis.get().await?;
+
+        let pattern = RedisKey::lobby_player(KeyPart::Wildcard, user_id);
+        let keys: Vec<String> = conn
+            .keys(pattern)
+            .await
+            .map_err(AppError::RedisCommandError)?;
+
+        if keys.is_empty() {
+            return Ok(0);
+        }
+
+        let updates = [
+            ("wallet_address", placeholder_wallet),
+            ("display_name", placeholder_name),
+        ];
+
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        for key in &keys {
+            pipe.hset_multiple(key, &updates).ignore();
+            pipe.hdel(key, "username").ignore();
+        }
+
+        let _: () = pipe
+            .query_async(&mut *conn)
+            .await
+            .map_err(AppError::RedisCommandError)?;
+
+        tracing::info!("Anonymized user {} across {} lobbies", user_id, keys.len());
+
+        Ok(keys.len())
+    }
 }
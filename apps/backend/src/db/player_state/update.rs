@@ -1,11 +1,13 @@
 // Update operations for PlayerState (Redis)
 
+use crate::db::expiry::{hold_for_claim, release_claim};
 use crate::db::player_state::PlayerStateRepository;
 use crate::errors::AppError;
 use crate::models::RedisKey;
 use crate::models::player_state::{ClaimState, PlayerStatus};
 use chrono::Utc;
 use redis::AsyncCommands;
+use rust_decimal::Decimal;
 use uuid::Uuid;
 
 impl PlayerStateRepository {
@@ -16,10 +18,7 @@ impl PlayerStateRepository {
         user_id: Uuid,
         status: PlayerStatus,
     ) -> Result<(), AppError> {
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
+        let mut conn = self.redis.get().await?;
         let key = RedisKey::lobby_player(lobby_id, user_id);
 
         // Check if exists
@@ -53,19 +52,17 @@ impl PlayerStateRepository {
         Ok(())
     }
 
-    /// Set player rank, prize, and wars_point (for game results).
+    /// Set player rank, prize, and wars_point (for game results). A prize holds
+    /// the lobby's keys until it is claimed.
     pub async fn set_result(
         &self,
         lobby_id: Uuid,
         user_id: Uuid,
         rank: usize,
-        prize: Option<f64>,
+        prize: Option<Decimal>,
         wars_point: f64,
     ) -> Result<(), AppError> {
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
+        let mut conn = self.redis.get().await?;
         let key = RedisKey::lobby_player(lobby_id, user_id);
 
         let now = Utc::now().timestamp();
@@ -90,6 +87,29 @@ impl PlayerStateRepository {
             .hset_multiple(&key, &fields_ref)
             .await
             .map_err(AppError::RedisCommandError)?;
+        if prize.is_some() {
+            hold_for_claim(&mut conn, lobby_id, user_id).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Record the transaction that paid a player's entry.
+    pub async fn set_tx_id(
+        &self,
+        lobby_id: Uuid,
+        user_id: Uuid,
+        tx_id: &str,
+    ) -> Result<(), AppError> {
+        let mut conn = self.redis.get().await?;
+        let key = RedisKey::lobby_player(lobby_id, user_id);
+
+        let now = Utc::now().timestamp();
+
+        let _: () = conn
+            .hset_multiple(&key, &[("tx_id", tx_id), ("updated_at", &now.to_string())])
+            .await
+            .map_err(AppError::RedisCommandError)?;
 
         Ok(())
     }
@@ -101,10 +121,7 @@ impl PlayerStateRepository {
         user_id: Uuid,
         rank: usize,
     ) -> Result<(), AppError> {
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
+        let mut conn = self.redis.get().await?;
         let key = RedisKey::lobby_player(lobby_id, user_id);
 
         let now = Utc::now().timestamp();
@@ -128,12 +145,9 @@ impl PlayerStateRepository {
         &self,
         lobby_id: Uuid,
         user_id: Uuid,
-        prize: f64,
+        prize: Decimal,
     ) -> Result<(), AppError> {
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
+        let mut conn = self.redis.get().await?;
         let key = RedisKey::lobby_player(lobby_id, user_id);
 
         let now = Utc::now().timestamp();
@@ -149,16 +163,14 @@ impl PlayerStateRepository {
             )
             .await
             .map_err(AppError::RedisCommandError)?;
+        hold_for_claim(&mut conn, lobby_id, user_id).await?;
 
         Ok(())
     }
 
-    /// Mark a player's prize as claimed.
+    /// Mark a player's prize as claimed, releasing the lobby once every prize is.
     pub async fn mark_claimed(&self, lobby_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
+        let mut conn = self.redis.get().await?;
         let key = RedisKey::lobby_player(lobby_id, user_id);
 
         let now = Utc::now().timestamp();
@@ -170,6 +182,7 @@ impl PlayerStateRepository {
             )
             .await
             .map_err(AppError::RedisCommandError)?;
+        release_claim(&mut conn, lobby_id, user_id).await?;
 
         Ok(())
     }
@@ -181,14 +194,13 @@ impl PlayerStateRepository {
         user_id: Uuid,
         claim_state: ClaimState,
     ) -> Result<(), AppError> {
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
+        let mut conn = self.redis.get().await?;
         let key = RedisKey::lobby_player(lobby_id, user_id);
 
         let now = Utc::now().timestamp();
-        let claim_str = format!("{:?}", claim_state);
+        // Stored as JSON, the form `PlayerState::from_redis` reads back
+        let claim_str = serde_json::to_string(&claim_state)
+            .map_err(|e| AppError::Serialization(e.to_string()))?;
 
         let _: () = conn
             .hset_multiple(
@@ -200,16 +212,34 @@ impl PlayerStateRepository {
             )
             .await
             .map_err(AppError::RedisCommandError)?;
+        if claim_state.is_claimed() {
+            release_claim(&mut conn, lobby_id, user_id).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Set or clear a player's creator flag.
+    pub async fn set_creator(
+        &self,
+        lobby_id: Uuid,
+        user_id: Uuid,
+        is_creator: bool,
+    ) -> Result<(), AppError> {
+        let mut conn = self.redis.get().await?;
+        let key = RedisKey::lobby_player(lobby_id, user_id);
+
+        let _: () = conn
+            .hset(&key, "is_creator", is_creator.to_string())
+            .await
+            .map_err(AppError::RedisCommandError)?;
 
         Ok(())
     }
 
     /// Update a player's last ping timestamp.
     pub async fn update_ping(&self, lobby_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
+        let mut conn = self.redis.get().await?;
         let key = RedisKey::lobby_player(lobby_id, user_id);
 
         let now_ms = Utc::now().timestamp_millis() as u64;
@@ -231,10 +261,7 @@ impl PlayerStateRepository {
 
     /// Touch the player state (refresh updated_at timestamp).
     pub async fn touch(&self, lobby_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
-        let mut conn =
-            self.redis.get().await.map_err(|e| {
-                AppError::RedisError(format!("Failed to get Redis connection: {}", e))
-            })?;
+        let mut conn = self.redis.get().await?;
         let key = RedisKey::lobby_player(lobby_id, user_id);
 
         let now = Utc::now().timestamp();
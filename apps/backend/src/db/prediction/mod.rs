@@ -0,0 +1,23 @@
+// Prediction repository (Redis): spectators' predicted winners and points won
+
+mod read;
+mod update;
+
+use crate::state::RedisClient;
+
+/// Repository for winner predictions.
+///
+/// Each lobby has a hash of predictor to predicted winner, writable only
+/// while the lobby's runtime status is waiting or starting. Points won are
+/// kept in one sorted set across all lobbies.
+#[derive(Clone)]
+pub struct PredictionRepository {
+    pub(crate) redis: RedisClient,
+}
+
+impl PredictionRepository {
+    /// Create a new `PredictionRepository`.
+    pub fn new(redis: RedisClient) -> Self {
+        Self { redis }
+    }
+}
@@ -0,0 +1,34 @@
+use std::collections::HashMap;
+
+use redis::AsyncCommands;
+use uuid::Uuid;
+
+use crate::{db::prediction::PredictionRepository, errors::AppError, models::keys::RedisKey};
+
+impl PredictionRepository {
+    /// Every prediction in the lobby, predictor to predicted winner.
+    pub async fn get_predictions(&self, lobby_id: Uuid) -> Result<HashMap<Uuid, Uuid>, AppError> {
+        let mut conn = self.redis.get().await?;
+        let raw: HashMap<String, String> = conn
+            .hgetall(RedisKey::lobby_predictions(lobby_id))
+            .await
+            .map_err(AppError::RedisCommandError)?;
+
+        Ok(raw
+            .into_iter()
+            .filter_map(|(user, winner)| {
+                Some((Uuid::parse_str(&user).ok()?, Uuid::parse_str(&winner).ok()?))
+            })
+            .collect())
+    }
+
+    /// Prediction points the user has won so far.
+    pub async fn get_points(&self, user_id: Uuid) -> Result<u64, AppError> {
+        let mut conn = self.redis.get().await?;
+        let points: Option<f64> = conn
+            .zscore(RedisKey::prediction_points(), user_id.to_string())
+            .await
+            .map_err(AppError::RedisCommandError)?;
+        Ok(points.unwrap_or(0.0) as u64)
+    }
+}
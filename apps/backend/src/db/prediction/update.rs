@@ -0,0 +1,87 @@
+use redis::AsyncCommands;
+use uuid::Uuid;
+
+use crate::{
+    db::prediction::PredictionRepository,
+    errors::AppError,
+    models::keys::{ExpiryPolicy, RedisKey},
+};
+
+/// Store a prediction unless the lobby has started (or never existed), or
+/// the predictor is one of its players
+const RECORD_SCRIPT: &str = r#"
+local status = redis.call('HGET', KEYS[1], 'status')
+if status ~= 'Waiting' and status ~= 'Starting' then
+    return 0
+end
+if redis.call('EXISTS', KEYS[3]) == 1 then
+    return -1
+end
+redis.call('HSET', KEYS[2], ARGV[1], ARGV[2])
+redis.call('EXPIRE', KEYS[2], ARGV[3])
+return 1
+"#;
+
+impl PredictionRepository {
+    /// Record `user_id`'s prediction that `winner_id` wins the lobby's game,
+    /// replacing any earlier one.
+    ///
+    /// Returns false once the game has started: the status check and the
+    /// write happen in one script, so a prediction can't slip in after the
+    /// lobby goes in progress. Players of the lobby are refused with
+    /// `AppError::Forbidden`, checked in the same script.
+    pub async fn record(
+        &self,
+        lobby_id: Uuid,
+        user_id: Uuid,
+        winner_id: Uuid,
+    ) -> Result<bool, AppError> {
+        let mut conn = self.redis.get().await?;
+        let recorded: i64 = redis::Script::new(RECORD_SCRIPT)
+            .key(RedisKey::lobby_state(lobby_id))
+            .key(RedisKey::lobby_predictions(lobby_id))
+            .key(RedisKey::lobby_player(lobby_id, user_id))
+            .arg(user_id.to_string())
+            .arg(winner_id.to_string())
+            .arg(ExpiryPolicy::LOBBY_TTL_SECS)
+            .invoke_async(&mut *conn)
+            .await
+            .map_err(AppError::RedisCommandError)?;
+        if recorded == -1 {
+            return Err(AppError::Forbidden(
+                "players can't predict their own lobby".into(),
+            ));
+        }
+        Ok(recorded == 1)
+    }
+
+    /// Drop `user_id`'s prediction in the lobby, e.g. because they joined it.
+    pub async fn void(&self, lobby_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        let mut conn = self.redis.get().await?;
+        let _: () = conn
+            .hdel(RedisKey::lobby_predictions(lobby_id), user_id.to_string())
+            .await
+            .map_err(AppError::RedisCommandError)?;
+        Ok(())
+    }
+
+    /// Add `points` to each of `user_ids`' prediction points.
+    pub async fn award_points(&self, user_ids: &[Uuid], points: u32) -> Result<(), AppError> {
+        if user_ids.is_empty() || points == 0 {
+            return Ok(());
+        }
+
+        let key = RedisKey::prediction_points();
+        let mut pipe = redis::pipe();
+        for user_id in user_ids {
+            pipe.zincr(&key, user_id.to_string(), points).ignore();
+        }
+
+        let mut conn = self.redis.get().await?;
+        let _: () = pipe
+            .query_async(&mut *conn)
+            .await
+            .map_err(AppError::RedisCommandError)?;
+        Ok(())
+    }
+}
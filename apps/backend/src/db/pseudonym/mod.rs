@@ -0,0 +1,23 @@
+// Pseudonym repository (Redis): the pseudonyms of anonymized lobbies' players
+
+mod read;
+mod update;
+
+use crate::state::RedisClient;
+
+/// Repository for anonymized lobbies' pseudonym tables.
+///
+/// Each lobby has a hash of user id to the player's pseudonym and the
+/// identities it stands for, so a restart doesn't rename anyone. The hash is
+/// dropped when the lobby's players are revealed.
+#[derive(Clone)]
+pub struct PseudonymRepository {
+    pub(crate) redis: RedisClient,
+}
+
+impl PseudonymRepository {
+    /// Create a new `PseudonymRepository`.
+    pub fn new(redis: RedisClient) -> Self {
+        Self { redis }
+    }
+}
@@ -0,0 +1,37 @@
+use std::collections::HashMap;
+
+use redis::AsyncCommands;
+use uuid::Uuid;
+
+use crate::{
+    db::pseudonym::PseudonymRepository,
+    errors::AppError,
+    models::keys::RedisKey,
+    ws::core::pseudonyms::{Alias, Pseudonyms},
+};
+
+impl PseudonymRepository {
+    /// The lobby's stored table, or None if nobody in it has been named.
+    pub async fn get_table(&self, lobby_id: Uuid) -> Result<Option<Pseudonyms>, AppError> {
+        let mut conn = self.redis.get().await?;
+        let raw: HashMap<String, String> = conn
+            .hgetall(RedisKey::lobby_pseudonyms(lobby_id))
+            .await
+            .map_err(AppError::RedisCommandError)?;
+        if raw.is_empty() {
+            return Ok(None);
+        }
+
+        let mut table = Pseudonyms::default();
+        for (user_id, alias) in raw {
+            let user_id = Uuid::parse_str(&user_id).map_err(|e| {
+                AppError::Deserialization(format!("Invalid pseudonym user id: {}", e))
+            })?;
+            let alias: Alias = serde_json::from_str(&alias).map_err(|e| {
+                AppError::Deserialization(format!("Invalid pseudonym entry: {}", e))
+            })?;
+            table.insert(user_id, alias);
+        }
+        Ok(Some(table))
+    }
+}
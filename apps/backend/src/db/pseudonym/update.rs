@@ -0,0 +1,54 @@
+use redis::AsyncCommands;
+use uuid::Uuid;
+
+use crate::{
+    db::{expiry::apply_expiry_pipe, pseudonym::PseudonymRepository},
+    errors::AppError,
+    models::keys::RedisKey,
+    ws::core::pseudonyms::Pseudonyms,
+};
+
+impl PseudonymRepository {
+    /// Store the pseudonyms of `user_ids`, as named in `table`.
+    pub async fn save(
+        &self,
+        lobby_id: Uuid,
+        table: &Pseudonyms,
+        user_ids: &[Uuid],
+    ) -> Result<(), AppError> {
+        let mut fields = Vec::new();
+        for user_id in user_ids {
+            if let Some(alias) = table.alias_of(*user_id) {
+                let raw = serde_json::to_string(alias).map_err(|e| {
+                    AppError::Serialization(format!("Failed to serialize pseudonym: {}", e))
+                })?;
+                fields.push((user_id.to_string(), raw));
+            }
+        }
+        if fields.is_empty() {
+            return Ok(());
+        }
+
+        let key = RedisKey::lobby_pseudonyms(lobby_id);
+        let mut pipe = redis::pipe();
+        pipe.atomic().hset_multiple(&key, &fields).ignore();
+        apply_expiry_pipe(&mut pipe, &key);
+
+        let mut conn = self.redis.get().await?;
+        let _: () = pipe
+            .query_async(&mut *conn)
+            .await
+            .map_err(AppError::RedisCommandError)?;
+        Ok(())
+    }
+
+    /// Forget the lobby's pseudonyms, once its players are revealed.
+    pub async fn delete(&self, lobby_id: Uuid) -> Result<(), AppError> {
+        let mut conn = self.redis.get().await?;
+        let _: () = conn
+            .del(RedisKey::lobby_pseudonyms(lobby_id))
+            .await
+            .map_err(AppError::RedisCommandError)?;
+        Ok(())
+    }
+}
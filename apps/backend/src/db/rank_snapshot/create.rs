@@ -0,0 +1,29 @@
+use crate::db::timing::TimedQuery;
+use crate::errors::AppError;
+
+use super::RankSnapshotRepository;
+
+impl RankSnapshotRepository {
+    /// Record every ranked user's current standing in a season.
+    ///
+    /// Only users with wars points in the season are ranked, so users who
+    /// join partway through have no snapshots before then. Returns the
+    /// number of users recorded.
+    pub async fn record(&self, season_id: i32) -> Result<u64, AppError> {
+        let result = sqlx::query(
+            "INSERT INTO rank_snapshots (season_id, user_id, rank, points, taken_at)
+            SELECT season_id, user_id,
+                   RANK() OVER (ORDER BY COALESCE(points, 0) DESC)::INT,
+                   COALESCE(points, 0), NOW()
+            FROM user_wars_points
+            WHERE season_id = $1",
+        )
+        .bind(season_id)
+        .execute(&self.pool)
+        .timed("RankSnapshotRepository::record")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to record rank snapshot: {}", e)))?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,17 @@
+use sqlx::PgPool;
+
+mod create;
+mod read;
+
+/// Repository for periodic snapshots of season leaderboard ranks.
+#[derive(Clone)]
+pub struct RankSnapshotRepository {
+    pub(crate) pool: PgPool,
+}
+
+impl RankSnapshotRepository {
+    /// Create a new `RankSnapshotRepository` with the given pool.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
@@ -0,0 +1,27 @@
+use uuid::Uuid;
+
+use crate::{db::timing::TimedQuery, errors::AppError, models::RankSnapshot};
+
+use super::RankSnapshotRepository;
+
+impl RankSnapshotRepository {
+    /// A user's snapshots for a season, oldest first.
+    pub async fn history(
+        &self,
+        season_id: i32,
+        user_id: Uuid,
+    ) -> Result<Vec<RankSnapshot>, AppError> {
+        sqlx::query_as::<_, RankSnapshot>(
+            "SELECT rank, points, taken_at
+            FROM rank_snapshots
+            WHERE season_id = $1 AND user_id = $2
+            ORDER BY taken_at, id",
+        )
+        .bind(season_id)
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .timed("RankSnapshotRepository::history")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch rank history: {}", e)))
+    }
+}
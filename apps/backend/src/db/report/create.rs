@@ -0,0 +1,117 @@
+use uuid::Uuid;
+
+use crate::{
+    db::{timing::TimedQuery, user::UserRepository},
+    errors::AppError,
+    models::{
+        Report, ReportCategory,
+        report::{MAX_REPORTS_PER_HOUR, PROVISIONAL_TRUST_PENALTY},
+    },
+};
+
+use super::ReportRepository;
+
+impl ReportRepository {
+    /// File a report against another player and take the provisional trust
+    /// penalty from them in the same transaction. Returns the report and the
+    /// reported player's new trust rating.
+    ///
+    /// Rejects a second pending report from the same reporter about the same
+    /// player and lobby with `AppError::Conflict`, and more than
+    /// `MAX_REPORTS_PER_HOUR` reports per reporter with `AppError::RateLimited`.
+    pub async fn create_report(
+        &self,
+        reporter_id: Uuid,
+        reported_user_id: Uuid,
+        lobby_id: Option<Uuid>,
+        category: ReportCategory,
+        description: Option<&str>,
+    ) -> Result<(Report, f64), AppError> {
+        let mut transaction =
+            self.pool.begin().await.map_err(|e| {
+                AppError::DatabaseError(format!("Failed to begin transaction: {}", e))
+            })?;
+
+        // Serialize reports from one reporter so the checks below can't race
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext($1::TEXT))")
+            .bind(reporter_id)
+            .execute(&mut *transaction)
+            .timed("ReportRepository::create_report")
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to lock reporter: {}", e)))?;
+
+        let duplicate = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(
+                SELECT 1 FROM reports
+                WHERE reporter_id = $1 AND reported_user_id = $2
+                AND lobby_id IS NOT DISTINCT FROM $3
+                AND status IN ('open', 'assigned')
+            )",
+        )
+        .bind(reporter_id)
+        .bind(reported_user_id)
+        .bind(lobby_id)
+        .fetch_one(&mut *transaction)
+        .timed("ReportRepository::create_report")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to check duplicate report: {}", e)))?;
+
+        if duplicate {
+            return Err(AppError::Conflict(
+                "You have already reported this player".into(),
+            ));
+        }
+
+        let recent = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM reports
+            WHERE reporter_id = $1 AND created_at > NOW() - INTERVAL '1 hour'",
+        )
+        .bind(reporter_id)
+        .fetch_one(&mut *transaction)
+        .timed("ReportRepository::create_report")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to count reports: {}", e)))?;
+
+        if recent >= MAX_REPORTS_PER_HOUR {
+            return Err(AppError::RateLimited(
+                "Too many reports, try again later".into(),
+            ));
+        }
+
+        let report = sqlx::query_as::<_, Report>(
+            "INSERT INTO reports (reporter_id, reported_user_id, lobby_id, category, description)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *",
+        )
+        .bind(reporter_id)
+        .bind(reported_user_id)
+        .bind(lobby_id)
+        .bind(category)
+        .bind(description)
+        .fetch_one(&mut *transaction)
+        .timed("ReportRepository::create_report")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to create report: {}", e)))?;
+
+        let trust_rating = UserRepository::adjust_trust_rating(
+            &mut transaction,
+            reported_user_id,
+            -PROVISIONAL_TRUST_PENALTY,
+        )
+        .await?;
+
+        transaction
+            .commit()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to commit report: {}", e)))?;
+
+        tracing::info!(
+            "User {} reported {} ({:?})",
+            reporter_id,
+            reported_user_id,
+            category
+        );
+
+        Ok((report, trust_rating))
+    }
+}
@@ -0,0 +1,18 @@
+use sqlx::PgPool;
+
+mod create;
+mod read;
+mod update;
+
+/// Repository for player reports and the moderation queue.
+#[derive(Clone)]
+pub struct ReportRepository {
+    pub(crate) pool: PgPool,
+}
+
+impl ReportRepository {
+    /// Create a new `ReportRepository` with the given pool.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
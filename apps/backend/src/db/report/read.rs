@@ -0,0 +1,56 @@
+use uuid::Uuid;
+
+use crate::{
+    db::timing::TimedQuery,
+    errors::AppError,
+    models::{Report, ReportStatus},
+};
+
+use super::ReportRepository;
+
+impl ReportRepository {
+    /// Find a report by id.
+    pub async fn find_by_id(&self, report_id: Uuid) -> Result<Report, AppError> {
+        sqlx::query_as::<_, Report>("SELECT * FROM reports WHERE id = $1")
+            .bind(report_id)
+            .fetch_optional(&self.pool)
+            .timed("ReportRepository::find_by_id")
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to fetch report: {}", e)))?
+            .ok_or_else(|| AppError::NotFound("Report not found".into()))
+    }
+
+    /// Moderation queue: reports oldest first, optionally filtered by status.
+    pub async fn list(
+        &self,
+        status: Option<ReportStatus>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Report>, AppError> {
+        sqlx::query_as::<_, Report>(
+            "SELECT * FROM reports
+            WHERE ($1::report_status IS NULL OR status = $1)
+            ORDER BY created_at ASC, id
+            LIMIT $2 OFFSET $3",
+        )
+        .bind(status)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .timed("ReportRepository::list")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to list reports: {}", e)))
+    }
+
+    /// Reports filed by a user, newest first.
+    pub async fn find_by_reporter(&self, reporter_id: Uuid) -> Result<Vec<Report>, AppError> {
+        sqlx::query_as::<_, Report>(
+            "SELECT * FROM reports WHERE reporter_id = $1 ORDER BY created_at DESC",
+        )
+        .bind(reporter_id)
+        .fetch_all(&self.pool)
+        .timed("ReportRepository::find_by_reporter")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch reports: {}", e)))
+    }
+}
@@ -0,0 +1,150 @@
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::{
+    db::{timing::TimedQuery, user::UserRepository},
+    errors::AppError,
+    models::{
+        Report, ReportAction, ReportStatus,
+        report::{PROVISIONAL_TRUST_PENALTY, WARN_TRUST_PENALTY},
+    },
+};
+
+use super::ReportRepository;
+
+impl ReportRepository {
+    /// Assign a pending report to a moderator.
+    pub async fn assign(&self, report_id: Uuid, moderator_id: Uuid) -> Result<Report, AppError> {
+        let mut transaction =
+            self.pool.begin().await.map_err(|e| {
+                AppError::DatabaseError(format!("Failed to begin transaction: {}", e))
+            })?;
+
+        Self::lock_for_transition(&mut transaction, report_id, ReportStatus::Assigned).await?;
+
+        let report = sqlx::query_as::<_, Report>(
+            "UPDATE reports
+            SET status = 'assigned', assigned_to = $1, updated_at = NOW()
+            WHERE id = $2
+            RETURNING *",
+        )
+        .bind(moderator_id)
+        .bind(report_id)
+        .fetch_one(&mut *transaction)
+        .timed("ReportRepository::assign")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to assign report: {}", e)))?;
+
+        transaction
+            .commit()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to commit report: {}", e)))?;
+
+        tracing::info!("Report {} assigned to {}", report_id, moderator_id);
+
+        Ok(report)
+    }
+
+    /// Close a pending report with a moderator decision and apply it to the
+    /// reported user in the same transaction.
+    ///
+    /// - `Dismiss`: the provisional trust penalty is returned
+    /// - `Warn`: a further trust penalty
+    /// - `Ban`: the user can no longer sign in
+    ///
+    /// Returns the report and the reported user's new trust rating if it
+    /// changed. Revoking a banned user's sessions is up to the caller.
+    pub async fn resolve(
+        &self,
+        report_id: Uuid,
+        moderator_id: Uuid,
+        action: ReportAction,
+        note: Option<&str>,
+    ) -> Result<(Report, Option<f64>), AppError> {
+        let status = action.resulting_status();
+
+        let mut transaction =
+            self.pool.begin().await.map_err(|e| {
+                AppError::DatabaseError(format!("Failed to begin transaction: {}", e))
+            })?;
+
+        Self::lock_for_transition(&mut transaction, report_id, status).await?;
+
+        let report = sqlx::query_as::<_, Report>(
+            "UPDATE reports
+            SET status = $1, action = $2, resolution_note = $3,
+                assigned_to = COALESCE(assigned_to, $4),
+                resolved_at = NOW(), updated_at = NOW()
+            WHERE id = $5
+            RETURNING *",
+        )
+        .bind(status)
+        .bind(action)
+        .bind(note)
+        .bind(moderator_id)
+        .bind(report_id)
+        .fetch_one(&mut *transaction)
+        .timed("ReportRepository::resolve")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to resolve report: {}", e)))?;
+
+        let reported = report.reported_user_id;
+        let trust_rating = match action {
+            ReportAction::Dismiss => Some(
+                UserRepository::adjust_trust_rating(
+                    &mut transaction,
+                    reported,
+                    PROVISIONAL_TRUST_PENALTY,
+                )
+                .await?,
+            ),
+            ReportAction::Warn => Some(
+                UserRepository::adjust_trust_rating(
+                    &mut transaction,
+                    reported,
+                    -WARN_TRUST_PENALTY,
+                )
+                .await?,
+            ),
+            ReportAction::Ban => {
+                UserRepository::ban_user(&mut transaction, reported).await?;
+                None
+            }
+        };
+
+        transaction
+            .commit()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to commit report: {}", e)))?;
+
+        tracing::info!("Report {} closed with {:?}", report_id, action);
+
+        Ok((report, trust_rating))
+    }
+
+    /// Lock a report row and check it may move to `next`.
+    async fn lock_for_transition(
+        conn: &mut PgConnection,
+        report_id: Uuid,
+        next: ReportStatus,
+    ) -> Result<(), AppError> {
+        let current = sqlx::query_scalar::<_, ReportStatus>(
+            "SELECT status FROM reports WHERE id = $1 FOR UPDATE",
+        )
+        .bind(report_id)
+        .fetch_optional(&mut *conn)
+        .timed("ReportRepository::lock_for_transition")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch report: {}", e)))?
+        .ok_or_else(|| AppError::NotFound("Report not found".into()))?;
+
+        if !current.can_transition_to(next) {
+            return Err(AppError::Conflict(format!(
+                "Report is already {:?}",
+                current
+            )));
+        }
+
+        Ok(())
+    }
+}
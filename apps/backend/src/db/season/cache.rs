@@ -0,0 +1,79 @@
+use chrono::Utc;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    errors::AppError,
+    models::{RedisKey, Season},
+};
+
+use super::SeasonRepository;
+
+/// Upper bound on how long the current season is cached
+pub const CURRENT_SEASON_CACHE_TTL_SECS: i64 = 60;
+
+/// `Season` skips its id when deserializing, so the cache stores it alongside.
+#[derive(Serialize, Deserialize)]
+struct CachedSeason {
+    id: i32,
+    season: Season,
+}
+
+impl SeasonRepository {
+    /// The currently active season, served from Redis when cached.
+    ///
+    /// Falls back to Postgres on a miss (or without a cache) and caches the
+    /// result until the TTL or the season's end, whichever is first. Returns
+    /// `AppError::NotFound` when no season is active; that case isn't cached.
+    pub async fn current(&self) -> Result<Season, AppError> {
+        let Some(redis) = &self.redis else {
+            return self.get_current_season().await;
+        };
+
+        let mut conn = redis.get().await?;
+        let key = RedisKey::current_season();
+        let now = Utc::now().naive_utc();
+
+        let cached: Option<String> = conn.get(&key).await.map_err(AppError::RedisCommandError)?;
+        if let Some(raw) = cached
+            && let Ok(CachedSeason { id, mut season }) = serde_json::from_str(&raw)
+            && season.start_date <= now
+            && now <= season.end_date
+        {
+            season.id = id;
+            return Ok(season);
+        }
+
+        let season = self.get_current_season().await?;
+
+        let ttl = (season.end_date - now)
+            .num_seconds()
+            .clamp(1, CURRENT_SEASON_CACHE_TTL_SECS) as u64;
+        let raw = serde_json::to_string(&CachedSeason {
+            id: season.id(),
+            season: season.clone(),
+        })
+        .map_err(|e| AppError::Serialization(format!("Failed to serialize season: {}", e)))?;
+        let _: () = conn
+            .set_ex(&key, raw, ttl)
+            .await
+            .map_err(AppError::RedisCommandError)?;
+
+        Ok(season)
+    }
+
+    /// Drop the cached current season. No-op without a cache.
+    pub async fn invalidate_current(&self) -> Result<(), AppError> {
+        let Some(redis) = &self.redis else {
+            return Ok(());
+        };
+
+        let mut conn = redis.get().await?;
+        let _: () = conn
+            .del(RedisKey::current_season())
+            .await
+            .map_err(AppError::RedisCommandError)?;
+
+        Ok(())
+    }
+}
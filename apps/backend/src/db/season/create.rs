@@ -1,29 +1,85 @@
-use crate::{errors::AppError, models::Season};
+use chrono::NaiveDateTime;
+use sqlx::PgConnection;
+
+use crate::{
+    db::timing::TimedQuery,
+    errors::AppError,
+    models::{
+        Season,
+        seed::{SeedMode, SeedOutcome, SeedSeason},
+    },
+};
 
 use super::SeasonRepository;
 
 impl SeasonRepository {
     /// Create a new season.
+    ///
+    /// Rejects a season overlapping an existing one with `AppError::Conflict`
+    /// unless `allow_overlap` is set (e.g. for special events).
     pub async fn create_season(
         &self,
         name: &str,
         description: Option<&str>,
         start_date: &str,
         end_date: &str,
+        allow_overlap: bool,
+    ) -> Result<Season, AppError> {
+        let mut transaction =
+            self.pool.begin().await.map_err(|e| {
+                AppError::DatabaseError(format!("Failed to begin transaction: {}", e))
+            })?;
+
+        let season = Self::insert_season(
+            &mut transaction,
+            name,
+            description,
+            start_date,
+            end_date,
+            allow_overlap,
+        )
+        .await?;
+
+        transaction
+            .commit()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to commit season: {}", e)))?;
+
+        tracing::info!("Created new season: {} (ID: {})", season.name, season.id());
+        self.invalidate_current().await?;
+
+        Ok(season)
+    }
+
+    /// Create a new season inside the caller's transaction, checked as in
+    /// `create_season`. The caller invalidates the current season cache once
+    /// the transaction commits.
+    pub async fn insert_season(
+        conn: &mut PgConnection,
+        name: &str,
+        description: Option<&str>,
+        start_date: &str,
+        end_date: &str,
+        allow_overlap: bool,
     ) -> Result<Season, AppError> {
         let (start_date, end_date) = Season::parse_date_range(start_date, end_date)?;
 
+        if !allow_overlap {
+            Self::ensure_no_overlap(conn, start_date, end_date, None).await?;
+        }
+
         // Try to insert season
-        let season = sqlx::query_as::<_, Season>(
+        sqlx::query_as::<_, Season>(
             "INSERT INTO seasons (name, description, start_date, end_date)
             VALUES ($1, $2, $3, $4)
-            RETURNING id, name, description, start_date, end_date, created_at",
+            RETURNING id, name, description, start_date, end_date, created_at, updated_at",
         )
         .bind(name)
         .bind(description)
         .bind(start_date)
         .bind(end_date)
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *conn)
+        .timed("SeasonRepository::create_season")
         .await
         .map_err(|e| {
             if let sqlx::Error::Database(db_err) = &e {
@@ -36,9 +92,108 @@ impl SeasonRepository {
             }
             tracing::error!("Database error creating season: {}", e);
             AppError::DatabaseError(format!("Failed to create season: {}", e))
-        })?;
+        })
+    }
 
-        tracing::info!("Created new season: {} (ID: {})", season.name, season.id());
-        Ok(season)
+    /// Insert or update a season from a seed bundle inside the caller's transaction.
+    ///
+    /// Seasons have no unique key, so an existing season is matched by name.
+    pub async fn seed_season(
+        conn: &mut PgConnection,
+        season: &SeedSeason,
+        mode: SeedMode,
+    ) -> Result<SeedOutcome, AppError> {
+        let (start_date, end_date) =
+            Season::parse_date_range(&season.start_date, &season.end_date)?;
+
+        let existing: Option<(i32,)> =
+            sqlx::query_as("SELECT id FROM seasons WHERE name = $1 LIMIT 1 FOR UPDATE")
+                .bind(&season.name)
+                .fetch_optional(&mut *conn)
+                .timed("SeasonRepository::seed_season")
+                .await
+                .map_err(|e| AppError::DatabaseError(format!("Failed to look up season: {}", e)))?;
+
+        match (existing, mode) {
+            (Some(_), SeedMode::Skip) => Ok(SeedOutcome::Skipped),
+            (Some((id,)), SeedMode::Update) => {
+                if !season.allow_overlap {
+                    Self::ensure_no_overlap(conn, start_date, end_date, Some(id)).await?;
+                }
+                sqlx::query(
+                    "UPDATE seasons
+                    SET description = $1, start_date = $2, end_date = $3, updated_at = NOW()
+                    WHERE id = $4",
+                )
+                .bind(&season.description)
+                .bind(start_date)
+                .bind(end_date)
+                .bind(id)
+                .execute(&mut *conn)
+                .timed("SeasonRepository::seed_season")
+                .await
+                .map_err(|e| AppError::DatabaseError(format!("Failed to update season: {}", e)))?;
+                Ok(SeedOutcome::Updated)
+            }
+            (None, _) => {
+                if !season.allow_overlap {
+                    Self::ensure_no_overlap(conn, start_date, end_date, None).await?;
+                }
+                sqlx::query(
+                    "INSERT INTO seasons (name, description, start_date, end_date)
+                    VALUES ($1, $2, $3, $4)",
+                )
+                .bind(&season.name)
+                .bind(&season.description)
+                .bind(start_date)
+                .bind(end_date)
+                .execute(&mut *conn)
+                .timed("SeasonRepository::seed_season")
+                .await
+                .map_err(|e| AppError::DatabaseError(format!("Failed to create season: {}", e)))?;
+                Ok(SeedOutcome::Inserted)
+            }
+        }
+    }
+
+    /// Fail with `AppError::Conflict` if `[start_date, end_date)` overlaps an
+    /// existing season. Seasons that merely touch end-to-start are allowed.
+    ///
+    /// Locks the seasons table for writes until the caller's transaction ends,
+    /// so two concurrent writes can't both pass the check.
+    pub(super) async fn ensure_no_overlap(
+        conn: &mut PgConnection,
+        start_date: NaiveDateTime,
+        end_date: NaiveDateTime,
+        exclude_id: Option<i32>,
+    ) -> Result<(), AppError> {
+        sqlx::query("LOCK TABLE seasons IN SHARE ROW EXCLUSIVE MODE")
+            .execute(&mut *conn)
+            .timed("SeasonRepository::ensure_no_overlap")
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to lock seasons: {}", e)))?;
+
+        let overlapping: Option<String> = sqlx::query_scalar(
+            "SELECT name FROM seasons
+            WHERE start_date < $2 AND end_date > $1
+            AND ($3::INT IS NULL OR id <> $3)
+            ORDER BY start_date
+            LIMIT 1",
+        )
+        .bind(start_date)
+        .bind(end_date)
+        .bind(exclude_id)
+        .fetch_optional(&mut *conn)
+        .timed("SeasonRepository::ensure_no_overlap")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to check season overlap: {}", e)))?;
+
+        match overlapping {
+            Some(name) => Err(AppError::Conflict(format!(
+                "Season overlaps existing season '{}'",
+                name
+            ))),
+            None => Ok(()),
+        }
     }
 }
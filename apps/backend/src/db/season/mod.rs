@@ -1,15 +1,20 @@
 use sqlx::PgPool;
 
+use crate::state::RedisClient;
+
+mod cache;
 mod create;
 mod read;
 mod update;
 
 /// Season repository: create/read/update operations for competitive seasons.
 ///
-/// Modules: `create`, `read`, `update`.
+/// Modules: `cache`, `create`, `read`, `update`.
 #[derive(Clone)]
 pub struct SeasonRepository {
     pub(crate) pool: PgPool,
+    /// Optional Redis client backing the current-season cache
+    pub(crate) redis: Option<RedisClient>,
 }
 
 impl SeasonRepository {
@@ -18,6 +23,14 @@ impl SeasonRepository {
     /// # Arguments
     /// * `pool` - PostgreSQL connection pool
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self { pool, redis: None }
+    }
+
+    /// Enable the Redis current-season cache.
+    ///
+    /// Writes through a cached repository invalidate the cache.
+    pub fn with_cache(mut self, redis: RedisClient) -> Self {
+        self.redis = Some(redis);
+        self
     }
 }
@@ -1,4 +1,4 @@
-use crate::{errors::AppError, models::Season};
+use crate::{db::timing::TimedQuery, errors::AppError, models::Season};
 
 use super::SeasonRepository;
 
@@ -16,6 +16,7 @@ impl SeasonRepository {
         )
         .bind(now)
         .fetch_optional(&self.pool)
+        .timed("SeasonRepository::get_current_season_id")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to fetch current season: {}", e)))?
         .ok_or_else(|| AppError::NotFound("No active season found".into()))?;
@@ -28,7 +29,7 @@ impl SeasonRepository {
         let now = chrono::Utc::now();
 
         let season = sqlx::query_as::<_, Season>(
-            "SELECT id, name, description, start_date, end_date, created_at
+            "SELECT id, name, description, start_date, end_date, created_at, updated_at
             FROM seasons
             WHERE start_date <= $1 AND end_date >= $1
             ORDER BY start_date DESC
@@ -36,6 +37,7 @@ impl SeasonRepository {
         )
         .bind(now)
         .fetch_optional(&self.pool)
+        .timed("SeasonRepository::get_current_season")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to fetch current season: {}", e)))?
         .ok_or_else(|| AppError::NotFound("No active season found".into()))?;
@@ -43,15 +45,30 @@ impl SeasonRepository {
         Ok(season)
     }
 
+    /// The season that ends last, if there are any.
+    pub async fn find_latest(&self) -> Result<Option<Season>, AppError> {
+        sqlx::query_as::<_, Season>(
+            "SELECT id, name, description, start_date, end_date, created_at, updated_at
+            FROM seasons
+            ORDER BY end_date DESC
+            LIMIT 1",
+        )
+        .fetch_optional(&self.pool)
+        .timed("SeasonRepository::find_latest")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch latest season: {}", e)))
+    }
+
     /// Find a `Season` by its ID.
     pub async fn find_by_id(&self, season_id: i32) -> Result<Season, AppError> {
         let season = sqlx::query_as::<_, Season>(
-            "SELECT id, name, description, start_date, end_date, created_at
+            "SELECT id, name, description, start_date, end_date, created_at, updated_at
             FROM seasons
             WHERE id = $1",
         )
         .bind(season_id)
         .fetch_optional(&self.pool)
+        .timed("SeasonRepository::find_by_id")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to fetch season: {}", e)))?
         .ok_or_else(|| AppError::NotFound("Season not found".into()))?;
@@ -62,12 +79,13 @@ impl SeasonRepository {
     /// Find a `Season` by its name.
     pub async fn find_by_name(&self, name: &str) -> Result<Season, AppError> {
         let season = sqlx::query_as::<_, Season>(
-            "SELECT id, name, description, start_date, end_date, created_at
+            "SELECT id, name, description, start_date, end_date, created_at, updated_at
             FROM seasons
             WHERE name = $1",
         )
         .bind(name)
         .fetch_optional(&self.pool)
+        .timed("SeasonRepository::find_by_name")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to fetch season by name: {}", e)))?
         .ok_or_else(|| AppError::NotFound("Season not found".into()))?;
@@ -78,7 +96,7 @@ impl SeasonRepository {
     /// List seasons (most recent first) with `limit` and `offset`.
     pub async fn get_all_seasons(&self, limit: i64, offset: i64) -> Result<Vec<Season>, AppError> {
         let seasons = sqlx::query_as::<_, Season>(
-            "SELECT id, name, description, start_date, end_date, created_at
+            "SELECT id, name, description, start_date, end_date, created_at, updated_at
             FROM seasons
             ORDER BY start_date DESC
             LIMIT $1 OFFSET $2",
@@ -86,6 +104,7 @@ impl SeasonRepository {
         .bind(limit)
         .bind(offset)
         .fetch_all(&self.pool)
+        .timed("SeasonRepository::get_all_seasons")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to fetch seasons: {}", e)))?;
 
@@ -97,7 +116,7 @@ impl SeasonRepository {
         let now = chrono::Utc::now();
 
         let seasons = sqlx::query_as::<_, Season>(
-            "SELECT id, name, description, start_date, end_date, created_at
+            "SELECT id, name, description, start_date, end_date, created_at, updated_at
             FROM seasons
             WHERE end_date < $1
             ORDER BY end_date DESC
@@ -106,6 +125,7 @@ impl SeasonRepository {
         .bind(now)
         .bind(limit)
         .fetch_all(&self.pool)
+        .timed("SeasonRepository::get_past_seasons")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to fetch past seasons: {}", e)))?;
 
@@ -123,7 +143,7 @@ impl SeasonRepository {
         let now = chrono::Utc::now();
 
         let seasons = sqlx::query_as::<_, Season>(
-            "SELECT id, name, description, start_date, end_date, created_at
+            "SELECT id, name, description, start_date, end_date, created_at, updated_at
             FROM seasons
             WHERE start_date > $1
             ORDER BY start_date ASC
@@ -132,6 +152,7 @@ impl SeasonRepository {
         .bind(now)
         .bind(limit)
         .fetch_all(&self.pool)
+        .timed("SeasonRepository::get_upcoming_seasons")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to fetch upcoming seasons: {}", e)))?;
 
@@ -145,6 +166,7 @@ impl SeasonRepository {
     pub async fn count_seasons(&self) -> Result<i64, AppError> {
         let count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM seasons")
             .fetch_one(&self.pool)
+            .timed("SeasonRepository::count_seasons")
             .await
             .map_err(|e| AppError::DatabaseError(format!("Failed to count seasons: {}", e)))?;
 
@@ -163,6 +185,7 @@ impl SeasonRepository {
             sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM seasons WHERE id = $1)")
                 .bind(season_id)
                 .fetch_one(&self.pool)
+                .timed("SeasonRepository::exists")
                 .await
                 .map_err(|e| {
                     AppError::DatabaseError(format!("Failed to check season existence: {}", e))
@@ -1,4 +1,4 @@
-use crate::{errors::AppError, models::Season};
+use crate::{db::timing::TimedQuery, errors::AppError, models::Season};
 use chrono::NaiveDateTime;
 
 use super::SeasonRepository;
@@ -13,6 +13,7 @@ impl SeasonRepository {
         .bind(&name)
         .bind(season_id)
         .fetch_optional(&self.pool)
+        .timed("SeasonRepository::update_name")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to check season name: {}", e)))?;
 
@@ -25,18 +26,20 @@ impl SeasonRepository {
 
         let season = sqlx::query_as::<_, Season>(
             "UPDATE seasons
-            SET name = $1
+            SET name = $1, updated_at = NOW()
             WHERE id = $2
-            RETURNING id, name, description, start_date, end_date, created_at",
+            RETURNING id, name, description, start_date, end_date, created_at, updated_at",
         )
         .bind(&name)
         .bind(season_id)
         .fetch_optional(&self.pool)
+        .timed("SeasonRepository::update_name")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to update season name: {}", e)))?
         .ok_or_else(|| AppError::NotFound("Season not found".into()))?;
 
         tracing::info!("Updated season {} name to '{}'", season_id, name);
+        self.invalidate_current().await?;
 
         Ok(season)
     }
@@ -49,13 +52,14 @@ impl SeasonRepository {
     ) -> Result<Season, AppError> {
         let season = sqlx::query_as::<_, Season>(
             "UPDATE seasons
-            SET description = $1
+            SET description = $1, updated_at = NOW()
             WHERE id = $2
-            RETURNING id, name, description, start_date, end_date, created_at",
+            RETURNING id, name, description, start_date, end_date, created_at, updated_at",
         )
         .bind(&description)
         .bind(season_id)
         .fetch_optional(&self.pool)
+        .timed("SeasonRepository::update_description")
         .await
         .map_err(|e| {
             AppError::DatabaseError(format!("Failed to update season description: {}", e))
@@ -63,16 +67,21 @@ impl SeasonRepository {
         .ok_or_else(|| AppError::NotFound("Season not found".into()))?;
 
         tracing::info!("Updated season {} description", season_id);
+        self.invalidate_current().await?;
 
         Ok(season)
     }
 
     /// Update season dates (validates end_date > start_date).
+    ///
+    /// Rejects dates overlapping another season with `AppError::Conflict`
+    /// unless `allow_overlap` is set, as `create_season` does.
     pub async fn update_dates(
         &self,
         season_id: i32,
         start_date: NaiveDateTime,
         end_date: NaiveDateTime,
+        allow_overlap: bool,
     ) -> Result<Season, AppError> {
         // Validate dates
         if end_date <= start_date {
@@ -81,26 +90,46 @@ impl SeasonRepository {
             ));
         }
 
+        let mut transaction =
+            self.pool.begin().await.map_err(|e| {
+                AppError::DatabaseError(format!("Failed to begin transaction: {}", e))
+            })?;
+
+        if !allow_overlap {
+            Self::ensure_no_overlap(&mut transaction, start_date, end_date, Some(season_id))
+                .await?;
+        }
+
         let season = sqlx::query_as::<_, Season>(
             "UPDATE seasons
-            SET start_date = $1, end_date = $2
+            SET start_date = $1, end_date = $2, updated_at = NOW()
             WHERE id = $3
-            RETURNING id, name, description, start_date, end_date, created_at",
+            RETURNING id, name, description, start_date, end_date, created_at, updated_at",
         )
         .bind(start_date)
         .bind(end_date)
         .bind(season_id)
-        .fetch_optional(&self.pool)
+        .fetch_optional(&mut *transaction)
+        .timed("SeasonRepository::update_dates")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to update season dates: {}", e)))?
         .ok_or_else(|| AppError::NotFound("Season not found".into()))?;
 
+        transaction
+            .commit()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to commit season: {}", e)))?;
+
         tracing::info!("Updated season {} dates", season_id);
+        self.invalidate_current().await?;
 
         Ok(season)
     }
 
     /// Partially update a season (only provided fields are changed).
+    ///
+    /// New dates overlapping another season are rejected with
+    /// `AppError::Conflict` unless `allow_overlap` is set.
     pub async fn update_season(
         &self,
         season_id: i32,
@@ -108,6 +137,7 @@ impl SeasonRepository {
         description: Option<String>,
         start_date: Option<NaiveDateTime>,
         end_date: Option<NaiveDateTime>,
+        allow_overlap: bool,
     ) -> Result<Season, AppError> {
         // Fetch current season
         let current = self.find_by_id(season_id).await?;
@@ -132,6 +162,7 @@ impl SeasonRepository {
             .bind(&new_name)
             .bind(season_id)
             .fetch_optional(&self.pool)
+            .timed("SeasonRepository::update_season")
             .await
             .map_err(|e| AppError::DatabaseError(format!("Failed to check season name: {}", e)))?;
 
@@ -143,32 +174,75 @@ impl SeasonRepository {
             }
         }
 
+        let mut transaction =
+            self.pool.begin().await.map_err(|e| {
+                AppError::DatabaseError(format!("Failed to begin transaction: {}", e))
+            })?;
+
+        let dates_changed = new_start != current.start_date || new_end != current.end_date;
+        if dates_changed && !allow_overlap {
+            Self::ensure_no_overlap(&mut transaction, new_start, new_end, Some(season_id)).await?;
+        }
+
         let season = sqlx::query_as::<_, Season>(
             "UPDATE seasons
-            SET name = $1, description = $2, start_date = $3, end_date = $4
+            SET name = $1, description = $2, start_date = $3, end_date = $4,
+                updated_at = NOW()
             WHERE id = $5
-            RETURNING id, name, description, start_date, end_date, created_at",
+            RETURNING id, name, description, start_date, end_date, created_at, updated_at",
         )
         .bind(&new_name)
         .bind(&new_description)
         .bind(new_start)
         .bind(new_end)
         .bind(season_id)
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *transaction)
+        .timed("SeasonRepository::update_season")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to update season: {}", e)))?;
 
+        transaction
+            .commit()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to commit season: {}", e)))?;
+
         tracing::info!("Updated season {}", season_id);
+        self.invalidate_current().await?;
 
         Ok(season)
     }
 
+    /// Mark an ended season as finalized.
+    ///
+    /// Returns false if it already was.
+    pub async fn finalize(&self, season_id: i32) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            "UPDATE seasons SET finalized_at = NOW(), updated_at = NOW()
+            WHERE id = $1 AND finalized_at IS NULL",
+        )
+        .bind(season_id)
+        .execute(&self.pool)
+        .timed("SeasonRepository::finalize")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to finalize season: {}", e)))?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
     /// Extend a season's end date by a number of days.
-    pub async fn extend_season(&self, season_id: i32, days: i64) -> Result<Season, AppError> {
+    ///
+    /// Fails with `AppError::Conflict` if the extension runs into another
+    /// season, unless `allow_overlap` is set.
+    pub async fn extend_season(
+        &self,
+        season_id: i32,
+        days: i64,
+        allow_overlap: bool,
+    ) -> Result<Season, AppError> {
         let current = self.find_by_id(season_id).await?;
         let new_end = current.end_date + chrono::Duration::days(days);
 
-        self.update_dates(season_id, current.start_date, new_end)
+        self.update_dates(season_id, current.start_date, new_end, allow_overlap)
             .await
     }
 }
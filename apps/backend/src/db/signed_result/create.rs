@@ -0,0 +1,23 @@
+use crate::{db::timing::TimedQuery, errors::AppError, models::SignedResults};
+
+use super::SignedResultRepository;
+
+impl SignedResultRepository {
+    /// Store a lobby's signed results; a lobby keeps the first results signed for it.
+    pub async fn store(&self, signed: &SignedResults) -> Result<(), AppError> {
+        sqlx::query(
+            "INSERT INTO signed_results (lobby_id, payload, signature, public_key)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (lobby_id) DO NOTHING",
+        )
+        .bind(signed.lobby_id)
+        .bind(&signed.payload)
+        .bind(&signed.signature)
+        .bind(&signed.public_key)
+        .execute(&self.pool)
+        .timed("SignedResultRepository::store")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to store signed results: {}", e)))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,17 @@
+use sqlx::PgPool;
+
+mod create;
+mod read;
+
+/// Repository for lobbies' signed game results.
+#[derive(Clone)]
+pub struct SignedResultRepository {
+    pub(crate) pool: PgPool,
+}
+
+impl SignedResultRepository {
+    /// Create a new `SignedResultRepository` with the given pool.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
@@ -0,0 +1,21 @@
+use uuid::Uuid;
+
+use crate::{db::timing::TimedQuery, errors::AppError, models::SignedResults};
+
+use super::SignedResultRepository;
+
+impl SignedResultRepository {
+    /// A lobby's signed results, if its game was ranked and signed.
+    pub async fn find_by_lobby(&self, lobby_id: Uuid) -> Result<Option<SignedResults>, AppError> {
+        sqlx::query_as::<_, SignedResults>(
+            "SELECT lobby_id, payload, signature, public_key
+            FROM signed_results
+            WHERE lobby_id = $1",
+        )
+        .bind(lobby_id)
+        .fetch_optional(&self.pool)
+        .timed("SignedResultRepository::find_by_lobby")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch signed results: {}", e)))
+    }
+}
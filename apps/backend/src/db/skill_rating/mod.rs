@@ -0,0 +1,17 @@
+use sqlx::PgPool;
+
+mod read;
+mod update;
+
+/// Repository for per-game Elo skill ratings.
+#[derive(Clone)]
+pub struct SkillRatingRepository {
+    pub(crate) pool: PgPool,
+}
+
+impl SkillRatingRepository {
+    /// Create a new `SkillRatingRepository` with the given pool.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
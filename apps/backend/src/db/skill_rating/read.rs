@@ -0,0 +1,56 @@
+use crate::{
+    db::timing::TimedQuery,
+    errors::AppError,
+    models::{SkillRating, skill_rating::DEFAULT_SKILL_RATING},
+};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::SkillRatingRepository;
+
+impl SkillRatingRepository {
+    /// Get all of a user's skill ratings, highest first.
+    pub async fn find_by_user(&self, user_id: Uuid) -> Result<Vec<SkillRating>, AppError> {
+        let ratings = sqlx::query_as::<_, SkillRating>(
+            "SELECT user_id, game_id, rating, games_played, created_at, updated_at
+            FROM skill_ratings
+            WHERE user_id = $1
+            ORDER BY rating DESC",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .timed("SkillRatingRepository::find_by_user")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch skill ratings: {}", e)))?;
+
+        Ok(ratings)
+    }
+
+    /// Get current ratings for a set of users in a game.
+    ///
+    /// Users who haven't played the game yet get `DEFAULT_SKILL_RATING`.
+    pub async fn get_ratings(
+        &self,
+        game_id: Uuid,
+        user_ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, f64>, AppError> {
+        let rows = sqlx::query_as::<_, (Uuid, f64)>(
+            "SELECT user_id, rating
+            FROM skill_ratings
+            WHERE game_id = $1 AND user_id = ANY($2)",
+        )
+        .bind(game_id)
+        .bind(user_ids)
+        .fetch_all(&self.pool)
+        .timed("SkillRatingRepository::get_ratings")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch skill ratings: {}", e)))?;
+
+        let mut ratings: HashMap<Uuid, f64> = rows.into_iter().collect();
+        for user_id in user_ids {
+            ratings.entry(*user_id).or_insert(DEFAULT_SKILL_RATING);
+        }
+
+        Ok(ratings)
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{
+    db::timing::TimedQuery,
+    errors::AppError,
+    models::skill_rating::{DEFAULT_SKILL_RATING, EloPlacement, calculate_elo_changes},
+};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::SkillRatingRepository;
+
+impl SkillRatingRepository {
+    /// Apply a finished game's placements to the players' ratings.
+    ///
+    /// `placements` are `(user_id, rank)` pairs. Rows are locked in user id order
+    /// and all deltas are computed from the pre-game ratings before any write, so
+    /// the outcome doesn't depend on the order players are listed or saved in.
+    ///
+    /// Every player's `games_played` goes up by one, and `wins` too for those
+    /// ranked first.
+    ///
+    /// Returns each player's new rating.
+    pub async fn apply_results(
+        &self,
+        game_id: Uuid,
+        placements: &[(Uuid, usize)],
+    ) -> Result<HashMap<Uuid, f64>, AppError> {
+        let mut user_ids: Vec<Uuid> = placements.iter().map(|(id, _)| *id).collect();
+        user_ids.sort();
+        user_ids.dedup();
+
+        let mut transaction =
+            self.pool.begin().await.map_err(|e| {
+                AppError::DatabaseError(format!("Failed to start transaction: {}", e))
+            })?;
+
+        for user_id in &user_ids {
+            sqlx::query(
+                "INSERT INTO skill_ratings (user_id, game_id, rating)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (user_id, game_id) DO NOTHING",
+            )
+            .bind(user_id)
+            .bind(game_id)
+            .bind(DEFAULT_SKILL_RATING)
+            .execute(&mut *transaction)
+            .timed("SkillRatingRepository::apply_results")
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!("Failed to initialize skill rating: {}", e))
+            })?;
+        }
+
+        let current: HashMap<Uuid, f64> = sqlx::query_as::<_, (Uuid, f64)>(
+            "SELECT user_id, rating
+            FROM skill_ratings
+            WHERE game_id = $1 AND user_id = ANY($2)
+            ORDER BY user_id
+            FOR UPDATE",
+        )
+        .bind(game_id)
+        .bind(&user_ids)
+        .fetch_all(&mut *transaction)
+        .timed("SkillRatingRepository::apply_results")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to lock skill ratings: {}", e)))?
+        .into_iter()
+        .collect();
+
+        let elo_placements: Vec<EloPlacement> = placements
+            .iter()
+            .map(|(user_id, rank)| EloPlacement {
+                user_id: *user_id,
+                rank: *rank,
+                rating: current
+                    .get(user_id)
+                    .copied()
+                    .unwrap_or(DEFAULT_SKILL_RATING),
+            })
+            .collect();
+        let changes = calculate_elo_changes(&elo_placements);
+
+        let winners: Vec<Uuid> = placements
+            .iter()
+            .filter(|(_, rank)| *rank == 1)
+            .map(|(id, _)| *id)
+            .collect();
+
+        let mut updated = HashMap::with_capacity(changes.len());
+        for user_id in &user_ids {
+            let delta = changes.get(user_id).copied().unwrap_or(0.0);
+            let won = i32::from(winners.contains(user_id));
+            let rating: f64 = sqlx::query_scalar(
+                "UPDATE skill_ratings
+                SET rating = rating + $1, games_played = games_played + 1, wins = wins + $4,
+                    updated_at = NOW()
+                WHERE user_id = $2 AND game_id = $3
+                RETURNING rating",
+            )
+            .bind(delta)
+            .bind(user_id)
+            .bind(game_id)
+            .bind(won)
+            .fetch_one(&mut *transaction)
+            .timed("SkillRatingRepository::apply_results")
+            .await
+            .map_err(|e| {
+                AppError::DatabaseError(format!("Failed to update skill rating: {}", e))
+            })?;
+
+            updated.insert(*user_id, rating);
+        }
+
+        transaction
+            .commit()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to commit transaction: {}", e)))?;
+
+        tracing::info!(
+            "Updated skill ratings for {} players in game {}",
+            updated.len(),
+            game_id
+        );
+
+        Ok(updated)
+    }
+}
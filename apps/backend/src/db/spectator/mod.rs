@@ -0,0 +1,22 @@
+// Spectator repository (Redis): which lobbies each signed-in user is watching
+
+mod read;
+mod update;
+
+use crate::state::RedisClient;
+
+/// Repository for spectator tracking.
+///
+/// Each user has a hash of lobby id to open spectator connections, so a user
+/// watching from two tabs stays listed until both close.
+#[derive(Clone)]
+pub struct SpectatorRepository {
+    pub(crate) redis: RedisClient,
+}
+
+impl SpectatorRepository {
+    /// Create a new `SpectatorRepository`.
+    pub fn new(redis: RedisClient) -> Self {
+        Self { redis }
+    }
+}
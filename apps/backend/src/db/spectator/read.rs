@@ -0,0 +1,33 @@
+use std::collections::HashMap;
+
+use redis::AsyncCommands;
+use uuid::Uuid;
+
+use crate::{db::spectator::SpectatorRepository, errors::AppError, models::keys::RedisKey};
+
+impl SpectatorRepository {
+    /// IDs of the lobbies the user has a spectator connection open to.
+    pub async fn get_lobby_ids_for_user(&self, user_id: Uuid) -> Result<Vec<Uuid>, AppError> {
+        let mut conn = self.redis.get().await?;
+        let watching: HashMap<String, i64> = conn
+            .hgetall(RedisKey::user_spectating(user_id))
+            .await
+            .map_err(AppError::RedisCommandError)?;
+
+        Ok(watching
+            .into_iter()
+            .filter(|(_, count)| *count > 0)
+            .filter_map(|(id, _)| Uuid::parse_str(&id).ok())
+            .collect())
+    }
+
+    /// Whether the user still has a spectator connection open to the lobby.
+    pub async fn is_watching(&self, user_id: Uuid, lobby_id: Uuid) -> Result<bool, AppError> {
+        let mut conn = self.redis.get().await?;
+        let count: Option<i64> = conn
+            .hget(RedisKey::user_spectating(user_id), lobby_id.to_string())
+            .await
+            .map_err(AppError::RedisCommandError)?;
+        Ok(count.is_some_and(|count| count > 0))
+    }
+}
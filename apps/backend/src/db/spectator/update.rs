@@ -0,0 +1,48 @@
+use uuid::Uuid;
+
+use crate::{
+    db::spectator::SpectatorRepository,
+    errors::AppError,
+    models::keys::{ExpiryPolicy, RedisKey},
+};
+
+/// Drop one connection from a lobby's count, removing the lobby at zero
+const REMOVE_SCRIPT: &str = r#"
+local remaining = redis.call('HINCRBY', KEYS[1], ARGV[1], -1)
+if remaining <= 0 then
+    redis.call('HDEL', KEYS[1], ARGV[1])
+end
+return remaining
+"#;
+
+impl SpectatorRepository {
+    /// Record a spectator connection from `user_id` to `lobby_id`.
+    ///
+    /// The hash lives as long as a lobby does, so counts left behind by an
+    /// instance that died without cleaning up eventually go away.
+    pub async fn add(&self, user_id: Uuid, lobby_id: Uuid) -> Result<(), AppError> {
+        let key = RedisKey::user_spectating(user_id);
+        let mut conn = self.redis.get().await?;
+        let _: () = redis::pipe()
+            .hincr(&key, lobby_id.to_string(), 1)
+            .ignore()
+            .expire(&key, ExpiryPolicy::LOBBY_TTL_SECS)
+            .ignore()
+            .query_async(&mut *conn)
+            .await
+            .map_err(AppError::RedisCommandError)?;
+        Ok(())
+    }
+
+    /// Forget one of `user_id`'s spectator connections to `lobby_id`.
+    pub async fn remove(&self, user_id: Uuid, lobby_id: Uuid) -> Result<(), AppError> {
+        let mut conn = self.redis.get().await?;
+        let _: i64 = redis::Script::new(REMOVE_SCRIPT)
+            .key(RedisKey::user_spectating(user_id))
+            .arg(lobby_id.to_string())
+            .invoke_async(&mut *conn)
+            .await
+            .map_err(AppError::RedisCommandError)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+use redis::AsyncCommands;
+
+use crate::{
+    errors::AppError,
+    models::{RedisKey, StatsOverview},
+};
+
+use super::StatsRepository;
+
+/// How long a computed overview is served before it is recomputed
+pub const STATS_OVERVIEW_CACHE_TTL_SECS: u64 = 60;
+
+impl StatsRepository {
+    /// The stats overview, served from Redis when cached.
+    ///
+    /// Computed from Postgres on a miss (or without a cache) and cached for
+    /// `STATS_OVERVIEW_CACHE_TTL_SECS`, so the numbers may lag by that much.
+    pub async fn overview(&self) -> Result<StatsOverview, AppError> {
+        let Some(redis) = &self.redis else {
+            return self.compute_overview().await;
+        };
+
+        let mut conn = redis.get().await?;
+        let key = RedisKey::stats_overview();
+
+        let cached: Option<String> = conn.get(&key).await.map_err(AppError::RedisCommandError)?;
+        if let Some(raw) = cached
+            && let Ok(overview) = serde_json::from_str(&raw)
+        {
+            return Ok(overview);
+        }
+
+        let overview = self.compute_overview().await?;
+
+        let raw = serde_json::to_string(&overview).map_err(|e| {
+            AppError::Serialization(format!("Failed to serialize stats overview: {}", e))
+        })?;
+        let _: () = conn
+            .set_ex(&key, raw, STATS_OVERVIEW_CACHE_TTL_SECS)
+            .await
+            .map_err(AppError::RedisCommandError)?;
+
+        Ok(overview)
+    }
+}
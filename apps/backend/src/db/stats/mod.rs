@@ -0,0 +1,31 @@
+use sqlx::PgPool;
+
+use crate::state::RedisClient;
+
+mod cache;
+mod read;
+
+pub use cache::STATS_OVERVIEW_CACHE_TTL_SECS;
+
+/// Stats repository: platform-wide aggregates over finished games.
+///
+/// Modules: `cache`, `read`.
+#[derive(Clone)]
+pub struct StatsRepository {
+    pub(crate) pool: PgPool,
+    /// Optional Redis client backing the overview cache
+    pub(crate) redis: Option<RedisClient>,
+}
+
+impl StatsRepository {
+    /// Create a new StatsRepository
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool, redis: None }
+    }
+
+    /// Serve the overview from a short-lived Redis cache.
+    pub fn with_cache(mut self, redis: RedisClient) -> Self {
+        self.redis = Some(redis);
+        self
+    }
+}
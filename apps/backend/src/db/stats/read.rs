@@ -0,0 +1,80 @@
+use sqlx::query_as;
+
+use crate::{
+    db::timing::TimedQuery,
+    errors::AppError,
+    models::{ActivePlayers, PopularGame, StatsOverview, TokenVolume},
+};
+
+use super::StatsRepository;
+
+impl StatsRepository {
+    /// Compute the overview from Postgres, bypassing the cache.
+    ///
+    /// A game counts once its lobby has a `finished_at`; active players are
+    /// those whose skill rating a finished ranked game moved.
+    pub async fn compute_overview(&self) -> Result<StatsOverview, AppError> {
+        let db_err = |what: &str, e: sqlx::Error| {
+            AppError::DatabaseError(format!("Failed to compute {}: {}", what, e))
+        };
+
+        let (games_played, average_game_duration_secs): (i64, Option<f64>) = query_as(
+            "SELECT COUNT(*),
+                    AVG(EXTRACT(EPOCH FROM finished_at - started_at))
+                        FILTER (WHERE started_at <= finished_at)::DOUBLE PRECISION
+             FROM lobbies
+             WHERE finished_at IS NOT NULL",
+        )
+        .fetch_one(&self.pool)
+        .timed("StatsRepository::games_played")
+        .await
+        .map_err(|e| db_err("games played", e))?;
+
+        let pot_volume: Vec<TokenVolume> = query_as(
+            "SELECT token_symbol, SUM(current_amount) AS volume
+             FROM lobbies
+             WHERE finished_at IS NOT NULL
+               AND token_symbol IS NOT NULL
+               AND current_amount > 0
+             GROUP BY token_symbol
+             ORDER BY volume DESC, token_symbol",
+        )
+        .fetch_all(&self.pool)
+        .timed("StatsRepository::pot_volume")
+        .await
+        .map_err(|e| db_err("pot volume", e))?;
+
+        let (last_24h, last_7d): (i64, i64) = query_as(
+            "SELECT COUNT(DISTINCT user_id) FILTER (WHERE updated_at >= NOW() - INTERVAL '1 day'),
+                    COUNT(DISTINCT user_id)
+             FROM skill_ratings
+             WHERE games_played > 0 AND updated_at >= NOW() - INTERVAL '7 days'",
+        )
+        .fetch_one(&self.pool)
+        .timed("StatsRepository::active_players")
+        .await
+        .map_err(|e| db_err("active players", e))?;
+
+        let most_popular_game: Option<PopularGame> = query_as(
+            "SELECT g.id AS game_id, g.name, g.path, COUNT(*) AS games_played
+             FROM lobbies l
+             JOIN games g ON g.id = l.game_id
+             WHERE l.finished_at IS NOT NULL
+             GROUP BY g.id, g.name, g.path
+             ORDER BY games_played DESC, g.name
+             LIMIT 1",
+        )
+        .fetch_optional(&self.pool)
+        .timed("StatsRepository::most_popular_game")
+        .await
+        .map_err(|e| db_err("most popular game", e))?;
+
+        Ok(StatsOverview {
+            games_played,
+            pot_volume,
+            active_players: ActivePlayers { last_24h, last_7d },
+            most_popular_game,
+            average_game_duration_secs: average_game_duration_secs.unwrap_or(0.0),
+        })
+    }
+}
@@ -0,0 +1,19 @@
+// Streak repository (Redis): per-user win and daily-participation streaks
+
+mod read;
+mod update;
+
+use crate::state::RedisClient;
+
+/// Repository for user streak operations.
+#[derive(Clone)]
+pub struct StreakRepository {
+    pub(crate) redis: RedisClient,
+}
+
+impl StreakRepository {
+    /// Create a new `StreakRepository`.
+    pub fn new(redis: RedisClient) -> Self {
+        Self { redis }
+    }
+}
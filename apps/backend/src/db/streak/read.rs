@@ -0,0 +1,34 @@
+use chrono::Utc;
+use redis::AsyncCommands;
+use uuid::Uuid;
+
+use crate::{
+    errors::AppError,
+    models::{RedisKey, UserStreaks, streak::streak_day},
+};
+
+use super::StreakRepository;
+
+impl StreakRepository {
+    /// A user's streaks as stored, without lapsing stale daily streaks.
+    pub async fn get_raw(&self, user_id: Uuid) -> Result<UserStreaks, AppError> {
+        let mut conn = self.redis.get().await?;
+
+        let raw: Option<String> = conn
+            .get(RedisKey::user_streaks(user_id))
+            .await
+            .map_err(AppError::RedisCommandError)?;
+
+        match raw {
+            Some(json) => serde_json::from_str(&json)
+                .map_err(|e| AppError::Deserialization(format!("Invalid streak data: {}", e))),
+            None => Ok(UserStreaks::default()),
+        }
+    }
+
+    /// A user's current streaks; a daily streak missed yesterday reads as 0.
+    pub async fn get(&self, user_id: Uuid, day_offset_secs: i64) -> Result<UserStreaks, AppError> {
+        let today = streak_day(Utc::now().timestamp(), day_offset_secs);
+        Ok(self.get_raw(user_id).await?.as_of(today))
+    }
+}
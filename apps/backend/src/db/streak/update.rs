@@ -0,0 +1,65 @@
+use redis::AsyncCommands;
+use uuid::Uuid;
+
+use crate::{
+    errors::AppError,
+    models::{RedisKey, UserStreaks, streak::streak_day},
+};
+
+use super::StreakRepository;
+
+/// Optimistic-lock retries before giving up on a contended update
+const MAX_RETRIES: usize = 5;
+
+impl StreakRepository {
+    /// Record a finished game for a user at `timestamp` (unix seconds).
+    ///
+    /// Uses WATCH so concurrent game finishes for the same user don't lose
+    /// an update. Returns the updated streaks.
+    pub async fn record_game(
+        &self,
+        user_id: Uuid,
+        won: bool,
+        timestamp: i64,
+        day_offset_secs: i64,
+    ) -> Result<UserStreaks, AppError> {
+        let mut conn = self.redis.get().await?;
+        let key = RedisKey::user_streaks(user_id);
+        let day = streak_day(timestamp, day_offset_secs);
+
+        for _ in 0..MAX_RETRIES {
+            let _: () = redis::cmd("WATCH")
+                .arg(&key)
+                .query_async(&mut *conn)
+                .await
+                .map_err(AppError::RedisCommandError)?;
+
+            let raw: Option<String> = conn.get(&key).await.map_err(AppError::RedisCommandError)?;
+            let mut streaks: UserStreaks = match raw {
+                Some(json) => serde_json::from_str(&json).unwrap_or_default(),
+                None => UserStreaks::default(),
+            };
+            streaks.record_game(won, day);
+
+            let json = serde_json::to_string(&streaks).map_err(|e| {
+                AppError::Serialization(format!("Failed to serialize streaks: {}", e))
+            })?;
+            let committed: Option<()> = redis::pipe()
+                .atomic()
+                .set(&key, json)
+                .ignore()
+                .query_async(&mut *conn)
+                .await
+                .map_err(AppError::RedisCommandError)?;
+
+            if committed.is_some() {
+                return Ok(streaks);
+            }
+        }
+
+        Err(AppError::RedisError(format!(
+            "Streak update for user {} kept conflicting",
+            user_id
+        )))
+    }
+}
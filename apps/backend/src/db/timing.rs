@@ -0,0 +1,113 @@
+// Query timing: labels repository queries and warns about slow ones
+//
+// Wrap a query future with `.timed("Repository::method")`. Anything slower
+// than the threshold (SLOW_QUERY_THRESHOLD_MS, set once at startup) is logged
+// with its label and duration and counted; the count is reported by /health.
+
+use std::{
+    future::Future,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+/// Threshold used until `set_slow_query_threshold` is called
+pub const DEFAULT_SLOW_QUERY_THRESHOLD_MS: u64 = 500;
+
+static SLOW_QUERY_THRESHOLD_MS: AtomicU64 = AtomicU64::new(DEFAULT_SLOW_QUERY_THRESHOLD_MS);
+static SLOW_QUERIES: AtomicU64 = AtomicU64::new(0);
+
+/// Set how long a query may take before it is logged as slow.
+pub fn set_slow_query_threshold(threshold: Duration) {
+    SLOW_QUERY_THRESHOLD_MS.store(threshold.as_millis() as u64, Ordering::Relaxed);
+}
+
+pub fn slow_query_threshold() -> Duration {
+    Duration::from_millis(SLOW_QUERY_THRESHOLD_MS.load(Ordering::Relaxed))
+}
+
+/// Slow queries seen since startup
+pub fn slow_query_count() -> u64 {
+    SLOW_QUERIES.load(Ordering::Relaxed)
+}
+
+/// Log and count `label` if it took longer than the threshold. Returns whether it did.
+fn record(label: &'static str, elapsed: Duration) -> bool {
+    let threshold = slow_query_threshold();
+    if elapsed <= threshold {
+        return false;
+    }
+
+    SLOW_QUERIES.fetch_add(1, Ordering::Relaxed);
+    tracing::warn!(
+        query = label,
+        elapsed_ms = elapsed.as_millis() as u64,
+        threshold_ms = threshold.as_millis() as u64,
+        "Slow query {} took {:?}",
+        label,
+        elapsed
+    );
+    true
+}
+
+/// Times a query future under a label naming the repository and method.
+pub trait TimedQuery: Future + Sized {
+    fn timed(self, label: &'static str) -> impl Future<Output = Self::Output> {
+        async move {
+            let started = Instant::now();
+            let output = self.await;
+            record(label, started.elapsed());
+            output
+        }
+    }
+}
+
+impl<F: Future> TimedQuery for F {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// Collects formatted log output
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_fast_queries_are_not_recorded() {
+        assert!(!record("Test::fast", Duration::ZERO));
+    }
+
+    #[tokio::test]
+    async fn test_slow_query_logs_warning() {
+        set_slow_query_threshold(Duration::from_millis(10));
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let before = slow_query_count();
+        tokio::time::sleep(Duration::from_millis(30))
+            .timed("Test::slow")
+            .await;
+        set_slow_query_threshold(Duration::from_millis(DEFAULT_SLOW_QUERY_THRESHOLD_MS));
+
+        assert!(slow_query_count() > before);
+        let logs = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        assert!(logs.contains("WARN"), "{}", logs);
+        assert!(logs.contains("Slow query Test::slow"), "{}", logs);
+    }
+}
@@ -0,0 +1,182 @@
+use std::{future::Future, time::Duration};
+
+use chrono::Utc;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    errors::AppError,
+    models::{RedisKey, stacks::TokenInfo},
+    redis_lock::RedisLock,
+};
+
+use super::TokenInfoCache;
+
+/// Longest a refresh may hold the single-flight lock
+const REFRESH_LOCK_TTL: Duration = Duration::from_secs(5);
+
+/// How often callers waiting on another's fetch re-check the cache
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Serialize, Deserialize)]
+struct CachedTokenInfo {
+    info: TokenInfo,
+    fetched_at_ms: i64,
+}
+
+impl TokenInfoCache {
+    /// Token metadata for `contract_id`, calling `fetch` only when needed.
+    ///
+    /// - Fresh entry: returned as-is.
+    /// - Stale entry: returned as-is; if no refresh is running, `fetch` runs
+    ///   in the background and its result replaces the entry.
+    /// - Miss: the first caller runs `fetch` and caches the result; concurrent
+    ///   callers wait for it, and only fetch themselves if that one fails.
+    ///
+    /// Fetch errors are returned to the caller that ran it and never cached.
+    pub async fn get_or_fetch<F, Fut>(
+        &self,
+        contract_id: &str,
+        fetch: F,
+    ) -> Result<TokenInfo, AppError>
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<TokenInfo, AppError>> + Send + 'static,
+    {
+        if let Some(cached) = self.read(contract_id).await? {
+            let age_ms = Utc::now().timestamp_millis() - cached.fetched_at_ms;
+            if age_ms < self.fresh_ttl.as_millis() as i64 {
+                return Ok(cached.info);
+            }
+
+            if let Some(lock) = self.try_lock(contract_id).await? {
+                let cache = self.clone();
+                let contract_id = contract_id.to_string();
+                tokio::spawn(async move {
+                    match fetch().await {
+                        Ok(info) => {
+                            if let Err(e) = cache.store(&contract_id, &info).await {
+                                tracing::warn!(
+                                    "Failed to cache token info for {}: {}",
+                                    contract_id,
+                                    e
+                                );
+                            }
+                        }
+                        Err(e) => {
+                            tracing::warn!(
+                                "Background token info refresh for {} failed: {}",
+                                contract_id,
+                                e
+                            );
+                        }
+                    }
+                    unlock(&contract_id, lock).await;
+                });
+            }
+
+            return Ok(cached.info);
+        }
+
+        if let Some(lock) = self.try_lock(contract_id).await? {
+            let result = self.fetch_and_store(contract_id, fetch).await;
+            unlock(contract_id, lock).await;
+            return result;
+        }
+
+        // Another caller is fetching; wait for it to land or give up
+        let lock_key = RedisKey::token_info_lock(contract_id);
+        let waits = REFRESH_LOCK_TTL.as_millis() / WAIT_POLL_INTERVAL.as_millis();
+        for _ in 0..waits {
+            tokio::time::sleep(WAIT_POLL_INTERVAL).await;
+            if let Some(cached) = self.read(contract_id).await? {
+                return Ok(cached.info);
+            }
+
+            let mut conn = self.redis.get().await?;
+            let fetching: bool = conn
+                .exists(&lock_key)
+                .await
+                .map_err(AppError::RedisCommandError)?;
+            if !fetching {
+                break;
+            }
+        }
+
+        self.fetch_and_store(contract_id, fetch).await
+    }
+
+    /// Drop the cached entry so the next read fetches.
+    pub async fn invalidate(&self, contract_id: &str) -> Result<(), AppError> {
+        let mut conn = self.redis.get().await?;
+        let _: () = conn
+            .del(RedisKey::token_info(contract_id))
+            .await
+            .map_err(AppError::RedisCommandError)?;
+        Ok(())
+    }
+
+    async fn fetch_and_store<F, Fut>(
+        &self,
+        contract_id: &str,
+        fetch: F,
+    ) -> Result<TokenInfo, AppError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<TokenInfo, AppError>>,
+    {
+        let info = fetch().await?;
+        if let Err(e) = self.store(contract_id, &info).await {
+            tracing::warn!("Failed to cache token info for {}: {}", contract_id, e);
+        }
+        Ok(info)
+    }
+
+    async fn read(&self, contract_id: &str) -> Result<Option<CachedTokenInfo>, AppError> {
+        let mut conn = self.redis.get().await?;
+        let raw: Option<String> = conn
+            .get(RedisKey::token_info(contract_id))
+            .await
+            .map_err(AppError::RedisCommandError)?;
+
+        // A corrupt entry is treated as a miss and overwritten
+        Ok(raw.and_then(|raw| serde_json::from_str(&raw).ok()))
+    }
+
+    async fn store(&self, contract_id: &str, info: &TokenInfo) -> Result<(), AppError> {
+        let raw = serde_json::to_string(&CachedTokenInfo {
+            info: info.clone(),
+            fetched_at_ms: Utc::now().timestamp_millis(),
+        })
+        .map_err(|e| AppError::Serialization(format!("Failed to serialize token info: {}", e)))?;
+
+        let ttl_ms = (self.fresh_ttl + self.stale_ttl).as_millis() as u64;
+        let mut conn = self.redis.get().await?;
+        let _: () = conn
+            .pset_ex(RedisKey::token_info(contract_id), raw, ttl_ms)
+            .await
+            .map_err(AppError::RedisCommandError)?;
+        Ok(())
+    }
+
+    /// Take the single-flight lock if no one else holds it.
+    async fn try_lock(&self, contract_id: &str) -> Result<Option<RedisLock>, AppError> {
+        RedisLock::try_acquire(
+            &self.redis,
+            &RedisKey::token_info_lock(contract_id),
+            REFRESH_LOCK_TTL,
+        )
+        .await
+    }
+}
+
+async fn unlock(contract_id: &str, lock: RedisLock) {
+    // The lock expires on its own; this only frees it early
+    if let Err(e) = lock.release().await {
+        tracing::warn!(
+            "Failed to release token info lock for {}: {}",
+            contract_id,
+            e
+        );
+    }
+}
@@ -0,0 +1,43 @@
+use std::time::Duration;
+
+use crate::state::RedisClient;
+
+mod cache;
+
+/// How long fetched token metadata is served without revalidating
+pub const TOKEN_INFO_FRESH_TTL: Duration = Duration::from_secs(60);
+
+/// How long past freshness stale metadata may still be served while a
+/// background refresh runs
+pub const TOKEN_INFO_STALE_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Redis cache for token metadata fetched from upstream price APIs.
+///
+/// Entries are fresh for `fresh_ttl`, then served stale for up to `stale_ttl`
+/// while one caller refreshes them in the background. Misses are
+/// single-flight: one caller fetches while the rest wait for its result.
+///
+/// Modules: `cache`.
+#[derive(Clone)]
+pub struct TokenInfoCache {
+    pub(crate) redis: RedisClient,
+    pub(crate) fresh_ttl: Duration,
+    pub(crate) stale_ttl: Duration,
+}
+
+impl TokenInfoCache {
+    pub fn new(redis: RedisClient) -> Self {
+        Self {
+            redis,
+            fresh_ttl: TOKEN_INFO_FRESH_TTL,
+            stale_ttl: TOKEN_INFO_STALE_TTL,
+        }
+    }
+
+    /// Override the default freshness and stale windows.
+    pub fn with_ttl(mut self, fresh_ttl: Duration, stale_ttl: Duration) -> Self {
+        self.fresh_ttl = fresh_ttl;
+        self.stale_ttl = stale_ttl;
+        self
+    }
+}
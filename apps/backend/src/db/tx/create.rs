@@ -0,0 +1,57 @@
+use crate::{db::timing::TimedQuery, errors::AppError};
+
+use super::TxRepository;
+
+impl TxRepository {
+    /// Record a contract call the node accepted.
+    pub async fn record_submitted(
+        &self,
+        function: &str,
+        nonce: u64,
+        tx_id: &str,
+        attempts: u32,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            "INSERT INTO server_transactions (function, nonce, tx_id, status, attempts)
+            VALUES ($1, $2, $3, 'submitted', $4)",
+        )
+        .bind(function)
+        .bind(nonce as i64)
+        .bind(tx_id)
+        .bind(attempts as i32)
+        .execute(&self.pool)
+        .timed("TxRepository::record_submitted")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to record transaction: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Record a contract call that failed after every retry.
+    ///
+    /// `nonce` is the one the last attempt used, if the node ever gave one.
+    pub async fn record_failed(
+        &self,
+        function: &str,
+        nonce: Option<u64>,
+        error: &str,
+        attempts: u32,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            "INSERT INTO server_transactions (function, nonce, status, attempts, error)
+            VALUES ($1, $2, 'failed', $3, $4)",
+        )
+        .bind(function)
+        .bind(nonce.map(|n| n as i64))
+        .bind(attempts as i32)
+        .bind(error)
+        .execute(&self.pool)
+        .timed("TxRepository::record_failed")
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to record failed transaction: {}", e))
+        })?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,16 @@
+use sqlx::PgPool;
+
+mod create;
+
+/// Repository for contract calls submitted from the server account.
+#[derive(Clone)]
+pub struct TxRepository {
+    pub(crate) pool: PgPool,
+}
+
+impl TxRepository {
+    /// Create a new `TxRepository` with the given pool.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
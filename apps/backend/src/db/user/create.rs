@@ -1,5 +1,5 @@
 use crate::{
-    auth::generate_jwt,
+    auth::{generate_jwt, jwt::JwtKeys},
     errors::AppError,
     models::{User, WalletAddress},
 };
@@ -7,6 +7,20 @@ use email_address::EmailAddress;
 use std::str::FromStr;
 
 use super::UserRepository;
+use crate::db::timing::TimedQuery;
+
+/// The `Conflict` for a unique violation on `users`, naming what's taken.
+fn taken(constraint: Option<&str>) -> AppError {
+    AppError::Conflict(
+        match constraint {
+            Some("users_wallet_address_key") => "A user with this wallet address already exists",
+            Some("users_username_key") => "Username already taken",
+            Some("users_email_key") => "Email address already in use",
+            _ => "User already exists",
+        }
+        .into(),
+    )
+}
 
 impl UserRepository {
     /// Create a new user or return an existing user with JWT token.
@@ -14,7 +28,7 @@ impl UserRepository {
         &self,
         wallet_address: &str,
         email_address: Option<&str>,
-        jwt_secret: &str,
+        jwt_keys: &JwtKeys,
     ) -> Result<(User, String), AppError> {
         let wallet_address = WalletAddress::new(wallet_address)?;
 
@@ -50,6 +64,7 @@ impl UserRepository {
         .bind(&email)
         .bind(email_verified)
         .fetch_one(&self.pool)
+        .timed("UserRepository::create_user")
         .await;
 
         let user = match result {
@@ -58,9 +73,15 @@ impl UserRepository {
                 user
             }
             Err(sqlx::Error::Database(db_err)) if db_err.is_unique_violation() => {
-                // User already exists, fetch and return
+                // Signing in again, or losing a race with a concurrent sign-in for the
+                // same wallet: either way the existing user is the one to return.
+                // Anything else unique (the email) belongs to someone else.
                 tracing::info!("User already exists, fetching: {}", wallet_address);
-                self.find_by_wallet(wallet_address.as_str()).await?
+                match self.find_by_wallet(wallet_address.as_str()).await {
+                    Ok(user) => user,
+                    Err(AppError::NotFound(_)) => return Err(taken(db_err.constraint())),
+                    Err(e) => return Err(e),
+                }
             }
             Err(e) => {
                 return Err(AppError::DatabaseError(format!(
@@ -70,7 +91,7 @@ impl UserRepository {
             }
         };
 
-        let token = generate_jwt(&user, jwt_secret)?;
+        let token = generate_jwt(&user, jwt_keys)?;
         Ok((user, token))
     }
 
@@ -112,12 +133,13 @@ impl UserRepository {
         .bind(false)
         .bind(trust_rating)
         .fetch_one(&self.pool)
+        .timed("UserRepository::create_user_with_details")
         .await
         .map_err(|e| {
-            if let sqlx::Error::Database(db_err) = &e {
-                if db_err.is_unique_violation() {
-                    return AppError::BadRequest("User with this wallet address or username already exists".into());
-                }
+            if let sqlx::Error::Database(db_err) = &e
+                && db_err.is_unique_violation()
+            {
+                return taken(db_err.constraint());
             }
             AppError::DatabaseError(format!("Failed to create user: {}", e))
         })?;
@@ -1,7 +1,10 @@
 use crate::errors::AppError;
+use crate::models::{DELETED_USER_NAME, User};
+use sqlx::PgConnection;
 use uuid::Uuid;
 
 use super::UserRepository;
+use crate::db::timing::TimedQuery;
 
 impl UserRepository {
     /// Delete a user by ID (hard delete; cascades to related data).
@@ -9,6 +12,7 @@ impl UserRepository {
         let result = sqlx::query("DELETE FROM users WHERE id = $1")
             .bind(user_id)
             .execute(&self.pool)
+            .timed("UserRepository::delete_user")
             .await
             .map_err(|e| AppError::DatabaseError(format!("Failed to delete user: {}", e)))?;
 
@@ -20,11 +24,59 @@ impl UserRepository {
         Ok(())
     }
 
+    /// Soft-delete a user inside the caller's transaction.
+    ///
+    /// The row is kept so season points, ratings and lobbies still reference
+    /// it, but wallet, email, username and display name are replaced with
+    /// placeholders. Returns false if the user was already deleted, and
+    /// `NotFound` if there is no such user.
+    pub async fn anonymize_user(conn: &mut PgConnection, user_id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query(
+            "UPDATE users
+            SET wallet_address = $2,
+                email = $3,
+                email_verified = FALSE,
+                username = NULL,
+                display_name = $4,
+                deleted_at = NOW(),
+                updated_at = NOW()
+            WHERE id = $1 AND deleted_at IS NULL",
+        )
+        .bind(user_id)
+        .bind(User::anonymized_wallet(user_id))
+        .bind(User::anonymized_email(user_id))
+        .bind(DELETED_USER_NAME)
+        .execute(&mut *conn)
+        .timed("UserRepository::anonymize_user")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to anonymize user: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            let exists =
+                sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM users WHERE id = $1)")
+                    .bind(user_id)
+                    .fetch_one(&mut *conn)
+                    .timed("UserRepository::anonymize_user")
+                    .await
+                    .map_err(|e| {
+                        AppError::DatabaseError(format!("Failed to check user existence: {}", e))
+                    })?;
+            if !exists {
+                return Err(AppError::NotFound("User not found".into()));
+            }
+            return Ok(false);
+        }
+
+        tracing::warn!("Anonymized deleted user: {}", user_id);
+        Ok(true)
+    }
+
     /// Delete a user by wallet address.
     pub async fn delete_user_by_wallet(&self, wallet_address: &str) -> Result<(), AppError> {
         let result = sqlx::query("DELETE FROM users WHERE wallet_address = $1")
             .bind(wallet_address)
             .execute(&self.pool)
+            .timed("UserRepository::delete_user_by_wallet")
             .await
             .map_err(|e| AppError::DatabaseError(format!("Failed to delete user: {}", e)))?;
 
@@ -45,6 +97,7 @@ impl UserRepository {
         let result = sqlx::query("DELETE FROM users WHERE id = ANY($1)")
             .bind(&user_ids)
             .execute(&self.pool)
+            .timed("UserRepository::bulk_delete_users")
             .await
             .map_err(|e| AppError::DatabaseError(format!("Failed to bulk delete users: {}", e)))?;
 
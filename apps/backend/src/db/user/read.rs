@@ -1,10 +1,11 @@
 use crate::{
     errors::AppError,
-    models::{User, Username, WalletAddress},
+    models::{Role, User, UserStats, Username, WalletAddress},
 };
 use uuid::Uuid;
 
 use super::UserRepository;
+use crate::db::timing::TimedQuery;
 
 impl UserRepository {
     /// Find a user by ID (returns user profile data).
@@ -16,6 +17,7 @@ impl UserRepository {
         )
         .bind(user_id)
         .fetch_optional(&self.pool)
+        .timed("UserRepository::find_by_id")
         .await
         .map_err(|e| {
             tracing::error!("Failed to query user by id: {}", e);
@@ -36,6 +38,7 @@ impl UserRepository {
         )
         .bind(wallet_address)
         .fetch_optional(&self.pool)
+        .timed("UserRepository::find_by_wallet")
         .await
         .map_err(|e| {
             tracing::error!("Failed to query user by wallet: {}", e);
@@ -59,6 +62,7 @@ impl UserRepository {
         )
         .bind(&normalized_username)
         .fetch_optional(&self.pool)
+        .timed("UserRepository::find_by_username")
         .await
         .map_err(|e| {
             tracing::error!("Failed to query user by username: {}", e);
@@ -110,6 +114,7 @@ impl UserRepository {
             sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM users WHERE id = $1)")
                 .bind(user_id)
                 .fetch_one(&self.pool)
+                .timed("UserRepository::exists_by_id")
                 .await
                 .map_err(|e| {
                     AppError::DatabaseError(format!("Failed to check user existence: {}", e))
@@ -117,4 +122,65 @@ impl UserRepository {
 
         Ok(exists)
     }
+
+    /// Check if a user has been banned by a moderator.
+    pub async fn is_banned(&self, user_id: Uuid) -> Result<bool, AppError> {
+        let banned = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM users WHERE id = $1 AND banned_at IS NOT NULL)",
+        )
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .timed("UserRepository::is_banned")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to check user ban: {}", e)))?;
+
+        Ok(banned)
+    }
+
+    /// A user's role; users who don't exist have none beyond `User`.
+    pub async fn find_role(&self, user_id: Uuid) -> Result<Role, AppError> {
+        let role = sqlx::query_scalar::<_, Role>("SELECT role FROM users WHERE id = $1")
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .timed("UserRepository::find_role")
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to query user role: {}", e)))?;
+
+        Ok(role.unwrap_or_default())
+    }
+
+    /// Compute a user's profile stats in one aggregated query.
+    ///
+    /// Games and wins are summed over the user's skill ratings; season points
+    /// come from the current season, if one is running.
+    pub async fn find_stats(&self, user_id: Uuid) -> Result<UserStats, AppError> {
+        let now = chrono::Utc::now();
+
+        let (games_played, wins, season_points) = sqlx::query_as::<_, (i64, i64, Option<f64>)>(
+            "WITH current_season AS (
+                    SELECT id
+                    FROM seasons
+                    WHERE start_date <= $2 AND end_date >= $2
+                    ORDER BY start_date DESC
+                    LIMIT 1
+                )
+                SELECT
+                    COALESCE(SUM(sr.games_played), 0)::BIGINT,
+                    COALESCE(SUM(sr.wins), 0)::BIGINT,
+                    (SELECT COALESCE(MAX(wp.points), 0)
+                        FROM current_season cs
+                        LEFT JOIN user_wars_points wp ON wp.season_id = cs.id AND wp.user_id = $1
+                        GROUP BY cs.id)
+                FROM skill_ratings sr
+                WHERE sr.user_id = $1",
+        )
+        .bind(user_id)
+        .bind(now)
+        .fetch_one(&self.pool)
+        .timed("UserRepository::find_stats")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to compute user stats: {}", e)))?;
+
+        Ok(UserStats::new(user_id, games_played, wins, season_points))
+    }
 }
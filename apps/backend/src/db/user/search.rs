@@ -1,6 +1,7 @@
 use crate::{errors::AppError, models::User};
 
 use super::UserRepository;
+use crate::db::timing::TimedQuery;
 
 /// Search filters for user queries.
 #[derive(Debug, Clone, Default)]
@@ -67,6 +68,7 @@ impl UserRepository {
 
         let user_ids: Vec<uuid::Uuid> = query_builder
             .fetch_all(&self.pool)
+            .timed("UserRepository::search_users")
             .await
             .map_err(|e| AppError::DatabaseError(format!("Failed to search users: {}", e)))?;
 
@@ -88,6 +90,7 @@ impl UserRepository {
                 .bind(limit)
                 .bind(offset)
                 .fetch_all(&self.pool)
+                .timed("UserRepository::get_all_users")
                 .await
                 .map_err(|e| AppError::DatabaseError(format!("Failed to get all users: {}", e)))?;
 
@@ -105,6 +108,7 @@ impl UserRepository {
     pub async fn count_users(&self) -> Result<i64, AppError> {
         let count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM users")
             .fetch_one(&self.pool)
+            .timed("UserRepository::count_users")
             .await
             .map_err(|e| AppError::DatabaseError(format!("Failed to count users: {}", e)))?;
 
@@ -1,11 +1,13 @@
 use crate::{
     errors::AppError,
-    models::{User, Username},
+    models::{Role, User, Username},
 };
+use sqlx::PgConnection;
 use uuid::Uuid;
 
 use super::UserRepository;
 use crate::db::player_state::PlayerStateRepository;
+use crate::db::timing::TimedQuery;
 use crate::state::RedisClient;
 
 impl UserRepository {
@@ -26,6 +28,7 @@ impl UserRepository {
         .bind(&username)
         .bind(user_id)
         .execute(&self.pool)
+        .timed("UserRepository::update_username")
         .await
         .map_err(|e| {
             if let sqlx::Error::Database(db_err) = &e {
@@ -70,6 +73,7 @@ impl UserRepository {
         .bind(display_name)
         .bind(user_id)
         .execute(&self.pool)
+        .timed("UserRepository::update_display_name")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to update display name: {}", e)))?;
 
@@ -113,6 +117,7 @@ impl UserRepository {
         .bind(trust_rating)
         .bind(user_id)
         .execute(&self.pool)
+        .timed("UserRepository::update_trust_rating")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to update trust rating: {}", e)))?;
 
@@ -181,14 +186,18 @@ impl UserRepository {
 
         query_builder = query_builder.bind(user_id);
 
-        query_builder.execute(&self.pool).await.map_err(|e| {
-            if let sqlx::Error::Database(db_err) = &e {
-                if db_err.is_unique_violation() {
-                    return AppError::BadRequest("Username already taken".into());
+        query_builder
+            .execute(&self.pool)
+            .timed("UserRepository::update_profile")
+            .await
+            .map_err(|e| {
+                if let sqlx::Error::Database(db_err) = &e {
+                    if db_err.is_unique_violation() {
+                        return AppError::BadRequest("Username already taken".into());
+                    }
                 }
-            }
-            AppError::DatabaseError(format!("Failed to update profile: {}", e))
-        })?;
+                AppError::DatabaseError(format!("Failed to update profile: {}", e))
+            })?;
 
         tracing::info!("Updated profile for user {}", user_id);
 
@@ -234,6 +243,7 @@ impl UserRepository {
         .bind(amount)
         .bind(user_id)
         .fetch_one(&self.pool)
+        .timed("UserRepository::increment_trust_rating")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to increment trust rating: {}", e)))?;
 
@@ -245,19 +255,7 @@ impl UserRepository {
         );
 
         // Sync trust rating across all lobbies in Redis
-        let player_repo = PlayerStateRepository::new(redis);
-        tokio::spawn(async move {
-            if let Err(e) = player_repo
-                .sync_user_profile_across_lobbies(user_id, None, None, None, Some(new_rating))
-                .await
-            {
-                tracing::warn!(
-                    "Failed to sync trust rating for user {} across lobbies: {}",
-                    user_id,
-                    e
-                );
-            }
-        });
+        Self::sync_trust_rating(redis, user_id, new_rating);
 
         Ok(new_rating)
     }
@@ -278,6 +276,7 @@ impl UserRepository {
         .bind(amount)
         .bind(user_id)
         .fetch_one(&self.pool)
+        .timed("UserRepository::decrement_trust_rating")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to decrement trust rating: {}", e)))?;
 
@@ -289,10 +288,51 @@ impl UserRepository {
         );
 
         // Sync trust rating across all lobbies in Redis
+        Self::sync_trust_rating(redis, user_id, new_rating);
+
+        Ok(new_rating)
+    }
+
+    /// Change a user's trust rating by `delta` inside the caller's transaction,
+    /// returning the new rating.
+    ///
+    /// Lobbies in Redis aren't updated; once committed, pass the new rating to
+    /// `sync_trust_rating`.
+    pub async fn adjust_trust_rating(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        delta: f64,
+    ) -> Result<f64, AppError> {
+        let new_rating = sqlx::query_scalar::<_, f64>(
+            "UPDATE users
+            SET trust_rating = trust_rating + $1, updated_at = NOW()
+            WHERE id = $2
+            RETURNING trust_rating",
+        )
+        .bind(delta)
+        .bind(user_id)
+        .fetch_optional(&mut *conn)
+        .timed("UserRepository::adjust_trust_rating")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to adjust trust rating: {}", e)))?
+        .ok_or_else(|| AppError::NotFound("User not found".into()))?;
+
+        tracing::info!(
+            "Adjusted trust rating for user {} by {} to {}",
+            user_id,
+            delta,
+            new_rating
+        );
+
+        Ok(new_rating)
+    }
+
+    /// Copy a user's trust rating to every lobby they're in, in the background.
+    pub fn sync_trust_rating(redis: RedisClient, user_id: Uuid, rating: f64) {
         let player_repo = PlayerStateRepository::new(redis);
         tokio::spawn(async move {
             if let Err(e) = player_repo
-                .sync_user_profile_across_lobbies(user_id, None, None, None, Some(new_rating))
+                .sync_user_profile_across_lobbies(user_id, None, None, None, Some(rating))
                 .await
             {
                 tracing::warn!(
@@ -302,7 +342,53 @@ impl UserRepository {
                 );
             }
         });
+    }
 
-        Ok(new_rating)
+    /// Ban a user from signing in, inside the caller's transaction. Banning an
+    /// already banned user keeps the original ban time.
+    ///
+    /// Existing sessions are untouched; revoke them once committed.
+    pub async fn ban_user(conn: &mut PgConnection, user_id: Uuid) -> Result<(), AppError> {
+        let result = sqlx::query(
+            "UPDATE users
+            SET banned_at = COALESCE(banned_at, NOW()), updated_at = NOW()
+            WHERE id = $1",
+        )
+        .bind(user_id)
+        .execute(&mut *conn)
+        .timed("UserRepository::ban_user")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to ban user: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("User not found".into()));
+        }
+
+        tracing::info!("Banned user {}", user_id);
+
+        Ok(())
+    }
+
+    /// Give a user a role.
+    pub async fn set_role(&self, user_id: Uuid, role: Role) -> Result<(), AppError> {
+        let result = sqlx::query(
+            "UPDATE users
+            SET role = $1, updated_at = NOW()
+            WHERE id = $2",
+        )
+        .bind(role)
+        .bind(user_id)
+        .execute(&self.pool)
+        .timed("UserRepository::set_role")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to set user role: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("User not found".into()));
+        }
+
+        tracing::info!("Set role of user {} to {}", user_id, role);
+
+        Ok(())
     }
 }
@@ -0,0 +1,47 @@
+use uuid::Uuid;
+
+use crate::db::timing::TimedQuery;
+use crate::errors::AppError;
+
+use super::UserBadgeRepository;
+
+impl UserBadgeRepository {
+    /// Award badges to a user for a season, skipping any already held.
+    ///
+    /// Returns the ids of badges that were newly awarded.
+    pub async fn award(
+        &self,
+        user_id: Uuid,
+        season_id: i32,
+        badge_ids: &[String],
+    ) -> Result<Vec<String>, AppError> {
+        if badge_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let awarded: Vec<String> = sqlx::query_scalar(
+            "INSERT INTO user_badges (user_id, season_id, badge_id)
+            SELECT $1, $2, UNNEST($3::TEXT[])
+            ON CONFLICT (user_id, season_id, badge_id) DO NOTHING
+            RETURNING badge_id",
+        )
+        .bind(user_id)
+        .bind(season_id)
+        .bind(badge_ids)
+        .fetch_all(&self.pool)
+        .timed("UserBadgeRepository::award")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to award badges: {}", e)))?;
+
+        if !awarded.is_empty() {
+            tracing::info!(
+                "Awarded badges {:?} to user {} for season {}",
+                awarded,
+                user_id,
+                season_id
+            );
+        }
+
+        Ok(awarded)
+    }
+}
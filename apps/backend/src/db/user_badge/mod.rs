@@ -0,0 +1,17 @@
+use sqlx::PgPool;
+
+mod create;
+mod read;
+
+/// Repository for badges awarded to users per season.
+#[derive(Clone)]
+pub struct UserBadgeRepository {
+    pub(crate) pool: PgPool,
+}
+
+impl UserBadgeRepository {
+    /// Create a new `UserBadgeRepository` with the given pool.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
@@ -0,0 +1,27 @@
+use uuid::Uuid;
+
+use crate::{db::timing::TimedQuery, errors::AppError, models::UserBadge};
+
+use super::UserBadgeRepository;
+
+impl UserBadgeRepository {
+    /// All badges a user holds, newest first. Optionally limited to one season.
+    pub async fn find_by_user(
+        &self,
+        user_id: Uuid,
+        season_id: Option<i32>,
+    ) -> Result<Vec<UserBadge>, AppError> {
+        sqlx::query_as::<_, UserBadge>(
+            "SELECT id, user_id, season_id, badge_id, awarded_at
+            FROM user_badges
+            WHERE user_id = $1 AND ($2::INT IS NULL OR season_id = $2)
+            ORDER BY awarded_at DESC, badge_id",
+        )
+        .bind(user_id)
+        .bind(season_id)
+        .fetch_all(&self.pool)
+        .timed("UserBadgeRepository::find_by_user")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch user badges: {}", e)))
+    }
+}
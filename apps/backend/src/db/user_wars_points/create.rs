@@ -1,8 +1,15 @@
-use crate::{errors::AppError, models::UserWarsPoints};
+use std::collections::HashSet;
+
+use sqlx::PgConnection;
+
+use crate::{db::timing::TimedQuery, errors::AppError, models::UserWarsPoints};
 use uuid::Uuid;
 
 use super::UserWarsPointsRepository;
 
+/// Users per statement when awarding in bulk
+const AWARD_BATCH_SIZE: usize = 5_000;
+
 impl UserWarsPointsRepository {
     /// Upsert (create or update) user wars points for a season.
     pub async fn upsert_wars_points(
@@ -22,6 +29,7 @@ impl UserWarsPointsRepository {
         .bind(season_id)
         .bind(points)
         .fetch_one(&self.pool)
+        .timed("UserWarsPointsRepository::upsert_wars_points")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to upsert wars points: {}", e)))?;
 
@@ -34,4 +42,107 @@ impl UserWarsPointsRepository {
 
         Ok(wars_points)
     }
+
+    /// Record the points a user was awarded for a lobby's game in the award
+    /// ledger, with the `active_players` count they were computed from. A
+    /// lobby keeps the first award recorded for each player; `correct_awards`
+    /// changes it.
+    pub async fn record_award(
+        &self,
+        lobby_id: Uuid,
+        user_id: Uuid,
+        season_id: i32,
+        game_id: Option<Uuid>,
+        points: f64,
+        active_players: usize,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            "INSERT INTO wars_point_awards
+                (lobby_id, user_id, season_id, game_id, points, active_players)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (lobby_id, user_id) DO NOTHING",
+        )
+        .bind(lobby_id)
+        .bind(user_id)
+        .bind(season_id)
+        .bind(game_id)
+        .bind(points)
+        .bind(active_players as i32)
+        .execute(&self.pool)
+        .timed("UserWarsPointsRepository::record_award")
+        .await
+        .map_err(|e| {
+            AppError::DatabaseError(format!("Failed to record wars point award: {}", e))
+        })?;
+
+        Ok(())
+    }
+
+    /// Add points to many users' season totals at once, starting from zero,
+    /// e.g. points carried over into a new season.
+    ///
+    /// All awards land in one transaction, a few thousand users per INSERT.
+    /// A user listed more than once gets the sum. Returns the number of users
+    /// awarded.
+    pub async fn award_many(
+        &self,
+        season_id: i32,
+        awards: &[(Uuid, f64)],
+    ) -> Result<u64, AppError> {
+        let mut transaction =
+            self.pool.begin().await.map_err(|e| {
+                AppError::DatabaseError(format!("Failed to start transaction: {}", e))
+            })?;
+
+        let awarded = Self::award_many_in(&mut transaction, season_id, awards).await?;
+
+        transaction
+            .commit()
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to commit transaction: {}", e)))?;
+
+        Ok(awarded)
+    }
+
+    /// `award_many` inside the caller's transaction
+    pub async fn award_many_in(
+        conn: &mut PgConnection,
+        season_id: i32,
+        awards: &[(Uuid, f64)],
+    ) -> Result<u64, AppError> {
+        for batch in awards.chunks(AWARD_BATCH_SIZE) {
+            let (user_ids, points): (Vec<Uuid>, Vec<f64>) = batch.iter().copied().unzip();
+
+            // Summed first: one INSERT can't update the same row twice
+            sqlx::query(
+                "INSERT INTO user_wars_points (user_id, season_id, points)
+                SELECT award.user_id, $1, SUM(award.points)
+                FROM UNNEST($2::UUID[], $3::DOUBLE PRECISION[]) AS award(user_id, points)
+                GROUP BY award.user_id
+                ON CONFLICT (user_id, season_id)
+                DO UPDATE SET points = COALESCE(user_wars_points.points, 0) + EXCLUDED.points,
+                              updated_at = NOW()",
+            )
+            .bind(season_id)
+            .bind(&user_ids)
+            .bind(&points)
+            .execute(&mut *conn)
+            .timed("UserWarsPointsRepository::award_many")
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to award wars points: {}", e)))?;
+        }
+
+        let awarded = awards
+            .iter()
+            .map(|(user_id, _)| user_id)
+            .collect::<HashSet<_>>()
+            .len() as u64;
+        tracing::info!(
+            "Awarded wars points to {} users in season {}",
+            awarded,
+            season_id
+        );
+
+        Ok(awarded)
+    }
 }
@@ -1,3 +1,4 @@
+use crate::db::timing::TimedQuery;
 use crate::errors::AppError;
 use uuid::Uuid;
 
@@ -13,6 +14,7 @@ impl UserWarsPointsRepository {
         .bind(user_id)
         .bind(season_id)
         .execute(&self.pool)
+        .timed("UserWarsPointsRepository::delete_wars_points")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to delete wars points: {}", e)))?;
 
@@ -37,6 +39,7 @@ impl UserWarsPointsRepository {
         )
         .bind(user_id)
         .execute(&self.pool)
+        .timed("UserWarsPointsRepository::delete_all_user_wars_points")
         .await
         .map_err(|e| {
             AppError::DatabaseError(format!("Failed to delete all user wars points: {}", e))
@@ -60,6 +63,7 @@ impl UserWarsPointsRepository {
         )
         .bind(season_id)
         .execute(&self.pool)
+        .timed("UserWarsPointsRepository::delete_season_wars_points")
         .await
         .map_err(|e| {
             AppError::DatabaseError(format!("Failed to delete season wars points: {}", e))
@@ -84,6 +88,7 @@ impl UserWarsPointsRepository {
         )
         .bind(season_id)
         .execute(&self.pool)
+        .timed("UserWarsPointsRepository::reset_season_points")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to reset season points: {}", e)))?;
 
@@ -1,9 +1,58 @@
-use crate::{errors::AppError, models::UserWarsPoints};
+use crate::{
+    db::timing::TimedQuery,
+    errors::AppError,
+    models::{LeaderboardEntry, UserWarsPoints, WarsPointAward},
+};
 use uuid::Uuid;
 
 use super::UserWarsPointsRepository;
 
+/// Wars points row joined with the user's wallet address
+type LeaderboardRow = (
+    Uuid,
+    Uuid,
+    i32,
+    f64,
+    Option<String>,
+    chrono::NaiveDateTime,
+    chrono::NaiveDateTime,
+    String,
+);
+
+fn leaderboard_entry(
+    (id, user_id, season_id, points, rank_badge, created_at, updated_at, wallet): LeaderboardRow,
+) -> (UserWarsPoints, String) {
+    (
+        UserWarsPoints {
+            id,
+            user_id,
+            season_id,
+            points,
+            rank_badge,
+            created_at,
+            updated_at,
+        },
+        wallet,
+    )
+}
+
 impl UserWarsPointsRepository {
+    /// Every player's recorded award for a lobby's game.
+    pub async fn find_awards(&self, lobby_id: Uuid) -> Result<Vec<WarsPointAward>, AppError> {
+        sqlx::query_as::<_, WarsPointAward>(
+            "SELECT lobby_id, user_id, season_id, game_id, points, active_players,
+                created_at, updated_at
+            FROM wars_point_awards
+            WHERE lobby_id = $1
+            ORDER BY user_id",
+        )
+        .bind(lobby_id)
+        .fetch_all(&self.pool)
+        .timed("UserWarsPointsRepository::find_awards")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to fetch wars point awards: {}", e)))
+    }
+
     /// Get a user's wars points for a specific season.
     pub async fn get_wars_points(
         &self,
@@ -18,6 +67,7 @@ impl UserWarsPointsRepository {
         .bind(user_id)
         .bind(season_id)
         .fetch_optional(&self.pool)
+        .timed("UserWarsPointsRepository::get_wars_points")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to fetch user wars points: {}", e)))?
         .ok_or_else(|| AppError::NotFound("Wars points not found for this season".into()))?;
@@ -38,6 +88,7 @@ impl UserWarsPointsRepository {
         )
         .bind(user_id)
         .fetch_all(&self.pool)
+        .timed("UserWarsPointsRepository::get_all_wars_points")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to fetch all wars points: {}", e)))?;
 
@@ -50,19 +101,7 @@ impl UserWarsPointsRepository {
         season_id: i32,
         limit: i64,
     ) -> Result<Vec<(UserWarsPoints, String)>, AppError> {
-        let results = sqlx::query_as::<
-            _,
-            (
-                Uuid,
-                Uuid,
-                i32,
-                f64,
-                Option<String>,
-                chrono::NaiveDateTime,
-                chrono::NaiveDateTime,
-                String,
-            ),
-        >(
+        let results = sqlx::query_as::<_, LeaderboardRow>(
             "SELECT uwp.id, uwp.user_id, uwp.season_id, uwp.points, uwp.rank_badge,
                     uwp.created_at, uwp.updated_at, u.wallet_address
             FROM user_wars_points uwp
@@ -74,32 +113,57 @@ impl UserWarsPointsRepository {
         .bind(season_id)
         .bind(limit)
         .fetch_all(&self.pool)
+        .timed("UserWarsPointsRepository::get_leaderboard")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to get leaderboard: {}", e)))?;
 
-        let leaderboard: Vec<(UserWarsPoints, String)> = results
-            .into_iter()
-            .map(
-                |(id, user_id, season_id, points, rank_badge, created_at, updated_at, wallet)| {
-                    (
-                        UserWarsPoints {
-                            id,
-                            user_id,
-                            season_id,
-                            points,
-                            rank_badge,
-                            created_at,
-                            updated_at,
-                        },
-                        wallet,
-                    )
-                },
-            )
-            .collect();
+        let leaderboard = results.into_iter().map(leaderboard_entry).collect();
 
         Ok(leaderboard)
     }
 
+    /// The top `limit` of a season's leaderboard with their ranks, or of one
+    /// game's leaderboard within the season when `game_id` is given.
+    pub async fn get_ranked_leaderboard(
+        &self,
+        season_id: i32,
+        game_id: Option<Uuid>,
+        limit: i64,
+    ) -> Result<Vec<LeaderboardEntry>, AppError> {
+        let query = match game_id {
+            None => {
+                "SELECT uwp.user_id, u.wallet_address, uwp.points,
+                        RANK() OVER (ORDER BY uwp.points DESC)::INT AS rank
+                FROM user_wars_points uwp
+                JOIN users u ON uwp.user_id = u.id
+                WHERE uwp.season_id = $1
+                ORDER BY rank, uwp.user_id
+                LIMIT $2"
+            }
+            Some(_) => {
+                "SELECT ugwp.user_id, u.wallet_address, ugwp.points,
+                        RANK() OVER (ORDER BY ugwp.points DESC)::INT AS rank
+                FROM user_game_wars_points ugwp
+                JOIN users u ON ugwp.user_id = u.id
+                WHERE ugwp.season_id = $1 AND ugwp.game_id = $3
+                ORDER BY rank, ugwp.user_id
+                LIMIT $2"
+            }
+        };
+
+        let mut query = sqlx::query_as::<_, LeaderboardEntry>(query)
+            .bind(season_id)
+            .bind(limit);
+        if let Some(game_id) = game_id {
+            query = query.bind(game_id);
+        }
+        query
+            .fetch_all(&self.pool)
+            .timed("UserWarsPointsRepository::get_ranked_leaderboard")
+            .await
+            .map_err(|e| AppError::DatabaseError(format!("Failed to get leaderboard: {}", e)))
+    }
+
     /// Get all users' wars points for a specific season.
     pub async fn get_season_wars_points(
         &self,
@@ -113,6 +177,7 @@ impl UserWarsPointsRepository {
         )
         .bind(season_id)
         .fetch_all(&self.pool)
+        .timed("UserWarsPointsRepository::get_season_wars_points")
         .await
         .map_err(|e| {
             AppError::DatabaseError(format!("Failed to fetch season wars points: {}", e))
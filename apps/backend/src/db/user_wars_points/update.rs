@@ -1,4 +1,8 @@
-use crate::{errors::AppError, models::UserWarsPoints};
+use crate::{
+    db::timing::TimedQuery,
+    errors::AppError,
+    models::{AwardCorrection, UserWarsPoints},
+};
 use uuid::Uuid;
 
 use super::UserWarsPointsRepository;
@@ -21,6 +25,7 @@ impl UserWarsPointsRepository {
         .bind(user_id)
         .bind(season_id)
         .fetch_optional(&self.pool)
+        .timed("UserWarsPointsRepository::add_wars_points")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to add wars points: {}", e)))?
         .ok_or_else(|| AppError::NotFound("Wars points entry not found".into()))?;
@@ -36,6 +41,145 @@ impl UserWarsPointsRepository {
         Ok(wars_points)
     }
 
+    /// Add to a user's wars points for one game in a season, starting from zero.
+    pub async fn add_game_wars_points(
+        &self,
+        user_id: Uuid,
+        season_id: i32,
+        game_id: Uuid,
+        points: f64,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            "INSERT INTO user_game_wars_points (user_id, season_id, game_id, points)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (user_id, season_id, game_id)
+            DO UPDATE SET points = user_game_wars_points.points + EXCLUDED.points,
+                          updated_at = NOW()",
+        )
+        .bind(user_id)
+        .bind(season_id)
+        .bind(game_id)
+        .bind(points)
+        .execute(&self.pool)
+        .timed("UserWarsPointsRepository::add_game_wars_points")
+        .await
+        .map_err(|e| AppError::DatabaseError(format!("Failed to add game wars points: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Replace a lobby's recorded awards with `awards`, moving each player's
+    /// season and game totals by the difference.
+    ///
+    /// The old award is reversed and the new one made in the same step, in
+    /// one transaction that holds the lobby's stored results locked, so runs
+    /// for the same lobby queue up and repeating one with the same awards
+    /// changes nothing. An award stays in the season and game it was first
+    /// made for; a player with no recorded award gets one in `season_id`, or
+    /// is skipped if there's no current season.
+    pub async fn correct_awards(
+        &self,
+        lobby_id: Uuid,
+        awards: &[(Uuid, f64)],
+        season_id: Option<i32>,
+        game_id: Option<Uuid>,
+    ) -> Result<Vec<AwardCorrection>, AppError> {
+        let db_error = |e: sqlx::Error| {
+            AppError::DatabaseError(format!("Failed to correct wars point awards: {}", e))
+        };
+        let mut transaction = self.pool.begin().await.map_err(db_error)?;
+
+        sqlx::query("SELECT 1 FROM lobby_results WHERE lobby_id = $1 FOR UPDATE")
+            .bind(lobby_id)
+            .execute(&mut *transaction)
+            .timed("UserWarsPointsRepository::correct_awards")
+            .await
+            .map_err(db_error)?;
+
+        let mut corrections = Vec::with_capacity(awards.len());
+        for &(user_id, points) in awards {
+            let prior = sqlx::query_as::<_, (i32, Option<Uuid>, f64)>(
+                "SELECT season_id, game_id, points FROM wars_point_awards
+                WHERE lobby_id = $1 AND user_id = $2
+                FOR UPDATE",
+            )
+            .bind(lobby_id)
+            .bind(user_id)
+            .fetch_optional(&mut *transaction)
+            .timed("UserWarsPointsRepository::correct_awards")
+            .await
+            .map_err(db_error)?;
+
+            let (award_season, award_game, previous) = match (prior, season_id) {
+                (Some(prior), _) => prior,
+                (None, Some(season_id)) => (season_id, game_id, 0.0),
+                (None, None) => continue,
+            };
+
+            let delta = points - previous;
+            if delta != 0.0 {
+                sqlx::query(
+                    "INSERT INTO user_wars_points (user_id, season_id, points)
+                    VALUES ($1, $2, $3)
+                    ON CONFLICT (user_id, season_id)
+                    DO UPDATE SET points = COALESCE(user_wars_points.points, 0) + EXCLUDED.points,
+                                  updated_at = NOW()",
+                )
+                .bind(user_id)
+                .bind(award_season)
+                .bind(delta)
+                .execute(&mut *transaction)
+                .timed("UserWarsPointsRepository::correct_awards")
+                .await
+                .map_err(db_error)?;
+
+                if let Some(award_game) = award_game {
+                    sqlx::query(
+                        "INSERT INTO user_game_wars_points (user_id, season_id, game_id, points)
+                        VALUES ($1, $2, $3, $4)
+                        ON CONFLICT (user_id, season_id, game_id)
+                        DO UPDATE SET points = user_game_wars_points.points + EXCLUDED.points,
+                                      updated_at = NOW()",
+                    )
+                    .bind(user_id)
+                    .bind(award_season)
+                    .bind(award_game)
+                    .bind(delta)
+                    .execute(&mut *transaction)
+                    .timed("UserWarsPointsRepository::correct_awards")
+                    .await
+                    .map_err(db_error)?;
+                }
+
+                sqlx::query(
+                    "INSERT INTO wars_point_awards (lobby_id, user_id, season_id, game_id, points)
+                    VALUES ($1, $2, $3, $4, $5)
+                    ON CONFLICT (lobby_id, user_id)
+                    DO UPDATE SET points = EXCLUDED.points, updated_at = NOW()",
+                )
+                .bind(lobby_id)
+                .bind(user_id)
+                .bind(award_season)
+                .bind(award_game)
+                .bind(points)
+                .execute(&mut *transaction)
+                .timed("UserWarsPointsRepository::correct_awards")
+                .await
+                .map_err(db_error)?;
+            }
+
+            corrections.push(AwardCorrection {
+                user_id,
+                previous,
+                points,
+            });
+        }
+
+        transaction.commit().await.map_err(db_error)?;
+
+        Ok(corrections)
+    }
+
     /// Set a user's wars points to an explicit value.
     pub async fn set_wars_points(
         &self,
@@ -53,6 +197,7 @@ impl UserWarsPointsRepository {
         .bind(user_id)
         .bind(season_id)
         .fetch_optional(&self.pool)
+        .timed("UserWarsPointsRepository::set_wars_points")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to set wars points: {}", e)))?
         .ok_or_else(|| AppError::NotFound("Wars points entry not found".into()))?;
@@ -84,6 +229,7 @@ impl UserWarsPointsRepository {
         .bind(user_id)
         .bind(season_id)
         .fetch_optional(&self.pool)
+        .timed("UserWarsPointsRepository::update_rank_badge")
         .await
         .map_err(|e| AppError::DatabaseError(format!("Failed to update rank badge: {}", e)))?
         .ok_or_else(|| AppError::NotFound("Wars points entry not found".into()))?;
@@ -97,41 +243,4 @@ impl UserWarsPointsRepository {
 
         Ok(wars_points)
     }
-
-    /// Bulk-update wars points for many users; returns number updated.
-    pub async fn bulk_add_points(&self, updates: Vec<(Uuid, i32, f64)>) -> Result<u64, AppError> {
-        let mut transaction =
-            self.pool.begin().await.map_err(|e| {
-                AppError::DatabaseError(format!("Failed to start transaction: {}", e))
-            })?;
-
-        let mut total_updated = 0u64;
-
-        for (user_id, season_id, points_to_add) in updates {
-            let result = sqlx::query(
-                "UPDATE user_wars_points
-                SET points = points + $1, updated_at = NOW()
-                WHERE user_id = $2 AND season_id = $3",
-            )
-            .bind(points_to_add)
-            .bind(user_id)
-            .bind(season_id)
-            .execute(&mut *transaction)
-            .await
-            .map_err(|e| {
-                AppError::DatabaseError(format!("Failed to bulk update wars points: {}", e))
-            })?;
-
-            total_updated += result.rows_affected();
-        }
-
-        transaction
-            .commit()
-            .await
-            .map_err(|e| AppError::DatabaseError(format!("Failed to commit transaction: {}", e)))?;
-
-        tracing::info!("Bulk updated {} wars points entries", total_updated);
-
-        Ok(total_updated)
-    }
 }
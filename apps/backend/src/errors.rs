@@ -2,8 +2,12 @@ use axum::http::StatusCode;
 use redis::RedisError;
 use thiserror::Error;
 
+use crate::feature_flags::Feature;
+use crate::games::GameError;
 use crate::models::game::PlayerCountError;
 use crate::models::lobby::LobbyAmountError;
+use crate::models::lobby_invite::InviteError;
+use crate::models::payout::PayoutError;
 use crate::models::season::DateRangeError;
 use crate::models::username::UsernameError;
 use crate::models::wallet_address::WalletAddressError;
@@ -40,6 +44,22 @@ pub enum AppError {
     #[error("Invalid Input: {0}")]
     AlreadyExists(String),
 
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
+    #[error("Too many requests: {0}")]
+    RateLimited(String),
+
+    #[error("Service unavailable: {0}")]
+    ServiceUnavailable(String),
+
+    /// The feature is switched off by a feature flag
+    #[error("{} is currently disabled", .0.label())]
+    FeatureDisabled(Feature),
+
     #[error("Env error: {0}")]
     EnvError(String),
 
@@ -67,6 +87,16 @@ pub enum AppError {
     #[error("Invalid lobby amount: {0}")]
     LobbyAmountError(#[from] LobbyAmountError),
 
+    #[error("Invalid invite: {0}")]
+    InviteError(#[from] InviteError),
+
+    #[error("Invalid payout table: {0}")]
+    PayoutError(#[from] PayoutError),
+
+    /// A game engine rejected a player's action
+    #[error("Bad request: {0}")]
+    GameError(GameError),
+
     #[error("Invalid email address: {0}")]
     EmailAddressError(String),
 
@@ -90,6 +120,11 @@ impl AppError {
             AppError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg.clone()),
             AppError::InvalidInput(msg) => (StatusCode::UNAUTHORIZED, msg.clone()),
             AppError::AlreadyExists(msg) => (StatusCode::UNAUTHORIZED, msg.clone()),
+            AppError::Conflict(msg) => (StatusCode::CONFLICT, msg.clone()),
+            AppError::Forbidden(msg) => (StatusCode::FORBIDDEN, msg.clone()),
+            AppError::RateLimited(msg) => (StatusCode::TOO_MANY_REQUESTS, msg.clone()),
+            AppError::ServiceUnavailable(msg) => (StatusCode::SERVICE_UNAVAILABLE, msg.clone()),
+            AppError::FeatureDisabled(_) => (StatusCode::SERVICE_UNAVAILABLE, self.to_string()),
             AppError::EnvError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg.clone()),
             AppError::DatabaseError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg.clone()),
             AppError::InternalError => (
@@ -102,6 +137,13 @@ impl AppError {
             AppError::DateRangeError(e) => (StatusCode::BAD_REQUEST, e.to_string()),
             AppError::PlayerCountError(e) => (StatusCode::BAD_REQUEST, e.to_string()),
             AppError::LobbyAmountError(e) => (StatusCode::BAD_REQUEST, e.to_string()),
+            AppError::InviteError(InviteError::InvalidToken) => (
+                StatusCode::UNAUTHORIZED,
+                InviteError::InvalidToken.to_string(),
+            ),
+            AppError::InviteError(e) => (StatusCode::BAD_REQUEST, e.to_string()),
+            AppError::PayoutError(e) => (StatusCode::BAD_REQUEST, e.to_string()),
+            AppError::GameError(e) => (StatusCode::BAD_REQUEST, e.to_string()),
             AppError::EmailAddressError(e) => (StatusCode::BAD_REQUEST, e.clone()),
             AppError::ReadError(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.clone()),
             AppError::FetchError(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.clone()),
@@ -0,0 +1,255 @@
+// Feature flags: switch features off without a deploy
+//
+// Each flag starts from its configured default (`FEATURE_FLAGS`, e.g.
+// `matchmaking=off,predictions=on`; unlisted features are on). An admin can
+// override a flag at runtime; overrides live in Redis, so every instance sees
+// them on the next check, and clearing one falls back to the default.
+//
+// Handlers check a flag with `FeatureFlags::ensure_enabled`, which fails with
+// `AppError::FeatureDisabled`.
+
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+
+use crate::{errors::AppError, models::RedisKey, state::AppState, state::RedisClient};
+
+/// A feature that can be switched off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Feature {
+    /// Quick-play queue
+    Matchmaking,
+    /// Lobbies against a practice bot
+    PracticeLobbies,
+    /// Spectator winner predictions
+    Predictions,
+}
+
+impl Feature {
+    pub const ALL: [Feature; 3] = [
+        Feature::Matchmaking,
+        Feature::PracticeLobbies,
+        Feature::Predictions,
+    ];
+
+    /// Name used in config, Redis and the admin API
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Feature::Matchmaking => "matchmaking",
+            Feature::PracticeLobbies => "practiceLobbies",
+            Feature::Predictions => "predictions",
+        }
+    }
+
+    /// How the feature is described to players
+    pub fn label(&self) -> &'static str {
+        match self {
+            Feature::Matchmaking => "Matchmaking",
+            Feature::PracticeLobbies => "Practice lobbies",
+            Feature::Predictions => "Predictions",
+        }
+    }
+}
+
+impl fmt::Display for Feature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Feature {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Feature::ALL
+            .into_iter()
+            .find(|feature| feature.as_str() == s)
+            .ok_or_else(|| format!("unknown feature '{}'", s))
+    }
+}
+
+/// Flag values used when no override is set.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FeatureDefaults {
+    /// Features configured explicitly; the rest are on
+    pub configured: HashMap<Feature, bool>,
+}
+
+impl FeatureDefaults {
+    /// Read `FEATURE_FLAGS`; unset leaves every feature on.
+    pub fn from_env() -> Result<Self, String> {
+        Self::parse(&std::env::var("FEATURE_FLAGS").unwrap_or_default())
+    }
+
+    /// Parse comma-separated `FEATURE=on|off` entries,
+    /// e.g. `matchmaking=off,predictions=on`.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut configured = HashMap::new();
+
+        for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (name, value) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("FEATURE_FLAGS: '{}' is missing '=on|off'", entry))?;
+            let feature = name
+                .trim()
+                .parse::<Feature>()
+                .map_err(|e| format!("FEATURE_FLAGS: {}", e))?;
+            let enabled = match value.trim() {
+                "on" | "true" | "1" => true,
+                "off" | "false" | "0" => false,
+                other => {
+                    return Err(format!(
+                        "FEATURE_FLAGS: '{}' for {} is not on or off",
+                        other,
+                        feature.as_str()
+                    ));
+                }
+            };
+            configured.insert(feature, enabled);
+        }
+
+        Ok(Self { configured })
+    }
+
+    pub fn enabled(&self, feature: Feature) -> bool {
+        self.configured.get(&feature).copied().unwrap_or(true)
+    }
+}
+
+/// A flag's current value, as returned to admins.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeatureFlagStatus {
+    pub feature: Feature,
+    pub enabled: bool,
+    /// Value from config, used while no override is set
+    pub default: bool,
+    /// Whether an admin override is in effect
+    pub overridden: bool,
+}
+
+/// Config defaults plus the runtime overrides in Redis.
+#[derive(Clone)]
+pub struct FeatureFlags {
+    redis: RedisClient,
+    defaults: FeatureDefaults,
+}
+
+impl FeatureFlags {
+    pub fn new(redis: RedisClient, defaults: FeatureDefaults) -> Self {
+        Self { redis, defaults }
+    }
+
+    /// Flags for this instance's config and Redis
+    pub fn from_state(state: &AppState) -> Self {
+        Self::new(state.redis.clone(), state.config.feature_defaults.clone())
+    }
+
+    /// Admin overrides currently set
+    async fn overrides(&self) -> Result<HashMap<Feature, bool>, AppError> {
+        let mut conn = self.redis.get().await?;
+        let raw: HashMap<String, String> = conn
+            .hgetall(RedisKey::feature_flags())
+            .await
+            .map_err(AppError::RedisCommandError)?;
+
+        // Overrides for features that no longer exist are ignored
+        Ok(raw
+            .into_iter()
+            .filter_map(|(name, value)| Some((name.parse().ok()?, value == "1")))
+            .collect())
+    }
+
+    pub async fn enabled(&self, feature: Feature) -> Result<bool, AppError> {
+        Ok(self.status(feature).await?.enabled)
+    }
+
+    pub async fn status(&self, feature: Feature) -> Result<FeatureFlagStatus, AppError> {
+        let overrides = self.overrides().await?;
+        Ok(self.status_with(feature, &overrides))
+    }
+
+    /// Every flag, in `Feature::ALL` order
+    pub async fn list(&self) -> Result<Vec<FeatureFlagStatus>, AppError> {
+        let overrides = self.overrides().await?;
+        Ok(Feature::ALL
+            .into_iter()
+            .map(|feature| self.status_with(feature, &overrides))
+            .collect())
+    }
+
+    fn status_with(
+        &self,
+        feature: Feature,
+        overrides: &HashMap<Feature, bool>,
+    ) -> FeatureFlagStatus {
+        let default = self.defaults.enabled(feature);
+        let overridden = overrides.get(&feature).copied();
+        FeatureFlagStatus {
+            feature,
+            enabled: overridden.unwrap_or(default),
+            default,
+            overridden: overridden.is_some(),
+        }
+    }
+
+    /// Override a flag, or with `None` go back to its default.
+    pub async fn set(
+        &self,
+        feature: Feature,
+        enabled: Option<bool>,
+    ) -> Result<FeatureFlagStatus, AppError> {
+        let mut conn = self.redis.get().await?;
+        let key = RedisKey::feature_flags();
+        let _: () = match enabled {
+            Some(enabled) => {
+                conn.hset(&key, feature.as_str(), if enabled { "1" } else { "0" })
+                    .await
+            }
+            None => conn.hdel(&key, feature.as_str()).await,
+        }
+        .map_err(AppError::RedisCommandError)?;
+
+        tracing::warn!("feature {} override set to {:?}", feature.as_str(), enabled);
+        self.status(feature).await
+    }
+
+    /// Fail with `FeatureDisabled` if `feature` is off.
+    pub async fn ensure_enabled(&self, feature: Feature) -> Result<(), AppError> {
+        if !self.enabled(feature).await? {
+            return Err(AppError::FeatureDisabled(feature));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_parse_and_leave_unlisted_features_on() {
+        let defaults = FeatureDefaults::parse("matchmaking=off, predictions=on").unwrap();
+        assert!(!defaults.enabled(Feature::Matchmaking));
+        assert!(defaults.enabled(Feature::Predictions));
+        assert!(defaults.enabled(Feature::PracticeLobbies));
+
+        assert!(FeatureDefaults::parse("").unwrap().configured.is_empty());
+        assert!(FeatureDefaults::parse("rematch=on").is_err());
+        assert!(FeatureDefaults::parse("matchmaking").is_err());
+        assert!(FeatureDefaults::parse("matchmaking=maybe").is_err());
+    }
+
+    #[test]
+    fn test_feature_names_round_trip() {
+        for feature in Feature::ALL {
+            assert_eq!(feature.as_str().parse::<Feature>(), Ok(feature));
+            assert_eq!(
+                serde_json::to_value(feature).unwrap(),
+                serde_json::json!(feature.as_str())
+            );
+        }
+    }
+}
@@ -0,0 +1,154 @@
+// Coin Flip practice bot
+//
+// A `CoinFlipBot` fills the second seat of a practice lobby. When a round
+// starts it picks a think time; once that passes, the engine's `tick` asks it
+// for a call and records it like a player action.
+//
+// The bot is the house: it is dealt the round's flip and calls it right as
+// often as its difficulty allows. Easy bots lose most calls, medium ones are
+// a fair coin and hard ones win most. Every think time is well inside
+// ROUND_TIMEOUT_SECS, so a bot never misses a call.
+
+use std::time::{Duration, Instant};
+
+use uuid::Uuid;
+
+use super::message::CoinSide;
+use crate::{games::GameRng, models::BotDifficulty};
+
+/// Shortest and longest time a bot of `difficulty` thinks before calling
+pub fn think_time_range(difficulty: BotDifficulty) -> (Duration, Duration) {
+    match difficulty {
+        BotDifficulty::Easy => (Duration::from_secs(3), Duration::from_secs(6)),
+        BotDifficulty::Medium => (Duration::from_secs(2), Duration::from_secs(4)),
+        BotDifficulty::Hard => (Duration::from_millis(500), Duration::from_millis(1500)),
+    }
+}
+
+/// Percentage of calls a bot of `difficulty` gets right
+pub fn accuracy(difficulty: BotDifficulty) -> usize {
+    match difficulty {
+        BotDifficulty::Easy => 35,
+        BotDifficulty::Medium => 50,
+        BotDifficulty::Hard => 65,
+    }
+}
+
+/// Bot opponent for practice games
+#[derive(Debug, Clone)]
+pub struct CoinFlipBot {
+    pub user_id: Uuid,
+    pub difficulty: BotDifficulty,
+    /// When the bot calls in the current round (None once it has)
+    ready_at: Option<Instant>,
+}
+
+impl CoinFlipBot {
+    pub fn new(difficulty: BotDifficulty) -> Self {
+        Self {
+            user_id: Uuid::new_v4(),
+            difficulty,
+            ready_at: None,
+        }
+    }
+
+    /// Display name shown to the other player
+    pub fn name(&self) -> String {
+        let level = match self.difficulty {
+            BotDifficulty::Easy => "Easy",
+            BotDifficulty::Medium => "Medium",
+            BotDifficulty::Hard => "Hard",
+        };
+        format!("FlipBot ({})", level)
+    }
+
+    /// A round started at `now`; returns how long the bot will think
+    pub fn start_round(&mut self, now: Instant, rng: &mut GameRng) -> Duration {
+        let (min, max) = think_time_range(self.difficulty);
+        let think =
+            Duration::from_millis(rng.in_range(min.as_millis() as u64..=max.as_millis() as u64));
+        self.ready_at = Some(now + think);
+        think
+    }
+
+    /// Whether the bot has finished thinking in the current round
+    pub fn is_ready(&self, now: Instant) -> bool {
+        self.ready_at.is_some_and(|at| now >= at)
+    }
+
+    /// The bot has called (or the round is over)
+    pub fn end_round(&mut self) {
+        self.ready_at = None;
+    }
+
+    /// The bot's call for a round that lands on `flip`
+    pub fn call(&self, flip: CoinSide, rng: &mut GameRng) -> CoinSide {
+        if rng.below(100) < accuracy(self.difficulty) {
+            flip
+        } else {
+            flip.other()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_harder_bots_call_it_right_more_often() {
+        let right_calls = |difficulty| {
+            let bot = CoinFlipBot::new(difficulty);
+            let mut rng = GameRng::new(3);
+            (0..1000)
+                .filter(|_| bot.call(CoinSide::Heads, &mut rng) == CoinSide::Heads)
+                .count()
+        };
+        let easy = right_calls(BotDifficulty::Easy);
+        let medium = right_calls(BotDifficulty::Medium);
+        let hard = right_calls(BotDifficulty::Hard);
+
+        assert!(easy < medium && medium < hard);
+        assert!((250..450).contains(&easy));
+        assert!((550..750).contains(&hard));
+    }
+
+    #[test]
+    fn test_bots_with_the_same_seed_call_alike() {
+        let play = |seed| {
+            let mut rng = GameRng::new(seed);
+            let mut bot = CoinFlipBot::new(BotDifficulty::Medium);
+            (0..8)
+                .map(|_| {
+                    let think = bot.start_round(Instant::now(), &mut rng);
+                    (think, bot.call(CoinSide::Tails, &mut rng))
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(play(5), play(5));
+    }
+
+    #[test]
+    fn test_bot_waits_out_its_think_time() {
+        for difficulty in [
+            BotDifficulty::Easy,
+            BotDifficulty::Medium,
+            BotDifficulty::Hard,
+        ] {
+            let (min, max) = think_time_range(difficulty);
+            let mut bot = CoinFlipBot::new(difficulty);
+            let start = Instant::now();
+
+            assert!(!bot.is_ready(start), "not ready before the round");
+            let think = bot.start_round(start, &mut GameRng::new(1));
+            assert!(think >= min && think <= max);
+            assert!(max < Duration::from_secs(super::super::ROUND_TIMEOUT_SECS));
+
+            assert!(!bot.is_ready(start + think - Duration::from_millis(1)));
+            assert!(bot.is_ready(start + think));
+
+            bot.end_round();
+            assert!(!bot.is_ready(start + max));
+        }
+    }
+}
@@ -0,0 +1,858 @@
+// Coin Flip Game Engine
+//
+// Core game logic including:
+// - CoinFlipEngine struct and implementation
+// - GameEngine trait implementation
+// - Round resolution and prize calculation
+//
+// The game has no background loop: the room engine's tick starts rounds,
+// plays the bot's calls and flips the coin once every call is in or the
+// round times out.
+
+use crate::{
+    db::{
+        lobby::LobbyRepository, lobby_result::LobbyResultRepository,
+        player_state::PlayerStateRepository, signed_result::SignedResultRepository,
+    },
+    errors::AppError,
+    games::{COIN_FLIP_GAME_ID, GameEngine, GameError, GameResults, GameRng, common::*},
+    models::{BotDifficulty, Lobby, PayoutTable, PlayerState, SignedResults},
+    state::AppState,
+    ws::{broadcast, room::messages::RoomServerMessage},
+};
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+use serde_json::{Value, json};
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+use uuid::Uuid;
+
+use super::bot::CoinFlipBot;
+use super::message::{CoinFlipAction, CoinFlipEvent, CoinSide};
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+pub const ROUND_TIMEOUT_SECS: u64 = 10;
+
+/// JSON Schema of the game's settings, with the defaults this server plays by.
+pub fn config_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "roundTimeoutSecs": { "type": "integer", "default": ROUND_TIMEOUT_SECS },
+        },
+    })
+}
+
+/// Players knocked out when the coin lands on `flip`: everyone in `active`
+/// who called the other side or didn't call, in seat order.
+///
+/// Empty when nobody called it right, since the round is flipped again
+/// rather than knocking everyone out.
+pub fn knocked_out(active: &[Uuid], calls: &HashMap<Uuid, CoinSide>, flip: CoinSide) -> Vec<Uuid> {
+    let out: Vec<Uuid> = active
+        .iter()
+        .filter(|id| calls.get(id) != Some(&flip))
+        .copied()
+        .collect();
+    if out.len() == active.len() {
+        Vec::new()
+    } else {
+        out
+    }
+}
+
+/// Finishing order, winner first: players still in by right calls (ties in
+/// seat order), then each round's losers, latest round first.
+pub fn finishing_order(mut standing: Vec<(Uuid, i32)>, knockouts: &[Vec<Uuid>]) -> Vec<Uuid> {
+    standing.sort_by_key(|(_, score)| Reverse(*score));
+    standing
+        .into_iter()
+        .map(|(id, _)| id)
+        .chain(knockouts.iter().rev().flatten().copied())
+        .collect()
+}
+
+// ============================================================================
+// Game Engine
+// ============================================================================
+
+/// Coin Flip game engine
+pub struct CoinFlipEngine {
+    lobby_id: Uuid,
+    players: HashMap<Uuid, GamePlayerState>,
+    player_states: HashMap<Uuid, PlayerState>,
+    /// Seats and who is still in; nobody takes turns
+    seating: TurnRotation,
+    /// Current round, 0 until the first one starts
+    round: usize,
+    /// Where the coin lands this round, drawn when the round starts
+    flip: Option<CoinSide>,
+    /// Calls made so far this round
+    calls: HashMap<Uuid, CoinSide>,
+    /// When the current round started (server epoch millis)
+    round_started_ms: u64,
+    /// When calls for the current round close
+    round_deadline: Option<Instant>,
+    /// Players knocked out, round by round, in seat order within a round
+    knockouts: Vec<Vec<Uuid>>,
+    total_players: usize,
+    finished: bool,
+    results: Option<GameResults>,
+
+    // Prize/points calculation context
+    entry_amount: Option<Decimal>,
+    current_amount: Option<Decimal>,
+    is_sponsored: bool,
+    creator_id: Option<Uuid>,
+    /// The lobby's own payout table; the default for the player count if unset
+    payouts: Option<PayoutTable>,
+
+    /// Practice bots seated in this game, by their player ID
+    bots: HashMap<Uuid, CoinFlipBot>,
+    /// Seed the seats are drawn from, from `set_seeds`; without one players
+    /// keep the order they were passed in
+    seating_seed: Option<u64>,
+    /// Seed `rng` was started from, recorded on the lobby once the game ends
+    game_seed: Option<u64>,
+    /// Draws the flips and bot calls
+    rng: GameRng,
+
+    state: AppState,
+}
+
+impl CoinFlipEngine {
+    pub fn new(lobby_id: Uuid, state: AppState) -> Self {
+        let rng = GameRng::new(state.config.game_seed());
+        Self {
+            lobby_id,
+            players: HashMap::new(),
+            player_states: HashMap::new(),
+            seating: TurnRotation::new(Vec::new()),
+            round: 0,
+            flip: None,
+            calls: HashMap::new(),
+            round_started_ms: 0,
+            round_deadline: None,
+            knockouts: Vec::new(),
+            total_players: 0,
+            finished: false,
+            results: None,
+            entry_amount: None,
+            current_amount: None,
+            is_sponsored: false,
+            creator_id: None,
+            payouts: None,
+            bots: HashMap::new(),
+            seating_seed: None,
+            game_seed: None,
+            rng,
+            state,
+        }
+    }
+
+    /// Practice games have a bot seated; their results aren't saved
+    fn is_practice(&self) -> bool {
+        !self.bots.is_empty()
+    }
+
+    /// How the pot is split between `participants` players
+    fn payout_table(&self, participants: usize) -> PayoutTable {
+        self.payouts
+            .clone()
+            .unwrap_or_else(|| PayoutTable::default_for(participants))
+    }
+
+    /// Calculate prize for a given rank
+    fn calculate_prize(&self, rank: usize, participants: usize) -> Option<Decimal> {
+        let total_pool = self.current_amount?;
+
+        if total_pool <= Decimal::ZERO {
+            return None;
+        }
+
+        let shares = self
+            .payout_table(participants)
+            .shares(total_pool, participants);
+        let prize = shares.get(rank.checked_sub(1)?).copied()?;
+
+        if prize > Decimal::ZERO {
+            Some(prize)
+        } else {
+            None
+        }
+    }
+
+    /// Build WarsPointContext for a player result
+    fn build_wars_point_context(
+        &self,
+        user_id: Uuid,
+        rank: usize,
+        prize: Option<Decimal>,
+    ) -> WarsPointContext {
+        WarsPointContext {
+            user_id,
+            game_id: COIN_FLIP_GAME_ID,
+            rank,
+            prize,
+            participants: self.total_players,
+            entry_amount: self.entry_amount,
+            current_amount: self.current_amount,
+            is_sponsored: self.is_sponsored,
+            creator_id: self.creator_id,
+            active_players: self.seating.active_count(),
+        }
+    }
+
+    /// The current round as announced to the room
+    fn round_event(&self) -> CoinFlipEvent {
+        CoinFlipEvent::Round {
+            round: self.round,
+            timeout_secs: ROUND_TIMEOUT_SECS,
+            round_deadline_ms: deadline_ms(self.round_started_ms, ROUND_TIMEOUT_SECS),
+            server_time_ms: server_time_ms(),
+        }
+    }
+
+    /// Draw the next flip, get the bots thinking and announce the round
+    async fn start_round(&mut self) {
+        self.round += 1;
+        self.calls.clear();
+        self.flip = Some(if self.rng.below(2) == 0 {
+            CoinSide::Heads
+        } else {
+            CoinSide::Tails
+        });
+
+        let now = Instant::now();
+        self.round_deadline = Some(now + Duration::from_secs(ROUND_TIMEOUT_SECS));
+        self.round_started_ms = server_time_ms();
+        let active = self.seating.active_players();
+        for (bot_id, bot) in self.bots.iter_mut() {
+            if active.contains(bot_id) {
+                bot.start_round(now, &mut self.rng);
+            }
+        }
+
+        broadcast::broadcast_game_message(
+            &self.state,
+            self.lobby_id,
+            serde_json::to_value(self.round_event()).unwrap_or_default(),
+        )
+        .await;
+    }
+
+    /// Whether `user_id` may call right now
+    fn check_can_call(&self, user_id: Uuid) -> Result<(), GameError> {
+        if self.finished {
+            return Err(GameError::GameFinished);
+        }
+        if self.round == 0 {
+            return Err(GameError::GameNotStarted);
+        }
+        if !self.players.contains_key(&user_id) {
+            return Err(GameError::NotInGame);
+        }
+        if !self.seating.active_players().contains(&user_id) {
+            return Err(GameError::AlreadyEliminated);
+        }
+        if self.calls.contains_key(&user_id) {
+            return Err(GameError::InvalidAction(
+                "You already called this round".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Record `user_id`'s call for the current round
+    fn call(&mut self, user_id: Uuid, side: CoinSide) -> Result<Vec<Value>, AppError> {
+        self.check_can_call(user_id)?;
+        self.calls.insert(user_id, side);
+
+        self.player_states
+            .get(&user_id)
+            .cloned()
+            .map(|player| CoinFlipEvent::Called { player })
+            .into_iter()
+            .map(|e| serde_json::to_value(e).map_err(|e| AppError::Serialization(e.to_string())))
+            .collect()
+    }
+
+    /// Make the calls of bots that have finished thinking
+    fn play_bot_calls(&mut self) -> Result<Vec<Value>, AppError> {
+        let Some(flip) = self.flip else {
+            return Ok(Vec::new());
+        };
+        let now = Instant::now();
+        let ready: Vec<Uuid> = self
+            .bots
+            .values()
+            .filter(|bot| bot.is_ready(now))
+            .map(|bot| bot.user_id)
+            .collect();
+
+        let mut events = Vec::new();
+        for bot_id in ready {
+            let Some(bot) = self.bots.get_mut(&bot_id) else {
+                continue;
+            };
+            bot.end_round();
+            let side = bot.call(flip, &mut self.rng);
+            events.extend(self.call(bot_id, side)?);
+        }
+        Ok(events)
+    }
+
+    /// Flip the coin and knock out everyone who called it wrong
+    async fn resolve_round(&mut self) {
+        let Some(flip) = self.flip.take() else {
+            return;
+        };
+        for bot in self.bots.values_mut() {
+            bot.end_round();
+        }
+
+        let flip_event = CoinFlipEvent::Flip {
+            round: self.round,
+            side: flip,
+        };
+        broadcast::broadcast_game_message(
+            &self.state,
+            self.lobby_id,
+            serde_json::to_value(&flip_event).unwrap_or_default(),
+        )
+        .await;
+
+        let active = self.seating.active_players();
+        for user_id in &active {
+            if self.calls.get(user_id) == Some(&flip)
+                && let Some(player) = self.players.get_mut(user_id)
+            {
+                player.score += 1;
+            }
+        }
+
+        let out = knocked_out(&active, &self.calls, flip);
+        if out.is_empty() {
+            if !active.iter().any(|id| self.calls.get(id) == Some(&flip)) {
+                let replay = CoinFlipEvent::Replay { round: self.round };
+                broadcast::broadcast_game_message(
+                    &self.state,
+                    self.lobby_id,
+                    serde_json::to_value(&replay).unwrap_or_default(),
+                )
+                .await;
+            }
+            return;
+        }
+
+        // The round's losers take the last places still open, in seat order
+        let first_rank = active.len() - out.len() + 1;
+        for user_id in &out {
+            self.seating.eliminate_player(*user_id);
+            if let Some(player) = self.players.get_mut(user_id) {
+                player.eliminate();
+            }
+        }
+        for (offset, user_id) in out.iter().enumerate() {
+            let reason = if self.calls.contains_key(user_id) {
+                "Called the wrong side"
+            } else {
+                "Didn't call in time"
+            };
+            self.eliminate_player(*user_id, first_rank + offset, reason)
+                .await;
+        }
+        self.knockouts.push(out);
+
+        let count_event = CoinFlipEvent::PlayersCount {
+            remaining: self.seating.active_count(),
+            total: self.total_players,
+        };
+        broadcast::broadcast_game_message(
+            &self.state,
+            self.lobby_id,
+            serde_json::to_value(&count_event).unwrap_or_default(),
+        )
+        .await;
+    }
+
+    /// Save a knocked out player's result and send them GameOver
+    async fn eliminate_player(&mut self, player_id: Uuid, rank: usize, reason: &str) {
+        let prize = self.calculate_prize(rank, self.total_players);
+
+        let ctx = self.build_wars_point_context(player_id, rank, prize);
+        let wars_point = if self.is_practice() {
+            0.0
+        } else {
+            match save_player_result(&self.state, self.lobby_id, &ctx).await {
+                Ok(result) => result.wars_point,
+                Err(e) => {
+                    tracing::error!("Failed to save player result: {}", e);
+                    calculate_wars_point(&ctx)
+                }
+            }
+        };
+
+        if let Some(ps) = self.player_states.get_mut(&player_id) {
+            ps.rank = Some(rank);
+            ps.prize = prize;
+            ps.wars_point = Some(wars_point);
+        }
+
+        if let Some(player) = self.player_states.get(&player_id).cloned() {
+            let event = CoinFlipEvent::Eliminated {
+                player,
+                reason: reason.to_string(),
+            };
+            broadcast::broadcast_game_message(
+                &self.state,
+                self.lobby_id,
+                serde_json::to_value(&event).unwrap_or_default(),
+            )
+            .await;
+
+            let game_over = RoomServerMessage::GameOver {
+                rank,
+                prize,
+                wars_point,
+                signed_results: None,
+            };
+            broadcast::broadcast_user(&self.state, player_id, &game_over).await;
+        }
+    }
+
+    /// End the game and calculate final standings
+    /// Sends GameOver to the players still in and FinalStanding to room
+    async fn end_game(&mut self) {
+        if self.finished {
+            return;
+        }
+        self.finished = true;
+
+        let active_player_ids = self.seating.active_players();
+        let standing = active_player_ids
+            .iter()
+            .map(|id| (*id, self.players.get(id).map_or(0, |p| p.score)))
+            .collect();
+        let mut results =
+            GameResults::from_ordered_players(finishing_order(standing, &self.knockouts))
+                .with_stakes(self.entry_amount, self.current_amount);
+        for ranking in &mut results.rankings {
+            ranking.score = self.players.get(&ranking.user_id).map(|p| p.score);
+        }
+        if self.is_practice() {
+            results = results.unranked();
+        } else if let Some(pool) = self.current_amount {
+            results.apply_payouts(pool, &self.payout_table(self.total_players));
+        }
+
+        let signed_results = if results.ranked {
+            self.sign_results(&results).await
+        } else {
+            None
+        };
+
+        let mut final_standings: Vec<PlayerState> = Vec::new();
+        let state = self.state.clone();
+        let lobby_id = self.lobby_id;
+
+        for ranking in &results.rankings {
+            let prize = ranking.prize;
+            let is_active = active_player_ids.contains(&ranking.user_id);
+
+            // Knocked out players were saved when they went out
+            let wars_point = if self.is_practice() {
+                0.0
+            } else if is_active {
+                let ctx = self.build_wars_point_context(ranking.user_id, ranking.rank, prize);
+                match save_player_result(&state, lobby_id, &ctx).await {
+                    Ok(result) => result.wars_point,
+                    Err(e) => {
+                        tracing::error!("Failed to save player result: {}", e);
+                        calculate_wars_point(&ctx)
+                    }
+                }
+            } else {
+                self.player_states
+                    .get(&ranking.user_id)
+                    .and_then(|ps| ps.wars_point)
+                    .unwrap_or_default()
+            };
+
+            if let Some(player_state) = self.player_states.get_mut(&ranking.user_id) {
+                player_state.rank = Some(ranking.rank);
+                player_state.prize = prize;
+                player_state.wars_point = Some(wars_point);
+                final_standings.push(player_state.clone());
+            }
+
+            if is_active {
+                let game_over = RoomServerMessage::GameOver {
+                    rank: ranking.rank,
+                    prize,
+                    wars_point,
+                    signed_results: signed_results.clone(),
+                };
+                broadcast::broadcast_user(&state, ranking.user_id, &game_over).await;
+            }
+        }
+
+        let lobby_repo = LobbyRepository::new(state.postgres.clone());
+        if let Err(e) = lobby_repo.record_finished(lobby_id).await {
+            tracing::error!("Failed to record finish of {}: {}", lobby_id, e);
+        }
+        // The gameplay seed can't help anyone now; keep it for replays and audits
+        if let Some(seed) = self.game_seed
+            && let Err(e) = lobby_repo.record_game_seed(lobby_id, seed).await
+        {
+            tracing::error!("Failed to record game seed for {}: {}", lobby_id, e);
+        }
+        // Kept so the lobby's points can be reprocessed later
+        if results.ranked
+            && let Err(e) = LobbyResultRepository::new(state.postgres.clone())
+                .store(lobby_id, &results)
+                .await
+        {
+            tracing::error!("Failed to store results of {}: {}", lobby_id, e);
+        }
+
+        // The standings reveal who played to spectators of anonymized lobbies
+        state.room_pseudonyms.reveal(&state.redis, lobby_id).await;
+
+        let final_standing = RoomServerMessage::FinalStanding {
+            standings: final_standings,
+            ranked: results.ranked,
+        };
+        broadcast::broadcast_room(&state, lobby_id, &final_standing).await;
+
+        if !self.is_practice() {
+            if let Err(e) = update_skill_ratings(&state, lobby_id, &results).await {
+                tracing::error!("Failed to update skill ratings: {}", e);
+            }
+            if let Err(e) = settle_predictions(&state, lobby_id, &results).await {
+                tracing::error!("Failed to settle predictions: {}", e);
+            }
+        }
+
+        self.results = Some(results);
+    }
+
+    /// Sign the final results with the server key, if one is configured, and
+    /// store them for settlement
+    async fn sign_results(&self, results: &GameResults) -> Option<SignedResults> {
+        let key = self.state.config.result_signing_key.as_ref()?;
+        let wallets: HashMap<Uuid, String> = self
+            .player_states
+            .iter()
+            .map(|(user_id, player)| (*user_id, player.wallet_address.clone()))
+            .collect();
+        let signed = match key.sign_results(self.lobby_id, results, &wallets) {
+            Ok(signed) => signed,
+            Err(e) => {
+                tracing::error!("Failed to sign results for {}: {}", self.lobby_id, e);
+                return None;
+            }
+        };
+        if let Err(e) = SignedResultRepository::new(self.state.postgres.clone())
+            .store(&signed)
+            .await
+        {
+            tracing::error!(
+                "Failed to store signed results for {}: {}",
+                self.lobby_id,
+                e
+            );
+        }
+        Some(signed)
+    }
+}
+
+// ============================================================================
+// GameEngine Trait Implementation
+// ============================================================================
+
+#[async_trait]
+impl GameEngine for CoinFlipEngine {
+    async fn add_bot(&mut self, difficulty: BotDifficulty) -> Result<Uuid, AppError> {
+        let bot = CoinFlipBot::new(difficulty);
+        let bot_id = bot.user_id;
+        self.bots.insert(bot_id, bot);
+        Ok(bot_id)
+    }
+
+    async fn set_lobby(&mut self, lobby: &Lobby) {
+        self.entry_amount = lobby.entry_amount;
+        self.current_amount = lobby.current_amount;
+        self.is_sponsored = lobby.is_sponsored;
+        self.creator_id = Some(lobby.creator_id);
+        self.payouts = lobby.payout_table();
+    }
+
+    async fn set_seeds(&mut self, seating_seed: u64, game_seed: u64) {
+        self.seating_seed = Some(seating_seed);
+        self.game_seed = Some(game_seed);
+        self.rng = GameRng::new(game_seed);
+    }
+
+    async fn initialize(&mut self, player_ids: Vec<Uuid>) -> Result<Vec<Value>, AppError> {
+        tracing::info!("Initializing CoinFlip with {} players", player_ids.len());
+
+        if player_ids.len() < 2 {
+            let event = RoomServerMessage::GameStartFailed {
+                reason: "Need at least 2 players to start".to_string(),
+            };
+            return Ok(vec![
+                serde_json::to_value(event).map_err(|e| AppError::Serialization(e.to_string()))?,
+            ]);
+        }
+
+        // Draw the seats; bots keep theirs after the players
+        let player_ids = match self.seating_seed {
+            Some(seed) => {
+                let (bots, players): (Vec<Uuid>, Vec<Uuid>) = player_ids
+                    .into_iter()
+                    .partition(|id| self.bots.contains_key(id));
+                let mut seats = seat_players(players, seed);
+                seats.extend(bots);
+                seats
+            }
+            None => player_ids,
+        };
+
+        self.total_players = player_ids.len();
+        self.players = player_ids
+            .iter()
+            .map(|&id| (id, GamePlayerState::new(id)))
+            .collect();
+        self.seating = TurnRotation::new(player_ids.clone());
+
+        // Load player states from Redis
+        let player_repo = PlayerStateRepository::new(self.state.redis.clone());
+        if let Ok(states) = player_repo.list_players(self.lobby_id).await {
+            for ps in states {
+                self.player_states.insert(ps.user_id, ps);
+            }
+        }
+
+        // Bots never joined through the lobby, so they have no stored state
+        for bot in self.bots.values() {
+            self.player_states.insert(
+                bot.user_id,
+                PlayerState::new(
+                    bot.user_id,
+                    self.lobby_id,
+                    String::new(),
+                    Some(bot.name()),
+                    Some(bot.name()),
+                    0.0,
+                    None,
+                    false,
+                ),
+            );
+        }
+
+        // The first tick starts round one
+        Ok(vec![
+            serde_json::to_value(RoomServerMessage::GameStarted {
+                seats: player_ids,
+                seating_seed: self.seating_seed.map(|seed| seed.to_string()),
+            })
+            .map_err(|e| AppError::Serialization(e.to_string()))?,
+        ])
+    }
+
+    async fn handle_action(
+        &mut self,
+        user_id: Uuid,
+        action: Value,
+    ) -> Result<Vec<Value>, AppError> {
+        let action: CoinFlipAction = serde_json::from_value(action)
+            .map_err(|e| AppError::BadRequest(format!("Invalid CoinFlip action: {}", e)))?;
+
+        tracing::debug!("CoinFlip action from {}: {:?}", user_id, action);
+
+        match action {
+            CoinFlipAction::Call { side } => self.call(user_id, side),
+        }
+    }
+
+    async fn get_bootstrap(&self) -> Result<Value, AppError> {
+        let active_players: Vec<PlayerState> = self
+            .seating
+            .active_players()
+            .iter()
+            .filter_map(|id| self.player_states.get(id).cloned())
+            .collect();
+
+        Ok(json!({
+            "gameId": self.lobby_id,
+            "status": if self.finished { "finished" } else { "inProgress" },
+            "round": self.round,
+            "activePlayers": active_players,
+            "timeoutSecs": ROUND_TIMEOUT_SECS,
+            "totalPlayers": self.total_players,
+            "remainingPlayers": self.seating.active_count(),
+        }))
+    }
+
+    async fn get_game_state(&self, user_id: Option<Uuid>) -> Result<Value, AppError> {
+        let players_count = CoinFlipEvent::PlayersCount {
+            remaining: self.seating.active_count(),
+            total: self.total_players,
+        };
+
+        let mut game_state = json!({
+            "playersCount": serde_json::to_value(&players_count).unwrap_or_default(),
+        });
+        if self.round > 0 && !self.finished {
+            game_state["round"] = serde_json::to_value(self.round_event()).unwrap_or_default();
+        }
+        // A player sees their own call; nobody sees anyone else's before the flip
+        if let Some(side) = user_id.and_then(|id| self.calls.get(&id)) {
+            game_state["call"] = json!(side);
+        }
+
+        Ok(game_state)
+    }
+
+    async fn get_results(&self) -> Result<Option<GameResults>, AppError> {
+        Ok(self.results.clone())
+    }
+
+    async fn tick(&mut self) -> Result<Vec<Value>, AppError> {
+        if self.finished || self.total_players == 0 {
+            return Ok(Vec::new());
+        }
+        if self.round == 0 {
+            self.start_round().await;
+            return Ok(Vec::new());
+        }
+
+        // Bot calls go out before the flip, which waits for the next tick
+        let events = self.play_bot_calls()?;
+        if !events.is_empty() {
+            return Ok(events);
+        }
+
+        let all_called = self
+            .seating
+            .active_players()
+            .iter()
+            .all(|id| self.calls.contains_key(id));
+        let timed_out = self
+            .round_deadline
+            .is_some_and(|deadline| Instant::now() >= deadline);
+        if all_called || timed_out {
+            self.resolve_round().await;
+            if self.seating.is_game_over() {
+                self.end_game().await;
+            } else {
+                self.start_round().await;
+            }
+        }
+
+        Ok(Vec::new())
+    }
+
+    fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    async fn force_finish(&mut self) -> Result<bool, AppError> {
+        if self.finished {
+            return Ok(false);
+        }
+        self.end_game().await;
+        Ok(true)
+    }
+}
+
+// ============================================================================
+// Factory
+// ============================================================================
+
+/// Factory function to create new CoinFlip game instances
+pub fn create_coin_flip(lobby_id: Uuid, state: AppState) -> Box<dyn GameEngine> {
+    Box::new(CoinFlipEngine::new(lobby_id, state))
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seats(count: usize) -> Vec<Uuid> {
+        (0..count).map(|_| Uuid::new_v4()).collect()
+    }
+
+    #[test]
+    fn test_wrong_and_missing_calls_are_knocked_out_in_seat_order() {
+        let active = seats(4);
+        let calls = HashMap::from([
+            (active[0], CoinSide::Tails),
+            (active[1], CoinSide::Heads),
+            (active[3], CoinSide::Tails),
+        ]);
+
+        // active[2] never called
+        assert_eq!(
+            knocked_out(&active, &calls, CoinSide::Heads),
+            vec![active[0], active[2], active[3]]
+        );
+        assert_eq!(
+            knocked_out(&active, &calls, CoinSide::Tails),
+            vec![active[1], active[2]]
+        );
+    }
+
+    #[test]
+    fn test_nobody_goes_out_when_nobody_calls_it() {
+        let active = seats(3);
+        let calls = HashMap::from([(active[0], CoinSide::Tails), (active[1], CoinSide::Tails)]);
+        assert!(knocked_out(&active, &calls, CoinSide::Heads).is_empty());
+        assert!(knocked_out(&active, &HashMap::new(), CoinSide::Heads).is_empty());
+
+        // Everyone right: nobody goes out either
+        let calls = active.iter().map(|id| (*id, CoinSide::Heads)).collect();
+        assert!(knocked_out(&active, &calls, CoinSide::Heads).is_empty());
+    }
+
+    #[test]
+    fn test_later_knockouts_finish_higher() {
+        let players = seats(5);
+        let knockouts = vec![vec![players[3], players[4]], vec![players[1]]];
+
+        let order = finishing_order(vec![(players[0], 2), (players[2], 3)], &knockouts);
+        assert_eq!(
+            order,
+            vec![players[2], players[0], players[1], players[3], players[4]]
+        );
+
+        // Ties among players still in keep the seat order
+        let order = finishing_order(vec![(players[0], 2), (players[2], 2)], &knockouts);
+        assert_eq!(order[..2], [players[0], players[2]]);
+    }
+
+    #[test]
+    fn test_round_event_serializes_deadline() {
+        let event = CoinFlipEvent::Round {
+            round: 2,
+            timeout_secs: ROUND_TIMEOUT_SECS,
+            round_deadline_ms: deadline_ms(1_000, ROUND_TIMEOUT_SECS),
+            server_time_ms: 1_500,
+        };
+        let value = serde_json::to_value(&event).unwrap();
+        assert_eq!(value["type"], "round");
+        assert_eq!(value["roundDeadlineMs"], 11_000);
+        assert_eq!(value["timeoutSecs"], ROUND_TIMEOUT_SECS);
+    }
+}
@@ -0,0 +1,86 @@
+// Coin Flip Message Types
+//
+// Client -> Server: CoinFlipAction
+// Server -> Client: CoinFlipEvent
+//
+// Note: Shared game events (GameStarted, GameStartFailed, FinalStanding, GameOver)
+// are in RoomServerMessage and should be used via broadcast::broadcast_room
+
+use crate::games::{GameAction, GameEvent};
+use crate::models::PlayerState;
+use serde::{Deserialize, Serialize};
+
+/// A side of the coin
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum CoinSide {
+    Heads,
+    Tails,
+}
+
+impl CoinSide {
+    /// The side facing down
+    pub fn other(self) -> Self {
+        match self {
+            CoinSide::Heads => CoinSide::Tails,
+            CoinSide::Tails => CoinSide::Heads,
+        }
+    }
+}
+
+// ============================================================================
+// Client -> Server Messages
+// ============================================================================
+
+/// Coin Flip game actions (client -> server)
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum CoinFlipAction {
+    /// Call the side the coin lands on this round
+    Call { side: CoinSide },
+}
+
+impl GameAction for CoinFlipAction {}
+
+// ============================================================================
+// Server -> Client Messages (Game-Specific)
+// ============================================================================
+
+/// Coin Flip game events (server -> client)
+///
+/// These are game-specific events sent via GameMessage wrapper.
+/// Shared events (GameStarted, GameStartFailed, FinalStanding, GameOver)
+/// are in RoomServerMessage.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum CoinFlipEvent {
+    /// A round started; active players have until the deadline to call -
+    /// broadcast to room
+    #[serde(rename_all = "camelCase")]
+    Round {
+        round: usize,
+        timeout_secs: u64,
+        /// When the round ends (server epoch millis)
+        round_deadline_ms: u64,
+        server_time_ms: u64,
+    },
+
+    /// A player made their call; the side stays hidden until the flip -
+    /// broadcast to room
+    Called { player: PlayerState },
+
+    /// The coin landed - broadcast to room
+    Flip { round: usize, side: CoinSide },
+
+    /// Nobody called it, so nobody goes out and the round is flipped again -
+    /// broadcast to room
+    Replay { round: usize },
+
+    /// Player called the wrong side or didn't call - broadcast to room
+    Eliminated { player: PlayerState, reason: String },
+
+    /// Players count update - broadcast to room
+    PlayersCount { remaining: usize, total: usize },
+}
+
+impl GameEvent for CoinFlipEvent {}
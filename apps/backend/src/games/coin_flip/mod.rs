@@ -0,0 +1,45 @@
+// Coin Flip Game Module
+//
+// A round-based guessing game: each round every player still in calls heads
+// or tails, then the coin is flipped. Players who called it wrong, or didn't
+// call in time, are knocked out; if nobody called it right the coin is flipped
+// again. The last player standing wins.
+//
+// Module structure:
+// - bot.rs: CoinFlipBot, the house opponent in practice lobbies
+// - engine.rs: Core game logic (CoinFlipEngine, round resolution, prize calculation)
+// - message.rs: Game-specific message types (CoinFlipAction, CoinFlipEvent)
+//
+// Shared game events (GameStarted, GameStartFailed, FinalStanding, GameOver) are in
+// ws/room/messages.rs as RoomServerMessage variants.
+//
+// Flow:
+// 1. UpdateLobbyStatus::Starting triggers countdown in ws/room/engine.rs
+// 2. After countdown, engine.rs calls game.initialize() → broadcasts GameStarted
+// 3. The room engine calls tick() a few times a second; the first starts round one
+// 4. Round (to room) → players send Call → Called (to room, side hidden)
+// 5. Once every call is in or ROUND_TIMEOUT_SECS pass → Flip, then Eliminated
+//    + GameOver (to user) for each loser, or Replay when nobody called it
+// 6. Next Round, or FinalStanding once one player is left
+//
+// Losers of the same round share the last places still open, in seat order.
+//
+// Practice lobbies seat a CoinFlipBot (add_bot) before initialize(). Each round
+// it thinks for a while, then tick() makes its call through the same path as a
+// player's Call. Practice results are shown but not saved.
+
+pub mod bot;
+pub mod engine;
+pub mod message;
+
+// Re-export bot types
+pub use bot::CoinFlipBot;
+
+// Re-export engine types
+pub use engine::{
+    CoinFlipEngine, ROUND_TIMEOUT_SECS, config_schema, create_coin_flip, finishing_order,
+    knocked_out,
+};
+
+// Re-export message types
+pub use message::{CoinFlipAction, CoinFlipEvent, CoinSide};
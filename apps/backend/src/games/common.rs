@@ -9,18 +9,37 @@
 // - Save permanent game summaries to Redis
 
 use crate::{
+    badges::{BadgeContext, BadgeEngine},
     db::{
-        player_state::PlayerStateRepository, season::SeasonRepository,
-        user_wars_points::UserWarsPointsRepository,
+        expiry::apply_expiry, lobby::LobbyRepository, lobby_result::LobbyResultRepository,
+        player_state::PlayerStateRepository, prediction::PredictionRepository,
+        season::SeasonRepository, skill_rating::SkillRatingRepository, streak::StreakRepository,
+        user_badge::UserBadgeRepository, user_wars_points::UserWarsPointsRepository,
     },
     errors::AppError,
+    games::GameRng,
+    models::{AwardCorrection, Lobby, PayoutTable, PredictionOutcome, RedisKey, WarsPointAward},
     state::{AppState, RedisClient},
+    ws::{broadcast, room::messages::RoomServerMessage},
 };
 use redis::AsyncCommands;
+use rust_decimal::{Decimal, prelude::ToPrimitive};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
 use uuid::Uuid;
 
+/// Current server time in epoch milliseconds
+///
+/// Timer events carry this so clients can estimate their clock offset.
+pub fn server_time_ms() -> u64 {
+    chrono::Utc::now().timestamp_millis() as u64
+}
+
+/// Absolute deadline (epoch millis) for a timer of `timeout_secs` started at `started_ms`
+pub fn deadline_ms(started_ms: u64, timeout_secs: u64) -> u64 {
+    started_ms + timeout_secs * 1000
+}
+
 /// Game-specific player state (stored separately from lobby PlayerState)
 ///
 /// This tracks game-specific information like eliminations, scores, positions, etc.
@@ -56,6 +75,22 @@ impl GamePlayerState {
     }
 }
 
+/// Turn order for `player_ids`, drawn from `seed`
+///
+/// Players are sorted by ID, then Fisher-Yates shuffled with a `GameRng`
+/// seeded with `seed`. The result depends only on who is playing and the seed,
+/// so anyone given the seed can redraw the order and check it.
+pub fn seat_players(mut player_ids: Vec<Uuid>, seed: u64) -> Vec<Uuid> {
+    player_ids.sort();
+
+    let mut rng = GameRng::new(seed);
+    for i in (1..player_ids.len()).rev() {
+        let j = rng.below(i + 1);
+        player_ids.swap(i, j);
+    }
+    player_ids
+}
+
 /// Turn-based game rotation system
 ///
 /// Handles player turns with automatic rotation, skip eliminated players,
@@ -91,6 +126,11 @@ impl TurnRotation {
             .collect()
     }
 
+    /// Every player, eliminated or not, in seat order
+    pub fn seats(&self) -> impl Iterator<Item = &Uuid> {
+        self.players.iter()
+    }
+
     /// Get count of active players
     pub fn active_count(&self) -> usize {
         self.active_players().len()
@@ -133,22 +173,80 @@ impl TurnRotation {
     }
 }
 
+/// Version of the `GameResults` JSON shape
+///
+/// 1. `rankings`, `finishedAt`, `metadata`, `ranked` (results carrying no version)
+/// 2. adds `schemaVersion` and `rake`
+pub const GAME_RESULTS_SCHEMA_VERSION: u32 = 2;
+
 /// Final game results with player rankings
 ///
 /// This is the standard format for game results that the platform expects.
-/// All games should return this structure at the end.
+/// All games should return this structure at the end. Clients, points and
+/// settlement all read it, so its JSON shape only grows: new fields are
+/// optional with a default that reads older results as they were meant, each
+/// addition bumps GAME_RESULTS_SCHEMA_VERSION, and nothing is renamed or
+/// removed. tests/fixtures/game_results_v*.json hold the shape of each version.
+///
+/// ```json
+/// {
+///   "schemaVersion": 2,
+///   "rankings": [
+///     { "userId": "…", "rank": 1, "score": 12, "prize": 70.0 },
+///     { "userId": "…", "rank": 2, "score": 9, "prize": 30.0 }
+///   ],
+///   "finishedAt": 1760000000,
+///   "metadata": { … },
+///   "ranked": true,
+///   "rake": 0.0
+/// }
+/// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GameResults {
+    /// Shape of these results; 1 for results stored before it was recorded
+    #[serde(default = "first_schema_version")]
+    pub schema_version: u32,
+
     /// Ordered list of players by rank (1st place first)
     pub rankings: Vec<PlayerRanking>,
 
     /// Unix timestamp when game ended
     pub finished_at: i64,
 
-    /// Optional game-specific metadata
+    /// Optional game-specific metadata, plus the lobby's `stakes` (see `with_stakes`)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<serde_json::Value>,
+
+    /// False for practice games, whose results earn no points or rating
+    #[serde(default = "ranked_by_default")]
+    pub ranked: bool,
+
+    /// Part of the pool paid to no player (unfilled places whose shares stay
+    /// in the pot); None when no pool was paid out
+    #[serde(default, with = "rust_decimal::serde::float_option")]
+    pub rake: Option<Decimal>,
+}
+
+/// `metadata` key of the lobby's stakes
+const STAKES_KEY: &str = "stakes";
+
+/// A lobby's stakes as recorded in its results' `metadata`
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Stakes {
+    #[serde(default, with = "rust_decimal::serde::float_option")]
+    entry_amount: Option<Decimal>,
+    #[serde(default, with = "rust_decimal::serde::float_option")]
+    current_amount: Option<Decimal>,
+}
+
+fn first_schema_version() -> u32 {
+    1
+}
+
+fn ranked_by_default() -> bool {
+    true
 }
 
 /// Individual player ranking in final results
@@ -158,7 +256,8 @@ pub struct PlayerRanking {
     pub user_id: Uuid,
     pub rank: usize,        // 1-based: 1 = first place, 2 = second, etc.
     pub score: Option<i32>, // Optional score
-    pub prize: Option<f64>, // Prize amount (calculated by platform)
+    #[serde(with = "rust_decimal::serde::float_option")]
+    pub prize: Option<Decimal>, // Prize amount (calculated by platform)
 }
 
 impl GameResults {
@@ -176,20 +275,82 @@ impl GameResults {
             .collect();
 
         Self {
+            schema_version: GAME_RESULTS_SCHEMA_VERSION,
             rankings,
             finished_at: chrono::Utc::now().timestamp(),
             metadata: None,
+            ranked: true,
+            rake: None,
         }
     }
 
+    /// Pay each ranking its share of `pool` under `payouts`.
+    ///
+    /// Places past the table's last paid place get nothing; shares of paid
+    /// places nobody reached follow the table's underfill rule, and what
+    /// nobody is paid is the rake.
+    pub fn apply_payouts(&mut self, pool: Decimal, payouts: &PayoutTable) {
+        let shares = payouts.shares(pool, self.rankings.len());
+        for ranking in &mut self.rankings {
+            ranking.prize = ranking
+                .rank
+                .checked_sub(1)
+                .and_then(|idx| shares.get(idx).copied())
+                .filter(|prize| *prize > Decimal::ZERO);
+        }
+        let paid: Decimal = self.rankings.iter().filter_map(|r| r.prize).sum();
+        self.rake = Some(pool - paid);
+    }
+
+    /// Record the lobby's entry amount and pool in `metadata`, so the game's
+    /// points can be recomputed later from the results alone
+    pub fn with_stakes(
+        mut self,
+        entry_amount: Option<Decimal>,
+        current_amount: Option<Decimal>,
+    ) -> Self {
+        let stakes = Stakes {
+            entry_amount,
+            current_amount,
+        };
+        let mut metadata = match self.metadata.take() {
+            Some(serde_json::Value::Object(fields)) => fields,
+            _ => serde_json::Map::new(),
+        };
+        if let Ok(stakes) = serde_json::to_value(stakes) {
+            metadata.insert(STAKES_KEY.to_string(), stakes);
+        }
+        self.metadata = Some(serde_json::Value::Object(metadata));
+        self
+    }
+
+    /// Entry amount and pool recorded by `with_stakes`; None for results
+    /// stored before they were
+    pub fn stakes(&self) -> Option<(Option<Decimal>, Option<Decimal>)> {
+        let stakes = self.metadata.as_ref()?.get(STAKES_KEY)?;
+        let stakes: Stakes = serde_json::from_value(stakes.clone()).ok()?;
+        Some((stakes.entry_amount, stakes.current_amount))
+    }
+
+    /// Mark the results as coming from a practice game
+    pub fn unranked(mut self) -> Self {
+        self.ranked = false;
+        self
+    }
+
     /// Create results from game player states (ordered by elimination)
+    ///
+    /// `states` are expected in seat order, which settles ties: players with
+    /// the same score, or eliminated in the same second, rank in seat order.
     pub fn from_game_states(mut states: Vec<GamePlayerState>) -> Self {
         // Sort by: active players first, then by elimination time (last eliminated = higher rank)
+        // Several active players only happens when a game is cut short; higher score ranks first
+        // The sort is stable, so ties keep the seat order they came in
         states.sort_by(|a, b| {
             match (a.is_eliminated, b.is_eliminated) {
                 (false, true) => std::cmp::Ordering::Less, // Active beats eliminated
                 (true, false) => std::cmp::Ordering::Greater,
-                (false, false) => std::cmp::Ordering::Equal,
+                (false, false) => b.score.cmp(&a.score),
                 (true, true) => {
                     // Both eliminated: later elimination = higher rank
                     b.eliminated_at.cmp(&a.eliminated_at)
@@ -209,9 +370,12 @@ impl GameResults {
             .collect();
 
         Self {
+            schema_version: GAME_RESULTS_SCHEMA_VERSION,
             rankings,
             finished_at: chrono::Utc::now().timestamp(),
             metadata: None,
+            ranked: true,
+            rake: None,
         }
     }
 }
@@ -257,7 +421,7 @@ pub struct GameSummary {
 #[derive(Debug, Clone)]
 pub struct PlayerResult {
     pub rank: usize,
-    pub prize: Option<f64>,
+    pub prize: Option<Decimal>,
     pub wars_point: f64,
 }
 
@@ -266,8 +430,11 @@ pub struct PlayerResult {
 /// This function:
 /// 1. Calculates wars_point using the provided context
 /// 2. Saves rank, prize, wars_point to Redis PlayerState
-/// 3. Saves wars_point to PostgreSQL user_wars_points for current season
-/// 4. Returns the calculated values
+/// 3. Updates the player's win and daily streaks
+/// 4. Saves wars_point to PostgreSQL user_wars_points for current season,
+///    and to the game's season total, and tells live leaderboards
+/// 5. Awards any badges the player has now earned
+/// 6. Returns the calculated values
 pub async fn save_player_result(
     state: &AppState,
     lobby_id: Uuid,
@@ -281,13 +448,69 @@ pub async fn save_player_result(
         .set_result(lobby_id, ctx.user_id, ctx.rank, ctx.prize, wars_point)
         .await?;
 
+    // Update win/daily streaks
+    let streaks = StreakRepository::new(state.redis.clone())
+        .record_game(
+            ctx.user_id,
+            ctx.rank == 1,
+            chrono::Utc::now().timestamp(),
+            state.config.streak_day_offset_secs,
+        )
+        .await
+        .inspect_err(|e| tracing::error!("Failed to update streaks for {}: {}", ctx.user_id, e))
+        .ok();
+
     // Save wars_point to PostgreSQL user_wars_points for current season
-    let season_repo = SeasonRepository::new(state.postgres.clone());
-    if let Ok(season_id) = season_repo.get_current_season_id().await {
+    let season_repo = SeasonRepository::new(state.postgres.clone()).with_cache(state.redis.clone());
+    if let Ok(season) = season_repo.current().await {
+        let season_id = season.id();
         let wars_points_repo = UserWarsPointsRepository::new(state.postgres.clone());
-        let _ = wars_points_repo
+        if let Ok(season_points) = wars_points_repo
             .upsert_wars_points(ctx.user_id, season_id, wars_point)
-            .await;
+            .await
+        {
+            // Also count the points towards the game's own leaderboard
+            if let Err(e) = wars_points_repo
+                .add_game_wars_points(ctx.user_id, season_id, ctx.game_id, wars_point)
+                .await
+            {
+                tracing::error!("Failed to add game wars points for {}: {}", ctx.user_id, e);
+            }
+            if let Err(e) = wars_points_repo
+                .record_award(
+                    lobby_id,
+                    ctx.user_id,
+                    season_id,
+                    Some(ctx.game_id),
+                    wars_point,
+                    ctx.active_players,
+                )
+                .await
+            {
+                tracing::error!("Failed to record award for {}: {}", ctx.user_id, e);
+            }
+            state
+                .leaderboard_deltas
+                .changed(state, Some(ctx.game_id))
+                .await;
+
+            let badge_ctx = BadgeContext {
+                season_points: season_points.points,
+                rank: ctx.rank,
+                win_streak: streaks.map(|s| s.current_win_streak),
+            };
+            if let Err(e) = BadgeEngine::default()
+                .award(
+                    &UserBadgeRepository::new(state.postgres.clone()),
+                    ctx.user_id,
+                    season_id,
+                    &badge_ctx,
+                )
+                .await
+            {
+                tracing::error!("Failed to award badges to {}: {}", ctx.user_id, e);
+            }
+        }
     }
 
     Ok(PlayerResult {
@@ -297,6 +520,64 @@ pub async fn save_player_result(
     })
 }
 
+/// Update players' per-game skill ratings from final results
+///
+/// Call once per finished game with the complete rankings. The game is looked
+/// up from the lobby so this works for any registered game.
+pub async fn update_skill_ratings(
+    state: &AppState,
+    lobby_id: Uuid,
+    results: &GameResults,
+) -> Result<HashMap<Uuid, f64>, AppError> {
+    let lobby = LobbyRepository::new(state.postgres.clone())
+        .find_by_id(lobby_id)
+        .await?;
+
+    let placements: Vec<(Uuid, usize)> = results
+        .rankings
+        .iter()
+        .map(|r| (r.user_id, r.rank))
+        .collect();
+
+    SkillRatingRepository::new(state.postgres.clone())
+        .apply_results(lobby.game_id, &placements)
+        .await
+}
+
+/// Score spectators' winner predictions and award points to those who were right
+///
+/// Call once per finished ranked game, after its final standings are out.
+/// The room is told how the predictions fared.
+pub async fn settle_predictions(
+    state: &AppState,
+    lobby_id: Uuid,
+    results: &GameResults,
+) -> Result<PredictionOutcome, AppError> {
+    let repo = PredictionRepository::new(state.redis.clone());
+    let mut predictions = repo.get_predictions(lobby_id).await?;
+    // Anyone who ended up playing has no say, whatever they predicted before
+    predictions.retain(|user_id, _| results.rankings.iter().all(|r| r.user_id != *user_id));
+
+    let winner_id = results
+        .rankings
+        .iter()
+        .find(|r| r.rank == 1)
+        .map(|r| r.user_id);
+    let outcome = PredictionOutcome::score(&predictions, winner_id);
+
+    let points = state.config.prediction_points;
+    repo.award_points(&outcome.correct, points).await?;
+
+    if outcome.predictions > 0 {
+        let msg = RoomServerMessage::PredictionsSettled {
+            outcome: outcome.clone(),
+            points_awarded: points,
+        };
+        broadcast::broadcast_room(state, lobby_id, &msg).await;
+    }
+    Ok(outcome)
+}
+
 /// Save permanent game summary to Redis
 ///
 /// This persists the final game results and metadata so players can view
@@ -308,12 +589,9 @@ pub async fn save_game_summary(
     results: &GameResults,
     metadata: serde_json::Value,
 ) -> Result<(), AppError> {
-    let mut conn = redis
-        .get()
-        .await
-        .map_err(|e| AppError::RedisError(format!("Failed to get Redis connection: {}", e)))?;
+    let mut conn = redis.get().await?;
 
-    let key = format!("game:{}:state", lobby_id);
+    let key = RedisKey::game_summary(lobby_id);
     let summary = GameSummary {
         results: results.clone(),
         metadata,
@@ -327,11 +605,28 @@ pub async fn save_game_summary(
         .set(&key, json)
         .await
         .map_err(AppError::RedisCommandError)?;
+    apply_expiry(&mut conn, &key).await?;
 
     tracing::info!("Saved game summary for lobby {}", lobby_id);
     Ok(())
 }
 
+/// Load a game summary saved by `save_game_summary`, if the game has finished.
+pub async fn load_game_summary(
+    redis: &RedisClient,
+    lobby_id: Uuid,
+) -> Result<Option<GameSummary>, AppError> {
+    let mut conn = redis.get().await?;
+
+    let json: Option<String> = conn
+        .get(RedisKey::game_summary(lobby_id))
+        .await
+        .map_err(AppError::RedisCommandError)?;
+
+    json.map(|j| serde_json::from_str(&j).map_err(|e| AppError::Deserialization(e.to_string())))
+        .transpose()
+}
+
 // ============================================================================
 // Wars Points Calculation
 // ============================================================================
@@ -343,16 +638,18 @@ pub async fn save_game_summary(
 pub struct WarsPointContext {
     /// The user being calculated for
     pub user_id: Uuid,
+    /// The game played, whose own leaderboard also gets the points
+    pub game_id: Uuid,
     /// Player's final rank (1 = winner)
     pub rank: usize,
     /// Prize amount won (calculated by game)
-    pub prize: Option<f64>,
+    pub prize: Option<Decimal>,
     /// Total number of participants in the game
     pub participants: usize,
     /// Entry amount per player (if any)
-    pub entry_amount: Option<f64>,
+    pub entry_amount: Option<Decimal>,
     /// Total prize pool
-    pub current_amount: Option<f64>,
+    pub current_amount: Option<Decimal>,
     /// Whether this is a sponsored lobby
     pub is_sponsored: bool,
     /// The creator's user ID (for sponsor bonus)
@@ -375,7 +672,10 @@ pub fn calculate_wars_point(ctx: &WarsPointContext) -> f64 {
 
     // Pool bonus for non-sponsored games
     if !ctx.is_sponsored {
-        if let (Some(entry_amount), Some(current_amount)) = (ctx.entry_amount, ctx.current_amount) {
+        if let (Some(entry_amount), Some(current_amount)) = (
+            ctx.entry_amount.and_then(|a| a.to_f64()),
+            ctx.current_amount.and_then(|a| a.to_f64()),
+        ) {
             if entry_amount > 0.0 {
                 let pool_bonus = (current_amount / ctx.participants as f64) + (entry_amount / 5.0);
                 total_point += pool_bonus;
@@ -397,6 +697,96 @@ pub fn calculate_wars_point(ctx: &WarsPointContext) -> f64 {
     total_point.min(50.0)
 }
 
+/// Wars points for each ranking in `results` under the current formula
+///
+/// Stakes come from the results; only results stored before they were
+/// recorded fall back to the `lobby` row's current amounts. The active
+/// player count comes from each player's recorded award; awards recorded
+/// before it was kept use the count left once the player went out, one
+/// fewer than their rank (and the winner alone).
+pub fn recompute_wars_points(
+    results: &GameResults,
+    lobby: &Lobby,
+    awards: &[WarsPointAward],
+) -> Vec<(Uuid, f64)> {
+    let participants = results.rankings.len();
+    let (entry_amount, current_amount) = results
+        .stakes()
+        .unwrap_or((lobby.entry_amount, lobby.current_amount));
+    let recorded: HashMap<Uuid, usize> = awards
+        .iter()
+        .filter_map(|award| Some((award.user_id, award.active_players? as usize)))
+        .collect();
+    results
+        .rankings
+        .iter()
+        .map(|ranking| {
+            let active_players = recorded
+                .get(&ranking.user_id)
+                .copied()
+                .unwrap_or_else(|| ranking.rank.saturating_sub(1).max(1));
+            let ctx = WarsPointContext {
+                user_id: ranking.user_id,
+                game_id: lobby.game_id,
+                rank: ranking.rank,
+                prize: ranking.prize,
+                participants,
+                entry_amount,
+                current_amount,
+                is_sponsored: lobby.is_sponsored,
+                creator_id: Some(lobby.creator_id),
+                active_players,
+            };
+            (ranking.user_id, calculate_wars_point(&ctx))
+        })
+        .collect()
+}
+
+/// Recompute a finished lobby's wars points from its stored results and
+/// correct every player's award to match
+///
+/// Used after a points bug: each player's recorded award is reversed and the
+/// recomputed one made in its place (see `correct_awards`), so running it
+/// again changes nothing until the formula changes.
+pub async fn reprocess_points(
+    state: &AppState,
+    lobby_id: Uuid,
+) -> Result<Vec<AwardCorrection>, AppError> {
+    let results = LobbyResultRepository::new(state.postgres.clone())
+        .find_by_lobby(lobby_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("No results stored for lobby {}", lobby_id)))?;
+    if !results.ranked {
+        return Err(AppError::BadRequest(
+            "Practice games award no points".to_string(),
+        ));
+    }
+    let lobby = LobbyRepository::new(state.postgres.clone())
+        .find_by_id(lobby_id)
+        .await?;
+
+    let season_id = SeasonRepository::new(state.postgres.clone())
+        .with_cache(state.redis.clone())
+        .current()
+        .await
+        .ok()
+        .map(|season| season.id());
+    let wars_points_repo = UserWarsPointsRepository::new(state.postgres.clone());
+    let recorded = wars_points_repo.find_awards(lobby_id).await?;
+    let awards = recompute_wars_points(&results, &lobby, &recorded);
+    let corrections = wars_points_repo
+        .correct_awards(lobby_id, &awards, season_id, Some(lobby.game_id))
+        .await?;
+
+    if corrections.iter().any(|c| c.points != c.previous) {
+        state
+            .leaderboard_deltas
+            .changed(state, Some(lobby.game_id))
+            .await;
+    }
+    Ok(corrections)
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -404,6 +794,7 @@ pub fn calculate_wars_point(ctx: &WarsPointContext) -> f64 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::PayoutUnderfill;
 
     #[test]
     fn test_turn_rotation() {
@@ -426,6 +817,26 @@ mod tests {
         assert_eq!(rotation.get_winner(), Some(players[0]));
     }
 
+    #[test]
+    fn test_seating_is_reproducible_from_the_seed() {
+        let players: Vec<Uuid> = (0..6).map(|_| Uuid::new_v4()).collect();
+        let seats = seat_players(players.clone(), 42);
+
+        // Same seed, same order, whatever order the players were listed in
+        let mut reversed = players.clone();
+        reversed.reverse();
+        assert_eq!(seat_players(reversed, 42), seats);
+
+        let mut sorted_seats = seats.clone();
+        sorted_seats.sort();
+        let mut sorted_players = players.clone();
+        sorted_players.sort();
+        assert_eq!(sorted_seats, sorted_players);
+
+        // Some other seed draws a different order
+        assert!((0..10).any(|seed| seat_players(players.clone(), seed) != seats));
+    }
+
     #[test]
     fn test_game_results() {
         let players = vec![Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
@@ -436,4 +847,154 @@ mod tests {
         assert_eq!(results.rankings[0].user_id, players[0]);
         assert_eq!(results.rankings[2].rank, 3);
     }
+
+    #[test]
+    fn test_game_results_pay_top_places() {
+        let players: Vec<Uuid> = (0..4).map(|_| Uuid::new_v4()).collect();
+        let mut results = GameResults::from_ordered_players(players);
+        results.apply_payouts(Decimal::from(200), &PayoutTable::default_for(4));
+
+        let prizes: Vec<Option<Decimal>> = results.rankings.iter().map(|r| r.prize).collect();
+        assert_eq!(
+            prizes,
+            vec![
+                Some(Decimal::from(100)),
+                Some(Decimal::from(60)),
+                Some(Decimal::from(40)),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rake_is_the_unpaid_part_of_the_pool() {
+        let players: Vec<Uuid> = (0..2).map(|_| Uuid::new_v4()).collect();
+        let table = PayoutTable::new(vec![50, 30, 20], PayoutUnderfill::Pot).unwrap();
+
+        let mut results = GameResults::from_ordered_players(players.clone());
+        assert_eq!(results.rake, None);
+        results.apply_payouts(Decimal::from(100), &table);
+        assert_eq!(results.rake, Some(Decimal::from(20)));
+
+        let mut results = GameResults::from_ordered_players(players);
+        results.apply_payouts(Decimal::from(100), &PayoutTable::default_for(2));
+        assert_eq!(results.rake, Some(Decimal::ZERO));
+    }
+
+    /// The results tests/fixtures/game_results_v2.json holds
+    fn golden_results() -> GameResults {
+        let mut results = GameResults::from_ordered_players(vec![
+            uuid::uuid!("0b6e0c7e-4d0a-4c43-9a55-0d8f3b1e2a01"),
+            uuid::uuid!("0b6e0c7e-4d0a-4c43-9a55-0d8f3b1e2a02"),
+        ]);
+        results.rankings[0].score = Some(12);
+        results.rankings[1].score = Some(9);
+        results.finished_at = 1_760_000_000;
+        results.metadata = Some(serde_json::json!({ "mode": "scoring", "wordsPlayed": 14 }));
+        let table = PayoutTable::new(vec![50, 30, 20], PayoutUnderfill::Pot).unwrap();
+        results.apply_payouts(Decimal::from(100), &table);
+        results
+    }
+
+    #[test]
+    fn test_results_serialize_to_the_golden_shape() {
+        let golden: serde_json::Value =
+            serde_json::from_str(include_str!("../../tests/fixtures/game_results_v2.json"))
+                .unwrap();
+        assert_eq!(golden["schemaVersion"], GAME_RESULTS_SCHEMA_VERSION);
+        assert_eq!(serde_json::to_value(golden_results()).unwrap(), golden);
+
+        // ...and read back unchanged
+        let read: GameResults = serde_json::from_value(golden.clone()).unwrap();
+        assert_eq!(serde_json::to_value(read).unwrap(), golden);
+    }
+
+    #[test]
+    fn test_version_1_results_still_deserialize() {
+        let v1: GameResults =
+            serde_json::from_str(include_str!("../../tests/fixtures/game_results_v1.json"))
+                .unwrap();
+        assert_eq!(v1.schema_version, 1);
+        assert_eq!(v1.rake, None);
+        assert!(v1.ranked);
+        assert_eq!(v1.finished_at, 1_750_000_000);
+        assert_eq!(v1.rankings.len(), 2);
+        assert_eq!(v1.rankings[0].prize, Some(Decimal::from(70)));
+        assert_eq!(v1.rankings[1].score, None);
+    }
+
+    #[test]
+    fn test_stakes_are_kept_alongside_game_metadata() {
+        let mut results = GameResults::from_ordered_players(vec![Uuid::new_v4()]);
+        assert_eq!(results.stakes(), None);
+
+        results.metadata = Some(serde_json::json!({ "wordsPlayed": 14 }));
+        let results = results.with_stakes(Some(Decimal::new(5, 1)), Some(Decimal::from(2)));
+        assert_eq!(
+            results.stakes(),
+            Some((Some(Decimal::new(5, 1)), Some(Decimal::from(2))))
+        );
+        assert_eq!(results.metadata.as_ref().unwrap()["wordsPlayed"], 14);
+
+        // ...and survive being stored
+        let stored: GameResults =
+            serde_json::from_value(serde_json::to_value(&results).unwrap()).unwrap();
+        assert_eq!(stored.stakes(), results.stakes());
+
+        let free = GameResults::from_ordered_players(vec![Uuid::new_v4()]).with_stakes(None, None);
+        assert_eq!(free.stakes(), Some((None, None)));
+    }
+
+    #[test]
+    fn test_practice_results_are_unranked() {
+        let results = GameResults::from_ordered_players(vec![Uuid::new_v4(), Uuid::new_v4()]);
+        assert!(results.ranked);
+
+        let practice = results.unranked();
+        assert!(!practice.ranked);
+        assert_eq!(practice.rankings.len(), 2);
+
+        // Summaries saved before the flag existed were all ranked games
+        let mut json = serde_json::to_value(&practice).unwrap();
+        json.as_object_mut().unwrap().remove("ranked");
+        let stored: GameResults = serde_json::from_value(json).unwrap();
+        assert!(stored.ranked);
+    }
+
+    #[test]
+    fn test_tied_players_rank_in_seat_order() {
+        let seats: Vec<Uuid> = (0..4).map(|_| Uuid::new_v4()).collect();
+        let mut states: Vec<GamePlayerState> =
+            seats.iter().map(|&id| GamePlayerState::new(id)).collect();
+        states[0].score = 3;
+        states[1].score = 7;
+        states[2].score = 3;
+        states[3].score = 7;
+
+        let results = GameResults::from_game_states(states);
+        let order: Vec<Uuid> = results.rankings.iter().map(|r| r.user_id).collect();
+        assert_eq!(order, vec![seats[1], seats[3], seats[0], seats[2]]);
+    }
+
+    #[test]
+    fn test_cut_short_game_ranks_active_players_by_score() {
+        let (low, high, out) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let mut states = vec![
+            GamePlayerState::new(low),
+            GamePlayerState::new(high),
+            GamePlayerState::new(out),
+        ];
+        states[0].score = 1;
+        states[1].score = 4;
+        states[2].score = 9;
+        states[2].eliminate();
+
+        let results = GameResults::from_game_states(states);
+        let order: Vec<(Uuid, usize)> = results
+            .rankings
+            .iter()
+            .map(|r| (r.user_id, r.rank))
+            .collect();
+        assert_eq!(order, vec![(high, 1), (low, 2), (out, 3)]);
+    }
 }
@@ -14,6 +14,10 @@ pub enum GameError {
     InvalidAction(String),
     /// Player already eliminated
     AlreadyEliminated,
+    /// Submitted word has characters other than letters, or none at all
+    InvalidWord(String),
+    /// Submitted word is longer than the game allows
+    WordTooLong { length: usize, max: usize },
     /// Insufficient players to start
     InsufficientPlayers { required: usize, actual: usize },
     /// Internal game error
@@ -29,6 +33,10 @@ impl fmt::Display for GameError {
             GameError::GameNotStarted => write!(f, "Game has not started yet"),
             GameError::InvalidAction(msg) => write!(f, "Invalid action: {}", msg),
             GameError::AlreadyEliminated => write!(f, "You have been eliminated"),
+            GameError::InvalidWord(msg) => write!(f, "Invalid word: {}", msg),
+            GameError::WordTooLong { length, max } => {
+                write!(f, "Word is {} letters long; the limit is {}", length, max)
+            }
             GameError::InsufficientPlayers { required, actual } => {
                 write!(f, "Need at least {} players, got {}", required, actual)
             }
@@ -48,6 +56,8 @@ impl GameError {
             GameError::GameNotStarted => "GAME_NOT_STARTED",
             GameError::InvalidAction(_) => "INVALID_ACTION",
             GameError::AlreadyEliminated => "ALREADY_ELIMINATED",
+            GameError::InvalidWord(_) => "INVALID_WORD",
+            GameError::WordTooLong { .. } => "WORD_TOO_LONG",
             GameError::InsufficientPlayers { .. } => "INSUFFICIENT_PLAYERS",
             GameError::Internal(_) => "INTERNAL_ERROR",
         }
@@ -58,17 +68,8 @@ impl GameError {
 impl From<GameError> for crate::errors::AppError {
     fn from(err: GameError) -> Self {
         match err {
-            GameError::NotYourTurn
-            | GameError::NotInGame
-            | GameError::InvalidAction(_)
-            | GameError::AlreadyEliminated => crate::errors::AppError::BadRequest(err.to_string()),
-            GameError::GameFinished | GameError::GameNotStarted => {
-                crate::errors::AppError::BadRequest(err.to_string())
-            }
-            GameError::InsufficientPlayers { .. } => {
-                crate::errors::AppError::BadRequest(err.to_string())
-            }
             GameError::Internal(_msg) => crate::errors::AppError::InternalError,
+            other => crate::errors::AppError::GameError(other),
         }
     }
 }
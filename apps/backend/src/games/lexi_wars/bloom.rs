@@ -0,0 +1,96 @@
+// Bloom filter over dictionary words
+//
+// Answers "definitely not a word" or "maybe a word" from a fixed bit array,
+// without holding the words. Built once from a word list; never gives a
+// false negative, and gives false positives at about the rate it was sized
+// for.
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+/// Share of non-words `from_words` filters let through
+pub const DEFAULT_FALSE_POSITIVE_RATE: f64 = 0.01;
+
+#[derive(Debug, Clone)]
+pub struct BloomFilter {
+    bits: Vec<u64>,
+    bit_count: u64,
+    hash_count: u32,
+}
+
+impl BloomFilter {
+    /// An empty filter sized for `expected` words at `false_positive_rate`
+    pub fn with_rate(expected: usize, false_positive_rate: f64) -> Self {
+        let n = expected.max(1) as f64;
+        let ln2 = std::f64::consts::LN_2;
+        let bit_count = (-n * false_positive_rate.ln() / (ln2 * ln2))
+            .ceil()
+            .max(64.0) as u64;
+        let hash_count = ((bit_count as f64 / n) * ln2).round().clamp(1.0, 16.0) as u32;
+
+        Self {
+            bits: vec![0; bit_count.div_ceil(64) as usize],
+            bit_count,
+            hash_count,
+        }
+    }
+
+    /// A filter holding every word in `words`
+    pub fn from_words<'a>(words: impl ExactSizeIterator<Item = &'a String>) -> Self {
+        let mut filter = Self::with_rate(words.len(), DEFAULT_FALSE_POSITIVE_RATE);
+        for word in words {
+            filter.insert(word);
+        }
+        filter
+    }
+
+    pub fn insert(&mut self, word: &str) {
+        for bit in self.bit_indexes(word) {
+            self.bits[(bit / 64) as usize] |= 1 << (bit % 64);
+        }
+    }
+
+    /// False means `word` was never inserted; true means it probably was
+    pub fn may_contain(&self, word: &str) -> bool {
+        self.bit_indexes(word)
+            .all(|bit| self.bits[(bit / 64) as usize] & (1 << (bit % 64)) != 0)
+    }
+
+    /// Bits for `word`, from two hashes combined (Kirsch-Mitzenmacher)
+    fn bit_indexes(&self, word: &str) -> impl Iterator<Item = u64> + use<> {
+        let h1 = hash_with(word, 0);
+        let h2 = hash_with(word, 1) | 1;
+        let bit_count = self.bit_count;
+        (0..self.hash_count as u64).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % bit_count)
+    }
+}
+
+/// `word` hashed with a fixed `salt`; stable within a process
+fn hash_with(word: &str, salt: u64) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    salt.hash(&mut hasher);
+    word.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inserted_words_are_always_found() {
+        let words: Vec<String> = (0..5000).map(|i| format!("word{}", i)).collect();
+        let filter = BloomFilter::from_words(words.iter());
+        assert!(words.iter().all(|w| filter.may_contain(w)));
+    }
+
+    #[test]
+    fn test_false_positive_rate_is_near_target() {
+        let words: Vec<String> = (0..5000).map(|i| format!("word{}", i)).collect();
+        let filter = BloomFilter::from_words(words.iter());
+
+        let false_positives = (0..10_000)
+            .filter(|i| filter.may_contain(&format!("other{}", i)))
+            .count();
+        assert!(false_positives < 300, "{} false positives", false_positives);
+    }
+}
@@ -0,0 +1,285 @@
+// Lexi Wars practice bot
+//
+// A `LexiBot` fills the second seat of a practice lobby. When its turn starts
+// it picks a think time; once that passes, the engine's `tick` asks it for a
+// word that satisfies the current rule and submits it like a player action.
+//
+// Difficulty sets both halves: easy bots take longer and play common, short
+// words; hard bots answer quickly with rarer ones. Every think time is well
+// inside TURN_TIMEOUT_SECS, so a bot only loses a turn when no word fits.
+
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+use uuid::Uuid;
+
+use super::rule::{Rule, RuleContext};
+use crate::{games::GameRng, models::BotDifficulty};
+
+/// Shortest and longest time a bot of `difficulty` thinks before answering
+pub fn think_time_range(difficulty: BotDifficulty) -> (Duration, Duration) {
+    match difficulty {
+        BotDifficulty::Easy => (Duration::from_secs(7), Duration::from_secs(11)),
+        BotDifficulty::Medium => (Duration::from_secs(4), Duration::from_secs(7)),
+        BotDifficulty::Hard => (Duration::from_millis(1500), Duration::from_secs(3)),
+    }
+}
+
+/// How unusual a word is, from its letters (Scrabble letter values)
+pub fn word_rarity(word: &str) -> u32 {
+    word.chars()
+        .map(|c| match c {
+            'd' | 'g' => 2,
+            'b' | 'c' | 'm' | 'p' => 3,
+            'f' | 'h' | 'v' | 'w' | 'y' => 4,
+            'k' => 5,
+            'j' | 'x' => 8,
+            'q' | 'z' => 10,
+            _ => 1,
+        })
+        .sum()
+}
+
+/// Bot opponent for practice games
+#[derive(Debug, Clone)]
+pub struct LexiBot {
+    pub user_id: Uuid,
+    pub difficulty: BotDifficulty,
+    /// When the bot answers on its current turn (None when it isn't its turn)
+    ready_at: Option<Instant>,
+}
+
+impl LexiBot {
+    pub fn new(difficulty: BotDifficulty) -> Self {
+        Self {
+            user_id: Uuid::new_v4(),
+            difficulty,
+            ready_at: None,
+        }
+    }
+
+    /// Display name shown to the other player
+    pub fn name(&self) -> String {
+        let level = match self.difficulty {
+            BotDifficulty::Easy => "Easy",
+            BotDifficulty::Medium => "Medium",
+            BotDifficulty::Hard => "Hard",
+        };
+        format!("LexiBot ({})", level)
+    }
+
+    /// The bot's turn started at `now`; returns how long it will think
+    pub fn start_turn(&mut self, now: Instant, rng: &mut GameRng) -> Duration {
+        let (min, max) = think_time_range(self.difficulty);
+        let think =
+            Duration::from_millis(rng.in_range(min.as_millis() as u64..=max.as_millis() as u64));
+        self.ready_at = Some(now + think);
+        think
+    }
+
+    /// Whether the bot has finished thinking on its current turn
+    pub fn is_ready(&self, now: Instant) -> bool {
+        self.ready_at.is_some_and(|at| now >= at)
+    }
+
+    /// The bot has answered (or given up) on its current turn
+    pub fn end_turn(&mut self) {
+        self.ready_at = None;
+    }
+
+    /// Pick an unused word from `dictionary` that passes `rule`
+    ///
+    /// Candidates are ordered by rarity and split in thirds; the bot picks at
+    /// random from the third its difficulty calls for. Returns None when no
+    /// word fits.
+    pub fn pick_word<'a>(
+        &self,
+        dictionary: impl IntoIterator<Item = &'a String>,
+        rule: &Rule,
+        ctx: &RuleContext,
+        used: &HashSet<String>,
+        rng: &mut GameRng,
+    ) -> Option<String> {
+        let mut candidates: Vec<&String> = dictionary
+            .into_iter()
+            .filter(|word| !used.contains(*word) && rule.check(word, ctx).is_ok())
+            .collect();
+        if candidates.is_empty() {
+            return None;
+        }
+        candidates.sort_by_key(|word| (word_rarity(word), word.len(), word.as_str()));
+
+        let third = candidates.len().div_ceil(3);
+        let band = match self.difficulty {
+            BotDifficulty::Easy => &candidates[..third],
+            BotDifficulty::Medium => {
+                let start = third.min(candidates.len() - 1);
+                &candidates[start..(2 * third).clamp(start + 1, candidates.len())]
+            }
+            BotDifficulty::Hard => &candidates[candidates.len() - third..],
+        };
+
+        Some(band[rng.below(band.len())].clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::lexi_wars::rule::lexi_wars_rules;
+
+    fn dictionary() -> Vec<String> {
+        [
+            "apple", "table", "stone", "tiger", "eagle", "about", "river", "alien", "quartz",
+            "jazzy", "zebra", "oxide", "dozen", "pizza", "ozone", "trees", "north", "lemon",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect()
+    }
+
+    fn context(letter: char) -> RuleContext {
+        RuleContext {
+            min_word_length: 5,
+            random_letter: letter,
+            round_number: 1,
+            rule_index: 0,
+            rare_letters_required: 0,
+        }
+    }
+
+    #[test]
+    fn test_bot_words_satisfy_every_rule() {
+        let dictionary = dictionary();
+        let used: HashSet<String> = ["apple".to_string()].into();
+        let mut rng = GameRng::new(1);
+
+        for difficulty in [
+            BotDifficulty::Easy,
+            BotDifficulty::Medium,
+            BotDifficulty::Hard,
+        ] {
+            let bot = LexiBot::new(difficulty);
+            for letter in ['a', 'e', 'z'] {
+                let ctx = context(letter);
+                for rule in lexi_wars_rules(&ctx) {
+                    let Some(word) = bot.pick_word(&dictionary, &rule, &ctx, &used, &mut rng)
+                    else {
+                        // Only acceptable when nothing in the dictionary fits
+                        assert!(
+                            dictionary
+                                .iter()
+                                .all(|w| used.contains(w) || rule.check(w, &ctx).is_err()),
+                            "{:?} bot found nothing for {} with '{}'",
+                            difficulty,
+                            rule.name,
+                            letter
+                        );
+                        continue;
+                    };
+                    assert!(
+                        rule.check(&word, &ctx).is_ok(),
+                        "{} breaks {}",
+                        word,
+                        rule.name
+                    );
+                    assert_ne!(word, "apple", "used words are never replayed");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_bot_gives_up_when_nothing_fits() {
+        let ctx = RuleContext {
+            min_word_length: 20,
+            ..context('a')
+        };
+        let rule = &lexi_wars_rules(&ctx)[0];
+        let bot = LexiBot::new(BotDifficulty::Hard);
+        assert_eq!(
+            bot.pick_word(
+                &dictionary(),
+                rule,
+                &ctx,
+                &HashSet::new(),
+                &mut GameRng::new(1)
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_harder_bots_play_rarer_words() {
+        let dictionary = dictionary();
+        let ctx = context('a');
+        let rule = &lexi_wars_rules(&ctx)[0];
+        let used = HashSet::new();
+        let mut rng = GameRng::new(1);
+
+        let easy = LexiBot::new(BotDifficulty::Easy);
+        let hard = LexiBot::new(BotDifficulty::Hard);
+        for _ in 0..20 {
+            let easy_word = easy
+                .pick_word(&dictionary, rule, &ctx, &used, &mut rng)
+                .unwrap();
+            let hard_word = hard
+                .pick_word(&dictionary, rule, &ctx, &used, &mut rng)
+                .unwrap();
+            assert!(word_rarity(&hard_word) > word_rarity(&easy_word));
+        }
+    }
+
+    #[test]
+    fn test_bots_with_the_same_seed_play_alike() {
+        let dictionary = dictionary();
+        let ctx = context('e');
+        let rules = lexi_wars_rules(&ctx);
+        let play = |seed| {
+            let mut rng = GameRng::new(seed);
+            let mut bot = LexiBot::new(BotDifficulty::Medium);
+            let mut used = HashSet::new();
+            let mut moves = Vec::new();
+            for rule in rules.iter().cycle().take(8) {
+                let think = bot.start_turn(Instant::now(), &mut rng);
+                let word = bot.pick_word(&dictionary, rule, &ctx, &used, &mut rng);
+                if let Some(word) = &word {
+                    used.insert(word.clone());
+                }
+                moves.push((think, word));
+            }
+            moves
+        };
+        assert_eq!(play(5), play(5));
+    }
+
+    #[test]
+    fn test_bot_waits_out_its_think_time() {
+        for difficulty in [
+            BotDifficulty::Easy,
+            BotDifficulty::Medium,
+            BotDifficulty::Hard,
+        ] {
+            let (min, max) = think_time_range(difficulty);
+            let mut bot = LexiBot::new(difficulty);
+            let start = Instant::now();
+
+            assert!(!bot.is_ready(start), "not ready before its turn");
+            let think = bot.start_turn(start, &mut GameRng::new(1));
+            assert!(think >= min && think <= max);
+            assert!(max < Duration::from_secs(super::super::TURN_TIMEOUT_SECS));
+
+            assert!(!bot.is_ready(start));
+            assert!(!bot.is_ready(start + think - Duration::from_millis(1)));
+            assert!(bot.is_ready(start + think));
+
+            bot.end_turn();
+            assert!(!bot.is_ready(start + max));
+        }
+
+        // Harder bots answer sooner
+        let easy = think_time_range(BotDifficulty::Easy);
+        let hard = think_time_range(BotDifficulty::Hard);
+        assert!(hard.1 < easy.0);
+    }
+}
@@ -0,0 +1,174 @@
+// Lexi Wars settings: how a game is won
+//
+// LEXI_WARS_MODE is `last_standing` (the default: timing out eliminates, the
+// last player left wins) or `scoring` (nobody is eliminated; every valid word
+// earns points and the highest score when LEXI_WARS_TIME_LIMIT_SECS runs out
+// wins).
+//
+// LEXI_WARS_MAX_SUBMISSION_LENGTH caps how many letters a submitted word may
+// have; longer submissions are refused before the dictionary is consulted.
+
+/// How long a scoring game runs unless configured otherwise
+pub const DEFAULT_SCORING_TIME_LIMIT_SECS: u64 = 300;
+
+/// Longest word a player may submit unless configured otherwise (the longest
+/// English dictionary word has 45 letters)
+pub const DEFAULT_MAX_SUBMISSION_LENGTH: usize = 45;
+
+/// How a Lexi Wars game is won
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LexiWarsMode {
+    #[default]
+    LastStanding,
+    Scoring {
+        time_limit_secs: u64,
+    },
+}
+
+impl LexiWarsMode {
+    /// Name sent to clients
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::LastStanding => "lastStanding",
+            Self::Scoring { .. } => "scoring",
+        }
+    }
+
+    /// How long the game runs; None when it runs until one player is left
+    pub fn time_limit_secs(&self) -> Option<u64> {
+        match self {
+            Self::LastStanding => None,
+            Self::Scoring { time_limit_secs } => Some(*time_limit_secs),
+        }
+    }
+
+    pub fn is_scoring(&self) -> bool {
+        matches!(self, Self::Scoring { .. })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LexiWarsConfig {
+    pub mode: LexiWarsMode,
+    /// Letters allowed in a submitted word
+    pub max_submission_length: usize,
+}
+
+impl Default for LexiWarsConfig {
+    fn default() -> Self {
+        Self {
+            mode: LexiWarsMode::default(),
+            max_submission_length: DEFAULT_MAX_SUBMISSION_LENGTH,
+        }
+    }
+}
+
+impl LexiWarsConfig {
+    /// Read `LEXI_WARS_MODE`, `LEXI_WARS_TIME_LIMIT_SECS` and
+    /// `LEXI_WARS_MAX_SUBMISSION_LENGTH`.
+    pub fn from_env() -> Result<Self, String> {
+        let var = |name: &str| std::env::var(name).ok();
+        Self::parse(
+            var("LEXI_WARS_MODE").as_deref(),
+            var("LEXI_WARS_TIME_LIMIT_SECS").as_deref(),
+            var("LEXI_WARS_MAX_SUBMISSION_LENGTH").as_deref(),
+        )
+    }
+
+    pub fn parse(
+        mode: Option<&str>,
+        time_limit_secs: Option<&str>,
+        max_submission_length: Option<&str>,
+    ) -> Result<Self, String> {
+        let mode = match mode.map(str::trim) {
+            None | Some("") | Some("last_standing") => LexiWarsMode::LastStanding,
+            Some("scoring") => {
+                let time_limit_secs = match time_limit_secs {
+                    Some(value) => value
+                        .trim()
+                        .parse()
+                        .ok()
+                        .filter(|secs| *secs > 0)
+                        .ok_or_else(|| {
+                            format!("LEXI_WARS_TIME_LIMIT_SECS: invalid time limit '{}'", value)
+                        })?,
+                    None => DEFAULT_SCORING_TIME_LIMIT_SECS,
+                };
+                LexiWarsMode::Scoring { time_limit_secs }
+            }
+            Some(other) => {
+                return Err(format!(
+                    "LEXI_WARS_MODE: '{}' is not last_standing or scoring",
+                    other
+                ));
+            }
+        };
+        let max_submission_length = match max_submission_length {
+            Some(value) => value
+                .trim()
+                .parse()
+                .ok()
+                .filter(|len| *len > 0)
+                .ok_or_else(|| {
+                    format!(
+                        "LEXI_WARS_MAX_SUBMISSION_LENGTH: invalid length '{}'",
+                        value
+                    )
+                })?,
+            None => DEFAULT_MAX_SUBMISSION_LENGTH,
+        };
+        Ok(Self {
+            mode,
+            max_submission_length,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mode() {
+        assert_eq!(
+            LexiWarsConfig::parse(None, Some("60"), None).unwrap().mode,
+            LexiWarsMode::LastStanding
+        );
+        assert_eq!(
+            LexiWarsConfig::parse(Some("scoring"), None, None)
+                .unwrap()
+                .mode,
+            LexiWarsMode::Scoring {
+                time_limit_secs: DEFAULT_SCORING_TIME_LIMIT_SECS
+            }
+        );
+        assert_eq!(
+            LexiWarsConfig::parse(Some(" scoring "), Some("90"), None)
+                .unwrap()
+                .mode,
+            LexiWarsMode::Scoring {
+                time_limit_secs: 90
+            }
+        );
+        assert!(LexiWarsConfig::parse(Some("scoring"), Some("0"), None).is_err());
+        assert!(LexiWarsConfig::parse(Some("sudden_death"), None, None).is_err());
+    }
+
+    #[test]
+    fn test_parse_max_submission_length() {
+        assert_eq!(
+            LexiWarsConfig::parse(None, None, None)
+                .unwrap()
+                .max_submission_length,
+            DEFAULT_MAX_SUBMISSION_LENGTH
+        );
+        assert_eq!(
+            LexiWarsConfig::parse(None, None, Some(" 20 "))
+                .unwrap()
+                .max_submission_length,
+            20
+        );
+        assert!(LexiWarsConfig::parse(None, None, Some("0")).is_err());
+        assert!(LexiWarsConfig::parse(None, None, Some("long")).is_err());
+    }
+}
@@ -8,25 +8,32 @@
 // - Word validation
 
 use crate::{
-    db::player_state::PlayerStateRepository,
+    db::{
+        game_word::GameWordRepository, lobby::LobbyRepository, lobby_result::LobbyResultRepository,
+        player_state::PlayerStateRepository, signed_result::SignedResultRepository,
+    },
     errors::AppError,
-    games::{GameEngine, GameError, GameResults, common::*},
-    models::PlayerState,
+    games::{Audience, GameEngine, GameError, GameResults, GameRng, LEXI_WARS_GAME_ID, common::*},
+    models::{BotDifficulty, Lobby, PayoutTable, PlayerState, SignedResults},
     state::AppState,
     ws::{broadcast, room::messages::RoomServerMessage},
 };
 use async_trait::async_trait;
-use once_cell::sync::Lazy;
-use serde_json::Value;
+use rust_decimal::Decimal;
+use serde_json::{Value, json};
 use std::{
     collections::{HashMap, HashSet},
     sync::Arc,
+    time::{Duration, Instant},
 };
 use tokio::sync::{Notify, RwLock};
 use uuid::Uuid;
 
+use super::bot::LexiBot;
+use super::config::{DEFAULT_MAX_SUBMISSION_LENGTH, LexiWarsMode};
 use super::message::{LexiWarsAction, LexiWarsEvent};
-use super::rule::{Rule, RuleContext, get_rule_at_index, rule_count};
+use super::rule::{ClientRule, Rule, RuleContext, get_rule_at_index, rule_count};
+use super::validator::{BloomWordValidator, DICTIONARY, WordValidator};
 
 // ============================================================================
 // Constants
@@ -35,12 +42,94 @@ use super::rule::{Rule, RuleContext, get_rule_at_index, rule_count};
 pub const TURN_TIMEOUT_SECS: u64 = 15;
 pub const INITIAL_MIN_WORD_LENGTH: usize = 4;
 pub const WORD_LENGTH_INCREMENT: usize = 2;
+/// Minimum word length stops growing here unless configured otherwise
+pub const DEFAULT_MAX_WORD_LENGTH: usize = 10;
+/// Fresh letters tried before a rule nobody can satisfy is declared an impasse
+pub const IMPASSE_LETTER_REROLLS: usize = 8;
+/// Letters worth more than the rest in the scoring mode
+pub const BONUS_LETTERS: &[char] = &['q', 'x', 'z'];
+/// Points for each of the BONUS_LETTERS; every other letter is worth one
+pub const BONUS_LETTER_POINTS: i32 = 5;
+
+/// JSON Schema of the game's settings, with the defaults this server plays by.
+pub fn config_schema(max_word_length: usize) -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "turnTimeoutSecs": { "type": "integer", "default": TURN_TIMEOUT_SECS },
+            "initialMinWordLength": { "type": "integer", "default": INITIAL_MIN_WORD_LENGTH },
+            "wordLengthIncrement": { "type": "integer", "default": WORD_LENGTH_INCREMENT },
+            "maxWordLength": { "type": "integer", "default": max_word_length },
+        },
+    })
+}
 
-// Load dictionary at compile time
-static DICTIONARY: Lazy<HashSet<String>> = Lazy::new(|| {
-    let dict_json = include_str!("../../assets/dictionary.json");
-    serde_json::from_str(dict_json).unwrap_or_default()
-});
+/// Difficulty after a full rule cycle: a longer minimum length until `cap`,
+/// then one more required rare letter per cycle.
+pub fn escalate(min_word_length: usize, rare_letters: usize, cap: usize) -> (usize, usize) {
+    let next = (min_word_length + WORD_LENGTH_INCREMENT).min(cap);
+    if next > min_word_length {
+        (next, rare_letters)
+    } else {
+        (min_word_length, rare_letters + 1)
+    }
+}
+
+/// Points `word` earns in the scoring mode: one per letter, more for rare ones
+pub fn word_points(word: &str) -> i32 {
+    word.chars()
+        .map(|c| {
+            if BONUS_LETTERS.contains(&c.to_ascii_lowercase()) {
+                BONUS_LETTER_POINTS
+            } else {
+                1
+            }
+        })
+        .sum()
+}
+
+/// Normalize a submitted word: trimmed and lowercased, refused if it is
+/// empty, has anything but ASCII letters, or runs past `max_len` letters.
+pub fn sanitize_word(word: &str, max_len: usize) -> Result<String, GameError> {
+    let word = word.trim();
+    let length = word.chars().count();
+    if length > max_len {
+        return Err(GameError::WordTooLong {
+            length,
+            max: max_len,
+        });
+    }
+    if word.is_empty() {
+        return Err(GameError::InvalidWord("no word submitted".into()));
+    }
+    if !word.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(GameError::InvalidWord("only letters are allowed".into()));
+    }
+    Ok(word.to_ascii_lowercase())
+}
+
+/// The rule for `ctx`, re-rolling its letter from `rng` until some unused word
+/// in `dictionary` satisfies it. None when no word can, i.e. the game is at an
+/// impasse.
+pub fn playable_rule<'a>(
+    mut ctx: RuleContext,
+    dictionary: impl IntoIterator<Item = &'a String> + Clone,
+    used: &HashSet<String>,
+    rng: &mut GameRng,
+) -> Option<(Rule, RuleContext)> {
+    for _ in 0..=IMPASSE_LETTER_REROLLS {
+        let rule = get_rule_at_index(&ctx);
+        let playable = dictionary
+            .clone()
+            .into_iter()
+            .any(|word| !used.contains(word) && rule.check(word, &ctx).is_ok());
+        if playable {
+            return Some((rule, ctx));
+        }
+        ctx.regenerate_letter(rng);
+    }
+    None
+}
 
 // ============================================================================
 // Inner State (shared via Arc<RwLock>)
@@ -53,9 +142,17 @@ struct LexiWarsInner {
     player_states: HashMap<Uuid, PlayerState>,
     turn_rotation: TurnRotation,
     used_words: HashSet<String>,
+    /// Valid words accepted so far; numbers the rows in `game_words`
+    words_played: i32,
     current_round: usize,
     current_rule_index: usize,
     current_min_word_length: usize,
+    /// Cap on `current_min_word_length`, from config
+    max_word_length: usize,
+    /// Longest word a player may submit, from config
+    max_submission_length: usize,
+    /// Rare letters required once the length cap is reached
+    rare_letters_required: usize,
     current_rule: Option<Rule>,
     current_rule_context: Option<RuleContext>,
     total_players: usize,
@@ -63,28 +160,57 @@ struct LexiWarsInner {
     results: Option<GameResults>,
 
     // Prize/points calculation context
-    entry_amount: Option<f64>,
-    current_amount: Option<f64>,
+    entry_amount: Option<Decimal>,
+    current_amount: Option<Decimal>,
     is_sponsored: bool,
     creator_id: Option<Uuid>,
+    /// The lobby's own payout table; the default for the player count if unset
+    payouts: Option<PayoutTable>,
 
     // Game loop control - Notify is used to signal valid word submission
     turn_advance_notify: Arc<Notify>,
 
+    /// When the current turn started (server epoch millis, 0 before the first turn)
+    turn_started_ms: u64,
+
+    /// When the game was initialized
+    started_at: Option<Instant>,
+    /// The game doesn't resolve before this much time has passed
+    min_duration: Duration,
+
+    /// Practice bots seated in this game, by their player ID
+    bots: HashMap<Uuid, LexiBot>,
+    /// Checks submitted words against the dictionary
+    validator: Arc<dyn WordValidator>,
+    /// Seed the turn order is drawn from, from `set_seeds`; without one
+    /// players keep the order they were passed in
+    seating_seed: Option<u64>,
+    /// Seed `rng` was started from, recorded on the lobby once the game ends
+    game_seed: Option<u64>,
+    /// Draws rule letters and bot moves
+    rng: GameRng,
+    /// Last player standing, or points against the clock
+    mode: LexiWarsMode,
+
     state: AppState,
 }
 
 impl LexiWarsInner {
-    fn new(lobby_id: Uuid, state: AppState) -> Self {
+    fn new(lobby_id: Uuid, state: AppState, validator: Arc<dyn WordValidator>) -> Self {
+        let rng = GameRng::new(state.config.game_seed());
         Self {
             lobby_id,
             players: HashMap::new(),
             player_states: HashMap::new(),
             turn_rotation: TurnRotation::new(Vec::new()),
             used_words: HashSet::new(),
+            words_played: 0,
             current_round: 0,
             current_rule_index: 0,
             current_min_word_length: INITIAL_MIN_WORD_LENGTH,
+            max_word_length: DEFAULT_MAX_WORD_LENGTH,
+            max_submission_length: DEFAULT_MAX_SUBMISSION_LENGTH,
+            rare_letters_required: 0,
             current_rule: None,
             current_rule_context: None,
             total_players: 0,
@@ -94,7 +220,17 @@ impl LexiWarsInner {
             current_amount: None,
             is_sponsored: false,
             creator_id: None,
+            payouts: None,
             turn_advance_notify: Arc::new(Notify::new()),
+            turn_started_ms: 0,
+            started_at: None,
+            min_duration: Duration::ZERO,
+            bots: HashMap::new(),
+            validator,
+            seating_seed: None,
+            game_seed: None,
+            rng,
+            mode: LexiWarsMode::LastStanding,
             state,
         }
     }
@@ -111,8 +247,25 @@ pub struct LexiWarsEngine {
 
 impl LexiWarsEngine {
     pub fn new(lobby_id: Uuid, state: AppState) -> Self {
+        let validator = state
+            .config
+            .lexi_wars_dictionary
+            .build(state.redis.clone())
+            .unwrap_or_else(|e| {
+                tracing::error!("Dictionary unavailable, using the bundled one: {}", e);
+                Arc::new(BloomWordValidator::bundled())
+            });
+        Self::with_validator(lobby_id, state, validator)
+    }
+
+    /// An engine checking words with `validator` instead of the configured one
+    pub fn with_validator(
+        lobby_id: Uuid,
+        state: AppState,
+        validator: Arc<dyn WordValidator>,
+    ) -> Self {
         Self {
-            inner: Arc::new(RwLock::new(LexiWarsInner::new(lobby_id, state))),
+            inner: Arc::new(RwLock::new(LexiWarsInner::new(lobby_id, state, validator))),
         }
     }
 
@@ -124,16 +277,18 @@ impl LexiWarsEngine {
     /// Set lobby context for prize/points calculation
     pub async fn set_lobby_context(
         &self,
-        entry_amount: Option<f64>,
-        current_amount: Option<f64>,
+        entry_amount: Option<Decimal>,
+        current_amount: Option<Decimal>,
         is_sponsored: bool,
         creator_id: Uuid,
+        payouts: Option<PayoutTable>,
     ) {
         let mut inner = self.inner.write().await;
         inner.entry_amount = entry_amount;
         inner.current_amount = current_amount;
         inner.is_sponsored = is_sponsored;
         inner.creator_id = Some(creator_id);
+        inner.payouts = payouts;
     }
 }
 
@@ -142,9 +297,20 @@ impl LexiWarsEngine {
 // ============================================================================
 
 impl LexiWarsInner {
-    /// Validate a word against dictionary
-    fn is_valid_dictionary_word(&self, word: &str) -> bool {
-        DICTIONARY.contains(&word.to_lowercase())
+    /// Practice games have a bot seated; their results aren't saved
+    fn is_practice(&self) -> bool {
+        !self.bots.is_empty()
+    }
+
+    /// When a scoring game's clock runs out
+    fn deadline(&self) -> Option<Instant> {
+        let limit = self.mode.time_limit_secs()?;
+        Some(self.started_at? + Duration::from_secs(limit))
+    }
+
+    fn is_out_of_time(&self) -> bool {
+        self.deadline()
+            .is_some_and(|deadline| Instant::now() >= deadline)
     }
 
     /// Check if word has been used
@@ -171,11 +337,16 @@ impl LexiWarsInner {
         // Check if we've completed a full cycle of rules
         if self.current_rule_index >= rule_count() {
             self.current_rule_index = 0;
-            self.current_min_word_length += WORD_LENGTH_INCREMENT;
+            (self.current_min_word_length, self.rare_letters_required) = escalate(
+                self.current_min_word_length,
+                self.rare_letters_required,
+                self.max_word_length,
+            );
             self.current_round += 1;
             tracing::info!(
-                "LexiWars: Rule cycle complete, increasing min word length to {}",
-                self.current_min_word_length
+                "LexiWars: Rule cycle complete, min word length {}, rare letters {}",
+                self.current_min_word_length,
+                self.rare_letters_required
             );
         }
 
@@ -184,7 +355,9 @@ impl LexiWarsInner {
             self.current_round,
             self.current_rule_index,
             self.current_min_word_length,
-        );
+            &mut self.rng,
+        )
+        .with_rare_letters(self.rare_letters_required);
         let rule = get_rule_at_index(&ctx);
 
         self.current_rule_context = Some(ctx);
@@ -196,11 +369,13 @@ impl LexiWarsInner {
         self.current_round = 1;
         self.current_rule_index = 0;
         self.current_min_word_length = INITIAL_MIN_WORD_LENGTH;
+        self.rare_letters_required = 0;
 
         let ctx = RuleContext::new(
             self.current_round,
             self.current_rule_index,
             self.current_min_word_length,
+            &mut self.rng,
         );
         let rule = get_rule_at_index(&ctx);
 
@@ -208,28 +383,56 @@ impl LexiWarsInner {
         self.current_rule = Some(rule);
     }
 
+    /// Make sure someone can still play a word under the current rule,
+    /// re-rolling its letter if needed. Returns false at an impasse.
+    fn ensure_playable_rule(&mut self) -> bool {
+        let Some(ctx) = self.current_rule_context.clone() else {
+            return true;
+        };
+        match playable_rule(ctx, DICTIONARY.iter(), &self.used_words, &mut self.rng) {
+            Some((rule, ctx)) => {
+                self.current_rule = Some(rule);
+                self.current_rule_context = Some(ctx);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The current rule as sent to the player whose turn it is
+    fn client_rule(&self) -> Option<ClientRule> {
+        let (rule, ctx) = (
+            self.current_rule.as_ref()?,
+            self.current_rule_context.as_ref()?,
+        );
+        Some(rule.to_client_rule(ctx))
+    }
+
+    /// How the pot is split between `participants` players
+    fn payout_table(&self, participants: usize) -> PayoutTable {
+        self.payouts
+            .clone()
+            .unwrap_or_else(|| PayoutTable::default_for(participants))
+    }
+
     /// Calculate prize for a given rank
-    fn calculate_prize(&self, rank: usize, participants: usize) -> Option<f64> {
+    fn calculate_prize(&self, rank: usize, participants: usize) -> Option<Decimal> {
         let total_pool = self.current_amount?;
 
-        if total_pool <= 0.0 {
+        if total_pool <= Decimal::ZERO {
             return None;
         }
 
-        let prize = match rank {
-            1 => {
-                if participants == 2 {
-                    (total_pool * 70.0) / 100.0
-                } else {
-                    (total_pool * 50.0) / 100.0
-                }
-            }
-            2 => (total_pool * 30.0) / 100.0,
-            3 => (total_pool * 20.0) / 100.0,
-            _ => 0.0,
-        };
+        let shares = self
+            .payout_table(participants)
+            .shares(total_pool, participants);
+        let prize = shares.get(rank.checked_sub(1)?).copied()?;
 
-        if prize > 0.0 { Some(prize) } else { None }
+        if prize > Decimal::ZERO {
+            Some(prize)
+        } else {
+            None
+        }
     }
 
     /// Build WarsPointContext for a player result
@@ -237,10 +440,11 @@ impl LexiWarsInner {
         &self,
         user_id: Uuid,
         rank: usize,
-        prize: Option<f64>,
+        prize: Option<Decimal>,
     ) -> WarsPointContext {
         WarsPointContext {
             user_id,
+            game_id: LEXI_WARS_GAME_ID,
             rank,
             prize,
             participants: self.total_players,
@@ -269,11 +473,15 @@ impl LexiWarsInner {
 
         // Save to Redis and PostgreSQL using save_player_result
         let ctx = self.build_wars_point_context(player_id, rank, prize);
-        let wars_point = match save_player_result(&self.state, self.lobby_id, &ctx).await {
-            Ok(result) => result.wars_point,
-            Err(e) => {
-                tracing::error!("Failed to save player result: {}", e);
-                calculate_wars_point(&ctx) // Fallback to just calculating
+        let wars_point = if self.is_practice() {
+            0.0
+        } else {
+            match save_player_result(&self.state, self.lobby_id, &ctx).await {
+                Ok(result) => result.wars_point,
+                Err(e) => {
+                    tracing::error!("Failed to save player result: {}", e);
+                    calculate_wars_point(&ctx) // Fallback to just calculating
+                }
             }
         };
 
@@ -302,6 +510,7 @@ impl LexiWarsInner {
                 rank,
                 prize,
                 wars_point,
+                signed_results: None,
             };
             broadcast::broadcast_user(&self.state, player_id, &game_over).await;
 
@@ -322,27 +531,47 @@ impl LexiWarsInner {
     /// End the game and calculate final standings
     /// Sends GameOver to remaining players (winner(s)) and FinalStanding to room
     async fn end_game(&mut self) {
+        if self.finished {
+            return;
+        }
         self.finished = true;
 
-        // Build rankings from player states
-        let player_game_states: Vec<GamePlayerState> = self.players.values().cloned().collect();
-        let results = GameResults::from_game_states(player_game_states);
+        // Build rankings from player states, in seat order so ties are settled by seat
+        let player_game_states: Vec<GamePlayerState> = self
+            .turn_rotation
+            .seats()
+            .filter_map(|id| self.players.get(id).cloned())
+            .collect();
+        let mut results = GameResults::from_game_states(player_game_states)
+            .with_stakes(self.entry_amount, self.current_amount);
+        if self.is_practice() {
+            results = results.unranked();
+        } else if let Some(pool) = self.current_amount {
+            results.apply_payouts(pool, &self.payout_table(self.total_players));
+        }
+
+        let signed_results = if results.ranked {
+            self.sign_results(&results).await
+        } else {
+            None
+        };
 
         // Get remaining active players (they need results saved + GameOver)
         let active_player_ids: Vec<Uuid> = self.turn_rotation.active_players().clone();
 
         // Update player states with rank, prize, wars_point
-        let participants = self.total_players;
         let mut final_standings: Vec<PlayerState> = Vec::new();
         let state = self.state.clone();
         let lobby_id = self.lobby_id;
 
         for ranking in &results.rankings {
-            let prize = self.calculate_prize(ranking.rank, participants);
+            let prize = ranking.prize;
             let is_active = active_player_ids.contains(&ranking.user_id);
 
             // Only save results for active players (winner) - eliminated players already saved
-            let wars_point = if is_active {
+            let wars_point = if self.is_practice() {
+                0.0
+            } else if is_active {
                 let ctx = self.build_wars_point_context(ranking.user_id, ranking.rank, prize);
                 match save_player_result(&state, lobby_id, &ctx).await {
                     Ok(result) => result.wars_point,
@@ -372,20 +601,82 @@ impl LexiWarsInner {
                     rank: ranking.rank,
                     prize,
                     wars_point,
+                    signed_results: signed_results.clone(),
                 };
                 broadcast::broadcast_user(&state, ranking.user_id, &game_over).await;
             }
         }
 
+        let lobby_repo = LobbyRepository::new(state.postgres.clone());
+        if let Err(e) = lobby_repo.record_finished(lobby_id).await {
+            tracing::error!("Failed to record finish of {}: {}", lobby_id, e);
+        }
+        // The gameplay seed can't help anyone now; keep it for replays and audits
+        if let Some(seed) = self.game_seed
+            && let Err(e) = lobby_repo.record_game_seed(lobby_id, seed).await
+        {
+            tracing::error!("Failed to record game seed for {}: {}", lobby_id, e);
+        }
+        // Kept so the lobby's points can be reprocessed later
+        if results.ranked
+            && let Err(e) = LobbyResultRepository::new(state.postgres.clone())
+                .store(lobby_id, &results)
+                .await
+        {
+            tracing::error!("Failed to store results of {}: {}", lobby_id, e);
+        }
+
+        // The standings reveal who played to spectators of anonymized lobbies
+        state.room_pseudonyms.reveal(&state.redis, lobby_id).await;
+
         // Broadcast FinalStanding to room (shared event via RoomServerMessage)
         let final_standing = RoomServerMessage::FinalStanding {
             standings: final_standings,
+            ranked: results.ranked,
         };
         broadcast::broadcast_room(&state, lobby_id, &final_standing).await;
 
+        if !self.is_practice() {
+            if let Err(e) = update_skill_ratings(&state, lobby_id, &results).await {
+                tracing::error!("Failed to update skill ratings: {}", e);
+            }
+            if let Err(e) = settle_predictions(&state, lobby_id, &results).await {
+                tracing::error!("Failed to settle predictions: {}", e);
+            }
+        }
+
         self.results = Some(results);
     }
 
+    /// Sign the final results with the server key, if one is configured, and
+    /// store them for settlement
+    async fn sign_results(&self, results: &GameResults) -> Option<SignedResults> {
+        let key = self.state.config.result_signing_key.as_ref()?;
+        let wallets: HashMap<Uuid, String> = self
+            .player_states
+            .iter()
+            .map(|(user_id, player)| (*user_id, player.wallet_address.clone()))
+            .collect();
+        let signed = match key.sign_results(self.lobby_id, results, &wallets) {
+            Ok(signed) => signed,
+            Err(e) => {
+                tracing::error!("Failed to sign results for {}: {}", self.lobby_id, e);
+                return None;
+            }
+        };
+        if let Err(e) = SignedResultRepository::new(self.state.postgres.clone())
+            .store(&signed)
+            .await
+        {
+            tracing::error!(
+                "Failed to store signed results for {}: {}",
+                self.lobby_id,
+                e
+            );
+        }
+        Some(signed)
+    }
+
     /// Start the turn for the current player
     async fn start_turn(&mut self) {
         let Some(current_player_id) = self.turn_rotation.current_player() else {
@@ -396,11 +687,13 @@ impl LexiWarsInner {
             return;
         };
 
+        if let Some(bot) = self.bots.get_mut(&current_player_id) {
+            bot.start_turn(Instant::now(), &mut self.rng);
+        }
+
         // Broadcast Turn event to room
-        let turn_event = LexiWarsEvent::Turn {
-            player: current_player_state.clone(),
-            timeout_secs: TURN_TIMEOUT_SECS,
-        };
+        self.turn_started_ms = server_time_ms();
+        let turn_event = turn_event(current_player_state.clone(), self.turn_started_ms);
         broadcast::broadcast_game_message(
             &self.state,
             self.lobby_id,
@@ -410,7 +703,7 @@ impl LexiWarsInner {
 
         // Broadcast Rule event to room
         // Current player gets Some(rule), others get None (to clear previous rule)
-        let rule_for_current = self.current_rule.as_ref().map(|r| r.to_client_rule());
+        let rule_for_current = self.client_rule();
 
         // Send Rule with Some(rule) to current player
         let rule_event = LexiWarsEvent::Rule {
@@ -423,26 +716,35 @@ impl LexiWarsInner {
         )
         .await;
 
-        // Send Rule with None to all other players in room (to clear their UI)
+        // Send Rule with None to the other players (to clear their UI);
+        // spectators never see a rule
         let rule_event_clear = LexiWarsEvent::Rule { rule: None };
-        broadcast::broadcast_game_message_to_room_except(
+        broadcast::broadcast_game_message_to(
             &self.state,
             self.lobby_id,
-            current_player_id,
+            Audience::Players,
+            Some(current_player_id),
             serde_json::to_value(&rule_event_clear).unwrap_or_default(),
         )
         .await;
     }
 
-    /// Handle word submission
-    fn handle_submit_word(
-        &mut self,
-        user_id: Uuid,
-        word: String,
-    ) -> Result<Vec<LexiWarsEvent>, GameError> {
-        let mut events = Vec::new();
-        let word_lower = word.to_lowercase().trim().to_string();
+    /// Persist an accepted word in the background; the turn number keeps order
+    fn record_word(&self, user_id: Uuid, word: String) {
+        if self.is_practice() {
+            return;
+        }
+        let repo = GameWordRepository::new(self.state.postgres.clone());
+        let (lobby_id, turn) = (self.lobby_id, self.words_played);
+        tokio::spawn(async move {
+            if let Err(e) = repo.record_word(lobby_id, user_id, &word, turn).await {
+                tracing::error!("Failed to record word for lobby {}: {}", lobby_id, e);
+            }
+        });
+    }
 
+    /// Whether `user_id` may submit a word right now
+    fn check_can_submit(&self, user_id: Uuid) -> Result<(), GameError> {
         // Verify it's this player's turn
         if self.turn_rotation.current_player() != Some(user_id) {
             return Err(GameError::NotYourTurn);
@@ -462,6 +764,24 @@ impl LexiWarsInner {
             return Err(GameError::AlreadyEliminated);
         }
 
+        Ok(())
+    }
+
+    /// Handle word submission
+    ///
+    /// `lookup` is the dictionary's answer for the word, asked before the game
+    /// was locked; the turn checks run again since the turn may have moved on.
+    fn handle_submit_word(
+        &mut self,
+        user_id: Uuid,
+        word: String,
+        lookup: Result<bool, AppError>,
+    ) -> Result<Vec<LexiWarsEvent>, GameError> {
+        let mut events = Vec::new();
+
+        self.check_can_submit(user_id)?;
+        let word_lower = sanitize_word(&word, self.max_submission_length)?;
+
         // Check if word has been used - send only to submitting user
         if self.is_word_used(&word_lower) {
             events.push(LexiWarsEvent::UsedWord { word: word_lower });
@@ -469,16 +789,27 @@ impl LexiWarsInner {
         }
 
         // Validate against dictionary - send only to submitting user
-        if !self.is_valid_dictionary_word(&word_lower) {
-            events.push(LexiWarsEvent::Invalid {
-                reason: format!("'{}' is not in the dictionary", word),
-            });
-            return Ok(events);
+        match lookup {
+            Ok(true) => {}
+            Ok(false) => {
+                events.push(LexiWarsEvent::Invalid {
+                    reason: format!("'{}' is not in the dictionary", word),
+                });
+                return Ok(events);
+            }
+            Err(e) => {
+                // The turn goes on; the player can submit again
+                tracing::warn!("Couldn't check '{}' in the dictionary: {}", word_lower, e);
+                events.push(LexiWarsEvent::Invalid {
+                    reason: format!("Couldn't check '{}' right now, try again", word),
+                });
+                return Ok(events);
+            }
         }
 
         // Validate against current rule - send only to submitting user
         if let (Some(rule), Some(ctx)) = (&self.current_rule, &self.current_rule_context) {
-            if let Err(reason) = (rule.validate)(&word_lower, ctx) {
+            if let Err(reason) = rule.check(&word_lower, ctx) {
                 events.push(LexiWarsEvent::Invalid { reason });
                 return Ok(events);
             }
@@ -486,6 +817,18 @@ impl LexiWarsInner {
 
         // Word is valid! Mark as used
         self.used_words.insert(word_lower.clone());
+        self.words_played += 1;
+        // Last standing counts words played; scoring counts points
+        let points = if self.mode.is_scoring() {
+            word_points(&word_lower)
+        } else {
+            1
+        };
+        let score = self.players.get_mut(&user_id).map(|player| {
+            player.score += points;
+            player.score
+        });
+        self.record_word(user_id, word_lower.clone());
 
         // Get player state for WordEntry event
         let player_state = self.get_player_state(user_id);
@@ -493,12 +836,81 @@ impl LexiWarsInner {
         if let Some(player) = player_state {
             events.push(LexiWarsEvent::WordEntry {
                 word: word_lower,
-                player,
+                player: player.clone(),
             });
+            if self.mode.is_scoring()
+                && let Some(score) = score
+            {
+                events.push(LexiWarsEvent::ScoreUpdate {
+                    player,
+                    points,
+                    score,
+                });
+            }
         }
 
         Ok(events)
     }
+
+    /// Submit a word for `user_id`, waking the game loop when it's accepted
+    fn submit_word(
+        &mut self,
+        user_id: Uuid,
+        word: String,
+        lookup: Result<bool, AppError>,
+    ) -> Result<Vec<Value>, AppError> {
+        let events = self.handle_submit_word(user_id, word, lookup)?;
+
+        // Check if we got a valid WordEntry (not UsedWord or Invalid)
+        let has_valid_word = events
+            .iter()
+            .any(|e| matches!(e, LexiWarsEvent::WordEntry { .. }));
+
+        if has_valid_word {
+            // Signal the game loop to advance turn
+            self.turn_advance_notify.notify_one();
+        }
+
+        // Convert to JSON
+        events
+            .into_iter()
+            .map(|e| serde_json::to_value(e).map_err(|e| AppError::Serialization(e.to_string())))
+            .collect()
+    }
+
+    /// Play the bot's word once it's the bot's turn and it has finished thinking
+    async fn play_bot_turn(&mut self) -> Result<Vec<Value>, AppError> {
+        if self.finished {
+            return Ok(Vec::new());
+        }
+        let Some(bot_id) = self.turn_rotation.current_player() else {
+            return Ok(Vec::new());
+        };
+        let Some(bot) = self.bots.get_mut(&bot_id) else {
+            return Ok(Vec::new());
+        };
+        if !bot.is_ready(Instant::now()) {
+            return Ok(Vec::new());
+        }
+        bot.end_turn();
+
+        let (Some(rule), Some(ctx)) = (&self.current_rule, &self.current_rule_context) else {
+            return Ok(Vec::new());
+        };
+        // No word fits: the bot sits out the turn and the timer eliminates it
+        let Some(word) = self.bots[&bot_id].pick_word(
+            DICTIONARY.iter(),
+            rule,
+            ctx,
+            &self.used_words,
+            &mut self.rng,
+        ) else {
+            return Ok(Vec::new());
+        };
+
+        // Bots only play bundled dictionary words, so there's nothing to look up
+        self.submit_word(bot_id, word, Ok(true))
+    }
 }
 
 // ============================================================================
@@ -507,6 +919,31 @@ impl LexiWarsInner {
 
 #[async_trait]
 impl GameEngine for LexiWarsEngine {
+    async fn add_bot(&mut self, difficulty: BotDifficulty) -> Result<Uuid, AppError> {
+        let bot = LexiBot::new(difficulty);
+        let bot_id = bot.user_id;
+        self.inner.write().await.bots.insert(bot_id, bot);
+        Ok(bot_id)
+    }
+
+    async fn set_lobby(&mut self, lobby: &Lobby) {
+        self.set_lobby_context(
+            lobby.entry_amount,
+            lobby.current_amount,
+            lobby.is_sponsored,
+            lobby.creator_id,
+            lobby.payout_table(),
+        )
+        .await;
+    }
+
+    async fn set_seeds(&mut self, seating_seed: u64, game_seed: u64) {
+        let mut inner = self.inner.write().await;
+        inner.seating_seed = Some(seating_seed);
+        inner.game_seed = Some(game_seed);
+        inner.rng = GameRng::new(game_seed);
+    }
+
     async fn initialize(&mut self, player_ids: Vec<Uuid>) -> Result<Vec<Value>, AppError> {
         tracing::info!("Initializing LexiWars with {} players", player_ids.len());
 
@@ -522,28 +959,76 @@ impl GameEngine for LexiWarsEngine {
 
         let mut inner = self.inner.write().await;
 
+        // Draw the turn order; bots keep their seats after the players
+        let player_ids = match inner.seating_seed {
+            Some(seed) => {
+                let (bots, players): (Vec<Uuid>, Vec<Uuid>) = player_ids
+                    .into_iter()
+                    .partition(|id| inner.bots.contains_key(id));
+                let mut seats = seat_players(players, seed);
+                seats.extend(bots);
+                seats
+            }
+            None => player_ids,
+        };
+
         inner.total_players = player_ids.len();
         inner.players = player_ids
             .iter()
             .map(|&id| (id, GamePlayerState::new(id)))
             .collect();
         inner.turn_rotation = TurnRotation::new(player_ids.clone());
+        inner.started_at = Some(Instant::now());
+        inner.min_duration = inner
+            .state
+            .config
+            .game_durations
+            .for_game(LEXI_WARS_GAME_ID)
+            .min();
+        inner.max_word_length = inner.state.config.lexi_wars_max_word_length;
+        inner.mode = inner.state.config.lexi_wars.mode;
+        inner.max_submission_length = inner.state.config.lexi_wars.max_submission_length;
 
         // Load player states from Redis
         let player_repo = PlayerStateRepository::new(inner.state.redis.clone());
-        if let Ok(states) = player_repo.get_all_in_lobby(inner.lobby_id).await {
+        if let Ok(states) = player_repo.list_players(inner.lobby_id).await {
             for ps in states {
                 inner.player_states.insert(ps.user_id, ps);
             }
         }
 
+        // Bots never joined through the lobby, so they have no stored state
+        let lobby_id = inner.lobby_id;
+        let bot_states: Vec<PlayerState> = inner
+            .bots
+            .values()
+            .map(|bot| {
+                PlayerState::new(
+                    bot.user_id,
+                    lobby_id,
+                    String::new(),
+                    Some(bot.name()),
+                    Some(bot.name()),
+                    0.0,
+                    None,
+                    false,
+                )
+            })
+            .collect();
+        for ps in bot_states {
+            inner.player_states.insert(ps.user_id, ps);
+        }
+
         // Initialize first rule
         inner.init_first_rule();
 
-        // Send GameStarted event (room-level, no game-specific fields)
+        // Send GameStarted event with the seating (room-level, no game-specific fields)
         let events = vec![
-            serde_json::to_value(RoomServerMessage::GameStarted)
-                .map_err(|e| AppError::Serialization(e.to_string()))?,
+            serde_json::to_value(RoomServerMessage::GameStarted {
+                seats: player_ids,
+                seating_seed: inner.seating_seed.map(|seed| seed.to_string()),
+            })
+            .map_err(|e| AppError::Serialization(e.to_string()))?,
         ];
 
         Ok(events)
@@ -554,42 +1039,33 @@ impl GameEngine for LexiWarsEngine {
         user_id: Uuid,
         action: Value,
     ) -> Result<Vec<Value>, AppError> {
-        let mut inner = self.inner.write().await;
-
-        if inner.finished {
-            return Err(AppError::BadRequest(
-                "Game has already finished".to_string(),
-            ));
-        }
-
         let action: LexiWarsAction = serde_json::from_value(action)
             .map_err(|e| AppError::BadRequest(format!("Invalid LexiWars action: {}", e)))?;
 
         tracing::debug!("LexiWars action from {}: {:?}", user_id, action);
 
-        let game_events = match action {
+        match action {
             LexiWarsAction::SubmitWord { word } => {
-                let events = inner.handle_submit_word(user_id, word)?;
-
-                // Check if we got a valid WordEntry (not UsedWord or Invalid)
-                let has_valid_word = events
-                    .iter()
-                    .any(|e| matches!(e, LexiWarsEvent::WordEntry { .. }));
+                // Ask the dictionary without holding the game, so a slow lookup
+                // stalls neither the game loop nor anyone else
+                let (word_lower, validator) = {
+                    let inner = self.inner.read().await;
+                    if inner.finished {
+                        return Err(GameError::GameFinished.into());
+                    }
+                    inner.check_can_submit(user_id)?;
+                    let word_lower = sanitize_word(&word, inner.max_submission_length)?;
+                    (word_lower, inner.validator.clone())
+                };
+                let lookup = validator.is_valid(&word_lower).await;
 
-                if has_valid_word {
-                    // Signal the game loop to advance turn
-                    inner.turn_advance_notify.notify_one();
+                let mut inner = self.inner.write().await;
+                if inner.finished {
+                    return Err(GameError::GameFinished.into());
                 }
-
-                events
+                inner.submit_word(user_id, word, lookup)
             }
-        };
-
-        // Convert to JSON
-        game_events
-            .into_iter()
-            .map(|e| serde_json::to_value(e).map_err(|e| AppError::Serialization(e.to_string())))
-            .collect()
+        }
     }
 
     async fn get_bootstrap(&self) -> Result<Value, AppError> {
@@ -611,10 +1087,13 @@ impl GameEngine for LexiWarsEngine {
             "currentRound": inner.current_round,
             "currentRuleIndex": inner.current_rule_index,
             "minWordLength": inner.current_min_word_length,
+            "rareLettersRequired": inner.rare_letters_required,
             "timeoutSecs": TURN_TIMEOUT_SECS,
             "usedWordsCount": inner.used_words.len(),
             "totalPlayers": inner.total_players,
             "remainingPlayers": inner.turn_rotation.active_count(),
+            "mode": inner.mode.name(),
+            "timeLimitSecs": inner.mode.time_limit_secs(),
         });
 
         Ok(bootstrap)
@@ -631,38 +1110,41 @@ impl GameEngine for LexiWarsEngine {
 
         // Turn - current player info
         let current_player = inner.get_current_player_state();
-        let turn = current_player.as_ref().map(|player| LexiWarsEvent::Turn {
-            player: player.clone(),
-            timeout_secs: TURN_TIMEOUT_SECS,
-        });
-
-        // Rule - Some(rule) for current player, None for others
-        let is_current_player = match user_id {
-            Some(uid) => inner.turn_rotation.current_player() == Some(uid),
-            None => false,
-        };
-
-        let rule = LexiWarsEvent::Rule {
-            rule: if is_current_player {
-                inner.current_rule.as_ref().map(|r| r.to_client_rule())
+        let turn = current_player
+            .as_ref()
+            .map(|player| turn_event(player.clone(), inner.turn_started_ms));
+
+        // Rule - Some(rule) for current player, None for other players,
+        // left out for spectators
+        let rule = user_id.map(|uid| LexiWarsEvent::Rule {
+            rule: if inner.turn_rotation.current_player() == Some(uid) {
+                inner.client_rule()
             } else {
                 None
             },
-        };
+        });
 
-        // Countdown - we don't track exact remaining time in state,
-        // but the game loop will broadcast the next countdown tick
-        // For now, we'll use the full timeout; the next tick will correct it
-        let countdown = LexiWarsEvent::Countdown {
-            time: TURN_TIMEOUT_SECS,
-        };
+        // Countdown - remaining whole seconds until the current turn's deadline
+        let deadline = deadline_ms(inner.turn_started_ms, TURN_TIMEOUT_SECS);
+        let remaining = deadline.saturating_sub(server_time_ms()).div_ceil(1000);
+        let countdown = countdown_event(remaining.min(TURN_TIMEOUT_SECS), inner.turn_started_ms);
 
-        let game_state = serde_json::json!({
+        let mut game_state = serde_json::json!({
             "playersCount": serde_json::to_value(&players_count).unwrap_or_default(),
             "turn": turn.map(|t| serde_json::to_value(&t).unwrap_or_default()),
-            "rule": serde_json::to_value(&rule).unwrap_or_default(),
             "countdown": serde_json::to_value(&countdown).unwrap_or_default(),
         });
+        if let Some(rule) = rule {
+            game_state["rule"] = serde_json::to_value(&rule).unwrap_or_default();
+        }
+        if inner.mode.is_scoring() {
+            let scores: HashMap<Uuid, i32> = inner
+                .players
+                .iter()
+                .map(|(id, player)| (*id, player.score))
+                .collect();
+            game_state["scores"] = json!(scores);
+        }
 
         Ok(game_state)
     }
@@ -673,8 +1155,13 @@ impl GameEngine for LexiWarsEngine {
     }
 
     async fn tick(&mut self) -> Result<Vec<Value>, AppError> {
-        // Tick is handled by the game loop spawned in start_loop
-        Ok(Vec::new())
+        // Turn timing is handled by the game loop spawned in start_loop;
+        // ticks only drive the practice bot
+        let mut inner = self.inner.write().await;
+        if !inner.is_practice() {
+            return Ok(Vec::new());
+        }
+        inner.play_bot_turn().await
     }
 
     fn is_finished(&self) -> bool {
@@ -691,6 +1178,17 @@ impl GameEngine for LexiWarsEngine {
         let inner = self.get_inner();
         tokio::spawn(run_game_loop(inner, state));
     }
+
+    async fn force_finish(&mut self) -> Result<bool, AppError> {
+        let mut inner = self.inner.write().await;
+        if inner.finished {
+            return Ok(false);
+        }
+        inner.end_game().await;
+        // Wake the loop so it sees the game is over
+        inner.turn_advance_notify.notify_one();
+        Ok(true)
+    }
 }
 
 // ============================================================================
@@ -709,15 +1207,19 @@ impl GameEngine for LexiWarsEngine {
 /// 6. Wait for either:
 ///    - turn_advance_notify (valid word submitted) → advance turn
 ///    - timeout → Eliminated event + advance turn or end_game
+///      (in the scoring mode the turn just passes)
 /// 7. Loop back to step 1
+///
+/// A scoring game also ends, mid-turn if need be, once its time limit is up.
 async fn run_game_loop(inner: Arc<RwLock<LexiWarsInner>>, state: AppState) {
     // Get the notify handle and lobby_id
-    let (turn_advance_notify, lobby_id, total_players) = {
+    let (turn_advance_notify, lobby_id, total_players, deadline) = {
         let inner_guard = inner.read().await;
         (
             inner_guard.turn_advance_notify.clone(),
             inner_guard.lobby_id,
             inner_guard.total_players,
+            inner_guard.deadline(),
         )
     };
 
@@ -749,18 +1251,40 @@ async fn run_game_loop(inner: Arc<RwLock<LexiWarsInner>>, state: AppState) {
             break;
         }
 
-        // Check if game should end (1 or fewer players)
-        if active_count <= 1 {
+        // Check if game should end (1 or fewer players, or the clock ran out)
+        if active_count <= 1 || inner.read().await.is_out_of_time() {
+            wait_min_duration(&inner).await;
+            let mut inner_guard = inner.write().await;
+            inner_guard.end_game().await;
+            break;
+        }
+
+        // No word fits the rule any more: rather than time every player out,
+        // end the game with the survivors ranked by words played
+        if !inner.write().await.ensure_playable_rule() {
+            tracing::info!("LexiWars: no playable words left in lobby {}", lobby_id);
+            let impasse = LexiWarsEvent::Impasse {
+                reason: "No valid words remain for the current rule".to_string(),
+            };
+            broadcast::broadcast_game_message(
+                &state,
+                lobby_id,
+                serde_json::to_value(&impasse).unwrap_or_default(),
+            )
+            .await;
+
+            wait_min_duration(&inner).await;
             let mut inner_guard = inner.write().await;
             inner_guard.end_game().await;
             break;
         }
 
         // Start the turn - broadcasts Turn to room and Rule to current player
-        {
+        let turn_started_ms = {
             let mut inner_guard = inner.write().await;
             inner_guard.start_turn().await;
-        }
+            inner_guard.turn_started_ms
+        };
 
         // Countdown loop
         let mut time_remaining = TURN_TIMEOUT_SECS;
@@ -768,13 +1292,11 @@ async fn run_game_loop(inner: Arc<RwLock<LexiWarsInner>>, state: AppState) {
 
         while time_remaining > 0 {
             // Broadcast Countdown event to room
-            let countdown_event = LexiWarsEvent::Countdown {
-                time: time_remaining,
-            };
+            let countdown = countdown_event(time_remaining, turn_started_ms);
             broadcast::broadcast_game_message(
                 &state,
                 lobby_id,
-                serde_json::to_value(&countdown_event).unwrap_or_default(),
+                serde_json::to_value(&countdown).unwrap_or_default(),
             )
             .await;
 
@@ -782,6 +1304,9 @@ async fn run_game_loop(inner: Arc<RwLock<LexiWarsInner>>, state: AppState) {
             tokio::select! {
                 _ = tokio::time::sleep(tokio::time::Duration::from_secs(1)) => {
                     time_remaining -= 1;
+                    if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                        break;
+                    }
                 }
                 _ = turn_advance_notify.notified() => {
                     word_submitted = true;
@@ -790,12 +1315,27 @@ async fn run_game_loop(inner: Arc<RwLock<LexiWarsInner>>, state: AppState) {
             }
         }
 
+        // Force-finished while the turn was running
+        if inner.read().await.finished {
+            break;
+        }
+
+        if !word_submitted && inner.read().await.is_out_of_time() {
+            // The clock ran out mid-turn; the next pass ends the game
+            continue;
+        }
+
         if word_submitted {
             // Player submitted a valid word (WordEntry was broadcast)
             // Advance to next turn and next rule
             let mut inner_guard = inner.write().await;
             inner_guard.turn_rotation.next_turn();
             inner_guard.advance_rule();
+        } else if inner.read().await.mode.is_scoring() {
+            // Timeout in the scoring mode - the turn passes, nobody is eliminated
+            let mut inner_guard = inner.write().await;
+            inner_guard.turn_rotation.next_turn();
+            inner_guard.advance_rule();
         } else {
             // Timeout - eliminate current player with Eliminated event
             if let Some(player_id) = current_player_id {
@@ -814,6 +1354,39 @@ async fn run_game_loop(inner: Arc<RwLock<LexiWarsInner>>, state: AppState) {
     }
 }
 
+/// Hold the result back until the game's minimum duration has passed
+async fn wait_min_duration(inner: &RwLock<LexiWarsInner>) {
+    let remaining = {
+        let inner_guard = inner.read().await;
+        inner_guard
+            .started_at
+            .map(|started| inner_guard.min_duration.saturating_sub(started.elapsed()))
+            .unwrap_or_default()
+    };
+    if !remaining.is_zero() {
+        tokio::time::sleep(remaining).await;
+    }
+}
+
+/// Build a `Turn` event for a turn that started at `started_ms`
+fn turn_event(player: PlayerState, started_ms: u64) -> LexiWarsEvent {
+    LexiWarsEvent::Turn {
+        player,
+        timeout_secs: TURN_TIMEOUT_SECS,
+        turn_deadline_ms: deadline_ms(started_ms, TURN_TIMEOUT_SECS),
+        server_time_ms: server_time_ms(),
+    }
+}
+
+/// Build a `Countdown` tick for the turn that started at `started_ms`
+fn countdown_event(time: u64, started_ms: u64) -> LexiWarsEvent {
+    LexiWarsEvent::Countdown {
+        time,
+        turn_deadline_ms: deadline_ms(started_ms, TURN_TIMEOUT_SECS),
+        server_time_ms: server_time_ms(),
+    }
+}
+
 // ============================================================================
 // Factory
 // ============================================================================
@@ -835,36 +1408,23 @@ mod tests {
     /// Prize distribution: 1st = 50% (or 70% for 2 players), 2nd = 30%, 3rd = 20%
     #[test]
     fn test_prize_calculation() {
-        let total_pool = 100.0;
-
-        // Helper to calculate prize (mirrors the logic in LexiWarsInner::calculate_prize)
-        let calc_prize = |rank: usize, participants: usize| -> Option<f64> {
-            if total_pool <= 0.0 {
-                return None;
-            }
-            let prize = match rank {
-                1 => {
-                    if participants == 2 {
-                        (total_pool * 70.0) / 100.0
-                    } else {
-                        (total_pool * 50.0) / 100.0
-                    }
-                }
-                2 => (total_pool * 30.0) / 100.0,
-                3 => (total_pool * 20.0) / 100.0,
-                _ => 0.0,
-            };
-            if prize > 0.0 { Some(prize) } else { None }
-        };
+        let total_pool = Decimal::from(100);
 
         // 3 players
-        assert_eq!(calc_prize(1, 3), Some(50.0)); // 50%
-        assert_eq!(calc_prize(2, 3), Some(30.0)); // 30%
-        assert_eq!(calc_prize(3, 3), Some(20.0)); // 20%
+        let shares = PayoutTable::default_for(3).shares(total_pool, 3);
+        assert_eq!(
+            shares,
+            vec![Decimal::from(50), Decimal::from(30), Decimal::from(20)]
+        );
 
         // 2 players
-        assert_eq!(calc_prize(1, 2), Some(70.0)); // 70%
-        assert_eq!(calc_prize(2, 2), Some(30.0)); // 30%
+        let shares = PayoutTable::default_for(2).shares(total_pool, 2);
+        assert_eq!(shares, vec![Decimal::from(70), Decimal::from(30)]);
+
+        // A pool of three 0.1 entries splits without losing a micro-unit
+        let pool = Decimal::new(1, 1) * Decimal::from(3);
+        let shares = PayoutTable::default_for(3).shares(pool, 3);
+        assert_eq!(shares.iter().sum::<Decimal>(), pool);
     }
 
     /// Test wars point calculation using WarsPointContext
@@ -874,11 +1434,12 @@ mod tests {
 
         let ctx = WarsPointContext {
             user_id,
+            game_id: LEXI_WARS_GAME_ID,
             rank: 1,
             prize: None,
             participants: 3,
-            entry_amount: Some(10.0),
-            current_amount: Some(30.0),
+            entry_amount: Some(Decimal::from(10)),
+            current_amount: Some(Decimal::from(30)),
             is_sponsored: false,
             creator_id: None,
             active_players: 1,
@@ -890,4 +1451,118 @@ mod tests {
         assert!(points >= 6.0);
         assert!(points <= 50.0); // Cap
     }
+
+    #[test]
+    fn test_turn_deadline_is_start_plus_timeout() {
+        let started_ms = 1_700_000_000_000;
+        let player = PlayerState::new(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7".to_string(),
+            None,
+            None,
+            10.0,
+            None,
+            false,
+        );
+
+        let LexiWarsEvent::Turn {
+            turn_deadline_ms,
+            timeout_secs,
+            ..
+        } = turn_event(player, started_ms)
+        else {
+            panic!("expected Turn event");
+        };
+        assert_eq!(timeout_secs, TURN_TIMEOUT_SECS);
+        assert_eq!(turn_deadline_ms, started_ms + TURN_TIMEOUT_SECS * 1000);
+
+        let LexiWarsEvent::Countdown {
+            turn_deadline_ms, ..
+        } = countdown_event(3, started_ms)
+        else {
+            panic!("expected Countdown event");
+        };
+        assert_eq!(turn_deadline_ms, started_ms + TURN_TIMEOUT_SECS * 1000);
+    }
+
+    #[test]
+    fn test_turn_event_serializes_deadline() {
+        let value = serde_json::to_value(countdown_event(5, 1_000)).unwrap();
+        assert_eq!(value["type"], "countdown");
+        assert_eq!(value["turnDeadlineMs"], 1_000 + TURN_TIMEOUT_SECS * 1000);
+        assert!(value["serverTimeMs"].as_u64().unwrap() > 0);
+    }
+
+    #[test]
+    fn test_word_length_stops_at_cap_then_rare_letters_grow() {
+        let cap = 9;
+        let mut difficulty = (INITIAL_MIN_WORD_LENGTH, 0);
+        let mut seen = Vec::new();
+        for _ in 0..5 {
+            difficulty = escalate(difficulty.0, difficulty.1, cap);
+            seen.push(difficulty);
+        }
+        assert_eq!(seen, vec![(6, 0), (8, 0), (9, 0), (9, 1), (9, 2)]);
+    }
+
+    #[test]
+    fn test_word_points_favor_rare_letters() {
+        assert_eq!(word_points("apple"), 5);
+        assert_eq!(word_points("quiz"), 1 + 1 + 2 * BONUS_LETTER_POINTS);
+        assert_eq!(word_points("Zax"), 1 + 2 * BONUS_LETTER_POINTS);
+        assert!(word_points("jazz") > word_points("jabs"));
+    }
+
+    #[test]
+    fn test_sanitize_word_normalizes_mixed_case() {
+        assert_eq!(sanitize_word("  HeLLo\n", 45).unwrap(), "hello");
+        assert_eq!(sanitize_word("QUIZ", 4).unwrap(), "quiz");
+    }
+
+    #[test]
+    fn test_sanitize_word_rejects_non_letters() {
+        for word in ["", "   ", "don't", "h3llo", "two words", "café", "<script>"] {
+            let err = sanitize_word(word, 45).unwrap_err();
+            assert_eq!(err.code(), "INVALID_WORD", "{:?}", word);
+        }
+    }
+
+    #[test]
+    fn test_sanitize_word_rejects_overlong_words() {
+        let err = sanitize_word(&"a".repeat(46), 45).unwrap_err();
+        assert_eq!(err.code(), "WORD_TOO_LONG");
+        assert!(matches!(
+            err,
+            GameError::WordTooLong {
+                length: 46,
+                max: 45
+            }
+        ));
+        // Length is counted after trimming
+        assert!(sanitize_word(&format!("  {}  ", "a".repeat(45)), 45).is_ok());
+    }
+
+    #[test]
+    fn test_impasse_when_no_word_can_satisfy_the_rule() {
+        let dictionary: Vec<String> = ["apple", "banana", "jukebox"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let mut rng = GameRng::new(0);
+        let ctx = RuleContext::new(3, 0, 6, &mut rng);
+
+        let (rule, ctx) =
+            playable_rule(ctx, &dictionary, &HashSet::new(), &mut rng).expect("playable");
+        assert_eq!(rule.name, "min_length");
+        assert!(dictionary.iter().any(|w| rule.check(w, &ctx).is_ok()));
+
+        // Every fitting word already played
+        let used: HashSet<String> = ["banana".to_string(), "jukebox".to_string()].into();
+        assert!(playable_rule(ctx.clone(), &dictionary, &used, &mut rng).is_none());
+
+        // Or the rare-letter requirement outgrew the dictionary
+        let ctx = ctx.with_rare_letters(4);
+        assert!(playable_rule(ctx, &dictionary, &HashSet::new(), &mut rng).is_none());
+    }
 }
@@ -43,6 +43,14 @@ pub enum LexiWarsEvent {
     /// A valid word was submitted by a player - broadcast to room
     WordEntry { word: String, player: PlayerState },
 
+    /// Points a word earned in the scoring mode, with the player's new total -
+    /// broadcast to room after the WordEntry
+    ScoreUpdate {
+        player: PlayerState,
+        points: i32,
+        score: i32,
+    },
+
     /// Invalid word submission - sent to submitting player only
     Invalid { reason: String },
 
@@ -54,17 +62,30 @@ pub enum LexiWarsEvent {
     Turn {
         player: PlayerState,
         timeout_secs: u64,
+        /// When the turn ends (server epoch millis)
+        turn_deadline_ms: u64,
+        server_time_ms: u64,
     },
 
-    /// Current rule - broadcast to room
+    /// Current rule - sent to players only, never spectators
     /// Some(rule) for current player, None for others (to clear previous rule)
     Rule { rule: Option<ClientRule> },
 
     /// Player was eliminated (timeout) - broadcast to room
     Eliminated { player: PlayerState, reason: String },
 
+    /// No word can satisfy the current rule; the game ends with the remaining
+    /// players ranked by words played - broadcast to room
+    Impasse { reason: String },
+
     /// Countdown tick - broadcast to room
-    Countdown { time: u64 },
+    #[serde(rename_all = "camelCase")]
+    Countdown {
+        time: u64,
+        /// When the turn ends (server epoch millis)
+        turn_deadline_ms: u64,
+        server_time_ms: u64,
+    },
 }
 
 impl GameEvent for LexiWarsEvent {}
@@ -1,12 +1,17 @@
 // Lexi Wars Game Module
 //
 // A turn-based word game where players must submit valid words following rules.
-// Rules cycle sequentially; after all rules used, min word length increases by 2.
+// Rules cycle sequentially; after all rules used, min word length increases by 2
+// up to a configured cap, after which each cycle requires another rare letter.
 //
 // Module structure:
+// - bloom.rs: BloomFilter, the fast "not a word" check in front of the dictionary
+// - bot.rs: LexiBot, the opponent in practice lobbies
+// - config.rs: LexiWarsConfig, which mode (last standing or scoring) games play
 // - engine.rs: Core game logic (LexiWarsEngine, game loop, prize calculation)
 // - message.rs: Game-specific message types (LexiWarsAction, LexiWarsEvent)
 // - rule.rs: Rule definitions and validation logic
+// - validator.rs: WordValidator, the dictionary backend (bundled, Redis or remote)
 //
 // Shared game events (GameStarted, GameStartFailed, FinalStanding, GameOver) are in
 // ws/room/messages.rs as RoomServerMessage variants.
@@ -19,19 +24,51 @@
 // 5. On SubmitWord action: validate → WordEntry (room) or Invalid/UsedWord (user)
 // 6. Valid word signals turn advance via notify channel
 // 7. Timeout → Eliminated + GameOver (to user) → next turn or FinalStanding if 1 player left
+// 8. Before each turn, if no unused word fits the rule (even after re-rolling its
+//    letter) → Impasse + FinalStanding, remaining players ranked by words played
+//
+// In the scoring mode nobody is eliminated: a timeout only passes the turn, each
+// valid word earns points by length and rare letters (ScoreUpdate to the room),
+// and when the time limit runs out FinalStanding ranks everyone by score.
+//
+// Practice lobbies seat a LexiBot (add_bot) before initialize(). The room engine
+// calls tick() a few times a second; on the bot's turn, once it has thought long
+// enough, tick() submits its word through the same path as a player's SubmitWord.
+// Practice results are shown but not saved.
 
+pub mod bloom;
+pub mod bot;
+pub mod config;
 pub mod engine;
 pub mod message;
 pub mod rule;
+pub mod validator;
+
+// Re-export bot types
+pub use bot::LexiBot;
+
+// Re-export config types
+pub use config::{
+    DEFAULT_MAX_SUBMISSION_LENGTH, DEFAULT_SCORING_TIME_LIMIT_SECS, LexiWarsConfig, LexiWarsMode,
+};
 
 // Re-export engine types
 pub use engine::{
-    create_lexi_wars, LexiWarsEngine, INITIAL_MIN_WORD_LENGTH, TURN_TIMEOUT_SECS,
-    WORD_LENGTH_INCREMENT,
+    BONUS_LETTER_POINTS, BONUS_LETTERS, DEFAULT_MAX_WORD_LENGTH, INITIAL_MIN_WORD_LENGTH,
+    LexiWarsEngine, TURN_TIMEOUT_SECS, WORD_LENGTH_INCREMENT, config_schema, create_lexi_wars,
+    sanitize_word, word_points,
 };
 
 // Re-export message types
 pub use message::{LexiWarsAction, LexiWarsEvent};
 
 // Re-export rule types
-pub use rule::{get_rule_at_index, lexi_wars_rules, rule_count, ClientRule, Rule, RuleContext};
+pub use rule::{
+    ClientRule, RARE_LETTERS, Rule, RuleContext, get_rule_at_index, lexi_wars_rules, rule_count,
+};
+
+// Re-export validator types
+pub use validator::{
+    BloomWordValidator, InMemoryWordValidator, RedisWordValidator, RemoteWordValidator,
+    WordValidator, WordValidatorConfig,
+};
@@ -1,10 +1,14 @@
 // Lexi Wars Rule System
 //
 // Rules are cycled sequentially (not random). After all rules have been used,
-// the cycle restarts with increased minimum word length.
+// the cycle restarts with increased minimum word length. Once the length hits
+// its cap, each further cycle instead requires one more rare letter (see
+// RARE_LETTERS) on top of whatever the rule asks.
 
 use serde::{Deserialize, Serialize};
 
+use crate::games::GameRng;
+
 /// Context for rule validation - acts as difficulty settings
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -13,34 +17,47 @@ pub struct RuleContext {
     pub random_letter: char,
     pub round_number: usize,
     pub rule_index: usize,
+    /// Rare letters every word must use, once the length cap is reached
+    #[serde(default)]
+    pub rare_letters_required: usize,
 }
 
 impl RuleContext {
-    pub fn new(round_number: usize, rule_index: usize, min_word_length: usize) -> Self {
-        let random_letter = Self::generate_random_letter();
+    pub fn new(
+        round_number: usize,
+        rule_index: usize,
+        min_word_length: usize,
+        rng: &mut GameRng,
+    ) -> Self {
+        let random_letter = Self::generate_random_letter(rng);
 
         Self {
             min_word_length,
             random_letter,
             round_number,
             rule_index,
+            rare_letters_required: 0,
         }
     }
 
-    fn generate_random_letter() -> char {
-        use rand::Rng;
+    /// Require `count` rare letters in every word
+    pub fn with_rare_letters(mut self, count: usize) -> Self {
+        self.rare_letters_required = count;
+        self
+    }
+
+    fn generate_random_letter(rng: &mut GameRng) -> char {
         // Common letters weighted more heavily for fairness
         const LETTERS: &[char] = &[
             'a', 'a', 'e', 'e', 'i', 'i', 'o', 'o', 'u', 'b', 'c', 'd', 'f', 'g', 'h', 'l', 'm',
             'n', 'p', 'r', 's', 't', 'w',
         ];
-        let idx = rand::rng().random_range(0..LETTERS.len());
-        LETTERS[idx]
+        LETTERS[rng.below(LETTERS.len())]
     }
 
     /// Regenerate the random letter for a new turn
-    pub fn regenerate_letter(&mut self) {
-        self.random_letter = Self::generate_random_letter();
+    pub fn regenerate_letter(&mut self, rng: &mut GameRng) {
+        self.random_letter = Self::generate_random_letter(rng);
     }
 }
 
@@ -53,11 +70,31 @@ pub struct Rule {
 }
 
 impl Rule {
+    /// Validate `word` against the rule and the context's rare-letter requirement
+    pub fn check(&self, word: &str, ctx: &RuleContext) -> Result<(), String> {
+        (self.validate)(word, ctx)?;
+        if rare_letter_count(word) < ctx.rare_letters_required {
+            return Err(rare_letter_requirement(ctx.rare_letters_required));
+        }
+        Ok(())
+    }
+
     /// Serialize rule for sending to clients (without the validate function)
-    pub fn to_client_rule(&self) -> ClientRule {
+    pub fn to_client_rule(&self, ctx: &RuleContext) -> ClientRule {
+        let description = if ctx.rare_letters_required > 0 {
+            format!(
+                "{} {}",
+                self.description,
+                rare_letter_requirement(ctx.rare_letters_required)
+            )
+        } else {
+            self.description.clone()
+        };
         ClientRule {
             name: self.name.clone(),
-            description: self.description.clone(),
+            description,
+            min_word_length: ctx.min_word_length,
+            rare_letters_required: ctx.rare_letters_required,
         }
     }
 }
@@ -68,6 +105,29 @@ impl Rule {
 pub struct ClientRule {
     pub name: String,
     pub description: String,
+    pub min_word_length: usize,
+    /// How many letters from RARE_LETTERS the word must use (0 before the length cap)
+    #[serde(default)]
+    pub rare_letters_required: usize,
+}
+
+/// Letters that count towards the rare-letter requirement
+pub const RARE_LETTERS: &[char] = &['f', 'h', 'j', 'k', 'q', 'v', 'w', 'x', 'y', 'z'];
+
+/// How many of the word's letters are rare (repeats count)
+pub fn rare_letter_count(word: &str) -> usize {
+    word.chars()
+        .filter(|c| RARE_LETTERS.contains(&c.to_ascii_lowercase()))
+        .count()
+}
+
+fn rare_letter_requirement(count: usize) -> String {
+    let letters: Vec<String> = RARE_LETTERS.iter().map(char::to_string).collect();
+    format!(
+        "Word must use at least {} of the letters {}!",
+        count,
+        letters.join(", ")
+    )
 }
 
 /// Generate available rules for the game (order matters - cycled sequentially)
@@ -75,10 +135,7 @@ pub fn lexi_wars_rules(ctx: &RuleContext) -> Vec<Rule> {
     vec![
         Rule {
             name: "min_length".to_string(),
-            description: format!(
-                "Word must be at least {} characters!",
-                ctx.min_word_length
-            ),
+            description: format!("Word must be at least {} characters!", ctx.min_word_length),
             validate: |word, ctx| {
                 if word.len() < ctx.min_word_length {
                     Err(format!(
@@ -171,12 +228,28 @@ mod tests {
 
     #[test]
     fn test_rule_context_creation() {
-        let ctx = RuleContext::new(1, 0, 4);
+        let ctx = RuleContext::new(1, 0, 4, &mut GameRng::new(0));
         assert_eq!(ctx.min_word_length, 4);
         assert_eq!(ctx.round_number, 1);
         assert_eq!(ctx.rule_index, 0);
     }
 
+    #[test]
+    fn test_letters_follow_the_seed() {
+        let letters = |seed| {
+            let mut rng = GameRng::new(seed);
+            let mut ctx = RuleContext::new(1, 1, 4, &mut rng);
+            (0..10)
+                .map(|_| {
+                    ctx.regenerate_letter(&mut rng);
+                    ctx.random_letter
+                })
+                .collect::<String>()
+        };
+        assert_eq!(letters(99), letters(99));
+        assert!((0..5).any(|seed| letters(seed) != letters(99)));
+    }
+
     #[test]
     fn test_rule_validation() {
         let ctx = RuleContext {
@@ -184,6 +257,7 @@ mod tests {
             random_letter: 'a',
             round_number: 1,
             rule_index: 0,
+            rare_letters_required: 0,
         };
 
         let rules = lexi_wars_rules(&ctx);
@@ -201,17 +275,34 @@ mod tests {
 
     #[test]
     fn test_rule_cycling() {
-        let ctx = RuleContext::new(1, 0, 4);
+        let ctx = RuleContext::new(1, 0, 4, &mut GameRng::new(0));
         let rule0 = get_rule_at_index(&ctx);
         assert_eq!(rule0.name, "min_length");
 
-        let ctx = RuleContext::new(1, 1, 4);
+        let ctx = RuleContext::new(1, 1, 4, &mut GameRng::new(0));
         let rule1 = get_rule_at_index(&ctx);
         assert_eq!(rule1.name, "contains_letter");
 
         // After 4 rules, should wrap around
-        let ctx = RuleContext::new(1, 4, 4);
+        let ctx = RuleContext::new(1, 4, 4, &mut GameRng::new(0));
         let rule4 = get_rule_at_index(&ctx);
         assert_eq!(rule4.name, "min_length");
     }
+
+    #[test]
+    fn test_rare_letters_apply_on_top_of_the_rule() {
+        let ctx = RuleContext::new(5, 0, 6, &mut GameRng::new(0)).with_rare_letters(2);
+        let rule = get_rule_at_index(&ctx);
+
+        assert!(rule.check("jukebox", &ctx).is_ok()); // j, k, x
+        assert!(rule.check("whisky", &ctx).is_ok()); // w, h, k, y
+        assert!(rule.check("sample", &ctx).is_err()); // none
+        assert!(rule.check("wax", &ctx).is_err()); // too short
+        assert_eq!(rare_letter_count("Fizz"), 3);
+
+        let client = rule.to_client_rule(&ctx);
+        assert_eq!(client.min_word_length, 6);
+        assert_eq!(client.rare_letters_required, 2);
+        assert!(client.description.contains("at least 2 of the letters"));
+    }
 }
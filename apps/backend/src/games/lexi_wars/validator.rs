@@ -0,0 +1,451 @@
+// Word validation backends for Lexi Wars
+//
+// The engine asks a `WordValidator` whether a submitted word is a real word;
+// which backend answers is chosen by `LEXI_WARS_DICTIONARY`:
+// - `bundled` (default): the dictionary compiled into the server
+// - `redis`: a Redis set of words, so a large dictionary can be loaded once
+//   and shared by every instance (`LEXI_WARS_DICTIONARY_KEY`)
+// - `remote`: a dictionary API at `LEXI_WARS_DICTIONARY_URL`, asked with
+//   `GET {url}/{word}`; 2xx means the word exists, 404 that it doesn't
+//
+// Rule playability and practice bots still draw words from the bundled
+// dictionary, since only it can be listed.
+//
+// Lookups go through a bloom filter first, so most non-words are turned away
+// without touching the backend. The bundled dictionary always has one; for
+// Redis or remote dictionaries, `LEXI_WARS_DICTIONARY_FILTER_FILE` names a
+// JSON word list to build it from, which must hold every word the backend
+// accepts. The list is read once, with the rest of the config at startup; a
+// file that can't be read or parsed fails the config.
+
+use std::{borrow::Cow, collections::HashSet, fmt, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use redis::AsyncCommands;
+use reqwest::{Client, StatusCode, Url};
+
+use super::bloom::BloomFilter;
+use crate::{errors::AppError, models::RedisKey, state::RedisClient};
+
+/// Remote lookups taking longer than this fail
+pub const DEFAULT_REMOTE_TIMEOUT_MS: u64 = 2000;
+
+// Load dictionary at compile time
+pub(super) static DICTIONARY: Lazy<HashSet<String>> = Lazy::new(|| {
+    let dict_json = include_str!("../../assets/dictionary.json");
+    serde_json::from_str(dict_json).unwrap_or_default()
+});
+
+/// Filter over the bundled dictionary
+static DICTIONARY_FILTER: Lazy<Arc<BloomFilter>> =
+    Lazy::new(|| Arc::new(BloomFilter::from_words(DICTIONARY.iter())));
+
+/// HTTP client shared by every remote dictionary, so engines reuse its
+/// connection pool
+static HTTP_CLIENT: Lazy<Client> = Lazy::new(Client::new);
+
+/// Decides whether a word is in the dictionary.
+///
+/// Words are passed lowercased and trimmed.
+#[async_trait]
+pub trait WordValidator: Send + Sync {
+    async fn is_valid(&self, word: &str) -> Result<bool, AppError>;
+}
+
+/// Words held in memory
+pub struct InMemoryWordValidator {
+    words: Cow<'static, HashSet<String>>,
+}
+
+impl InMemoryWordValidator {
+    pub fn new(words: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            words: Cow::Owned(words.into_iter().map(|w| w.into().to_lowercase()).collect()),
+        }
+    }
+
+    /// The dictionary bundled with the server
+    pub fn bundled() -> Self {
+        Self {
+            words: Cow::Borrowed(&DICTIONARY),
+        }
+    }
+}
+
+#[async_trait]
+impl WordValidator for InMemoryWordValidator {
+    async fn is_valid(&self, word: &str) -> Result<bool, AppError> {
+        Ok(self.words.contains(word))
+    }
+}
+
+/// Words in a Redis set
+pub struct RedisWordValidator {
+    redis: RedisClient,
+    key: String,
+}
+
+impl RedisWordValidator {
+    pub fn new(redis: RedisClient, key: String) -> Self {
+        Self { redis, key }
+    }
+}
+
+#[async_trait]
+impl WordValidator for RedisWordValidator {
+    async fn is_valid(&self, word: &str) -> Result<bool, AppError> {
+        let mut conn = self.redis.get().await?;
+        conn.sismember(&self.key, word)
+            .await
+            .map_err(AppError::RedisCommandError)
+    }
+}
+
+/// Words looked up on a dictionary API
+pub struct RemoteWordValidator {
+    url: Url,
+    timeout: Duration,
+}
+
+impl RemoteWordValidator {
+    pub fn new(url: &str, timeout: Duration) -> Result<Self, AppError> {
+        let url = Url::parse(url)
+            .map_err(|e| AppError::EnvError(format!("dictionary URL '{}': {}", url, e)))?;
+        Ok(Self { url, timeout })
+    }
+}
+
+#[async_trait]
+impl WordValidator for RemoteWordValidator {
+    async fn is_valid(&self, word: &str) -> Result<bool, AppError> {
+        let mut url = self.url.clone();
+        url.path_segments_mut()
+            .map_err(|_| AppError::FetchError(format!("{} can't take a path", self.url)))?
+            .pop_if_empty()
+            .push(word);
+
+        let response = HTTP_CLIENT
+            .get(url)
+            .timeout(self.timeout)
+            .send()
+            .await
+            .map_err(|e| AppError::FetchError(e.to_string()))?;
+
+        match response.status() {
+            status if status.is_success() => Ok(true),
+            StatusCode::NOT_FOUND => Ok(false),
+            status => Err(AppError::FetchError(format!(
+                "dictionary API answered {}",
+                status
+            ))),
+        }
+    }
+}
+
+/// Another validator behind a bloom filter of its words
+///
+/// Words the filter rules out are rejected straight away; the rest are
+/// left to the inner validator.
+pub struct BloomWordValidator {
+    filter: Arc<BloomFilter>,
+    inner: Arc<dyn WordValidator>,
+}
+
+impl BloomWordValidator {
+    /// `filter` must hold every word `inner` accepts
+    pub fn new(filter: Arc<BloomFilter>, inner: Arc<dyn WordValidator>) -> Self {
+        Self { filter, inner }
+    }
+
+    /// The bundled dictionary behind its filter
+    pub fn bundled() -> Self {
+        Self::new(
+            DICTIONARY_FILTER.clone(),
+            Arc::new(InMemoryWordValidator::bundled()),
+        )
+    }
+}
+
+#[async_trait]
+impl WordValidator for BloomWordValidator {
+    async fn is_valid(&self, word: &str) -> Result<bool, AppError> {
+        if !self.filter.may_contain(word) {
+            return Ok(false);
+        }
+        self.inner.is_valid(word).await
+    }
+}
+
+/// Filter built from a JSON word list file
+#[derive(Clone)]
+pub struct WordListFilter {
+    path: String,
+    filter: Arc<BloomFilter>,
+}
+
+impl WordListFilter {
+    /// Read the word list at `path` and build its filter
+    pub fn load(path: &str) -> Result<Self, String> {
+        let json = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        let words: Vec<String> =
+            serde_json::from_str(&json).map_err(|e| format!("{}: {}", path, e))?;
+        let words: Vec<String> = words.into_iter().map(|w| w.to_lowercase()).collect();
+        Ok(Self {
+            path: path.to_string(),
+            filter: Arc::new(BloomFilter::from_words(words.iter())),
+        })
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+}
+
+/// Filters are compared by the file they came from
+impl PartialEq for WordListFilter {
+    fn eq(&self, other: &Self) -> bool {
+        self.path == other.path
+    }
+}
+
+impl Eq for WordListFilter {}
+
+impl fmt::Debug for WordListFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("WordListFilter").field(&self.path).finish()
+    }
+}
+
+/// Which backend validates words.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum WordValidatorConfig {
+    #[default]
+    Bundled,
+    Redis {
+        key: String,
+        filter: Option<WordListFilter>,
+    },
+    Remote {
+        url: String,
+        timeout_ms: u64,
+        filter: Option<WordListFilter>,
+    },
+}
+
+impl WordValidatorConfig {
+    /// Read `LEXI_WARS_DICTIONARY` and the settings of the backend it names.
+    pub fn from_env() -> Result<Self, String> {
+        let var = |name: &str| std::env::var(name).ok();
+        Self::parse(
+            var("LEXI_WARS_DICTIONARY").as_deref(),
+            var("LEXI_WARS_DICTIONARY_KEY"),
+            var("LEXI_WARS_DICTIONARY_URL"),
+            var("LEXI_WARS_DICTIONARY_TIMEOUT_MS").as_deref(),
+            var("LEXI_WARS_DICTIONARY_FILTER_FILE"),
+        )
+    }
+
+    pub fn parse(
+        kind: Option<&str>,
+        key: Option<String>,
+        url: Option<String>,
+        timeout_ms: Option<&str>,
+        filter_file: Option<String>,
+    ) -> Result<Self, String> {
+        let filter = filter_file
+            .filter(|path| !path.trim().is_empty())
+            .map(|path| WordListFilter::load(&path))
+            .transpose()
+            .map_err(|e| format!("LEXI_WARS_DICTIONARY_FILTER_FILE: {}", e))?;
+        match kind.map(str::trim) {
+            None | Some("") | Some("bundled") => Ok(Self::Bundled),
+            Some("redis") => Ok(Self::Redis {
+                key: key.unwrap_or_else(RedisKey::lexi_wars_dictionary),
+                filter,
+            }),
+            Some("remote") => {
+                let url = url
+                    .filter(|url| !url.trim().is_empty())
+                    .ok_or("LEXI_WARS_DICTIONARY_URL is required for a remote dictionary")?;
+                Url::parse(&url)
+                    .map_err(|e| format!("LEXI_WARS_DICTIONARY_URL: '{}': {}", url, e))?;
+                let timeout_ms = match timeout_ms {
+                    Some(value) => value.trim().parse().map_err(|_| {
+                        format!(
+                            "LEXI_WARS_DICTIONARY_TIMEOUT_MS: invalid number '{}'",
+                            value
+                        )
+                    })?,
+                    None => DEFAULT_REMOTE_TIMEOUT_MS,
+                };
+                Ok(Self::Remote {
+                    url,
+                    timeout_ms,
+                    filter,
+                })
+            }
+            Some(other) => Err(format!(
+                "LEXI_WARS_DICTIONARY: '{}' is not bundled, redis or remote",
+                other
+            )),
+        }
+    }
+
+    /// Build the configured validator
+    pub fn build(&self, redis: RedisClient) -> Result<Arc<dyn WordValidator>, AppError> {
+        let (validator, filter): (Arc<dyn WordValidator>, _) = match self {
+            Self::Bundled => return Ok(Arc::new(BloomWordValidator::bundled())),
+            Self::Redis { key, filter } => (
+                Arc::new(RedisWordValidator::new(redis, key.clone())),
+                filter,
+            ),
+            Self::Remote {
+                url,
+                timeout_ms,
+                filter,
+            } => (
+                Arc::new(RemoteWordValidator::new(
+                    url,
+                    Duration::from_millis(*timeout_ms),
+                )?),
+                filter,
+            ),
+        };
+
+        Ok(match filter {
+            Some(list) => Arc::new(BloomWordValidator::new(list.filter.clone(), validator)),
+            None => validator,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, extract::Path, http::StatusCode as HttpStatus, routing::get};
+
+    #[tokio::test]
+    async fn test_in_memory_validator() {
+        let validator = InMemoryWordValidator::new(["Apple", "banana"]);
+        assert!(validator.is_valid("apple").await.unwrap());
+        assert!(validator.is_valid("banana").await.unwrap());
+        assert!(!validator.is_valid("cherry").await.unwrap());
+    }
+
+    /// A dictionary API that knows "apple", and breaks on "boom"
+    async fn stub_dictionary_api() -> String {
+        let app = Router::new().route(
+            "/entries/{word}",
+            get(|Path(word): Path<String>| async move {
+                match word.as_str() {
+                    "apple" => HttpStatus::OK,
+                    "boom" => HttpStatus::INTERNAL_SERVER_ERROR,
+                    _ => HttpStatus::NOT_FOUND,
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}/entries/", addr)
+    }
+
+    #[tokio::test]
+    async fn test_remote_validator_follows_the_api() {
+        let url = stub_dictionary_api().await;
+        let validator = RemoteWordValidator::new(&url, Duration::from_secs(2)).unwrap();
+
+        assert!(validator.is_valid("apple").await.unwrap());
+        assert!(!validator.is_valid("pear").await.unwrap());
+        assert!(validator.is_valid("boom").await.is_err());
+    }
+
+    /// Counts the lookups that reach it
+    #[derive(Default)]
+    struct Counting {
+        lookups: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait]
+    impl WordValidator for Counting {
+        async fn is_valid(&self, word: &str) -> Result<bool, AppError> {
+            self.lookups
+                .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            Ok(DICTIONARY.contains(word))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bloom_filter_turns_away_non_words_before_the_backend() {
+        let backend = Arc::new(Counting::default());
+        let validator = BloomWordValidator::new(DICTIONARY_FILTER.clone(), backend.clone());
+
+        let non_words: Vec<String> = (0..1000).map(|i| format!("qxz{}vjk", i)).collect();
+        for word in &non_words {
+            assert!(!validator.is_valid(word).await.unwrap());
+        }
+        // Only the filter's few false positives reach the backend
+        let lookups = backend.lookups.load(std::sync::atomic::Ordering::Relaxed);
+        assert!(
+            lookups < 50,
+            "{} of 1000 non-words reached the backend",
+            lookups
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bloom_filter_has_no_false_negatives() {
+        let validator = BloomWordValidator::bundled();
+        for word in DICTIONARY.iter() {
+            assert!(validator.is_valid(word).await.unwrap(), "{} rejected", word);
+        }
+    }
+
+    #[test]
+    fn test_config_parse() {
+        let words = std::env::temp_dir().join(format!("words-{}.json", uuid::Uuid::new_v4()));
+        std::fs::write(&words, r#"["Apple", "banana"]"#).unwrap();
+        let words = words.to_str().unwrap().to_string();
+
+        assert_eq!(
+            WordValidatorConfig::parse(None, None, None, None, None),
+            Ok(WordValidatorConfig::Bundled)
+        );
+        let redis =
+            WordValidatorConfig::parse(Some("redis"), None, None, None, Some(words.clone()));
+        let Ok(WordValidatorConfig::Redis { key, filter }) = redis else {
+            panic!("expected a Redis dictionary: {:?}", redis);
+        };
+        assert_eq!(key, RedisKey::lexi_wars_dictionary());
+        let filter = filter.expect("filter loaded");
+        assert_eq!(filter.path(), words);
+        assert!(filter.filter.may_contain("apple"));
+        assert_eq!(
+            WordValidatorConfig::parse(
+                Some("remote"),
+                None,
+                Some("https://dict.example/words".to_string()),
+                Some("500"),
+                None
+            ),
+            Ok(WordValidatorConfig::Remote {
+                url: "https://dict.example/words".to_string(),
+                timeout_ms: 500,
+                filter: None,
+            })
+        );
+        assert!(WordValidatorConfig::parse(Some("remote"), None, None, None, None).is_err());
+        assert!(WordValidatorConfig::parse(Some("sqlite"), None, None, None, None).is_err());
+
+        // A filter file that's missing or isn't a word list fails the config
+        let missing = Some("/nonexistent/words.json".to_string());
+        assert!(WordValidatorConfig::parse(Some("redis"), None, None, None, missing).is_err());
+        std::fs::write(&words, "not json").unwrap();
+        assert!(
+            WordValidatorConfig::parse(Some("redis"), None, None, None, Some(words.clone()))
+                .is_err()
+        );
+        std::fs::remove_file(&words).ok();
+    }
+}
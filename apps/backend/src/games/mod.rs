@@ -1,19 +1,27 @@
 // Game engine infrastructure
 use crate::errors::AppError;
-use crate::state::AppState;
+use crate::models::{BotDifficulty, Lobby};
+use crate::state::{AppState, ConnectionInfo};
 use async_trait::async_trait;
-use serde::{Serialize, de::DeserializeOwned};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use serde_json::Value;
 use uuid::Uuid;
 
+pub mod coin_flip;
 pub mod common;
 pub mod error;
 pub mod lexi_wars;
 pub mod registry;
+pub mod rng;
+pub mod signing;
 
 pub use common::*;
 pub use error::GameError;
-pub use registry::{LEXI_WARS_GAME_ID, create_game_registry};
+pub use registry::{
+    COIN_FLIP_GAME_ID, GameDurationLimits, LEXI_WARS_GAME_ID, RegisteredGame, create_game_registry,
+    game_duration_limits, registered_games,
+};
+pub use rng::GameRng;
 
 /// Base trait for all game actions (client -> server messages)
 /// Each game defines its own action enum that implements this trait
@@ -23,6 +31,67 @@ pub trait GameAction: DeserializeOwned + Send + Sync + 'static {}
 /// Each game defines its own event enum that implements this trait
 pub trait GameEvent: Serialize + Send + Sync + 'static {}
 
+/// Which room connections a game event is for
+///
+/// Events are for everyone unless tagged. An event returned from the engine is
+/// tagged with `Audience::tag`, which the room broadcaster strips off again
+/// before sending. Events for only part of the room don't take a `seq`, so
+/// the other part sees no gap.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Audience {
+    /// Users who joined the lobby
+    Players,
+    /// Everyone else watching the room
+    Spectators,
+    #[default]
+    All,
+}
+
+impl Audience {
+    /// Field an engine event's audience travels in
+    const FIELD: &'static str = "audience";
+
+    /// Whether a connection with this room role receives the event
+    pub fn includes_participant(self, is_participant: bool) -> bool {
+        match self {
+            Audience::Players => is_participant,
+            Audience::Spectators => !is_participant,
+            Audience::All => true,
+        }
+    }
+
+    /// Whether `conn` receives the event; connections with no room role are spectators
+    pub fn includes(self, conn: &ConnectionInfo) -> bool {
+        self.includes_participant(
+            conn.room_context()
+                .is_some_and(|context| context.is_participant()),
+        )
+    }
+
+    /// Mark an engine event as being for this audience only
+    pub fn tag(self, mut event: Value) -> Value {
+        if self != Audience::All
+            && let Value::Object(fields) = &mut event
+            && let Ok(audience) = serde_json::to_value(self)
+        {
+            fields.insert(Self::FIELD.to_string(), audience);
+        }
+        event
+    }
+
+    /// Remove an event's audience tag, returning who it's for
+    pub fn take(event: &mut Value) -> Self {
+        match event {
+            Value::Object(fields) => fields
+                .remove(Self::FIELD)
+                .and_then(|audience| serde_json::from_value(audience).ok())
+                .unwrap_or_default(),
+            _ => Audience::All,
+        }
+    }
+}
+
 /// Core game engine trait that all games must implement
 ///
 /// Actions and events are passed as JSON Value to avoid trait object issues
@@ -34,10 +103,34 @@ pub trait GameEngine: Send + Sync {
         // Default: no-op - override if game needs app state
     }
 
+    /// Pass the lobby's settings (stakes, payout table) to the game
+    /// Called before add_bot() and initialize()
+    async fn set_lobby(&mut self, _lobby: &Lobby) {
+        // Default: no-op - override if the game pays out or scores by lobby
+    }
+
+    /// Seeds for the game's random decisions
+    /// Called before initialize(); turn-based games seat players with
+    /// `seat_players` and `seating_seed`, which is revealed in `GameStarted`.
+    /// Anything else random draws from a `GameRng` seeded with `game_seed`,
+    /// kept secret until the game ends
+    async fn set_seeds(&mut self, _seating_seed: u64, _game_seed: u64) {
+        // Default: no-op - override if the game makes random decisions
+    }
+
     /// Handle a player action (as JSON) and return events to broadcast (as JSON)
     async fn handle_action(&mut self, user_id: Uuid, action: Value)
     -> Result<Vec<Value>, AppError>;
 
+    /// Seat a bot for a practice game, returning its player ID
+    /// Called before initialize(); the ID is passed along with the other players
+    /// Default: bots aren't supported
+    async fn add_bot(&mut self, _difficulty: BotDifficulty) -> Result<Uuid, AppError> {
+        Err(AppError::BadRequest(
+            "This game has no practice bot".to_string(),
+        ))
+    }
+
     /// Initialize game with player list, return initial events (as JSON)
     async fn initialize(&mut self, player_ids: Vec<Uuid>) -> Result<Vec<Value>, AppError>;
 
@@ -55,7 +148,7 @@ pub trait GameEngine: Send + Sync {
     /// Get game state for a specific user reconnecting mid-game
     /// This returns game-specific state that the client needs to restore the UI
     /// The user_id is optional - if provided, games can include user-specific info (e.g., current rule if it's their turn)
-    /// Spectators (unauthenticated users) will receive the generic state without user-specific info
+    /// Spectators (signed in or not) get None and must not see any player's private fields
     async fn get_game_state(&self, _user_id: Option<Uuid>) -> Result<Value, AppError> {
         // Default: return the generic bootstrap
         self.get_bootstrap().await
@@ -64,12 +157,42 @@ pub trait GameEngine: Send + Sync {
     /// Get final results if game is finished
     async fn get_results(&self) -> Result<Option<GameResults>, AppError>;
 
-    /// Game tick for time-based events (called periodically), returns events (as JSON)
+    /// Game tick for time-based events, returns events (as JSON)
+    /// The room engine calls this every `GAME_TICK_INTERVAL` while the game runs;
+    /// returned events are broadcast to the room like action events
     async fn tick(&mut self) -> Result<Vec<Value>, AppError>;
 
     /// Check if game is finished
     fn is_finished(&self) -> bool;
+
+    /// End the game now, ranking players by their current standings
+    /// Called when the game overruns its maximum duration
+    /// Returns false if the game had already finished
+    async fn force_finish(&mut self) -> Result<bool, AppError>;
 }
 
 /// Type of factory function that creates game engine instances
 pub type GameFactory = fn(Uuid, AppState) -> Box<dyn GameEngine>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audience_tag_round_trips_and_defaults_to_all() {
+        let mut event = Audience::Players.tag(serde_json::json!({ "type": "rule", "rule": null }));
+        assert_eq!(event["audience"], "players");
+        assert_eq!(Audience::take(&mut event), Audience::Players);
+        assert_eq!(event, serde_json::json!({ "type": "rule", "rule": null }));
+
+        // Untagged events are for everyone, and `All` adds no tag
+        let mut event = Audience::All.tag(serde_json::json!({ "type": "turn" }));
+        assert!(event.get("audience").is_none());
+        assert_eq!(Audience::take(&mut event), Audience::All);
+
+        assert!(Audience::Players.includes_participant(true));
+        assert!(!Audience::Players.includes_participant(false));
+        assert!(Audience::Spectators.includes_participant(false));
+        assert!(!Audience::Spectators.includes_participant(true));
+    }
+}
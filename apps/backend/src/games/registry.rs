@@ -1,10 +1,47 @@
 // Game registry - central place for game contributors to register their games
-use crate::games::{GameFactory, lexi_wars::create_lexi_wars};
+use crate::games::{
+    GameFactory,
+    coin_flip::{self, create_coin_flip},
+    lexi_wars::{self, TURN_TIMEOUT_SECS, create_lexi_wars},
+};
+use crate::state::AppConfig;
+use serde::Serialize;
+use serde_json::Value;
 use std::collections::HashMap;
+use std::time::Duration;
 use uuid::Uuid;
 
 // Game IDs - randomly generated UUIDs
 pub const LEXI_WARS_GAME_ID: Uuid = uuid::uuid!("97f19daa-b6b4-455b-a21e-f225884767d5");
+pub const COIN_FLIP_GAME_ID: Uuid = uuid::uuid!("05f920e9-6b71-471e-a98a-2e5fe9402c00");
+
+/// How long a game may run, enforced by the room engine
+///
+/// A game still running after `max_secs` is force-finished with its current
+/// standings. Turn-based games don't resolve before `min_secs`, so a bug
+/// can't end one instantly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameDurationLimits {
+    pub min_secs: u64,
+    pub max_secs: u64,
+}
+
+impl GameDurationLimits {
+    /// Limits for games that don't register their own
+    pub const DEFAULT: Self = Self {
+        min_secs: 0,
+        max_secs: 60 * 60,
+    };
+
+    pub fn min(&self) -> Duration {
+        Duration::from_secs(self.min_secs)
+    }
+
+    pub fn max(&self) -> Duration {
+        Duration::from_secs(self.max_secs)
+    }
+}
 
 /// Initialize and return the game registry with all registered games
 ///
@@ -19,9 +56,68 @@ pub fn create_game_registry() -> HashMap<Uuid, GameFactory> {
 
     // Register games
     registry.insert(LEXI_WARS_GAME_ID, create_lexi_wars as GameFactory);
+    registry.insert(COIN_FLIP_GAME_ID, create_coin_flip as GameFactory);
 
     // Future games can be added here:
     // registry.insert(YOUR_GAME_ID, create_your_game as GameFactory);
 
     registry
 }
+
+/// A registered game as clients see it
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisteredGame {
+    pub id: Uuid,
+    pub name: &'static str,
+    pub duration_limits: GameDurationLimits,
+    /// JSON Schema of the game's settings
+    pub config_schema: Value,
+}
+
+/// Every game in the registry, described with the settings `config` runs it by
+///
+/// Contributors registering a game in `create_game_registry` add it here too.
+pub fn registered_games(config: &AppConfig) -> Vec<RegisteredGame> {
+    vec![
+        RegisteredGame {
+            id: LEXI_WARS_GAME_ID,
+            name: "Lexi Wars",
+            duration_limits: config.game_durations.for_game(LEXI_WARS_GAME_ID),
+            config_schema: lexi_wars::config_schema(config.lexi_wars_max_word_length),
+        },
+        RegisteredGame {
+            id: COIN_FLIP_GAME_ID,
+            name: "Coin Flip",
+            duration_limits: config.game_durations.for_game(COIN_FLIP_GAME_ID),
+            config_schema: coin_flip::config_schema(),
+        },
+    ]
+}
+
+/// Default duration limits per game, overridable with `GAME_DURATION_LIMITS`
+///
+/// Games left out get `GameDurationLimits::DEFAULT`.
+pub fn game_duration_limits() -> HashMap<Uuid, GameDurationLimits> {
+    let mut limits = HashMap::new();
+
+    // At least one full turn must time out before anyone can win
+    limits.insert(
+        LEXI_WARS_GAME_ID,
+        GameDurationLimits {
+            min_secs: TURN_TIMEOUT_SECS,
+            max_secs: 60 * 60,
+        },
+    );
+
+    // Rounds resolve as soon as every call is in
+    limits.insert(
+        COIN_FLIP_GAME_ID,
+        GameDurationLimits {
+            min_secs: 0,
+            max_secs: 30 * 60,
+        },
+    );
+
+    limits
+}
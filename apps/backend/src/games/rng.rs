@@ -0,0 +1,78 @@
+// Seeded randomness for games
+//
+// Every random decision a game makes comes from seeds drawn per game with
+// `AppConfig::game_seed` - from a CSPRNG, or GAME_RNG_SEED when set - and
+// handed to the engine with `set_seeds`. There are two:
+//
+// - the seating seed draws the turn order. It is revealed in `GameStarted`,
+//   so anyone can redraw the seats.
+// - the gameplay seed drives everything after (rule letters, bot moves). It
+//   stays secret while the game runs, since knowing it would tell players the
+//   upcoming rules, and is stored on the lobby once the game ends so the game
+//   can be replayed and audited.
+//
+// The generator is SplitMix64: small, and its output is fixed by its
+// definition. (rand's `StdRng` isn't used because its output may change
+// between releases.)
+
+use std::ops::RangeInclusive;
+
+/// Deterministic random source for a game
+#[derive(Debug, Clone)]
+pub struct GameRng {
+    state: u64,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Next value of the SplitMix64 generator
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// An index below `len`; `len` must not be zero
+    pub fn below(&mut self, len: usize) -> usize {
+        (self.next_u64() % len as u64) as usize
+    }
+
+    /// A value in `range`
+    pub fn in_range(&mut self, range: RangeInclusive<u64>) -> u64 {
+        let (start, end) = range.into_inner();
+        match (end - start).checked_add(1) {
+            Some(span) => start + self.next_u64() % span,
+            None => self.next_u64(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_splitmix64_matches_reference_output() {
+        let mut rng = GameRng::new(1234567);
+        assert_eq!(rng.next_u64(), 6457827717110365317);
+        assert_eq!(rng.next_u64(), 3203168211198807973);
+    }
+
+    #[test]
+    fn test_same_seed_draws_the_same_values() {
+        let draws = |seed| {
+            let mut rng = GameRng::new(seed);
+            (0..20)
+                .map(|i| (rng.below(i + 1), rng.in_range(10..=20)))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(draws(7), draws(7));
+        assert_ne!(draws(7), draws(8));
+        assert!(draws(7).iter().all(|(_, v)| (10..=20).contains(v)));
+    }
+}
@@ -0,0 +1,359 @@
+// Signed game results for on-chain settlement
+//
+// When a ranked game ends, what settlement needs from it - the lobby, its
+// players in rank order and the prize each is paid - is written as the Clarity
+// tuple
+//
+//     { lobby-id: (buff 16), players: (list principal), prizes: (list uint) }
+//
+// in Clarity's consensus serialization, and the SHA-256 of those bytes is
+// signed with the server's secp256k1 key (`RESULT_SIGNING_KEY`, a hex secret
+// key). A contract rebuilds the tuple, hashes `to-consensus-buff?` of it with
+// `sha256` and checks the signature with `secp256k1-verify`, so a payout can
+// be checked against what the server decided without trusting whoever relays
+// it. Prizes are in micro-units; players without one get u0.
+//
+// The payload (hex of the serialized tuple), signature and public key go out
+// with `GameOver` and are stored per lobby.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use k256::ecdsa::{Signature, SigningKey, VerifyingKey, signature::hazmat::PrehashVerifier};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::games::{GameResults, PlayerRanking};
+use crate::models::{SignedResults, money::to_micro_units};
+
+/// Clarity type prefixes in the consensus serialization
+const CLARITY_UINT: u8 = 0x01;
+const CLARITY_BUFFER: u8 = 0x02;
+const CLARITY_STANDARD_PRINCIPAL: u8 = 0x05;
+const CLARITY_LIST: u8 = 0x0b;
+const CLARITY_TUPLE: u8 = 0x0c;
+
+/// The server's result signing key, a 32-byte secp256k1 secret key.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ResultSigningKey([u8; 32]);
+
+impl fmt::Debug for ResultSigningKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ResultSigningKey(..)")
+    }
+}
+
+impl ResultSigningKey {
+    /// Read the hex key in `RESULT_SIGNING_KEY`; unset means results go unsigned.
+    pub fn from_env() -> Result<Option<Self>, String> {
+        match std::env::var("RESULT_SIGNING_KEY") {
+            Ok(key) if !key.trim().is_empty() => Self::from_hex(key.trim())
+                .map(Some)
+                .map_err(|e| format!("RESULT_SIGNING_KEY: {}", e)),
+            _ => Ok(None),
+        }
+    }
+
+    pub fn from_hex(key: &str) -> Result<Self, String> {
+        let bytes = hex::decode(key).map_err(|e| e.to_string())?;
+        let key: [u8; 32] = bytes
+            .try_into()
+            .map_err(|bytes: Vec<u8>| format!("expected 32 bytes, got {}", bytes.len()))?;
+        SigningKey::from_bytes(&key.into())
+            .map_err(|_| "not a valid secp256k1 secret key".to_string())?;
+        Ok(Self(key))
+    }
+
+    fn signing_key(&self) -> SigningKey {
+        SigningKey::from_bytes(&self.0.into()).expect("checked when the key was read")
+    }
+
+    /// Compressed public key settlement verifies against, hex encoded
+    pub fn public_key(&self) -> String {
+        hex::encode(
+            self.signing_key()
+                .verifying_key()
+                .to_encoded_point(true)
+                .as_bytes(),
+        )
+    }
+
+    /// Sign the settlement of `results` for `lobby_id`.
+    ///
+    /// `wallets` holds each ranked player's Stacks address.
+    pub fn sign_results(
+        &self,
+        lobby_id: Uuid,
+        results: &GameResults,
+        wallets: &HashMap<Uuid, String>,
+    ) -> Result<SignedResults, String> {
+        let payload = settlement_payload(lobby_id, results, wallets)?;
+        let (signature, recovery_id) = self
+            .signing_key()
+            .sign_prehash_recoverable(&Sha256::digest(&payload))
+            .expect("a 32-byte digest can be signed");
+        let mut signature = signature.to_bytes().to_vec();
+        signature.push(recovery_id.to_byte());
+
+        Ok(SignedResults {
+            lobby_id,
+            payload: hex::encode(payload),
+            signature: hex::encode(signature),
+            public_key: self.public_key(),
+        })
+    }
+}
+
+impl SignedResults {
+    /// Whether `signature` is `public_key`'s signature over the SHA-256 of `payload`.
+    pub fn verify(&self) -> bool {
+        let (Ok(payload), Ok(signature), Ok(public_key)) = (
+            hex::decode(&self.payload),
+            hex::decode(&self.signature),
+            hex::decode(&self.public_key),
+        ) else {
+            return false;
+        };
+        if signature.len() != 65 {
+            return false;
+        }
+        let (Ok(key), Ok(signature)) = (
+            VerifyingKey::from_sec1_bytes(&public_key),
+            Signature::from_slice(&signature[..64]),
+        ) else {
+            return false;
+        };
+        key.verify_prehash(&Sha256::digest(&payload), &signature)
+            .is_ok()
+    }
+}
+
+/// The consensus-serialized settlement tuple signed for `results` in `lobby_id`.
+///
+/// Fails when a ranked player has no wallet in `wallets`, or one that isn't a
+/// standard principal.
+pub fn settlement_payload(
+    lobby_id: Uuid,
+    results: &GameResults,
+    wallets: &HashMap<Uuid, String>,
+) -> Result<Vec<u8>, String> {
+    let mut rankings: Vec<&PlayerRanking> = results.rankings.iter().collect();
+    rankings.sort_by(|a, b| a.rank.cmp(&b.rank).then(a.user_id.cmp(&b.user_id)));
+
+    let mut players = Vec::with_capacity(rankings.len());
+    let mut prizes = Vec::with_capacity(rankings.len());
+    for ranking in rankings {
+        let wallet = wallets
+            .get(&ranking.user_id)
+            .ok_or_else(|| format!("no wallet for player {}", ranking.user_id))?;
+        players.push(principal(wallet)?);
+
+        let prize = match ranking.prize {
+            Some(prize) => to_micro_units(prize)
+                .and_then(|prize| u128::try_from(prize).ok())
+                .ok_or_else(|| format!("prize {} can't be paid out", prize))?,
+            None => 0,
+        };
+        prizes.push(uint(prize));
+    }
+
+    // Tuple fields are serialized in name order
+    Ok(tuple(&[
+        ("lobby-id", buffer(lobby_id.as_bytes())),
+        ("players", list(players)),
+        ("prizes", list(prizes)),
+    ]))
+}
+
+fn uint(value: u128) -> Vec<u8> {
+    let mut bytes = vec![CLARITY_UINT];
+    bytes.extend(value.to_be_bytes());
+    bytes
+}
+
+fn buffer(data: &[u8]) -> Vec<u8> {
+    let mut bytes = vec![CLARITY_BUFFER];
+    bytes.extend((data.len() as u32).to_be_bytes());
+    bytes.extend(data);
+    bytes
+}
+
+fn principal(address: &str) -> Result<Vec<u8>, String> {
+    let (hash160, version) = c32::decode_check_prefixed(address, 'S')
+        .ok()
+        .filter(|(hash160, _)| hash160.len() == 20)
+        .ok_or_else(|| format!("'{}' is not a standard principal", address))?;
+    let mut bytes = vec![CLARITY_STANDARD_PRINCIPAL, version];
+    bytes.extend(hash160);
+    Ok(bytes)
+}
+
+fn list(items: Vec<Vec<u8>>) -> Vec<u8> {
+    let mut bytes = vec![CLARITY_LIST];
+    bytes.extend((items.len() as u32).to_be_bytes());
+    bytes.extend(items.concat());
+    bytes
+}
+
+/// `fields` must already be in name order
+fn tuple(fields: &[(&str, Vec<u8>)]) -> Vec<u8> {
+    let mut bytes = vec![CLARITY_TUPLE];
+    bytes.extend((fields.len() as u32).to_be_bytes());
+    for (name, value) in fields {
+        bytes.push(name.len() as u8);
+        bytes.extend(name.as_bytes());
+        bytes.extend(value);
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+    use std::str::FromStr;
+
+    const WINNER: &str = "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7";
+    const WINNER_HASH160: &str = "a46ff88886c2ef9762d970b4d2c63678835bd39d";
+
+    fn key() -> ResultSigningKey {
+        ResultSigningKey::from_hex(&"07".repeat(32)).unwrap()
+    }
+
+    fn wallets() -> HashMap<Uuid, String> {
+        [
+            (Uuid::from_u128(1), WINNER.to_string()),
+            (
+                Uuid::from_u128(2),
+                crate::auth::wallet_signature::stacks_address(&[2; 33], 22),
+            ),
+            (
+                Uuid::from_u128(3),
+                crate::auth::wallet_signature::stacks_address(&[3; 33], 26),
+            ),
+        ]
+        .into()
+    }
+
+    fn results() -> GameResults {
+        let mut results = GameResults::from_ordered_players(vec![
+            Uuid::from_u128(1),
+            Uuid::from_u128(2),
+            Uuid::from_u128(3),
+        ]);
+        results.rankings[0].prize = Some(Decimal::from_str("7.50").unwrap());
+        results.rankings[1].prize = Some(Decimal::from_str("2.5").unwrap());
+        results
+    }
+
+    #[test]
+    fn test_payload_is_the_consensus_serialized_tuple() {
+        let lobby_id = Uuid::from_u128(9);
+        let mut results = GameResults::from_ordered_players(vec![Uuid::from_u128(1)]);
+        results.rankings[0].prize = Some(Decimal::from_str("7.5").unwrap());
+
+        let expected = [
+            "0c00000003",
+            // lobby-id: (buff 16)
+            "086c6f6262792d6964",
+            "0200000010",
+            "00000000000000000000000000000009",
+            // players: (list 1 principal)
+            "07706c6179657273",
+            "0b00000001",
+            "0516",
+            WINNER_HASH160,
+            // prizes: (list 1 uint), 7.5 as micro-units
+            "067072697a6573",
+            "0b00000001",
+            "01000000000000000000000000007270e0",
+        ]
+        .concat();
+        assert_eq!(
+            hex::encode(settlement_payload(lobby_id, &results, &wallets()).unwrap()),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_same_results_sign_the_same() {
+        let lobby_id = Uuid::from_u128(9);
+        let first = key()
+            .sign_results(lobby_id, &results(), &wallets())
+            .unwrap();
+
+        // Same results listed in another order, with equal but differently scaled prizes
+        let mut reordered = results();
+        reordered.rankings.reverse();
+        reordered.rankings[2].prize = Some(Decimal::from_str("7.5").unwrap());
+        let second = key()
+            .sign_results(lobby_id, &reordered, &wallets())
+            .unwrap();
+
+        assert_eq!(first, second);
+        assert!(first.verify());
+        assert_eq!(first.public_key, key().public_key());
+        assert_eq!(hex::decode(&first.signature).unwrap().len(), 65);
+    }
+
+    #[test]
+    fn test_any_payout_change_changes_the_signature() {
+        let lobby_id = Uuid::from_u128(9);
+        let signed = key()
+            .sign_results(lobby_id, &results(), &wallets())
+            .unwrap();
+
+        let changes: [fn(&mut GameResults); 3] = [
+            |r| r.rankings.swap(0, 1),
+            |r| r.rankings[1].prize = Some(Decimal::from_str("2.51").unwrap()),
+            |r| r.rankings[2].prize = Some(Decimal::ONE),
+        ];
+        for change in changes {
+            let mut changed = results();
+            change(&mut changed);
+            for (rank, ranking) in changed.rankings.iter_mut().enumerate() {
+                ranking.rank = rank + 1;
+            }
+            assert_ne!(
+                key()
+                    .sign_results(lobby_id, &changed, &wallets())
+                    .unwrap()
+                    .signature,
+                signed.signature
+            );
+        }
+        assert_ne!(
+            key()
+                .sign_results(Uuid::from_u128(10), &results(), &wallets())
+                .unwrap()
+                .signature,
+            signed.signature
+        );
+    }
+
+    #[test]
+    fn test_tampered_payload_fails_verification() {
+        let mut signed = key()
+            .sign_results(Uuid::from_u128(9), &results(), &wallets())
+            .unwrap();
+        signed.payload = signed.payload.replace("7270e0", "7270e1");
+        assert!(!signed.verify());
+    }
+
+    #[test]
+    fn test_players_need_a_principal() {
+        let mut wallets = wallets();
+        wallets.remove(&Uuid::from_u128(3));
+        assert!(settlement_payload(Uuid::nil(), &results(), &wallets).is_err());
+
+        wallets.insert(Uuid::from_u128(3), "SP000NOTANADDRESS".to_string());
+        assert!(settlement_payload(Uuid::nil(), &results(), &wallets).is_err());
+    }
+
+    #[test]
+    fn test_signing_key_must_be_a_valid_secret_key() {
+        assert!(ResultSigningKey::from_hex("abcd").is_err());
+        assert!(ResultSigningKey::from_hex("zz").is_err());
+        assert!(ResultSigningKey::from_hex(&"00".repeat(32)).is_err());
+    }
+}
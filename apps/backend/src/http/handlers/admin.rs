@@ -0,0 +1,392 @@
+// Admin tooling handlers: bulk seeding of seasons and games, maintenance mode,
+// feature flags, wallet access lists, announcements, lobby counts, user roles,
+// reprocessing a lobby's wars points
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    announcements::{self, Announcement, AnnouncementLevel},
+    auth::roles::{Admins, RequireRole},
+    db::{game::GameRepository, season::SeasonRepository, user::UserRepository},
+    errors::AppError,
+    feature_flags::{Feature, FeatureFlagStatus, FeatureFlags},
+    games::common::reprocess_points,
+    lobby_service::{LobbyService, LobbyStatusSummary},
+    maintenance::{MaintenanceMode, MaintenanceStatus},
+    models::{
+        AwardCorrection, Role,
+        seed::{SeedBundle, SeedCounts},
+    },
+    state::AppState,
+    wallet_access::{WalletAccess, WalletAccessStatus, WalletList},
+};
+
+// ============================================================================
+// Request/Response Types
+// ============================================================================
+
+/// Per-kind counts of what a seed run changed
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeedReport {
+    pub seasons: SeedCounts,
+    pub games: SeedCounts,
+}
+
+/// Body for toggling maintenance mode
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetMaintenanceRequest {
+    pub enabled: bool,
+    /// Shown to players instead of the default message
+    pub message: Option<String>,
+}
+
+/// Body for overriding a feature flag
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetFeatureFlagRequest {
+    /// `null` drops the override, going back to the configured default
+    pub enabled: Option<bool>,
+}
+
+/// Body for switching allowlist-only mode
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetAllowlistOnlyRequest {
+    /// `null` drops the override, going back to `WALLET_ALLOWLIST_ONLY`
+    pub allowlist_only: Option<bool>,
+}
+
+/// Body for posting an announcement
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnounceRequest {
+    pub level: AnnouncementLevel,
+    pub message: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// Seed seasons and games from a JSON bundle (admin only)
+///
+/// The bundle is validated up front and applied in one transaction, so a bad
+/// entry leaves the database untouched. Existing rows are skipped or updated
+/// depending on `mode`.
+pub async fn seed(
+    State(state): State<AppState>,
+    RequireRole(auth, _): RequireRole<Admins>,
+    Json(bundle): Json<SeedBundle>,
+) -> Result<Json<SeedReport>, (StatusCode, String)> {
+    let creator_id = auth.user_id()?;
+
+    bundle.validate().map_err(|e| e.to_response())?;
+
+    let mut transaction = state.postgres.begin().await.map_err(|e| {
+        AppError::DatabaseError(format!("Failed to begin transaction: {}", e)).to_response()
+    })?;
+
+    let mut report = SeedReport {
+        seasons: SeedCounts::default(),
+        games: SeedCounts::default(),
+    };
+
+    for season in &bundle.seasons {
+        let outcome = SeasonRepository::seed_season(&mut transaction, season, bundle.mode)
+            .await
+            .map_err(|e| e.to_response())?;
+        report.seasons.record(outcome);
+    }
+
+    for game in &bundle.games {
+        let outcome = GameRepository::seed_game(&mut transaction, game, bundle.mode, creator_id)
+            .await
+            .map_err(|e| e.to_response())?;
+        report.games.record(outcome);
+    }
+
+    transaction.commit().await.map_err(|e| {
+        AppError::DatabaseError(format!("Failed to commit seed: {}", e)).to_response()
+    })?;
+
+    if report.seasons.inserted + report.seasons.updated > 0 {
+        SeasonRepository::new(state.postgres.clone())
+            .with_cache(state.redis.clone())
+            .invalidate_current()
+            .await
+            .map_err(|e| e.to_response())?;
+    }
+
+    tracing::info!(
+        "Admin {} seeded seasons {:?} and games {:?}",
+        auth.wallet_address(),
+        report.seasons,
+        report.games
+    );
+
+    Ok(Json(report))
+}
+
+/// Current maintenance mode status (admin only)
+pub async fn get_maintenance(
+    State(state): State<AppState>,
+    _: RequireRole<Admins>,
+) -> Result<Json<MaintenanceStatus>, (StatusCode, String)> {
+    let status = MaintenanceMode::new(state.redis.clone())
+        .status()
+        .await
+        .map_err(|e| e.to_response())?;
+
+    Ok(Json(status))
+}
+
+/// Turn maintenance mode on or off (admin only)
+///
+/// While on, lobby creation and game starts are refused; games already in
+/// progress and reconnections are unaffected.
+pub async fn set_maintenance(
+    State(state): State<AppState>,
+    RequireRole(auth, _): RequireRole<Admins>,
+    Json(payload): Json<SetMaintenanceRequest>,
+) -> Result<Json<MaintenanceStatus>, (StatusCode, String)> {
+    let maintenance = MaintenanceMode::new(state.redis.clone());
+    let status = if payload.enabled {
+        maintenance.enable(payload.message).await
+    } else {
+        maintenance.disable().await
+    }
+    .map_err(|e| e.to_response())?;
+
+    tracing::info!(
+        "Admin {} set maintenance mode to {}",
+        auth.wallet_address(),
+        status.enabled
+    );
+
+    Ok(Json(status))
+}
+
+/// Every feature flag with its default and any override (admin only)
+pub async fn list_feature_flags(
+    State(state): State<AppState>,
+    _: RequireRole<Admins>,
+) -> Result<Json<Vec<FeatureFlagStatus>>, (StatusCode, String)> {
+    let flags = FeatureFlags::from_state(&state)
+        .list()
+        .await
+        .map_err(|e| e.to_response())?;
+
+    Ok(Json(flags))
+}
+
+/// Switch a feature on or off at runtime, or back to its default (admin only)
+pub async fn set_feature_flag(
+    State(state): State<AppState>,
+    RequireRole(auth, _): RequireRole<Admins>,
+    Path(feature): Path<Feature>,
+    Json(payload): Json<SetFeatureFlagRequest>,
+) -> Result<Json<FeatureFlagStatus>, (StatusCode, String)> {
+    let status = FeatureFlags::from_state(&state)
+        .set(feature, payload.enabled)
+        .await
+        .map_err(|e| e.to_response())?;
+
+    tracing::info!(
+        "Admin {} set feature {} to {:?}",
+        auth.wallet_address(),
+        feature,
+        payload.enabled
+    );
+
+    Ok(Json(status))
+}
+
+/// Wallet allow and deny lists and whether only allowed wallets get in (admin only)
+pub async fn get_wallet_access(
+    State(state): State<AppState>,
+    _: RequireRole<Admins>,
+) -> Result<Json<WalletAccessStatus>, (StatusCode, String)> {
+    let status = WalletAccess::from_state(&state)
+        .status()
+        .await
+        .map_err(|e| e.to_response())?;
+
+    Ok(Json(status))
+}
+
+/// Turn allowlist-only mode on or off, or back to its configured value (admin only)
+pub async fn set_allowlist_only(
+    State(state): State<AppState>,
+    RequireRole(auth, _): RequireRole<Admins>,
+    Json(payload): Json<SetAllowlistOnlyRequest>,
+) -> Result<Json<WalletAccessStatus>, (StatusCode, String)> {
+    let access = WalletAccess::from_state(&state);
+    access
+        .set_allowlist_only(payload.allowlist_only)
+        .await
+        .map_err(|e| e.to_response())?;
+
+    tracing::info!(
+        "Admin {} set allowlist-only mode to {:?}",
+        auth.wallet_address(),
+        payload.allowlist_only
+    );
+
+    let status = access.status().await.map_err(|e| e.to_response())?;
+    Ok(Json(status))
+}
+
+/// Put a wallet on the allow or deny list (admin only)
+///
+/// Takes effect on the wallet's next request or connection; sockets it
+/// already has open stay up.
+pub async fn add_wallet_access(
+    State(state): State<AppState>,
+    RequireRole(auth, _): RequireRole<Admins>,
+    Path((list, wallet)): Path<(WalletList, String)>,
+) -> Result<Json<WalletAccessStatus>, (StatusCode, String)> {
+    let access = WalletAccess::from_state(&state);
+    access
+        .add(list, &wallet)
+        .await
+        .map_err(|e| e.to_response())?;
+
+    tracing::info!(
+        "Admin {} added {} to the {} list",
+        auth.wallet_address(),
+        wallet,
+        list
+    );
+
+    let status = access.status().await.map_err(|e| e.to_response())?;
+    Ok(Json(status))
+}
+
+/// Take a wallet off the allow or deny list (admin only)
+pub async fn remove_wallet_access(
+    State(state): State<AppState>,
+    RequireRole(auth, _): RequireRole<Admins>,
+    Path((list, wallet)): Path<(WalletList, String)>,
+) -> Result<Json<WalletAccessStatus>, (StatusCode, String)> {
+    let access = WalletAccess::from_state(&state);
+    access
+        .remove(list, &wallet)
+        .await
+        .map_err(|e| e.to_response())?;
+
+    tracing::info!(
+        "Admin {} removed {} from the {} list",
+        auth.wallet_address(),
+        wallet,
+        list
+    );
+
+    let status = access.status().await.map_err(|e| e.to_response())?;
+    Ok(Json(status))
+}
+
+/// Lobby counts by status and game, live status first (admin only)
+pub async fn lobby_status_summary(
+    State(state): State<AppState>,
+    _: RequireRole<Admins>,
+) -> Result<Json<LobbyStatusSummary>, (StatusCode, String)> {
+    let summary = LobbyService::new(state)
+        .status_summary()
+        .await
+        .map_err(|e| e.to_response())?;
+
+    Ok(Json(summary))
+}
+
+/// Push a notice to every connected client (admin only)
+///
+/// Clients that connect before `expiresAt` are sent it too.
+pub async fn announce(
+    State(state): State<AppState>,
+    RequireRole(auth, _): RequireRole<Admins>,
+    Json(payload): Json<AnnounceRequest>,
+) -> Result<(StatusCode, Json<Announcement>), (StatusCode, String)> {
+    let announcement = Announcement::new(payload.level, &payload.message, payload.expires_at)
+        .map_err(|e| e.to_response())?;
+    announcements::announce(&state, announcement.clone())
+        .await
+        .map_err(|e| e.to_response())?;
+
+    tracing::info!(
+        "Admin {} posted {:?} announcement {}",
+        auth.wallet_address(),
+        announcement.level,
+        announcement.id
+    );
+
+    Ok((StatusCode::CREATED, Json(announcement)))
+}
+
+/// Request body for giving a user a role
+#[derive(Debug, Deserialize)]
+pub struct SetRoleRequest {
+    pub role: Role,
+}
+
+/// Make a user a moderator or admin, or take that away (admin only)
+pub async fn set_user_role(
+    State(state): State<AppState>,
+    RequireRole(auth, _): RequireRole<Admins>,
+    Path(user_id): Path<Uuid>,
+    Json(payload): Json<SetRoleRequest>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    UserRepository::new(state.postgres.clone())
+        .set_role(user_id, payload.role)
+        .await
+        .map_err(|e| e.to_response())?;
+
+    tracing::info!(
+        "Admin {} set the role of {} to {}",
+        auth.wallet_address(),
+        user_id,
+        payload.role
+    );
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Result of reprocessing a lobby's wars points
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReprocessPointsResponse {
+    pub lobby_id: Uuid,
+    pub awards: Vec<AwardCorrection>,
+}
+
+/// Recompute a finished lobby's wars points under the current formula and
+/// correct each player's award (admin only)
+///
+/// Safe to repeat: awards already matching the formula are left alone.
+pub async fn reprocess_lobby_points(
+    State(state): State<AppState>,
+    RequireRole(auth, _): RequireRole<Admins>,
+    Path(lobby_id): Path<Uuid>,
+) -> Result<Json<ReprocessPointsResponse>, (StatusCode, String)> {
+    let awards = reprocess_points(&state, lobby_id)
+        .await
+        .map_err(|e| e.to_response())?;
+
+    tracing::info!(
+        "Admin {} reprocessed wars points of lobby {} ({} changed)",
+        auth.wallet_address(),
+        lobby_id,
+        awards.iter().filter(|a| a.points != a.previous).count()
+    );
+
+    Ok(Json(ReprocessPointsResponse { lobby_id, awards }))
+}
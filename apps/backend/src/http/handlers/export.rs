@@ -0,0 +1,339 @@
+// Personal data export: streams everything we hold about the caller as one JSON document
+
+use std::collections::BTreeSet;
+
+use axum::{
+    body::{Body, Bytes},
+    extract::State,
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use futures::{SinkExt, channel::mpsc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    auth::AuthClaims,
+    db::{
+        lobby::LobbyRepository, lobby_chat::LobbyChatRepository,
+        lobby_refund::LobbyRefundRepository, pagination::Paginator,
+        platform_rating::PlatformRatingRepository, player_state::PlayerStateRepository,
+        report::ReportRepository, skill_rating::SkillRatingRepository, streak::StreakRepository,
+        user::UserRepository, user_badge::UserBadgeRepository,
+        user_wars_points::UserWarsPointsRepository,
+    },
+    errors::AppError,
+    games::common::{GameSummary, load_game_summary},
+    models::{Lobby, PlayerState, player_state::ClaimState},
+    state::AppState,
+};
+
+/// Rows fetched per query for the sections that can grow without bound
+const EXPORT_PAGE_SIZE: usize = 100;
+
+/// Chunks buffered between the export task and the response body
+const EXPORT_CHANNEL_CAPACITY: usize = 16;
+
+type Chunk = Result<Bytes, std::io::Error>;
+
+// ============================================================================
+// Export Types
+// ============================================================================
+
+/// One lobby the user played in, with their final state and the game outcome
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MatchRecord {
+    lobby_id: Uuid,
+    /// `None` if the lobby row has since been removed
+    lobby: Option<Lobby>,
+    player: PlayerState,
+    /// `None` until the game has finished
+    summary: Option<GameSummary>,
+}
+
+/// An on-chain transaction the user was party to
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TransactionRecord {
+    lobby_id: Uuid,
+    kind: &'static str,
+    tx_id: String,
+}
+
+// ============================================================================
+// Handler
+// ============================================================================
+
+/// Export the authenticated user's personal data as a downloadable JSON file.
+///
+/// Sections are fetched and written one at a time (lobbies and chat a page at
+/// a time), so large histories are never held in memory as a whole. A failure
+/// part-way through aborts the body, leaving the client with truncated JSON
+/// rather than a document that silently misses data.
+pub async fn export_me(
+    State(state): State<AppState>,
+    auth: AuthClaims,
+) -> Result<Response, (StatusCode, String)> {
+    let user_id = auth.user_id()?;
+
+    // Fail with a proper status if the user is gone, before any bytes are sent
+    let user = UserRepository::new(state.postgres.clone())
+        .find_by_id(user_id)
+        .await
+        .map_err(|e| e.to_response())?;
+
+    let (tx, rx) = mpsc::channel::<Chunk>(EXPORT_CHANNEL_CAPACITY);
+
+    tokio::spawn(async move {
+        let mut writer = ExportWriter::new(tx);
+        let result = async {
+            writer.begin(&user).await?;
+            write_sections(&state, user_id, &mut writer).await?;
+            writer.finish().await
+        }
+        .await;
+
+        match result {
+            Ok(()) => {}
+            Err(_) if writer.tx.is_closed() => {
+                tracing::debug!("Client went away during data export for user {}", user_id);
+            }
+            Err(e) => {
+                tracing::error!("Data export for user {} failed: {}", user_id, e);
+                writer.abort(e).await;
+            }
+        }
+    });
+
+    let disposition = format!(
+        "attachment; filename=\"stacks-wars-export-{}.json\"",
+        user_id
+    );
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/json".to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        Body::from_stream(rx),
+    )
+        .into_response())
+}
+
+async fn write_sections(
+    state: &AppState,
+    user_id: Uuid,
+    writer: &mut ExportWriter,
+) -> Result<(), AppError> {
+    let pg = state.postgres.clone();
+
+    let skill_ratings = SkillRatingRepository::new(pg.clone())
+        .find_by_user(user_id)
+        .await?;
+    writer.field("skillRatings", &skill_ratings).await?;
+
+    let wars_points = UserWarsPointsRepository::new(pg.clone())
+        .get_all_wars_points(user_id)
+        .await?;
+    writer.field("warsPoints", &wars_points).await?;
+
+    let badges = UserBadgeRepository::new(pg.clone())
+        .find_by_user(user_id, None)
+        .await?;
+    writer.field("badges", &badges).await?;
+
+    let streaks = StreakRepository::new(state.redis.clone())
+        .get(user_id, state.config.streak_day_offset_secs)
+        .await?;
+    writer.field("streaks", &streaks).await?;
+
+    let platform_rating = PlatformRatingRepository::new(pg.clone())
+        .get_by_user(user_id)
+        .await?;
+    writer.field("platformRating", &platform_rating).await?;
+
+    let reports = ReportRepository::new(pg.clone())
+        .find_by_reporter(user_id)
+        .await?;
+    writer.field("reportsFiled", &reports).await?;
+
+    // Lobbies created, paged; keep only ids for the chat section
+    let lobby_repo = LobbyRepository::new(pg.clone());
+    let mut chat_lobbies = BTreeSet::new();
+
+    writer.begin_array("lobbiesCreated").await?;
+    let mut offset = 0;
+    loop {
+        let (lobbies, _) = lobby_repo
+            .find_by_creator(user_id, offset, EXPORT_PAGE_SIZE)
+            .await?;
+        for lobby in &lobbies {
+            chat_lobbies.insert(lobby.id);
+            writer.item(lobby).await?;
+        }
+        if lobbies.len() < EXPORT_PAGE_SIZE {
+            break;
+        }
+        offset += EXPORT_PAGE_SIZE;
+    }
+    writer.end_array().await?;
+
+    // Match history: one lobby at a time, collecting transaction ids as we go
+    let player_repo = PlayerStateRepository::new(state.redis.clone());
+    let mut transactions = Vec::new();
+
+    writer.begin_array("matchHistory").await?;
+    for lobby_id in player_repo.get_lobby_ids_for_user(user_id).await? {
+        let player = match player_repo.get_state(lobby_id, user_id).await {
+            Ok(player) => player,
+            // Removed between listing and reading
+            Err(AppError::NotFound(_)) => continue,
+            Err(e) => return Err(e),
+        };
+        chat_lobbies.insert(lobby_id);
+
+        if let Some(tx_id) = &player.tx_id {
+            transactions.push(TransactionRecord {
+                lobby_id,
+                kind: "entry",
+                tx_id: tx_id.clone(),
+            });
+        }
+        if let Some(ClaimState::Claimed { tx_id }) = &player.claim_state {
+            transactions.push(TransactionRecord {
+                lobby_id,
+                kind: "claim",
+                tx_id: tx_id.clone(),
+            });
+        }
+
+        let lobby = match lobby_repo.find_by_id(lobby_id).await {
+            Ok(lobby) => Some(lobby),
+            Err(AppError::NotFound(_)) => None,
+            Err(e) => return Err(e),
+        };
+        // Practice games against a bot aren't part of anyone's history
+        if lobby.as_ref().is_some_and(|l| l.practice_bot.is_some()) {
+            continue;
+        }
+        let summary = load_game_summary(&state.redis, lobby_id).await?;
+
+        writer
+            .item(&MatchRecord {
+                lobby_id,
+                lobby,
+                player,
+                summary,
+            })
+            .await?;
+    }
+    writer.end_array().await?;
+
+    writer.field("transactions", &transactions).await?;
+
+    let refunds = LobbyRefundRepository::new(pg).find_by_user(user_id).await?;
+    writer.field("refunds", &refunds).await?;
+
+    // Chat: page through each lobby's history and keep the user's own messages
+    let chat_repo = LobbyChatRepository::new(state.redis.clone());
+    let chat_pages = Paginator::new(Some(EXPORT_PAGE_SIZE));
+
+    writer.begin_array("chatMessages").await?;
+    for lobby_id in chat_lobbies {
+        let mut cursor = None;
+        loop {
+            let page = chat_repo
+                .get_page(lobby_id, &chat_pages, cursor.as_deref())
+                .await
+                .map_err(AppError::RedisError)?;
+            for message in page.items.iter().filter(|m| m.user_id == user_id) {
+                writer.item(message).await?;
+            }
+            if !page.has_more {
+                break;
+            }
+            cursor = page.next_cursor;
+        }
+    }
+    writer.end_array().await?;
+
+    Ok(())
+}
+
+// ============================================================================
+// Streaming Writer
+// ============================================================================
+
+/// Writes a JSON object to the response body piece by piece.
+struct ExportWriter {
+    tx: mpsc::Sender<Chunk>,
+    /// Whether the next array item is the first one
+    first_item: bool,
+}
+
+impl ExportWriter {
+    fn new(tx: mpsc::Sender<Chunk>) -> Self {
+        Self {
+            tx,
+            first_item: true,
+        }
+    }
+
+    /// Fails with `InternalError` once the client has disconnected.
+    async fn send(&mut self, chunk: Vec<u8>) -> Result<(), AppError> {
+        self.tx
+            .send(Ok(Bytes::from(chunk)))
+            .await
+            .map_err(|_| AppError::InternalError)
+    }
+
+    fn to_json<T: Serialize>(value: &T) -> Result<Vec<u8>, AppError> {
+        serde_json::to_vec(value).map_err(|e| AppError::Serialization(e.to_string()))
+    }
+
+    /// Open the document with the `profile` section.
+    async fn begin<T: Serialize>(&mut self, profile: &T) -> Result<(), AppError> {
+        let mut chunk = b"{\"profile\":".to_vec();
+        chunk.extend(Self::to_json(profile)?);
+        self.send(chunk).await
+    }
+
+    async fn field<T: Serialize>(&mut self, name: &str, value: &T) -> Result<(), AppError> {
+        let mut chunk = format!(",\"{}\":", name).into_bytes();
+        chunk.extend(Self::to_json(value)?);
+        self.send(chunk).await
+    }
+
+    async fn begin_array(&mut self, name: &str) -> Result<(), AppError> {
+        self.first_item = true;
+        self.send(format!(",\"{}\":[", name).into_bytes()).await
+    }
+
+    async fn item<T: Serialize>(&mut self, value: &T) -> Result<(), AppError> {
+        let mut chunk = if self.first_item {
+            Vec::new()
+        } else {
+            b",".to_vec()
+        };
+        self.first_item = false;
+        chunk.extend(Self::to_json(value)?);
+        self.send(chunk).await
+    }
+
+    async fn end_array(&mut self) -> Result<(), AppError> {
+        self.send(b"]".to_vec()).await
+    }
+
+    async fn finish(&mut self) -> Result<(), AppError> {
+        self.send(b"}".to_vec()).await
+    }
+
+    /// Fail the response body so the client sees an error instead of a short file.
+    async fn abort(&mut self, error: AppError) {
+        let _ = self
+            .tx
+            .send(Err(std::io::Error::other(error.to_string())))
+            .await;
+    }
+}
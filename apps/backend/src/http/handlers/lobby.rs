@@ -5,11 +5,24 @@ use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
 };
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::auth::invite::generate_invite_token;
+use crate::db::game_word::GameWordRepository;
+use crate::db::join_request::{JoinRequestRepository, JoinRequestState};
+use crate::db::lobby_invite::LobbyInviteRepository;
+use crate::db::user::UserRepository;
+use crate::errors::AppError;
+use crate::feature_flags::{Feature, FeatureFlags};
 use crate::http::handlers::stacks::has_joined;
-use crate::models::WalletAddress;
+use crate::http::validation::{ValidJson, Validate, ValidationErrors};
+use crate::lobby_service::LobbyService;
+use crate::models::{
+    BotDifficulty, GameWord, GameWordStats, PayoutError, PayoutTable, PayoutUnderfill,
+    ReplayTimeline, WalletAddress,
+};
 use crate::{auth::AuthClaims, db::lobby::LobbyRepository, models::Lobby, state::AppState};
 
 // ============================================================================
@@ -21,24 +34,150 @@ use crate::{auth::AuthClaims, db::lobby::LobbyRepository, models::Lobby, state::
 pub struct CreateLobbyRequest {
     pub name: String,
     pub description: Option<String>,
-    pub entry_amount: Option<f64>,
-    pub current_amount: Option<f64>,
+    #[serde(default, with = "rust_decimal::serde::float_option")]
+    pub entry_amount: Option<Decimal>,
+    #[serde(default, with = "rust_decimal::serde::float_option")]
+    pub current_amount: Option<Decimal>,
     pub token_symbol: Option<String>,
     pub token_contract_id: Option<String>,
     pub contract_address: Option<String>,
     pub is_private: Option<bool>,
     #[serde(default)]
     pub is_sponsored: bool,
+    /// Let non-players watch the room (defaults to true)
+    pub spectators_allowed: Option<bool>,
+    /// Show spectators "Player A/B" instead of who is playing until the game
+    /// finishes
+    #[serde(default)]
+    pub anonymize_players: bool,
+    /// Practice solo against a bot of this difficulty
+    pub practice_bot: Option<BotDifficulty>,
+    /// Private lobbies only: admit join requests from users with at least
+    /// this trust rating without the creator's approval
+    pub auto_approve_trust_threshold: Option<f64>,
+    /// Percent of the pot paid to each place, 1st first; must add up to 100
+    pub payout_percents: Option<Vec<u32>>,
+    /// Where shares of places nobody finishes in go (defaults to the winner)
+    pub payout_underfill: Option<PayoutUnderfill>,
     pub game_id: Uuid,
     pub game_path: String,
 }
 
+impl Validate for CreateLobbyRequest {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        errors.check(!self.name.trim().is_empty(), "name", "must not be empty");
+        for (field, amount) in [
+            ("entryAmount", self.entry_amount),
+            ("currentAmount", self.current_amount),
+        ] {
+            errors.check(
+                amount.is_none_or(|a| a >= Decimal::ZERO),
+                field,
+                "must be >= 0",
+            );
+        }
+        for (field, address) in [
+            ("tokenContractId", &self.token_contract_id),
+            ("contractAddress", &self.contract_address),
+        ] {
+            if let Some(Err(e)) = address.as_deref().map(WalletAddress::new) {
+                errors.add(field, e.to_string());
+            }
+        }
+        match self.auto_approve_trust_threshold {
+            Some(t) if !t.is_finite() || t < 0.0 => {
+                errors.add("autoApproveTrustThreshold", "must be a finite number >= 0")
+            }
+            Some(_) if self.is_private != Some(true) => errors.add(
+                "autoApproveTrustThreshold",
+                "only applies to private lobbies",
+            ),
+            _ => {}
+        }
+        if let Some(Err(e)) = self.payout_table() {
+            errors.add("payoutPercents", e.to_string());
+        }
+        errors.check(
+            !self.game_path.trim().is_empty(),
+            "gamePath",
+            "must not be empty",
+        );
+    }
+}
+
+impl CreateLobbyRequest {
+    /// The requested payout table, if any
+    fn payout_table(&self) -> Option<Result<PayoutTable, PayoutError>> {
+        let percents = self.payout_percents.clone()?;
+        Some(PayoutTable::new(
+            percents,
+            self.payout_underfill.unwrap_or_default(),
+        ))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateInviteRequest {
+    /// Maximum number of redemptions (omit for unlimited)
+    pub max_uses: Option<u32>,
+    /// Invite lifetime in seconds (omit for no expiry)
+    pub expires_in_secs: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpectatorSettingRequest {
+    pub spectators_allowed: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoApproveRequest {
+    /// Trust rating at which join requests are approved (omit to turn off)
+    pub trust_threshold: Option<f64>,
+}
+
+impl Validate for AutoApproveRequest {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        errors.check(
+            self.trust_threshold
+                .is_none_or(|t| t.is_finite() && t >= 0.0),
+            "trustThreshold",
+            "must be a finite number >= 0",
+        );
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InviteResponse {
+    pub invite_id: Uuid,
+    pub lobby_id: Uuid,
+    pub token: String,
+    pub max_uses: Option<u32>,
+    pub expires_at: Option<i64>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct LobbyQuery {
     pub limit: Option<i64>,
     pub offset: Option<i64>,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LobbyWordsResponse {
+    pub words: Vec<GameWord>,
+    pub stats: GameWordStats,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReplayFrameQuery {
+    /// Milliseconds into the game; negative seeks to before the start
+    pub at_ms: i64,
+}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PaginatedResponse<T> {
@@ -48,6 +187,30 @@ pub struct PaginatedResponse<T> {
     pub offset: i64,
 }
 
+// ============================================================================
+// Helpers
+// ============================================================================
+
+/// Ensure the authenticated user created the lobby
+async fn require_lobby_creator(
+    state: &AppState,
+    lobby_id: Uuid,
+    user_id: Uuid,
+) -> Result<(), (StatusCode, String)> {
+    let lobby = LobbyRepository::new(state.postgres.clone())
+        .find_by_id(lobby_id)
+        .await
+        .map_err(|e| e.to_response())?;
+
+    if lobby.creator_id != user_id {
+        return Err((
+            StatusCode::FORBIDDEN,
+            "Only the lobby creator can manage this lobby".to_string(),
+        ));
+    }
+    Ok(())
+}
+
 // ============================================================================
 // Handlers
 // ============================================================================
@@ -56,7 +219,7 @@ pub struct PaginatedResponse<T> {
 pub async fn create_lobby(
     State(state): State<AppState>,
     AuthClaims(claims): AuthClaims,
-    Json(payload): Json<CreateLobbyRequest>,
+    ValidJson(payload): ValidJson<CreateLobbyRequest>,
 ) -> Result<(StatusCode, Json<Lobby>), (StatusCode, String)> {
     let user_id = Uuid::parse_str(&claims.sub).map_err(|_| {
         tracing::error!("Invalid user ID in token");
@@ -71,8 +234,22 @@ pub async fn create_lobby(
         )
     })?;
 
-    // Confirm join if contract_address is provided
-    if let Some(ref contract_addr) = payload.contract_address {
+    let min_trust = &state.config.paid_lobby_trust;
+    if Lobby::is_paid_entry(payload.entry_amount, payload.is_sponsored) && min_trust.create > 0.0 {
+        let user = UserRepository::new(state.postgres.clone())
+            .find_by_id(user_id)
+            .await
+            .map_err(|e| e.to_response())?;
+        min_trust
+            .check_create(user.trust_rating)
+            .map_err(|e| e.to_response())?;
+    }
+
+    // Confirm join if contract_address is provided; practice lobbies are
+    // refused one below without asking the chain
+    if payload.practice_bot.is_none()
+        && let Some(ref contract_addr) = payload.contract_address
+    {
         let contract_wallet = WalletAddress::try_from(contract_addr.as_str()).map_err(|_| {
             (
                 StatusCode::BAD_REQUEST,
@@ -97,6 +274,18 @@ pub async fn create_lobby(
         payload.current_amount
     };
 
+    if payload.practice_bot.is_some() {
+        FeatureFlags::from_state(&state)
+            .ensure_enabled(Feature::PracticeLobbies)
+            .await
+            .map_err(|e| e.to_response())?;
+    }
+
+    let payouts = payload
+        .payout_table()
+        .transpose()
+        .map_err(|e| AppError::from(e).to_response())?;
+
     let repo = LobbyRepository::new(state.postgres.clone());
 
     let lobby = repo
@@ -113,6 +302,11 @@ pub async fn create_lobby(
             payload.contract_address.as_deref(),
             payload.is_private.unwrap_or(false),
             payload.is_sponsored,
+            payload.spectators_allowed.unwrap_or(true),
+            payload.anonymize_players,
+            payload.practice_bot,
+            payload.auto_approve_trust_threshold,
+            payouts,
             state.redis.clone(),
             state.clone(),
         )
@@ -122,6 +316,99 @@ pub async fn create_lobby(
     Ok((StatusCode::CREATED, Json(lobby)))
 }
 
+/// Create a signed invite link for a lobby. Creator only.
+pub async fn create_lobby_invite(
+    State(state): State<AppState>,
+    AuthClaims(claims): AuthClaims,
+    Path(lobby_id): Path<Uuid>,
+    Json(payload): Json<CreateInviteRequest>,
+) -> Result<(StatusCode, Json<InviteResponse>), (StatusCode, String)> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| (StatusCode::UNAUTHORIZED, "Invalid token".to_string()))?;
+
+    require_lobby_creator(&state, lobby_id, user_id).await?;
+
+    let invite = LobbyInviteRepository::new(state.redis.clone())
+        .create_invite(lobby_id, user_id, payload.max_uses, payload.expires_in_secs)
+        .await
+        .map_err(|e| e.to_response())?;
+
+    let token =
+        generate_invite_token(&invite, &state.config.invite_secret).map_err(|e| e.to_response())?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(InviteResponse {
+            invite_id: invite.invite_id,
+            lobby_id,
+            token,
+            max_uses: invite.max_uses,
+            expires_at: invite.expires_at,
+        }),
+    ))
+}
+
+/// Revoke a lobby invite, invalidating all of its tokens. Creator only.
+pub async fn revoke_lobby_invite(
+    State(state): State<AppState>,
+    AuthClaims(claims): AuthClaims,
+    Path((lobby_id, invite_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| (StatusCode::UNAUTHORIZED, "Invalid token".to_string()))?;
+
+    require_lobby_creator(&state, lobby_id, user_id).await?;
+
+    LobbyInviteRepository::new(state.redis.clone())
+        .revoke(lobby_id, invite_id)
+        .await
+        .map_err(|e| e.to_response())?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Allow or refuse spectators. Creator only. Refused spectators already
+/// watching are closed out when the game starts.
+pub async fn set_lobby_spectators(
+    State(state): State<AppState>,
+    AuthClaims(claims): AuthClaims,
+    Path(lobby_id): Path<Uuid>,
+    Json(payload): Json<SpectatorSettingRequest>,
+) -> Result<Json<Lobby>, (StatusCode, String)> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| (StatusCode::UNAUTHORIZED, "Invalid token".to_string()))?;
+
+    require_lobby_creator(&state, lobby_id, user_id).await?;
+
+    let lobby = LobbyRepository::new(state.postgres.clone())
+        .set_spectators_allowed(lobby_id, payload.spectators_allowed, state.clone())
+        .await
+        .map_err(|e| e.to_response())?;
+
+    Ok(Json(lobby))
+}
+
+/// Set or clear the trust rating at which a private lobby's join requests are
+/// approved without the creator. Creator only.
+pub async fn set_lobby_auto_approve(
+    State(state): State<AppState>,
+    AuthClaims(claims): AuthClaims,
+    Path(lobby_id): Path<Uuid>,
+    ValidJson(payload): ValidJson<AutoApproveRequest>,
+) -> Result<Json<Lobby>, (StatusCode, String)> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| (StatusCode::UNAUTHORIZED, "Invalid token".to_string()))?;
+
+    require_lobby_creator(&state, lobby_id, user_id).await?;
+
+    let lobby = LobbyRepository::new(state.postgres.clone())
+        .set_auto_approve_trust_threshold(lobby_id, payload.trust_threshold, state.clone())
+        .await
+        .map_err(|e| e.to_response())?;
+
+    Ok(Json(lobby))
+}
+
 /// Get lobby details by UUID. Public endpoint returning `Lobby`.
 pub async fn get_lobby(
     State(state): State<AppState>,
@@ -136,6 +423,26 @@ pub async fn get_lobby(
     Ok(Json(lobby))
 }
 
+/// Lobby config, runtime state, players and spectator count in one read.
+/// Public endpoint returning `LobbyFullView`, with the players masked while
+/// the lobby hides them from spectators.
+pub async fn get_lobby_full_view(
+    State(state): State<AppState>,
+    Path(lobby_id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let service = LobbyService::new(state);
+    let view = service
+        .full_snapshot(lobby_id)
+        .await
+        .map_err(|e| e.to_response())?;
+    let body = service
+        .mask_for_public(lobby_id, view.config.anonymize_players, &view)
+        .await
+        .map_err(|e| e.to_response())?;
+
+    Ok(Json(body))
+}
+
 /// Get lobby details by path. Public endpoint returning `Lobby`.
 pub async fn get_lobby_by_path(
     State(state): State<AppState>,
@@ -149,6 +456,116 @@ pub async fn get_lobby_by_path(
 
     Ok(Json(lobby))
 }
+
+/// Words played in a lobby's game, in turn order, with aggregate stats.
+/// Players are masked while the lobby hides them from spectators.
+pub async fn get_lobby_words(
+    State(state): State<AppState>,
+    Path(lobby_id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let lobby = LobbyRepository::new(state.postgres.clone())
+        .find_by_id(lobby_id)
+        .await
+        .map_err(|e| e.to_response())?;
+
+    let words = GameWordRepository::new(state.postgres.clone())
+        .find_by_lobby(lobby_id)
+        .await
+        .map_err(|e| e.to_response())?;
+    let words = seen_by_spectators(&state, words);
+    let stats = GameWordStats::from_words(&words);
+
+    LobbyService::new(state)
+        .mask_for_public(
+            lobby_id,
+            lobby.anonymize_players,
+            &LobbyWordsResponse { words, stats },
+        )
+        .await
+        .map(Json)
+        .map_err(|e| e.to_response())
+}
+
+/// A lobby's game as timed frames, for seeking through its replay.
+/// Players are masked while the lobby hides them from spectators.
+pub async fn get_lobby_replay(
+    State(state): State<AppState>,
+    Path(lobby_id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let (lobby, timeline) = load_replay(&state, lobby_id)
+        .await
+        .map_err(|e| e.to_response())?;
+
+    LobbyService::new(state)
+        .mask_for_public(lobby_id, lobby.anonymize_players, &timeline)
+        .await
+        .map(Json)
+        .map_err(|e| e.to_response())
+}
+
+/// The replayed game state `at_ms` into a lobby's game.
+pub async fn get_lobby_replay_frame(
+    State(state): State<AppState>,
+    Path(lobby_id): Path<Uuid>,
+    Query(query): Query<ReplayFrameQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let (lobby, timeline) = load_replay(&state, lobby_id)
+        .await
+        .map_err(|e| e.to_response())?;
+
+    LobbyService::new(state)
+        .mask_for_public(
+            lobby_id,
+            lobby.anonymize_players,
+            &timeline.state_at(query.at_ms),
+        )
+        .await
+        .map(Json)
+        .map_err(|e| e.to_response())
+}
+
+/// `words` without the ones played within the spectator delay, which the
+/// room hasn't shown its spectators yet
+fn seen_by_spectators(state: &AppState, mut words: Vec<GameWord>) -> Vec<GameWord> {
+    let delay = state.spectator_delay.delay();
+    if let Ok(delay) = chrono::Duration::from_std(delay)
+        && !delay.is_zero()
+    {
+        let cutoff = chrono::Utc::now().naive_utc() - delay;
+        words.retain(|word| word.played_at <= cutoff);
+    }
+    words
+}
+
+/// Words played in a lobby as spectators have seen them, timed from the
+/// game's recorded start, with the game's seed once it has ended
+async fn load_replay(
+    state: &AppState,
+    lobby_id: Uuid,
+) -> Result<(Lobby, ReplayTimeline), AppError> {
+    let lobby_repo = LobbyRepository::new(state.postgres.clone());
+    let lobby = lobby_repo.find_by_id(lobby_id).await?;
+
+    let words = seen_by_spectators(
+        state,
+        GameWordRepository::new(state.postgres.clone())
+            .find_by_lobby(lobby_id)
+            .await?,
+    );
+    let started_at_ms = lobby_repo
+        .find_started_at(lobby_id)
+        .await?
+        .map(|started_at| started_at.and_utc().timestamp_millis());
+
+    let mut timeline = ReplayTimeline::new(&words, started_at_ms);
+    timeline.game_seed = lobby_repo
+        .find_game_seed(lobby_id)
+        .await?
+        .map(|seed| seed.to_string());
+
+    Ok((lobby, timeline))
+}
+
 /// List lobbies for a game with optional pagination. Public endpoint.
 pub async fn list_lobbies_by_game(
     State(state): State<AppState>,
@@ -198,6 +615,73 @@ pub async fn list_my_lobbies(
     }))
 }
 
+/// Longest a join status request is held open, in seconds
+pub const MAX_JOIN_STATUS_WAIT_SECS: u64 = 30;
+
+#[derive(Debug, Deserialize)]
+pub struct JoinStatusQuery {
+    /// Seconds to hold the request while the join request is still pending
+    pub wait: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JoinStatusResponse {
+    pub lobby_id: Uuid,
+    pub state: JoinRequestState,
+}
+
+/// The caller's join request state in a lobby. Requires JWT.
+///
+/// For clients that can't keep a room socket open: with `?wait=N` a pending
+/// request is held up to N seconds (at most `MAX_JOIN_STATUS_WAIT_SECS`)
+/// until the creator decides it, then the new state is returned. A request
+/// that was never made, or was withdrawn, is a 404. Once
+/// `MAX_JOIN_REQUEST_WAITERS` requests are held, further waits get a 503.
+pub async fn get_join_status(
+    State(state): State<AppState>,
+    AuthClaims(claims): AuthClaims,
+    Path(lobby_id): Path<Uuid>,
+    Query(query): Query<JoinStatusQuery>,
+) -> Result<Json<JoinStatusResponse>, (StatusCode, String)> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| (StatusCode::UNAUTHORIZED, "Invalid token".to_string()))?;
+
+    let not_found =
+        || AppError::NotFound(format!("No join request for lobby {}", lobby_id)).to_response();
+    let repo = JoinRequestRepository::new(state.redis.clone());
+    let mut request = repo.get(lobby_id, user_id).await.ok_or_else(not_found)?;
+
+    let wait = query.wait.unwrap_or(0).min(MAX_JOIN_STATUS_WAIT_SECS);
+    if wait > 0 && request.state == JoinRequestState::Pending {
+        let _waiting = state.join_request_changes.try_wait().ok_or_else(|| {
+            AppError::ServiceUnavailable("Too many join requests waited on, retry shortly".into())
+                .to_response()
+        })?;
+        let changes = state
+            .join_request_changes
+            .subscribe(&state.config.redis_url)
+            .await
+            .map_err(|e| e.to_response())?;
+        request = repo
+            .wait_for_change(
+                changes,
+                lobby_id,
+                user_id,
+                &JoinRequestState::Pending,
+                std::time::Duration::from_secs(wait),
+            )
+            .await
+            .map_err(|e| e.to_response())?
+            .ok_or_else(not_found)?;
+    }
+
+    Ok(Json(JoinStatusResponse {
+        lobby_id,
+        state: request.state,
+    }))
+}
+
 /// List all lobbies with pagination. Public endpoint.
 pub async fn get_all_lobbies(
     State(state): State<AppState>,
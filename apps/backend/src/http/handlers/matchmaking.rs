@@ -0,0 +1,153 @@
+// Quick-play matchmaking handlers: join/leave the queue
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    auth::AuthClaims,
+    db::{game::GameRepository, skill_rating::SkillRatingRepository, user::UserRepository},
+    errors::AppError,
+    feature_flags::{Feature, FeatureFlags},
+    matchmaking::{MatchmakingQueue, StakeRange, run_matcher},
+    models::{Lobby, skill_rating::DEFAULT_SKILL_RATING},
+    state::AppState,
+};
+
+// ============================================================================
+// Request/Response Types
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnqueueRequest {
+    pub game_id: Uuid,
+    /// Lowest entry amount the player accepts (defaults to free play)
+    #[serde(default)]
+    pub min_stake: f64,
+    /// Highest entry amount the player accepts (defaults to `min_stake`)
+    pub max_stake: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MatchmakingStatus {
+    Queued,
+    Matched,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchmakingResponse {
+    pub status: MatchmakingStatus,
+    pub lobby: Option<Lobby>,
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// Enter the quick-play queue for a game and run a matching pass.
+///
+/// Returns the lobby immediately if this enqueue completed a match; otherwise the
+/// player stays queued and is notified over websocket with `matchFound`.
+pub async fn enqueue(
+    State(state): State<AppState>,
+    AuthClaims(claims): AuthClaims,
+    Json(payload): Json<EnqueueRequest>,
+) -> Result<Json<MatchmakingResponse>, (StatusCode, String)> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| (StatusCode::UNAUTHORIZED, "Invalid token".to_string()))?;
+
+    FeatureFlags::from_state(&state)
+        .ensure_enabled(Feature::Matchmaking)
+        .await
+        .map_err(|e| e.to_response())?;
+
+    let stake_range = StakeRange::new(
+        payload.min_stake,
+        payload.max_stake.unwrap_or(payload.min_stake),
+    )
+    .map_err(|e| e.to_response())?;
+
+    // Only queue for games a lobby could be created for
+    let game = GameRepository::new(state.postgres.clone())
+        .find_by_id(payload.game_id)
+        .await
+        .map_err(|e| e.to_response())?;
+    if !game.is_active {
+        return Err(
+            AppError::BadRequest(format!("Game '{}' is not active", game.name)).to_response(),
+        );
+    }
+
+    // A paid match seats its players straight into a paid lobby
+    let min_trust = &state.config.paid_lobby_trust;
+    if stake_range.max > 0.0 && min_trust.join > 0.0 {
+        let user = UserRepository::new(state.postgres.clone())
+            .find_by_id(user_id)
+            .await
+            .map_err(|e| e.to_response())?;
+        min_trust
+            .check_join(user.trust_rating)
+            .map_err(|e| e.to_response())?;
+    }
+
+    let rating = SkillRatingRepository::new(state.postgres.clone())
+        .get_ratings(payload.game_id, &[user_id])
+        .await
+        .map_err(|e| e.to_response())?
+        .remove(&user_id)
+        .unwrap_or(DEFAULT_SKILL_RATING);
+
+    let queue = MatchmakingQueue::new(state.redis.clone());
+    queue
+        .enqueue(user_id, payload.game_id, stake_range, rating)
+        .await
+        .map_err(|e| e.to_response())?;
+
+    let matched = run_matcher(&state, payload.game_id)
+        .await
+        .map_err(|e| e.to_response())?;
+
+    // The pass may have matched other (longer-waiting) players only
+    let lobby = matched
+        .filter(|(_, group)| group.players.iter().any(|p| p.user_id == user_id))
+        .map(|(lobby, _)| lobby);
+
+    Ok(Json(match lobby {
+        Some(lobby) => MatchmakingResponse {
+            status: MatchmakingStatus::Matched,
+            lobby: Some(lobby),
+        },
+        None => MatchmakingResponse {
+            status: MatchmakingStatus::Queued,
+            lobby: None,
+        },
+    }))
+}
+
+/// Leave the quick-play queue for a game.
+pub async fn leave_queue(
+    State(state): State<AppState>,
+    AuthClaims(claims): AuthClaims,
+    Path(game_id): Path<Uuid>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| (StatusCode::UNAUTHORIZED, "Invalid token".to_string()))?;
+
+    let removed = MatchmakingQueue::new(state.redis.clone())
+        .dequeue(user_id, game_id)
+        .await
+        .map_err(|e| e.to_response())?;
+
+    if !removed {
+        return Err((StatusCode::NOT_FOUND, "Not in queue".to_string()));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -1,9 +1,15 @@
-// HTTP handlers: user, game, lobby, season, token_info
+// HTTP handlers: user, game, lobby, season, stats, token_info, admin, report, export, version
 
+pub mod admin;
 pub mod contract;
+pub mod export;
 pub mod game;
 pub mod lobby;
+pub mod matchmaking;
 pub mod platform_rating;
+pub mod report;
 pub mod season;
 pub mod stacks;
+pub mod stats;
 pub mod user;
+pub mod version;
@@ -1,8 +1,14 @@
 use axum::{Json, extract::Path, extract::Query, extract::State, http::StatusCode};
+use chrono::NaiveDateTime;
 use serde::Deserialize;
 use uuid::Uuid;
 
-use crate::{auth::AuthClaims, db::platform_rating::PlatformRatingRepository, state::AppState};
+use crate::{
+    auth::AuthClaims,
+    db::platform_rating::PlatformRatingRepository,
+    models::{PlatformRating, PlatformRatingFilter, PlatformRatingSummary},
+    state::AppState,
+};
 
 // Request/Response types
 #[derive(Debug, Deserialize)]
@@ -28,6 +34,8 @@ pub async fn create_rating(
     let user_id = Uuid::parse_str(&claims.sub)
         .map_err(|_| (StatusCode::UNAUTHORIZED, "Invalid token".to_string()))?;
 
+    validate_rating(payload.rating)?;
+
     let repo = PlatformRatingRepository::new(state.postgres.clone());
 
     repo.create_rating(user_id, payload.rating, payload.comment.as_deref())
@@ -41,7 +49,7 @@ pub async fn create_rating(
 pub async fn get_rating(
     State(state): State<AppState>,
     Path(user_id): Path<Uuid>,
-) -> Result<Json<crate::models::PlatformRating>, (StatusCode, String)> {
+) -> Result<Json<PlatformRating>, (StatusCode, String)> {
     let repo = PlatformRatingRepository::new(state.postgres.clone());
 
     match repo
@@ -54,42 +62,92 @@ pub async fn get_rating(
     }
 }
 
-// List ratings (public) with optional `?rating=1..5` filter
+// List ratings (public) with optional filters and pagination:
+// `?rating=` (exact), `?minRating=`/`?maxRating=`, `?since=`/`?until=`
+// (ISO datetimes), `?limit=` (default 20, max 100) and `?offset=`
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ListRatingsQuery {
     pub rating: Option<i16>,
+    pub min_rating: Option<i16>,
+    pub max_rating: Option<i16>,
+    pub since: Option<NaiveDateTime>,
+    pub until: Option<NaiveDateTime>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+impl ListRatingsQuery {
+    fn to_filter(&self) -> Result<PlatformRatingFilter, (StatusCode, String)> {
+        for r in [self.rating, self.min_rating, self.max_rating]
+            .into_iter()
+            .flatten()
+        {
+            validate_rating(r)?;
+        }
+
+        Ok(PlatformRatingFilter {
+            min_rating: self.rating.or(self.min_rating),
+            max_rating: self.rating.or(self.max_rating),
+            since: self.since,
+            until: self.until,
+        })
+    }
+}
+
+fn validate_rating(rating: i16) -> Result<(), (StatusCode, String)> {
+    if !(1..=5).contains(&rating) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "rating must be between 1 and 5".to_string(),
+        ));
+    }
+    Ok(())
 }
 
 pub async fn list_ratings(
     State(state): State<AppState>,
     Query(query): Query<ListRatingsQuery>,
-) -> Result<Json<Vec<crate::models::PlatformRating>>, (StatusCode, String)> {
-    if let Some(r) = query.rating {
-        if !(1..=5).contains(&r) {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                "rating must be between 1 and 5".to_string(),
-            ));
-        }
-    }
+) -> Result<Json<Vec<PlatformRating>>, (StatusCode, String)> {
+    let filter = query.to_filter()?;
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+    let offset = query.offset.unwrap_or(0).max(0);
 
     let repo = PlatformRatingRepository::new(state.postgres.clone());
 
-    let list = repo.list(query.rating).await.map_err(|e| e.to_response())?;
+    let list = repo
+        .list(&filter, limit, offset)
+        .await
+        .map_err(|e| e.to_response())?;
 
     Ok(Json(list))
 }
 
+// Aggregate stats (public) over ratings matching the same filters as the list
+pub async fn get_ratings_summary(
+    State(state): State<AppState>,
+    Query(query): Query<ListRatingsQuery>,
+) -> Result<Json<PlatformRatingSummary>, (StatusCode, String)> {
+    let filter = query.to_filter()?;
+
+    let repo = PlatformRatingRepository::new(state.postgres.clone());
+
+    let summary = repo.summary(&filter).await.map_err(|e| e.to_response())?;
+
+    Ok(Json(summary))
+}
+
 // Update rating for authenticated user
 pub async fn update_rating(
     State(state): State<AppState>,
     AuthClaims(claims): AuthClaims,
     Json(payload): Json<UpdatePlatformRatingRequest>,
-) -> Result<Json<crate::models::PlatformRating>, (StatusCode, String)> {
+) -> Result<Json<PlatformRating>, (StatusCode, String)> {
     let user_id = Uuid::parse_str(&claims.sub)
         .map_err(|_| (StatusCode::UNAUTHORIZED, "Invalid token".to_string()))?;
 
+    validate_rating(payload.rating)?;
+
     let repo = PlatformRatingRepository::new(state.postgres.clone());
 
     let updated = repo
@@ -0,0 +1,167 @@
+// Player reports: filing reports and the moderation queue
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    auth::{
+        extractors::AuthClaims,
+        jwt::revoke_user_tokens,
+        roles::{Moderators, RequireRole},
+    },
+    db::{report::ReportRepository, user::UserRepository},
+    errors::AppError,
+    models::{Report, ReportAction, ReportCategory, ReportStatus},
+    state::AppState,
+    ws::core::manager,
+};
+
+// ============================================================================
+// Request/Response Types
+// ============================================================================
+
+/// Request payload for reporting a player
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateReportRequest {
+    pub reported_user_id: Uuid,
+    /// Lobby the behaviour happened in, if any
+    pub lobby_id: Option<Uuid>,
+    pub category: ReportCategory,
+    pub description: Option<String>,
+}
+
+/// Moderation queue query: `?status=open&limit=20&offset=0`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListReportsQuery {
+    pub status: Option<ReportStatus>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// Request payload for closing a report
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveReportRequest {
+    pub action: ReportAction,
+    pub note: Option<String>,
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// Report another player
+///
+/// The reported player's trust rating takes a small provisional hit until a
+/// moderator reviews the report.
+pub async fn create_report(
+    State(state): State<AppState>,
+    auth: AuthClaims,
+    Json(payload): Json<CreateReportRequest>,
+) -> Result<(StatusCode, Json<Report>), (StatusCode, String)> {
+    let reporter_id = auth.user_id()?;
+
+    if payload.reported_user_id == reporter_id {
+        return Err(AppError::BadRequest("You can't report yourself".into()).to_response());
+    }
+
+    let user_repo = UserRepository::new(state.postgres.clone());
+    if !user_repo
+        .exists_by_id(payload.reported_user_id)
+        .await
+        .map_err(|e| e.to_response())?
+    {
+        return Err(AppError::NotFound("Reported user not found".into()).to_response());
+    }
+
+    let (report, trust_rating) = ReportRepository::new(state.postgres.clone())
+        .create_report(
+            reporter_id,
+            payload.reported_user_id,
+            payload.lobby_id,
+            payload.category,
+            payload.description.as_deref(),
+        )
+        .await
+        .map_err(|e| e.to_response())?;
+    UserRepository::sync_trust_rating(state.redis.clone(), report.reported_user_id, trust_rating);
+
+    Ok((StatusCode::CREATED, Json(report)))
+}
+
+/// List the moderation queue, oldest first (moderators)
+pub async fn list_reports(
+    State(state): State<AppState>,
+    _: RequireRole<Moderators>,
+    Query(query): Query<ListReportsQuery>,
+) -> Result<Json<Vec<Report>>, (StatusCode, String)> {
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    let reports = ReportRepository::new(state.postgres.clone())
+        .list(query.status, limit, offset)
+        .await
+        .map_err(|e| e.to_response())?;
+
+    Ok(Json(reports))
+}
+
+/// Assign a report to the calling moderator (moderators)
+pub async fn assign_report(
+    State(state): State<AppState>,
+    RequireRole(auth, _): RequireRole<Moderators>,
+    Path(report_id): Path<Uuid>,
+) -> Result<Json<Report>, (StatusCode, String)> {
+    let moderator_id = auth.user_id()?;
+
+    let report = ReportRepository::new(state.postgres.clone())
+        .assign(report_id, moderator_id)
+        .await
+        .map_err(|e| e.to_response())?;
+
+    Ok(Json(report))
+}
+
+/// Close a report and apply the decision to the reported player (moderators)
+///
+/// - `dismiss`: the provisional trust penalty is returned
+/// - `warn`: a further trust penalty
+/// - `ban`: the player can no longer sign in, and is signed out everywhere
+pub async fn resolve_report(
+    State(state): State<AppState>,
+    RequireRole(auth, _): RequireRole<Moderators>,
+    Path(report_id): Path<Uuid>,
+    Json(payload): Json<ResolveReportRequest>,
+) -> Result<Json<Report>, (StatusCode, String)> {
+    let moderator_id = auth.user_id()?;
+
+    let (report, trust_rating) = ReportRepository::new(state.postgres.clone())
+        .resolve(
+            report_id,
+            moderator_id,
+            payload.action,
+            payload.note.as_deref(),
+        )
+        .await
+        .map_err(|e| e.to_response())?;
+
+    let reported = report.reported_user_id;
+    if let Some(trust_rating) = trust_rating {
+        UserRepository::sync_trust_rating(state.redis.clone(), reported, trust_rating);
+    }
+    if payload.action == ReportAction::Ban {
+        revoke_user_tokens(&state.redis, reported)
+            .await
+            .map_err(|e| e.to_response())?;
+        manager::unregister_user_connections(&state, reported).await;
+    }
+
+    Ok(Json(report))
+}
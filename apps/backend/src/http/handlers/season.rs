@@ -1,16 +1,25 @@
-// Season management handlers: create/list/get current season
+// Season management handlers: create/list/get current season, rank history
 
 use axum::{
     Json,
     extract::{Path, Query, State},
     http::StatusCode,
 };
+use chrono::NaiveDateTime;
 use serde::Deserialize;
+use uuid::Uuid;
 
 use crate::{
-    auth::extractors::AuthClaims, db::season::SeasonRepository, models::Season, state::AppState,
+    auth::roles::{Admins, RequireRole},
+    db::{rank_snapshot::RankSnapshotRepository, season::SeasonRepository},
+    http::validation::{ValidJson, Validate, ValidationErrors},
+    models::{RankSnapshot, Season},
+    state::AppState,
 };
 
+/// Format of season start and end dates
+const SEASON_DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
 // ============================================================================
 // Request/Response Types
 // ============================================================================
@@ -27,6 +36,33 @@ pub struct CreateSeasonRequest {
     pub start_date: String,
     /// End date in format: "YYYY-MM-DD HH:MM:SS"
     pub end_date: String,
+    /// Allow overlapping an existing season (special events)
+    #[serde(default)]
+    pub allow_overlap: bool,
+}
+
+impl Validate for CreateSeasonRequest {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        errors.check(!self.name.trim().is_empty(), "name", "must not be empty");
+
+        let start = parse_season_date(&self.start_date, "startDate", errors);
+        let end = parse_season_date(&self.end_date, "endDate", errors);
+        if let (Some(start), Some(end)) = (start, end)
+            && let Err(e) = Season::validate_date_range(start, end)
+        {
+            errors.add("endDate", e.to_string());
+        }
+    }
+}
+
+fn parse_season_date(
+    value: &str,
+    field: &str,
+    errors: &mut ValidationErrors,
+) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(value, SEASON_DATE_FORMAT)
+        .map_err(|e| errors.add(field, format!("expected {}: {}", SEASON_DATE_FORMAT, e)))
+        .ok()
 }
 
 /// Request payload for updating a season
@@ -41,18 +77,31 @@ pub struct UpdateSeasonRequest {
     pub start_date: Option<String>,
     /// New end date in format: "YYYY-MM-DD HH:MM:SS" (optional)
     pub end_date: Option<String>,
+    /// Allow the new dates to overlap an existing season (special events)
+    #[serde(default)]
+    pub allow_overlap: bool,
 }
 
-// ============================================================================
-// Helpers
-// ============================================================================
-
-/// Check if the authenticated user is an admin
-fn require_admin(state: &AppState, auth: &AuthClaims) -> Result<(), (StatusCode, String)> {
-    if !state.config.is_admin(auth.wallet_address()) {
-        return Err((StatusCode::FORBIDDEN, "Admin access required".to_string()));
+impl Validate for UpdateSeasonRequest {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        if let Some(name) = &self.name {
+            errors.check(!name.trim().is_empty(), "name", "must not be empty");
+        }
+
+        let start = self
+            .start_date
+            .as_deref()
+            .and_then(|date| parse_season_date(date, "startDate", errors));
+        let end = self
+            .end_date
+            .as_deref()
+            .and_then(|date| parse_season_date(date, "endDate", errors));
+        if let (Some(start), Some(end)) = (start, end)
+            && let Err(e) = Season::validate_date_range(start, end)
+        {
+            errors.add("endDate", e.to_string());
+        }
     }
-    Ok(())
 }
 
 // ============================================================================
@@ -62,19 +111,17 @@ fn require_admin(state: &AppState, auth: &AuthClaims) -> Result<(), (StatusCode,
 /// Create a new competitive season (admin only)
 pub async fn create_season(
     State(state): State<AppState>,
-    auth: AuthClaims,
-    Json(payload): Json<CreateSeasonRequest>,
+    _: RequireRole<Admins>,
+    ValidJson(payload): ValidJson<CreateSeasonRequest>,
 ) -> Result<Json<Season>, (StatusCode, String)> {
-    // Admin check
-    require_admin(&state, &auth)?;
-
-    let repo = SeasonRepository::new(state.postgres.clone());
+    let repo = SeasonRepository::new(state.postgres.clone()).with_cache(state.redis.clone());
     let season = repo
         .create_season(
             &payload.name,
             payload.description.as_deref(),
             &payload.start_date,
             &payload.end_date,
+            payload.allow_overlap,
         )
         .await
         .map_err(|e| e.to_response())?;
@@ -85,39 +132,16 @@ pub async fn create_season(
 /// Update an existing season (admin only)
 pub async fn update_season(
     State(state): State<AppState>,
-    auth: AuthClaims,
+    _: RequireRole<Admins>,
     Path(season_id): Path<i32>,
-    Json(payload): Json<UpdateSeasonRequest>,
+    ValidJson(payload): ValidJson<UpdateSeasonRequest>,
 ) -> Result<Json<Season>, (StatusCode, String)> {
-    // Admin check
-    require_admin(&state, &auth)?;
-
-    let repo = SeasonRepository::new(state.postgres.clone());
-
-    // Parse dates if provided
-    let start_date = payload
-        .start_date
-        .map(|s| {
-            chrono::NaiveDateTime::parse_from_str(&s, "%Y-%m-%d %H:%M:%S").map_err(|e| {
-                (
-                    StatusCode::BAD_REQUEST,
-                    format!("Invalid start_date format: {}", e),
-                )
-            })
-        })
-        .transpose()?;
-
-    let end_date = payload
-        .end_date
-        .map(|s| {
-            chrono::NaiveDateTime::parse_from_str(&s, "%Y-%m-%d %H:%M:%S").map_err(|e| {
-                (
-                    StatusCode::BAD_REQUEST,
-                    format!("Invalid end_date format: {}", e),
-                )
-            })
-        })
-        .transpose()?;
+    let repo = SeasonRepository::new(state.postgres.clone()).with_cache(state.redis.clone());
+
+    // Dates were checked by `Validate`
+    let parse = |date: String| NaiveDateTime::parse_from_str(&date, SEASON_DATE_FORMAT).ok();
+    let start_date = payload.start_date.and_then(parse);
+    let end_date = payload.end_date.and_then(parse);
 
     let season = repo
         .update_season(
@@ -126,6 +150,7 @@ pub async fn update_season(
             payload.description,
             start_date,
             end_date,
+            payload.allow_overlap,
         )
         .await
         .map_err(|e| e.to_response())?;
@@ -137,11 +162,8 @@ pub async fn update_season(
 pub async fn get_current_season(
     State(state): State<AppState>,
 ) -> Result<Json<Season>, (StatusCode, String)> {
-    let repo = SeasonRepository::new(state.postgres);
-    let season = repo
-        .get_current_season()
-        .await
-        .map_err(|e| e.to_response())?;
+    let repo = SeasonRepository::new(state.postgres).with_cache(state.redis);
+    let season = repo.current().await.map_err(|e| e.to_response())?;
 
     Ok(Json(season))
 }
@@ -170,3 +192,27 @@ pub async fn list_seasons(
 
     Ok(Json(seasons))
 }
+
+/// A user's leaderboard rank over a season, oldest snapshot first
+///
+/// Empty when the user wasn't ranked in any snapshot (e.g. joined after the
+/// last one); 404 if the season doesn't exist.
+pub async fn get_rank_history(
+    State(state): State<AppState>,
+    Path((season_id, user_id)): Path<(i32, Uuid)>,
+) -> Result<Json<Vec<RankSnapshot>>, (StatusCode, String)> {
+    let exists = SeasonRepository::new(state.postgres.clone())
+        .exists(season_id)
+        .await
+        .map_err(|e| e.to_response())?;
+    if !exists {
+        return Err((StatusCode::NOT_FOUND, "Season not found".to_string()));
+    }
+
+    let history = RankSnapshotRepository::new(state.postgres)
+        .history(season_id, user_id)
+        .await
+        .map_err(|e| e.to_response())?;
+
+    Ok(Json(history))
+}
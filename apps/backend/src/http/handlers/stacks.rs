@@ -1,4 +1,5 @@
 use crate::{
+    db::token_info::TokenInfoCache,
     errors::AppError,
     models::{
         WalletAddress,
@@ -151,6 +152,8 @@ fn serialize_principal(address: &str) -> Result<String, AppError> {
 }
 
 /// Get token information including price and minimum amount for $10 USD
+///
+/// Mainnet prices are cached in Redis per contract (see `TokenInfoCache`).
 pub async fn get_token_info(
     Path(contract_address_str): Path<String>,
     State(state): State<AppState>,
@@ -170,10 +173,20 @@ pub async fn get_token_info(
         }));
     }
 
-    let url = format!(
-        "https://api.stxtools.io/tokens/{}",
-        contract_address.as_str()
-    );
+    let contract_id = contract_address.as_str().to_string();
+    let info = TokenInfoCache::new(state.redis.clone())
+        .get_or_fetch(contract_address.as_str(), move || {
+            fetch_token_info(contract_id)
+        })
+        .await
+        .map_err(|e| e.to_response())?;
+
+    Ok(Json(info))
+}
+
+/// Fetch token price from StxTools and derive the $10 minimum
+async fn fetch_token_info(contract_id: String) -> Result<TokenInfo, AppError> {
+    let url = format!("https://api.stxtools.io/tokens/{}", contract_id);
 
     let client = Client::new();
     let response = client
@@ -181,24 +194,24 @@ pub async fn get_token_info(
         .header("Accept", "application/json")
         .send()
         .await
-        .map_err(|e| AppError::FetchError(e.to_string()).to_response())?;
+        .map_err(|e| AppError::FetchError(e.to_string()))?;
 
     if !response.status().is_success() {
-        return Err(AppError::NotFound("Token not found".to_string()).to_response());
+        return Err(AppError::NotFound("Token not found".to_string()));
     }
 
     let token_data: StxToolsResponse = response
         .json()
         .await
-        .map_err(|e| AppError::Deserialization(e.to_string()).to_response())?;
+        .map_err(|e| AppError::Deserialization(e.to_string()))?;
 
     let price = token_data.metrics.price_usd;
     let minimum_amount = if price > 0.0 { 10.0 / price } else { 0.0 };
 
-    Ok(Json(TokenInfo {
+    Ok(TokenInfo {
         price,
         minimum_amount,
-    }))
+    })
 }
 
 /// Check if a player has joined a vault contract
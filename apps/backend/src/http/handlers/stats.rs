@@ -0,0 +1,18 @@
+// Platform stats handlers: aggregate totals for community pages
+
+use axum::{Json, extract::State, http::StatusCode};
+
+use crate::{db::stats::StatsRepository, models::StatsOverview, state::AppState};
+
+/// Platform-wide totals, cached for a minute
+pub async fn get_stats_overview(
+    State(state): State<AppState>,
+) -> Result<Json<StatsOverview>, (StatusCode, String)> {
+    let overview = StatsRepository::new(state.postgres.clone())
+        .with_cache(state.redis.clone())
+        .overview()
+        .await
+        .map_err(|e| e.to_response())?;
+
+    Ok(Json(overview))
+}
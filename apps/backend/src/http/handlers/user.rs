@@ -2,36 +2,128 @@
 
 use axum::{
     Json,
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::{StatusCode, header},
     response::{IntoResponse, Response},
 };
 use axum_extra::extract::cookie::{Cookie, SameSite};
+use email_address::EmailAddress;
 use redis::AsyncCommands;
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 use uuid::Uuid;
 
 use crate::{
-    auth::AuthClaims,
-    db::user::UserRepository,
+    auth::{
+        AuthClaims,
+        challenge::{self, AuthChallenge},
+        jwt::revoke_user_tokens,
+        wallet_signature,
+    },
+    db::{
+        lobby_chat::LobbyChatRepository, lobby_refund::LobbyRefundRepository,
+        player_state::PlayerStateRepository, skill_rating::SkillRatingRepository,
+        streak::StreakRepository, user::UserRepository,
+    },
     errors::AppError,
-    models::{User, keys::RedisKey},
+    http::validation::{ValidJson, Validate, ValidationErrors},
+    lobby_service::{ActiveLobby, LobbyService},
+    models::{
+        DELETED_USER_ID, DELETED_USER_NAME, SkillRating, User, UserStats, UserStreaks,
+        WalletAddress, keys::RedisKey,
+    },
     state::AppState,
+    wallet_access::WalletAccess,
 };
 
 // ============================================================================
 // Request/Response Types
 // ============================================================================
 
-/// Request body for creating a new user
+/// Request body for creating a new user: an answered sign-in challenge, so
+/// only the wallet's owner can register it, plus an optional email
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateUserRequest {
-    /// User's Stacks wallet address (principal)
-    pub wallet_address: String,
+    #[serde(flatten)]
+    pub proof: VerifySignatureRequest,
     pub email_address: Option<String>,
 }
 
+impl Validate for CreateUserRequest {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        self.proof.validate(errors);
+        if let Some(email) = &self.email_address
+            && let Err(e) = EmailAddress::from_str(email)
+        {
+            errors.add("emailAddress", e.to_string());
+        }
+    }
+}
+
+/// Query for `GET /api/auth/challenge`
+#[derive(Debug, Deserialize)]
+pub struct AuthChallengeQuery {
+    pub wallet: String,
+}
+
+/// Request body answering a sign-in challenge
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifySignatureRequest {
+    pub wallet_address: String,
+    pub nonce: String,
+    /// The wallet's signature over the challenge message, hex
+    pub signature: String,
+    /// The key the wallet signed with, hex
+    pub public_key: String,
+}
+
+impl Validate for VerifySignatureRequest {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        if let Err(e) = WalletAddress::new(&self.wallet_address) {
+            errors.add("walletAddress", e.to_string());
+        }
+        for (field, value) in [
+            ("nonce", &self.nonce),
+            ("signature", &self.signature),
+            ("publicKey", &self.public_key),
+        ] {
+            if value.is_empty() {
+                errors.add(field, "is required");
+            }
+        }
+    }
+}
+
+/// Public profile: the user plus their per-game skill ratings and streaks
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserProfile {
+    #[serde(flatten)]
+    pub user: User,
+    pub skill_ratings: Vec<SkillRating>,
+    pub streaks: UserStreaks,
+    /// Only present with `?include=stats`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stats: Option<UserStats>,
+}
+
+/// Query parameters for the public profile
+#[derive(Debug, Deserialize)]
+pub struct UserProfileQuery {
+    /// Comma-separated optional sections, e.g. `stats`
+    pub include: Option<String>,
+}
+
+impl UserProfileQuery {
+    fn includes(&self, section: &str) -> bool {
+        self.include
+            .as_deref()
+            .is_some_and(|list| list.split(',').any(|s| s.trim() == section))
+    }
+}
+
 /// Request body for updating username
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -66,24 +158,112 @@ pub struct UpdateProfileRequest {
 
 /// Register a new user and return user details with JWT token.
 ///
-/// Public endpoint. Returns the created User and sets an httpOnly cookie with the auth token.
+/// Public endpoint. The body answers a `GET /api/auth/challenge` like
+/// `POST /api/auth/verify` does. Returns the created User and sets an httpOnly
+/// cookie with the auth token.
 pub async fn create_user(
     State(state): State<AppState>,
-    Json(payload): Json<CreateUserRequest>,
+    ValidJson(payload): ValidJson<CreateUserRequest>,
 ) -> Result<Response, (StatusCode, String)> {
+    // Refused wallets don't get an account created for them either
+    let wallet = WalletAddress::new(&payload.proof.wallet_address)
+        .map_err(|e| AppError::from(e).to_response())?;
+    WalletAccess::from_state(&state)
+        .check(wallet.as_str())
+        .await
+        .map_err(|e| e.to_response())?;
+    prove_wallet(&state, &wallet, &payload.proof)
+        .await
+        .map_err(|e| e.to_response())?;
+
     let repo = UserRepository::new(state.postgres.clone());
 
     let (user, token) = repo
         .create_user(
-            &payload.wallet_address,
+            wallet.as_str(),
             payload.email_address.as_deref(),
-            &state.config.jwt_secret,
+            &state.config.jwt_keys,
         )
         .await
         .map_err(|e| e.to_response())?;
 
-    // Create httpOnly cookie for the token
-    let cookie = Cookie::build(("auth_token", token.clone()))
+    signed_in(&state, &repo, user, token).await
+}
+
+/// Start a signature sign-in: a nonce for the wallet to sign.
+///
+/// Public endpoint. The challenge expires after a few minutes and answers one
+/// `POST /api/auth/verify`.
+pub async fn get_auth_challenge(
+    State(state): State<AppState>,
+    Query(query): Query<AuthChallengeQuery>,
+) -> Result<Json<AuthChallenge>, (StatusCode, String)> {
+    let wallet = WalletAddress::new(&query.wallet).map_err(|e| AppError::from(e).to_response())?;
+    let challenge = challenge::issue(&state.redis, &wallet)
+        .await
+        .map_err(|e| e.to_response())?;
+    Ok(Json(challenge))
+}
+
+/// Finish a signature sign-in, registering the wallet if it's new.
+///
+/// Public endpoint. The nonce is used up whether or not the signature checks
+/// out. Returns the `User` and sets the auth cookie like registration does.
+pub async fn verify_auth_signature(
+    State(state): State<AppState>,
+    ValidJson(payload): ValidJson<VerifySignatureRequest>,
+) -> Result<Response, (StatusCode, String)> {
+    let wallet =
+        WalletAddress::new(&payload.wallet_address).map_err(|e| AppError::from(e).to_response())?;
+    prove_wallet(&state, &wallet, &payload)
+        .await
+        .map_err(|e| e.to_response())?;
+    WalletAccess::from_state(&state)
+        .check(wallet.as_str())
+        .await
+        .map_err(|e| e.to_response())?;
+
+    let repo = UserRepository::new(state.postgres.clone());
+    let (user, token) = repo
+        .create_user(wallet.as_str(), None, &state.config.jwt_keys)
+        .await
+        .map_err(|e| e.to_response())?;
+
+    signed_in(&state, &repo, user, token).await
+}
+
+/// Use up the answered challenge and check its signature is `wallet`'s.
+async fn prove_wallet(
+    state: &AppState,
+    wallet: &WalletAddress,
+    proof: &VerifySignatureRequest,
+) -> Result<(), AppError> {
+    challenge::consume(&state.redis, wallet, &proof.nonce).await?;
+    wallet_signature::verify_message(
+        wallet,
+        &challenge::challenge_message(wallet, &proof.nonce),
+        &proof.signature,
+        &proof.public_key,
+    )
+}
+
+/// Respond to a sign-in with the user and their token as an httpOnly cookie,
+/// unless the account is banned.
+async fn signed_in(
+    state: &AppState,
+    repo: &UserRepository,
+    user: User,
+    token: String,
+) -> Result<Response, (StatusCode, String)> {
+    if repo
+        .is_banned(user.id())
+        .await
+        .map_err(|e| e.to_response())?
+    {
+        return Err(AppError::Forbidden("Account is banned".into()).to_response());
+    }
+
+    let cookie = Cookie::build(("auth_token", token))
         .path("/")
         .max_age(time::Duration::days(7))
         .same_site(SameSite::Strict)
@@ -123,17 +303,41 @@ pub async fn get_me(
     Ok(Json(user))
 }
 
+/// Lobbies the authenticated user is playing in or watching and can go back to.
+///
+/// Each entry carries the lobby, its current status (waiting, starting or in
+/// progress) and whether the user is a `player` or `spectator` there.
+pub async fn get_my_active_lobbies(
+    State(state): State<AppState>,
+    AuthClaims(claims): AuthClaims,
+) -> Result<Json<Vec<ActiveLobby>>, (StatusCode, String)> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Unauthorized("Invalid token".into()).to_response())?;
+
+    let active = LobbyService::new(state)
+        .active_for_user(user_id)
+        .await
+        .map_err(|e| e.to_response())?;
+
+    Ok(Json(active))
+}
+
 /// Get a user's public profile by UUID, wallet address, or username.
 ///
-/// Public endpoint returning `User` or `404` if not found.
+/// Public endpoint returning the user's profile (`User` fields plus skill
+/// ratings) or `404` if not found.
 /// Accepts any of:
 /// - UUID (e.g., "550e8400-e29b-41d4-a716-446655440000")
 /// - Wallet address (e.g., "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7")
 /// - Username (case-insensitive)
+///
+/// `?include=stats` adds games played, win rate, season points and an avatar
+/// seed; they cost an extra query, so they are left out by default.
 pub async fn get_user(
     State(state): State<AppState>,
     Path(identifier): Path<String>,
-) -> Result<Json<User>, (StatusCode, String)> {
+    Query(query): Query<UserProfileQuery>,
+) -> Result<Json<UserProfile>, (StatusCode, String)> {
     let repo = UserRepository::new(state.postgres.clone());
 
     let user = repo
@@ -141,7 +345,32 @@ pub async fn get_user(
         .await
         .map_err(|e| e.to_response())?;
 
-    Ok(Json(user))
+    let skill_ratings = SkillRatingRepository::new(state.postgres.clone())
+        .find_by_user(user.id)
+        .await
+        .map_err(|e| e.to_response())?;
+
+    let streaks = StreakRepository::new(state.redis.clone())
+        .get(user.id, state.config.streak_day_offset_secs)
+        .await
+        .map_err(|e| e.to_response())?;
+
+    let stats = if query.includes("stats") {
+        Some(
+            repo.find_stats(user.id)
+                .await
+                .map_err(|e| e.to_response())?,
+        )
+    } else {
+        None
+    };
+
+    Ok(Json(UserProfile {
+        user,
+        skill_ratings,
+        streaks,
+        stats,
+    }))
 }
 
 // ============================================================================
@@ -264,7 +493,16 @@ pub async fn logout(
         )
     })?;
 
-    // Create cookie with max-age=0 to clear it
+    tracing::info!(
+        "User {} logged out successfully",
+        claims.user_id().unwrap_or_default()
+    );
+
+    Ok(clear_auth_cookie(&state))
+}
+
+/// `204 No Content` response that clears the auth cookie (max-age=0).
+fn clear_auth_cookie(state: &AppState) -> Response {
     let cookie = Cookie::build(("auth_token", ""))
         .path("/")
         .max_age(time::Duration::seconds(0))
@@ -278,10 +516,72 @@ pub async fn logout(
         .headers_mut()
         .insert(header::SET_COOKIE, cookie.to_string().parse().unwrap());
 
-    tracing::info!(
-        "User {} logged out successfully",
-        claims.user_id().unwrap_or_default()
-    );
+    response
+}
 
-    Ok(response)
+// ============================================================================
+// Account Deletion
+// ============================================================================
+
+/// Delete the authenticated user's account.
+///
+/// The user row is kept but its wallet, email, username and display name are
+/// replaced with placeholders, so season points and past standings still
+/// resolve (to "[deleted user]"). Lobby player state is anonymized, chat
+/// messages are reattributed to the placeholder author and every session the
+/// user holds is revoked. Returns `204` and clears the auth cookie.
+///
+/// Sessions are revoked before anything else, so no request made with them
+/// can race the deletion. Deleting an already deleted account (a request that
+/// was in flight alongside the first) redoes the cleanup and also returns `204`.
+pub async fn delete_me(
+    State(state): State<AppState>,
+    auth: AuthClaims,
+) -> Result<Response, (StatusCode, String)> {
+    let user_id = auth.user_id()?;
+    let placeholder_wallet = User::anonymized_wallet(user_id);
+
+    revoke_user_tokens(&state.redis, user_id)
+        .await
+        .map_err(|e| e.to_response())?;
+
+    // Postgres next, in one transaction: nothing is anonymized unless all of it is
+    let mut transaction = state.postgres.begin().await.map_err(|e| {
+        AppError::DatabaseError(format!("Failed to begin transaction: {}", e)).to_response()
+    })?;
+
+    let anonymized = UserRepository::anonymize_user(&mut transaction, user_id)
+        .await
+        .map_err(|e| e.to_response())?;
+    LobbyRefundRepository::anonymize_user(&mut transaction, user_id, placeholder_wallet.as_ref())
+        .await
+        .map_err(|e| e.to_response())?;
+
+    transaction.commit().await.map_err(|e| {
+        AppError::DatabaseError(format!("Failed to commit account deletion: {}", e)).to_response()
+    })?;
+
+    let player_repo = PlayerStateRepository::new(state.redis.clone());
+    let lobby_ids = player_repo
+        .get_lobby_ids_for_user(user_id)
+        .await
+        .map_err(|e| e.to_response())?;
+    player_repo
+        .anonymize_user_across_lobbies(user_id, placeholder_wallet.as_ref(), DELETED_USER_NAME)
+        .await
+        .map_err(|e| e.to_response())?;
+
+    let chat_repo = LobbyChatRepository::new(state.redis.clone());
+    for lobby_id in lobby_ids {
+        chat_repo
+            .reattribute_messages(lobby_id, user_id, DELETED_USER_ID)
+            .await
+            .map_err(|e| AppError::RedisError(e).to_response())?;
+    }
+
+    if anonymized {
+        tracing::warn!("User {} deleted their account", user_id);
+    }
+
+    Ok(clear_auth_cookie(&state))
 }
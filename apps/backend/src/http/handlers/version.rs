@@ -0,0 +1,41 @@
+// Version HTTP handler: what this deployment runs, for client feature gates and deploy checks
+
+use axum::{Json, extract::State};
+use serde::Serialize;
+
+use crate::{
+    games::{RegisteredGame, registered_games},
+    state::AppState,
+    ws::core::ProtocolVersion,
+};
+
+/// Build and capability info for this server
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionInfo {
+    pub version: &'static str,
+    /// Commit the build came from, when the deploy provides it
+    pub git_sha: Option<String>,
+    /// WebSocket protocol versions accepted, oldest first
+    pub ws_protocols: &'static [ProtocolVersion],
+    pub games: Vec<RegisteredGame>,
+}
+
+/// The commit named by `GIT_SHA`, at runtime or else at build time
+fn git_sha() -> Option<String> {
+    std::env::var("GIT_SHA")
+        .ok()
+        .or_else(|| option_env!("GIT_SHA").map(str::to_string))
+        .map(|sha| sha.trim().to_string())
+        .filter(|sha| !sha.is_empty())
+}
+
+/// Report the deployed version, WebSocket protocols, and registered games
+pub async fn get_version(State(state): State<AppState>) -> Json<VersionInfo> {
+    Json(VersionInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        git_sha: git_sha(),
+        ws_protocols: ProtocolVersion::SUPPORTED,
+        games: registered_games(&state.config),
+    })
+}
@@ -1,5 +1,6 @@
-// HTTP layer: handlers and route composition
+// HTTP layer: handlers, route composition and request validation
 pub mod handlers;
 pub mod routes;
+pub mod validation;
 
 pub use routes::create_http_routes;
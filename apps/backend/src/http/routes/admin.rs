@@ -1,20 +1,51 @@
 use axum::{
     Router,
     middleware::from_fn_with_state,
-    routing::{post, put},
+    routing::{get, post, put},
 };
 
 use crate::{
-    http::handlers::season::{create_season, update_season},
+    http::handlers::{
+        admin::{
+            add_wallet_access, announce, get_maintenance, get_wallet_access, list_feature_flags,
+            lobby_status_summary, remove_wallet_access, reprocess_lobby_points, seed,
+            set_allowlist_only, set_feature_flag, set_maintenance, set_user_role,
+        },
+        report::{assign_report, list_reports, resolve_report},
+        season::{create_season, update_season},
+    },
     middleware::{AuthRateLimit, rate_limit_with_state},
     state::AppState,
 };
 
-/// Admin routes - all require authentication + admin wallet
+/// Admin and moderation routes - each requires a role (see `auth::roles`)
 pub fn routes(state_for_layer: AppState) -> Router<AppState> {
     Router::new()
         .route("/season", post(create_season))
         .route("/season/{season_id}", put(update_season))
+        .route("/admin/seed", post(seed))
+        .route("/admin/announce", post(announce))
+        .route(
+            "/admin/maintenance",
+            get(get_maintenance).put(set_maintenance),
+        )
+        .route("/admin/features", get(list_feature_flags))
+        .route("/admin/features/{feature}", put(set_feature_flag))
+        .route("/admin/wallet-access", get(get_wallet_access))
+        .route("/admin/wallet-access/mode", put(set_allowlist_only))
+        .route(
+            "/admin/wallet-access/{list}/{wallet}",
+            put(add_wallet_access).delete(remove_wallet_access),
+        )
+        .route("/admin/lobbies/summary", get(lobby_status_summary))
+        .route(
+            "/admin/lobbies/{lobby_id}/reprocess-points",
+            post(reprocess_lobby_points),
+        )
+        .route("/admin/users/{user_id}/role", put(set_user_role))
+        .route("/admin/reports", get(list_reports))
+        .route("/admin/reports/{report_id}/assign", post(assign_report))
+        .route("/admin/reports/{report_id}/resolve", post(resolve_report))
         .layer(from_fn_with_state(
             state_for_layer.clone(),
             rate_limit_with_state::<AuthRateLimit>,
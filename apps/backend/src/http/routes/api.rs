@@ -1,20 +1,27 @@
 // Read-focused API routes mounted under `/api` (public/read-only)
 
-use axum::{Router, middleware::from_fn_with_state, routing::get};
+use axum::{
+    Router,
+    middleware::{from_fn, from_fn_with_state},
+    routing::get,
+};
 
 use crate::{
     http::handlers::{
-        contract::{get_contract, get_sponsored_contract},
         game::{get_game, get_game_by_path, get_games_by_creator, list_games},
         lobby::{
-            get_all_lobbies, get_lobby, get_lobby_by_path, list_lobbies_by_game, list_my_lobbies,
+            get_all_lobbies, get_join_status, get_lobby, get_lobby_by_path, get_lobby_full_view,
+            get_lobby_replay, get_lobby_replay_frame, get_lobby_words, list_lobbies_by_game,
+            list_my_lobbies,
         },
-        platform_rating::{get_rating, list_ratings},
-        season::{get_current_season, list_seasons},
+        platform_rating::{get_rating, get_ratings_summary, list_ratings},
+        season::{get_current_season, get_rank_history, list_seasons},
         stacks::{get_balance, get_token_info},
+        stats::get_stats_overview,
         user::get_user,
+        version::get_version,
     },
-    middleware::{ApiRateLimit, rate_limit_with_state},
+    middleware::{ApiRateLimit, conditional_get, rate_limit_with_state},
     state::AppState,
 };
 
@@ -23,20 +30,43 @@ pub fn routes(state_for_layer: AppState) -> Router<AppState> {
         .route("/user/{user_id}", get(get_user))
         .route("/platform-rating", get(list_ratings))
         .route("/platform-rating/{user_id}", get(get_rating))
-        .route("/games", get(list_games))
-        .route("/game/{game_id}", get(get_game))
-        .route("/game/by-path/{path}", get(get_game_by_path))
+        .route("/platform/ratings", get(list_ratings))
+        .route("/platform/ratings/summary", get(get_ratings_summary))
+        .route("/version", get(get_version))
+        .route("/stats/overview", get(get_stats_overview))
+        .route("/games", get(list_games).layer(from_fn(conditional_get)))
+        .route(
+            "/game/{game_id}",
+            get(get_game).layer(from_fn(conditional_get)),
+        )
+        .route(
+            "/game/by-path/{path}",
+            get(get_game_by_path).layer(from_fn(conditional_get)),
+        )
         .route("/game/by-creator/{creator_id}", get(get_games_by_creator))
         .route("/game/{game_id}/lobbies", get(list_lobbies_by_game))
         .route("/lobbies", get(get_all_lobbies))
+        .route("/lobbies/{lobby_id}", get(get_lobby_full_view))
+        .route("/lobbies/{lobby_id}/replay", get(get_lobby_replay))
+        .route("/lobbies/{lobby_id}/join-status", get(get_join_status))
+        .route(
+            "/lobbies/{lobby_id}/replay/frame",
+            get(get_lobby_replay_frame),
+        )
         .route("/lobby/{lobby_id}", get(get_lobby))
+        .route("/lobby/{lobby_id}/words", get(get_lobby_words))
         .route("/lobby/by-path/{path}", get(get_lobby_by_path))
         .route("/lobby/my", get(list_my_lobbies))
-        .route("/season/current", get(get_current_season))
-        .route("/season", get(list_seasons))
+        .route(
+            "/season/current",
+            get(get_current_season).layer(from_fn(conditional_get)),
+        )
+        .route("/season", get(list_seasons).layer(from_fn(conditional_get)))
+        .route(
+            "/seasons/{season_id}/users/{user_id}/rank-history",
+            get(get_rank_history),
+        )
         .route("/token/{contract_address}", get(get_token_info))
-        .route("/contract", get(get_contract))
-        .route("/sponsored-contract", get(get_sponsored_contract))
         .route("/balance/{wallet_address}", get(get_balance))
         .layer(from_fn_with_state(
             state_for_layer.clone(),
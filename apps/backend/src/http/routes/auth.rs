@@ -7,10 +7,19 @@ use axum::{
 
 use crate::{
     http::handlers::{
+        export::export_me,
         game::create_game,
-        lobby::create_lobby,
+        lobby::{
+            create_lobby, create_lobby_invite, revoke_lobby_invite, set_lobby_auto_approve,
+            set_lobby_spectators,
+        },
+        matchmaking::{enqueue, leave_queue},
         platform_rating::{create_rating, delete_rating, update_rating},
-        user::{get_me, logout, update_display_name, update_profile, update_username},
+        report::create_report,
+        user::{
+            delete_me, get_me, get_my_active_lobbies, logout, update_display_name, update_profile,
+            update_username,
+        },
     },
     middleware::{AuthRateLimit, rate_limit_with_state},
     state::AppState,
@@ -20,6 +29,9 @@ pub fn routes(state_for_layer: AppState) -> Router<AppState> {
     Router::new()
         .route("/me", get(get_me))
         .route("/user/profile", patch(update_profile))
+        .route("/users/me", delete(delete_me))
+        .route("/users/me/export", get(export_me))
+        .route("/users/me/active", get(get_my_active_lobbies))
         .route("/platform-rating", post(create_rating))
         .route("/platform-rating", patch(update_rating))
         .route("/platform-rating", delete(delete_rating))
@@ -27,6 +39,19 @@ pub fn routes(state_for_layer: AppState) -> Router<AppState> {
         .route("/user/display-name", patch(update_display_name))
         .route("/game", post(create_game))
         .route("/lobby", post(create_lobby))
+        .route("/lobby/{lobby_id}/invite", post(create_lobby_invite))
+        .route(
+            "/lobby/{lobby_id}/invite/{invite_id}",
+            delete(revoke_lobby_invite),
+        )
+        .route("/lobby/{lobby_id}/spectators", patch(set_lobby_spectators))
+        .route(
+            "/lobby/{lobby_id}/auto-approve",
+            patch(set_lobby_auto_approve),
+        )
+        .route("/matchmaking", post(enqueue))
+        .route("/matchmaking/{game_id}", delete(leave_queue))
+        .route("/report", post(create_report))
         .route("/logout", post(logout))
         .layer(from_fn_with_state(
             state_for_layer.clone(),
@@ -1,6 +1,7 @@
 // Main HTTP routing: compose and mount sub-routers under `/api`.
+use crate::middleware::abuse_guard;
 use crate::state::AppState;
-use axum::Router;
+use axum::{Router, middleware::from_fn_with_state};
 
 pub mod admin;
 pub mod api;
@@ -33,7 +34,9 @@ pub fn create_http_routes(state: AppState) -> Router {
                 .merge(api_router)
                 .merge(auth_router)
                 .merge(strict_router)
-                .merge(admin_router),
+                .merge(admin_router)
+                // Outermost, so it sees 401s and 429s from every router above
+                .layer(from_fn_with_state(state_for_layer.clone(), abuse_guard)),
         )
         .with_state(state)
 }
@@ -1,5 +1,5 @@
 use crate::state::AppState;
-use axum::{Json, Router, routing::get};
+use axum::{Json, Router, extract::State, routing::get};
 use serde_json::{Value, json};
 
 /// Public routes - no authentication or rate limiting required
@@ -13,9 +13,15 @@ pub fn routes() -> Router<AppState> {
 
 /// Health check endpoint
 ///
-/// Returns 200 OK if the service is running.
-async fn health_handler() -> &'static str {
-    "OK"
+/// Returns 200 while the service is running, with the Redis health gauge so
+/// monitoring can alert on a degraded or unavailable connection, and the
+/// number of slow database queries seen since startup.
+async fn health_handler(State(state): State<AppState>) -> Json<Value> {
+    Json(json!({
+        "status": "OK",
+        "redis": state.redis.health(),
+        "slowQueries": crate::db::timing::slow_query_count(),
+    }))
 }
 
 /// Root endpoint with API information
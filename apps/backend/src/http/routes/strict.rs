@@ -1,15 +1,28 @@
-// Strict routes: sensitive write endpoints wrapped by strict limiter
+// Strict routes: sensitive writes and contract calls wrapped by strict limiter
 
 use axum::middleware::from_fn_with_state;
-use axum::{routing::post, Router};
+use axum::{
+    Router,
+    routing::{get, post},
+};
 
-use crate::middleware::{rate_limit_with_state, StrictRateLimit};
-use crate::{http::handlers::user::create_user, state::AppState};
+use crate::middleware::{StrictRateLimit, rate_limit_with_state};
+use crate::{
+    http::handlers::{
+        contract::{get_contract, get_sponsored_contract},
+        user::{create_user, get_auth_challenge, verify_auth_signature},
+    },
+    state::AppState,
+};
 
 /// Routes that should be subject to the strict limiter.
 pub fn routes(state_for_layer: AppState) -> Router<AppState> {
     Router::new()
         .route("/user", post(create_user))
+        .route("/auth/challenge", get(get_auth_challenge))
+        .route("/auth/verify", post(verify_auth_signature))
+        .route("/contract", get(get_contract))
+        .route("/sponsored-contract", get(get_sponsored_contract))
         .layer(from_fn_with_state(
             state_for_layer.clone(),
             rate_limit_with_state::<StrictRateLimit>,
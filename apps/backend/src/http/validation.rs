@@ -0,0 +1,111 @@
+// Request validation: check a JSON body and report every bad field at once
+//
+// Handlers that take `ValidJson<T>` receive a body that deserialized into `T`
+// and passed `T::validate`. Anything else is rejected with 400 and
+//
+//     { "errors": [{ "field": "entryAmount", "error": "must be >= 0" }, ...] }
+//
+// listing every failed check, not just the first. Fields are named as they
+// appear in the JSON. A body that doesn't deserialize at all can only report
+// the first problem serde hit. A request axum refuses before reading it as
+// JSON (no JSON content type, body too large, ...) keeps axum's status, with
+// the same body. Checks that need the database (game exists, token allowed,
+// ...) stay in the repositories.
+
+use axum::{
+    Json,
+    extract::{FromRequest, Request},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde::{Serialize, de::DeserializeOwned};
+
+/// One failed check on a request field
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub error: String,
+}
+
+/// Every failed check on a request body.
+#[derive(Debug, Default, Serialize)]
+pub struct ValidationErrors {
+    pub errors: Vec<FieldError>,
+}
+
+impl ValidationErrors {
+    /// Errors holding a single failure
+    pub fn single(field: impl Into<String>, error: impl Into<String>) -> Self {
+        let mut errors = Self::default();
+        errors.add(field, error);
+        errors
+    }
+
+    pub fn add(&mut self, field: impl Into<String>, error: impl Into<String>) {
+        self.errors.push(FieldError {
+            field: field.into(),
+            error: error.into(),
+        });
+    }
+
+    /// Record `error` against `field` unless `ok`.
+    pub fn check(&mut self, ok: bool, field: &str, error: impl Into<String>) {
+        if !ok {
+            self.add(field, error);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+impl IntoResponse for ValidationErrors {
+    fn into_response(self) -> Response {
+        (StatusCode::BAD_REQUEST, Json(self)).into_response()
+    }
+}
+
+/// A request body that can check its own fields.
+pub trait Validate {
+    /// Record every problem with the body in `errors`.
+    fn validate(&self, errors: &mut ValidationErrors);
+}
+
+/// JSON body extractor that runs `Validate` before the handler sees it.
+pub struct ValidJson<T>(pub T);
+
+impl<S, T> FromRequest<S> for ValidJson<T>
+where
+    S: Send + Sync,
+    T: DeserializeOwned + Validate,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(body) = Json::<serde_json::Value>::from_request(req, state)
+            .await
+            .map_err(|e| {
+                (
+                    e.status(),
+                    Json(ValidationErrors::single("", e.body_text())),
+                )
+                    .into_response()
+            })?;
+
+        let value: T = serde_path_to_error::deserialize(body).map_err(|e| {
+            let field = match e.path().to_string() {
+                root if root == "." => String::new(),
+                path => path,
+            };
+            ValidationErrors::single(field, e.into_inner().to_string()).into_response()
+        })?;
+
+        let mut errors = ValidationErrors::default();
+        value.validate(&mut errors);
+        if !errors.is_empty() {
+            return Err(errors.into_response());
+        }
+        Ok(ValidJson(value))
+    }
+}
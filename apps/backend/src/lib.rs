@@ -1,14 +1,30 @@
 // Stacks Wars backend
 
+pub mod abuse;
+pub mod announcements;
 pub mod auth;
+pub mod badges;
+pub mod chat_retention;
+pub mod contract_calls;
+pub mod contract_events;
 pub mod db;
 pub mod errors;
+pub mod feature_flags;
 pub mod games;
 pub mod http;
+pub mod lobby_service;
+pub mod maintenance;
+pub mod matchmaking;
 mod middleware;
 pub use middleware::cors_layer;
 pub mod models;
+pub mod rank_snapshots;
+pub mod reaper;
+pub mod redis_client;
+pub mod redis_lock;
+pub mod season_rollover;
 pub mod state;
+pub mod wallet_access;
 pub mod ws;
 
 use axum::Router;
@@ -35,12 +51,20 @@ pub async fn start_server() {
 
     tracing::info!("PostgreSQL and Redis connection pools established");
 
+    reaper::spawn_lobby_reaper(state.clone());
+    season_rollover::spawn_season_rollover(state.clone());
+    rank_snapshots::spawn_rank_snapshots(state.clone());
+    chat_retention::spawn_chat_retention(state.clone());
+    contract_events::spawn_contract_indexer(state.clone());
+    matchmaking::spawn_queue_sweeper(state.clone());
+    state.spectator_delay.spawn_flusher();
+
     // Build HTTP router
     let app = Router::new()
         .merge(http::create_http_routes(state.clone()))
         // WebSocket routes (lobbies, games, bots, real-time endpoints)
         .merge(ws::create_ws_routes(state.clone()))
-        .layer(cors_layer())
+        .layer(cors_layer(&state.config.cors))
         .fallback(|| async { "404 Not Found" });
 
     let port = std::env::var("PORT")
@@ -0,0 +1,455 @@
+// Lobby snapshots: a lobby's Postgres config and Redis runtime read together
+//
+// Config and runtime live in different stores, so a status transition can land
+// between the two reads. `full_snapshot` compares the status both stores report
+// and reads again when they disagree, settling for the last read after
+// SNAPSHOT_ATTEMPTS. Missing runtime keys are not an error: a lobby whose Redis
+// state expired or was never written gets defaults derived from its config.
+//
+// The status summary counts lobbies in Postgres, then recounts in-flight ones
+// whose Redis status has moved on from their row.
+
+use std::sync::Arc;
+
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    db::{
+        lobby::LobbyRepository, lobby_state::LobbyStateRepository,
+        player_state::PlayerStateRepository, spectator::SpectatorRepository,
+    },
+    errors::AppError,
+    models::{Lobby, LobbyExtended, LobbyState, LobbyStatus, LobbyStatusCount, PlayerState},
+    state::AppState,
+    ws::core::pseudonyms::Pseudonyms,
+};
+
+/// Reads of both stores before a snapshot settles for disagreeing statuses
+const SNAPSHOT_ATTEMPTS: usize = 3;
+
+/// Everything known about a lobby: config, runtime, roster and audience.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LobbyFullView {
+    pub config: LobbyExtended,
+    pub runtime: LobbyState,
+    pub players: Vec<PlayerState>,
+    pub spectator_count: usize,
+}
+
+impl LobbyFullView {
+    /// Merge a lobby row with whatever runtime Redis returned.
+    ///
+    /// Without runtime state the lobby is reported as its config describes it,
+    /// with the players that were found.
+    pub fn assemble(
+        lobby: Lobby,
+        runtime: Option<LobbyState>,
+        players: Vec<PlayerState>,
+        spectator_count: usize,
+    ) -> Self {
+        let runtime = runtime.unwrap_or_else(|| {
+            let mut runtime = LobbyState::new(lobby.id);
+            runtime.status = lobby.status;
+            runtime.participant_count = players.len();
+            runtime.created_at = lobby.created_at.and_utc().timestamp();
+            runtime.updated_at = lobby.updated_at.and_utc().timestamp();
+            runtime
+        });
+
+        Self {
+            config: LobbyExtended::from_parts(lobby, runtime.clone()),
+            runtime,
+            players,
+            spectator_count,
+        }
+    }
+}
+
+/// How a user takes part in a lobby they can go back to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ActiveRole {
+    Player,
+    Spectator,
+}
+
+/// A lobby the user is in or watching that hasn't finished yet.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveLobby {
+    pub lobby: LobbyExtended,
+    /// Runtime status, which leads the config row during transitions
+    pub status: LobbyStatus,
+    pub role: ActiveRole,
+}
+
+/// Lobby counts by status and game for dashboards.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LobbyStatusSummary {
+    pub counts: Vec<LobbyStatusCount>,
+    /// Lobbies counted under their Redis status instead of their row's
+    pub corrected: usize,
+}
+
+impl LobbyStatusSummary {
+    /// Move each `(game_id, from, to)` lobby out of its Postgres count and into
+    /// its runtime one.
+    pub fn corrected(
+        mut counts: Vec<LobbyStatusCount>,
+        moves: &[(Uuid, LobbyStatus, LobbyStatus)],
+    ) -> Self {
+        let mut corrected = 0;
+        for &(game_id, from, to) in moves.iter().filter(|(_, from, to)| from != to) {
+            let Some(source) = counts
+                .iter_mut()
+                .find(|c| c.game_id == game_id && c.status == from && c.count > 0)
+            else {
+                continue;
+            };
+            source.count -= 1;
+
+            match counts
+                .iter_mut()
+                .find(|c| c.game_id == game_id && c.status == to)
+            {
+                Some(target) => target.count += 1,
+                None => counts.push(LobbyStatusCount {
+                    status: to,
+                    game_id,
+                    count: 1,
+                }),
+            }
+            corrected += 1;
+        }
+
+        counts.retain(|c| c.count > 0);
+        counts.sort_by_key(|c| (c.game_id, c.status as u8));
+        Self { counts, corrected }
+    }
+}
+
+/// Lobby reads that span Postgres, Redis and live connections.
+pub struct LobbyService {
+    state: AppState,
+}
+
+impl LobbyService {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+
+    /// Config, runtime, players and spectator count for a lobby in one call.
+    ///
+    /// Fails only when the lobby row is missing or Postgres is unreachable.
+    pub async fn full_snapshot(&self, lobby_id: Uuid) -> Result<LobbyFullView, AppError> {
+        let lobby_repo = LobbyRepository::new(self.state.postgres.clone());
+
+        let mut attempt = 1;
+        loop {
+            let lobby = lobby_repo.find_by_id(lobby_id).await?;
+            let (runtime, players) = self.read_runtime(lobby_id).await;
+
+            let settled = runtime
+                .as_ref()
+                .is_none_or(|runtime| runtime.status == lobby.status);
+            if settled || attempt == SNAPSHOT_ATTEMPTS {
+                let spectator_count = self.spectator_count(lobby_id, &players).await;
+                return Ok(LobbyFullView::assemble(
+                    lobby,
+                    runtime,
+                    players,
+                    spectator_count,
+                ));
+            }
+            attempt += 1;
+        }
+    }
+
+    /// `body` as public reads show it: with the lobby's players masked while
+    /// its game hides them from spectators.
+    ///
+    /// Uses the room's pseudonyms, naming the players first if no room
+    /// connection has yet.
+    pub async fn mask_for_public<T: Serialize>(
+        &self,
+        lobby_id: Uuid,
+        anonymize_players: bool,
+        body: &T,
+    ) -> Result<serde_json::Value, AppError> {
+        let mut value = serde_json::to_value(body)
+            .map_err(|e| AppError::Serialization(format!("Failed to serialize lobby: {}", e)))?;
+        if anonymize_players && let Some(table) = self.public_pseudonyms(lobby_id).await? {
+            table.mask(&mut value);
+        }
+        Ok(value)
+    }
+
+    async fn public_pseudonyms(&self, lobby_id: Uuid) -> Result<Option<Arc<Pseudonyms>>, AppError> {
+        let pseudonyms = &self.state.room_pseudonyms;
+        if let Some(table) = pseudonyms.load(&self.state.redis, lobby_id).await? {
+            return Ok(Some(table));
+        }
+
+        // Once the game is over (or its state gone) the players are revealed
+        let (runtime, players) = self.read_runtime(lobby_id).await;
+        let hiding = runtime.is_some_and(|runtime| {
+            matches!(
+                runtime.status,
+                LobbyStatus::Waiting | LobbyStatus::Starting | LobbyStatus::InProgress
+            )
+        });
+        if !hiding || players.is_empty() {
+            return Ok(None);
+        }
+        pseudonyms
+            .track(&self.state.redis, lobby_id, &players)
+            .await
+            .map(Some)
+    }
+
+    /// Lobbies the user can resume: ones they have player state in, then ones
+    /// they are spectating, skipping lobbies that have finished or been
+    /// cancelled. A user who both plays and watches a lobby is listed as a player.
+    pub async fn active_for_user(&self, user_id: Uuid) -> Result<Vec<ActiveLobby>, AppError> {
+        let player_repo = PlayerStateRepository::new(self.state.redis.clone());
+        let spectator_repo = SpectatorRepository::new(self.state.redis.clone());
+        let lobby_repo = LobbyRepository::new(self.state.postgres.clone());
+
+        let (playing, watching) = tokio::join!(
+            player_repo.get_lobby_ids_for_user(user_id),
+            spectator_repo.get_lobby_ids_for_user(user_id),
+        );
+        let mut roles: Vec<(Uuid, ActiveRole)> = playing?
+            .into_iter()
+            .map(|id| (id, ActiveRole::Player))
+            .collect();
+        for id in watching? {
+            if !roles.iter().any(|(lobby_id, _)| *lobby_id == id) {
+                roles.push((id, ActiveRole::Spectator));
+            }
+        }
+
+        let mut active = Vec::new();
+        for (lobby_id, role) in roles {
+            let lobby = match lobby_repo.find_by_id(lobby_id).await {
+                Ok(lobby) => lobby,
+                // Stale Redis keys for a deleted lobby
+                Err(AppError::NotFound(_)) => continue,
+                Err(e) => return Err(e),
+            };
+            let (runtime, players) = self.read_runtime(lobby_id).await;
+            let view = LobbyFullView::assemble(lobby, runtime, players, 0);
+
+            let status = view.runtime.status;
+            if matches!(status, LobbyStatus::Finished | LobbyStatus::Cancelled) {
+                continue;
+            }
+            active.push(ActiveLobby {
+                lobby: view.config,
+                status,
+                role,
+            });
+        }
+
+        active.sort_by_key(|a| std::cmp::Reverse(a.lobby.updated_at));
+        Ok(active)
+    }
+
+    /// Lobby counts per status and game, with in-flight lobbies counted by
+    /// their live Redis status.
+    pub async fn status_summary(&self) -> Result<LobbyStatusSummary, AppError> {
+        let lobby_repo = LobbyRepository::new(self.state.postgres.clone());
+        let lobby_state_repo = LobbyStateRepository::new(self.state.redis.clone());
+
+        let counts = lobby_repo.status_summary().await?;
+        let in_flight = lobby_repo.find_in_flight_statuses().await?;
+
+        let ids: Vec<Uuid> = in_flight.iter().map(|(id, _, _)| *id).collect();
+        let runtime = lobby_state_repo
+            .get_states_batch(&ids)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!("Lobby runtime unavailable for status summary: {}", e);
+                Vec::new()
+            });
+
+        let moves: Vec<(Uuid, LobbyStatus, LobbyStatus)> = in_flight
+            .iter()
+            .zip(runtime)
+            .filter_map(|((_, game_id, status), (_, runtime))| {
+                Some((*game_id, *status, runtime?.status))
+            })
+            .collect();
+
+        Ok(LobbyStatusSummary::corrected(counts, &moves))
+    }
+
+    /// Lobby state and roster from Redis; misses and errors read as absent
+    async fn read_runtime(&self, lobby_id: Uuid) -> (Option<LobbyState>, Vec<PlayerState>) {
+        let lobby_state_repo = LobbyStateRepository::new(self.state.redis.clone());
+        let player_repo = PlayerStateRepository::new(self.state.redis.clone());
+
+        let (runtime, players) = tokio::join!(
+            lobby_state_repo.get_state(lobby_id),
+            player_repo.list_players(lobby_id),
+        );
+
+        let runtime = match runtime {
+            Ok(runtime) => Some(runtime),
+            Err(AppError::NotFound(_)) => None,
+            Err(e) => {
+                tracing::warn!("Lobby {} runtime unavailable for snapshot: {}", lobby_id, e);
+                None
+            }
+        };
+        let players = players.unwrap_or_else(|e| {
+            tracing::warn!("Lobby {} players unavailable for snapshot: {}", lobby_id, e);
+            Vec::new()
+        });
+
+        (runtime, players)
+    }
+
+    /// Room connections that don't belong to a player
+    pub(crate) async fn spectator_count(&self, lobby_id: Uuid, players: &[PlayerState]) -> usize {
+        let indices = self.state.indices.lock().await;
+        let Some(conn_ids) = indices.get_lobby_connections(&lobby_id) else {
+            return 0;
+        };
+        let conns = self.state.connections.lock().await;
+
+        conn_ids
+            .iter()
+            .filter_map(|conn_id| conns.get(conn_id))
+            .filter(|conn| {
+                conn.user_id
+                    .is_none_or(|user_id| !players.iter().any(|p| p.user_id == user_id))
+            })
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::LobbyStatus;
+    use chrono::Utc;
+
+    fn lobby(status: LobbyStatus) -> Lobby {
+        let now = Utc::now().naive_utc();
+        Lobby {
+            id: Uuid::new_v4(),
+            path: "snapshot".into(),
+            name: "Snapshot".into(),
+            description: None,
+            game_id: Uuid::new_v4(),
+            game_path: "lexi-wars".into(),
+            creator_id: Uuid::new_v4(),
+            entry_amount: None,
+            current_amount: None,
+            token_symbol: None,
+            token_contract_id: None,
+            contract_address: None,
+            is_private: false,
+            is_sponsored: false,
+            spectators_allowed: true,
+            anonymize_players: false,
+            practice_bot: None,
+            auto_approve_trust_threshold: None,
+            payout_percents: None,
+            payout_underfill: Default::default(),
+            status,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn player(lobby_id: Uuid) -> PlayerState {
+        PlayerState::new(
+            Uuid::new_v4(),
+            lobby_id,
+            "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7".to_string(),
+            None,
+            None,
+            10.0,
+            None,
+            false,
+        )
+    }
+
+    #[test]
+    fn test_view_merges_config_and_runtime() {
+        let lobby = lobby(LobbyStatus::InProgress);
+        let mut runtime = LobbyState::new(lobby.id);
+        runtime.status = LobbyStatus::InProgress;
+        runtime.participant_count = 2;
+        runtime.started_at = Some(1_700_000_000);
+        let players = vec![player(lobby.id), player(lobby.id)];
+
+        let view = LobbyFullView::assemble(lobby.clone(), Some(runtime), players, 3);
+
+        assert_eq!(view.config.name, lobby.name);
+        assert_eq!(view.config.participant_count, 2);
+        assert_eq!(view.config.started_at, Some(1_700_000_000));
+        assert_eq!(view.runtime.started_at, Some(1_700_000_000));
+        assert_eq!(view.players.len(), 2);
+        assert_eq!(view.spectator_count, 3);
+    }
+
+    #[test]
+    fn test_missing_runtime_falls_back_to_config() {
+        let lobby = lobby(LobbyStatus::Finished);
+        let players = vec![player(lobby.id)];
+
+        let view = LobbyFullView::assemble(lobby.clone(), None, players, 0);
+
+        assert_eq!(view.runtime.lobby_id, lobby.id);
+        assert_eq!(view.runtime.status, LobbyStatus::Finished);
+        assert_eq!(view.runtime.participant_count, 1);
+        assert_eq!(view.runtime.started_at, None);
+        assert_eq!(view.config.participant_count, 1);
+        assert_eq!(view.config.status, LobbyStatus::Finished);
+    }
+
+    #[test]
+    fn test_status_summary_follows_runtime_status() {
+        let (lexi, other) = (Uuid::new_v4(), Uuid::new_v4());
+        let count = |status, game_id, count| LobbyStatusCount {
+            status,
+            game_id,
+            count,
+        };
+        let counts = vec![
+            count(LobbyStatus::Waiting, lexi, 3),
+            count(LobbyStatus::Finished, lexi, 5),
+            count(LobbyStatus::Waiting, other, 1),
+        ];
+
+        let summary = LobbyStatusSummary::corrected(
+            counts,
+            &[
+                (lexi, LobbyStatus::Waiting, LobbyStatus::InProgress),
+                (lexi, LobbyStatus::Waiting, LobbyStatus::Waiting),
+                (other, LobbyStatus::Waiting, LobbyStatus::Starting),
+            ],
+        );
+
+        assert_eq!(summary.corrected, 2);
+        let find = |game_id, status| {
+            summary
+                .counts
+                .iter()
+                .find(|c| c.game_id == game_id && c.status == status)
+                .map(|c| c.count)
+        };
+        assert_eq!(find(lexi, LobbyStatus::Waiting), Some(2));
+        assert_eq!(find(lexi, LobbyStatus::InProgress), Some(1));
+        assert_eq!(find(lexi, LobbyStatus::Finished), Some(5));
+        // Emptied counts are dropped
+        assert_eq!(find(other, LobbyStatus::Waiting), None);
+        assert_eq!(find(other, LobbyStatus::Starting), Some(1));
+    }
+}
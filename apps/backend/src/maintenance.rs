@@ -0,0 +1,119 @@
+// Maintenance mode: a Redis flag that stops new lobbies and game starts
+//
+// Only new work is refused. Games already running, reconnections and
+// everything else keep working, so a deploy can wait for the floor to drain.
+
+use chrono::Utc;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+
+use crate::{errors::AppError, models::RedisKey, state::RedisClient};
+
+/// Shown to players when no custom message was set
+pub const DEFAULT_MAINTENANCE_MESSAGE: &str = "Stacks Wars is undergoing maintenance. Games in progress will finish normally; please try again shortly.";
+
+/// Current maintenance state, as stored in Redis and returned to admins.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintenanceStatus {
+    pub enabled: bool,
+    pub message: Option<String>,
+    /// When maintenance was switched on (ms since epoch)
+    pub since_ms: Option<i64>,
+}
+
+impl MaintenanceStatus {
+    fn off() -> Self {
+        Self {
+            enabled: false,
+            message: None,
+            since_ms: None,
+        }
+    }
+
+    /// The message players see while maintenance is on.
+    pub fn player_message(&self) -> &str {
+        self.message
+            .as_deref()
+            .unwrap_or(DEFAULT_MAINTENANCE_MESSAGE)
+    }
+}
+
+/// Redis-backed maintenance flag, shared by every instance.
+#[derive(Clone)]
+pub struct MaintenanceMode {
+    redis: RedisClient,
+}
+
+impl MaintenanceMode {
+    pub fn new(redis: RedisClient) -> Self {
+        Self { redis }
+    }
+
+    pub async fn status(&self) -> Result<MaintenanceStatus, AppError> {
+        let mut conn = self.redis.get().await?;
+
+        let raw: Option<String> = conn
+            .get(RedisKey::maintenance())
+            .await
+            .map_err(AppError::RedisCommandError)?;
+
+        match raw {
+            Some(raw) => serde_json::from_str(&raw).map_err(|e| {
+                AppError::Deserialization(format!("Invalid maintenance status: {}", e))
+            }),
+            None => Ok(MaintenanceStatus::off()),
+        }
+    }
+
+    /// Switch maintenance on, keeping the original start time if it already was.
+    pub async fn enable(&self, message: Option<String>) -> Result<MaintenanceStatus, AppError> {
+        let since_ms = match self.status().await? {
+            MaintenanceStatus {
+                enabled: true,
+                since_ms: Some(since),
+                ..
+            } => since,
+            _ => Utc::now().timestamp_millis(),
+        };
+
+        let status = MaintenanceStatus {
+            enabled: true,
+            message: message.filter(|m| !m.trim().is_empty()),
+            since_ms: Some(since_ms),
+        };
+        let raw = serde_json::to_string(&status)
+            .map_err(|e| AppError::Serialization(format!("Failed to encode status: {}", e)))?;
+
+        let mut conn = self.redis.get().await?;
+        let _: () = conn
+            .set(RedisKey::maintenance(), raw)
+            .await
+            .map_err(AppError::RedisCommandError)?;
+
+        tracing::warn!("maintenance mode enabled");
+        Ok(status)
+    }
+
+    pub async fn disable(&self) -> Result<MaintenanceStatus, AppError> {
+        let mut conn = self.redis.get().await?;
+        let _: () = conn
+            .del(RedisKey::maintenance())
+            .await
+            .map_err(AppError::RedisCommandError)?;
+
+        tracing::warn!("maintenance mode disabled");
+        Ok(MaintenanceStatus::off())
+    }
+
+    /// Fail with `ServiceUnavailable` if new games are not being accepted.
+    pub async fn ensure_accepting_games(&self) -> Result<(), AppError> {
+        let status = self.status().await?;
+        if status.enabled {
+            return Err(AppError::ServiceUnavailable(
+                status.player_message().to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,395 @@
+// Matcher: groups stake-compatible queued players and forms a quick-play lobby
+
+use chrono::Utc;
+use rust_decimal::{Decimal, prelude::FromPrimitive};
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::db::{game::GameRepository, lobby::LobbyRepository};
+use crate::errors::AppError;
+use crate::maintenance::MaintenanceMode;
+use crate::matchmaking::{
+    MAX_RATING_GAP, MatchHistory, MatchmakingQueue, QUEUE_TIMEOUT_SECS, QueueEntry, Separation,
+    StakeRange,
+};
+use crate::models::{Lobby, money::floor_to_micro};
+use crate::state::AppState;
+use crate::ws::broadcast;
+use crate::ws::lobby::LobbyServerMessage;
+
+/// How often queues are swept for players who have waited too long
+const QUEUE_SWEEP_INTERVAL_SECS: u64 = 15;
+
+/// A set of players that can share a lobby, and the stake they agree on.
+#[derive(Debug, Clone)]
+pub struct MatchGroup {
+    pub players: Vec<QueueEntry>,
+    /// Entry amount for the lobby (lowest stake acceptable to every player)
+    pub stake: f64,
+}
+
+/// Find the first group of at least `min_players` compatible players.
+///
+/// Entries are expected oldest first. Each player, in order, anchors a group
+/// that later players join while their stake range still overlaps the group's
+/// and their rating is within `MAX_RATING_GAP` of the anchor's; the
+/// longest-waiting player therefore gets matched first. `separation` can
+/// keep a candidate out of a paid group it would otherwise fit.
+pub fn find_match(
+    entries: &[QueueEntry],
+    min_players: usize,
+    max_players: usize,
+    separation: &Separation,
+) -> Option<MatchGroup> {
+    let min_players = min_players.max(1);
+    let max_players = max_players.max(min_players);
+
+    for (i, anchor) in entries.iter().enumerate() {
+        let mut range: StakeRange = anchor.stake_range;
+        let mut players = vec![anchor.clone()];
+
+        for candidate in entries.iter().skip(i + 1) {
+            if players.len() >= max_players {
+                break;
+            }
+            if (candidate.rating - anchor.rating).abs() > MAX_RATING_GAP {
+                continue;
+            }
+            if let Some(overlap) = range.intersect(&candidate.stake_range)
+                && separation.allows(&players, candidate, overlap.min)
+            {
+                range = overlap;
+                players.push(candidate.clone());
+            }
+        }
+
+        if players.len() >= min_players {
+            return Some(MatchGroup {
+                players,
+                stake: range.min,
+            });
+        }
+    }
+
+    None
+}
+
+/// Drop a game's timed-out players and tell them with `MatchmakingTimedOut`.
+/// Returns their ids.
+pub async fn expire_queue(state: &AppState, game_id: Uuid) -> Result<Vec<Uuid>, AppError> {
+    let timed_out = MatchmakingQueue::new(state.redis.clone())
+        .remove_expired(game_id, QUEUE_TIMEOUT_SECS)
+        .await?;
+    if !timed_out.is_empty() {
+        broadcast::broadcast_users(
+            state,
+            &timed_out,
+            &LobbyServerMessage::MatchmakingTimedOut { game_id },
+        )
+        .await;
+    }
+    Ok(timed_out)
+}
+
+/// Expire timed-out players in every game's queue. Returns how many were dropped.
+///
+/// Matching passes only run when someone enqueues, so a quiet game's queue
+/// relies on this to let its players go.
+pub async fn sweep_queue_timeouts(state: &AppState) -> Result<usize, AppError> {
+    let queue = MatchmakingQueue::new(state.redis.clone());
+    let mut dropped = 0;
+    for game_id in queue.queued_games().await? {
+        dropped += expire_queue(state, game_id).await?.len();
+    }
+    Ok(dropped)
+}
+
+/// Spawn the background task that periodically sweeps queues for timeouts.
+pub fn spawn_queue_sweeper(state: AppState) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(QUEUE_SWEEP_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            match sweep_queue_timeouts(&state).await {
+                Ok(dropped) if dropped > 0 => {
+                    tracing::info!("Dropped {} timed-out players from matchmaking", dropped);
+                }
+                Ok(_) => {}
+                Err(e) => tracing::error!("Matchmaking queue sweep failed: {}", e),
+            }
+        }
+    });
+}
+
+/// Run one matching pass for a game.
+///
+/// Drops timed-out players, then claims a compatible group and creates a public
+/// lobby for it. The longest-waiting player becomes the creator; everyone in the
+/// group is sent `MatchFound` and joins through the room socket as usual.
+/// Nobody is matched during maintenance; players stay queued until it ends.
+/// Paid groups are recorded in the match history that seat separation reads.
+pub async fn run_matcher(
+    state: &AppState,
+    game_id: Uuid,
+) -> Result<Option<(Lobby, MatchGroup)>, AppError> {
+    let queue = MatchmakingQueue::new(state.redis.clone());
+    expire_queue(state, game_id).await?;
+
+    if MaintenanceMode::new(state.redis.clone())
+        .status()
+        .await?
+        .enabled
+    {
+        return Ok(None);
+    }
+
+    let game = GameRepository::new(state.postgres.clone())
+        .find_by_id(game_id)
+        .await?;
+
+    // Quick play always needs an opponent, even for games that allow solo lobbies
+    let min_players = game.min_players.max(2) as usize;
+    let max_players = game.max_players.max(2) as usize;
+
+    let entries = queue.list(game_id).await?;
+    let history = MatchHistory::new(state.redis.clone());
+    let config = &state.config.seat_separation;
+    let now = Utc::now().timestamp();
+    let separation = if config.is_enabled() {
+        let queued: Vec<Uuid> = entries.iter().map(|e| e.user_id).collect();
+        let recent = history.recent(&queued, now, config.window_secs).await?;
+        Separation::new(config.clone(), recent, now)
+    } else {
+        Separation::off()
+    };
+
+    let Some(group) = find_match(&entries, min_players, max_players, &separation) else {
+        return Ok(None);
+    };
+
+    // Claim the group; players taken by a concurrent pass are skipped
+    let user_ids: Vec<Uuid> = group.players.iter().map(|p| p.user_id).collect();
+    let claimed = queue.remove_many(game_id, &user_ids).await?;
+    if claimed.len() < user_ids.len() {
+        for entry in group
+            .players
+            .iter()
+            .filter(|p| claimed.contains(&p.user_id))
+        {
+            queue.restore(entry).await?;
+        }
+        return Ok(None);
+    }
+
+    let stake = Decimal::from_f64(group.stake)
+        .map(floor_to_micro)
+        .filter(|stake| *stake > Decimal::ZERO);
+    let creator_id = group.players[0].user_id;
+
+    let lobby = LobbyRepository::new(state.postgres.clone())
+        .create_lobby(
+            &format!("Quick Play: {}", game.name),
+            None,
+            creator_id,
+            game_id,
+            &game.path,
+            stake,
+            stake,
+            stake.map(|_| "STX"),
+            None,
+            None,
+            false,
+            false,
+            true,
+            false,
+            None,
+            None,
+            None,
+            state.redis.clone(),
+            state.clone(),
+        )
+        .await;
+
+    let lobby = match lobby {
+        Ok(lobby) => lobby,
+        Err(e) => {
+            // Put everyone back so they aren't silently dropped
+            for entry in &group.players {
+                let _ = queue.restore(entry).await;
+            }
+            return Err(e);
+        }
+    };
+
+    if stake.is_some()
+        && config.is_enabled()
+        && let Err(e) = history
+            .record(lobby.id(), &user_ids, now, config.window_secs)
+            .await
+    {
+        tracing::warn!("Failed to record match history for {}: {}", lobby.id(), e);
+    }
+
+    tracing::info!(
+        "Quick play matched {} players into lobby {} (stake {:?})",
+        user_ids.len(),
+        lobby.id(),
+        stake
+    );
+
+    broadcast::broadcast_users(
+        state,
+        &user_ids,
+        &LobbyServerMessage::MatchFound {
+            lobby_id: lobby.id(),
+            lobby_path: lobby.path.clone(),
+            game_id,
+        },
+    )
+    .await;
+
+    Ok(Some((lobby, group)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matchmaking::RecentPairs;
+    use crate::models::skill_rating::DEFAULT_SKILL_RATING;
+    use crate::state::SeatSeparationConfig;
+
+    fn entry(min: f64, max: f64, enqueued_at: i64) -> QueueEntry {
+        QueueEntry {
+            user_id: Uuid::new_v4(),
+            game_id: Uuid::nil(),
+            stake_range: StakeRange::new(min, max).unwrap(),
+            rating: DEFAULT_SKILL_RATING,
+            enqueued_at,
+        }
+    }
+
+    fn rated(rating: f64, enqueued_at: i64) -> QueueEntry {
+        QueueEntry {
+            rating,
+            ..entry(0.0, 0.0, enqueued_at)
+        }
+    }
+
+    #[test]
+    fn test_two_compatible_players_match() {
+        let entries = vec![entry(1.0, 5.0, 1), entry(3.0, 10.0, 2)];
+
+        let group = find_match(&entries, 2, 4, &Separation::off()).expect("should match");
+        assert_eq!(group.players.len(), 2);
+        assert_eq!(group.stake, 3.0);
+        assert_eq!(group.players[0].user_id, entries[0].user_id);
+    }
+
+    #[test]
+    fn test_incompatible_stakes_do_not_match() {
+        let entries = vec![entry(0.0, 1.0, 1), entry(5.0, 10.0, 2)];
+        assert!(find_match(&entries, 2, 4, &Separation::off()).is_none());
+    }
+
+    #[test]
+    fn test_waits_for_min_players() {
+        let entries = vec![entry(1.0, 5.0, 1), entry(1.0, 5.0, 2)];
+        assert!(find_match(&entries, 3, 4, &Separation::off()).is_none());
+    }
+
+    #[test]
+    fn test_group_capped_at_max_players() {
+        let entries: Vec<QueueEntry> = (0..5).map(|i| entry(0.0, 0.0, i)).collect();
+
+        let group = find_match(&entries, 2, 3, &Separation::off()).expect("should match");
+        assert_eq!(group.players.len(), 3);
+        assert_eq!(group.stake, 0.0);
+    }
+
+    #[test]
+    fn test_group_narrows_overlap() {
+        // Third player overlaps the first but not the (1..5) ∩ (4..8) = 4..5 window
+        let entries = vec![
+            entry(1.0, 5.0, 1),
+            entry(4.0, 8.0, 2),
+            entry(1.0, 2.0, 3),
+            entry(4.5, 6.0, 4),
+        ];
+
+        let group = find_match(&entries, 3, 4, &Separation::off()).expect("should match");
+        let ids: Vec<Uuid> = group.players.iter().map(|p| p.user_id).collect();
+        assert_eq!(
+            ids,
+            vec![entries[0].user_id, entries[1].user_id, entries[3].user_id]
+        );
+        assert_eq!(group.stake, 4.5);
+    }
+
+    #[test]
+    fn test_later_anchor_matches_when_oldest_cannot() {
+        let entries = vec![
+            entry(100.0, 200.0, 1),
+            entry(1.0, 2.0, 2),
+            entry(1.5, 3.0, 3),
+        ];
+
+        let group = find_match(&entries, 2, 2, &Separation::off()).expect("should match");
+        assert_eq!(group.players[0].user_id, entries[1].user_id);
+        assert_eq!(group.players[1].user_id, entries[2].user_id);
+    }
+
+    #[test]
+    fn test_rating_gap_keeps_players_apart() {
+        let entries = vec![rated(1200.0, 1), rated(1700.0, 2), rated(1350.0, 3)];
+
+        let group = find_match(&entries, 2, 4, &Separation::off()).expect("should match");
+        let ids: Vec<Uuid> = group.players.iter().map(|p| p.user_id).collect();
+        assert_eq!(ids, vec![entries[0].user_id, entries[2].user_id]);
+
+        let far_apart = vec![rated(1000.0, 1), rated(1500.0, 2)];
+        assert!(find_match(&far_apart, 2, 2, &Separation::off()).is_none());
+    }
+
+    fn separation(recent: RecentPairs, now: i64) -> Separation {
+        Separation::new(SeatSeparationConfig::default(), recent, now)
+    }
+
+    #[test]
+    fn test_recent_opponents_are_kept_apart_in_paid_play() {
+        let entries = vec![
+            entry(1.0, 5.0, 100),
+            entry(1.0, 5.0, 101),
+            entry(1.0, 5.0, 102),
+        ];
+        let mut recent = RecentPairs::default();
+        recent.add(entries[0].user_id, entries[1].user_id);
+
+        let group =
+            find_match(&entries, 2, 2, &separation(recent.clone(), 110)).expect("should match");
+        let ids: Vec<Uuid> = group.players.iter().map(|p| p.user_id).collect();
+        assert_eq!(ids, vec![entries[0].user_id, entries[2].user_id]);
+
+        // Without anyone else, the pair waits until one of them has queued long enough
+        let pair = &entries[..2];
+        assert!(find_match(pair, 2, 2, &separation(recent.clone(), 110)).is_none());
+        let relax = SeatSeparationConfig::default().relax_after_secs;
+        assert!(find_match(pair, 2, 2, &separation(recent, 100 + relax)).is_some());
+    }
+
+    #[test]
+    fn test_separation_ignores_free_play() {
+        let entries = vec![entry(0.0, 0.0, 100), entry(0.0, 5.0, 101)];
+        let mut recent = RecentPairs::default();
+        recent.add(entries[0].user_id, entries[1].user_id);
+
+        assert!(find_match(&entries, 2, 2, &separation(recent, 110)).is_some());
+    }
+
+    #[test]
+    fn test_stake_range_validation() {
+        assert!(StakeRange::new(-1.0, 1.0).is_err());
+        assert!(StakeRange::new(2.0, 1.0).is_err());
+        assert!(StakeRange::new(0.0, f64::INFINITY).is_err());
+        assert_eq!(StakeRange::free(), StakeRange::new(0.0, 0.0).unwrap());
+    }
+}
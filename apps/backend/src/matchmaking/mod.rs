@@ -0,0 +1,78 @@
+// Matchmaking: quick-play queue (Redis sorted set) and matcher that forms lobbies
+
+mod matcher;
+mod queue;
+mod separation;
+
+pub use matcher::{
+    MatchGroup, expire_queue, find_match, run_matcher, spawn_queue_sweeper, sweep_queue_timeouts,
+};
+pub use queue::MatchmakingQueue;
+pub use separation::{MatchHistory, RecentPairs, Separation};
+
+use crate::errors::AppError;
+use crate::models::skill_rating::DEFAULT_SKILL_RATING;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Seconds a player may wait in the queue before being dropped unmatched
+pub const QUEUE_TIMEOUT_SECS: i64 = 120;
+
+/// Widest skill rating difference allowed between a group's anchor and its members
+pub const MAX_RATING_GAP: f64 = 400.0;
+
+/// Inclusive range of entry amounts a player is willing to stake.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StakeRange {
+    pub min: f64,
+    pub max: f64,
+}
+
+impl StakeRange {
+    /// Validate and build a stake range.
+    pub fn new(min: f64, max: f64) -> Result<Self, AppError> {
+        if !min.is_finite() || !max.is_finite() || min < 0.0 {
+            return Err(AppError::BadRequest(
+                "Stake range must be finite and non-negative".into(),
+            ));
+        }
+        if max < min {
+            return Err(AppError::BadRequest(format!(
+                "Stake range max ({}) is below min ({})",
+                max, min
+            )));
+        }
+        Ok(Self { min, max })
+    }
+
+    /// Free play only (zero stake)
+    pub fn free() -> Self {
+        Self { min: 0.0, max: 0.0 }
+    }
+
+    /// Overlap of two ranges, if any.
+    pub fn intersect(&self, other: &StakeRange) -> Option<StakeRange> {
+        let min = self.min.max(other.min);
+        let max = self.max.min(other.max);
+        (min <= max).then_some(StakeRange { min, max })
+    }
+}
+
+/// A player waiting in a game's quick-play queue.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueEntry {
+    pub user_id: Uuid,
+    pub game_id: Uuid,
+    pub stake_range: StakeRange,
+    /// Player's skill rating for the game at enqueue time
+    #[serde(default = "default_rating")]
+    pub rating: f64,
+    /// Unix timestamp when the player entered the queue
+    pub enqueued_at: i64,
+}
+
+fn default_rating() -> f64 {
+    DEFAULT_SKILL_RATING
+}
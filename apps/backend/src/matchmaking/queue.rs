@@ -0,0 +1,191 @@
+// Quick-play queue storage (Redis): sorted set of user ids + entry details hash
+
+use crate::errors::AppError;
+use crate::matchmaking::{QueueEntry, StakeRange};
+use crate::models::{KeyPart, RedisKey};
+use crate::state::RedisClient;
+use chrono::Utc;
+use redis::AsyncCommands;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Keys examined per SCAN call
+const SCAN_COUNT: usize = 500;
+
+/// Redis-backed matchmaking queue, one per game.
+#[derive(Clone)]
+pub struct MatchmakingQueue {
+    pub(crate) redis: RedisClient,
+}
+
+impl MatchmakingQueue {
+    /// Create a new `MatchmakingQueue`.
+    pub fn new(redis: RedisClient) -> Self {
+        Self { redis }
+    }
+
+    /// Add (or re-add) a player to a game's queue.
+    ///
+    /// Re-enqueueing replaces the stake range and rating and resets the wait timer.
+    pub async fn enqueue(
+        &self,
+        user_id: Uuid,
+        game_id: Uuid,
+        stake_range: StakeRange,
+        rating: f64,
+    ) -> Result<QueueEntry, AppError> {
+        let entry = QueueEntry {
+            user_id,
+            game_id,
+            stake_range,
+            rating,
+            enqueued_at: Utc::now().timestamp(),
+        };
+        self.restore(&entry).await?;
+
+        Ok(entry)
+    }
+
+    /// Write an entry back into the queue, keeping its original enqueue time.
+    pub(crate) async fn restore(&self, entry: &QueueEntry) -> Result<(), AppError> {
+        let mut conn = self.redis.get().await?;
+        let raw = serde_json::to_string(entry)
+            .map_err(|e| AppError::Serialization(format!("Failed to serialize entry: {}", e)))?;
+
+        let _: () = redis::pipe()
+            .atomic()
+            .hset(
+                RedisKey::matchmaking_entries(entry.game_id),
+                entry.user_id.to_string(),
+                raw,
+            )
+            .zadd(
+                RedisKey::matchmaking_queue(entry.game_id),
+                entry.user_id.to_string(),
+                entry.enqueued_at,
+            )
+            .query_async(&mut *conn)
+            .await
+            .map_err(AppError::RedisCommandError)?;
+
+        Ok(())
+    }
+
+    /// Remove a player from a game's queue. Returns whether they were queued.
+    pub async fn dequeue(&self, user_id: Uuid, game_id: Uuid) -> Result<bool, AppError> {
+        let removed = self.remove_many(game_id, &[user_id]).await?;
+        Ok(!removed.is_empty())
+    }
+
+    /// Remove players from a game's queue, returning the ones that were actually removed.
+    ///
+    /// Used by the matcher to claim a group: a player already claimed by a
+    /// concurrent matcher run won't be returned.
+    pub async fn remove_many(
+        &self,
+        game_id: Uuid,
+        user_ids: &[Uuid],
+    ) -> Result<Vec<Uuid>, AppError> {
+        let mut conn = self.redis.get().await?;
+        let queue_key = RedisKey::matchmaking_queue(game_id);
+        let entries_key = RedisKey::matchmaking_entries(game_id);
+
+        let mut removed = Vec::new();
+        for user_id in user_ids {
+            let n: i64 = conn
+                .zrem(&queue_key, user_id.to_string())
+                .await
+                .map_err(AppError::RedisCommandError)?;
+            if n > 0 {
+                removed.push(*user_id);
+            }
+            let _: () = conn
+                .hdel(&entries_key, user_id.to_string())
+                .await
+                .map_err(AppError::RedisCommandError)?;
+        }
+
+        Ok(removed)
+    }
+
+    /// List queued players for a game, oldest first.
+    pub async fn list(&self, game_id: Uuid) -> Result<Vec<QueueEntry>, AppError> {
+        let mut conn = self.redis.get().await?;
+
+        let user_ids: Vec<String> = conn
+            .zrange(RedisKey::matchmaking_queue(game_id), 0, -1)
+            .await
+            .map_err(AppError::RedisCommandError)?;
+        let raw: HashMap<String, String> = conn
+            .hgetall(RedisKey::matchmaking_entries(game_id))
+            .await
+            .map_err(AppError::RedisCommandError)?;
+
+        Ok(user_ids
+            .iter()
+            .filter_map(|uid| raw.get(uid))
+            .filter_map(|json| serde_json::from_str::<QueueEntry>(json).ok())
+            .collect())
+    }
+
+    /// Every game with a queue in Redis, found with an incremental SCAN.
+    pub async fn queued_games(&self) -> Result<Vec<Uuid>, AppError> {
+        let mut conn = self.redis.get().await?;
+
+        let pattern = RedisKey::matchmaking_queue(KeyPart::Wildcard);
+        let mut game_ids = Vec::new();
+        let mut cursor: u64 = 0;
+
+        loop {
+            let (next, keys): (u64, Vec<String>) = redis::cmd("SCAN")
+                .cursor_arg(cursor)
+                .arg("MATCH")
+                .arg(&pattern)
+                .arg("COUNT")
+                .arg(SCAN_COUNT)
+                .query_async(&mut *conn)
+                .await
+                .map_err(AppError::RedisCommandError)?;
+            game_ids.extend(
+                keys.iter()
+                    .filter_map(|key| key.split(':').nth(1))
+                    .filter_map(|id| Uuid::parse_str(id).ok()),
+            );
+            if next == 0 {
+                break;
+            }
+            cursor = next;
+        }
+
+        // SCAN may return a key more than once
+        game_ids.sort_unstable();
+        game_ids.dedup();
+        Ok(game_ids)
+    }
+
+    /// Drop players that have waited longer than `timeout_secs`, returning their ids.
+    pub async fn remove_expired(
+        &self,
+        game_id: Uuid,
+        timeout_secs: i64,
+    ) -> Result<Vec<Uuid>, AppError> {
+        let mut conn = self.redis.get().await?;
+        let cutoff = Utc::now().timestamp() - timeout_secs;
+
+        let expired: Vec<String> = conn
+            .zrangebyscore(RedisKey::matchmaking_queue(game_id), "-inf", cutoff)
+            .await
+            .map_err(AppError::RedisCommandError)?;
+        drop(conn);
+
+        let expired: Vec<Uuid> = expired
+            .iter()
+            .filter_map(|id| Uuid::parse_str(id).ok())
+            .collect();
+        if expired.is_empty() {
+            return Ok(expired);
+        }
+
+        self.remove_many(game_id, &expired).await
+    }
+}
@@ -0,0 +1,167 @@
+// Seat separation: keeps players who keep meeting apart in paid quick play
+//
+// Each paid match is recorded against every player in it as
+// `{lobby_id}:{other_user_id}` members of their `users:{id}:matched_with`
+// sorted set, scored by when the match formed and trimmed to the configured
+// window. Before a pass the matcher counts how often each queued pair shared
+// a match in that window, and won't seat a pair at the limit together until
+// one of them has waited long enough that any opponent beats none.
+
+use std::collections::HashMap;
+
+use redis::AsyncCommands;
+use uuid::Uuid;
+
+use crate::errors::AppError;
+use crate::matchmaking::QueueEntry;
+use crate::models::RedisKey;
+use crate::state::{RedisClient, SeatSeparationConfig};
+
+/// Who recently shared a paid match with whom, in Redis.
+#[derive(Clone)]
+pub struct MatchHistory {
+    redis: RedisClient,
+}
+
+impl MatchHistory {
+    pub fn new(redis: RedisClient) -> Self {
+        Self { redis }
+    }
+
+    /// Record that `user_ids` were matched into `lobby_id` at `now`.
+    pub async fn record(
+        &self,
+        lobby_id: Uuid,
+        user_ids: &[Uuid],
+        now: i64,
+        window_secs: i64,
+    ) -> Result<(), AppError> {
+        let mut conn = self.redis.get().await?;
+        let mut pipe = redis::pipe();
+
+        for user_id in user_ids {
+            let key = RedisKey::user_matched_with(*user_id);
+            for other in user_ids.iter().filter(|other| *other != user_id) {
+                pipe.zadd(&key, format!("{}:{}", lobby_id, other), now)
+                    .ignore();
+            }
+            pipe.zrembyscore(&key, "-inf", now - window_secs)
+                .ignore()
+                .expire(&key, window_secs)
+                .ignore();
+        }
+
+        let _: () = pipe
+            .query_async(&mut *conn)
+            .await
+            .map_err(AppError::RedisCommandError)?;
+        Ok(())
+    }
+
+    /// How many matches each pair of `user_ids` shared since `now - window_secs`.
+    pub async fn recent(
+        &self,
+        user_ids: &[Uuid],
+        now: i64,
+        window_secs: i64,
+    ) -> Result<RecentPairs, AppError> {
+        let mut conn = self.redis.get().await?;
+        let mut pairs = RecentPairs::default();
+
+        for user_id in user_ids {
+            let members: Vec<String> = conn
+                .zrangebyscore(
+                    RedisKey::user_matched_with(*user_id),
+                    now - window_secs,
+                    "+inf",
+                )
+                .await
+                .map_err(AppError::RedisCommandError)?;
+
+            // Both players hold the match; count it from the lower id's side only
+            for member in members {
+                if let Some(other) = member
+                    .split_once(':')
+                    .and_then(|(_, other)| other.parse::<Uuid>().ok())
+                    && *user_id < other
+                    && user_ids.contains(&other)
+                {
+                    pairs.add(*user_id, other);
+                }
+            }
+        }
+
+        Ok(pairs)
+    }
+}
+
+/// Shared match counts by pair of players.
+#[derive(Debug, Clone, Default)]
+pub struct RecentPairs(HashMap<(Uuid, Uuid), usize>);
+
+impl RecentPairs {
+    pub fn add(&mut self, a: Uuid, b: Uuid) {
+        *self.0.entry(pair(a, b)).or_default() += 1;
+    }
+
+    pub fn count(&self, a: Uuid, b: Uuid) -> usize {
+        self.0.get(&pair(a, b)).copied().unwrap_or(0)
+    }
+}
+
+fn pair(a: Uuid, b: Uuid) -> (Uuid, Uuid) {
+    if a < b { (a, b) } else { (b, a) }
+}
+
+/// Which players a matching pass may seat together.
+#[derive(Debug, Clone)]
+pub struct Separation {
+    config: SeatSeparationConfig,
+    recent: RecentPairs,
+    /// Unix timestamp of the pass, for how long players have waited
+    now: i64,
+}
+
+impl Separation {
+    pub fn new(config: SeatSeparationConfig, recent: RecentPairs, now: i64) -> Self {
+        Self {
+            config,
+            recent,
+            now,
+        }
+    }
+
+    /// Anyone may play anyone
+    pub fn off() -> Self {
+        Self::new(
+            SeatSeparationConfig {
+                window_secs: 0,
+                ..Default::default()
+            },
+            RecentPairs::default(),
+            0,
+        )
+    }
+
+    /// Whether `candidate` may join `group` in a lobby staking `stake`.
+    ///
+    /// Every pair is checked, not only the candidate's, since the candidate
+    /// can be what turns a free group into a paid one.
+    pub fn allows(&self, group: &[QueueEntry], candidate: &QueueEntry, stake: f64) -> bool {
+        if !self.config.is_enabled() || stake <= 0.0 {
+            return true;
+        }
+
+        let players: Vec<&QueueEntry> = group.iter().chain([candidate]).collect();
+        players
+            .iter()
+            .enumerate()
+            .all(|(i, a)| players[i + 1..].iter().all(|b| self.pair_allowed(a, b)))
+    }
+
+    fn pair_allowed(&self, a: &QueueEntry, b: &QueueEntry) -> bool {
+        let waited = |entry: &QueueEntry| self.now - entry.enqueued_at;
+        self.recent.count(a.user_id, b.user_id) < self.config.max_shared_matches
+            || waited(a).max(waited(b)) >= self.config.relax_after_secs
+    }
+}
@@ -1,26 +1,39 @@
+use crate::abuse::{AbuseTracker, Violation};
+use crate::auth::jwt::{JwtKeys, decode_jwt};
 use crate::models::keys::RedisKey;
-use crate::state::AppState;
+use crate::state::{AppState, CorsConfig, RateLimitTier, RateLimits};
 use axum::{
+    body::Body,
     extract::{ConnectInfo, Request},
-    http::StatusCode,
+    http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode, header},
     middleware::Next,
-    response::Response,
+    response::{IntoResponse, Response},
 };
+use axum_extra::extract::cookie::CookieJar;
 use redis::AsyncCommands;
+use sha2::{Digest, Sha256};
 use std::{net::SocketAddr, time::Duration};
-use tower_http::cors::CorsLayer;
+use tower_http::cors::{AllowOrigin, CorsLayer};
 use uuid::Uuid;
 
 /// Redis-backed, type-safe rate limiting middleware.
 ///
-/// Marker types select the policy. Behavior summary:
-/// - ApiRateLimit: unauthenticated => 60/min by IP; authenticated => 300/min by user
-/// - AuthRateLimit / StrictRateLimit: strict write routes => 30/min per user
+/// Marker types select the tier from `AppConfig::rate_limits`:
+/// - ApiRateLimit: public reads => `api` by IP, `api_authenticated` by user
+/// - AuthRateLimit: authenticated writes => `auth`
+/// - StrictRateLimit: sensitive writes (registration, claims, contract calls) => `strict`
 ///
-/// Adds X-RateLimit-Limit, X-RateLimit-Remaining and X-RateLimit-Reset headers.
+/// Tiers count independently in fixed windows, keyed by user id when the
+/// request carries a valid auth cookie and by IP otherwise.
+///
+/// Adds X-RateLimit-Limit, X-RateLimit-Remaining and X-RateLimit-Reset headers;
+/// rejected requests get `429` with `Retry-After`.
 /// On Redis errors the middleware fails open (allows the request).
 pub trait RateLimitConfig {
     fn name() -> &'static str;
+
+    /// Quota for this tier given whether the caller is signed in.
+    fn tier(limits: &RateLimits, authenticated: bool) -> RateLimitTier;
 }
 
 pub struct ApiRateLimit;
@@ -28,6 +41,14 @@ impl RateLimitConfig for ApiRateLimit {
     fn name() -> &'static str {
         "API"
     }
+
+    fn tier(limits: &RateLimits, authenticated: bool) -> RateLimitTier {
+        if authenticated {
+            limits.api_authenticated
+        } else {
+            limits.api
+        }
+    }
 }
 
 pub struct AuthRateLimit;
@@ -35,6 +56,10 @@ impl RateLimitConfig for AuthRateLimit {
     fn name() -> &'static str {
         "Auth"
     }
+
+    fn tier(limits: &RateLimits, _authenticated: bool) -> RateLimitTier {
+        limits.auth
+    }
 }
 
 pub struct StrictRateLimit;
@@ -42,6 +67,102 @@ impl RateLimitConfig for StrictRateLimit {
     fn name() -> &'static str {
         "Strict"
     }
+
+    fn tier(limits: &RateLimits, _authenticated: bool) -> RateLimitTier {
+        limits.strict
+    }
+}
+
+/// Counter key and quota for a caller under policy `T`.
+fn rate_limit_key<T: RateLimitConfig>(
+    limits: &RateLimits,
+    client_ip: &str,
+    user_id: Option<Uuid>,
+) -> (String, RateLimitTier) {
+    let tier_name = T::name().to_lowercase();
+    let key = match user_id {
+        Some(user_id) => RedisKey::rate_user(&tier_name, user_id),
+        None => RedisKey::rate_ip(&tier_name, client_ip),
+    };
+
+    (key, T::tier(limits, user_id.is_some()))
+}
+
+/// Request count in the current window and seconds until it resets.
+#[derive(Debug, Clone, Copy)]
+struct RateLimitHit {
+    count: u64,
+    reset_secs: u64,
+}
+
+impl RateLimitHit {
+    fn exceeded(&self, tier: RateLimitTier) -> bool {
+        self.count > tier.requests as u64
+    }
+}
+
+/// Count one request against `key`. Returns `None` when Redis is unavailable.
+async fn record_hit(state: &AppState, key: &str, tier: RateLimitTier) -> Option<RateLimitHit> {
+    let mut conn = match state.redis.get().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            tracing::warn!("rate_limit: could not get redis connection: {}", e);
+            return None;
+        }
+    };
+
+    let count: u64 = match conn.incr(key, 1).await {
+        Ok(count) => count,
+        Err(e) => {
+            tracing::error!("rate_limit: redis incr error: {}", e);
+            return None;
+        }
+    };
+
+    let mut ttl: i64 = conn.ttl(key).await.unwrap_or(-1);
+    // First hit of a window, or a counter that lost its expiry
+    if count == 1 || ttl < 0 {
+        match conn.expire::<_, bool>(key, tier.window_secs as i64).await {
+            Ok(_) => ttl = tier.window_secs as i64,
+            Err(e) => tracing::warn!("rate_limit: expire set error for key {}: {}", key, e),
+        }
+    }
+
+    Some(RateLimitHit {
+        count,
+        reset_secs: ttl.max(0) as u64,
+    })
+}
+
+/// User id from a valid `auth_token` cookie, if any.
+///
+/// Only the signature and expiry are checked; a revoked token still counts
+/// against its user's quota, which is the stricter outcome.
+fn caller_user_id(headers: &HeaderMap, jwt_keys: &JwtKeys) -> Option<Uuid> {
+    let jar = CookieJar::from_headers(headers);
+    let token = jar.get("auth_token")?.value();
+    decode_jwt(token, jwt_keys).ok()?.user_id().ok()
+}
+
+fn insert_rate_limit_headers(
+    headers: &mut HeaderMap,
+    tier: RateLimitTier,
+    hit: Option<RateLimitHit>,
+) {
+    let limit = tier.requests as u64;
+    // Without Redis, report a full quota and window
+    let (remaining, reset) = match hit {
+        Some(hit) => (limit.saturating_sub(hit.count), hit.reset_secs),
+        None => (limit, tier.window_secs),
+    };
+
+    for (name, value) in [
+        ("x-ratelimit-limit", limit),
+        ("x-ratelimit-remaining", remaining),
+        ("x-ratelimit-reset", reset),
+    ] {
+        headers.insert(HeaderName::from_static(name), HeaderValue::from(value));
+    }
 }
 
 /// Redis-backed middleware. It reads AppState from request extensions if present.
@@ -57,14 +178,6 @@ pub async fn rate_limit_middleware<T: RateLimitConfig>(
             "unknown".to_string()
         };
 
-    // Keep only concise diagnostics to avoid noisy logs in high-volume tests.
-    // Lower-frequency builds can enable `trace` to see these internal details.
-    tracing::trace!("rate_limit: extensions.len={}", request.extensions().len());
-
-    // try to obtain AppState from extensions (set by Router::with_state)
-    // Try to read the shared AppState from request extensions. Depending on how the
-    // router/service was constructed the state may be stored as `axum::extract::State<AppState>`
-    // or left as the bare `AppState` in extensions — try both.
     // Try a few different ways the state might be stored in extensions. Different
     // versions/compositions of axum/tower can end up storing the state as
     // `axum::extract::State<T>`, `axum::Extension<T>`, or the bare `T`.
@@ -73,170 +186,46 @@ pub async fn rate_limit_middleware<T: RateLimitConfig>(
             Some(s.0.clone())
         } else if let Some(s) = request.extensions().get::<axum::Extension<AppState>>() {
             Some(s.0.clone())
-        } else if let Some(s) = request.extensions().get::<AppState>() {
-            Some(s.clone())
         } else {
-            None
+            request.extensions().get::<AppState>().cloned()
         };
 
-    // determine key and limit
-    // prefer an `AuthClaims` instance placed in request extensions by an upstream auth extractor/middleware
-    let user_id_opt = request
-        .extensions()
-        .get::<crate::auth::extractors::AuthClaims>()
-        .and_then(|claims| claims.user_id().ok());
-
-    let (key, limit) = match T::name() {
-        "API" => {
-            if let Some(user_id) = user_id_opt {
-                (RedisKey::rate_user_auth(user_id), 300)
-            } else {
-                (RedisKey::rate_user_ip(&client_ip), 60)
-            }
-        }
-        "Auth" | "Strict" => {
-            if let Some(user_id) = user_id_opt {
-                (RedisKey::rate_user_strict(user_id), 30)
-            } else {
-                (RedisKey::rate_user_ip(&client_ip), 30)
-            }
-        }
-        _ => (RedisKey::rate_user_ip(&client_ip), 60),
+    let Some(state) = app_state_opt else {
+        tracing::debug!("rate_limit: no AppState found in request extensions (skipping redis)");
+        let tier = T::tier(&RateLimits::default(), false);
+        let mut response = next.run(request).await;
+        insert_rate_limit_headers(response.headers_mut(), tier, None);
+        return Ok(response);
     };
 
-    // If we have AppState, use Redis for counting. Capture count and ttl to append headers later.
-    let mut maybe_count: Option<i64> = None;
-    let mut maybe_ttl: Option<i64> = None;
+    let user_id = caller_user_id(request.headers(), &state.config.jwt_keys);
+    let (key, tier) = rate_limit_key::<T>(&state.config.rate_limits, &client_ip, user_id);
 
     tracing::debug!(
-        "rate_limit: policy={} selected key={} limit={} client_ip={}",
+        "rate_limit: policy={} selected key={} limit={}/{}s client_ip={}",
         T::name(),
         key,
-        limit,
+        tier.requests,
+        tier.window_secs,
         client_ip
     );
 
-    if let Some(state) = app_state_opt {
-        tracing::trace!("rate_limit: AppState found, using redis for counting");
-        match state.redis.get().await {
-            Ok(mut conn) => {
-                // INCR and set EXPIRE to 60s when count == 1
-                let count_res: redis::RedisResult<i64> = conn.incr(&key, 1).await;
-                match count_res {
-                    Ok(count) => {
-                        maybe_count = Some(count);
-                        if count == 1 {
-                            // best-effort: set expire, warn on error but don't block the request
-                            let expire_res: redis::RedisResult<bool> = conn.expire(&key, 60).await;
-                            match expire_res {
-                                Ok(_) => tracing::trace!("rate_limit: set expire for key={}", key),
-                                Err(e) => tracing::warn!(
-                                    "rate_limit: expire set error for key {}: {}",
-                                    key,
-                                    e
-                                ),
-                            }
-                        }
-                        // attempt to read TTL for reset header
-                        match conn.ttl(&key).await {
-                            Ok(ttl) => {
-                                maybe_ttl = Some(ttl);
-                            }
-                            Err(e) => tracing::warn!("rate_limit: ttl read error: {}", e),
-                        }
-
-                        if count as usize > limit {
-                            tracing::warn!("rate limit exceeded key={} ip={}", key, client_ip);
-
-                            // Build a 429 response but still include the rate limit
-                            // headers so clients can see the limits and reset time.
-                            use axum::http::header::{HeaderName, HeaderValue};
-                            use axum::response::IntoResponse;
-
-                            let limit_val = limit.to_string();
-                            let remaining = 0usize;
-                            let reset_secs = maybe_ttl.unwrap_or(60).max(0) as i64;
-
-                            let mut resp = StatusCode::TOO_MANY_REQUESTS.into_response();
-
-                            resp.headers_mut().insert(
-                                HeaderName::from_static("x-ratelimit-limit"),
-                                HeaderValue::from_str(&limit_val)
-                                    .unwrap_or_else(|_| HeaderValue::from_static("")),
-                            );
-
-                            resp.headers_mut().insert(
-                                HeaderName::from_static("x-ratelimit-remaining"),
-                                HeaderValue::from_str(&remaining.to_string())
-                                    .unwrap_or_else(|_| HeaderValue::from_static("0")),
-                            );
-
-                            resp.headers_mut().insert(
-                                HeaderName::from_static("x-ratelimit-reset"),
-                                HeaderValue::from_str(&reset_secs.to_string())
-                                    .unwrap_or_else(|_| HeaderValue::from_static("60")),
-                            );
-
-                            return Ok(resp);
-                        } else if count as usize + 1 >= limit {
-                            // approaching limit
-                            tracing::trace!(
-                                "rate_limit: client approaching limit key={} count={} limit={}",
-                                key,
-                                count,
-                                limit
-                            );
-                        }
-                    }
-                    Err(e) => {
-                        tracing::error!("rate_limit: redis incr error: {}", e);
-                        // allow request on redis error (fail-open)
-                    }
-                }
-            }
-            Err(e) => tracing::warn!(
-                "rate_limit: could not get redis connection for rate limiter: {}",
-                e
-            ),
-        }
-    } else {
-        tracing::debug!("rate_limit: no AppState found in request extensions (skipping redis)");
-    }
-
-    // Run request and attach headers with rate info when available.
-    let mut response = next.run(request).await;
+    let hit = record_hit(&state, &key, tier).await;
 
-    // Always attach rate limit headers (use defaults when Redis was unavailable).
-    use axum::http::header::{HeaderName, HeaderValue};
+    if let Some(hit) = hit.filter(|hit| hit.exceeded(tier)) {
+        tracing::warn!("rate limit exceeded key={} ip={}", key, client_ip);
 
-    let limit_val = limit.to_string();
-    let (remaining_val, reset_val) = if let Some(count) = maybe_count {
-        let remaining = if count as usize >= limit {
-            0
-        } else {
-            limit - count as usize
-        };
-        let reset_secs = maybe_ttl.unwrap_or(60).max(0) as i64;
-        (remaining.to_string(), reset_secs.to_string())
-    } else {
-        // Redis unavailable or not used; provide conservative defaults
-        (limit.to_string(), "60".to_string())
-    };
-
-    response.headers_mut().insert(
-        HeaderName::from_static("x-ratelimit-limit"),
-        HeaderValue::from_str(&limit_val).unwrap_or_else(|_| HeaderValue::from_static("")),
-    );
-
-    response.headers_mut().insert(
-        HeaderName::from_static("x-ratelimit-remaining"),
-        HeaderValue::from_str(&remaining_val).unwrap_or_else(|_| HeaderValue::from_static("0")),
-    );
+        let mut resp = StatusCode::TOO_MANY_REQUESTS.into_response();
+        insert_rate_limit_headers(resp.headers_mut(), tier, Some(hit));
+        resp.headers_mut().insert(
+            header::RETRY_AFTER,
+            HeaderValue::from(hit.reset_secs.max(1)),
+        );
+        return Ok(resp);
+    }
 
-    response.headers_mut().insert(
-        HeaderName::from_static("x-ratelimit-reset"),
-        HeaderValue::from_str(&reset_val).unwrap_or_else(|_| HeaderValue::from_static("60")),
-    );
+    let mut response = next.run(request).await;
+    insert_rate_limit_headers(response.headers_mut(), tier, hit);
 
     Ok(response)
 }
@@ -261,32 +250,135 @@ pub async fn rate_limit_with_state<T: RateLimitConfig>(
     rate_limit_middleware::<T>(request, next).await
 }
 
-// CORS configuration using multiple allowed origins from env
-pub fn cors_layer() -> CorsLayer {
-    let allowed_origins = std::env::var("ALLOWED_ORIGINS")
-        .unwrap_or_else(|_| "http://localhost:3000".to_string())
-        .split(',')
-        .map(|s| s.trim().parse().unwrap())
-        .collect::<Vec<_>>();
+/// Conditional GET for cacheable reads.
+///
+/// A successful GET gets an `ETag` hashed from its body. When the request's
+/// `If-None-Match` lists that tag (or `*`), the body is dropped and the
+/// response becomes `304 Not Modified`. The handler still runs, so polling
+/// clients save bandwidth rather than server work.
+pub async fn conditional_get(request: Request, next: Next) -> Response {
+    if request.method() != Method::GET {
+        return next.run(request).await;
+    }
+    let if_none_match = request.headers().get(header::IF_NONE_MATCH).cloned();
+
+    let response = next.run(request).await;
+    if response.status() != StatusCode::OK {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::error!("Failed to buffer response for ETag: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let etag = etag_for(&bytes);
+    if let Ok(value) = HeaderValue::from_str(&etag) {
+        parts.headers.insert(header::ETAG, value);
+    }
+
+    if if_none_match
+        .as_ref()
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| etag_matches(value, &etag))
+    {
+        parts.status = StatusCode::NOT_MODIFIED;
+        parts.headers.remove(header::CONTENT_LENGTH);
+        return Response::from_parts(parts, Body::empty());
+    }
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+/// Strong ETag for a response body: a quoted, truncated SHA-256
+fn etag_for(body: &[u8]) -> String {
+    format!("\"{}\"", hex::encode(&Sha256::digest(body)[..16]))
+}
+
+/// Whether an `If-None-Match` header value lists `etag`. Weak tags compare by
+/// their opaque part, as the header's weak comparison requires.
+fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    if_none_match.split(',').map(str::trim).any(|candidate| {
+        candidate == "*" || candidate.strip_prefix("W/").unwrap_or(candidate) == etag
+    })
+}
+
+/// Temporary IP bans for abusive clients.
+///
+/// Banned IPs get `403` with `Retry-After` before the request reaches any
+/// handler. Otherwise the request runs, and a `401` (failed auth) or `429`
+/// (rate limited) response counts as a violation against the IP; crossing
+/// `AbuseConfig::threshold` earns a ban. Allowlisted IPs bypass all of this,
+/// and Redis errors fail open.
+pub async fn abuse_guard(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(ip) = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip())
+    else {
+        return next.run(request).await;
+    };
+    if state.config.abuse.is_allowlisted(ip) {
+        return next.run(request).await;
+    }
+
+    let ip = ip.to_string();
+    let tracker = AbuseTracker::new(state.redis.clone(), state.config.abuse.clone());
+
+    match tracker.ban_remaining(&ip).await {
+        Ok(Some(remaining)) => return banned_response(remaining),
+        Ok(None) => {}
+        Err(e) => tracing::warn!("abuse: ban check failed for {}: {}", ip, e),
+    }
+
+    let response = next.run(request).await;
+
+    let violation = match response.status() {
+        StatusCode::UNAUTHORIZED => Violation::FailedAuth,
+        StatusCode::TOO_MANY_REQUESTS => Violation::RateLimited,
+        _ => return response,
+    };
+    if let Err(e) = tracker.record_violation(&ip, violation).await {
+        tracing::warn!("abuse: failed to record violation for {}: {}", ip, e);
+    }
+
+    response
+}
+
+fn banned_response(retry_after: u64) -> Response {
+    let mut resp = (
+        StatusCode::FORBIDDEN,
+        "Too many failed or rejected requests; try again later",
+    )
+        .into_response();
+    resp.headers_mut()
+        .insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+    resp
+}
 
-    tracing::info!("CORS allowed origins: {:?}", allowed_origins);
+/// CORS layer built from the configured policy.
+///
+/// With `allow_any_origin` the request origin is echoed back, since a literal
+/// `*` can't be combined with credentials.
+pub fn cors_layer(config: &CorsConfig) -> CorsLayer {
+    let origins = if config.allow_any_origin {
+        tracing::warn!("CORS allows any origin; do not use this in production");
+        AllowOrigin::mirror_request()
+    } else {
+        tracing::info!("CORS allowed origins: {:?}", config.allowed_origins);
+        AllowOrigin::list(config.allowed_origins.clone())
+    };
 
     CorsLayer::new()
-        .allow_origin(allowed_origins)
-        .allow_methods([
-            axum::http::Method::GET,
-            axum::http::Method::POST,
-            axum::http::Method::PUT,
-            axum::http::Method::PATCH,
-            axum::http::Method::DELETE,
-            axum::http::Method::OPTIONS,
-        ])
-        .allow_headers([
-            axum::http::header::AUTHORIZATION,
-            axum::http::header::CONTENT_TYPE,
-            axum::http::header::ACCEPT,
-            axum::http::header::COOKIE,
-        ])
+        .allow_origin(origins)
+        .allow_methods(config.allowed_methods.clone())
+        .allow_headers(config.allowed_headers.clone())
         .allow_credentials(true)
         .max_age(Duration::from_secs(3600))
 }
@@ -294,61 +386,20 @@ pub fn cors_layer() -> CorsLayer {
 /// Programmatic rate-limit check that can be called from non-middleware paths
 /// (for example, before performing a WebSocket upgrade). It applies the
 /// same counting rules as `rate_limit_middleware` and returns an Err with
-/// `(StatusCode, String)` when the limit is exceeded or on fatal Redis errors.
+/// `(StatusCode, String)` when the limit is exceeded.
 pub async fn check_rate_limit<T: RateLimitConfig>(
     state: &AppState,
     client_ip: &str,
     user_id_opt: Option<Uuid>,
 ) -> Result<(), (StatusCode, String)> {
-    // determine key and limit
-    let (key, limit) = match T::name() {
-        "API" => {
-            if let Some(user_id) = user_id_opt {
-                (RedisKey::rate_user_auth(user_id), 300)
-            } else {
-                (RedisKey::rate_user_ip(client_ip), 60)
-            }
-        }
-        "Auth" | "Strict" => {
-            if let Some(user_id) = user_id_opt {
-                (RedisKey::rate_user_strict(user_id), 30)
-            } else {
-                (RedisKey::rate_user_ip(client_ip), 30)
-            }
-        }
-        _ => (RedisKey::rate_user_ip(client_ip), 60),
-    };
-
-    match state.redis.get().await {
-        Ok(mut conn) => {
-            // INCR and set EXPIRE to 60s when count == 1
-            let count_res: redis::RedisResult<i64> = conn.incr(&key, 1).await;
-            match count_res {
-                Ok(count) => {
-                    if count == 1 {
-                        let _: redis::RedisResult<bool> = conn.expire(&key, 60).await;
-                    }
-
-                    if count as usize > limit {
-                        return Err((
-                            StatusCode::TOO_MANY_REQUESTS,
-                            "rate limit exceeded".to_string(),
-                        ));
-                    }
-
-                    Ok(())
-                }
-                Err(e) => {
-                    tracing::error!("rate_limit: redis incr error: {}", e);
-                    // fail-open: allow request when Redis has transient errors
-                    Ok(())
-                }
-            }
-        }
-        Err(e) => {
-            tracing::warn!("rate_limit: could not get redis connection: {}", e);
-            // fail-open on missing Redis
-            Ok(())
-        }
+    let (key, tier) = rate_limit_key::<T>(&state.config.rate_limits, client_ip, user_id_opt);
+
+    match record_hit(state, &key, tier).await {
+        Some(hit) if hit.exceeded(tier) => Err((
+            StatusCode::TOO_MANY_REQUESTS,
+            format!("rate limit exceeded, retry in {}s", hit.reset_secs.max(1)),
+        )),
+        // fail-open when Redis is unavailable
+        _ => Ok(()),
     }
 }
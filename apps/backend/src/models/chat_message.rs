@@ -43,23 +43,106 @@ impl ChatMessage {
         })
     }
 
-    /// Add a reaction to this message
-    pub fn add_reaction(&mut self, user_id: Uuid, emoji: &str) {
-        // Remove existing reaction from this user for this emoji
-        self.reactions
-            .retain(|r| !(r.user_id == user_id && r.emoji == emoji));
+    /// Add a reaction to this message; returns false if it was already there
+    pub fn add_reaction(&mut self, user_id: Uuid, emoji: &str) -> bool {
+        if self.has_reaction(user_id, emoji) {
+            return false;
+        }
 
         self.reactions.push(Reaction {
             user_id,
             emoji: emoji.to_string(),
         });
+        true
     }
 
-    /// Remove a reaction from this message
-    pub fn remove_reaction(&mut self, user_id: Uuid, emoji: &str) {
+    /// Remove a reaction from this message; returns false if there was none
+    pub fn remove_reaction(&mut self, user_id: Uuid, emoji: &str) -> bool {
+        let before = self.reactions.len();
         self.reactions
             .retain(|r| !(r.user_id == user_id && r.emoji == emoji));
+        self.reactions.len() != before
+    }
+
+    fn has_reaction(&self, user_id: Uuid, emoji: &str) -> bool {
+        self.reactions
+            .iter()
+            .any(|r| r.user_id == user_id && r.emoji == emoji)
     }
+
+    /// Reaction counts per emoji, in the order each emoji was first used.
+    ///
+    /// `reacted` marks the emojis `viewer` reacted with.
+    pub fn reaction_summary(&self, viewer: Option<Uuid>) -> Vec<ReactionSummary> {
+        let mut summary: Vec<ReactionSummary> = Vec::new();
+        for reaction in &self.reactions {
+            let mine = viewer == Some(reaction.user_id);
+            match summary.iter_mut().find(|s| s.emoji == reaction.emoji) {
+                Some(entry) => {
+                    entry.count += 1;
+                    entry.reacted |= mine;
+                }
+                None => summary.push(ReactionSummary {
+                    emoji: reaction.emoji.clone(),
+                    count: 1,
+                    reacted: mine,
+                }),
+            }
+        }
+        summary
+    }
+}
+
+/// A chat message as one user sees it: with reaction counts, and which of
+/// them are theirs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatMessageView {
+    #[serde(flatten)]
+    pub message: ChatMessage,
+    pub reaction_summary: Vec<ReactionSummary>,
+}
+
+impl ChatMessageView {
+    pub fn new(message: ChatMessage, viewer: Option<Uuid>) -> Self {
+        let reaction_summary = message.reaction_summary(viewer);
+        Self {
+            message,
+            reaction_summary,
+        }
+    }
+}
+
+/// How many users reacted to a message with one emoji.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReactionSummary {
+    pub emoji: String,
+    pub count: usize,
+    /// Whether the viewing user is one of them
+    pub reacted: bool,
+}
+
+/// Where to page a lobby's chat history from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatCursor {
+    /// Messages older than this one
+    Before(Uuid),
+    /// Messages newer than this one
+    After(Uuid),
+}
+
+/// A page of chat history, oldest message first.
+///
+/// `next_cursor` continues in the direction that was paged (the oldest
+/// message going back, the newest going forward) and is set only while
+/// `has_more` is.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatPage {
+    pub messages: Vec<ChatMessageView>,
+    pub next_cursor: Option<Uuid>,
+    pub has_more: bool,
 }
 
 /// Reaction to a chat message
@@ -89,3 +172,57 @@ pub enum ChatMessageError {
     #[error("Message too long: maximum {max} characters")]
     MessageTooLong { max: usize },
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reactions_are_idempotent() {
+        let mut message = ChatMessage::new(Uuid::new_v4(), Uuid::new_v4(), "gg", None).unwrap();
+        let user = Uuid::new_v4();
+
+        assert!(message.add_reaction(user, "fire"));
+        assert!(!message.add_reaction(user, "fire"));
+        assert_eq!(message.reactions.len(), 1);
+
+        assert!(message.remove_reaction(user, "fire"));
+        assert!(!message.remove_reaction(user, "fire"));
+        assert!(message.reactions.is_empty());
+    }
+
+    #[test]
+    fn test_reaction_summary_counts_per_emoji() {
+        let mut message = ChatMessage::new(Uuid::new_v4(), Uuid::new_v4(), "gg", None).unwrap();
+        let (alice, bob, carol) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        message.add_reaction(alice, "fire");
+        message.add_reaction(bob, "heart");
+        message.add_reaction(bob, "fire");
+        message.add_reaction(carol, "fire");
+
+        let summary = message.reaction_summary(Some(bob));
+        assert_eq!(
+            summary,
+            vec![
+                ReactionSummary {
+                    emoji: "fire".into(),
+                    count: 3,
+                    reacted: true,
+                },
+                ReactionSummary {
+                    emoji: "heart".into(),
+                    count: 1,
+                    reacted: true,
+                },
+            ]
+        );
+
+        let anonymous = message.reaction_summary(None);
+        assert!(anonymous.iter().all(|s| !s.reacted));
+        let for_carol = message.reaction_summary(Some(carol));
+        assert_eq!(
+            for_carol.iter().map(|s| s.reacted).collect::<Vec<_>>(),
+            vec![true, false]
+        );
+    }
+}
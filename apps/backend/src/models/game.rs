@@ -52,6 +52,30 @@ impl Game {
         }
         Ok((min_players, max_players))
     }
+
+    /// Validate path format (1-50 chars, lowercase alphanumeric + hyphens).
+    pub fn validate_path(path: &str) -> Result<(), GamePathError> {
+        if !path
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        {
+            return Err(GamePathError::InvalidCharacters);
+        }
+        if path.is_empty() || path.len() > 50 {
+            return Err(GamePathError::InvalidLength);
+        }
+        Ok(())
+    }
+}
+
+/// Game path validation errors.
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum GamePathError {
+    #[error("Game path must be lowercase alphanumeric with hyphens only")]
+    InvalidCharacters,
+
+    #[error("Game path must be between 1-50 characters")]
+    InvalidLength,
 }
 
 /// Player count validation errors.
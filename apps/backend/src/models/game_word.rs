@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// A valid word played during a lexi_wars game.
+///
+/// `turn` counts accepted words from 1, so ordering by it replays the game.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct GameWord {
+    pub id: Uuid,
+    pub lobby_id: Uuid,
+    pub user_id: Uuid,
+    pub word: String,
+    pub turn: i32,
+    pub played_at: NaiveDateTime,
+}
+
+/// How many words one player got in.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerWordCount {
+    pub user_id: Uuid,
+    pub words: usize,
+}
+
+/// Aggregates over the words played in one game.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameWordStats {
+    pub total_words: usize,
+    /// Earliest of the longest words, by turn
+    pub longest_word: Option<GameWord>,
+    /// Player with the most words; ties go to whoever played first
+    pub most_words: Option<PlayerWordCount>,
+    /// Every player's count, most first
+    pub by_player: Vec<PlayerWordCount>,
+}
+
+impl GameWordStats {
+    /// Compute stats from words in turn order.
+    pub fn from_words(words: &[GameWord]) -> Self {
+        let longest_word = words
+            .iter()
+            .fold(None::<&GameWord>, |best, w| match best {
+                Some(b) if b.word.chars().count() >= w.word.chars().count() => Some(b),
+                _ => Some(w),
+            })
+            .cloned();
+
+        // Count per player, remembering who played first for tie-breaks
+        let mut counts: HashMap<Uuid, (usize, usize)> = HashMap::new();
+        for (i, w) in words.iter().enumerate() {
+            counts.entry(w.user_id).or_insert((0, i)).0 += 1;
+        }
+        let mut by_player: Vec<(Uuid, usize, usize)> = counts
+            .into_iter()
+            .map(|(user_id, (words, first))| (user_id, words, first))
+            .collect();
+        by_player.sort_by(|a, b| b.1.cmp(&a.1).then(a.2.cmp(&b.2)));
+
+        let by_player: Vec<PlayerWordCount> = by_player
+            .into_iter()
+            .map(|(user_id, words, _)| PlayerWordCount { user_id, words })
+            .collect();
+
+        Self {
+            total_words: words.len(),
+            longest_word,
+            most_words: by_player.first().cloned(),
+            by_player,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(user_id: Uuid, word: &str, turn: i32) -> GameWord {
+        GameWord {
+            id: Uuid::new_v4(),
+            lobby_id: Uuid::nil(),
+            user_id,
+            word: word.to_string(),
+            turn,
+            played_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+
+    #[test]
+    fn test_longest_word_prefers_earliest_on_tie() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let words = vec![
+            word(a, "cats", 1),
+            word(b, "zebras", 2),
+            word(a, "planet", 3),
+            word(b, "dogs", 4),
+        ];
+
+        let stats = GameWordStats::from_words(&words);
+        let longest = stats.longest_word.unwrap();
+        assert_eq!(longest.word, "zebras");
+        assert_eq!(longest.turn, 2);
+        assert_eq!(stats.total_words, 4);
+    }
+
+    #[test]
+    fn test_most_words_by_player() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let words = vec![
+            word(b, "tree", 1),
+            word(a, "apple", 2),
+            word(b, "river", 3),
+            word(a, "stone", 4),
+            word(b, "cloud", 5),
+        ];
+
+        let stats = GameWordStats::from_words(&words);
+        assert_eq!(
+            stats.by_player,
+            vec![
+                PlayerWordCount {
+                    user_id: b,
+                    words: 3
+                },
+                PlayerWordCount {
+                    user_id: a,
+                    words: 2
+                },
+            ]
+        );
+        assert_eq!(stats.most_words.unwrap().user_id, b);
+    }
+
+    #[test]
+    fn test_empty_game_has_no_aggregates() {
+        let stats = GameWordStats::from_words(&[]);
+        assert_eq!(stats.total_words, 0);
+        assert!(stats.longest_word.is_none());
+        assert!(stats.most_words.is_none());
+    }
+}
@@ -35,10 +35,92 @@ impl fmt::Display for KeyPart {
     }
 }
 
+/// What a Redis key holds, as far as expiry is concerned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyCategory {
+    LobbyState,
+    LobbyPlayer,
+    LobbyJoinRequests,
+    LobbyInvite,
+    LobbyCountdown,
+    /// The chat index and individual chat messages
+    LobbyChat,
+    GameSummary,
+}
+
+/// TTLs for each key category.
+///
+/// Keys outside every category (caches, rate limits, bans) set their own
+/// expiry or are meant to live forever.
+pub struct ExpiryPolicy;
+
+impl ExpiryPolicy {
+    /// Lobby and player state; live lobbies keep pushing this out, and
+    /// finished ones with unclaimed prizes are held until the last claim
+    pub const LOBBY_TTL_SECS: i64 = 6 * 60 * 60;
+    /// Pending join requests lapse unless the player asks again
+    pub const JOIN_REQUEST_TTL_SECS: i64 = 15 * 60;
+    /// Chat is temporary, but outlives the lobby so it can still be read
+    pub const CHAT_TTL_SECS: i64 = 24 * 60 * 60;
+    /// Long enough for any invite lifetime a creator is likely to pick
+    pub const INVITE_TTL_SECS: i64 = 7 * 24 * 60 * 60;
+    /// Only needs to outlast a countdown tick so clients can pick it up
+    pub const COUNTDOWN_TTL_SECS: i64 = 60;
+    /// Final standings for players reconnecting after the game ended
+    pub const GAME_SUMMARY_TTL_SECS: i64 = 24 * 60 * 60;
+
+    /// TTL for keys in `category`, in seconds.
+    pub fn ttl_secs(category: KeyCategory) -> i64 {
+        match category {
+            KeyCategory::LobbyState | KeyCategory::LobbyPlayer => Self::LOBBY_TTL_SECS,
+            KeyCategory::LobbyJoinRequests => Self::JOIN_REQUEST_TTL_SECS,
+            KeyCategory::LobbyChat => Self::CHAT_TTL_SECS,
+            KeyCategory::LobbyInvite => Self::INVITE_TTL_SECS,
+            KeyCategory::LobbyCountdown => Self::COUNTDOWN_TTL_SECS,
+            KeyCategory::GameSummary => Self::GAME_SUMMARY_TTL_SECS,
+        }
+    }
+
+    /// TTL for `key`, if it belongs to a category.
+    pub fn ttl_for_key(key: &str) -> Option<i64> {
+        RedisKey::category(key).map(Self::ttl_secs)
+    }
+
+    /// Whether a lobby key with `remaining` seconds to live is due a refresh.
+    ///
+    /// Refreshing once half the TTL has gone keeps activity from costing a
+    /// round of EXPIREs every time.
+    pub fn lobby_refresh_due(remaining: i64) -> bool {
+        remaining < Self::LOBBY_TTL_SECS / 2
+    }
+}
+
 /// Helper to build Redis keys consistently.
 pub struct RedisKey;
 
 impl RedisKey {
+    /// Category of a key built by one of the helpers below, if it has one.
+    pub fn category(key: &str) -> Option<KeyCategory> {
+        let parts: Vec<&str> = key.split(':').collect();
+        match parts.as_slice() {
+            ["lobbies", _, "state"]
+            | ["lobbies", _, "predictions"]
+            | ["lobbies", _, "pseudonyms"]
+            | ["lobbies", _, "ready"]
+            | ["lobbies", _, "deposits"]
+            | ["lobbies", _, "connections"] => Some(KeyCategory::LobbyState),
+            ["lobbies", _, "players", _] => Some(KeyCategory::LobbyPlayer),
+            ["lobbies", _, "join_requests"] => Some(KeyCategory::LobbyJoinRequests),
+            ["lobbies", _, "invites", _] => Some(KeyCategory::LobbyInvite),
+            ["lobbies", _, "countdown"] => Some(KeyCategory::LobbyCountdown),
+            ["lobbies", _, "chat"]
+            | ["lobbies", _, "chat", "messages", _]
+            | ["lobbies", _, "chat", "reads"] => Some(KeyCategory::LobbyChat),
+            ["game", _, "state"] => Some(KeyCategory::GameSummary),
+            _ => None,
+        }
+    }
+
     /// Build a key from arbitrary parts joined by ':'
     pub fn build(parts: &[KeyPart]) -> String {
         parts
@@ -94,6 +176,20 @@ impl RedisKey {
         ])
     }
 
+    /// Pub/sub channel announcing changes to a user's join request
+    /// (pattern: `lobbies:{lobby_id}:join_requests:{user_id}`).
+    pub fn join_request_channel(
+        lobby_id: impl Into<KeyPart>,
+        user_id: impl Into<KeyPart>,
+    ) -> String {
+        Self::build(&[
+            KeyPart::Str("lobbies".to_string()),
+            lobby_id.into(),
+            KeyPart::Str("join_requests".to_string()),
+            user_id.into(),
+        ])
+    }
+
     /// Key for lobby join requests (hash keyed by user id)
     pub fn lobby_join_requests(lobby_id: impl Into<KeyPart>) -> String {
         Self::build(&[
@@ -103,6 +199,16 @@ impl RedisKey {
         ])
     }
 
+    /// Key for a lobby invite record (pattern: `lobbies:{lobby_id}:invites:{invite_id}`).
+    pub fn lobby_invite(lobby_id: impl Into<KeyPart>, invite_id: impl Into<KeyPart>) -> String {
+        Self::build(&[
+            KeyPart::Str("lobbies".to_string()),
+            lobby_id.into(),
+            KeyPart::Str("invites".to_string()),
+            invite_id.into(),
+        ])
+    }
+
     /// Key for lobby countdown state
     pub fn lobby_countdown(lobby_id: impl Into<KeyPart>) -> String {
         Self::build(&[
@@ -112,6 +218,16 @@ impl RedisKey {
         ])
     }
 
+    /// Players who readied up for the lobby's start, set of user IDs
+    /// (pattern: `lobbies:{lobby_id}:ready`).
+    pub fn lobby_ready(lobby_id: impl Into<KeyPart>) -> String {
+        Self::build(&[
+            KeyPart::Str("lobbies".to_string()),
+            lobby_id.into(),
+            KeyPart::Str("ready".to_string()),
+        ])
+    }
+
     /// Key for lobby chat messages sorted set (pattern: `lobbies:{lobby_id}:chat`).
     /// Uses Redis sorted set with timestamp as score for chronological ordering.
     pub fn lobby_chat(lobby_id: impl Into<KeyPart>) -> String {
@@ -136,33 +252,188 @@ impl RedisKey {
         ])
     }
 
-    /// Rate limiter key for unauthenticated users by IP.
-    pub fn rate_user_ip(ip: &str) -> String {
+    /// Key for each user's last-read chat message (pattern: `lobbies:{lobby_id}:chat:reads`).
+    /// Hash of user_id -> message_id.
+    pub fn lobby_chat_reads(lobby_id: impl Into<KeyPart>) -> String {
+        Self::build(&[
+            KeyPart::Str("lobbies".to_string()),
+            lobby_id.into(),
+            KeyPart::Str("chat".to_string()),
+            KeyPart::Str("reads".to_string()),
+        ])
+    }
+
+    /// Spectators' predicted winners, hash of user id to predicted winner id
+    /// (pattern: `lobbies:{lobby_id}:predictions`).
+    pub fn lobby_predictions(lobby_id: impl Into<KeyPart>) -> String {
+        Self::build(&[
+            KeyPart::Str("lobbies".to_string()),
+            lobby_id.into(),
+            KeyPart::Str("predictions".to_string()),
+        ])
+    }
+
+    /// Pseudonyms of an anonymized lobby's players, hash of user id to the
+    /// player's pseudonym and identities (pattern: `lobbies:{lobby_id}:pseudonyms`).
+    pub fn lobby_pseudonyms(lobby_id: impl Into<KeyPart>) -> String {
+        Self::build(&[
+            KeyPart::Str("lobbies".to_string()),
+            lobby_id.into(),
+            KeyPart::Str("pseudonyms".to_string()),
+        ])
+    }
+
+    /// Entry deposits seen on the lobby's vault contract, hash of wallet to tx id
+    /// (pattern: `lobbies:{lobby_id}:deposits`).
+    pub fn lobby_deposits(lobby_id: impl Into<KeyPart>) -> String {
+        Self::build(&[
+            KeyPart::Str("lobbies".to_string()),
+            lobby_id.into(),
+            KeyPart::Str("deposits".to_string()),
+        ])
+    }
+
+    /// Room connections open to the lobby on any instance, sorted set of
+    /// connection ID by when it was last seen (pattern: `lobbies:{lobby_id}:connections`).
+    pub fn lobby_connections(lobby_id: impl Into<KeyPart>) -> String {
+        Self::build(&[
+            KeyPart::Str("lobbies".to_string()),
+            lobby_id.into(),
+            KeyPart::Str("connections".to_string()),
+        ])
+    }
+
+    /// Winners yet to claim their prize, set of user IDs; while it exists the
+    /// lobby's state and player keys don't expire (pattern: `lobbies:{lobby_id}:unclaimed`).
+    pub fn lobby_unclaimed(lobby_id: impl Into<KeyPart>) -> String {
+        Self::build(&[
+            KeyPart::Str("lobbies".to_string()),
+            lobby_id.into(),
+            KeyPart::Str("unclaimed".to_string()),
+        ])
+    }
+
+    /// Key for a finished game's summary (pattern: `game:{lobby_id}:state`).
+    pub fn game_summary(lobby_id: impl Into<KeyPart>) -> String {
+        Self::build(&[
+            KeyPart::Str("game".to_string()),
+            lobby_id.into(),
+            KeyPart::Str("state".to_string()),
+        ])
+    }
+
+    /// Key for a game's quick-play queue (pattern: `matchmaking:{game_id}:queue`).
+    /// Sorted set of user ids scored by enqueue timestamp.
+    pub fn matchmaking_queue(game_id: impl Into<KeyPart>) -> String {
+        Self::build(&[
+            KeyPart::Str("matchmaking".to_string()),
+            game_id.into(),
+            KeyPart::Str("queue".to_string()),
+        ])
+    }
+
+    /// Key for quick-play queue entries (hash keyed by user id, pattern: `matchmaking:{game_id}:entries`).
+    pub fn matchmaking_entries(game_id: impl Into<KeyPart>) -> String {
+        Self::build(&[
+            KeyPart::Str("matchmaking".to_string()),
+            game_id.into(),
+            KeyPart::Str("entries".to_string()),
+        ])
+    }
+
+    /// Key for who a user was recently matched with in paid quick play
+    /// (pattern: `users:{user_id}:matched_with`). Sorted set of
+    /// `{lobby_id}:{other_user_id}` scored by match timestamp.
+    pub fn user_matched_with(user_id: impl Into<KeyPart>) -> String {
+        Self::build(&[
+            KeyPart::Str("users".to_string()),
+            user_id.into(),
+            KeyPart::Str("matched_with".to_string()),
+        ])
+    }
+
+    /// Key for a user's win/daily streaks (pattern: `users:{user_id}:streaks`).
+    pub fn user_streaks(user_id: impl Into<KeyPart>) -> String {
+        Self::build(&[
+            KeyPart::Str("users".to_string()),
+            user_id.into(),
+            KeyPart::Str("streaks".to_string()),
+        ])
+    }
+
+    /// Lobbies a user is watching, with their open connection count per lobby
+    /// (pattern: `users:{user_id}:spectating`).
+    pub fn user_spectating(user_id: impl Into<KeyPart>) -> String {
+        Self::build(&[
+            KeyPart::Str("users".to_string()),
+            user_id.into(),
+            KeyPart::Str("spectating".to_string()),
+        ])
+    }
+
+    /// Unix time before which all of a user's tokens are revoked
+    /// (pattern: `users:{user_id}:sessions_revoked_at`).
+    pub fn user_sessions_revoked(user_id: impl Into<KeyPart>) -> String {
+        Self::build(&[
+            KeyPart::Str("users".to_string()),
+            user_id.into(),
+            KeyPart::Str("sessions_revoked_at".to_string()),
+        ])
+    }
+
+    /// Wallet an outstanding sign-in challenge was issued to
+    /// (pattern: `auth_challenges:{nonce}`).
+    pub fn auth_challenge(nonce: &str) -> String {
+        Self::build(&[
+            KeyPart::Str("auth_challenges".to_string()),
+            KeyPart::Str(nonce.to_string()),
+        ])
+    }
+
+    /// Rate limiter counter for an anonymous caller (pattern: `rate:{tier}:ip:{ip}`).
+    pub fn rate_ip(tier: &str, ip: &str) -> String {
         Self::build(&[
             KeyPart::Str("rate".to_string()),
-            KeyPart::Str("user".to_string()),
+            KeyPart::Str(tier.to_string()),
             KeyPart::Str("ip".to_string()),
             KeyPart::Str(ip.to_string()),
         ])
     }
 
-    /// Rate limiter key for authenticated users (public APIs).
-    pub fn rate_user_auth(user_id: impl Into<KeyPart>) -> String {
+    /// Rate limiter counter for a signed-in caller (pattern: `rate:{tier}:user:{user_id}`).
+    pub fn rate_user(tier: &str, user_id: impl Into<KeyPart>) -> String {
         Self::build(&[
             KeyPart::Str("rate".to_string()),
+            KeyPart::Str(tier.to_string()),
             KeyPart::Str("user".to_string()),
-            KeyPart::Str("auth".to_string()),
             user_id.into(),
         ])
     }
 
-    /// Rate limiter key for strict/write operations (authenticated users).
-    pub fn rate_user_strict(user_id: impl Into<KeyPart>) -> String {
+    /// Recent abuse violations by an IP, scored by time (pattern: `abuse:{ip}:violations`).
+    pub fn abuse_violations(ip: &str) -> String {
         Self::build(&[
-            KeyPart::Str("rate".to_string()),
-            KeyPart::Str("user".to_string()),
-            KeyPart::Str("strict".to_string()),
-            user_id.into(),
+            KeyPart::Str("abuse".to_string()),
+            KeyPart::Str(ip.to_string()),
+            KeyPart::Str("violations".to_string()),
+        ])
+    }
+
+    /// Number of times an IP has been banned recently (pattern: `abuse:{ip}:offenses`).
+    pub fn abuse_offenses(ip: &str) -> String {
+        Self::build(&[
+            KeyPart::Str("abuse".to_string()),
+            KeyPart::Str(ip.to_string()),
+            KeyPart::Str("offenses".to_string()),
+        ])
+    }
+
+    /// Active temporary ban for an IP; expires with the ban (pattern: `abuse:{ip}:ban`).
+    pub fn abuse_ban(ip: &str) -> String {
+        Self::build(&[
+            KeyPart::Str("abuse".to_string()),
+            KeyPart::Str(ip.to_string()),
+            KeyPart::Str("ban".to_string()),
         ])
     }
 
@@ -173,4 +444,223 @@ impl RedisKey {
             KeyPart::Str(jti.to_string()),
         ])
     }
+
+    /// Prediction points won by each user, sorted set scored by points
+    /// (pattern: `predictions:points`).
+    pub fn prediction_points() -> String {
+        Self::build(&[
+            KeyPart::Str("predictions".to_string()),
+            KeyPart::Str("points".to_string()),
+        ])
+    }
+
+    /// Platform-wide announcements, sorted set of JSON scored by expiry in ms
+    /// (pattern: `system:announcements`).
+    pub fn announcements() -> String {
+        Self::build(&[
+            KeyPart::Str("system".to_string()),
+            KeyPart::Str("announcements".to_string()),
+        ])
+    }
+
+    /// Maintenance mode status, present only while it is on (pattern: `system:maintenance`).
+    pub fn maintenance() -> String {
+        Self::build(&[
+            KeyPart::Str("system".to_string()),
+            KeyPart::Str("maintenance".to_string()),
+        ])
+    }
+
+    /// Admin feature flag overrides, hash of feature name to "1"/"0"
+    /// (pattern: `system:feature_flags`).
+    pub fn feature_flags() -> String {
+        Self::build(&[
+            KeyPart::Str("system".to_string()),
+            KeyPart::Str("feature_flags".to_string()),
+        ])
+    }
+
+    /// Wallets admins have allowed in, a set (pattern: `system:wallet_access:allow`).
+    pub fn wallet_allowlist() -> String {
+        Self::build(&[
+            KeyPart::Str("system".to_string()),
+            KeyPart::Str("wallet_access".to_string()),
+            KeyPart::Str("allow".to_string()),
+        ])
+    }
+
+    /// Wallets admins have denied, a set (pattern: `system:wallet_access:deny`).
+    pub fn wallet_denylist() -> String {
+        Self::build(&[
+            KeyPart::Str("system".to_string()),
+            KeyPart::Str("wallet_access".to_string()),
+            KeyPart::Str("deny".to_string()),
+        ])
+    }
+
+    /// Admin override of allowlist-only mode, "1"/"0"
+    /// (pattern: `system:wallet_access:allowlist_only`).
+    pub fn wallet_allowlist_only() -> String {
+        Self::build(&[
+            KeyPart::Str("system".to_string()),
+            KeyPart::Str("wallet_access".to_string()),
+            KeyPart::Str("allowlist_only".to_string()),
+        ])
+    }
+
+    /// Lexi Wars words when the dictionary is kept in Redis, a set
+    /// (pattern: `system:lexi_wars:dictionary`).
+    pub fn lexi_wars_dictionary() -> String {
+        Self::build(&[
+            KeyPart::Str("system".to_string()),
+            KeyPart::Str("lexi_wars".to_string()),
+            KeyPart::Str("dictionary".to_string()),
+        ])
+    }
+
+    /// Cached token metadata (pattern: `token_info:{contract_id}`).
+    pub fn token_info(contract_id: &str) -> String {
+        Self::build(&[
+            KeyPart::Str("token_info".to_string()),
+            KeyPart::Str(contract_id.to_string()),
+        ])
+    }
+
+    /// Single-flight guard for refreshing a token's metadata (pattern: `token_info:{contract_id}:lock`).
+    pub fn token_info_lock(contract_id: &str) -> String {
+        Self::build(&[
+            KeyPart::Str("token_info".to_string()),
+            KeyPart::Str(contract_id.to_string()),
+            KeyPart::Str("lock".to_string()),
+        ])
+    }
+
+    /// Distributed lock guarding `resource` (pattern: `locks:{resource}`).
+    pub fn lock(resource: &str) -> String {
+        Self::build(&[
+            KeyPart::Str("locks".to_string()),
+            KeyPart::Str(resource.to_string()),
+        ])
+    }
+
+    /// Ids of a contract's events the indexer has applied, a set
+    /// (pattern: `contract_events:{contract}:seen`).
+    pub fn contract_events_seen(contract: &str) -> String {
+        Self::build(&[
+            KeyPart::Str("contract_events".to_string()),
+            KeyPart::Str(contract.to_string()),
+            KeyPart::Str("seen".to_string()),
+        ])
+    }
+
+    /// Last key each hydration phase finished, hash keyed by phase
+    /// (pattern: `hydration:checkpoint`).
+    pub fn hydration_checkpoint() -> String {
+        Self::build(&[
+            KeyPart::Str("hydration".to_string()),
+            KeyPart::Str("checkpoint".to_string()),
+        ])
+    }
+
+    /// Cached current season (pattern: `season:current`).
+    pub fn current_season() -> String {
+        Self::build(&[
+            KeyPart::Str("season".to_string()),
+            KeyPart::Str("current".to_string()),
+        ])
+    }
+
+    /// Cached platform stats overview (pattern: `stats:overview`).
+    pub fn stats_overview() -> String {
+        Self::build(&[
+            KeyPart::Str("stats".to_string()),
+            KeyPart::Str("overview".to_string()),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_keys_map_to_categories() {
+        let lobby_id = Uuid::new_v4();
+        let other_id = Uuid::new_v4();
+
+        let cases = [
+            (
+                RedisKey::lobby_state(lobby_id),
+                Some(KeyCategory::LobbyState),
+            ),
+            (
+                RedisKey::lobby_player(lobby_id, other_id),
+                Some(KeyCategory::LobbyPlayer),
+            ),
+            (
+                RedisKey::lobby_join_requests(lobby_id),
+                Some(KeyCategory::LobbyJoinRequests),
+            ),
+            (
+                RedisKey::lobby_invite(lobby_id, other_id),
+                Some(KeyCategory::LobbyInvite),
+            ),
+            (
+                RedisKey::lobby_countdown(lobby_id),
+                Some(KeyCategory::LobbyCountdown),
+            ),
+            (RedisKey::lobby_chat(lobby_id), Some(KeyCategory::LobbyChat)),
+            (
+                RedisKey::lobby_chat_message(lobby_id, other_id),
+                Some(KeyCategory::LobbyChat),
+            ),
+            (
+                RedisKey::lobby_chat_reads(lobby_id),
+                Some(KeyCategory::LobbyChat),
+            ),
+            (
+                RedisKey::lobby_predictions(lobby_id),
+                Some(KeyCategory::LobbyState),
+            ),
+            (
+                RedisKey::lobby_ready(lobby_id),
+                Some(KeyCategory::LobbyState),
+            ),
+            (
+                RedisKey::lobby_pseudonyms(lobby_id),
+                Some(KeyCategory::LobbyState),
+            ),
+            (
+                RedisKey::lobby_connections(lobby_id),
+                Some(KeyCategory::LobbyState),
+            ),
+            (
+                RedisKey::game_summary(lobby_id),
+                Some(KeyCategory::GameSummary),
+            ),
+            (RedisKey::lobby_unclaimed(lobby_id), None),
+            (RedisKey::prediction_points(), None),
+            (RedisKey::user_streaks(lobby_id), None),
+            (RedisKey::token_info("SP000.token"), None),
+            (RedisKey::lobby(lobby_id), None),
+        ];
+
+        for (key, category) in cases {
+            assert_eq!(RedisKey::category(&key), category, "{}", key);
+        }
+    }
+
+    #[test]
+    fn test_lobby_refresh_waits_for_half_the_ttl() {
+        assert_eq!(
+            ExpiryPolicy::ttl_for_key(&RedisKey::lobby_state(Uuid::new_v4())),
+            Some(ExpiryPolicy::LOBBY_TTL_SECS)
+        );
+        assert!(!ExpiryPolicy::lobby_refresh_due(
+            ExpiryPolicy::LOBBY_TTL_SECS - 60
+        ));
+        assert!(ExpiryPolicy::lobby_refresh_due(60));
+        // No TTL at all (-1) or no key (-2)
+        assert!(ExpiryPolicy::lobby_refresh_due(-1));
+    }
 }
@@ -1,11 +1,22 @@
 use chrono::NaiveDateTime;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sqlx::prelude::FromRow;
 use uuid::Uuid;
 
-use super::WalletAddress;
+use super::{PayoutTable, PayoutUnderfill, WalletAddress, money::floor_to_micro};
 use crate::models::{Game, LobbyState, LobbyStatus, User};
 
+/// Strength of the bot seated in a practice lobby.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy, sqlx::Type)]
+#[sqlx(type_name = "bot_difficulty", rename_all = "snake_case")]
+#[serde(rename_all = "camelCase")]
+pub enum BotDifficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
 /// Lobby model mapping to the `lobbies` table (room metadata and status).
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 #[serde(rename_all = "camelCase")]
@@ -18,13 +29,27 @@ pub struct Lobby {
     pub game_id: Uuid,
     pub game_path: String,
     pub creator_id: Uuid,
-    pub entry_amount: Option<f64>,
-    pub current_amount: Option<f64>,
+    #[serde(with = "rust_decimal::serde::float_option")]
+    pub entry_amount: Option<Decimal>,
+    #[serde(with = "rust_decimal::serde::float_option")]
+    pub current_amount: Option<Decimal>,
     pub token_symbol: Option<String>,
     pub token_contract_id: Option<WalletAddress>,
     pub contract_address: Option<WalletAddress>,
     pub is_private: bool,
     pub is_sponsored: bool,
+    /// Whether non-players may watch the room
+    pub spectators_allowed: bool,
+    /// Spectators see pseudonyms instead of players until the game finishes
+    pub anonymize_players: bool,
+    /// Set for solo practice lobbies, where a bot fills the second seat
+    pub practice_bot: Option<BotDifficulty>,
+    /// Private lobbies admit join requests from users whose trust rating is
+    /// at least this without waiting for the creator
+    pub auto_approve_trust_threshold: Option<f64>,
+    /// Percent of the pot paid to each place; the default table if unset
+    pub payout_percents: Option<Vec<i32>>,
+    pub payout_underfill: PayoutUnderfill,
     pub status: LobbyStatus,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
@@ -37,12 +62,12 @@ impl Lobby {
     }
 
     /// Validate amount is positive (if present).
-    pub fn validate_amount(amount: Option<f64>) -> Result<Option<f64>, LobbyAmountError> {
+    pub fn validate_amount(amount: Option<Decimal>) -> Result<Option<Decimal>, LobbyAmountError> {
         if let Some(amt) = amount {
-            if amt < 0.0 {
+            if amt < Decimal::ZERO {
                 return Err(LobbyAmountError::Negative { amount: amt });
             }
-            if amt.is_nan() || amt.is_infinite() {
+            if amt != floor_to_micro(amt) {
                 return Err(LobbyAmountError::Invalid { amount: amt });
             }
         }
@@ -59,10 +84,10 @@ impl Lobby {
     ///   - `entry_amount` must equal `current_amount` (creator has paid)
     ///   - Both can be None (free lobby) or Some(same_value)
     pub fn validate_creation_amounts(
-        entry_amount: Option<f64>,
-        current_amount: Option<f64>,
+        entry_amount: Option<Decimal>,
+        current_amount: Option<Decimal>,
         is_sponsored: bool,
-    ) -> Result<(Option<f64>, Option<f64>), LobbyAmountError> {
+    ) -> Result<(Option<Decimal>, Option<Decimal>), LobbyAmountError> {
         // First validate individual amounts
         let entry_amount = Self::validate_amount(entry_amount)?;
         let current_amount = Self::validate_amount(current_amount)?;
@@ -74,7 +99,9 @@ impl Lobby {
             }
             match current_amount {
                 None => return Err(LobbyAmountError::SponsoredWithoutCurrent),
-                Some(amt) if amt <= 0.0 => return Err(LobbyAmountError::SponsoredCurrentZero),
+                Some(amt) if amt <= Decimal::ZERO => {
+                    return Err(LobbyAmountError::SponsoredCurrentZero);
+                }
                 _ => {} // Valid
             }
         } else {
@@ -89,16 +116,45 @@ impl Lobby {
 
         Ok((entry_amount, current_amount))
     }
+
+    /// Whether players pay to enter: an entry fee is set and no sponsor covers it.
+    pub fn is_paid_entry(entry_amount: Option<Decimal>, is_sponsored: bool) -> bool {
+        !is_sponsored && entry_amount.is_some_and(|amount| amount > Decimal::ZERO)
+    }
+
+    pub fn is_paid(&self) -> bool {
+        Self::is_paid_entry(self.entry_amount, self.is_sponsored)
+    }
+
+    /// Whether a join request from a user with `trust_rating` skips the
+    /// creator's approval. Only private lobbies with a threshold set do this.
+    pub fn auto_approves(&self, trust_rating: f64) -> bool {
+        self.is_private
+            && self
+                .auto_approve_trust_threshold
+                .is_some_and(|threshold| trust_rating >= threshold)
+    }
+
+    /// The lobby's own payout table, if it set one.
+    pub fn payout_table(&self) -> Option<PayoutTable> {
+        let percents = self
+            .payout_percents
+            .as_ref()?
+            .iter()
+            .map(|p| u32::try_from(*p).ok())
+            .collect::<Option<Vec<u32>>>()?;
+        PayoutTable::new(percents, self.payout_underfill).ok()
+    }
 }
 
 /// Lobby amount validation errors.
 #[derive(Debug, Clone, thiserror::Error)]
 pub enum LobbyAmountError {
     #[error("Amount cannot be negative: {amount}")]
-    Negative { amount: f64 },
+    Negative { amount: Decimal },
 
-    #[error("Amount is invalid (NaN or Infinite): {amount}")]
-    Invalid { amount: f64 },
+    #[error("Amount is finer than one micro-unit (6 decimal places): {amount}")]
+    Invalid { amount: Decimal },
 
     #[error(
         "Sponsored lobby cannot have entry_amount set. Entry must be None for sponsored lobbies."
@@ -115,8 +171,8 @@ pub enum LobbyAmountError {
         "Non-sponsored lobby: entry_amount must equal current_amount (creator pays). Got entry={entry:?}, current={current:?}"
     )]
     MismatchedAmounts {
-        entry: Option<f64>,
-        current: Option<f64>,
+        entry: Option<Decimal>,
+        current: Option<Decimal>,
     },
 }
 
@@ -139,13 +195,21 @@ pub struct LobbyExtended {
     pub game_id: Uuid,
     pub game_path: String,
     pub creator_id: Uuid,
-    pub entry_amount: Option<f64>,
-    pub current_amount: Option<f64>,
+    #[serde(with = "rust_decimal::serde::float_option")]
+    pub entry_amount: Option<Decimal>,
+    #[serde(with = "rust_decimal::serde::float_option")]
+    pub current_amount: Option<Decimal>,
     pub token_symbol: Option<String>,
     pub token_contract_id: Option<WalletAddress>,
     pub contract_address: Option<WalletAddress>,
     pub is_private: bool,
     pub is_sponsored: bool,
+    pub spectators_allowed: bool,
+    pub anonymize_players: bool,
+    pub practice_bot: Option<BotDifficulty>,
+    pub auto_approve_trust_threshold: Option<f64>,
+    pub payout_percents: Option<Vec<i32>>,
+    pub payout_underfill: PayoutUnderfill,
     pub status: LobbyStatus,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
@@ -175,6 +239,12 @@ impl LobbyExtended {
             contract_address: lobby.contract_address,
             is_private: lobby.is_private,
             is_sponsored: lobby.is_sponsored,
+            spectators_allowed: lobby.spectators_allowed,
+            anonymize_players: lobby.anonymize_players,
+            practice_bot: lobby.practice_bot,
+            auto_approve_trust_threshold: lobby.auto_approve_trust_threshold,
+            payout_percents: lobby.payout_percents,
+            payout_underfill: lobby.payout_underfill,
             status: lobby.status,
             created_at: lobby.created_at,
             updated_at: lobby.updated_at,
@@ -185,3 +255,144 @@ impl LobbyExtended {
         }
     }
 }
+
+/// Which lobbies a lobby-list subscriber wants to see.
+///
+/// Empty `statuses` means any status. Stake bounds are inclusive and apply to
+/// the entry amount (free lobbies count as 0); `None` leaves that side open.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LobbyFilter {
+    #[serde(default)]
+    pub statuses: Vec<LobbyStatus>,
+    #[serde(default)]
+    pub game_id: Option<Uuid>,
+    #[serde(default, with = "rust_decimal::serde::float_option")]
+    pub min_stake: Option<Decimal>,
+    #[serde(default, with = "rust_decimal::serde::float_option")]
+    pub max_stake: Option<Decimal>,
+}
+
+impl LobbyFilter {
+    pub fn matches(&self, lobby: &LobbyExtended) -> bool {
+        let stake = lobby.entry_amount.unwrap_or_default();
+        (self.statuses.is_empty() || self.statuses.contains(&lobby.status))
+            && self.game_id.is_none_or(|id| id == lobby.game_id)
+            && self.min_stake.is_none_or(|min| stake >= min)
+            && self.max_stake.is_none_or(|max| stake <= max)
+    }
+}
+
+/// How many lobbies of one game are in one status.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct LobbyStatusCount {
+    pub status: LobbyStatus,
+    pub game_id: Uuid,
+    pub count: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(game_id: Uuid, entry: Option<Decimal>, status: LobbyStatus) -> Lobby {
+        let now = chrono::Utc::now().naive_utc();
+        Lobby {
+            id: Uuid::new_v4(),
+            path: "filter".into(),
+            name: "Filter".into(),
+            description: None,
+            game_id,
+            game_path: "lexi-wars".into(),
+            creator_id: Uuid::new_v4(),
+            entry_amount: entry,
+            current_amount: entry,
+            token_symbol: Some("STX".into()),
+            token_contract_id: None,
+            contract_address: None,
+            is_private: false,
+            is_sponsored: false,
+            spectators_allowed: true,
+            anonymize_players: false,
+            practice_bot: None,
+            auto_approve_trust_threshold: None,
+            payout_percents: None,
+            payout_underfill: PayoutUnderfill::default(),
+            status,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn lobby(game_id: Uuid, entry: Option<Decimal>, status: LobbyStatus) -> LobbyExtended {
+        let lobby = row(game_id, entry, status);
+        let id = lobby.id;
+        LobbyExtended::from_parts(lobby, LobbyState::new(id))
+    }
+
+    #[test]
+    fn test_sponsored_and_free_lobbies_are_not_paid() {
+        assert!(Lobby::is_paid_entry(Some(Decimal::from(5)), false));
+        assert!(!Lobby::is_paid_entry(Some(Decimal::from(5)), true));
+        assert!(!Lobby::is_paid_entry(Some(Decimal::ZERO), false));
+        assert!(!Lobby::is_paid_entry(None, false));
+    }
+
+    #[test]
+    fn test_auto_approve_needs_private_lobby_and_threshold() {
+        let mut lobby = row(Uuid::new_v4(), None, LobbyStatus::Waiting);
+        assert!(!lobby.auto_approves(100.0));
+
+        lobby.auto_approve_trust_threshold = Some(50.0);
+        assert!(
+            !lobby.auto_approves(100.0),
+            "only private lobbies auto-approve"
+        );
+
+        lobby.is_private = true;
+        assert!(lobby.auto_approves(50.0));
+        assert!(!lobby.auto_approves(49.9));
+    }
+
+    #[test]
+    fn test_lobby_filter_matches() {
+        let game = Uuid::new_v4();
+        let free = lobby(game, None, LobbyStatus::Waiting);
+        let staked = lobby(game, Some(Decimal::from(5)), LobbyStatus::InProgress);
+        let other_game = lobby(Uuid::new_v4(), None, LobbyStatus::Waiting);
+
+        let any = LobbyFilter::default();
+        assert!(any.matches(&free) && any.matches(&staked) && any.matches(&other_game));
+
+        let by_game = LobbyFilter {
+            game_id: Some(game),
+            ..Default::default()
+        };
+        assert!(by_game.matches(&free));
+        assert!(!by_game.matches(&other_game));
+
+        let waiting = LobbyFilter {
+            statuses: vec![LobbyStatus::Waiting, LobbyStatus::Starting],
+            ..Default::default()
+        };
+        assert!(waiting.matches(&free));
+        assert!(!waiting.matches(&staked));
+
+        // Bounds are inclusive; free lobbies have a stake of 0
+        let stake = LobbyFilter {
+            min_stake: Some(Decimal::from(1)),
+            max_stake: Some(Decimal::from(5)),
+            ..Default::default()
+        };
+        assert!(stake.matches(&staked));
+        assert!(!stake.matches(&free));
+        assert!(
+            !LobbyFilter {
+                max_stake: Some(Decimal::new(499, 2)),
+                ..Default::default()
+            }
+            .matches(&staked)
+        );
+    }
+}
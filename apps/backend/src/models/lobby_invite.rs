@@ -0,0 +1,218 @@
+// LobbyInvite: shareable invite links for lobbies (runtime record stored in Redis)
+
+use crate::errors::AppError;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Invite record backing a signed invite token.
+///
+/// The token only carries `lobby_id`/`invite_id`; uses and revocation live here
+/// so that revoking an invite invalidates every token minted for it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LobbyInvite {
+    pub invite_id: Uuid,
+    pub lobby_id: Uuid,
+    pub created_by: Uuid,
+
+    /// Maximum number of redemptions (None = unlimited)
+    pub max_uses: Option<u32>,
+
+    /// Number of times the invite has been redeemed
+    pub uses: u32,
+
+    /// Unix timestamp after which the invite is no longer valid (None = no expiry)
+    pub expires_at: Option<i64>,
+
+    pub revoked: bool,
+    pub created_at: i64,
+}
+
+impl LobbyInvite {
+    /// Create a new invite, optionally limited by uses and lifetime in seconds.
+    pub fn new(
+        lobby_id: Uuid,
+        created_by: Uuid,
+        max_uses: Option<u32>,
+        expires_in_secs: Option<i64>,
+    ) -> Self {
+        let now = Utc::now().timestamp();
+        Self {
+            invite_id: Uuid::new_v4(),
+            lobby_id,
+            created_by,
+            max_uses,
+            uses: 0,
+            expires_at: expires_in_secs.map(|secs| now + secs),
+            revoked: false,
+            created_at: now,
+        }
+    }
+
+    /// Check whether the invite can still be redeemed at `now`.
+    pub fn check_redeemable(&self, now: i64) -> Result<(), InviteError> {
+        if self.revoked {
+            return Err(InviteError::Revoked);
+        }
+        if let Some(expires_at) = self.expires_at
+            && now >= expires_at
+        {
+            return Err(InviteError::Expired);
+        }
+        if let Some(max_uses) = self.max_uses
+            && self.uses >= max_uses
+        {
+            return Err(InviteError::Exhausted { max_uses });
+        }
+        Ok(())
+    }
+
+    /// Remaining redemptions (None = unlimited)
+    pub fn remaining_uses(&self) -> Option<u32> {
+        self.max_uses.map(|max| max.saturating_sub(self.uses))
+    }
+
+    /// Convert to Redis hash map for storage
+    pub fn to_redis_hash(&self) -> Vec<(String, String)> {
+        let mut fields = vec![
+            ("invite_id".into(), self.invite_id.to_string()),
+            ("lobby_id".into(), self.lobby_id.to_string()),
+            ("created_by".into(), self.created_by.to_string()),
+            ("uses".into(), self.uses.to_string()),
+            ("revoked".into(), self.revoked.to_string()),
+            ("created_at".into(), self.created_at.to_string()),
+        ];
+
+        if let Some(max_uses) = self.max_uses {
+            fields.push(("max_uses".into(), max_uses.to_string()));
+        }
+        if let Some(expires_at) = self.expires_at {
+            fields.push(("expires_at".into(), expires_at.to_string()));
+        }
+
+        fields
+    }
+
+    /// Parse from Redis hash map
+    ///
+    /// # Errors
+    /// - `AppError::InvalidInput` if required fields are missing or invalid
+    pub fn from_redis_hash(map: &HashMap<String, String>) -> Result<Self, AppError> {
+        let parse_uuid = |field: &str| {
+            map.get(field)
+                .and_then(|id| Uuid::parse_str(id).ok())
+                .ok_or_else(|| AppError::InvalidInput(format!("Missing or invalid {}", field)))
+        };
+
+        let created_at = map
+            .get("created_at")
+            .and_then(|t| t.parse::<i64>().ok())
+            .ok_or_else(|| AppError::InvalidInput("Missing or invalid created_at".into()))?;
+
+        Ok(Self {
+            invite_id: parse_uuid("invite_id")?,
+            lobby_id: parse_uuid("lobby_id")?,
+            created_by: parse_uuid("created_by")?,
+            max_uses: map.get("max_uses").and_then(|v| v.parse::<u32>().ok()),
+            uses: map
+                .get("uses")
+                .and_then(|v| v.parse::<u32>().ok())
+                .unwrap_or(0),
+            expires_at: map.get("expires_at").and_then(|v| v.parse::<i64>().ok()),
+            revoked: map
+                .get("revoked")
+                .and_then(|v| v.parse::<bool>().ok())
+                .unwrap_or(false),
+            created_at,
+        })
+    }
+}
+
+/// Invite redemption errors.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum InviteError {
+    #[error("Invite token is invalid")]
+    InvalidToken,
+
+    #[error("Invite is for a different lobby")]
+    WrongLobby,
+
+    #[error("Invite has been revoked")]
+    Revoked,
+
+    #[error("Invite has expired")]
+    Expired,
+
+    #[error("Invite has no uses left (max {max_uses})")]
+    Exhausted { max_uses: u32 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn invite(max_uses: Option<u32>) -> LobbyInvite {
+        LobbyInvite::new(Uuid::new_v4(), Uuid::new_v4(), max_uses, None)
+    }
+
+    #[test]
+    fn test_single_use_invite_is_redeemable_once() {
+        let mut inv = invite(Some(1));
+        let now = Utc::now().timestamp();
+
+        assert!(inv.check_redeemable(now).is_ok());
+        assert_eq!(inv.remaining_uses(), Some(1));
+
+        inv.uses += 1;
+        assert_eq!(inv.remaining_uses(), Some(0));
+        assert_eq!(
+            inv.check_redeemable(now),
+            Err(InviteError::Exhausted { max_uses: 1 })
+        );
+    }
+
+    #[test]
+    fn test_unlimited_invite_never_exhausts() {
+        let mut inv = invite(None);
+        inv.uses = 1_000;
+        assert!(inv.check_redeemable(Utc::now().timestamp()).is_ok());
+        assert_eq!(inv.remaining_uses(), None);
+    }
+
+    #[test]
+    fn test_revoked_invite_rejected() {
+        let mut inv = invite(Some(5));
+        inv.revoked = true;
+        assert_eq!(
+            inv.check_redeemable(Utc::now().timestamp()),
+            Err(InviteError::Revoked)
+        );
+    }
+
+    #[test]
+    fn test_expired_invite_rejected() {
+        let inv = LobbyInvite::new(Uuid::new_v4(), Uuid::new_v4(), None, Some(60));
+        let expires_at = inv.expires_at.unwrap();
+
+        assert!(inv.check_redeemable(expires_at - 1).is_ok());
+        assert_eq!(inv.check_redeemable(expires_at), Err(InviteError::Expired));
+    }
+
+    #[test]
+    fn test_redis_hash_roundtrip() {
+        let mut inv = LobbyInvite::new(Uuid::new_v4(), Uuid::new_v4(), Some(3), Some(600));
+        inv.uses = 2;
+
+        let map: HashMap<String, String> = inv.to_redis_hash().into_iter().collect();
+        let parsed = LobbyInvite::from_redis_hash(&map).unwrap();
+
+        assert_eq!(parsed.invite_id, inv.invite_id);
+        assert_eq!(parsed.lobby_id, inv.lobby_id);
+        assert_eq!(parsed.max_uses, Some(3));
+        assert_eq!(parsed.uses, 2);
+        assert_eq!(parsed.expires_at, inv.expires_at);
+        assert!(!parsed.revoked);
+    }
+}
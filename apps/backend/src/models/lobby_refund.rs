@@ -0,0 +1,24 @@
+use chrono::NaiveDateTime;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Deposit owed back to a player after their lobby was cancelled.
+///
+/// `refund_tx_id` stays `None` until the payout has been sent.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct LobbyRefund {
+    pub id: Uuid,
+    pub lobby_id: Uuid,
+    pub user_id: Uuid,
+    pub wallet_address: String,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub amount: Decimal,
+    pub token_symbol: Option<String>,
+    pub token_contract_id: Option<String>,
+    pub refund_tx_id: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
@@ -7,7 +7,7 @@ use std::{collections::HashMap, str::FromStr};
 use uuid::Uuid;
 
 /// Lobby lifecycle status enum
-#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy, sqlx::Type)]
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Debug, Clone, Copy, sqlx::Type)]
 #[sqlx(type_name = "lobby_status", rename_all = "snake_case")]
 #[serde(rename_all = "camelCase")]
 pub enum LobbyStatus {
@@ -15,6 +15,8 @@ pub enum LobbyStatus {
     Starting,
     InProgress,
     Finished,
+    /// Closed before starting (e.g. reaped while idle); deposits are refunded
+    Cancelled,
 }
 
 impl FromStr for LobbyStatus {
@@ -28,6 +30,7 @@ impl FromStr for LobbyStatus {
                 Ok(LobbyStatus::InProgress)
             }
             "Finished" | "finished" => Ok(LobbyStatus::Finished),
+            "Cancelled" | "cancelled" => Ok(LobbyStatus::Cancelled),
             other => Err(AppError::BadRequest(format!(
                 "Unknown LobbyState: {}",
                 other
@@ -36,6 +39,19 @@ impl FromStr for LobbyStatus {
     }
 }
 
+impl LobbyStatus {
+    /// Lowercase name used in lobby-list context keys and query params
+    pub fn as_key(&self) -> &'static str {
+        match self {
+            LobbyStatus::Waiting => "waiting",
+            LobbyStatus::Starting => "starting",
+            LobbyStatus::InProgress => "in_progress",
+            LobbyStatus::Finished => "finished",
+            LobbyStatus::Cancelled => "cancelled",
+        }
+    }
+}
+
 /// Runtime state of a lobby stored in Redis (dynamic runtime fields).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -1,9 +1,25 @@
 pub mod game;
+pub mod game_word;
 pub mod lobby;
+pub mod lobby_invite;
+pub mod lobby_refund;
+pub mod money;
+pub mod payout;
 pub mod platform_rating;
+pub mod prediction;
+pub mod rank_snapshot;
+pub mod replay;
+pub mod report;
+pub mod role;
 pub mod season;
+pub mod seed;
+pub mod signed_results;
+pub mod skill_rating;
 pub mod stacks;
+pub mod stats;
+pub mod streak;
 pub mod user;
+pub mod user_badge;
 pub mod user_wars_point;
 pub mod username;
 pub mod wallet_address;
@@ -14,15 +30,32 @@ pub mod lobby_state;
 pub mod player_state;
 
 pub use game::Game;
-pub use lobby::{Lobby, LobbyExtended, LobbyInfo};
-pub use platform_rating::PlatformRating;
+pub use game_word::{GameWord, GameWordStats};
+pub use lobby::{BotDifficulty, Lobby, LobbyExtended, LobbyFilter, LobbyInfo, LobbyStatusCount};
+pub use lobby_invite::{InviteError, LobbyInvite};
+pub use lobby_refund::LobbyRefund;
+pub use payout::{PayoutError, PayoutTable, PayoutUnderfill};
+pub use platform_rating::{PlatformRating, PlatformRatingFilter, PlatformRatingSummary};
+pub use prediction::PredictionOutcome;
+pub use rank_snapshot::RankSnapshot;
+pub use replay::{ReplayFrame, ReplayState, ReplayTimeline};
+pub use report::{Report, ReportAction, ReportCategory, ReportStatus};
+pub use role::Role;
 pub use season::Season;
-pub use user::User;
-pub use user_wars_point::UserWarsPoints;
+pub use signed_results::SignedResults;
+pub use skill_rating::SkillRating;
+pub use stats::{ActivePlayers, PopularGame, StatsOverview, TokenVolume};
+pub use streak::UserStreaks;
+pub use user::{DELETED_USER_ID, DELETED_USER_NAME, User, UserStats};
+pub use user_badge::UserBadge;
+pub use user_wars_point::{AwardCorrection, LeaderboardEntry, UserWarsPoints, WarsPointAward};
 pub use username::Username;
 pub use wallet_address::WalletAddress;
 
-pub use chat_message::{ChatMessage, ChatMessageError, Reaction, ReactionType};
-pub use keys::{KeyPart, RedisKey};
+pub use chat_message::{
+    ChatCursor, ChatMessage, ChatMessageError, ChatMessageView, ChatPage, Reaction,
+    ReactionSummary, ReactionType,
+};
+pub use keys::{ExpiryPolicy, KeyCategory, KeyPart, RedisKey};
 pub use lobby_state::{LobbyState, LobbyStatus};
 pub use player_state::PlayerState;
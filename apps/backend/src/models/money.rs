@@ -0,0 +1,96 @@
+// Money: fixed-point amounts for lobby stakes, pots and prizes
+//
+// Amounts are `rust_decimal::Decimal` in whole token units (e.g. `1.5` STX).
+// On the wire they stay JSON numbers for existing clients; incoming amounts
+// may be numbers or strings. Annotate money fields with
+// `#[serde(with = "rust_decimal::serde::float_option")]` (or `float`).
+
+use rust_decimal::{Decimal, RoundingStrategy, prelude::ToPrimitive};
+
+/// Decimal places of the smallest unit we pay out (1 micro-unit = 0.000001).
+/// STX and the SIP-010 tokens we accept all use 6.
+pub const MICRO_UNIT_SCALE: u32 = 6;
+
+/// Round down to whole micro-units.
+pub fn floor_to_micro(amount: Decimal) -> Decimal {
+    amount.round_dp_with_strategy(MICRO_UNIT_SCALE, RoundingStrategy::ToZero)
+}
+
+/// `amount` in micro-units (the integer sent to contracts).
+pub fn to_micro_units(amount: Decimal) -> Option<i128> {
+    (floor_to_micro(amount) * Decimal::from(10u64.pow(MICRO_UNIT_SCALE))).to_i128()
+}
+
+/// Split `pool` into shares given in percent, to the micro-unit.
+///
+/// Each share is rounded down; when the percentages add up to 100 the
+/// leftover micro-units go to the first share, so the parts always sum to
+/// exactly `pool`. Otherwise the undistributed remainder stays in the pool.
+pub fn split_pot(pool: Decimal, percents: &[u32]) -> Vec<Decimal> {
+    let pool = floor_to_micro(pool);
+    let hundred = Decimal::from(100);
+
+    let mut shares: Vec<Decimal> = percents
+        .iter()
+        .map(|p| floor_to_micro(pool * Decimal::from(*p) / hundred))
+        .collect();
+
+    if percents.iter().sum::<u32>() == 100 {
+        let paid: Decimal = shares.iter().sum();
+        if let Some(first) = shares.first_mut() {
+            *first += pool - paid;
+        }
+    }
+
+    shares
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn dec(s: &str) -> Decimal {
+        Decimal::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_split_is_exact_where_f64_drifts() {
+        // Three 0.1 STX entries: f64 sums to 0.30000000000000004 and the
+        // 50/30/20 shares don't add back up to the pool
+        let f64_pool = 0.1 + 0.1 + 0.1;
+        let f64_shares = [f64_pool * 0.5, f64_pool * 0.3, f64_pool * 0.2];
+        assert_ne!(f64_shares.iter().sum::<f64>(), 0.3);
+
+        let pool = dec("0.1") + dec("0.1") + dec("0.1");
+        let shares = split_pot(pool, &[50, 30, 20]);
+        assert_eq!(shares, vec![dec("0.15"), dec("0.09"), dec("0.06")]);
+        assert_eq!(shares.iter().sum::<Decimal>(), dec("0.3"));
+    }
+
+    #[test]
+    fn test_split_assigns_leftover_micro_units_to_first_share() {
+        // 0.000007 split 70/30 can't be even; nothing may be lost
+        let pool = dec("0.000007");
+        let shares = split_pot(pool, &[70, 30]);
+        assert_eq!(shares, vec![dec("0.000005"), dec("0.000002")]);
+        assert_eq!(shares.iter().sum::<Decimal>(), pool);
+
+        let pool = dec("100.000001");
+        let shares = split_pot(pool, &[50, 30, 20]);
+        assert_eq!(shares.iter().sum::<Decimal>(), pool);
+        assert_eq!(shares[1], dec("30"));
+    }
+
+    #[test]
+    fn test_partial_split_keeps_remainder() {
+        let shares = split_pot(dec("10"), &[50]);
+        assert_eq!(shares, vec![dec("5")]);
+    }
+
+    #[test]
+    fn test_micro_units() {
+        assert_eq!(to_micro_units(dec("1.5")), Some(1_500_000));
+        assert_eq!(to_micro_units(dec("0.0000019")), Some(1));
+    }
+}
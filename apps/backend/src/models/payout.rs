@@ -0,0 +1,168 @@
+// Payouts: how a lobby's pot is shared between its top finishers
+//
+// A lobby may set its own payout table, e.g. 50/30/20 for the top three,
+// whose percentages must add up to 100. Lobbies without one use the default
+// table for their player count. When fewer players finish than the table has
+// paid places, the empty places' shares either go to the top finisher or stay
+// in the pot, as the table's `PayoutUnderfill` says.
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use super::money::split_pot;
+
+/// Where the shares of unfilled paid places go.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy, Default, sqlx::Type)]
+#[sqlx(type_name = "payout_underfill", rename_all = "snake_case")]
+#[serde(rename_all = "camelCase")]
+pub enum PayoutUnderfill {
+    /// Added to first place's share
+    #[default]
+    TopFinisher,
+    /// Left in the pot, unpaid
+    Pot,
+}
+
+/// Share of the pot paid to each place, in percent (1st first).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PayoutTable {
+    percents: Vec<u32>,
+    underfill: PayoutUnderfill,
+}
+
+impl PayoutTable {
+    /// Most places a table may pay
+    pub const MAX_PLACES: usize = 10;
+
+    /// Validate a custom table.
+    pub fn new(percents: Vec<u32>, underfill: PayoutUnderfill) -> Result<Self, PayoutError> {
+        if percents.is_empty() {
+            return Err(PayoutError::Empty);
+        }
+        if percents.len() > Self::MAX_PLACES {
+            return Err(PayoutError::TooManyPlaces {
+                max: Self::MAX_PLACES,
+            });
+        }
+        if let Some(place) = percents.iter().position(|p| *p == 0) {
+            return Err(PayoutError::ZeroShare { place: place + 1 });
+        }
+        let total: u32 = percents.iter().sum();
+        if total != 100 {
+            return Err(PayoutError::SumNot100 { total });
+        }
+        Ok(Self {
+            percents,
+            underfill,
+        })
+    }
+
+    /// The table used when a lobby sets none: 70/30 for two players,
+    /// 50/30/20 otherwise.
+    pub fn default_for(participants: usize) -> Self {
+        let percents = if participants == 2 {
+            vec![70, 30]
+        } else {
+            vec![50, 30, 20]
+        };
+        Self {
+            percents,
+            underfill: PayoutUnderfill::default(),
+        }
+    }
+
+    pub fn percents(&self) -> &[u32] {
+        &self.percents
+    }
+
+    pub fn underfill(&self) -> PayoutUnderfill {
+        self.underfill
+    }
+
+    /// Percent actually paid to each place when `finishers` players finish.
+    pub fn effective_percents(&self, finishers: usize) -> Vec<u32> {
+        let paid = finishers.min(self.percents.len());
+        let mut percents = self.percents[..paid].to_vec();
+        let unfilled: u32 = self.percents[paid..].iter().sum();
+        if self.underfill == PayoutUnderfill::TopFinisher
+            && let Some(first) = percents.first_mut()
+        {
+            *first += unfilled;
+        }
+        percents
+    }
+
+    /// Split `pool` between the first `finishers` places, to the micro-unit.
+    pub fn shares(&self, pool: Decimal, finishers: usize) -> Vec<Decimal> {
+        split_pot(pool, &self.effective_percents(finishers))
+    }
+}
+
+/// Payout table validation errors.
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum PayoutError {
+    #[error("Payout table must pay at least one place")]
+    Empty,
+
+    #[error("Payout table can pay at most {max} places")]
+    TooManyPlaces { max: usize },
+
+    #[error("Place {place} has a zero share")]
+    ZeroShare { place: usize },
+
+    #[error("Payout percentages must add up to 100, got {total}")]
+    SumNot100 { total: u32 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_three_way_split() {
+        let table = PayoutTable::new(vec![50, 30, 20], PayoutUnderfill::TopFinisher).unwrap();
+        let shares = table.shares(Decimal::from(90), 5);
+        assert_eq!(
+            shares,
+            vec![Decimal::from(45), Decimal::from(27), Decimal::from(18)]
+        );
+    }
+
+    #[test]
+    fn test_table_must_sum_to_100() {
+        assert!(matches!(
+            PayoutTable::new(vec![50, 30, 10], PayoutUnderfill::Pot),
+            Err(PayoutError::SumNot100 { total: 90 })
+        ));
+        assert!(matches!(
+            PayoutTable::new(vec![], PayoutUnderfill::Pot),
+            Err(PayoutError::Empty)
+        ));
+        assert!(matches!(
+            PayoutTable::new(vec![100, 0], PayoutUnderfill::Pot),
+            Err(PayoutError::ZeroShare { place: 2 })
+        ));
+        assert!(PayoutTable::new(vec![100], PayoutUnderfill::Pot).is_ok());
+    }
+
+    #[test]
+    fn test_underfill_rolls_up_or_stays_in_pot() {
+        let percents = vec![40, 30, 20, 10];
+        let pool = Decimal::from(100);
+
+        // Two finishers for four places: 3rd and 4th's 30% goes to the winner
+        let top = PayoutTable::new(percents.clone(), PayoutUnderfill::TopFinisher).unwrap();
+        assert_eq!(top.effective_percents(2), vec![70, 30]);
+        assert_eq!(
+            top.shares(pool, 2),
+            vec![Decimal::from(70), Decimal::from(30)]
+        );
+
+        // ...or stays in the pot
+        let pot = PayoutTable::new(percents, PayoutUnderfill::Pot).unwrap();
+        let shares = pot.shares(pool, 2);
+        assert_eq!(shares, vec![Decimal::from(40), Decimal::from(30)]);
+        assert_eq!(pool - shares.iter().sum::<Decimal>(), Decimal::from(30));
+    }
+}
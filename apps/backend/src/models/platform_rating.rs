@@ -14,3 +14,85 @@ pub struct PlatformRating {
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
 }
+
+/// Filters for listing and summarising platform ratings.
+///
+/// All bounds are inclusive; `None` leaves that side open.
+#[derive(Debug, Clone, Default)]
+pub struct PlatformRatingFilter {
+    pub min_rating: Option<i16>,
+    pub max_rating: Option<i16>,
+    /// Only ratings created at or after this time
+    pub since: Option<NaiveDateTime>,
+    /// Only ratings created at or before this time
+    pub until: Option<NaiveDateTime>,
+}
+
+/// Number of ratings with a given score.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RatingBucket {
+    pub rating: i16,
+    pub count: i64,
+}
+
+/// Aggregate view of platform ratings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlatformRatingSummary {
+    pub count: i64,
+    /// Mean rating; `None` when there are no ratings
+    pub average: Option<f64>,
+    /// One bucket per score 1-5, lowest first, including empty ones
+    pub distribution: Vec<RatingBucket>,
+}
+
+impl PlatformRatingSummary {
+    /// Build a summary from `(rating, count)` rows.
+    pub fn from_counts(counts: &[(i16, i64)]) -> Self {
+        let distribution: Vec<RatingBucket> = (1..=5)
+            .map(|rating| RatingBucket {
+                rating,
+                count: counts
+                    .iter()
+                    .filter(|(r, _)| *r == rating)
+                    .map(|(_, c)| c)
+                    .sum(),
+            })
+            .collect();
+
+        let count: i64 = distribution.iter().map(|b| b.count).sum();
+        let total: i64 = distribution.iter().map(|b| b.rating as i64 * b.count).sum();
+        let average = (count > 0).then(|| total as f64 / count as f64);
+
+        Self {
+            count,
+            average,
+            distribution,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_from_counts() {
+        let summary = PlatformRatingSummary::from_counts(&[(5, 2), (3, 1), (1, 1)]);
+
+        assert_eq!(summary.count, 4);
+        assert_eq!(summary.average, Some(3.5));
+        let counts: Vec<i64> = summary.distribution.iter().map(|b| b.count).collect();
+        assert_eq!(counts, vec![1, 0, 1, 0, 2]);
+    }
+
+    #[test]
+    fn test_empty_summary() {
+        let summary = PlatformRatingSummary::from_counts(&[]);
+
+        assert_eq!(summary.count, 0);
+        assert_eq!(summary.average, None);
+        assert_eq!(summary.distribution.len(), 5);
+    }
+}
@@ -2,6 +2,7 @@
 use crate::db::join_request::JoinRequestState;
 use crate::errors::AppError;
 use chrono::Utc;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, str::FromStr};
 use uuid::Uuid;
@@ -86,7 +87,8 @@ pub struct PlayerState {
     pub rank: Option<usize>,
 
     /// Prize amount won
-    pub prize: Option<f64>,
+    #[serde(with = "rust_decimal::serde::float_option")]
+    pub prize: Option<Decimal>,
 
     /// Wars points earned from this game
     pub wars_point: Option<f64>,
@@ -232,7 +234,7 @@ impl PlayerState {
 
         let rank = data.get("rank").and_then(|r| r.parse::<usize>().ok());
 
-        let prize = data.get("prize").and_then(|p| p.parse::<f64>().ok());
+        let prize = data.get("prize").and_then(|p| p.parse::<Decimal>().ok());
 
         let wars_point = data.get("wars_point").and_then(|v| v.parse().ok());
 
@@ -285,7 +287,7 @@ impl PlayerState {
 
     /// Check if player has a prize to claim
     pub fn has_prize(&self) -> bool {
-        self.prize.is_some() && self.prize.unwrap() > 0.0
+        self.prize.is_some_and(|prize| prize > Decimal::ZERO)
     }
 }
 
@@ -0,0 +1,79 @@
+// Winner predictions: spectators call the winner before a game, for points only
+//
+// No funds are involved. Spectators predict while the lobby is waiting or
+// counting down; predictions lock when the game starts. At the end of the
+// game each correct predictor is awarded `AppConfig::prediction_points`.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Points per correct prediction unless `PREDICTION_POINTS` says otherwise
+pub const DEFAULT_PREDICTION_POINTS: u32 = 10;
+
+/// How a lobby's predictions fared.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PredictionOutcome {
+    pub winner_id: Option<Uuid>,
+    pub predictions: usize,
+    /// Predictors who named the winner
+    pub correct: Vec<Uuid>,
+    /// Share of predictions that were right, 0.0 with none
+    pub accuracy: f64,
+}
+
+impl PredictionOutcome {
+    /// Score `predictions` (predictor → predicted winner) against `winner_id`.
+    pub fn score(predictions: &HashMap<Uuid, Uuid>, winner_id: Option<Uuid>) -> Self {
+        let mut correct: Vec<Uuid> = predictions
+            .iter()
+            .filter(|(_, predicted)| Some(**predicted) == winner_id)
+            .map(|(predictor, _)| *predictor)
+            .collect();
+        correct.sort();
+
+        let accuracy = if predictions.is_empty() {
+            0.0
+        } else {
+            correct.len() as f64 / predictions.len() as f64
+        };
+
+        Self {
+            winner_id,
+            predictions: predictions.len(),
+            correct,
+            accuracy,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_predictors_of_the_winner_are_correct() {
+        let (winner, loser) = (Uuid::new_v4(), Uuid::new_v4());
+        let (right, wrong, also_right) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let predictions = HashMap::from([(right, winner), (wrong, loser), (also_right, winner)]);
+
+        let outcome = PredictionOutcome::score(&predictions, Some(winner));
+        let mut expected = vec![right, also_right];
+        expected.sort();
+        assert_eq!(outcome.correct, expected);
+        assert_eq!(outcome.predictions, 3);
+        assert!((outcome.accuracy - 2.0 / 3.0).abs() < f64::EPSILON);
+
+        // No winner (everyone timed out at once): nobody was right
+        let outcome = PredictionOutcome::score(&predictions, None);
+        assert!(outcome.correct.is_empty());
+        assert_eq!(outcome.accuracy, 0.0);
+
+        assert_eq!(
+            PredictionOutcome::score(&HashMap::new(), Some(winner)).accuracy,
+            0.0
+        );
+    }
+}
@@ -0,0 +1,14 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// A user's leaderboard standing at one point in a season.
+/// Maps to `rank_snapshots` table in PostgreSQL
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct RankSnapshot {
+    /// 1 is the top of the leaderboard; tied points share a rank
+    pub rank: i32,
+    pub points: f64,
+    pub taken_at: NaiveDateTime,
+}
@@ -0,0 +1,173 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::game_word::{GameWord, PlayerWordCount};
+
+/// One accepted word, placed on the game's clock.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayFrame {
+    /// Milliseconds since the game started
+    pub at_ms: i64,
+    pub turn: i32,
+    pub user_id: Uuid,
+    pub word: String,
+}
+
+/// A game's words as seekable frames.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayTimeline {
+    /// Epoch milliseconds the clock counts from
+    pub started_at_ms: i64,
+    /// Elapsed time of the last frame
+    pub duration_ms: i64,
+    pub frames: Vec<ReplayFrame>,
+    /// Seed of the game's draws, as a decimal string; recorded once it ended
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub game_seed: Option<String>,
+}
+
+impl ReplayTimeline {
+    /// Frames for `words` in turn order, timed from `started_at_ms`.
+    ///
+    /// Without a recorded start the clock starts at the first word.
+    pub fn new(words: &[GameWord], started_at_ms: Option<i64>) -> Self {
+        let started_at_ms = started_at_ms
+            .or_else(|| words.first().map(played_at_ms))
+            .unwrap_or(0);
+        let frames: Vec<ReplayFrame> = words
+            .iter()
+            .map(|w| ReplayFrame {
+                at_ms: (played_at_ms(w) - started_at_ms).max(0),
+                turn: w.turn,
+                user_id: w.user_id,
+                word: w.word.clone(),
+            })
+            .collect();
+
+        Self {
+            started_at_ms,
+            duration_ms: frames.last().map(|f| f.at_ms).unwrap_or(0),
+            frames,
+            game_seed: None,
+        }
+    }
+
+    /// The game as it stood `at_ms` into it; the initial state before the start.
+    pub fn state_at(&self, at_ms: i64) -> ReplayState {
+        self.frames
+            .iter()
+            .take_while(|frame| frame.at_ms <= at_ms)
+            .fold(ReplayState::initial(at_ms), ReplayState::apply)
+    }
+}
+
+/// What a game looked like at one instant of its replay.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayState {
+    pub at_ms: i64,
+    pub words_played: usize,
+    /// Words used so far, in turn order
+    pub used_words: Vec<String>,
+    pub last_word: Option<ReplayFrame>,
+    /// Words per player, in the order they first scored
+    pub scores: Vec<PlayerWordCount>,
+}
+
+impl ReplayState {
+    /// Before any word is played
+    pub fn initial(at_ms: i64) -> Self {
+        Self {
+            at_ms,
+            words_played: 0,
+            used_words: Vec::new(),
+            last_word: None,
+            scores: Vec::new(),
+        }
+    }
+
+    /// The state after `frame` is played
+    pub fn apply(mut self, frame: &ReplayFrame) -> Self {
+        self.words_played += 1;
+        self.used_words.push(frame.word.clone());
+        match self.scores.iter_mut().find(|s| s.user_id == frame.user_id) {
+            Some(score) => score.words += 1,
+            None => self.scores.push(PlayerWordCount {
+                user_id: frame.user_id,
+                words: 1,
+            }),
+        }
+        self.last_word = Some(frame.clone());
+        self
+    }
+}
+
+fn played_at_ms(word: &GameWord) -> i64 {
+    word.played_at.and_utc().timestamp_millis()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(user_id: Uuid, word: &str, turn: i32, at_ms: i64) -> GameWord {
+        GameWord {
+            id: Uuid::new_v4(),
+            lobby_id: Uuid::nil(),
+            user_id,
+            word: word.to_string(),
+            turn,
+            played_at: chrono::DateTime::from_timestamp_millis(at_ms)
+                .unwrap()
+                .naive_utc(),
+        }
+    }
+
+    #[test]
+    fn test_seeking_folds_words_up_to_the_instant() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let start = 1_700_000_000_000;
+        let words = vec![
+            word(a, "cats", 1, start + 3_000),
+            word(b, "zebra", 2, start + 8_000),
+            word(a, "planet", 3, start + 15_000),
+        ];
+        let timeline = ReplayTimeline::new(&words, Some(start));
+        assert_eq!(timeline.duration_ms, 15_000);
+
+        assert_eq!(timeline.state_at(0), ReplayState::initial(0));
+        assert_eq!(timeline.state_at(-500), ReplayState::initial(-500));
+
+        let mid = timeline.state_at(10_000);
+        assert_eq!(mid.words_played, 2);
+        assert_eq!(mid.used_words, vec!["cats", "zebra"]);
+        assert_eq!(mid.last_word.unwrap().word, "zebra");
+
+        let end = timeline.state_at(15_000);
+        assert_eq!(
+            end.scores,
+            vec![
+                PlayerWordCount {
+                    user_id: a,
+                    words: 2
+                },
+                PlayerWordCount {
+                    user_id: b,
+                    words: 1
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_clock_starts_at_the_first_word_without_a_start_time() {
+        let a = Uuid::new_v4();
+        let words = vec![word(a, "cats", 1, 5_000), word(a, "dogs", 2, 9_000)];
+        let timeline = ReplayTimeline::new(&words, None);
+
+        assert_eq!(timeline.started_at_ms, 5_000);
+        assert_eq!(timeline.frames[1].at_ms, 4_000);
+    }
+}
@@ -0,0 +1,135 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Trust deducted from a reported user while their report is pending;
+/// returned if the report is dismissed.
+pub const PROVISIONAL_TRUST_PENALTY: f64 = 0.5;
+
+/// Additional trust deducted when a report is upheld with a warning.
+pub const WARN_TRUST_PENALTY: f64 = 2.0;
+
+/// Reports a single user may file per hour.
+pub const MAX_REPORTS_PER_HOUR: i64 = 5;
+
+/// What a player is being reported for.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy, sqlx::Type)]
+#[sqlx(type_name = "report_category", rename_all = "snake_case")]
+#[serde(rename_all = "camelCase")]
+pub enum ReportCategory {
+    Cheating,
+    Abuse,
+    Spam,
+    Other,
+}
+
+/// Where a report is in the moderation queue.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy, sqlx::Type)]
+#[sqlx(type_name = "report_status", rename_all = "snake_case")]
+#[serde(rename_all = "camelCase")]
+pub enum ReportStatus {
+    Open,
+    /// Picked up by a moderator
+    Assigned,
+    /// Upheld; an action was applied
+    Resolved,
+    /// Closed without action
+    Dismissed,
+}
+
+impl ReportStatus {
+    /// Whether the report is still waiting on a moderator.
+    pub fn is_pending(self) -> bool {
+        matches!(self, ReportStatus::Open | ReportStatus::Assigned)
+    }
+
+    /// Whether a report in this status may move to `next`.
+    pub fn can_transition_to(self, next: ReportStatus) -> bool {
+        match next {
+            ReportStatus::Open => false,
+            ReportStatus::Assigned => self.is_pending(),
+            ReportStatus::Resolved | ReportStatus::Dismissed => self.is_pending(),
+        }
+    }
+}
+
+/// Moderator decision on a report.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Copy, sqlx::Type)]
+#[sqlx(type_name = "report_action", rename_all = "snake_case")]
+#[serde(rename_all = "camelCase")]
+pub enum ReportAction {
+    /// No wrongdoing; the provisional trust penalty is returned
+    Dismiss,
+    /// Trust penalty for the reported user
+    Warn,
+    /// Reported user is banned from signing in
+    Ban,
+}
+
+impl ReportAction {
+    /// Status a report ends in after this action.
+    pub fn resulting_status(self) -> ReportStatus {
+        match self {
+            ReportAction::Dismiss => ReportStatus::Dismissed,
+            ReportAction::Warn | ReportAction::Ban => ReportStatus::Resolved,
+        }
+    }
+}
+
+/// A player report. Maps to the `reports` table.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Report {
+    pub id: Uuid,
+    pub reporter_id: Uuid,
+    pub reported_user_id: Uuid,
+    pub lobby_id: Option<Uuid>,
+    pub category: ReportCategory,
+    pub description: Option<String>,
+    pub status: ReportStatus,
+    /// Moderator handling the report
+    pub assigned_to: Option<Uuid>,
+    pub action: Option<ReportAction>,
+    pub resolution_note: Option<String>,
+    pub resolved_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pending_reports_can_be_assigned_and_closed() {
+        for status in [ReportStatus::Open, ReportStatus::Assigned] {
+            assert!(status.can_transition_to(ReportStatus::Assigned));
+            assert!(status.can_transition_to(ReportStatus::Resolved));
+            assert!(status.can_transition_to(ReportStatus::Dismissed));
+            assert!(!status.can_transition_to(ReportStatus::Open));
+        }
+    }
+
+    #[test]
+    fn test_closed_reports_are_final() {
+        for status in [ReportStatus::Resolved, ReportStatus::Dismissed] {
+            assert!(!status.is_pending());
+            assert!(!status.can_transition_to(ReportStatus::Assigned));
+            assert!(!status.can_transition_to(ReportStatus::Resolved));
+        }
+    }
+
+    #[test]
+    fn test_action_status() {
+        assert_eq!(
+            ReportAction::Dismiss.resulting_status(),
+            ReportStatus::Dismissed
+        );
+        assert_eq!(
+            ReportAction::Warn.resulting_status(),
+            ReportStatus::Resolved
+        );
+        assert_eq!(ReportAction::Ban.resulting_status(), ReportStatus::Resolved);
+    }
+}
@@ -0,0 +1,51 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// What a user may do beyond playing. Each role includes the ones before it.
+#[derive(
+    Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy, Default, sqlx::Type,
+)]
+#[sqlx(type_name = "user_role", rename_all = "snake_case")]
+#[serde(rename_all = "camelCase")]
+pub enum Role {
+    #[default]
+    User,
+    /// Works the report queue
+    Moderator,
+    /// Runs the platform: seasons, seeding, flags, access lists
+    Admin,
+}
+
+impl Role {
+    /// Name for messages shown to people
+    pub fn label(self) -> &'static str {
+        match self {
+            Role::User => "User",
+            Role::Moderator => "Moderator",
+            Role::Admin => "Admin",
+        }
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Role::User => "user",
+            Role::Moderator => "moderator",
+            Role::Admin => "admin",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roles_include_lesser_roles() {
+        assert!(Role::Admin > Role::Moderator);
+        assert!(Role::Moderator > Role::User);
+        assert_eq!(Role::default(), Role::User);
+    }
+}
@@ -22,6 +22,7 @@ pub struct Season {
     pub start_date: NaiveDateTime,
     pub end_date: NaiveDateTime,
     pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
 }
 
 impl Season {
@@ -0,0 +1,206 @@
+// Seed bundle: seasons and games inserted together by the admin seed endpoint
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::{
+    errors::AppError,
+    models::{Game, Season},
+};
+
+/// What to do when a seeded row already exists.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SeedMode {
+    /// Leave the existing row untouched
+    #[default]
+    Skip,
+    /// Overwrite the existing row with the bundle's values
+    Update,
+}
+
+/// Season entry; matched against existing seasons by name.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeedSeason {
+    pub name: String,
+    pub description: Option<String>,
+    /// Format: "YYYY-MM-DD HH:MM:SS"
+    pub start_date: String,
+    /// Format: "YYYY-MM-DD HH:MM:SS"
+    pub end_date: String,
+    /// Allow overlapping other seasons (special events)
+    #[serde(default)]
+    pub allow_overlap: bool,
+}
+
+/// Game entry; matched against existing games by path.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeedGame {
+    pub name: String,
+    pub path: String,
+    pub description: String,
+    pub image_url: String,
+    pub min_players: i16,
+    pub max_players: i16,
+    pub category: Option<String>,
+}
+
+/// A bundle of seasons and games applied in a single transaction.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeedBundle {
+    #[serde(default)]
+    pub mode: SeedMode,
+    #[serde(default)]
+    pub seasons: Vec<SeedSeason>,
+    #[serde(default)]
+    pub games: Vec<SeedGame>,
+}
+
+impl SeedBundle {
+    /// Validate every entry before anything is written.
+    ///
+    /// Errors name the offending entry, e.g. `seasons[1]: ...`.
+    pub fn validate(&self) -> Result<(), AppError> {
+        let mut season_names = HashSet::new();
+        for (i, season) in self.seasons.iter().enumerate() {
+            if season.name.trim().is_empty() {
+                return Err(AppError::BadRequest(format!(
+                    "seasons[{}]: name is required",
+                    i
+                )));
+            }
+            if !season_names.insert(season.name.as_str()) {
+                return Err(AppError::BadRequest(format!(
+                    "seasons[{}]: duplicate season name '{}'",
+                    i, season.name
+                )));
+            }
+            Season::parse_date_range(&season.start_date, &season.end_date)
+                .map_err(|e| AppError::BadRequest(format!("seasons[{}]: {}", i, e)))?;
+        }
+
+        let mut game_paths = HashSet::new();
+        for (i, game) in self.games.iter().enumerate() {
+            if game.name.trim().is_empty() {
+                return Err(AppError::BadRequest(format!(
+                    "games[{}]: name is required",
+                    i
+                )));
+            }
+            if !game_paths.insert(game.path.as_str()) {
+                return Err(AppError::BadRequest(format!(
+                    "games[{}]: duplicate game path '{}'",
+                    i, game.path
+                )));
+            }
+            Game::validate_player_count(game.min_players, game.max_players)
+                .map_err(|e| AppError::BadRequest(format!("games[{}]: {}", i, e)))?;
+            Game::validate_path(&game.path)
+                .map_err(|e| AppError::BadRequest(format!("games[{}]: {}", i, e)))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Result of seeding a single row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeedOutcome {
+    Inserted,
+    Updated,
+    Skipped,
+}
+
+/// Per-table counts returned by the seed endpoint.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeedCounts {
+    pub inserted: usize,
+    pub updated: usize,
+    pub skipped: usize,
+}
+
+impl SeedCounts {
+    pub fn record(&mut self, outcome: SeedOutcome) {
+        match outcome {
+            SeedOutcome::Inserted => self.inserted += 1,
+            SeedOutcome::Updated => self.updated += 1,
+            SeedOutcome::Skipped => self.skipped += 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn season(name: &str, start: &str, end: &str) -> SeedSeason {
+        SeedSeason {
+            name: name.into(),
+            description: None,
+            start_date: start.into(),
+            end_date: end.into(),
+            allow_overlap: false,
+        }
+    }
+
+    fn game(path: &str, min: i16, max: i16) -> SeedGame {
+        SeedGame {
+            name: path.into(),
+            path: path.into(),
+            description: "desc".into(),
+            image_url: "https://example.com/img.png".into(),
+            min_players: min,
+            max_players: max,
+            category: None,
+        }
+    }
+
+    #[test]
+    fn test_valid_bundle() {
+        let bundle = SeedBundle {
+            mode: SeedMode::Skip,
+            seasons: vec![season("S1", "2026-01-01 00:00:00", "2026-02-01 00:00:00")],
+            games: vec![game("word-wars", 2, 8)],
+        };
+        assert!(bundle.validate().is_ok());
+    }
+
+    #[test]
+    fn test_season_dates_must_be_ordered() {
+        let bundle = SeedBundle {
+            mode: SeedMode::Skip,
+            seasons: vec![
+                season("S1", "2026-01-01 00:00:00", "2026-02-01 00:00:00"),
+                season("S2", "2026-03-01 00:00:00", "2026-02-01 00:00:00"),
+            ],
+            games: vec![],
+        };
+        let err = bundle.validate().unwrap_err().to_string();
+        assert!(err.contains("seasons[1]"), "{}", err);
+    }
+
+    #[test]
+    fn test_game_player_bounds_checked() {
+        let bundle = SeedBundle {
+            mode: SeedMode::Update,
+            seasons: vec![],
+            games: vec![game("ok-game", 1, 4), game("bad-game", 5, 2)],
+        };
+        let err = bundle.validate().unwrap_err().to_string();
+        assert!(err.contains("games[1]"), "{}", err);
+    }
+
+    #[test]
+    fn test_duplicate_paths_rejected() {
+        let bundle = SeedBundle {
+            mode: SeedMode::Skip,
+            seasons: vec![],
+            games: vec![game("same", 1, 2), game("same", 1, 2)],
+        };
+        assert!(bundle.validate().is_err());
+    }
+}
@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// A lobby's settlement payload with the server's signature over it.
+///
+/// `payload` is the hex of the exact signed bytes (a consensus-serialized
+/// Clarity tuple); verifiers check `signature` against its SHA-256 rather than
+/// re-serializing anything.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedResults {
+    pub lobby_id: Uuid,
+    pub payload: String,
+    /// secp256k1 signature over the SHA-256 of `payload`, as `r || s ||
+    /// recovery id` hex
+    pub signature: String,
+    /// Compressed secp256k1 public key of the signer, hex encoded
+    pub public_key: String,
+}
@@ -0,0 +1,163 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Rating assigned to a player the first time they play a game
+pub const DEFAULT_SKILL_RATING: f64 = 1200.0;
+
+/// Maximum rating change from a single game
+pub const ELO_K_FACTOR: f64 = 32.0;
+
+/// Per-game Elo skill rating, independent of seasonal wars points.
+/// Maps to `skill_ratings` table in PostgreSQL
+///
+/// # Database Schema
+/// - Primary key: `(user_id, game_id)`
+/// - Foreign keys: `user_id` (users), `game_id` (games)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillRating {
+    pub user_id: Uuid,
+    pub game_id: Uuid,
+    pub rating: f64,
+    pub games_played: i32,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// A player's placement in a finished game with their pre-game rating.
+#[derive(Debug, Clone, Copy)]
+pub struct EloPlacement {
+    pub user_id: Uuid,
+    /// 1-based final rank (ties share a rank)
+    pub rank: usize,
+    pub rating: f64,
+}
+
+/// Expected score of a player rated `rating` against `opponent` (0.0..=1.0).
+pub fn expected_score(rating: f64, opponent: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf((opponent - rating) / 400.0))
+}
+
+/// Compute rating changes for a multiplayer result.
+///
+/// The game is scored as a round of pairwise matches: each player "beats" every
+/// player ranked below them and draws with equal ranks. The summed
+/// `actual - expected` is scaled by `K / (n - 1)` so a game's total swing stays
+/// comparable regardless of player count.
+///
+/// Only pre-game ratings are used, so the result does not depend on the order
+/// of `placements`.
+pub fn calculate_elo_changes(placements: &[EloPlacement]) -> HashMap<Uuid, f64> {
+    let n = placements.len();
+    if n < 2 {
+        return placements.iter().map(|p| (p.user_id, 0.0)).collect();
+    }
+
+    let scale = ELO_K_FACTOR / (n - 1) as f64;
+
+    // Sum in a fixed order so float rounding is identical however input is ordered
+    let mut sorted: Vec<&EloPlacement> = placements.iter().collect();
+    sorted.sort_by_key(|p| p.user_id);
+
+    placements
+        .iter()
+        .map(|player| {
+            let delta: f64 = sorted
+                .iter()
+                .filter(|other| other.user_id != player.user_id)
+                .map(|other| {
+                    let actual = match player.rank.cmp(&other.rank) {
+                        std::cmp::Ordering::Less => 1.0,
+                        std::cmp::Ordering::Equal => 0.5,
+                        std::cmp::Ordering::Greater => 0.0,
+                    };
+                    actual - expected_score(player.rating, other.rating)
+                })
+                .sum();
+            (player.user_id, delta * scale)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placement(rank: usize, rating: f64) -> EloPlacement {
+        EloPlacement {
+            user_id: Uuid::new_v4(),
+            rank,
+            rating,
+        }
+    }
+
+    #[test]
+    fn test_win_against_stronger_opponents_gains_more() {
+        let vs_strong = [
+            placement(1, 1200.0),
+            placement(2, 1600.0),
+            placement(3, 1500.0),
+        ];
+        let vs_weak = [
+            placement(1, 1200.0),
+            placement(2, 900.0),
+            placement(3, 1000.0),
+        ];
+
+        let gain_strong = calculate_elo_changes(&vs_strong)[&vs_strong[0].user_id];
+        let gain_weak = calculate_elo_changes(&vs_weak)[&vs_weak[0].user_id];
+
+        assert!(gain_strong > 0.0 && gain_weak > 0.0);
+        assert!(
+            gain_strong > gain_weak,
+            "gain vs stronger ({gain_strong}) should exceed gain vs weaker ({gain_weak})"
+        );
+    }
+
+    #[test]
+    fn test_changes_are_order_independent() {
+        let players = [
+            placement(2, 1300.0),
+            placement(1, 1100.0),
+            placement(3, 1250.0),
+            placement(4, 1400.0),
+        ];
+        let mut reversed = players;
+        reversed.reverse();
+
+        let a = calculate_elo_changes(&players);
+        let b = calculate_elo_changes(&reversed);
+        for p in &players {
+            assert_eq!(a[&p.user_id], b[&p.user_id]);
+        }
+    }
+
+    #[test]
+    fn test_changes_are_zero_sum() {
+        let players = [
+            placement(1, 1350.0),
+            placement(2, 1200.0),
+            placement(2, 1180.0),
+            placement(4, 990.0),
+        ];
+        let total: f64 = calculate_elo_changes(&players).values().sum();
+        assert!(total.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_equal_ratings_two_players() {
+        let players = [placement(1, 1200.0), placement(2, 1200.0)];
+        let changes = calculate_elo_changes(&players);
+        assert_eq!(changes[&players[0].user_id], ELO_K_FACTOR / 2.0);
+        assert_eq!(changes[&players[1].user_id], -ELO_K_FACTOR / 2.0);
+    }
+
+    #[test]
+    fn test_single_player_unchanged() {
+        let players = [placement(1, 1200.0)];
+        assert_eq!(calculate_elo_changes(&players)[&players[0].user_id], 0.0);
+    }
+}
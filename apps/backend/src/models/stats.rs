@@ -0,0 +1,47 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Platform-wide totals for community pages.
+///
+/// Only games that ran to the end count. Everything is zero (or `None`) on
+/// an empty database.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsOverview {
+    pub games_played: i64,
+    /// Pot volume of finished games per token, largest first
+    pub pot_volume: Vec<TokenVolume>,
+    pub active_players: ActivePlayers,
+    pub most_popular_game: Option<PopularGame>,
+    /// Mean time from start to finish, over games with both recorded
+    pub average_game_duration_secs: f64,
+}
+
+/// Total pot of finished games in one token
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenVolume {
+    pub token_symbol: String,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub volume: Decimal,
+}
+
+/// Distinct users who finished a ranked game within each window
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivePlayers {
+    pub last_24h: i64,
+    pub last_7d: i64,
+}
+
+/// The game with the most finished games
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct PopularGame {
+    pub game_id: Uuid,
+    pub name: String,
+    pub path: String,
+    pub games_played: i64,
+}
@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+
+const SECS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// Index of the streak day containing `timestamp` (unix seconds).
+///
+/// `day_offset_secs` shifts the day boundary away from UTC midnight; e.g.
+/// `-5 * 3600` makes days roll over at midnight UTC-5.
+pub fn streak_day(timestamp: i64, day_offset_secs: i64) -> i64 {
+    (timestamp + day_offset_secs).div_euclid(SECS_PER_DAY)
+}
+
+/// A player's win and daily-participation streaks.
+///
+/// Stored in Redis; see `StreakRepository`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserStreaks {
+    /// Consecutive games won (resets on any non-win)
+    pub current_win_streak: u32,
+    pub longest_win_streak: u32,
+    /// Consecutive days with at least one finished game
+    pub daily_streak: u32,
+    pub longest_daily_streak: u32,
+    /// Streak day of the last finished game
+    pub last_played_day: Option<i64>,
+}
+
+impl UserStreaks {
+    /// Record a finished game played on streak day `day`.
+    pub fn record_game(&mut self, won: bool, day: i64) {
+        if won {
+            self.current_win_streak += 1;
+            self.longest_win_streak = self.longest_win_streak.max(self.current_win_streak);
+        } else {
+            self.current_win_streak = 0;
+        }
+
+        match self.last_played_day {
+            Some(last) if last == day => {}
+            Some(last) if last + 1 == day => self.daily_streak += 1,
+            // First game, a missed day, or a clock that went backwards
+            _ => self.daily_streak = 1,
+        }
+        self.longest_daily_streak = self.longest_daily_streak.max(self.daily_streak);
+        self.last_played_day = Some(self.last_played_day.map_or(day, |last| last.max(day)));
+    }
+
+    /// Streaks as seen on `today`: a daily streak not continued yesterday or
+    /// today has lapsed.
+    pub fn as_of(mut self, today: i64) -> Self {
+        if self.last_played_day.is_none_or(|last| last < today - 1) {
+            self.daily_streak = 0;
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_win_streak_increments_and_resets_on_loss() {
+        let mut s = UserStreaks::default();
+        s.record_game(true, 10);
+        s.record_game(true, 10);
+        s.record_game(true, 10);
+        assert_eq!(s.current_win_streak, 3);
+
+        s.record_game(false, 10);
+        assert_eq!(s.current_win_streak, 0);
+        assert_eq!(s.longest_win_streak, 3);
+
+        s.record_game(true, 10);
+        assert_eq!(s.current_win_streak, 1);
+        assert_eq!(s.longest_win_streak, 3);
+    }
+
+    #[test]
+    fn test_daily_streak_rolls_over_and_resets_on_missed_day() {
+        let mut s = UserStreaks::default();
+        s.record_game(false, 100);
+        s.record_game(false, 100);
+        assert_eq!(s.daily_streak, 1);
+
+        s.record_game(false, 101);
+        assert_eq!(s.daily_streak, 2);
+        assert_eq!(s.as_of(102).daily_streak, 2);
+        assert_eq!(s.as_of(103).daily_streak, 0);
+
+        s.record_game(false, 103);
+        assert_eq!(s.daily_streak, 1);
+        assert_eq!(s.longest_daily_streak, 2);
+    }
+
+    #[test]
+    fn test_day_boundary_offset() {
+        // 2026-10-12 03:00 UTC
+        let ts = 1_791_774_000;
+        let utc_day = streak_day(ts, 0);
+
+        // At UTC-5 it is still the 11th
+        assert_eq!(streak_day(ts, -5 * 3600), utc_day - 1);
+        // An hour later UTC has not rolled over, but UTC+21 has
+        assert_eq!(streak_day(ts + 3600, 0), utc_day);
+        assert_eq!(streak_day(ts + 3600, 21 * 3600), utc_day + 1);
+    }
+}
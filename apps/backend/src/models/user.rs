@@ -8,6 +8,12 @@ use uuid::Uuid;
 
 use super::WalletAddress;
 
+/// Name shown in place of a deleted user's username and display name
+pub const DELETED_USER_NAME: &str = "[deleted user]";
+
+/// Author id that a deleted user's chat messages are reattributed to
+pub const DELETED_USER_ID: Uuid = Uuid::nil();
+
 /// User model mapping to PostgreSQL `users` table.
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 #[serde(rename_all = "camelCase")]
@@ -35,6 +41,20 @@ impl User {
         EmailAddress::from_str(&self.email).map_err(|e| format!("Invalid email: {}", e))
     }
 
+    /// Placeholder wallet for a deleted user.
+    ///
+    /// Derived from the user id so it stays unique and still passes
+    /// `WalletAddress` validation when the row is read back.
+    pub fn anonymized_wallet(user_id: Uuid) -> WalletAddress {
+        let address = format!("SN000{}", user_id.simple().to_string().to_uppercase());
+        WalletAddress::new(address).expect("uuid hex digits are valid c32 characters")
+    }
+
+    /// Placeholder email for a deleted user, unique per user id.
+    pub fn anonymized_email(user_id: Uuid) -> String {
+        format!("{}@deleted.invalid", user_id.simple())
+    }
+
     /// Validate email string
     pub fn validate_email(email: &str) -> Result<String, String> {
         EmailAddress::from_str(email)
@@ -42,3 +62,90 @@ impl User {
             .map_err(|e| format!("Invalid email: {}", e))
     }
 }
+
+/// Derived profile fields, computed on request (`?include=stats`).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserStats {
+    /// Finished games across every game type
+    pub games_played: i64,
+    /// First places since wins were recorded. Games finished before then
+    /// count in `games_played` but not here: no placements were stored for
+    /// them, so they can't be backfilled
+    pub wins: i64,
+    /// `wins / games_played`, from 0.0 to 1.0; 0.0 before the first game
+    pub win_rate: f64,
+    /// Wars points in the current season; `None` when no season is running
+    pub season_points: Option<f64>,
+    /// Stable seed for generating a default avatar
+    pub avatar_seed: String,
+}
+
+impl UserStats {
+    pub fn new(user_id: Uuid, games_played: i64, wins: i64, season_points: Option<f64>) -> Self {
+        let win_rate = if games_played > 0 {
+            wins as f64 / games_played as f64
+        } else {
+            0.0
+        };
+
+        Self {
+            games_played,
+            wins,
+            win_rate,
+            season_points,
+            avatar_seed: avatar_seed(user_id),
+        }
+    }
+}
+
+/// Default avatar seed for a user: FNV-1a of the id bytes, as 16 hex digits.
+///
+/// Uses a fixed hash rather than `DefaultHasher` so the seed (and the avatar
+/// the frontend draws from it) never changes between releases.
+pub fn avatar_seed(user_id: Uuid) -> String {
+    let hash = user_id
+        .as_bytes()
+        .iter()
+        .fold(0xcbf29ce484222325u64, |hash, &byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        });
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anonymized_wallet_is_valid_and_unique() {
+        let a = Uuid::new_v4();
+        let b = Uuid::new_v4();
+
+        let wallet = User::anonymized_wallet(a);
+        assert!(WalletAddress::new(wallet.as_ref()).is_ok());
+        assert_eq!(wallet, User::anonymized_wallet(a));
+        assert_ne!(wallet, User::anonymized_wallet(b));
+    }
+
+    #[test]
+    fn test_anonymized_email_is_valid() {
+        let email = User::anonymized_email(Uuid::new_v4());
+        assert!(User::validate_email(&email).is_ok());
+    }
+
+    #[test]
+    fn test_avatar_seed_is_stable_per_user() {
+        let id = Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap();
+        assert_eq!(avatar_seed(id), avatar_seed(id));
+        assert_eq!(avatar_seed(id).len(), 16);
+        assert_ne!(avatar_seed(id), avatar_seed(Uuid::new_v4()));
+    }
+
+    #[test]
+    fn test_win_rate() {
+        let id = Uuid::new_v4();
+        assert_eq!(UserStats::new(id, 0, 0, None).win_rate, 0.0);
+        assert_eq!(UserStats::new(id, 8, 2, Some(40.0)).win_rate, 0.25);
+    }
+}
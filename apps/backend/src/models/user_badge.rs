@@ -0,0 +1,17 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// A badge awarded to a user for a season.
+///
+/// `badge_id` refers to a definition registered with the `BadgeEngine`.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct UserBadge {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub season_id: i32,
+    pub badge_id: String,
+    pub awarded_at: NaiveDateTime,
+}
@@ -21,3 +21,42 @@ pub struct UserWarsPoints {
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
 }
+
+/// A user's place on a season leaderboard, overall or for a single game.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct LeaderboardEntry {
+    pub user_id: Uuid,
+    pub wallet_address: String,
+    /// 1 is the top; users on equal points share a rank
+    pub rank: i32,
+    pub points: f64,
+}
+
+/// Wars points a user was awarded for one lobby's game
+/// Maps to `wars_point_awards`: one row per player per lobby, the ledger that
+/// awards are corrected against
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct WarsPointAward {
+    pub lobby_id: Uuid,
+    pub user_id: Uuid,
+    pub season_id: i32,
+    pub game_id: Option<Uuid>,
+    pub points: f64,
+    /// Players still in when the award was made; None for awards recorded
+    /// before it was kept
+    pub active_players: Option<i32>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// A player's award for a lobby before and after it was recomputed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AwardCorrection {
+    pub user_id: Uuid,
+    /// Points awarded before; 0 if the player had no award
+    pub previous: f64,
+    pub points: f64,
+}
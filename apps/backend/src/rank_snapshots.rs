@@ -0,0 +1,75 @@
+// Rank snapshots: periodically record where every user stands in the season
+//
+// With RANK_SNAPSHOT_INTERVAL_SECS set, a background task records the current
+// season's leaderboard ranks each interval, building the series served by the
+// rank-history endpoint. The first instance to take the interval's Redis lock
+// records the snapshot; the lock is left to expire rather than released, so
+// the other instances skip that interval instead of recording it again.
+
+use std::time::Duration;
+
+use crate::{
+    db::{rank_snapshot::RankSnapshotRepository, season::SeasonRepository},
+    errors::AppError,
+    models::RedisKey,
+    redis_lock::RedisLock,
+    state::AppState,
+};
+
+/// Default time between snapshots (1 hour)
+pub const DEFAULT_RANK_SNAPSHOT_INTERVAL_SECS: u64 = 60 * 60;
+
+/// Spawn the background task that snapshots leaderboard ranks, if enabled.
+pub fn spawn_rank_snapshots(state: AppState) {
+    let interval_secs = state.config.rank_snapshot_interval_secs;
+    if interval_secs == 0 {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let period = Duration::from_secs(interval_secs);
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            match take_rank_snapshot(&state, period).await {
+                Ok(Some(count)) => tracing::debug!("Recorded ranks for {} users", count),
+                Ok(None) => {}
+                Err(e) => tracing::error!("Rank snapshot failed: {}", e),
+            }
+        }
+    });
+}
+
+/// Record the current season's ranks, unless another instance already has
+/// within `period`.
+///
+/// Returns the number of users recorded, or None when skipped or no season
+/// is running.
+pub async fn take_rank_snapshot(
+    state: &AppState,
+    period: Duration,
+) -> Result<Option<u64>, AppError> {
+    // Slightly shorter than the period, so the next tick finds it free
+    let hold = period.mul_f64(0.9);
+    if RedisLock::try_acquire(&state.redis, &RedisKey::lock("rank_snapshot"), hold)
+        .await?
+        .is_none()
+    {
+        return Ok(None);
+    }
+
+    let season = match SeasonRepository::new(state.postgres.clone())
+        .with_cache(state.redis.clone())
+        .current()
+        .await
+    {
+        Ok(season) => season,
+        Err(AppError::NotFound(_)) => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    RankSnapshotRepository::new(state.postgres.clone())
+        .record(season.id())
+        .await
+        .map(Some)
+}
@@ -0,0 +1,506 @@
+// Idle lobby reaper: cancels waiting lobbies nobody is connected to and cleans up after them
+//
+// Connections count on any instance: each reaper tick first marks this
+// instance's room connections as seen in the shared presence, and a lobby is
+// spared while any instance has been seen connected to it within
+// `PRESENCE_STALE_SECS`.
+//
+// The same pass hands off waiting lobbies whose creator has gone: once the
+// creator has had no open socket and no activity for the grace period, the
+// earliest-joined remaining player becomes creator. A lobby with nobody else
+// in it is cancelled instead.
+
+use chrono::Utc;
+use redis::AsyncCommands;
+use rust_decimal::Decimal;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::{
+    db::{
+        lobby::LobbyRepository, lobby_chat::LobbyChatRepository,
+        lobby_presence::LobbyPresenceRepository, lobby_refund::LobbyRefundRepository,
+        lobby_state::LobbyStateRepository, player_state::PlayerStateRepository,
+    },
+    errors::AppError,
+    models::{Lobby, LobbyState, LobbyStatus, PlayerState, RedisKey, player_state::PlayerStatus},
+    state::AppState,
+    ws::{
+        broadcast,
+        lobby::LobbyServerMessage,
+        room::{RoomServerMessage, context::refresh_room_context},
+    },
+};
+
+/// Default idle time before an empty waiting lobby is reaped (30 minutes)
+pub const DEFAULT_LOBBY_IDLE_TIMEOUT_SECS: i64 = 30 * 60;
+
+/// Default time an absent creator has to come back before the lobby passes on (2 minutes)
+pub const DEFAULT_CREATOR_GRACE_SECS: i64 = 2 * 60;
+
+/// How often the reaper scans for idle lobbies
+const REAPER_INTERVAL_SECS: u64 = 60;
+
+/// Presence not refreshed for this long belongs to an instance that is gone
+const PRESENCE_STALE_SECS: i64 = 3 * REAPER_INTERVAL_SECS as i64;
+
+/// Spawn the background task that periodically reaps idle lobbies.
+pub fn spawn_lobby_reaper(state: AppState) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(REAPER_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            refresh_presence(&state).await;
+            match hand_off_abandoned_lobbies(&state, state.config.creator_grace_secs).await {
+                Ok(handoffs) if !handoffs.is_empty() => {
+                    tracing::info!("Handed off {} abandoned lobbies", handoffs.len());
+                }
+                Ok(_) => {}
+                Err(e) => tracing::error!("Creator handoff pass failed: {}", e),
+            }
+            match reap_idle_lobbies(&state, state.config.lobby_idle_timeout_secs).await {
+                Ok(reaped) if !reaped.is_empty() => {
+                    tracing::info!("Reaped {} idle lobbies", reaped.len());
+                }
+                Ok(_) => {}
+                Err(e) => tracing::error!("Lobby reaper pass failed: {}", e),
+            }
+        }
+    });
+}
+
+/// Cancel every waiting lobby that has no open sockets and no activity for
+/// `idle_timeout_secs`. Returns the ids of reaped lobbies.
+///
+/// Lobbies with a connected socket are always left alone, however stale their
+/// stored state looks.
+pub async fn reap_idle_lobbies(
+    state: &AppState,
+    idle_timeout_secs: i64,
+) -> Result<Vec<Uuid>, AppError> {
+    let lobby_state_repo = LobbyStateRepository::new(state.redis.clone());
+    let player_repo = PlayerStateRepository::new(state.redis.clone());
+
+    let now = Utc::now().timestamp();
+    let mut reaped = Vec::new();
+
+    for lobby_state in lobby_state_repo.get_by_status(LobbyStatus::Waiting).await? {
+        let lobby_id = lobby_state.lobby_id;
+        if has_connections(state, lobby_id).await {
+            continue;
+        }
+
+        let players = player_repo.list_players(lobby_id).await?;
+        if now - last_activity(&lobby_state, &players) < idle_timeout_secs {
+            continue;
+        }
+
+        // A player may have connected while we were reading state
+        if has_connections(state, lobby_id).await {
+            continue;
+        }
+
+        match reap_lobby(state, lobby_id, &players).await {
+            Ok(true) => reaped.push(lobby_id),
+            Ok(false) => {}
+            Err(e) => tracing::error!("Failed to reap lobby {}: {}", lobby_id, e),
+        }
+    }
+
+    Ok(reaped)
+}
+
+/// What happened to a lobby whose creator didn't come back
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CreatorHandoff {
+    /// `to` is the creator now
+    Transferred { from: Uuid, to: Uuid },
+    /// Nobody was left to take over, so the lobby was cancelled
+    Cancelled,
+}
+
+/// Hand off every waiting lobby whose creator has had no socket open and no
+/// activity for `grace_secs`. Returns what was done to each lobby.
+pub async fn hand_off_abandoned_lobbies(
+    state: &AppState,
+    grace_secs: i64,
+) -> Result<Vec<(Uuid, CreatorHandoff)>, AppError> {
+    let lobby_state_repo = LobbyStateRepository::new(state.redis.clone());
+    let player_repo = PlayerStateRepository::new(state.redis.clone());
+
+    let now = Utc::now().timestamp();
+    let mut handoffs = Vec::new();
+
+    for lobby_state in lobby_state_repo.get_by_status(LobbyStatus::Waiting).await? {
+        let lobby_id = lobby_state.lobby_id;
+        let players = player_repo.list_players(lobby_id).await?;
+        let Some(creator) = players.iter().find(|p| p.is_creator) else {
+            continue;
+        };
+        if now - player_activity(creator) < grace_secs
+            || user_connected(state, lobby_id, creator.user_id).await
+        {
+            continue;
+        }
+
+        match hand_off_lobby(state, lobby_id, creator.user_id, &players).await {
+            Ok(Some(handoff)) => handoffs.push((lobby_id, handoff)),
+            Ok(None) => {}
+            Err(e) => tracing::error!("Failed to hand off lobby {}: {}", lobby_id, e),
+        }
+    }
+
+    Ok(handoffs)
+}
+
+/// The player who takes over from the creator: the earliest to join, with
+/// ties going to the lower user id.
+pub fn next_creator(players: &[PlayerState]) -> Option<&PlayerState> {
+    players
+        .iter()
+        .filter(|p| !p.is_creator && p.status == PlayerStatus::Joined)
+        .min_by_key(|p| (p.joined_at, p.user_id))
+}
+
+/// Most recent activity of one player (unix seconds).
+fn player_activity(player: &PlayerState) -> i64 {
+    let ping_secs = player.last_ping.map(|ms| (ms / 1000) as i64).unwrap_or(0);
+    player.updated_at.max(ping_secs)
+}
+
+/// Most recent activity in a lobby (unix seconds), from lobby and player state.
+pub fn last_activity(lobby_state: &LobbyState, players: &[PlayerState]) -> i64 {
+    let ping_secs = |ms: u64| (ms / 1000) as i64;
+
+    let lobby_activity = lobby_state
+        .updated_at
+        .max(lobby_state.creator_last_ping.map(ping_secs).unwrap_or(0));
+
+    players
+        .iter()
+        .map(player_activity)
+        .fold(lobby_activity, i64::max)
+}
+
+/// Deposits to return for a cancelled lobby as `(user_id, wallet_address, amount)`.
+///
+/// Sponsored lobbies refund the creator's pool; paid lobbies refund every
+/// joined player's entry.
+pub fn compute_refunds(lobby: &Lobby, players: &[PlayerState]) -> Vec<(Uuid, String, Decimal)> {
+    let refund = |p: &PlayerState, amount: Decimal| (p.user_id, p.wallet_address.clone(), amount);
+
+    if lobby.is_sponsored {
+        let pool = lobby.current_amount.unwrap_or_default();
+        if pool <= Decimal::ZERO {
+            return Vec::new();
+        }
+        return players
+            .iter()
+            .filter(|p| p.user_id == lobby.creator_id)
+            .map(|p| refund(p, pool))
+            .collect();
+    }
+
+    match lobby.entry_amount {
+        Some(entry) if entry > Decimal::ZERO => players
+            .iter()
+            .filter(|p| p.status == PlayerStatus::Joined)
+            .map(|p| refund(p, entry))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Mark this instance's room connections as seen, so other instances spare
+/// their lobbies.
+async fn refresh_presence(state: &AppState) {
+    let rooms: Vec<(Uuid, Vec<Uuid>)> = {
+        let indices = state.indices.lock().await;
+        indices
+            .by_lobby
+            .iter()
+            .map(|(lobby_id, conns)| (*lobby_id, conns.iter().copied().collect()))
+            .collect()
+    };
+
+    let presence_repo = LobbyPresenceRepository::new(state.redis.clone());
+    for (lobby_id, conn_ids) in rooms {
+        if let Err(e) = presence_repo.touch(lobby_id, &conn_ids).await {
+            tracing::warn!("Failed to refresh presence in {}: {}", lobby_id, e);
+        }
+    }
+}
+
+/// Whether the lobby has a connection open here or recently seen on any
+/// instance. Unknown presence counts as connected.
+async fn has_connections(state: &AppState, lobby_id: Uuid) -> bool {
+    let local = {
+        let indices = state.indices.lock().await;
+        indices
+            .by_lobby
+            .get(&lobby_id)
+            .is_some_and(|conns| !conns.is_empty())
+    };
+    if local {
+        return true;
+    }
+
+    let since = Utc::now().timestamp() - PRESENCE_STALE_SECS;
+    LobbyPresenceRepository::new(state.redis.clone())
+        .has_connections(lobby_id, since)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("Presence of {} unavailable: {}", lobby_id, e);
+            true
+        })
+}
+
+async fn user_connected(state: &AppState, lobby_id: Uuid, user_id: Uuid) -> bool {
+    let indices = state.indices.lock().await;
+    let Some(conn_ids) = indices.get_user_connections(&user_id) else {
+        return false;
+    };
+    let conns = state.connections.lock().await;
+    conn_ids
+        .iter()
+        .filter_map(|conn_id| conns.get(conn_id))
+        .any(|conn| conn.lobby_id() == Some(lobby_id))
+}
+
+/// Pass the lobby from `from` to the next creator, or cancel it if there is none.
+///
+/// Returns None if the lobby left the waiting state first.
+async fn hand_off_lobby(
+    state: &AppState,
+    lobby_id: Uuid,
+    from: Uuid,
+    players: &[PlayerState],
+) -> Result<Option<CreatorHandoff>, AppError> {
+    let Some(next) = next_creator(players) else {
+        let cancelled = reap_lobby(state, lobby_id, players).await?;
+        return Ok(cancelled.then_some(CreatorHandoff::Cancelled));
+    };
+    let to = next.user_id;
+
+    let lobby_repo = LobbyRepository::new(state.postgres.clone());
+    if lobby_repo.transfer_creator(lobby_id, to).await?.is_none() {
+        return Ok(None);
+    }
+
+    let player_repo = PlayerStateRepository::new(state.redis.clone());
+    player_repo.set_creator(lobby_id, from, false).await?;
+    player_repo.set_creator(lobby_id, to, true).await?;
+    refresh_room_context(state, lobby_id, from).await;
+    refresh_room_context(state, lobby_id, to).await;
+
+    broadcast::broadcast_room(
+        state,
+        lobby_id,
+        &RoomServerMessage::CreatorChanged {
+            previous_creator_id: from,
+            creator_id: to,
+        },
+    )
+    .await;
+    broadcast::broadcast_lobby_update(state.clone(), lobby_id).await;
+
+    tracing::info!("Lobby {} handed off from {} to {}", lobby_id, from, to);
+
+    Ok(Some(CreatorHandoff::Transferred { from, to }))
+}
+
+/// Cancel one lobby, record refunds, notify players and drop its Redis keys.
+///
+/// Returns `false` if the lobby left the waiting state before it could be cancelled.
+async fn reap_lobby(
+    state: &AppState,
+    lobby_id: Uuid,
+    players: &[PlayerState],
+) -> Result<bool, AppError> {
+    let lobby_repo = LobbyRepository::new(state.postgres.clone());
+
+    match lobby_repo.cancel_if_waiting(lobby_id).await? {
+        Some(lobby) => {
+            let refunds = compute_refunds(&lobby, players);
+            if !refunds.is_empty() {
+                LobbyRefundRepository::new(state.postgres.clone())
+                    .create_refunds(&lobby, &refunds)
+                    .await?;
+            }
+
+            for player in players {
+                let refund = refunds
+                    .iter()
+                    .find(|(user_id, _, _)| *user_id == player.user_id)
+                    .map(|(_, _, amount)| *amount);
+                broadcast::broadcast_user(
+                    state,
+                    player.user_id,
+                    &LobbyServerMessage::LobbyCancelled { lobby_id, refund },
+                )
+                .await;
+            }
+        }
+        // Redis state without a lobby row is an orphan; clean it up too
+        None if !lobby_repo.exists(lobby_id).await? => {}
+        None => return Ok(false),
+    }
+
+    cleanup_redis(state, lobby_id).await?;
+
+    broadcast::broadcast_lobby_removal(state, lobby_id).await;
+
+    tracing::info!("Reaped idle lobby {}", lobby_id);
+
+    Ok(true)
+}
+
+async fn cleanup_redis(state: &AppState, lobby_id: Uuid) -> Result<(), AppError> {
+    PlayerStateRepository::new(state.redis.clone())
+        .cleanup_lobby(lobby_id)
+        .await?;
+    LobbyStateRepository::new(state.redis.clone())
+        .delete_state_soft(lobby_id)
+        .await?;
+    LobbyChatRepository::new(state.redis.clone())
+        .cleanup_lobby(lobby_id)
+        .await
+        .map_err(AppError::RedisError)?;
+
+    let mut conn = state.redis.get().await?;
+    let _: () = conn
+        .del(&[
+            RedisKey::lobby_join_requests(lobby_id),
+            RedisKey::lobby_countdown(lobby_id),
+        ])
+        .await
+        .map_err(AppError::RedisCommandError)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player(lobby_id: Uuid, is_creator: bool) -> PlayerState {
+        PlayerState::new(
+            Uuid::new_v4(),
+            lobby_id,
+            "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7".to_string(),
+            None,
+            None,
+            10.0,
+            Some("0xabc".to_string()),
+            is_creator,
+        )
+    }
+
+    fn lobby(
+        creator_id: Uuid,
+        entry: Option<Decimal>,
+        current: Option<Decimal>,
+        sponsored: bool,
+    ) -> Lobby {
+        let now = Utc::now().naive_utc();
+        Lobby {
+            id: Uuid::new_v4(),
+            path: "idle".into(),
+            name: "Idle".into(),
+            description: None,
+            game_id: Uuid::new_v4(),
+            game_path: "lexi-wars".into(),
+            creator_id,
+            entry_amount: entry,
+            current_amount: current,
+            token_symbol: Some("STX".into()),
+            token_contract_id: None,
+            contract_address: None,
+            is_private: false,
+            is_sponsored: sponsored,
+            spectators_allowed: true,
+            anonymize_players: false,
+            practice_bot: None,
+            auto_approve_trust_threshold: None,
+            payout_percents: None,
+            payout_underfill: Default::default(),
+            status: LobbyStatus::Waiting,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn test_last_activity_uses_latest_ping() {
+        let lobby_id = Uuid::new_v4();
+        let mut state = LobbyState::new(lobby_id);
+        state.updated_at = 1_000;
+        state.creator_last_ping = Some(2_000_000);
+
+        let mut p = player(lobby_id, false);
+        p.updated_at = 500;
+        p.last_ping = Some(3_500_000);
+
+        assert_eq!(last_activity(&state, &[]), 2_000);
+        assert_eq!(last_activity(&state, &[p]), 3_500);
+    }
+
+    #[test]
+    fn test_next_creator_is_earliest_joined_player() {
+        let creator = player(Uuid::new_v4(), true);
+        let mut early = player(creator.lobby_id, false);
+        early.joined_at = 100;
+        let mut late = player(creator.lobby_id, false);
+        late.joined_at = 200;
+        let mut left = player(creator.lobby_id, false);
+        left.joined_at = 50;
+        left.status = PlayerStatus::NotJoined;
+
+        let players = [creator.clone(), late, left, early.clone()];
+        assert_eq!(
+            next_creator(&players).map(|p| p.user_id),
+            Some(early.user_id)
+        );
+        assert!(next_creator(&[creator]).is_none());
+    }
+
+    #[test]
+    fn test_paid_lobby_refunds_joined_players() {
+        let creator = player(Uuid::new_v4(), true);
+        let mut left = player(creator.lobby_id, false);
+        left.status = PlayerStatus::NotJoined;
+        let joined = player(creator.lobby_id, false);
+
+        let l = lobby(
+            creator.user_id,
+            Some(Decimal::from(5)),
+            Some(Decimal::from(10)),
+            false,
+        );
+        let refunds = compute_refunds(&l, &[creator.clone(), left, joined.clone()]);
+
+        let ids: Vec<Uuid> = refunds.iter().map(|r| r.0).collect();
+        assert_eq!(ids, vec![creator.user_id, joined.user_id]);
+        assert!(refunds.iter().all(|r| r.2 == Decimal::from(5)));
+    }
+
+    #[test]
+    fn test_sponsored_lobby_refunds_creator_pool() {
+        let creator = player(Uuid::new_v4(), true);
+        let other = player(creator.lobby_id, false);
+
+        let l = lobby(creator.user_id, None, Some(Decimal::from(50)), true);
+        let refunds = compute_refunds(&l, &[other, creator.clone()]);
+
+        assert_eq!(refunds.len(), 1);
+        assert_eq!(refunds[0].0, creator.user_id);
+        assert_eq!(refunds[0].2, Decimal::from(50));
+    }
+
+    #[test]
+    fn test_free_lobby_has_no_refunds() {
+        let creator = player(Uuid::new_v4(), true);
+        let l = lobby(creator.user_id, None, None, false);
+        assert!(compute_refunds(&l, &[creator]).is_empty());
+    }
+}
@@ -0,0 +1,272 @@
+// Redis pool wrapper: retries transient connection failures with backoff and tracks health
+
+use std::{
+    fmt::Display,
+    future::Future,
+    sync::{
+        Arc,
+        atomic::{AtomicU8, AtomicU32, Ordering},
+    },
+    time::Duration,
+};
+
+use bb8::{Pool, PooledConnection};
+use bb8_redis::RedisConnectionManager;
+use serde::Serialize;
+
+use crate::errors::AppError;
+
+pub type RedisPool = Pool<RedisConnectionManager>;
+pub type RedisConnection<'a> = PooledConnection<'a, RedisConnectionManager>;
+
+/// Redis connectivity as last observed when checking out a connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RedisHealth {
+    Healthy,
+    /// The last checkout succeeded, but only after retrying
+    Degraded,
+    /// The last checkout failed on every attempt
+    Unavailable,
+}
+
+impl RedisHealth {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => RedisHealth::Healthy,
+            1 => RedisHealth::Degraded,
+            _ => RedisHealth::Unavailable,
+        }
+    }
+}
+
+/// How many times to try a checkout, and how long to wait between tries.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            attempts: 3,
+            base_delay: Duration::from_millis(50),
+            max_delay: Duration::from_millis(500),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before retry number `retry` (0-based): doubles each time, capped.
+    pub fn delay(&self, retry: u32) -> Duration {
+        self.base_delay
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_delay)
+    }
+}
+
+/// Shared health gauge, updated on every checkout.
+#[derive(Debug, Default)]
+struct HealthGauge {
+    state: AtomicU8,
+    consecutive_failures: AtomicU32,
+}
+
+impl HealthGauge {
+    fn record(&self, health: RedisHealth) {
+        self.state.store(health as u8, Ordering::Relaxed);
+    }
+
+    fn record_failure(&self) {
+        self.consecutive_failures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn record_success(&self, retried: bool) {
+        self.consecutive_failures.store(0, Ordering::Relaxed);
+        self.record(if retried {
+            RedisHealth::Degraded
+        } else {
+            RedisHealth::Healthy
+        });
+    }
+}
+
+/// Point-in-time view of the gauge, as reported by the health endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RedisHealthSnapshot {
+    pub status: RedisHealth,
+    pub consecutive_failures: u32,
+}
+
+/// Redis connection pool that retries failed checkouts and reports its health.
+///
+/// Cloning is cheap; clones share the pool and the health gauge.
+#[derive(Clone)]
+pub struct RedisClient {
+    pool: RedisPool,
+    retry: RetryPolicy,
+    gauge: Arc<HealthGauge>,
+}
+
+impl RedisClient {
+    pub fn new(pool: RedisPool) -> Self {
+        Self {
+            pool,
+            retry: RetryPolicy::default(),
+            gauge: Arc::new(HealthGauge::default()),
+        }
+    }
+
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// The underlying pool, without retries.
+    pub fn pool(&self) -> &RedisPool {
+        &self.pool
+    }
+
+    /// Check out a connection, retrying with backoff.
+    ///
+    /// Fails with `ServiceUnavailable` once every attempt has failed.
+    pub async fn get(&self) -> Result<RedisConnection<'_>, AppError> {
+        get_with_retry(&self.retry, &self.gauge, || self.pool.get()).await
+    }
+
+    pub fn health(&self) -> RedisHealthSnapshot {
+        RedisHealthSnapshot {
+            status: RedisHealth::from_u8(self.gauge.state.load(Ordering::Relaxed)),
+            consecutive_failures: self.gauge.consecutive_failures.load(Ordering::Relaxed),
+        }
+    }
+}
+
+async fn get_with_retry<T, E, F, Fut>(
+    policy: &RetryPolicy,
+    gauge: &HealthGauge,
+    mut checkout: F,
+) -> Result<T, AppError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    E: Display,
+{
+    let attempts = policy.attempts.max(1);
+    let mut last_error = String::new();
+
+    for attempt in 0..attempts {
+        match checkout().await {
+            Ok(conn) => {
+                gauge.record_success(attempt > 0);
+                return Ok(conn);
+            }
+            Err(e) => {
+                gauge.record_failure();
+                last_error = e.to_string();
+                if attempt + 1 < attempts {
+                    let delay = policy.delay(attempt);
+                    tracing::warn!(
+                        "Redis checkout failed (attempt {}/{}), retrying in {:?}: {}",
+                        attempt + 1,
+                        attempts,
+                        delay,
+                        last_error
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+            }
+        }
+    }
+
+    gauge.record(RedisHealth::Unavailable);
+    tracing::error!(
+        "Redis unavailable after {} attempts: {}",
+        attempts,
+        last_error
+    );
+
+    Err(AppError::ServiceUnavailable(format!(
+        "Redis unavailable: {}",
+        last_error
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fast_policy() -> RetryPolicy {
+        RetryPolicy {
+            attempts: 3,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(2),
+        }
+    }
+
+    fn snapshot(gauge: &HealthGauge) -> (RedisHealth, u32) {
+        (
+            RedisHealth::from_u8(gauge.state.load(Ordering::Relaxed)),
+            gauge.consecutive_failures.load(Ordering::Relaxed),
+        )
+    }
+
+    #[test]
+    fn test_backoff_doubles_and_caps() {
+        let policy = RetryPolicy {
+            attempts: 5,
+            base_delay: Duration::from_millis(50),
+            max_delay: Duration::from_millis(150),
+        };
+        assert_eq!(policy.delay(0), Duration::from_millis(50));
+        assert_eq!(policy.delay(1), Duration::from_millis(100));
+        assert_eq!(policy.delay(2), Duration::from_millis(150));
+        assert_eq!(policy.delay(40), Duration::from_millis(150));
+    }
+
+    #[tokio::test]
+    async fn test_transient_failure_succeeds_on_retry() {
+        let gauge = HealthGauge::default();
+        let mut calls = 0;
+
+        let result = get_with_retry(&fast_policy(), &gauge, || {
+            calls += 1;
+            let n = calls;
+            async move {
+                if n < 3 {
+                    Err("connection refused")
+                } else {
+                    Ok(n)
+                }
+            }
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(snapshot(&gauge), (RedisHealth::Degraded, 0));
+    }
+
+    #[tokio::test]
+    async fn test_persistent_failure_is_service_unavailable() {
+        let gauge = HealthGauge::default();
+        let mut calls = 0;
+
+        let result: Result<(), _> = get_with_retry(&fast_policy(), &gauge, || {
+            calls += 1;
+            async { Err("timed out") }
+        })
+        .await;
+
+        assert!(matches!(result, Err(AppError::ServiceUnavailable(_))));
+        assert_eq!(calls, 3);
+        assert_eq!(snapshot(&gauge), (RedisHealth::Unavailable, 3));
+
+        // Recovery resets the gauge
+        let ok = get_with_retry(&fast_policy(), &gauge, || async { Ok::<_, &str>(()) }).await;
+        assert!(ok.is_ok());
+        assert_eq!(snapshot(&gauge), (RedisHealth::Healthy, 0));
+    }
+}
@@ -0,0 +1,125 @@
+// Distributed lock: mutual exclusion across instances through a single Redis key
+//
+// Acquiring is `SET key token NX PX ttl`, where the token is unique to the
+// holder. Releasing runs a script that deletes the key only while it still
+// holds that token, so a holder whose lock expired can't free the next
+// holder's lock. A holder that crashes simply lets the TTL run out.
+//
+// Prefer `with_lock`, which always releases. Keep `ttl` comfortably above how
+// long the critical section takes: once it runs out another instance may enter.
+
+use std::{future::Future, time::Duration};
+
+use uuid::Uuid;
+
+use crate::{errors::AppError, state::RedisClient};
+
+/// How often a waiting caller retries the lock
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(25);
+
+/// Deletes the lock only if it still holds our token
+const UNLOCK_SCRIPT: &str = r#"
+if redis.call('GET', KEYS[1]) == ARGV[1] then
+    return redis.call('DEL', KEYS[1])
+end
+return 0
+"#;
+
+/// A held lock. Dropping it without `release` leaves it to expire.
+pub struct RedisLock {
+    redis: RedisClient,
+    key: String,
+    token: String,
+}
+
+impl RedisLock {
+    /// Take the lock if it's free.
+    pub async fn try_acquire(
+        redis: &RedisClient,
+        key: &str,
+        ttl: Duration,
+    ) -> Result<Option<Self>, AppError> {
+        let token = Uuid::new_v4().to_string();
+        let mut conn = redis.get().await?;
+        let acquired: Option<String> = redis::cmd("SET")
+            .arg(key)
+            .arg(&token)
+            .arg("NX")
+            .arg("PX")
+            .arg(ttl.as_millis().max(1) as u64)
+            .query_async(&mut *conn)
+            .await
+            .map_err(AppError::RedisCommandError)?;
+
+        Ok(acquired.map(|_| Self {
+            redis: redis.clone(),
+            key: key.to_string(),
+            token,
+        }))
+    }
+
+    /// Take the lock, waiting up to `wait` for the current holder to finish.
+    ///
+    /// Fails with `Conflict` if it is still held after that.
+    pub async fn acquire(
+        redis: &RedisClient,
+        key: &str,
+        ttl: Duration,
+        wait: Duration,
+    ) -> Result<Self, AppError> {
+        let deadline = tokio::time::Instant::now() + wait;
+        loop {
+            if let Some(lock) = Self::try_acquire(redis, key, ttl).await? {
+                return Ok(lock);
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return Err(AppError::Conflict(format!("Lock {} is busy", key)));
+            }
+            tokio::time::sleep(LOCK_RETRY_INTERVAL).await;
+        }
+    }
+
+    /// The unique token this holder set
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    /// Free the lock. Returns false if it had already expired (and may now
+    /// belong to someone else, whose lock is left alone).
+    pub async fn release(self) -> Result<bool, AppError> {
+        let mut conn = self.redis.get().await?;
+        let deleted: i64 = redis::Script::new(UNLOCK_SCRIPT)
+            .key(&self.key)
+            .arg(&self.token)
+            .invoke_async(&mut *conn)
+            .await
+            .map_err(AppError::RedisCommandError)?;
+        Ok(deleted == 1)
+    }
+}
+
+/// Run `f` while holding the lock on `key`, then release it.
+///
+/// Waits up to `ttl` for the lock. The lock is released whether `f` succeeds
+/// or fails; a failed release is only logged, since the lock expires anyway.
+pub async fn with_lock<F, Fut, T>(
+    redis: &RedisClient,
+    key: &str,
+    ttl: Duration,
+    f: F,
+) -> Result<T, AppError>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<T, AppError>>,
+{
+    let lock = RedisLock::acquire(redis, key, ttl, ttl).await?;
+    let result = f().await;
+
+    match lock.release().await {
+        Ok(true) => {}
+        Ok(false) => tracing::warn!("Lock {} expired before its holder finished", key),
+        Err(e) => tracing::warn!("Failed to release lock {}: {}", key, e),
+    }
+
+    result
+}
@@ -0,0 +1,212 @@
+// Season rollover: start the next season when the last one ends
+//
+// With AUTO_SEASON_DAYS set, a background task checks periodically whether
+// the latest season has ended. If so it finalizes that season and creates
+// the next one, starting where it ended. Instances take a Redis lock around
+// the check, so only one of them creates the season; the rest then find it
+// running and do nothing.
+//
+// With AUTO_SEASON_CARRY_OVER set, players start the new season with that
+// share of the points they ended the last one on. The points are awarded in
+// the transaction that creates the season, so a season never exists without
+// its carry-over: if the award fails, neither is kept and the next check
+// tries again.
+
+use std::time::Duration;
+
+use chrono::{NaiveDateTime, Utc};
+use uuid::Uuid;
+
+use crate::{
+    db::{season::SeasonRepository, user_wars_points::UserWarsPointsRepository},
+    errors::AppError,
+    models::{RedisKey, Season},
+    redis_lock::with_lock,
+    state::{AppState, SeasonRolloverConfig},
+};
+
+/// How often the rollover task looks for an ended season
+const ROLLOVER_INTERVAL_SECS: u64 = 60;
+
+/// Longest a rollover may hold the lock
+const ROLLOVER_LOCK_TTL: Duration = Duration::from_secs(30);
+
+/// Format `Season::parse_date_range` accepts
+const DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Spawn the background task that rolls seasons over, if enabled.
+pub fn spawn_season_rollover(state: AppState) {
+    let config = state.config.season_rollover.clone();
+    if !config.is_enabled() {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(ROLLOVER_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            match roll_over_season(&state, &config).await {
+                Ok(Some(season)) => {
+                    tracing::info!("Started season {} (ID: {})", season.name, season.id());
+                }
+                Ok(None) => {}
+                Err(e) => tracing::error!("Season rollover failed: {}", e),
+            }
+        }
+    });
+}
+
+/// Finalize the latest season and create the next one, if it has ended.
+///
+/// Returns the new season, or None when the latest season is still running
+/// (or there are no seasons to follow on from).
+pub async fn roll_over_season(
+    state: &AppState,
+    config: &SeasonRolloverConfig,
+) -> Result<Option<Season>, AppError> {
+    let repo = SeasonRepository::new(state.postgres.clone()).with_cache(state.redis.clone());
+
+    with_lock(
+        &state.redis,
+        &RedisKey::lock("season_rollover"),
+        ROLLOVER_LOCK_TTL,
+        || async {
+            let now = Utc::now().naive_utc();
+            let Some(latest) = repo.find_latest().await? else {
+                return Ok(None);
+            };
+            if latest.end_date > now {
+                return Ok(None);
+            }
+
+            if repo.finalize(latest.id()).await? {
+                tracing::info!("Finalized season {} (ID: {})", latest.name, latest.id());
+            }
+
+            let (start, end) = next_season_dates(latest.end_date, now, config.duration_days);
+            let number = repo.count_seasons().await? + 1;
+            let name = season_name(&config.name_template, number, start);
+
+            // The ended season no longer changes, so it's read up front
+            let carried = if config.carry_over > 0.0 {
+                carried_points(state, latest.id(), config.carry_over).await?
+            } else {
+                Vec::new()
+            };
+
+            let mut transaction = state.postgres.begin().await.map_err(|e| {
+                AppError::DatabaseError(format!("Failed to begin transaction: {}", e))
+            })?;
+            let season = SeasonRepository::insert_season(
+                &mut transaction,
+                &name,
+                None,
+                &start.format(DATE_FORMAT).to_string(),
+                &end.format(DATE_FORMAT).to_string(),
+                false,
+            )
+            .await?;
+            let awarded = if carried.is_empty() {
+                0
+            } else {
+                UserWarsPointsRepository::award_many_in(&mut transaction, season.id(), &carried)
+                    .await?
+            };
+            transaction
+                .commit()
+                .await
+                .map_err(|e| AppError::DatabaseError(format!("Failed to commit season: {}", e)))?;
+            repo.invalidate_current().await?;
+
+            if config.carry_over > 0.0 {
+                tracing::info!(
+                    "Carried {}% of season {} points over to {} players",
+                    config.carry_over * 100.0,
+                    latest.id(),
+                    awarded
+                );
+            }
+            Ok(Some(season))
+        },
+    )
+    .await
+}
+
+/// What everyone who scored in season `from` starts the next season on:
+/// `share` of their points.
+async fn carried_points(
+    state: &AppState,
+    from: i32,
+    share: f64,
+) -> Result<Vec<(Uuid, f64)>, AppError> {
+    let awards = UserWarsPointsRepository::new(state.postgres.clone())
+        .get_season_wars_points(from)
+        .await?
+        .into_iter()
+        .filter(|entry| entry.points > 0.0)
+        .map(|entry| (entry.user_id, entry.points * share))
+        .collect();
+    Ok(awards)
+}
+
+/// Start and end of the season after one ending at `previous_end`.
+///
+/// It starts where the previous one ended, unless it would already be over
+/// (the rollover didn't run for a whole season), in which case it starts now.
+pub fn next_season_dates(
+    previous_end: NaiveDateTime,
+    now: NaiveDateTime,
+    duration_days: i64,
+) -> (NaiveDateTime, NaiveDateTime) {
+    let duration = chrono::Duration::days(duration_days);
+    let start = if previous_end + duration > now {
+        previous_end
+    } else {
+        now
+    };
+    (start, start + duration)
+}
+
+/// `template` with `{n}` and `{start}` filled in
+pub fn season_name(template: &str, number: i64, start: NaiveDateTime) -> String {
+    template
+        .replace("{n}", &number.to_string())
+        .replace("{start}", &start.format("%Y-%m-%d").to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, DATE_FORMAT).unwrap()
+    }
+
+    #[test]
+    fn test_next_season_follows_on_from_the_last() {
+        let (start, end) =
+            next_season_dates(at("2026-03-01 00:00:00"), at("2026-03-01 00:01:00"), 30);
+        assert_eq!(start, at("2026-03-01 00:00:00"));
+        assert_eq!(end, at("2026-03-31 00:00:00"));
+    }
+
+    #[test]
+    fn test_next_season_starts_now_after_a_long_gap() {
+        let (start, end) =
+            next_season_dates(at("2026-03-01 00:00:00"), at("2026-05-01 12:00:00"), 30);
+        assert_eq!(start, at("2026-05-01 12:00:00"));
+        assert_eq!(end, at("2026-05-31 12:00:00"));
+    }
+
+    #[test]
+    fn test_season_name_template() {
+        assert_eq!(
+            season_name("Season {n} ({start})", 4, at("2026-03-01 00:00:00")),
+            "Season 4 (2026-03-01)"
+        );
+        assert_eq!(
+            season_name("Season {n}", 2, at("2026-03-01 00:00:00")),
+            "Season 2"
+        );
+    }
+}
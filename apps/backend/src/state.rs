@@ -1,13 +1,32 @@
-use crate::games::{GameEngine, GameFactory, create_game_registry};
-use crate::models::WalletAddress;
-use axum::extract::ws::{Message, WebSocket};
+use crate::auth::jwt::JwtKeys;
+use crate::db::join_request::JoinRequestChanges;
+use crate::errors::AppError;
+use crate::feature_flags::FeatureDefaults;
+use crate::games::lexi_wars::{LexiWarsConfig, WordValidatorConfig};
+use crate::games::signing::ResultSigningKey;
+use crate::games::{
+    GameDurationLimits, GameEngine, GameFactory, create_game_registry, game_duration_limits,
+};
+use crate::models::{LobbyFilter, WalletAddress};
+use crate::wallet_access::WalletAccessConfig;
+use crate::ws::core::spectator_delay::SystemClock;
+use crate::ws::core::{
+    Compression, ConnectionSink, ProtocolVersion, RoomPseudonyms, RoomSequencer, SpectatorDelay,
+};
+use crate::ws::leaderboard::LeaderboardDeltas;
+use crate::ws::lobby::LobbyListDeltas;
+use crate::ws::room::RoomContext;
+use crate::ws::room::RoomStateBroadcasts;
+use crate::ws::room::typing::TypingTracker;
+use axum::http::{HeaderName, HeaderValue, Method, header};
 use bb8::Pool;
 use bb8_redis::RedisConnectionManager;
-use futures::stream::SplitSink;
+use rust_decimal::Decimal;
 use sqlx::PgPool;
 use sqlx::postgres::PgPoolOptions;
 use std::{
     collections::{HashMap, HashSet},
+    net::IpAddr,
     sync::Arc,
     time::Duration,
 };
@@ -59,10 +78,1026 @@ impl Network {
     }
 }
 
+/// Invalid CORS configuration; reported at startup.
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum CorsConfigError {
+    #[error("Invalid CORS origin '{0}': expected scheme://host[:port] with no path")]
+    InvalidOrigin(String),
+
+    #[error("Invalid CORS method '{0}'")]
+    InvalidMethod(String),
+
+    #[error("Invalid CORS header '{0}'")]
+    InvalidHeader(String),
+
+    #[error("CORS wildcard origin is only allowed outside production")]
+    WildcardInProduction,
+}
+
+/// Cross-origin policy for the HTTP API.
+#[derive(Clone, Debug)]
+pub struct CorsConfig {
+    pub allowed_origins: Vec<HeaderValue>,
+    pub allowed_methods: Vec<Method>,
+    pub allowed_headers: Vec<HeaderName>,
+    /// Reflect any origin (development only; see `CORS_ALLOW_ANY_ORIGIN`)
+    pub allow_any_origin: bool,
+}
+
+impl CorsConfig {
+    pub const DEFAULT_ORIGINS: &'static str = "http://localhost:3000";
+    pub const DEFAULT_METHODS: &'static str = "GET,POST,PUT,PATCH,DELETE,OPTIONS";
+
+    /// Read from `ALLOWED_ORIGINS`, `CORS_ALLOWED_METHODS`, `CORS_ALLOWED_HEADERS`
+    /// and `CORS_ALLOW_ANY_ORIGIN`, all comma-separated.
+    pub fn from_env(environment: &Environment) -> Result<Self, CorsConfigError> {
+        let allow_any_origin = std::env::var("CORS_ALLOW_ANY_ORIGIN")
+            .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(false);
+
+        Self::parse(
+            &std::env::var("ALLOWED_ORIGINS").unwrap_or_else(|_| Self::DEFAULT_ORIGINS.into()),
+            &std::env::var("CORS_ALLOWED_METHODS").unwrap_or_else(|_| Self::DEFAULT_METHODS.into()),
+            std::env::var("CORS_ALLOWED_HEADERS").ok().as_deref(),
+            allow_any_origin,
+            environment,
+        )
+    }
+
+    /// Validate a CORS policy from its comma-separated parts.
+    ///
+    /// `headers` of `None` keeps the defaults (authorization, content-type,
+    /// accept, cookie).
+    pub fn parse(
+        origins: &str,
+        methods: &str,
+        headers: Option<&str>,
+        allow_any_origin: bool,
+        environment: &Environment,
+    ) -> Result<Self, CorsConfigError> {
+        if allow_any_origin && environment.is_production() {
+            return Err(CorsConfigError::WildcardInProduction);
+        }
+
+        let allowed_origins = split_list(origins)
+            .map(|origin| {
+                if !is_valid_origin(origin) {
+                    return Err(CorsConfigError::InvalidOrigin(origin.to_string()));
+                }
+                HeaderValue::from_str(origin)
+                    .map_err(|_| CorsConfigError::InvalidOrigin(origin.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let allowed_methods = split_list(methods)
+            .map(|m| {
+                Method::from_bytes(m.to_uppercase().as_bytes())
+                    .map_err(|_| CorsConfigError::InvalidMethod(m.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let allowed_headers = match headers {
+            Some(headers) => split_list(headers)
+                .map(|h| {
+                    HeaderName::from_bytes(h.as_bytes())
+                        .map_err(|_| CorsConfigError::InvalidHeader(h.to_string()))
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            None => vec![
+                header::AUTHORIZATION,
+                header::CONTENT_TYPE,
+                header::ACCEPT,
+                header::COOKIE,
+            ],
+        };
+
+        Ok(Self {
+            allowed_origins,
+            allowed_methods,
+            allowed_headers,
+            allow_any_origin,
+        })
+    }
+}
+
+fn split_list(list: &str) -> impl Iterator<Item = &str> {
+    list.split(',').map(str::trim).filter(|s| !s.is_empty())
+}
+
+/// An origin is `scheme://host[:port]`: no path, query or trailing slash.
+fn is_valid_origin(origin: &str) -> bool {
+    let Some((scheme, rest)) = origin.split_once("://") else {
+        return false;
+    };
+    matches!(scheme, "http" | "https")
+        && !rest.is_empty()
+        && !rest.contains(['/', '?', '#', '*', ' '])
+}
+
+/// Quota for one rate-limit tier: `requests` per fixed `window_secs` window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RateLimitTier {
+    pub requests: u32,
+    pub window_secs: u64,
+}
+
+impl RateLimitTier {
+    pub const fn new(requests: u32, window_secs: u64) -> Self {
+        Self {
+            requests,
+            window_secs,
+        }
+    }
+
+    /// Parse `<requests>/<window_secs>`, e.g. `60/60`.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let (requests, window) = s
+            .trim()
+            .split_once('/')
+            .ok_or_else(|| format!("expected <requests>/<seconds>, got '{}'", s))?;
+        let requests: u32 = requests
+            .trim()
+            .parse()
+            .map_err(|_| format!("invalid request count in '{}'", s))?;
+        let window_secs: u64 = window
+            .trim()
+            .parse()
+            .map_err(|_| format!("invalid window in '{}'", s))?;
+
+        if requests == 0 || window_secs == 0 {
+            return Err(format!("requests and window must be positive in '{}'", s));
+        }
+
+        Ok(Self::new(requests, window_secs))
+    }
+}
+
+/// Rate-limit quotas per route tier.
+///
+/// Each tier counts separately, keyed by user id when the request carries a
+/// valid token and by IP otherwise.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RateLimits {
+    /// Public reads, anonymous callers
+    pub api: RateLimitTier,
+    /// Public reads, signed-in callers
+    pub api_authenticated: RateLimitTier,
+    /// Authenticated writes
+    pub auth: RateLimitTier,
+    /// Sensitive writes (registration, claims, contract calls)
+    pub strict: RateLimitTier,
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        Self {
+            api: RateLimitTier::new(60, 60),
+            api_authenticated: RateLimitTier::new(300, 60),
+            auth: RateLimitTier::new(30, 60),
+            strict: RateLimitTier::new(10, 60),
+        }
+    }
+}
+
+impl RateLimits {
+    /// Defaults overridden by `RATE_LIMIT_API`, `RATE_LIMIT_API_AUTHENTICATED`,
+    /// `RATE_LIMIT_AUTH` and `RATE_LIMIT_STRICT` (each `<requests>/<seconds>`).
+    pub fn from_env() -> Result<Self, String> {
+        let tier = |var: &str, default: RateLimitTier| match std::env::var(var) {
+            Ok(value) => RateLimitTier::parse(&value).map_err(|e| format!("{}: {}", var, e)),
+            Err(_) => Ok(default),
+        };
+        let defaults = Self::default();
+
+        Ok(Self {
+            api: tier("RATE_LIMIT_API", defaults.api)?,
+            api_authenticated: tier("RATE_LIMIT_API_AUTHENTICATED", defaults.api_authenticated)?,
+            auth: tier("RATE_LIMIT_AUTH", defaults.auth)?,
+            strict: tier("RATE_LIMIT_STRICT", defaults.strict)?,
+        })
+    }
+}
+
+/// Temporary IP bans for repeated failed auth and rate-limit violations.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AbuseConfig {
+    /// Violations within `window_secs` that earn a ban
+    pub threshold: u32,
+    /// Sliding window violations are counted over
+    pub window_secs: u64,
+    /// Length of a first ban; doubles with each repeat offense
+    pub ban_secs: u64,
+    /// Upper bound on a ban's length
+    pub max_ban_secs: u64,
+    /// IPs that are never tracked or banned (e.g. internal services)
+    pub allowlist: Vec<IpAddr>,
+}
+
+impl Default for AbuseConfig {
+    fn default() -> Self {
+        Self {
+            threshold: 20,
+            window_secs: 300,
+            ban_secs: 60,
+            max_ban_secs: 24 * 60 * 60,
+            allowlist: Vec::new(),
+        }
+    }
+}
+
+impl AbuseConfig {
+    /// Defaults overridden by `ABUSE_THRESHOLD`, `ABUSE_WINDOW_SECS`,
+    /// `ABUSE_BAN_SECS`, `ABUSE_MAX_BAN_SECS` and `ABUSE_ALLOWLIST`
+    /// (comma-separated IPs).
+    pub fn from_env() -> Result<Self, String> {
+        fn number<T: std::str::FromStr>(var: &str, default: T) -> Result<T, String> {
+            match std::env::var(var) {
+                Ok(value) => value
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("{}: invalid number '{}'", var, value)),
+                Err(_) => Ok(default),
+            }
+        }
+        let defaults = Self::default();
+
+        let allowlist = std::env::var("ABUSE_ALLOWLIST")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|ip| !ip.is_empty())
+            .map(|ip| {
+                ip.parse::<IpAddr>()
+                    .map_err(|_| format!("ABUSE_ALLOWLIST: invalid IP '{}'", ip))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let config = Self {
+            threshold: number("ABUSE_THRESHOLD", defaults.threshold)?,
+            window_secs: number("ABUSE_WINDOW_SECS", defaults.window_secs)?,
+            ban_secs: number("ABUSE_BAN_SECS", defaults.ban_secs)?,
+            max_ban_secs: number("ABUSE_MAX_BAN_SECS", defaults.max_ban_secs)?,
+            allowlist,
+        };
+
+        if config.threshold == 0 || config.window_secs == 0 || config.ban_secs == 0 {
+            return Err(
+                "ABUSE_THRESHOLD, ABUSE_WINDOW_SECS and ABUSE_BAN_SECS must be positive".into(),
+            );
+        }
+        if config.max_ban_secs < config.ban_secs {
+            return Err("ABUSE_MAX_BAN_SECS must be at least ABUSE_BAN_SECS".into());
+        }
+
+        Ok(config)
+    }
+
+    pub fn is_allowlisted(&self, ip: IpAddr) -> bool {
+        self.allowlist.contains(&ip)
+    }
+
+    /// Ban length for the `offense`-th ban (1-based): doubles each time, capped.
+    pub fn ban_duration(&self, offense: u32) -> u64 {
+        let doublings = offense.saturating_sub(1).min(63);
+        self.ban_secs
+            .saturating_mul(1u64 << doublings)
+            .min(self.max_ban_secs)
+    }
+}
+
+/// A token lobbies may be staked in, and the smallest entry accepted in it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AllowedToken {
+    pub symbol: String,
+    /// `None` for the native token (STX)
+    pub contract_id: Option<WalletAddress>,
+    pub min_entry: Decimal,
+}
+
+/// Tokens accepted for new lobbies. An empty list accepts any token, with no minimum.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TokenPolicy {
+    pub tokens: Vec<AllowedToken>,
+}
+
+impl TokenPolicy {
+    /// Read `LOBBY_TOKENS`; unset or empty accepts any token.
+    pub fn from_env() -> Result<Self, String> {
+        Self::parse(&std::env::var("LOBBY_TOKENS").unwrap_or_default())
+    }
+
+    /// Parse comma-separated `SYMBOL=MIN` (native token) or
+    /// `SYMBOL@CONTRACT_ID=MIN` entries,
+    /// e.g. `STX=1,WELSH@SP3NE50GEXFG9SZGTT51P40X2CKYSZ5CC4ZTZ7A2G.welshcorgicoin-token=500`.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let tokens = spec
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (token, min) = entry
+                    .split_once('=')
+                    .ok_or_else(|| format!("LOBBY_TOKENS: '{}' is missing '=MIN'", entry))?;
+                let min_entry = min
+                    .trim()
+                    .parse::<Decimal>()
+                    .ok()
+                    .filter(|m| !m.is_sign_negative())
+                    .ok_or_else(|| format!("LOBBY_TOKENS: invalid minimum '{}'", min))?;
+                let (symbol, contract_id) = match token.split_once('@') {
+                    Some((symbol, contract)) => {
+                        let contract = WalletAddress::new(contract.trim()).map_err(|e| {
+                            format!("LOBBY_TOKENS: invalid contract '{}': {}", contract, e)
+                        })?;
+                        (symbol, Some(contract))
+                    }
+                    None => (token, None),
+                };
+                let symbol = symbol.trim();
+                if symbol.is_empty() {
+                    return Err(format!("LOBBY_TOKENS: '{}' has no symbol", entry));
+                }
+                Ok(AllowedToken {
+                    symbol: symbol.to_string(),
+                    contract_id,
+                    min_entry,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Ok(Self { tokens })
+    }
+
+    /// Check a new lobby's token and entry amount against the allowlist.
+    ///
+    /// A contract id picks the token by contract, and any symbol given with it
+    /// must match; without one, the symbol names a native token. Sponsored and
+    /// free lobbies are exempt from the minimum, but not from the allowlist.
+    pub fn check(
+        &self,
+        symbol: Option<&str>,
+        contract_id: Option<&WalletAddress>,
+        entry_amount: Option<Decimal>,
+        is_sponsored: bool,
+    ) -> Result<(), AppError> {
+        if self.tokens.is_empty() {
+            return Ok(());
+        }
+
+        let token = match (symbol, contract_id) {
+            (None, None) if entry_amount.is_none() && !is_sponsored => return Ok(()),
+            (None, None) => {
+                return Err(AppError::BadRequest(
+                    "A token is required for lobbies with a stake".into(),
+                ));
+            }
+            (symbol, Some(contract)) => {
+                let token = self
+                    .tokens
+                    .iter()
+                    .find(|t| t.contract_id.as_ref() == Some(contract))
+                    .ok_or_else(|| self.not_accepted(contract.as_str()))?;
+                if let Some(symbol) = symbol
+                    && !symbol.eq_ignore_ascii_case(&token.symbol)
+                {
+                    return Err(AppError::BadRequest(format!(
+                        "Token symbol '{}' does not match contract {} ({})",
+                        symbol, contract, token.symbol
+                    )));
+                }
+                token
+            }
+            (Some(symbol), None) => self
+                .tokens
+                .iter()
+                .find(|t| t.contract_id.is_none() && t.symbol.eq_ignore_ascii_case(symbol))
+                .ok_or_else(|| self.not_accepted(symbol))?,
+        };
+
+        let entry = entry_amount.unwrap_or_default();
+        if !is_sponsored && entry > Decimal::ZERO && entry < token.min_entry {
+            return Err(AppError::BadRequest(format!(
+                "Entry amount {} {} is below the minimum of {} {}",
+                entry, token.symbol, token.min_entry, token.symbol
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn not_accepted(&self, token: &str) -> AppError {
+        let accepted: Vec<&str> = self.tokens.iter().map(|t| t.symbol.as_str()).collect();
+        AppError::BadRequest(format!(
+            "Token {} is not accepted for lobbies (accepted: {})",
+            token,
+            accepted.join(", ")
+        ))
+    }
+}
+
+/// Words players may not use in chat messages or lobby names. Matched
+/// case-insensitively against whole words.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ChatModeration {
+    pub blocked_words: Vec<String>,
+}
+
+impl ChatModeration {
+    /// Read `CHAT_BLOCKED_WORDS`, a comma-separated list; unset blocks nothing.
+    pub fn from_env() -> Self {
+        Self::parse(&std::env::var("CHAT_BLOCKED_WORDS").unwrap_or_default())
+    }
+
+    pub fn parse(spec: &str) -> Self {
+        let blocked_words = spec
+            .split(',')
+            .map(|word| word.trim().to_lowercase())
+            .filter(|word| !word.is_empty())
+            .collect();
+        Self { blocked_words }
+    }
+
+    /// The first blocked word in `text`, if any
+    pub fn blocked_word(&self, text: &str) -> Option<&str> {
+        text.split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .find_map(|word| {
+                let word = word.to_lowercase();
+                self.blocked_words
+                    .iter()
+                    .find(|blocked| **blocked == word)
+                    .map(String::as_str)
+            })
+    }
+}
+
+/// Rules a new lobby's name must follow.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LobbyNamePolicy {
+    /// Longest name accepted, in characters
+    pub max_len: usize,
+    /// Reject a name the creator already uses for a lobby that hasn't finished
+    pub unique_per_creator: bool,
+}
+
+impl Default for LobbyNamePolicy {
+    fn default() -> Self {
+        Self {
+            max_len: Self::DEFAULT_MAX_LEN,
+            unique_per_creator: false,
+        }
+    }
+}
+
+impl LobbyNamePolicy {
+    pub const DEFAULT_MAX_LEN: usize = 50;
+
+    /// Read `LOBBY_NAME_MAX_LEN` and `LOBBY_NAME_UNIQUE_PER_CREATOR`.
+    pub fn from_env() -> Result<Self, String> {
+        let max_len = match std::env::var("LOBBY_NAME_MAX_LEN") {
+            Ok(value) => value
+                .trim()
+                .parse::<usize>()
+                .ok()
+                .filter(|len| *len > 0)
+                .ok_or_else(|| format!("LOBBY_NAME_MAX_LEN: invalid length '{}'", value))?,
+            Err(_) => Self::DEFAULT_MAX_LEN,
+        };
+        let unique_per_creator = std::env::var("LOBBY_NAME_UNIQUE_PER_CREATOR")
+            .map(|v| matches!(v.trim(), "true" | "1"))
+            .unwrap_or(false);
+        Ok(Self {
+            max_len,
+            unique_per_creator,
+        })
+    }
+
+    /// The name as stored: trimmed, with control characters removed and runs
+    /// of whitespace collapsed to one space.
+    ///
+    /// Fails if nothing is left, if it's longer than `max_len` characters, or
+    /// if it contains a word `moderation` blocks.
+    pub fn sanitize(&self, name: &str, moderation: &ChatModeration) -> Result<String, AppError> {
+        let name = name
+            .chars()
+            .filter(|c| c.is_whitespace() || !c.is_control())
+            .collect::<String>()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        if name.is_empty() {
+            return Err(AppError::BadRequest("Lobby name must not be empty".into()));
+        }
+        let len = name.chars().count();
+        if len > self.max_len {
+            return Err(AppError::BadRequest(format!(
+                "Lobby name is {} characters long; the limit is {}",
+                len, self.max_len
+            )));
+        }
+        if let Some(word) = moderation.blocked_word(&name) {
+            return Err(AppError::BadRequest(format!(
+                "Lobby name contains the blocked word '{}'",
+                word
+            )));
+        }
+
+        Ok(name)
+    }
+}
+
+/// Per-game duration limits: registry defaults plus `GAME_DURATION_LIMITS` overrides.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameDurations {
+    pub limits: HashMap<Uuid, GameDurationLimits>,
+}
+
+impl Default for GameDurations {
+    fn default() -> Self {
+        Self {
+            limits: game_duration_limits(),
+        }
+    }
+}
+
+impl GameDurations {
+    /// Read `GAME_DURATION_LIMITS`; unset keeps the registry defaults.
+    pub fn from_env() -> Result<Self, String> {
+        Self::parse(&std::env::var("GAME_DURATION_LIMITS").unwrap_or_default())
+    }
+
+    /// Parse comma-separated `GAME_ID=MIN_SECS:MAX_SECS` overrides,
+    /// e.g. `97f19daa-b6b4-455b-a21e-f225884767d5=15:1800`.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut durations = Self::default();
+
+        for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (game_id, range) = entry.split_once('=').ok_or_else(|| {
+                format!("GAME_DURATION_LIMITS: '{}' is missing '=MIN:MAX'", entry)
+            })?;
+            let game_id = Uuid::parse_str(game_id.trim())
+                .map_err(|_| format!("GAME_DURATION_LIMITS: invalid game id '{}'", game_id))?;
+            let (min, max) = range
+                .split_once(':')
+                .ok_or_else(|| format!("GAME_DURATION_LIMITS: '{}' is missing ':MAX'", entry))?;
+            let secs = |value: &str| {
+                value
+                    .trim()
+                    .parse::<u64>()
+                    .map_err(|_| format!("GAME_DURATION_LIMITS: invalid seconds '{}'", value))
+            };
+            let limits = GameDurationLimits {
+                min_secs: secs(min)?,
+                max_secs: secs(max)?,
+            };
+            if limits.max_secs == 0 || limits.min_secs > limits.max_secs {
+                return Err(format!(
+                    "GAME_DURATION_LIMITS: '{}' needs 0 <= MIN <= MAX and MAX > 0",
+                    entry
+                ));
+            }
+            durations.limits.insert(game_id, limits);
+        }
+
+        Ok(durations)
+    }
+
+    /// Limits for `game_id`, or `GameDurationLimits::DEFAULT` if it has none.
+    pub fn for_game(&self, game_id: Uuid) -> GameDurationLimits {
+        self.limits
+            .get(&game_id)
+            .copied()
+            .unwrap_or(GameDurationLimits::DEFAULT)
+    }
+}
+
+/// Spectators a room admits per active player, by game.
+///
+/// Checked only when a spectator connects: a room over its ratio after
+/// players leave keeps the spectators it has but admits no more.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpectatorRatios {
+    /// Ratio for games without their own; None for no limit
+    pub default: Option<usize>,
+    /// Per-game ratios; None lifts the default for that game
+    pub per_game: HashMap<Uuid, Option<usize>>,
+}
+
+impl Default for SpectatorRatios {
+    fn default() -> Self {
+        Self {
+            default: Some(10),
+            per_game: HashMap::new(),
+        }
+    }
+}
+
+impl SpectatorRatios {
+    /// Read `SPECTATOR_RATIOS`; unset keeps the default.
+    pub fn from_env() -> Result<Self, String> {
+        Self::parse(&std::env::var("SPECTATOR_RATIOS").unwrap_or_default())
+    }
+
+    /// Parse comma-separated ratios, a bare one replacing the default and
+    /// `GAME_ID=RATIO` setting a game's, e.g. `20,97f19daa-b6b4-455b-a21e-f225884767d5=5`.
+    /// A ratio of 0 means no limit.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut ratios = Self::default();
+        let ratio = |value: &str| {
+            value
+                .trim()
+                .parse::<usize>()
+                .map(|ratio| (ratio > 0).then_some(ratio))
+                .map_err(|_| format!("SPECTATOR_RATIOS: invalid ratio '{}'", value))
+        };
+
+        for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            match entry.split_once('=') {
+                Some((game_id, value)) => {
+                    let game_id = Uuid::parse_str(game_id.trim())
+                        .map_err(|_| format!("SPECTATOR_RATIOS: invalid game id '{}'", game_id))?;
+                    ratios.per_game.insert(game_id, ratio(value)?);
+                }
+                None => ratios.default = ratio(entry)?,
+            }
+        }
+
+        Ok(ratios)
+    }
+
+    /// Ratio for `game_id`, if it has one
+    pub fn for_game(&self, game_id: Uuid) -> Option<usize> {
+        self.per_game.get(&game_id).copied().unwrap_or(self.default)
+    }
+
+    /// Whether a room of `game_id` with `players` active players and
+    /// `spectators` watching can take one more spectator.
+    ///
+    /// A room counts at least one player, so a lobby waiting for its first
+    /// can still be watched.
+    pub fn admits(&self, game_id: Uuid, players: usize, spectators: usize) -> bool {
+        self.for_game(game_id)
+            .is_none_or(|ratio| spectators < ratio * players.max(1))
+    }
+}
+
+/// What happens to players still not ready when the ready check times out.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UnreadyPolicy {
+    /// Removed from the lobby before the countdown
+    #[default]
+    Drop,
+    /// Kept; the game starts with them anyway
+    Keep,
+}
+
+/// Ready-up phase between Starting and the start countdown.
+///
+/// Players have `timeout_secs` to send `ready`. The countdown starts as soon
+/// as everyone is ready; at the timeout it starts if at least
+/// `quorum_percent` of the players are, otherwise the start is called off.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReadyUpConfig {
+    /// Seconds players have to ready up; 0 skips the ready check
+    pub timeout_secs: u64,
+    pub quorum_percent: u8,
+    pub unready: UnreadyPolicy,
+}
+
+impl Default for ReadyUpConfig {
+    fn default() -> Self {
+        Self {
+            timeout_secs: 0,
+            quorum_percent: 50,
+            unready: UnreadyPolicy::Drop,
+        }
+    }
+}
+
+impl ReadyUpConfig {
+    /// Defaults overridden by `READY_UP_TIMEOUT_SECS`, `READY_UP_QUORUM_PERCENT`
+    /// (1-100) and `READY_UP_UNREADY` (`drop` or `keep`).
+    pub fn from_env() -> Result<Self, String> {
+        let defaults = Self::default();
+
+        let timeout_secs = match std::env::var("READY_UP_TIMEOUT_SECS") {
+            Ok(value) => value
+                .trim()
+                .parse()
+                .map_err(|_| format!("READY_UP_TIMEOUT_SECS: invalid number '{}'", value))?,
+            Err(_) => defaults.timeout_secs,
+        };
+        let quorum_percent = match std::env::var("READY_UP_QUORUM_PERCENT") {
+            Ok(value) => value
+                .trim()
+                .parse()
+                .ok()
+                .filter(|percent| (1..=100).contains(percent))
+                .ok_or_else(|| format!("READY_UP_QUORUM_PERCENT: '{}' is not 1-100", value))?,
+            Err(_) => defaults.quorum_percent,
+        };
+        let unready = match std::env::var("READY_UP_UNREADY").as_deref().map(str::trim) {
+            Ok("drop") => UnreadyPolicy::Drop,
+            Ok("keep") => UnreadyPolicy::Keep,
+            Ok(other) => return Err(format!("READY_UP_UNREADY: '{}' is not drop or keep", other)),
+            Err(_) => defaults.unready,
+        };
+
+        Ok(Self {
+            timeout_secs,
+            quorum_percent,
+            unready,
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.timeout_secs > 0
+    }
+
+    /// Ready players needed to start after the timeout, out of `players`
+    pub fn quorum(&self, players: usize) -> usize {
+        (players * self.quorum_percent as usize)
+            .div_ceil(100)
+            .max(1)
+    }
+}
+
+/// Automatic season rollover.
+///
+/// With `duration_days` set, once the latest season ends a new one of that
+/// length starts where it left off, named from `name_template`: `{n}` is the
+/// season's number and `{start}` its start date. Players start it with
+/// `carry_over` of the points they ended the last one on.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SeasonRolloverConfig {
+    /// Length of auto-created seasons; 0 leaves season creation to admins
+    pub duration_days: i64,
+    pub name_template: String,
+    /// Share of the ended season's points each player starts the next with
+    pub carry_over: f64,
+}
+
+impl Default for SeasonRolloverConfig {
+    fn default() -> Self {
+        Self {
+            duration_days: 0,
+            name_template: "Season {n}".to_string(),
+            carry_over: 0.0,
+        }
+    }
+}
+
+impl SeasonRolloverConfig {
+    /// Defaults overridden by `AUTO_SEASON_DAYS`, `AUTO_SEASON_NAME` and
+    /// `AUTO_SEASON_CARRY_OVER` (a fraction, 0 to 1).
+    pub fn from_env() -> Result<Self, String> {
+        let defaults = Self::default();
+
+        let duration_days = match std::env::var("AUTO_SEASON_DAYS") {
+            Ok(value) => value
+                .trim()
+                .parse()
+                .ok()
+                .filter(|days| *days >= 0)
+                .ok_or_else(|| format!("AUTO_SEASON_DAYS: invalid number of days '{}'", value))?,
+            Err(_) => defaults.duration_days,
+        };
+        let name_template = std::env::var("AUTO_SEASON_NAME")
+            .ok()
+            .filter(|name| !name.trim().is_empty())
+            .unwrap_or(defaults.name_template);
+        let carry_over = match std::env::var("AUTO_SEASON_CARRY_OVER") {
+            Ok(value) => value
+                .trim()
+                .parse()
+                .ok()
+                .filter(|share| (0.0..=1.0).contains(share))
+                .ok_or_else(|| {
+                    format!("AUTO_SEASON_CARRY_OVER: expected 0 to 1, got '{}'", value)
+                })?,
+            Err(_) => defaults.carry_over,
+        };
+
+        Ok(Self {
+            duration_days,
+            name_template,
+            carry_over,
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.duration_days > 0
+    }
+}
+
+/// Keeping players who keep being matched together apart in paid quick play.
+///
+/// A pair who shared `max_shared_matches` paid matches within `window_secs`
+/// isn't grouped again, unless one of them has been queued `relax_after_secs`
+/// or more, so a small queue still drains.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SeatSeparationConfig {
+    /// How far back shared matches count; 0 turns separation off
+    pub window_secs: i64,
+    pub max_shared_matches: usize,
+    pub relax_after_secs: i64,
+}
+
+impl Default for SeatSeparationConfig {
+    fn default() -> Self {
+        Self {
+            window_secs: 6 * 60 * 60,
+            max_shared_matches: 1,
+            relax_after_secs: 60,
+        }
+    }
+}
+
+impl SeatSeparationConfig {
+    /// Defaults overridden by `SEAT_SEPARATION_WINDOW_SECS`,
+    /// `SEAT_SEPARATION_MAX_SHARED` (at least 1) and `SEAT_SEPARATION_RELAX_SECS`.
+    pub fn from_env() -> Result<Self, String> {
+        let defaults = Self::default();
+
+        let window_secs = match std::env::var("SEAT_SEPARATION_WINDOW_SECS") {
+            Ok(value) => value
+                .trim()
+                .parse()
+                .ok()
+                .filter(|secs| *secs >= 0)
+                .ok_or_else(|| {
+                    format!("SEAT_SEPARATION_WINDOW_SECS: invalid number '{}'", value)
+                })?,
+            Err(_) => defaults.window_secs,
+        };
+        let max_shared_matches = match std::env::var("SEAT_SEPARATION_MAX_SHARED") {
+            Ok(value) => value
+                .trim()
+                .parse()
+                .ok()
+                .filter(|max| *max >= 1)
+                .ok_or_else(|| {
+                    format!("SEAT_SEPARATION_MAX_SHARED: '{}' is not 1 or more", value)
+                })?,
+            Err(_) => defaults.max_shared_matches,
+        };
+        let relax_after_secs = match std::env::var("SEAT_SEPARATION_RELAX_SECS") {
+            Ok(value) => value
+                .trim()
+                .parse()
+                .ok()
+                .filter(|secs| *secs >= 0)
+                .ok_or_else(|| format!("SEAT_SEPARATION_RELAX_SECS: invalid number '{}'", value))?,
+            Err(_) => defaults.relax_after_secs,
+        };
+
+        Ok(Self {
+            window_secs,
+            max_shared_matches,
+            relax_after_secs,
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.window_secs > 0
+    }
+}
+
+/// Caps on how much of a lobby's chat is kept. A message past either limit
+/// is deleted; 0 turns a limit off.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    pub max_age_secs: u64,
+    pub max_messages: usize,
+}
+
+/// Pruning lobby chat on top of its TTL.
+///
+/// Every `interval_secs` the oldest messages past the lobby's policy are
+/// deleted: `active` while the lobby is open or being played, `finished`
+/// once it is over, so a game's chat can be kept around afterwards.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChatRetentionConfig {
+    /// Seconds between cleanup passes; 0 turns the job off
+    pub interval_secs: u64,
+    pub active: RetentionPolicy,
+    pub finished: RetentionPolicy,
+}
+
+impl Default for ChatRetentionConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: 5 * 60,
+            active: RetentionPolicy {
+                max_age_secs: 6 * 60 * 60,
+                max_messages: 500,
+            },
+            finished: RetentionPolicy {
+                max_age_secs: 24 * 60 * 60,
+                max_messages: 0,
+            },
+        }
+    }
+}
+
+impl ChatRetentionConfig {
+    /// Defaults overridden by `CHAT_RETENTION_INTERVAL_SECS`,
+    /// `CHAT_RETENTION_ACTIVE_SECS`, `CHAT_RETENTION_ACTIVE_MAX`,
+    /// `CHAT_RETENTION_FINISHED_SECS` and `CHAT_RETENTION_FINISHED_MAX`.
+    pub fn from_env() -> Result<Self, String> {
+        fn number<T: std::str::FromStr>(var: &str, default: T) -> Result<T, String> {
+            match std::env::var(var) {
+                Ok(value) => value
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("{}: invalid number '{}'", var, value)),
+                Err(_) => Ok(default),
+            }
+        }
+        let defaults = Self::default();
+
+        Ok(Self {
+            interval_secs: number("CHAT_RETENTION_INTERVAL_SECS", defaults.interval_secs)?,
+            active: RetentionPolicy {
+                max_age_secs: number("CHAT_RETENTION_ACTIVE_SECS", defaults.active.max_age_secs)?,
+                max_messages: number("CHAT_RETENTION_ACTIVE_MAX", defaults.active.max_messages)?,
+            },
+            finished: RetentionPolicy {
+                max_age_secs: number(
+                    "CHAT_RETENTION_FINISHED_SECS",
+                    defaults.finished.max_age_secs,
+                )?,
+                max_messages: number(
+                    "CHAT_RETENTION_FINISHED_MAX",
+                    defaults.finished.max_messages,
+                )?,
+            },
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.interval_secs > 0
+    }
+}
+
+/// Lowest trust ratings allowed to create and to join paid lobbies.
+/// Sponsored and free lobbies have no minimum.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PaidLobbyTrust {
+    pub create: f64,
+    pub join: f64,
+}
+
+impl PaidLobbyTrust {
+    /// Read `PAID_LOBBY_MIN_TRUST_CREATE` and `PAID_LOBBY_MIN_TRUST_JOIN`; unset means 0.
+    pub fn from_env() -> Result<Self, String> {
+        let threshold = |var: &str| match std::env::var(var) {
+            Ok(value) => value
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|t| t.is_finite() && *t >= 0.0)
+                .ok_or_else(|| format!("{}: invalid trust rating '{}'", var, value)),
+            Err(_) => Ok(0.0),
+        };
+
+        Ok(Self {
+            create: threshold("PAID_LOBBY_MIN_TRUST_CREATE")?,
+            join: threshold("PAID_LOBBY_MIN_TRUST_JOIN")?,
+        })
+    }
+
+    /// Fail with `Forbidden` if `trust_rating` is below the minimum to create a paid lobby.
+    pub fn check_create(&self, trust_rating: f64) -> Result<(), AppError> {
+        Self::check(self.create, trust_rating, "create")
+    }
+
+    /// Fail with `Forbidden` if `trust_rating` is below the minimum to join a paid lobby.
+    pub fn check_join(&self, trust_rating: f64) -> Result<(), AppError> {
+        Self::check(self.join, trust_rating, "join")
+    }
+
+    fn check(threshold: f64, trust_rating: f64, action: &str) -> Result<(), AppError> {
+        if trust_rating < threshold {
+            return Err(AppError::Forbidden(format!(
+                "A trust rating of at least {} is needed to {} paid lobbies",
+                threshold, action
+            )));
+        }
+        Ok(())
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct AppConfig {
     pub environment: Environment,
+    /// Secret of the HS256 auth key
     pub jwt_secret: String,
+    /// Signs invite tokens
+    pub invite_secret: String,
+    /// Sign and verify auth tokens
+    pub jwt_keys: JwtKeys,
     pub redis_url: String,
     pub database_url: String,
     pub telegram_bot_token: String,
@@ -70,6 +1105,56 @@ pub struct AppConfig {
     pub admins: Vec<WalletAddress>,
     pub network: Network,
     pub hiro_api_key: String,
+    /// Seconds a waiting lobby may sit empty and inactive before it is reaped
+    pub lobby_idle_timeout_secs: i64,
+    /// Seconds an absent creator has to return before their lobby is handed off
+    pub creator_grace_secs: i64,
+    /// Offset of the streak day boundary from UTC midnight, in seconds
+    pub streak_day_offset_secs: i64,
+    pub cors: CorsConfig,
+    pub rate_limits: RateLimits,
+    pub abuse: AbuseConfig,
+    pub lobby_tokens: TokenPolicy,
+    pub lobby_names: LobbyNamePolicy,
+    /// Words blocked from what players write; lobby names are checked against it
+    pub chat_moderation: ChatModeration,
+    pub game_durations: GameDurations,
+    pub spectator_ratios: SpectatorRatios,
+    /// Lexi Wars stops raising the minimum word length here
+    pub lexi_wars_max_word_length: usize,
+    /// Where Lexi Wars checks that a word exists
+    pub lexi_wars_dictionary: WordValidatorConfig,
+    /// Whether Lexi Wars is played to the last player or for points
+    pub lexi_wars: LexiWarsConfig,
+    /// Seed every game with this instead of a fresh random one (never in production)
+    pub game_rng_seed: Option<u64>,
+    /// Deliver room events in order, stamped with a per-lobby `seq`
+    pub ordered_room_broadcasts: bool,
+    /// How far spectators' view of a room lags the players' (0 = live)
+    pub spectator_delay_secs: u64,
+    /// Lobby changes within this many ms reach its room as one state message
+    pub room_state_window_ms: u64,
+    /// Most a room state flush is put off, spreading rooms out (at most the window)
+    pub room_state_jitter_ms: u64,
+    /// Points for each correct winner prediction (0 turns predictions off)
+    pub prediction_points: u32,
+    /// Repository queries slower than this are logged and counted
+    pub slow_query_threshold_ms: u64,
+    /// Feature flag values used until an admin overrides them
+    pub feature_defaults: FeatureDefaults,
+    pub ready_up: ReadyUpConfig,
+    pub season_rollover: SeasonRolloverConfig,
+    /// Seconds between leaderboard rank snapshots (0 = no snapshots)
+    pub rank_snapshot_interval_secs: u64,
+    /// Seconds between vault contract event indexing passes (0 = no indexing)
+    pub contract_event_interval_secs: u64,
+    pub seat_separation: SeatSeparationConfig,
+    pub chat_retention: ChatRetentionConfig,
+    pub paid_lobby_trust: PaidLobbyTrust,
+    /// Wallet allow/deny lists used alongside the admin's runtime changes
+    pub wallet_access: WalletAccessConfig,
+    /// Signs ranked results for settlement; None leaves them unsigned
+    pub result_signing_key: Option<ResultSigningKey>,
 }
 
 impl AppConfig {
@@ -78,14 +1163,24 @@ impl AppConfig {
         self.environment.is_production()
     }
 
+    /// Seed for a new game: the configured one, else a fresh draw from the
+    /// thread's CSPRNG
+    pub fn game_seed(&self) -> u64 {
+        self.game_rng_seed.unwrap_or_else(rand::random)
+    }
+
     /// Check if a wallet address is an admin
     pub fn is_admin(&self, wallet: &str) -> bool {
         self.admins.iter().any(|admin| admin.as_str() == wallet)
     }
 }
 
+/// A running game engine, locked on its own so one slow game doesn't hold up
+/// the others
+pub type SharedGameEngine = Arc<Mutex<Box<dyn GameEngine>>>;
+
 /// Active game engines by lobby ID
-pub type ActiveGames = Arc<Mutex<HashMap<Uuid, Box<dyn GameEngine>>>>;
+pub type ActiveGames = Arc<Mutex<HashMap<Uuid, SharedGameEngine>>>;
 
 #[derive(Clone)]
 pub struct AppState {
@@ -94,12 +1189,28 @@ pub struct AppState {
     pub indices: Arc<Mutex<ConnectionIndices>>,
     pub game_registry: Arc<HashMap<Uuid, GameFactory>>,
     pub active_games: ActiveGames,
+    pub room_sequencer: RoomSequencer,
+    pub spectator_delay: SpectatorDelay,
+    pub room_pseudonyms: RoomPseudonyms,
+    pub lobby_deltas: LobbyListDeltas,
+    pub leaderboard_deltas: LeaderboardDeltas,
+    pub room_state: RoomStateBroadcasts,
+    pub typing: TypingTracker,
+    pub join_request_changes: JoinRequestChanges,
     pub redis: RedisClient,
     pub postgres: PgPool,
     pub bot: Bot,
 }
 
 impl AppState {
+    /// The lobby's running game engine, if any
+    ///
+    /// The map is only locked long enough to clone the engine's handle; lock
+    /// the engine itself to use it.
+    pub async fn active_game(&self, lobby_id: Uuid) -> Option<SharedGameEngine> {
+        self.active_games.lock().await.get(&lobby_id).cloned()
+    }
+
     /// Create a new AppState by connecting to PostgreSQL and Redis
     pub async fn new() -> Result<Self, Box<dyn std::error::Error>> {
         // Read essential configuration from the environment and group it.
@@ -110,6 +1221,8 @@ impl AppState {
         let database_url = std::env::var("DATABASE_URL")?;
         let bot_token = std::env::var("TELEGRAM_BOT_TOKEN")?;
         let jwt_secret = std::env::var("JWT_SECRET")?;
+        let jwt_keys = JwtKeys::from_env(&jwt_secret)?;
+        let invite_secret = crate::auth::invite::invite_secret_from_env()?;
         let telegram_chat_id = std::env::var("TELEGRAM_CHAT_ID")?;
         let hiro_api_key = std::env::var("HIRO_API_KEY")?;
 
@@ -137,9 +1250,101 @@ impl AppState {
             })
             .collect();
 
+        let lobby_idle_timeout_secs = std::env::var("LOBBY_IDLE_TIMEOUT_SECS")
+            .ok()
+            .and_then(|s| s.parse::<i64>().ok())
+            .filter(|secs| *secs > 0)
+            .unwrap_or(crate::reaper::DEFAULT_LOBBY_IDLE_TIMEOUT_SECS);
+        let creator_grace_secs = std::env::var("CREATOR_GRACE_SECS")
+            .ok()
+            .and_then(|s| s.parse::<i64>().ok())
+            .filter(|secs| *secs > 0)
+            .unwrap_or(crate::reaper::DEFAULT_CREATOR_GRACE_SECS);
+
+        // e.g. STREAK_DAY_OFFSET_MINUTES=-300 rolls streak days over at midnight UTC-5
+        let streak_day_offset_secs = std::env::var("STREAK_DAY_OFFSET_MINUTES")
+            .ok()
+            .and_then(|s| s.parse::<i64>().ok())
+            .filter(|mins| mins.abs() < 24 * 60)
+            .map(|mins| mins * 60)
+            .unwrap_or(0);
+
+        // Bad origins should stop the server, not silently lock clients out
+        let cors = CorsConfig::from_env(&environment)?;
+        let rate_limits = RateLimits::from_env()?;
+        let abuse = AbuseConfig::from_env()?;
+        let lobby_tokens = TokenPolicy::from_env()?;
+        let lobby_names = LobbyNamePolicy::from_env()?;
+        let chat_moderation = ChatModeration::from_env();
+        let game_durations = GameDurations::from_env()?;
+        let spectator_ratios = SpectatorRatios::from_env()?;
+        let feature_defaults = FeatureDefaults::from_env()?;
+        let ready_up = ReadyUpConfig::from_env()?;
+        let season_rollover = SeasonRolloverConfig::from_env()?;
+        let seat_separation = SeatSeparationConfig::from_env()?;
+        let chat_retention = ChatRetentionConfig::from_env()?;
+        let paid_lobby_trust = PaidLobbyTrust::from_env()?;
+        let wallet_access = WalletAccessConfig::from_env()?;
+        let result_signing_key = ResultSigningKey::from_env()?;
+        let rank_snapshot_interval_secs = std::env::var("RANK_SNAPSHOT_INTERVAL_SECS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(crate::rank_snapshots::DEFAULT_RANK_SNAPSHOT_INTERVAL_SECS);
+        let contract_event_interval_secs = std::env::var("CONTRACT_EVENT_INTERVAL_SECS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(crate::contract_events::DEFAULT_CONTRACT_EVENT_INTERVAL_SECS);
+        let lexi_wars_max_word_length = std::env::var("LEXI_WARS_MAX_WORD_LENGTH")
+            .ok()
+            .and_then(|s| s.parse::<usize>().ok())
+            .filter(|len| *len >= crate::games::lexi_wars::INITIAL_MIN_WORD_LENGTH)
+            .unwrap_or(crate::games::lexi_wars::DEFAULT_MAX_WORD_LENGTH);
+        let lexi_wars_dictionary = WordValidatorConfig::from_env()?;
+        let lexi_wars = LexiWarsConfig::from_env()?;
+        // A fixed seed makes every game predictable; it's for tests and reproductions
+        let game_rng_seed = match std::env::var("GAME_RNG_SEED") {
+            Ok(value) => Some(
+                value
+                    .trim()
+                    .parse::<u64>()
+                    .map_err(|_| format!("GAME_RNG_SEED: invalid seed '{}'", value))?,
+            ),
+            Err(_) => None,
+        };
+        if game_rng_seed.is_some() && environment.is_production() {
+            return Err("GAME_RNG_SEED can't be set in production".into());
+        }
+        let ordered_room_broadcasts = std::env::var("ORDERED_ROOM_BROADCASTS")
+            .map(|v| !matches!(v.trim(), "false" | "0"))
+            .unwrap_or(true);
+        let spectator_delay_secs = std::env::var("SPECTATOR_DELAY_SECS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(0);
+        let room_state_window_ms = std::env::var("ROOM_STATE_WINDOW_MS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(100);
+        let room_state_jitter_ms = std::env::var("ROOM_STATE_JITTER_MS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(25)
+            .min(room_state_window_ms);
+        let prediction_points = std::env::var("PREDICTION_POINTS")
+            .ok()
+            .and_then(|s| s.parse::<u32>().ok())
+            .unwrap_or(crate::models::prediction::DEFAULT_PREDICTION_POINTS);
+        let slow_query_threshold_ms = std::env::var("SLOW_QUERY_THRESHOLD_MS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(crate::db::timing::DEFAULT_SLOW_QUERY_THRESHOLD_MS);
+        crate::db::timing::set_slow_query_threshold(Duration::from_millis(slow_query_threshold_ms));
+
         let config = AppConfig {
             environment,
             jwt_secret,
+            invite_secret,
+            jwt_keys,
             redis_url: redis_url.clone(),
             database_url: database_url.clone(),
             telegram_bot_token: bot_token.clone(),
@@ -147,6 +1352,37 @@ impl AppState {
             admins,
             network,
             hiro_api_key,
+            lobby_idle_timeout_secs,
+            creator_grace_secs,
+            streak_day_offset_secs,
+            cors,
+            rate_limits,
+            abuse,
+            lobby_tokens,
+            lobby_names,
+            chat_moderation,
+            game_durations,
+            spectator_ratios,
+            lexi_wars_max_word_length,
+            lexi_wars_dictionary,
+            lexi_wars,
+            game_rng_seed,
+            ordered_room_broadcasts,
+            spectator_delay_secs,
+            room_state_window_ms,
+            room_state_jitter_ms,
+            prediction_points,
+            slow_query_threshold_ms,
+            feature_defaults,
+            ready_up,
+            season_rollover,
+            rank_snapshot_interval_secs,
+            contract_event_interval_secs,
+            seat_separation,
+            chat_retention,
+            paid_lobby_trust,
+            wallet_access,
+            result_signing_key,
         };
 
         // Redis connection pool built from config.redis_url
@@ -180,13 +1416,26 @@ impl AppState {
         let game_registry: Arc<HashMap<Uuid, GameFactory>> = Arc::new(create_game_registry());
         let active_games: ActiveGames = Arc::new(Mutex::new(HashMap::new()));
 
+        let spectator_delay = SpectatorDelay::new(
+            Duration::from_secs(config.spectator_delay_secs),
+            Arc::new(SystemClock),
+        );
+
         Ok(Self {
             config,
             connections,
             indices,
             game_registry,
             active_games,
-            redis: redis_pool,
+            room_sequencer: RoomSequencer::default(),
+            spectator_delay,
+            room_pseudonyms: RoomPseudonyms::default(),
+            lobby_deltas: LobbyListDeltas::default(),
+            leaderboard_deltas: LeaderboardDeltas::default(),
+            room_state: RoomStateBroadcasts::default(),
+            typing: TypingTracker::default(),
+            join_request_changes: JoinRequestChanges::default(),
+            redis: RedisClient::new(redis_pool),
             postgres: postgres_pool,
             bot,
         })
@@ -198,8 +1447,10 @@ impl AppState {
 pub enum ConnectionContext {
     /// Room connection for a specific lobby (game + chat)
     Room(Uuid),
-    /// Lobby list connection with optional status filter
-    Lobby(Option<Vec<String>>), // e.g., Some(vec!["waiting", "starting"])
+    /// Lobby list connection with its subscription filter
+    Lobby(LobbyFilter),
+    /// Live leaderboard connection, for one game's board or the overall one
+    Leaderboard(Option<Uuid>),
 }
 
 impl ConnectionContext {
@@ -207,7 +1458,7 @@ impl ConnectionContext {
     pub fn lobby_id(&self) -> Option<Uuid> {
         match self {
             ConnectionContext::Room(id) => Some(*id),
-            ConnectionContext::Lobby(_) => None,
+            ConnectionContext::Lobby(_) | ConnectionContext::Leaderboard(_) => None,
         }
     }
 
@@ -215,14 +1466,21 @@ impl ConnectionContext {
     pub fn context_keys(&self) -> Vec<String> {
         match self {
             ConnectionContext::Room(_) => vec!["room".to_string()],
-            ConnectionContext::Lobby(Some(statuses)) => {
+            ConnectionContext::Lobby(filter) if filter.statuses.is_empty() => {
+                vec!["lobby".to_string()]
+            }
+            ConnectionContext::Lobby(filter) => {
                 // Create a key for each status filter
-                statuses
+                filter
+                    .statuses
                     .iter()
-                    .map(|status| format!("lobby:{}", status))
+                    .map(|status| format!("lobby:{}", status.as_key()))
                     .collect()
             }
-            ConnectionContext::Lobby(None) => vec!["lobby".to_string()],
+            ConnectionContext::Leaderboard(None) => vec!["leaderboard".to_string()],
+            ConnectionContext::Leaderboard(Some(game_id)) => {
+                vec![format!("leaderboard:{}", game_id)]
+            }
         }
     }
 }
@@ -232,7 +1490,14 @@ pub struct ConnectionInfo {
     pub connection_id: Uuid,
     pub user_id: Option<Uuid>,
     pub context: ConnectionContext,
-    pub sender: Arc<Mutex<SplitSink<WebSocket, Message>>>,
+    /// Message schema version negotiated at upgrade
+    pub protocol: ProtocolVersion,
+    /// Encoding accepted for large snapshot messages
+    pub compression: Compression,
+    /// WebSocket, or the channel behind an SSE stream
+    pub sender: Arc<Mutex<ConnectionSink>>,
+    /// Cached role of the user in the room; None on lobby list connections
+    pub room_context: std::sync::RwLock<Option<RoomContext>>,
 }
 
 impl ConnectionInfo {
@@ -240,6 +1505,20 @@ impl ConnectionInfo {
     pub fn lobby_id(&self) -> Option<Uuid> {
         self.context.lobby_id()
     }
+
+    pub fn room_context(&self) -> Option<RoomContext> {
+        *self
+            .room_context
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn set_room_context(&self, context: RoomContext) {
+        *self
+            .room_context
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(context);
+    }
 }
 
 /// Global map of all websocket connections keyed by `connection_id`.
@@ -343,4 +1622,290 @@ impl ConnectionIndices {
     }
 }
 
-pub type RedisClient = Pool<RedisConnectionManager>;
+pub use crate::redis_client::RedisClient;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(
+        origins: &str,
+        allow_any: bool,
+        env: Environment,
+    ) -> Result<CorsConfig, CorsConfigError> {
+        CorsConfig::parse(origins, CorsConfig::DEFAULT_METHODS, None, allow_any, &env)
+    }
+
+    #[test]
+    fn test_rate_limit_tier_parse() {
+        assert_eq!(
+            RateLimitTier::parse("10/60"),
+            Ok(RateLimitTier::new(10, 60))
+        );
+        assert_eq!(
+            RateLimitTier::parse(" 5 / 1 "),
+            Ok(RateLimitTier::new(5, 1))
+        );
+        assert!(RateLimitTier::parse("10").is_err());
+        assert!(RateLimitTier::parse("0/60").is_err());
+        assert!(RateLimitTier::parse("10/abc").is_err());
+    }
+
+    #[test]
+    fn test_default_strict_tier_is_tightest() {
+        let limits = RateLimits::default();
+        assert!(limits.strict.requests < limits.auth.requests);
+        assert!(limits.auth.requests < limits.api.requests);
+    }
+
+    #[test]
+    fn test_ban_duration_backs_off_exponentially() {
+        let abuse = AbuseConfig {
+            ban_secs: 60,
+            max_ban_secs: 300,
+            ..Default::default()
+        };
+
+        assert_eq!(abuse.ban_duration(1), 60);
+        assert_eq!(abuse.ban_duration(2), 120);
+        assert_eq!(abuse.ban_duration(3), 240);
+        assert_eq!(abuse.ban_duration(4), 300);
+        assert_eq!(abuse.ban_duration(u32::MAX), 300);
+    }
+
+    #[test]
+    fn test_cors_parses_multiple_origins() {
+        let cors = parse(
+            "https://stackswars.com, https://staging.stackswars.com,http://localhost:3000",
+            false,
+            Environment::Production,
+        )
+        .unwrap();
+
+        assert_eq!(cors.allowed_origins.len(), 3);
+        assert_eq!(cors.allowed_origins[1], "https://staging.stackswars.com");
+        assert_eq!(cors.allowed_methods.len(), 6);
+        assert!(cors.allowed_headers.contains(&header::COOKIE));
+    }
+
+    #[test]
+    fn test_cors_rejects_invalid_origins() {
+        for bad in [
+            "stackswars.com",
+            "https://stackswars.com/",
+            "ftp://x.io",
+            "*",
+        ] {
+            assert!(
+                matches!(
+                    parse(bad, false, Environment::Development),
+                    Err(CorsConfigError::InvalidOrigin(_))
+                ),
+                "{bad} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_cors_wildcard_only_outside_production() {
+        assert!(parse("", true, Environment::Development).is_ok());
+        assert!(matches!(
+            parse("", true, Environment::Production),
+            Err(CorsConfigError::WildcardInProduction)
+        ));
+    }
+
+    #[test]
+    fn test_cors_custom_methods_and_headers() {
+        let cors = CorsConfig::parse(
+            "http://localhost:3000",
+            "get,post",
+            Some("content-type,x-request-id"),
+            false,
+            &Environment::Development,
+        )
+        .unwrap();
+        assert_eq!(cors.allowed_methods, vec![Method::GET, Method::POST]);
+        assert_eq!(cors.allowed_headers[1], "x-request-id");
+
+        let bad = CorsConfig::parse(
+            "",
+            "GET",
+            Some("bad header"),
+            false,
+            &Environment::Development,
+        );
+        assert!(matches!(bad, Err(CorsConfigError::InvalidHeader(_))));
+    }
+
+    const WELSH: &str = "SP3NE50GEXFG9SZGTT51P40X2CKYSZ5CC4ZTZ7A2G.welshcorgicoin-token";
+
+    fn policy() -> TokenPolicy {
+        TokenPolicy::parse(&format!("STX=1, WELSH@{}=500", WELSH)).unwrap()
+    }
+
+    #[test]
+    fn test_token_policy_parse() {
+        let policy = policy();
+        assert_eq!(policy.tokens.len(), 2);
+        assert_eq!(policy.tokens[0].contract_id, None);
+        assert_eq!(policy.tokens[1].min_entry, Decimal::from(500));
+
+        assert!(TokenPolicy::parse("STX").is_err());
+        assert!(TokenPolicy::parse("STX=-1").is_err());
+        assert!(TokenPolicy::parse("WELSH@not-a-contract=1").is_err());
+        assert!(TokenPolicy::parse("").unwrap().tokens.is_empty());
+    }
+
+    #[test]
+    fn test_token_policy_check() {
+        let policy = policy();
+        let welsh = WalletAddress::new(WELSH).unwrap();
+
+        assert!(
+            policy
+                .check(Some("STX"), None, Some(Decimal::from(5)), false)
+                .is_ok()
+        );
+        assert!(
+            policy
+                .check(None, Some(&welsh), Some(Decimal::from(500)), false)
+                .is_ok()
+        );
+        // Sponsored and free lobbies skip the minimum
+        assert!(
+            policy
+                .check(Some("WELSH"), Some(&welsh), None, true)
+                .is_ok()
+        );
+        assert!(policy.check(Some("STX"), None, None, false).is_ok());
+
+        let err = policy
+            .check(Some("DOGE"), None, Some(Decimal::from(5)), false)
+            .unwrap_err();
+        assert!(err.to_string().contains("DOGE is not accepted"));
+        let err = policy
+            .check(Some("STX"), None, Some(Decimal::new(5, 1)), false)
+            .unwrap_err();
+        assert!(err.to_string().contains("below the minimum of 1 STX"));
+        assert!(
+            policy
+                .check(Some("STX"), Some(&welsh), Some(Decimal::from(500)), false)
+                .is_err()
+        );
+
+        // No allowlist, no restrictions
+        assert!(
+            TokenPolicy::default()
+                .check(Some("DOGE"), None, Some(Decimal::new(1, 1)), false)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_lobby_name_sanitize_normalizes_messy_names() {
+        let policy = LobbyNamePolicy::default();
+        let moderation = ChatModeration::default();
+
+        assert_eq!(
+            policy
+                .sanitize("  Friday\u{0007}  night\t\n wars ", &moderation)
+                .unwrap(),
+            "Friday night wars"
+        );
+        let err = policy.sanitize(" \u{0000}\t ", &moderation).unwrap_err();
+        assert!(err.to_string().contains("must not be empty"));
+    }
+
+    #[test]
+    fn test_lobby_name_sanitize_rejects_long_names() {
+        let policy = LobbyNamePolicy {
+            max_len: 10,
+            ..Default::default()
+        };
+        let moderation = ChatModeration::default();
+
+        // Counted in characters once whitespace is collapsed
+        assert!(policy.sanitize("  éééé     ééééé ", &moderation).is_ok());
+        let err = policy.sanitize("eleven char", &moderation).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("11 characters long; the limit is 10")
+        );
+    }
+
+    #[test]
+    fn test_lobby_name_sanitize_rejects_blocked_words() {
+        let policy = LobbyNamePolicy::default();
+        let moderation = ChatModeration::parse(" Darn, HECK ,");
+        assert_eq!(moderation.blocked_words, vec!["darn", "heck"]);
+
+        let err = policy.sanitize("What the heck!", &moderation).unwrap_err();
+        assert!(err.to_string().contains("blocked word 'heck'"));
+        assert!(policy.sanitize("DARN it", &moderation).is_err());
+        // Only whole words count
+        assert!(policy.sanitize("Checkmate", &moderation).is_ok());
+    }
+
+    #[test]
+    fn test_game_durations_parse() {
+        let lexi = crate::games::LEXI_WARS_GAME_ID;
+        let other = Uuid::new_v4();
+
+        let defaults = GameDurations::parse("").unwrap();
+        assert_eq!(defaults.for_game(lexi).min_secs, 15);
+        assert_eq!(defaults.for_game(other), GameDurationLimits::DEFAULT);
+
+        let durations = GameDurations::parse(&format!("{}=5:120, {}=0:30", lexi, other)).unwrap();
+        assert_eq!(
+            durations.for_game(lexi),
+            GameDurationLimits {
+                min_secs: 5,
+                max_secs: 120
+            }
+        );
+        assert_eq!(durations.for_game(other).max(), Duration::from_secs(30));
+
+        assert!(GameDurations::parse(&format!("{}=120", lexi)).is_err());
+        assert!(GameDurations::parse(&format!("{}=60:30", lexi)).is_err());
+        assert!(GameDurations::parse(&format!("{}=0:0", lexi)).is_err());
+        assert!(GameDurations::parse("lexi=1:2").is_err());
+    }
+
+    #[test]
+    fn test_spectator_ratios_parse() {
+        let lexi = crate::games::LEXI_WARS_GAME_ID;
+        let other = Uuid::new_v4();
+
+        assert_eq!(SpectatorRatios::parse("").unwrap().for_game(lexi), Some(10));
+
+        let ratios = SpectatorRatios::parse(&format!("20, {}=5, {}=0", lexi, other)).unwrap();
+        assert_eq!(ratios.for_game(lexi), Some(5));
+        assert_eq!(ratios.for_game(other), None);
+        assert_eq!(ratios.for_game(Uuid::new_v4()), Some(20));
+
+        assert!(SpectatorRatios::parse("lots").is_err());
+        assert!(SpectatorRatios::parse("lexi=5").is_err());
+    }
+
+    #[test]
+    fn test_spectator_ratio_blocks_and_reopens() {
+        let game = Uuid::new_v4();
+        let ratios = SpectatorRatios::parse("2").unwrap();
+
+        // Two players admit four spectators
+        assert!(ratios.admits(game, 2, 3));
+        assert!(!ratios.admits(game, 2, 4));
+
+        // A player leaving closes the room without dropping anyone...
+        assert!(!ratios.admits(game, 1, 3));
+        // ...and more players reopen it
+        assert!(ratios.admits(game, 3, 4));
+
+        // An empty room still takes spectators up to one player's worth
+        assert!(ratios.admits(game, 0, 1));
+        assert!(!ratios.admits(game, 0, 2));
+
+        assert!(SpectatorRatios::parse("0").unwrap().admits(game, 1, 1000));
+    }
+}
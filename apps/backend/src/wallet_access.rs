@@ -0,0 +1,263 @@
+// Wallet access lists: ban wallets, or let only listed ones in for a private beta
+//
+// A wallet on the deny list is refused wherever a signed-in wallet is seen:
+// sign-in, the `AuthClaims` extractor behind every authenticated route, and
+// `WsAuth` on WebSocket upgrades. In allowlist-only mode wallets missing from
+// the allow list are refused too. Admin wallets are never refused, so an
+// admin can't lock themselves out.
+//
+// Lists and mode start from config (`WALLET_DENYLIST`, `WALLET_ALLOWLIST`,
+// `WALLET_ALLOWLIST_ONLY`). Admins add and remove wallets and override the
+// mode at runtime; those changes live in Redis, so every instance sees them on
+// its next check. Configured wallets stay listed until the config changes.
+
+use std::{collections::BTreeSet, fmt};
+
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    errors::AppError,
+    models::{RedisKey, WalletAddress},
+    state::{AppState, RedisClient},
+};
+
+/// One of the two wallet lists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WalletList {
+    Allow,
+    Deny,
+}
+
+impl WalletList {
+    /// Name used in the admin API
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WalletList::Allow => "allow",
+            WalletList::Deny => "deny",
+        }
+    }
+
+    fn key(&self) -> String {
+        match self {
+            WalletList::Allow => RedisKey::wallet_allowlist(),
+            WalletList::Deny => RedisKey::wallet_denylist(),
+        }
+    }
+}
+
+impl fmt::Display for WalletList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Lists and mode from config, used before any admin change.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WalletAccessConfig {
+    pub allowlist_only: bool,
+    pub allow: BTreeSet<String>,
+    pub deny: BTreeSet<String>,
+}
+
+impl WalletAccessConfig {
+    /// Read `WALLET_ALLOWLIST_ONLY` and the comma-separated `WALLET_ALLOWLIST`
+    /// and `WALLET_DENYLIST`; unset means no lists and everyone allowed.
+    pub fn from_env() -> Result<Self, String> {
+        let wallets = |var: &str| {
+            parse_wallets(&std::env::var(var).unwrap_or_default())
+                .map_err(|e| format!("{}: {}", var, e))
+        };
+
+        Ok(Self {
+            allowlist_only: std::env::var("WALLET_ALLOWLIST_ONLY")
+                .map(|v| matches!(v.trim(), "true" | "1"))
+                .unwrap_or(false),
+            allow: wallets("WALLET_ALLOWLIST")?,
+            deny: wallets("WALLET_DENYLIST")?,
+        })
+    }
+}
+
+/// Parse a comma-separated wallet list, normalising each address.
+fn parse_wallets(spec: &str) -> Result<BTreeSet<String>, String> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|w| !w.is_empty())
+        .map(|w| {
+            WalletAddress::new(w)
+                .map(|w| w.as_str().to_string())
+                .map_err(|e| format!("'{}': {}", w, e))
+        })
+        .collect()
+}
+
+/// Both lists and the mode in effect, as returned to admins.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletAccessStatus {
+    pub allowlist_only: bool,
+    /// Whether an admin override of the configured mode is in effect
+    pub mode_overridden: bool,
+    pub allow: Vec<String>,
+    pub deny: Vec<String>,
+}
+
+/// Config lists plus the runtime changes in Redis.
+#[derive(Clone)]
+pub struct WalletAccess {
+    redis: RedisClient,
+    config: WalletAccessConfig,
+    admins: Vec<WalletAddress>,
+}
+
+impl WalletAccess {
+    /// Access lists for this instance's config and Redis
+    pub fn from_state(state: &AppState) -> Self {
+        Self {
+            redis: state.redis.clone(),
+            config: state.config.wallet_access.clone(),
+            admins: state.config.admins.clone(),
+        }
+    }
+
+    /// Fail with `Forbidden` if `wallet` may not connect.
+    pub async fn check(&self, wallet: &str) -> Result<(), AppError> {
+        if self.admins.iter().any(|admin| admin.as_str() == wallet) {
+            return Ok(());
+        }
+
+        let mut conn = self.redis.get().await?;
+        let (denied, allowed, mode): (bool, bool, Option<String>) = redis::pipe()
+            .sismember(RedisKey::wallet_denylist(), wallet)
+            .sismember(RedisKey::wallet_allowlist(), wallet)
+            .get(RedisKey::wallet_allowlist_only())
+            .query_async(&mut *conn)
+            .await
+            .map_err(AppError::RedisCommandError)?;
+
+        if denied || self.config.deny.contains(wallet) {
+            return Err(AppError::Forbidden(
+                "This wallet has been denied access".into(),
+            ));
+        }
+        let allowlist_only = mode
+            .map(|mode| mode == "1")
+            .unwrap_or(self.config.allowlist_only);
+        if allowlist_only && !allowed && !self.config.allow.contains(wallet) {
+            return Err(AppError::Forbidden(
+                "Access is limited to invited wallets right now".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub async fn status(&self) -> Result<WalletAccessStatus, AppError> {
+        let mut conn = self.redis.get().await?;
+        let (allow, deny, mode): (Vec<String>, Vec<String>, Option<String>) = redis::pipe()
+            .smembers(RedisKey::wallet_allowlist())
+            .smembers(RedisKey::wallet_denylist())
+            .get(RedisKey::wallet_allowlist_only())
+            .query_async(&mut *conn)
+            .await
+            .map_err(AppError::RedisCommandError)?;
+
+        let merge = |configured: &BTreeSet<String>, runtime: Vec<String>| -> Vec<String> {
+            configured
+                .iter()
+                .cloned()
+                .chain(runtime)
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect()
+        };
+
+        Ok(WalletAccessStatus {
+            allowlist_only: mode
+                .as_deref()
+                .map(|mode| mode == "1")
+                .unwrap_or(self.config.allowlist_only),
+            mode_overridden: mode.is_some(),
+            allow: merge(&self.config.allow, allow),
+            deny: merge(&self.config.deny, deny),
+        })
+    }
+
+    /// Put `wallet` on `list`.
+    pub async fn add(&self, list: WalletList, wallet: &str) -> Result<(), AppError> {
+        let wallet = WalletAddress::new(wallet)?;
+        let mut conn = self.redis.get().await?;
+        let _: () = conn
+            .sadd(list.key(), wallet.as_str())
+            .await
+            .map_err(AppError::RedisCommandError)?;
+        Ok(())
+    }
+
+    /// Take `wallet` off `list`; wallets listed in config can't be.
+    pub async fn remove(&self, list: WalletList, wallet: &str) -> Result<(), AppError> {
+        let wallet = WalletAddress::new(wallet)?;
+        let wallet = wallet.as_str();
+        let configured = match list {
+            WalletList::Allow => &self.config.allow,
+            WalletList::Deny => &self.config.deny,
+        };
+        if configured.contains(wallet) {
+            return Err(AppError::BadRequest(format!(
+                "{} is on the configured {} list",
+                wallet, list
+            )));
+        }
+
+        let mut conn = self.redis.get().await?;
+        let _: () = conn
+            .srem(list.key(), wallet)
+            .await
+            .map_err(AppError::RedisCommandError)?;
+        Ok(())
+    }
+
+    /// Override allowlist-only mode, or with `None` go back to the config.
+    pub async fn set_allowlist_only(&self, enabled: Option<bool>) -> Result<(), AppError> {
+        let mut conn = self.redis.get().await?;
+        let _: () = match enabled {
+            Some(enabled) => {
+                conn.set(
+                    RedisKey::wallet_allowlist_only(),
+                    if enabled { "1" } else { "0" },
+                )
+                .await
+            }
+            None => conn.del(RedisKey::wallet_allowlist_only()).await,
+        }
+        .map_err(AppError::RedisCommandError)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WALLET: &str = "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7";
+
+    #[test]
+    fn test_wallet_lists_parse() {
+        let wallets = parse_wallets(&format!(" {}, ,{} ", WALLET, WALLET)).unwrap();
+        assert_eq!(wallets.into_iter().collect::<Vec<_>>(), vec![WALLET]);
+
+        assert!(parse_wallets("").unwrap().is_empty());
+        assert!(parse_wallets(&format!("{},not-a-wallet", WALLET)).is_err());
+    }
+
+    #[test]
+    fn test_list_names_match_the_admin_api() {
+        for list in [WalletList::Allow, WalletList::Deny] {
+            assert_eq!(
+                serde_json::to_value(list).unwrap(),
+                serde_json::json!(list.as_str())
+            );
+        }
+    }
+}
@@ -1,93 +1,40 @@
 // Consolidated WebSocket broadcasting functions
-use crate::db::{
-    game::GameRepository, lobby::LobbyRepository, lobby_state::LobbyStateRepository,
-    user::UserRepository,
-};
-use crate::models::{LobbyExtended, LobbyInfo};
-use crate::state::AppState;
+use crate::games::Audience;
+use crate::state::{AppState, ConnectionContext, ConnectionInfo};
+use crate::ws::core::envelope::{Payload, encode_snapshot_for};
+use crate::ws::core::manager;
 use crate::ws::core::message::BroadcastMessage;
-use crate::ws::lobby::LobbyServerMessage;
 use crate::ws::room::messages::GameMessage;
 use axum::extract::ws::Message;
 use futures::SinkExt;
+use serde::Serialize;
+use std::sync::Arc;
 use uuid::Uuid;
 
-/// Broadcast lobby update to lobby list subscribers
+/// Report a lobby change to lobby list subscribers (coalesced into deltas)
+/// and to the lobby's room (coalesced into one state message)
 pub async fn broadcast_lobby_update(state: AppState, lobby_id: Uuid) {
-    tokio::spawn(async move {
-        let lobby_repo = LobbyRepository::new(state.postgres.clone());
-        let lobby_state_repo = LobbyStateRepository::new(state.redis.clone());
-
-        if let Ok(lobby) = lobby_repo.find_by_id(lobby_id).await {
-            let game_repo = GameRepository::new(state.postgres.clone());
-            let user_repo = UserRepository::new(state.postgres.clone());
-
-            let (game, creator, lobby_state) = tokio::join!(
-                game_repo.find_by_id(lobby.game_id),
-                user_repo.find_by_id(lobby.creator_id),
-                lobby_state_repo.get_state(lobby_id),
-            );
-
-            if let (Ok(game), Ok(creator), Ok(lobby_state)) = (game, creator, lobby_state) {
-                let lobby_extended = LobbyExtended::from_parts(lobby, lobby_state);
-                let lobby_info = LobbyInfo {
-                    lobby: lobby_extended,
-                    game,
-                    creator,
-                };
-
-                let _ = broadcast_lobby_list(
-                    &state,
-                    &LobbyServerMessage::LobbyUpdated { lobby: lobby_info },
-                )
-                .await;
-            }
-        }
-    });
+    state.lobby_deltas.changed(&state, lobby_id).await;
+    state.room_state.changed(&state, lobby_id).await;
 }
 
 /// Broadcast lobby creation to lobby list subscribers
-pub async fn broadcast_lobby_creation(
-    state: AppState,
-    lobby_id: Uuid,
-    game_id: Uuid,
-    creator_id: Uuid,
-) {
-    tokio::spawn(async move {
-        let lobby_repo = LobbyRepository::new(state.postgres.clone());
-        let game_repo = GameRepository::new(state.postgres.clone());
-        let user_repo = UserRepository::new(state.postgres.clone());
-        let lobby_state_repo = LobbyStateRepository::new(state.redis.clone());
-
-        let (lobby, game, creator, lobby_state) = tokio::join!(
-            lobby_repo.find_by_id(lobby_id),
-            game_repo.find_by_id(game_id),
-            user_repo.find_by_id(creator_id),
-            lobby_state_repo.get_state(lobby_id),
-        );
-
-        if let (Ok(lobby), Ok(lobby_state), Ok(game), Ok(creator)) =
-            (lobby, lobby_state, game, creator)
-        {
-            let lobby_extended = LobbyExtended::from_parts(lobby, lobby_state);
-            let lobby_info = LobbyInfo {
-                lobby: lobby_extended,
-                game,
-                creator,
-            };
-
-            let _ = broadcast_lobby_list(&state, &LobbyServerMessage::LobbyCreated { lobby_info })
-                .await;
-        }
-    });
+pub async fn broadcast_lobby_creation(state: AppState, lobby_id: Uuid) {
+    state.lobby_deltas.created(&state, lobby_id).await;
+}
+
+/// Broadcast lobby removal to lobby list subscribers
+pub async fn broadcast_lobby_removal(state: &AppState, lobby_id: Uuid) {
+    state.lobby_deltas.removed(state, lobby_id).await;
 }
 
 /// Send a message to a single connection
 pub async fn send<M: BroadcastMessage>(state: &AppState, connection_id: Uuid, msg: &M) {
-    if let Ok(json) = msg.to_json() {
+    if let Ok(json) = msg.to_json().map(Payload::new) {
         let conns = state.connections.lock().await;
         if let Some(conn) = conns.get(&connection_id) {
             let sender = conn.sender.clone();
+            let json = json.text_for(conn.protocol);
             tokio::spawn(async move {
                 let mut s = sender.lock().await;
                 let _ = s.send(Message::Text(json.into())).await;
@@ -98,12 +45,12 @@ pub async fn send<M: BroadcastMessage>(state: &AppState, connection_id: Uuid, ms
 
 /// Broadcast to all connections
 pub async fn broadcast_all<M: BroadcastMessage>(state: &AppState, msg: &M) {
-    if let Ok(json) = msg.to_json() {
+    if let Ok(json) = msg.to_json().map(Payload::new) {
         let conns = state.connections.lock().await;
 
         for conn in conns.values() {
             let sender = conn.sender.clone();
-            let json_clone = json.clone();
+            let json_clone = json.text_for(conn.protocol);
             tokio::spawn(async move {
                 let mut s = sender.lock().await;
                 let _ = s.send(Message::Text(json_clone.into())).await;
@@ -113,8 +60,31 @@ pub async fn broadcast_all<M: BroadcastMessage>(state: &AppState, msg: &M) {
 }
 
 /// Broadcast to all connections in a specific lobby room
+///
+/// Sequenced through the lobby's ordered queue unless that's disabled.
 pub async fn broadcast_room<M: BroadcastMessage>(state: &AppState, lobby_id: Uuid, msg: &M) {
-    if let Ok(json) = msg.to_json() {
+    broadcast_room_to(state, lobby_id, Audience::All, msg).await;
+}
+
+/// Broadcast to the connections in a lobby room that are in `audience`
+pub async fn broadcast_room_to<M: BroadcastMessage>(
+    state: &AppState,
+    lobby_id: Uuid,
+    audience: Audience,
+    msg: &M,
+) {
+    if state.config.ordered_room_broadcasts {
+        if let Ok(event) = msg.to_value() {
+            state
+                .room_sequencer
+                .publish(state, lobby_id, event, audience, None)
+                .await;
+        }
+        return;
+    }
+
+    if let Ok(json) = msg.to_json().map(Payload::new) {
+        let masked = masked_json(state, lobby_id, || msg.to_value().ok());
         let indices = state.indices.lock().await;
 
         if let Some(conn_ids) = indices.get_lobby_connections(&lobby_id) {
@@ -122,8 +92,39 @@ pub async fn broadcast_room<M: BroadcastMessage>(state: &AppState, lobby_id: Uui
 
             for conn_id in conn_ids.iter() {
                 if let Some(conn) = conns.get(conn_id) {
+                    if !audience.includes(conn) {
+                        continue;
+                    }
+                    deliver(state, conn, json_for(conn, &json, &masked));
+                }
+            }
+        }
+    }
+}
+
+/// Send a transient event (e.g. typing) to a lobby room, skipping `except_user`.
+///
+/// Never sequenced: a missed one is harmless, so it shouldn't open a `seq` gap.
+pub async fn broadcast_room_ephemeral<M: BroadcastMessage>(
+    state: &AppState,
+    lobby_id: Uuid,
+    except_user: Option<Uuid>,
+    msg: &M,
+) {
+    if let Ok(json) = msg.to_json().map(Payload::new) {
+        let masked = masked_json(state, lobby_id, || msg.to_value().ok());
+        let indices = state.indices.lock().await;
+
+        if let Some(conn_ids) = indices.get_lobby_connections(&lobby_id) {
+            let conns = state.connections.lock().await;
+
+            for conn_id in conn_ids.iter() {
+                if let Some(conn) = conns.get(conn_id) {
+                    if except_user.is_some() && conn.user_id == except_user {
+                        continue;
+                    }
                     let sender = conn.sender.clone();
-                    let json_clone = json.clone();
+                    let json_clone = json_for(conn, &json, &masked);
                     tokio::spawn(async move {
                         let mut s = sender.lock().await;
                         let _ = s.send(Message::Text(json_clone.into())).await;
@@ -134,9 +135,93 @@ pub async fn broadcast_room<M: BroadcastMessage>(state: &AppState, lobby_id: Uui
     }
 }
 
+/// Write a room message to `conn`, or hold it back while the spectator delay
+/// applies to it, as the room sequencer does
+fn deliver(state: &AppState, conn: &Arc<ConnectionInfo>, json: String) {
+    if state.spectator_delay.applies_to(conn) {
+        state.spectator_delay.hold(conn.clone(), json);
+        return;
+    }
+    let sender = conn.sender.clone();
+    tokio::spawn(async move {
+        let mut s = sender.lock().await;
+        let _ = s.send(Message::Text(json.into())).await;
+    });
+}
+
+/// `msg` masked for spectators, if the lobby hides its players from them
+fn masked_json(
+    state: &AppState,
+    lobby_id: Uuid,
+    to_value: impl FnOnce() -> Option<serde_json::Value>,
+) -> Option<Payload> {
+    let table = state.room_pseudonyms.get(lobby_id)?;
+    to_value().map(|mut value| {
+        table.mask(&mut value);
+        Payload::new(value.to_string())
+    })
+}
+
+/// The copy of a room message `conn` should get, in its protocol
+fn json_for(conn: &ConnectionInfo, json: &Payload, masked: &Option<Payload>) -> String {
+    match masked {
+        Some(masked) if Audience::Spectators.includes(conn) => masked.text_for(conn.protocol),
+        _ => json.text_for(conn.protocol),
+    }
+}
+
+/// Send a room message to one connection, masked if it's a spectator in a
+/// lobby that hides its players
+pub async fn send_room_message<M: Serialize>(
+    state: &AppState,
+    conn: &Arc<ConnectionInfo>,
+    msg: &M,
+) -> Result<(), serde_json::Error> {
+    if let Some(table) = conn
+        .lobby_id()
+        .and_then(|lobby_id| state.room_pseudonyms.get(lobby_id))
+        && Audience::Spectators.includes(conn)
+    {
+        let masked = table.masked(&serde_json::to_value(msg)?);
+        return manager::send_to_connection(conn, &masked).await;
+    }
+    manager::send_to_connection(conn, msg).await
+}
+
+/// Send a room snapshot (bootstrap, game state, standings) to one connection,
+/// stamped with the room's current `seq` when room events are ordered
+pub async fn send_room_snapshot<M: Serialize>(
+    state: &AppState,
+    lobby_id: Uuid,
+    conn: &Arc<ConnectionInfo>,
+    msg: &M,
+) -> Result<(), serde_json::Error> {
+    if !state.config.ordered_room_broadcasts {
+        let mut snapshot = serde_json::to_value(msg)?;
+        if let Some(table) = state.room_pseudonyms.get(lobby_id)
+            && Audience::Spectators.includes(conn)
+        {
+            table.mask(&mut snapshot);
+        }
+        if state.spectator_delay.applies_to(conn) {
+            let json = encode_snapshot_for(snapshot.to_string(), conn.protocol, conn.compression);
+            state.spectator_delay.hold(conn.clone(), json);
+            return Ok(());
+        }
+        return manager::send_snapshot_to_connection(conn, &snapshot).await;
+    }
+
+    let snapshot = serde_json::to_value(msg)?;
+    state
+        .room_sequencer
+        .publish_snapshot(state, lobby_id, conn.connection_id, snapshot)
+        .await;
+    Ok(())
+}
+
 /// Broadcast to all connections for a specific user (multi-tab support)
 pub async fn broadcast_user<M: BroadcastMessage>(state: &AppState, user_id: Uuid, msg: &M) {
-    if let Ok(json) = msg.to_json() {
+    if let Ok(json) = msg.to_json().map(Payload::new) {
         let indices = state.indices.lock().await;
         let conns = state.connections.lock().await;
 
@@ -144,7 +229,7 @@ pub async fn broadcast_user<M: BroadcastMessage>(state: &AppState, user_id: Uuid
             for conn_id in conn_ids.iter() {
                 if let Some(conn) = conns.get(conn_id) {
                     let sender = conn.sender.clone();
-                    let json_clone = json.clone();
+                    let json_clone = json.text_for(conn.protocol);
                     tokio::spawn(async move {
                         let mut s = sender.lock().await;
                         let _ = s.send(Message::Text(json_clone.into())).await;
@@ -157,7 +242,7 @@ pub async fn broadcast_user<M: BroadcastMessage>(state: &AppState, user_id: Uuid
 
 /// Broadcast to multiple users (batch operation)
 pub async fn broadcast_users<M: BroadcastMessage>(state: &AppState, user_ids: &[Uuid], msg: &M) {
-    if let Ok(json) = msg.to_json() {
+    if let Ok(json) = msg.to_json().map(Payload::new) {
         let indices = state.indices.lock().await;
         let conns = state.connections.lock().await;
 
@@ -166,7 +251,7 @@ pub async fn broadcast_users<M: BroadcastMessage>(state: &AppState, user_ids: &[
                 for conn_id in conn_ids.iter() {
                     if let Some(conn) = conns.get(conn_id) {
                         let sender = conn.sender.clone();
-                        let json_clone = json.clone();
+                        let json_clone = json.text_for(conn.protocol);
                         tokio::spawn(async move {
                             let mut s = sender.lock().await;
                             let _ = s.send(Message::Text(json_clone.into())).await;
@@ -188,7 +273,7 @@ pub async fn broadcast_room_participants<M: BroadcastMessage>(
 
     let player_repo = PlayerStateRepository::new(state.redis.clone());
 
-    if let Ok(players) = player_repo.get_all_in_lobby(lobby_id).await {
+    if let Ok(players) = player_repo.list_players(lobby_id).await {
         let user_ids: Vec<Uuid> = players.into_iter().map(|p| p.user_id).collect();
         if !user_ids.is_empty() {
             broadcast_users(state, &user_ids, msg).await;
@@ -196,41 +281,52 @@ pub async fn broadcast_room_participants<M: BroadcastMessage>(
     }
 }
 
-/// Broadcast to all lobby list connections (including those with status filters)
-pub async fn broadcast_lobby_list<M: BroadcastMessage>(state: &AppState, msg: &M) {
-    if let Ok(json) = msg.to_json() {
-        let indices = state.indices.lock().await;
-        let conns = state.connections.lock().await;
-
-        // Collect all unique connection IDs from lobby-related context keys
-        let mut sent_to: std::collections::HashSet<Uuid> = std::collections::HashSet::new();
-
-        // Include connections without status filter ("lobby" key)
-        if let Some(conn_ids) = indices.get_context_connections("lobby") {
-            for conn_id in conn_ids.iter() {
-                sent_to.insert(*conn_id);
-            }
-        }
+/// All lobby list connections, whatever their subscription filter
+pub(crate) async fn lobby_list_connections(state: &AppState) -> Vec<Arc<ConnectionInfo>> {
+    let indices = state.indices.lock().await;
+    let conns = state.connections.lock().await;
+
+    // Connections without a status filter are under "lobby", the rest under "lobby:*"
+    let conn_ids: std::collections::HashSet<Uuid> = indices
+        .by_context
+        .iter()
+        .filter(|(context_key, _)| *context_key == "lobby" || context_key.starts_with("lobby:"))
+        .flat_map(|(_, conn_ids)| conn_ids.iter().copied())
+        .collect();
+
+    conn_ids
+        .iter()
+        .filter_map(|conn_id| conns.get(conn_id).cloned())
+        .collect()
+}
 
-        // Include connections with status filters ("lobby:*" keys)
-        for (context_key, conn_ids) in indices.by_context.iter() {
-            if context_key.starts_with("lobby:") {
-                for conn_id in conn_ids.iter() {
-                    sent_to.insert(*conn_id);
-                }
-            }
-        }
+/// Live leaderboard connections following one game's board, or the overall one
+pub(crate) async fn leaderboard_connections(
+    state: &AppState,
+    game_id: Option<Uuid>,
+) -> Vec<Arc<ConnectionInfo>> {
+    let indices = state.indices.lock().await;
+    let conns = state.connections.lock().await;
+
+    ConnectionContext::Leaderboard(game_id)
+        .context_keys()
+        .iter()
+        .filter_map(|context_key| indices.get_context_connections(context_key))
+        .flatten()
+        .filter_map(|conn_id| conns.get(conn_id).cloned())
+        .collect()
+}
 
-        // Send to all unique connections
-        for conn_id in sent_to {
-            if let Some(conn) = conns.get(&conn_id) {
-                let sender = conn.sender.clone();
-                let json_clone = json.clone();
-                tokio::spawn(async move {
-                    let mut s = sender.lock().await;
-                    let _ = s.send(Message::Text(json_clone.into())).await;
-                });
-            }
+/// Broadcast to all lobby list connections (including those with status filters)
+pub async fn broadcast_lobby_list<M: BroadcastMessage>(state: &AppState, msg: &M) {
+    if let Ok(json) = msg.to_json().map(Payload::new) {
+        for conn in lobby_list_connections(state).await {
+            let sender = conn.sender.clone();
+            let json_clone = json.text_for(conn.protocol);
+            tokio::spawn(async move {
+                let mut s = sender.lock().await;
+                let _ = s.send(Message::Text(json_clone.into())).await;
+            });
         }
     }
 }
@@ -241,7 +337,7 @@ pub async fn broadcast_lobby_by_status<M: BroadcastMessage>(
     status: &str,
     msg: &M,
 ) {
-    if let Ok(json) = msg.to_json() {
+    if let Ok(json) = msg.to_json().map(Payload::new) {
         let indices = state.indices.lock().await;
 
         // Get connections for this specific status context
@@ -252,7 +348,7 @@ pub async fn broadcast_lobby_by_status<M: BroadcastMessage>(
             for conn_id in conn_ids.iter() {
                 if let Some(conn) = conns.get(conn_id) {
                     let sender = conn.sender.clone();
-                    let json_clone = json.clone();
+                    let json_clone = json.text_for(conn.protocol);
                     tokio::spawn(async move {
                         let mut s = sender.lock().await;
                         let _ = s.send(Message::Text(json_clone.into())).await;
@@ -278,7 +374,7 @@ pub async fn broadcast_game_message_to_user(
 ) {
     let game_msg = GameMessage::new(payload);
 
-    if let Ok(json) = serde_json::to_string(&game_msg) {
+    if let Ok(json) = serde_json::to_string(&game_msg).map(Payload::new) {
         let indices = state.indices.lock().await;
         let conns = state.connections.lock().await;
 
@@ -286,7 +382,7 @@ pub async fn broadcast_game_message_to_user(
             for conn_id in conn_ids.iter() {
                 if let Some(conn) = conns.get(conn_id) {
                     let sender = conn.sender.clone();
-                    let json_clone = json.clone();
+                    let json_clone = json.text_for(conn.protocol);
                     tokio::spawn(async move {
                         let mut s = sender.lock().await;
                         let _ = s.send(Message::Text(json_clone.into())).await;
@@ -305,74 +401,67 @@ pub async fn broadcast_game_message_to_user(
 /// ```
 ///
 /// The payload should be a serialized game event with a "type" field
-pub async fn broadcast_game_message(
+pub async fn broadcast_game_message(state: &AppState, lobby_id: Uuid, payload: serde_json::Value) {
+    broadcast_game_message_to(state, lobby_id, Audience::All, None, payload).await;
+}
+
+/// Broadcast a game-specific message to all connections in a lobby room except a specific user.
+///
+/// The payload is wrapped as in `broadcast_game_message`.
+pub async fn broadcast_game_message_to_room_except(
     state: &AppState,
     lobby_id: Uuid,
+    except_user_id: Uuid,
     payload: serde_json::Value,
 ) {
-    let game_msg = GameMessage::new(payload);
-
-    if let Ok(json) = serde_json::to_string(&game_msg) {
-        let indices = state.indices.lock().await;
-
-        if let Some(conn_ids) = indices.get_lobby_connections(&lobby_id) {
-            let conns = state.connections.lock().await;
-
-            for conn_id in conn_ids.iter() {
-                if let Some(conn) = conns.get(conn_id) {
-                    let sender = conn.sender.clone();
-                    let json_clone = json.clone();
-                    tokio::spawn(async move {
-                        let mut s = sender.lock().await;
-                        let _ = s.send(Message::Text(json_clone.into())).await;
-                    });
-                }
-            }
-        }
-    }
+    broadcast_game_message_to(
+        state,
+        lobby_id,
+        Audience::All,
+        Some(except_user_id),
+        payload,
+    )
+    .await;
 }
 
-/// Broadcast a game-specific message to all connections in a lobby room except a specific user.
+/// Broadcast a game-specific message to the connections in a lobby room that
+/// are in `audience`, skipping `except_user`'s connections if given.
 ///
-/// This wraps the message in the GameMessage wrapper format:
-/// ```json
-/// { "game": { "type": "...", ...fields } }
-/// ```
-///
-/// The payload should be a serialized game event with a "type" field
-pub async fn broadcast_game_message_to_room_except(
+/// The payload is wrapped as in `broadcast_game_message`.
+pub async fn broadcast_game_message_to(
     state: &AppState,
     lobby_id: Uuid,
-    except_user_id: Uuid,
+    audience: Audience,
+    except_user: Option<Uuid>,
     payload: serde_json::Value,
 ) {
     let game_msg = GameMessage::new(payload);
 
-    if let Ok(json) = serde_json::to_string(&game_msg) {
+    if state.config.ordered_room_broadcasts {
+        if let Ok(event) = serde_json::to_value(&game_msg) {
+            state
+                .room_sequencer
+                .publish(state, lobby_id, event, audience, except_user)
+                .await;
+        }
+        return;
+    }
+
+    if let Ok(json) = serde_json::to_string(&game_msg).map(Payload::new) {
+        let masked = masked_json(state, lobby_id, || serde_json::to_value(&game_msg).ok());
         let indices = state.indices.lock().await;
 
         if let Some(conn_ids) = indices.get_lobby_connections(&lobby_id) {
             let conns = state.connections.lock().await;
 
-            // Get connection IDs for the user to exclude
-            let excluded_conn_ids: std::collections::HashSet<Uuid> = indices
-                .get_user_connections(&except_user_id)
-                .map(|ids| ids.iter().copied().collect())
-                .unwrap_or_default();
-
             for conn_id in conn_ids.iter() {
-                // Skip connections belonging to the excluded user
-                if excluded_conn_ids.contains(conn_id) {
-                    continue;
-                }
-
                 if let Some(conn) = conns.get(conn_id) {
-                    let sender = conn.sender.clone();
-                    let json_clone = json.clone();
-                    tokio::spawn(async move {
-                        let mut s = sender.lock().await;
-                        let _ = s.send(Message::Text(json_clone.into())).await;
-                    });
+                    if (except_user.is_some() && conn.user_id == except_user)
+                        || !audience.includes(conn)
+                    {
+                        continue;
+                    }
+                    deliver(state, conn, json_for(conn, &json, &masked));
                 }
             }
         }
@@ -0,0 +1,122 @@
+// Per-key change coalescing
+//
+// `Coalescer::schedule` reports that something keyed by a lobby (or other id)
+// changed. The first report starts a flush after the window; reports arriving
+// before it runs are absorbed into it. Reports arriving while it runs mark the
+// key dirty, and the flush runs once more a window later. A key therefore has
+// at most one flush running, and its flushes never overlap.
+
+use std::{collections::HashMap, future::Future, sync::Arc, time::Duration};
+
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+#[derive(Default)]
+struct Pending {
+    /// A flush task is running for this key
+    flushing: bool,
+    /// Changed since the running flush started
+    dirty: bool,
+}
+
+/// Collapses bursts of changes into one flush per key per window.
+#[derive(Clone, Default)]
+pub struct Coalescer {
+    pending: Arc<Mutex<HashMap<Uuid, Pending>>>,
+}
+
+impl Coalescer {
+    /// `key` changed; `flush` runs once `window` has passed, along with any
+    /// other changes to `key` reported meanwhile.
+    pub async fn schedule<F, Fut>(&self, key: Uuid, window: Duration, flush: F)
+    where
+        F: Fn() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let mut pending = self.pending.lock().await;
+        let entry = pending.entry(key).or_default();
+        entry.dirty = true;
+        if !entry.flushing {
+            entry.flushing = true;
+            tokio::spawn(self.clone().run(key, window, flush));
+        }
+    }
+
+    async fn run<F, Fut>(self, key: Uuid, window: Duration, flush: F)
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = ()>,
+    {
+        loop {
+            tokio::time::sleep(window).await;
+
+            match self.pending.lock().await.get_mut(&key) {
+                Some(entry) => entry.dirty = false,
+                None => return,
+            }
+
+            flush().await;
+
+            let mut pending = self.pending.lock().await;
+            if !pending.get(&key).is_some_and(|entry| entry.dirty) {
+                pending.remove(&key);
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn counting() -> (
+        Arc<AtomicUsize>,
+        impl Fn() -> std::future::Ready<()> + Clone,
+    ) {
+        let flushes = Arc::new(AtomicUsize::new(0));
+        let counter = flushes.clone();
+        (flushes, move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            std::future::ready(())
+        })
+    }
+
+    #[tokio::test]
+    async fn test_rapid_changes_flush_once() {
+        let coalescer = Coalescer::default();
+        let window = Duration::from_millis(50);
+        let (flushes, flush) = counting();
+        let lobby = Uuid::new_v4();
+
+        for _ in 0..20 {
+            coalescer.schedule(lobby, window, flush.clone()).await;
+        }
+        tokio::time::sleep(window * 3).await;
+        assert_eq!(flushes.load(Ordering::SeqCst), 1);
+
+        // A later change gets a flush of its own
+        coalescer.schedule(lobby, window, flush.clone()).await;
+        tokio::time::sleep(window * 3).await;
+        assert_eq!(flushes.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_keys_flush_independently() {
+        let coalescer = Coalescer::default();
+        let window = Duration::from_millis(50);
+        let (flushes, flush) = counting();
+
+        for _ in 0..5 {
+            coalescer
+                .schedule(Uuid::from_u128(1), window, flush.clone())
+                .await;
+            coalescer
+                .schedule(Uuid::from_u128(2), window, flush.clone())
+                .await;
+        }
+        tokio::time::sleep(window * 3).await;
+        assert_eq!(flushes.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,140 @@
+// Application-level gzip for large snapshot messages
+//
+// Clients opt in per connection with `?compression=gzip` on the upgrade.
+// Snapshot messages (bootstrap, game state, standings) at or above
+// `COMPRESSION_THRESHOLD_BYTES` are then sent as a `CompressedMessage`:
+//
+// { "type": "lobbyBootstrap", "compressed": true, "encoding": "gzip", "data": "<base64>" }
+//
+// `type` is the wrapped message's own type, and `data` inflates to the exact
+// JSON that would otherwise have been sent. Messages without `compressed` are
+// plain. Small messages and everything outside snapshots stay uncompressed.
+
+use std::io::{Read, Write};
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use flate2::{Compression as Level, read::GzDecoder, write::GzEncoder};
+use serde::{Deserialize, Serialize};
+
+/// Serialized snapshots smaller than this are sent as-is
+pub const COMPRESSION_THRESHOLD_BYTES: usize = 16 * 1024;
+
+/// Encoding a connection accepts for large snapshots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Compression {
+    #[default]
+    None,
+    Gzip,
+}
+
+impl Compression {
+    /// Parse the `compression` query parameter; unknown values mean none.
+    pub fn parse(value: &str) -> Self {
+        match value.trim() {
+            "gzip" => Compression::Gzip,
+            _ => Compression::None,
+        }
+    }
+}
+
+/// Wire form of a compressed message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompressedMessage {
+    /// `type` of the wrapped message, so clients can route before inflating
+    #[serde(rename = "type")]
+    pub message_type: Option<String>,
+    pub compressed: bool,
+    pub encoding: String,
+    /// Base64 of the gzipped JSON
+    pub data: String,
+}
+
+/// Encode a serialized snapshot for a connection.
+///
+/// Returns `json` untouched unless the connection accepts gzip and the
+/// payload reaches the threshold.
+pub fn encode_snapshot(json: String, compression: Compression) -> String {
+    if compression != Compression::Gzip || json.len() < COMPRESSION_THRESHOLD_BYTES {
+        return json;
+    }
+
+    let compressed = match gzip(json.as_bytes()) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::warn!("Failed to compress snapshot, sending plain: {}", e);
+            return json;
+        }
+    };
+
+    let message_type = serde_json::from_str::<serde_json::Value>(&json)
+        .ok()
+        .and_then(|v| v.get("type")?.as_str().map(str::to_string));
+
+    let wrapped = CompressedMessage {
+        message_type,
+        compressed: true,
+        encoding: "gzip".to_string(),
+        data: STANDARD.encode(compressed),
+    };
+
+    serde_json::to_string(&wrapped).unwrap_or(json)
+}
+
+/// Inflate a `CompressedMessage` back to the original JSON.
+pub fn decode_snapshot(message: &CompressedMessage) -> Result<String, std::io::Error> {
+    let bytes = STANDARD
+        .decode(&message.data)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+
+    let mut json = String::new();
+    GzDecoder::new(bytes.as_slice()).read_to_string(&mut json)?;
+    Ok(json)
+}
+
+fn gzip(bytes: &[u8]) -> Result<Vec<u8>, std::io::Error> {
+    let mut encoder = GzEncoder::new(Vec::new(), Level::default());
+    encoder.write_all(bytes)?;
+    encoder.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn large_snapshot() -> String {
+        let words: Vec<String> = (0..4000).map(|i| format!("word{}", i)).collect();
+        json!({ "type": "gameState", "gameState": { "usedWords": words } }).to_string()
+    }
+
+    #[test]
+    fn test_large_snapshot_is_compressed_and_round_trips() {
+        let original = large_snapshot();
+        assert!(original.len() >= COMPRESSION_THRESHOLD_BYTES);
+
+        let encoded = encode_snapshot(original.clone(), Compression::Gzip);
+        assert!(encoded.len() < original.len());
+
+        let message: CompressedMessage = serde_json::from_str(&encoded).unwrap();
+        assert!(message.compressed);
+        assert_eq!(message.encoding, "gzip");
+        assert_eq!(message.message_type.as_deref(), Some("gameState"));
+        assert_eq!(decode_snapshot(&message).unwrap(), original);
+    }
+
+    #[test]
+    fn test_small_messages_and_opted_out_connections_stay_plain() {
+        let small = json!({ "type": "gameState", "gameState": {} }).to_string();
+        assert_eq!(encode_snapshot(small.clone(), Compression::Gzip), small);
+
+        let large = large_snapshot();
+        assert_eq!(encode_snapshot(large.clone(), Compression::None), large);
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Compression::parse("gzip"), Compression::Gzip);
+        assert_eq!(Compression::parse("brotli"), Compression::None);
+    }
+}
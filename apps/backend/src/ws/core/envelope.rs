@@ -0,0 +1,707 @@
+// Message envelope for protocol v2
+//
+// v1 clients get each server message as its own object: lobby, room and
+// leaderboard messages are tagged by `type`, game events are nested under
+// `game`, and room events carry `seq` only when sequenced. From v2 every
+// message has the same three top-level fields, so one parser routes them all:
+//
+// { "type": "playerUpdated", "seq": 42, "data": { "players": [...] } }
+// { "type": "game", "seq": 43, "data": { "type": "wordEntry", ... } }
+// { "type": "userTyping", "seq": null, "data": { "userId": ..., "isTyping": true } }
+//
+// `seq` is null on unsequenced messages. Messages are still built in the v1
+// shape; a broadcast wraps each one in a `Payload`, which builds the envelope
+// once, the first time a v2 connection needs it. Snapshots are wrapped before
+// compression: a compressed one keeps its `type`, `compressed` and `encoding`
+// fields, gains `seq`, and its `data` inflates to the whole envelope.
+
+use std::sync::OnceLock;
+
+use serde_json::{Map, Value};
+
+use super::{
+    compression::{Compression, encode_snapshot},
+    protocol::ProtocolVersion,
+};
+
+/// `type` of wrapped game events, whose own type is inside `data`
+pub const GAME_MESSAGE_TYPE: &str = "game";
+
+/// Wrap a v1 message in the envelope; anything but an object passes through
+pub fn envelope(message: Value) -> Value {
+    let Value::Object(mut fields) = message else {
+        return message;
+    };
+    let seq = fields.remove("seq").unwrap_or(Value::Null);
+
+    let (message_type, data) = match fields.remove("type") {
+        Some(message_type) => (message_type, Value::Object(fields)),
+        None => match fields.remove("game") {
+            Some(event) if fields.is_empty() => (GAME_MESSAGE_TYPE.into(), event),
+            Some(event) => {
+                fields.insert("game".to_string(), event);
+                (Value::Null, Value::Object(fields))
+            }
+            None => (Value::Null, Value::Object(fields)),
+        },
+    };
+
+    let mut wrapped = Map::new();
+    wrapped.insert("type".to_string(), message_type);
+    wrapped.insert("seq".to_string(), seq);
+    wrapped.insert("data".to_string(), data);
+    Value::Object(wrapped)
+}
+
+/// Wrap a serialized v1 message. Text that isn't JSON is returned unchanged.
+pub fn envelope_text(text: &str) -> String {
+    match serde_json::from_str::<Value>(text) {
+        Ok(message) => envelope(message).to_string(),
+        Err(_) => text.to_string(),
+    }
+}
+
+/// A serialized v1 message, rendered at most once per protocol version however
+/// many connections it goes to
+#[derive(Debug)]
+pub struct Payload {
+    json: String,
+    enveloped: OnceLock<String>,
+}
+
+impl Payload {
+    pub fn new(json: String) -> Self {
+        Self {
+            json,
+            enveloped: OnceLock::new(),
+        }
+    }
+
+    /// The text to send a connection speaking `version`
+    pub fn text_for(&self, version: ProtocolVersion) -> String {
+        if !version.uses_envelope() {
+            return self.json.clone();
+        }
+        self.enveloped
+            .get_or_init(|| envelope_text(&self.json))
+            .clone()
+    }
+}
+
+/// Encode a serialized v1 snapshot for one connection: wrapped if `version`
+/// uses the envelope, then compressed if the connection negotiated it
+pub fn encode_snapshot_for(
+    json: String,
+    version: ProtocolVersion,
+    compression: Compression,
+) -> String {
+    if !version.uses_envelope() {
+        return encode_snapshot(json, compression);
+    }
+    let Ok(message) = serde_json::from_str::<Value>(&json) else {
+        return encode_snapshot(json, compression);
+    };
+
+    let wrapped = envelope(message);
+    let seq = wrapped.get("seq").cloned().unwrap_or(Value::Null);
+    let encoded = encode_snapshot(wrapped.to_string(), compression);
+    // Compressed snapshots carry `seq` outside the gzipped data too
+    match serde_json::from_str::<Value>(&encoded) {
+        Ok(Value::Object(mut fields)) if fields.get("compressed") == Some(&Value::Bool(true)) => {
+            fields.insert("seq".to_string(), seq);
+            Value::Object(fields).to_string()
+        }
+        _ => encoded,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        announcements::{Announcement, AnnouncementLevel},
+        db::join_request::{JoinRequest, JoinRequestState},
+        games::lexi_wars::message::LexiWarsEvent,
+        models::{
+            ChatMessage, ChatMessageView, Game, LeaderboardEntry, Lobby, LobbyExtended, LobbyInfo,
+            LobbyState, LobbyStatus, PayoutUnderfill, PlayerState, PredictionOutcome, User,
+            WalletAddress,
+        },
+        ws::{
+            core::compression::{COMPRESSION_THRESHOLD_BYTES, CompressedMessage, decode_snapshot},
+            leaderboard::messages::LeaderboardServerMessage,
+            lobby::messages::LobbyServerMessage,
+            room::messages::{GameMessage, RoomServerMessage},
+        },
+    };
+    use chrono::Utc;
+    use rust_decimal::Decimal;
+    use serde::Serialize;
+    use serde_json::json;
+    use std::collections::HashMap;
+    use uuid::Uuid;
+
+    /// Assert `message` wraps to `{ type, seq, data }` with `expected_type`
+    fn assert_enveloped<M: Serialize>(message: &M, expected_type: &str, seq: Option<u64>) {
+        let mut value = serde_json::to_value(message).unwrap();
+        if let (Some(seq), Value::Object(fields)) = (seq, &mut value) {
+            fields.insert("seq".to_string(), seq.into());
+        }
+        let wrapped = envelope(value);
+        let fields = wrapped.as_object().expect("envelope is an object");
+        assert_eq!(fields.len(), 3, "{}", wrapped);
+        assert_eq!(fields["type"], expected_type, "{}", wrapped);
+        assert_eq!(fields["seq"], json!(seq), "{}", wrapped);
+        assert!(fields["data"].is_object(), "{}", wrapped);
+        assert!(fields["data"].get("seq").is_none(), "{}", wrapped);
+    }
+
+    fn player() -> PlayerState {
+        PlayerState::new(
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7".to_string(),
+            Some("alice".to_string()),
+            None,
+            10.0,
+            None,
+            false,
+        )
+    }
+
+    fn lobby_info() -> LobbyInfo {
+        let now = Utc::now().naive_utc();
+        let game_id = Uuid::new_v4();
+        let creator_id = Uuid::new_v4();
+        let lobby = Lobby {
+            id: Uuid::new_v4(),
+            path: "envelope".into(),
+            name: "Envelope".into(),
+            description: None,
+            game_id,
+            game_path: "lexi-wars".into(),
+            creator_id,
+            entry_amount: None,
+            current_amount: None,
+            token_symbol: None,
+            token_contract_id: None,
+            contract_address: None,
+            is_private: false,
+            is_sponsored: false,
+            spectators_allowed: true,
+            anonymize_players: false,
+            practice_bot: None,
+            auto_approve_trust_threshold: None,
+            payout_percents: None,
+            payout_underfill: PayoutUnderfill::default(),
+            status: LobbyStatus::Waiting,
+            created_at: now,
+            updated_at: now,
+        };
+        let lobby_id = lobby.id;
+        LobbyInfo {
+            lobby: LobbyExtended::from_parts(lobby, LobbyState::new(lobby_id)),
+            game: Game {
+                id: game_id,
+                name: "Lexi Wars".into(),
+                path: "lexi-wars".into(),
+                description: String::new(),
+                image_url: String::new(),
+                min_players: 2,
+                max_players: 8,
+                category: None,
+                creator_id,
+                is_active: true,
+                updated_at: now,
+                created_at: now,
+            },
+            creator: User {
+                id: creator_id,
+                wallet_address: WalletAddress::new("SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7")
+                    .unwrap(),
+                username: None,
+                display_name: None,
+                email: String::new(),
+                email_verified: false,
+                trust_rating: 10.0,
+                created_at: now,
+                updated_at: now,
+            },
+        }
+    }
+
+    fn announcement() -> Announcement {
+        Announcement::new(
+            AnnouncementLevel::Info,
+            "Maintenance at noon",
+            Utc::now() + chrono::Duration::hours(1),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_envelope_moves_fields_under_data() {
+        let wrapped = envelope(json!({ "type": "pong", "elapsedMs": 5, "seq": 3 }));
+        assert_eq!(
+            wrapped,
+            json!({ "type": "pong", "seq": 3, "data": { "elapsedMs": 5 } })
+        );
+
+        let wrapped = envelope(json!({ "game": { "type": "wordEntry", "word": "hi" } }));
+        assert_eq!(
+            wrapped,
+            json!({ "type": "game", "seq": null, "data": { "type": "wordEntry", "word": "hi" } })
+        );
+
+        assert_eq!(envelope(json!("text")), json!("text"));
+        assert_eq!(envelope_text("not json"), "not json");
+    }
+
+    #[test]
+    fn test_payload_is_wrapped_only_for_v2() {
+        let message = json!({ "type": "pong", "elapsedMs": 5 });
+        let payload = Payload::new(message.to_string());
+
+        assert_eq!(payload.text_for(ProtocolVersion::V1), message.to_string());
+        let wrapped: Value = serde_json::from_str(&payload.text_for(ProtocolVersion::V2)).unwrap();
+        assert_eq!(wrapped, envelope(message.clone()));
+        assert_eq!(
+            payload.text_for(ProtocolVersion::V2),
+            payload.text_for(ProtocolVersion::V2)
+        );
+        assert_eq!(
+            encode_snapshot_for(message.to_string(), ProtocolVersion::V1, Compression::Gzip),
+            message.to_string()
+        );
+    }
+
+    #[test]
+    fn test_compressed_snapshot_inflates_to_the_envelope() {
+        let words: Vec<String> = (0..4000).map(|i| format!("word{}", i)).collect();
+        let snapshot =
+            json!({ "type": "gameState", "gameState": { "usedWords": words }, "seq": 9 });
+        assert!(snapshot.to_string().len() >= COMPRESSION_THRESHOLD_BYTES);
+
+        let wrapped: Value = serde_json::from_str(&encode_snapshot_for(
+            snapshot.to_string(),
+            ProtocolVersion::V2,
+            Compression::Gzip,
+        ))
+        .unwrap();
+        assert_eq!(wrapped["type"], "gameState");
+        assert_eq!(wrapped["seq"], 9);
+        assert_eq!(wrapped["compressed"], true);
+
+        let message: CompressedMessage = serde_json::from_value(wrapped).unwrap();
+        let inflated: Value = serde_json::from_str(&decode_snapshot(&message).unwrap()).unwrap();
+        assert_eq!(inflated, envelope(snapshot));
+    }
+
+    #[test]
+    fn test_every_lobby_message_is_enveloped() {
+        let id = Uuid::new_v4();
+        let messages = [
+            (
+                LobbyServerMessage::Announcement(announcement()),
+                "announcement",
+            ),
+            (
+                LobbyServerMessage::LobbyList {
+                    lobby_info: vec![lobby_info()],
+                    total: 1,
+                },
+                "lobbyList",
+            ),
+            (
+                LobbyServerMessage::LobbyCreated {
+                    lobby_info: Box::new(lobby_info()),
+                },
+                "lobbyCreated",
+            ),
+            (
+                LobbyServerMessage::LobbyUpdated {
+                    lobby_id: id,
+                    fields: serde_json::Map::new(),
+                },
+                "lobbyUpdated",
+            ),
+            (
+                LobbyServerMessage::LobbyPlayerCountChanged {
+                    lobby_id: id,
+                    participant_count: 2,
+                },
+                "lobbyPlayerCountChanged",
+            ),
+            (
+                LobbyServerMessage::LobbyRemoved { lobby_id: id },
+                "lobbyRemoved",
+            ),
+            (
+                LobbyServerMessage::MatchFound {
+                    lobby_id: id,
+                    lobby_path: "abcd1234".into(),
+                    game_id: id,
+                },
+                "matchFound",
+            ),
+            (
+                LobbyServerMessage::LobbyCancelled {
+                    lobby_id: id,
+                    refund: Some(Decimal::from(5)),
+                },
+                "lobbyCancelled",
+            ),
+            (
+                LobbyServerMessage::MatchmakingTimedOut { game_id: id },
+                "matchmakingTimedOut",
+            ),
+            (
+                LobbyServerMessage::Error {
+                    code: "NOT_FOUND".into(),
+                    message: "gone".into(),
+                },
+                "error",
+            ),
+        ];
+        for (message, expected_type) in &messages {
+            assert_enveloped(message, expected_type, None);
+        }
+    }
+
+    #[test]
+    fn test_every_room_message_is_enveloped() {
+        let id = Uuid::new_v4();
+        let chat = ChatMessage::new(id, id, "hello", None).unwrap();
+        let join_request = JoinRequest {
+            user_id: id,
+            state: JoinRequestState::Pending,
+            wallet_address: "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7".into(),
+            username: None,
+            display_name: None,
+            trust_rating: 10.0,
+            is_creator: false,
+            created_at: 0,
+            reason: None,
+        };
+        let messages = [
+            (
+                RoomServerMessage::LobbyBootstrap {
+                    lobby_info: lobby_info(),
+                    players: vec![player()],
+                    join_requests: vec![join_request.clone()],
+                    chat_history: vec![ChatMessageView::new(chat.clone(), None)],
+                    read_receipts: HashMap::from([(id, chat.message_id)]),
+                },
+                "lobbyBootstrap",
+            ),
+            (
+                RoomServerMessage::Announcement(announcement()),
+                "announcement",
+            ),
+            (
+                RoomServerMessage::CreatorChanged {
+                    previous_creator_id: id,
+                    creator_id: id,
+                },
+                "creatorChanged",
+            ),
+            (
+                RoomServerMessage::LobbyStatusChanged {
+                    status: LobbyStatus::InProgress,
+                    participant_count: 2,
+                    current_amount: None,
+                },
+                "lobbyStatusChanged",
+            ),
+            (
+                RoomServerMessage::RoomStateUpdated {
+                    status: LobbyStatus::Waiting,
+                    participant_count: 1,
+                    current_amount: None,
+                    players: vec![],
+                    started_at: None,
+                    finished_at: None,
+                    updated_at: 0,
+                },
+                "roomStateUpdated",
+            ),
+            (
+                RoomServerMessage::StartCountdown {
+                    seconds_remaining: Some(3),
+                    deadline_ms: Some(3000),
+                    server_time_ms: 0,
+                },
+                "startCountdown",
+            ),
+            (
+                RoomServerMessage::ReadyCheck {
+                    timeout_secs: 30,
+                    deadline_ms: 30_000,
+                    server_time_ms: 0,
+                },
+                "readyCheck",
+            ),
+            (
+                RoomServerMessage::PlayerReady {
+                    user_id: id,
+                    ready_count: 1,
+                    player_count: 2,
+                },
+                "playerReady",
+            ),
+            (RoomServerMessage::AllReady, "allReady"),
+            (
+                RoomServerMessage::ReadyCheckTimedOut {
+                    unready: vec![id],
+                    dropped: true,
+                },
+                "readyCheckTimedOut",
+            ),
+            (
+                RoomServerMessage::PlayerJoined { player: player() },
+                "playerJoined",
+            ),
+            (
+                RoomServerMessage::PlayerLeft { player: player() },
+                "playerLeft",
+            ),
+            (
+                RoomServerMessage::PlayerKicked { player: player() },
+                "playerKicked",
+            ),
+            (
+                RoomServerMessage::JoinRequestsUpdated {
+                    join_requests: vec![join_request],
+                },
+                "joinRequestsUpdated",
+            ),
+            (
+                RoomServerMessage::JoinRequestStatus {
+                    user_id: id,
+                    accepted: true,
+                },
+                "joinRequestStatus",
+            ),
+            (
+                RoomServerMessage::MessageReceived {
+                    message: chat.clone(),
+                },
+                "messageReceived",
+            ),
+            (
+                RoomServerMessage::ReactionAdded {
+                    message_id: chat.message_id,
+                    user_id: id,
+                    emoji: "🔥".into(),
+                },
+                "reactionAdded",
+            ),
+            (
+                RoomServerMessage::ReactionRemoved {
+                    message_id: chat.message_id,
+                    user_id: id,
+                    emoji: "🔥".into(),
+                },
+                "reactionRemoved",
+            ),
+            (
+                RoomServerMessage::UserTyping {
+                    user_id: id,
+                    is_typing: true,
+                },
+                "userTyping",
+            ),
+            (
+                RoomServerMessage::ReadReceipt {
+                    user_id: id,
+                    message_id: chat.message_id,
+                },
+                "readReceipt",
+            ),
+            (
+                RoomServerMessage::ChatHistory {
+                    messages: vec![ChatMessageView::new(chat, Some(id))],
+                    next_cursor: None,
+                    has_more: false,
+                },
+                "chatHistory",
+            ),
+            (RoomServerMessage::Pong { elapsed_ms: 12 }, "pong"),
+            (
+                RoomServerMessage::ServerTime {
+                    server_time_ms: 0,
+                    client_ts: Some(0),
+                },
+                "serverTime",
+            ),
+            (
+                RoomServerMessage::PlayerUpdated {
+                    players: vec![player()],
+                },
+                "playerUpdated",
+            ),
+            (
+                RoomServerMessage::GameState {
+                    game_state: json!({ "usedWords": [] }),
+                },
+                "gameState",
+            ),
+            (
+                RoomServerMessage::GameStarted {
+                    seats: vec![id],
+                    seating_seed: Some("42".into()),
+                },
+                "gameStarted",
+            ),
+            (
+                RoomServerMessage::GameStartFailed {
+                    reason: "not enough players".into(),
+                },
+                "gameStartFailed",
+            ),
+            (
+                RoomServerMessage::FinalStanding {
+                    standings: vec![player()],
+                    ranked: true,
+                },
+                "finalStanding",
+            ),
+            (
+                RoomServerMessage::GameOver {
+                    rank: 1,
+                    prize: Some(Decimal::from(10)),
+                    wars_point: 4.0,
+                    signed_results: None,
+                },
+                "gameOver",
+            ),
+            (
+                RoomServerMessage::GameTimedOut {
+                    max_duration_secs: 600,
+                },
+                "gameTimedOut",
+            ),
+            (
+                RoomServerMessage::PredictionRecorded { winner_id: id },
+                "predictionRecorded",
+            ),
+            (
+                RoomServerMessage::PredictionsSettled {
+                    outcome: PredictionOutcome::score(&HashMap::from([(id, id)]), Some(id)),
+                    points_awarded: 10,
+                },
+                "predictionsSettled",
+            ),
+            (RoomServerMessage::ClaimSuccess, "claimSuccess"),
+            (
+                RoomServerMessage::ActionAck {
+                    client_action_id: "a1".into(),
+                    accepted: false,
+                    code: Some("NOT_YOUR_TURN".into()),
+                },
+                "actionAck",
+            ),
+            (
+                RoomServerMessage::Error {
+                    code: "NOT_FOUND".into(),
+                    message: "gone".into(),
+                },
+                "error",
+            ),
+        ];
+        for (message, expected_type) in &messages {
+            assert_enveloped(message, expected_type, Some(7));
+            assert_enveloped(message, expected_type, None);
+        }
+    }
+
+    #[test]
+    fn test_every_leaderboard_message_is_enveloped() {
+        let entry = LeaderboardEntry {
+            rank: 1,
+            user_id: Uuid::new_v4(),
+            wallet_address: "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7".into(),
+            points: 4.0,
+        };
+        let messages = [
+            (
+                LeaderboardServerMessage::LeaderboardSnapshot {
+                    season_id: Some(1),
+                    game_id: None,
+                    entries: vec![entry.clone()],
+                },
+                "leaderboardSnapshot",
+            ),
+            (
+                LeaderboardServerMessage::LeaderboardUpdated {
+                    season_id: 1,
+                    game_id: None,
+                    changed: vec![entry],
+                    removed: vec![Uuid::new_v4()],
+                },
+                "leaderboardUpdated",
+            ),
+            (
+                LeaderboardServerMessage::Error {
+                    code: "NOT_FOUND".into(),
+                    message: "gone".into(),
+                },
+                "error",
+            ),
+        ];
+        for (message, expected_type) in &messages {
+            assert_enveloped(message, expected_type, None);
+        }
+    }
+
+    #[test]
+    fn test_every_game_event_is_enveloped() {
+        let events = [
+            LexiWarsEvent::UsedWord {
+                word: "hello".into(),
+            },
+            LexiWarsEvent::WordEntry {
+                word: "hello".into(),
+                player: player(),
+            },
+            LexiWarsEvent::ScoreUpdate {
+                player: player(),
+                points: 5,
+                score: 12,
+            },
+            LexiWarsEvent::Invalid {
+                reason: "too short".into(),
+            },
+            LexiWarsEvent::PlayersCount {
+                remaining: 2,
+                total: 3,
+            },
+            LexiWarsEvent::Turn {
+                player: player(),
+                timeout_secs: 15,
+                turn_deadline_ms: 15_000,
+                server_time_ms: 0,
+            },
+            LexiWarsEvent::Rule { rule: None },
+            LexiWarsEvent::Eliminated {
+                player: player(),
+                reason: "timeout".into(),
+            },
+            LexiWarsEvent::Impasse {
+                reason: "no playable word".into(),
+            },
+            LexiWarsEvent::Countdown {
+                time: 10,
+                turn_deadline_ms: 10_000,
+                server_time_ms: 0,
+            },
+        ];
+        for event in &events {
+            let message = GameMessage::from_event(event).unwrap();
+            assert_enveloped(&message, GAME_MESSAGE_TYPE, Some(3));
+
+            let wrapped = envelope(serde_json::to_value(&message).unwrap());
+            assert_eq!(wrapped["data"], serde_json::to_value(event).unwrap());
+        }
+    }
+}
@@ -1,4 +1,6 @@
+use crate::db::lobby_presence::LobbyPresenceRepository;
 use crate::state::{AppState, ConnectionInfo};
+use crate::ws::core::envelope::{Payload, encode_snapshot_for};
 use axum::extract::ws::Message;
 use futures::SinkExt;
 use serde::Serialize;
@@ -10,7 +12,19 @@ pub async fn send_to_connection<M: Serialize>(
     conn: &Arc<ConnectionInfo>,
     msg: &M,
 ) -> Result<(), serde_json::Error> {
-    let json = serde_json::to_string(msg)?;
+    let json = Payload::new(serde_json::to_string(msg)?).text_for(conn.protocol);
+    let mut s = conn.sender.lock().await;
+    let _ = s.send(Message::Text(json.into())).await;
+    Ok(())
+}
+
+/// Send a snapshot (bootstrap, full state or list), compressed if the
+/// connection negotiated it and the payload is large
+pub async fn send_snapshot_to_connection<M: Serialize>(
+    conn: &Arc<ConnectionInfo>,
+    msg: &M,
+) -> Result<(), serde_json::Error> {
+    let json = encode_snapshot_for(serde_json::to_string(msg)?, conn.protocol, conn.compression);
     let mut s = conn.sender.lock().await;
     let _ = s.send(Message::Text(json.into())).await;
     Ok(())
@@ -26,6 +40,26 @@ pub async fn register_connection(state: &AppState, connection_id: Uuid, conn: Ar
     // Insert into all indices
     let mut indices = state.indices.lock().await;
     indices.insert(&conn);
+    drop(indices);
+
+    // Other instances' reapers see the room connection too
+    if let Some(lobby_id) = conn.lobby_id()
+        && let Err(e) = LobbyPresenceRepository::new(state.redis.clone())
+            .touch(lobby_id, &[connection_id])
+            .await
+    {
+        tracing::warn!("Failed to record presence in {}: {}", lobby_id, e);
+    }
+}
+
+/// Forget closed room connections in the shared presence, logging failures.
+async fn remove_presence(state: &AppState, lobby_id: Uuid, connection_ids: &[Uuid]) {
+    if let Err(e) = LobbyPresenceRepository::new(state.redis.clone())
+        .remove(lobby_id, connection_ids)
+        .await
+    {
+        tracing::warn!("Failed to clear presence in {}: {}", lobby_id, e);
+    }
 }
 
 /// Unregister connection by `connection_id` and remove it from all indices.
@@ -38,6 +72,16 @@ pub async fn unregister_connection(state: &AppState, connection_id: &Uuid) {
         // Remove from all indices
         let mut indices = state.indices.lock().await;
         indices.remove(&conn);
+
+        // Last one out of a room drops its delivery queue
+        if let Some(lobby_id) = conn.lobby_id() {
+            let last_out = indices.get_lobby_connections(&lobby_id).is_none();
+            drop(indices);
+            if last_out {
+                state.room_sequencer.close(lobby_id).await;
+            }
+            remove_presence(state, lobby_id, &[*connection_id]).await;
+        }
     }
 }
 
@@ -60,6 +104,11 @@ pub async fn unregister_lobby_connections(state: &AppState, lobby_id: Uuid) -> u
             indices.remove(&conn);
         }
     }
+    drop(conns);
+    drop(indices);
+    state.room_sequencer.close(lobby_id).await;
+    remove_presence(state, lobby_id, &conn_ids).await;
+    state.room_pseudonyms.reveal(&state.redis, lobby_id).await;
 
     count
 }
@@ -78,11 +127,21 @@ pub async fn unregister_user_connections(state: &AppState, user_id: Uuid) -> usi
 
     // Remove from global connections
     let mut conns = state.connections.lock().await;
+    let mut rooms: Vec<(Uuid, Uuid)> = Vec::new();
     for conn_id in &conn_ids {
         if let Some(conn) = conns.remove(conn_id) {
             indices.remove(&conn);
+            if let Some(lobby_id) = conn.lobby_id() {
+                rooms.push((lobby_id, *conn_id));
+            }
         }
     }
+    drop(conns);
+    drop(indices);
+
+    for (lobby_id, conn_id) in rooms {
+        remove_presence(state, lobby_id, &[conn_id]).await;
+    }
 
     count
 }
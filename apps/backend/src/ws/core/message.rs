@@ -8,6 +8,8 @@ use serde_json::Value;
 /// Trait for messages that can be broadcast over WebSocket
 pub trait BroadcastMessage {
     fn to_json(&self) -> Result<String, serde_json::Error>;
+
+    fn to_value(&self) -> Result<Value, serde_json::Error>;
 }
 
 /// Implement for any Serialize type (covers LobbyServerMessage and game events)
@@ -15,6 +17,10 @@ impl<T: Serialize> BroadcastMessage for T {
     fn to_json(&self) -> Result<String, serde_json::Error> {
         serde_json::to_string(self)
     }
+
+    fn to_value(&self) -> Result<Value, serde_json::Error> {
+        serde_json::to_value(self)
+    }
 }
 
 /// Wrapper for raw JSON values (for game events that are already serialized)
@@ -25,6 +31,10 @@ impl BroadcastMessage for JsonMessage {
     fn to_json(&self) -> Result<String, serde_json::Error> {
         serde_json::to_string(&self.0)
     }
+
+    fn to_value(&self) -> Result<Value, serde_json::Error> {
+        Ok(self.0.clone())
+    }
 }
 
 impl From<Value> for JsonMessage {
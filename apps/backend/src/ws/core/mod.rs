@@ -1,6 +1,21 @@
 // Core WebSocket utilities
+pub mod coalesce;
+pub mod compression;
+pub mod envelope;
 pub mod manager;
 pub mod message;
+pub mod protocol;
+pub mod pseudonyms;
+pub mod sequencer;
+pub mod sink;
+pub mod spectator_delay;
 
+pub use coalesce::Coalescer;
+pub use compression::Compression;
 pub use manager::*;
 pub use message::BroadcastMessage;
+pub use protocol::{Negotiated, ProtocolVersion, WsProtocol};
+pub use pseudonyms::RoomPseudonyms;
+pub use sequencer::RoomSequencer;
+pub use sink::ConnectionSink;
+pub use spectator_delay::SpectatorDelay;
@@ -0,0 +1,296 @@
+// WebSocket protocol version negotiation
+//
+// Clients name the message schema they speak either as a subprotocol
+// (`Sec-WebSocket-Protocol: stacks-wars.v1`) or with `?protocol=1`. Clients
+// that send neither are treated as the original v1 clients. v2 wraps every
+// outbound message in a `{ type, seq, data }` envelope (see `envelope`). The
+// upgrade also settles snapshot compression (`?compression=gzip`, see
+// `compression`).
+
+use std::{convert::Infallible, fmt};
+
+use axum::{
+    extract::{
+        FromRequestParts,
+        ws::{CloseFrame, Message, WebSocket, WebSocketUpgrade},
+    },
+    http::{HeaderMap, header::SEC_WEBSOCKET_PROTOCOL, request::Parts},
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+
+use super::compression::Compression;
+
+/// Subprotocol names are this prefix followed by the version number
+pub const SUBPROTOCOL_PREFIX: &str = "stacks-wars.v";
+
+/// Close code sent when the requested version isn't supported (private-use range)
+pub const UNSUPPORTED_PROTOCOL_CLOSE_CODE: u16 = 4001;
+
+/// Version of the WebSocket message schema a connection speaks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct ProtocolVersion(pub u16);
+
+impl ProtocolVersion {
+    pub const V1: ProtocolVersion = ProtocolVersion(1);
+    pub const V2: ProtocolVersion = ProtocolVersion(2);
+
+    /// Versions this server can speak, oldest first
+    pub const SUPPORTED: &'static [ProtocolVersion] = &[ProtocolVersion::V1, ProtocolVersion::V2];
+
+    /// Assumed for clients that don't ask for a version
+    pub const DEFAULT: ProtocolVersion = ProtocolVersion::V1;
+
+    /// Parse `1`, `v1` or `stacks-wars.v1`.
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        let number = value
+            .strip_prefix(SUBPROTOCOL_PREFIX)
+            .or_else(|| value.strip_prefix('v'))
+            .unwrap_or(value);
+        number.parse().ok().map(ProtocolVersion)
+    }
+
+    pub fn is_supported(self) -> bool {
+        Self::SUPPORTED.contains(&self)
+    }
+
+    /// Whether outbound messages go out in the `{ type, seq, data }` envelope
+    pub fn uses_envelope(self) -> bool {
+        self >= Self::V2
+    }
+
+    /// Subprotocol name for this version, e.g. `stacks-wars.v1`.
+    pub fn subprotocol(self) -> String {
+        format!("{}{}", SUBPROTOCOL_PREFIX, self.0)
+    }
+}
+
+impl fmt::Display for ProtocolVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "v{}", self.0)
+    }
+}
+
+/// Outcome of negotiation for an accepted connection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Negotiated {
+    pub version: ProtocolVersion,
+    /// Subprotocol to echo back, if the client offered one
+    pub subprotocol: Option<String>,
+    pub compression: Compression,
+}
+
+/// The client asked only for versions this server doesn't speak.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedProtocol {
+    /// What the client asked for, as sent
+    pub requested: String,
+    /// Offered subprotocol, echoed so the handshake completes and the client
+    /// sees the close reason instead of a bare handshake failure
+    pub subprotocol: Option<String>,
+}
+
+impl fmt::Display for UnsupportedProtocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let supported: Vec<String> = ProtocolVersion::SUPPORTED
+            .iter()
+            .map(|v| v.subprotocol())
+            .collect();
+        write!(
+            f,
+            "unsupported protocol version '{}'; supported: {}",
+            self.requested,
+            supported.join(", ")
+        )
+    }
+}
+
+/// Pick the version for a connection from its headers and query string.
+///
+/// The subprotocol header wins over the query parameter. Of several offered
+/// subprotocols the highest supported one is used; other applications'
+/// subprotocols are ignored.
+pub fn negotiate(
+    headers: &HeaderMap,
+    query: Option<&str>,
+) -> Result<Negotiated, UnsupportedProtocol> {
+    let compression = query_param(query, "compression")
+        .map(Compression::parse)
+        .unwrap_or_default();
+
+    let offered: Vec<&str> = headers
+        .get_all(SEC_WEBSOCKET_PROTOCOL)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .map(str::trim)
+        .filter(|p| p.starts_with(SUBPROTOCOL_PREFIX))
+        .collect();
+
+    if !offered.is_empty() {
+        let best = offered
+            .iter()
+            .filter_map(|p| ProtocolVersion::parse(p).map(|v| (v, *p)))
+            .filter(|(v, _)| v.is_supported())
+            .max_by_key(|(v, _)| *v);
+
+        return match best {
+            Some((version, name)) => Ok(Negotiated {
+                version,
+                subprotocol: Some(name.to_string()),
+                compression,
+            }),
+            None => Err(UnsupportedProtocol {
+                requested: offered.join(", "),
+                subprotocol: Some(offered[0].to_string()),
+            }),
+        };
+    }
+
+    match query_param(query, "protocol") {
+        None => Ok(Negotiated {
+            version: ProtocolVersion::DEFAULT,
+            subprotocol: None,
+            compression,
+        }),
+        Some(value) => match ProtocolVersion::parse(value) {
+            Some(version) if version.is_supported() => Ok(Negotiated {
+                version,
+                subprotocol: None,
+                compression,
+            }),
+            _ => Err(UnsupportedProtocol {
+                requested: value.to_string(),
+                subprotocol: None,
+            }),
+        },
+    }
+}
+
+fn query_param<'a>(query: Option<&'a str>, name: &str) -> Option<&'a str> {
+    query?
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+}
+
+/// Extractor running `negotiate` on the upgrade request; never rejects.
+pub struct WsProtocol(pub Result<Negotiated, UnsupportedProtocol>);
+
+impl<S: Send + Sync> FromRequestParts<S> for WsProtocol {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(WsProtocol(negotiate(&parts.headers, parts.uri.query())))
+    }
+}
+
+impl WsProtocol {
+    /// Complete the upgrade, running `on_accept` with the negotiated settings.
+    ///
+    /// Unsupported versions still get a completed handshake, followed by a
+    /// close frame whose reason says which versions are supported.
+    pub fn upgrade<F, Fut>(self, ws: WebSocketUpgrade, on_accept: F) -> Response
+    where
+        F: FnOnce(WebSocket, Negotiated) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        match self.0 {
+            Ok(negotiated) => ws
+                .protocols(negotiated.subprotocol.clone())
+                .on_upgrade(move |socket| on_accept(socket, negotiated))
+                .into_response(),
+            Err(unsupported) => {
+                tracing::info!("Refusing websocket: {}", unsupported);
+                ws.protocols(unsupported.subprotocol.clone())
+                    .on_upgrade(move |socket| refuse(socket, unsupported))
+                    .into_response()
+            }
+        }
+    }
+}
+
+/// Close frame reasons are limited to 123 bytes
+const MAX_CLOSE_REASON_LEN: usize = 123;
+
+async fn refuse(mut socket: WebSocket, unsupported: UnsupportedProtocol) {
+    let mut reason = unsupported.to_string();
+    if reason.len() > MAX_CLOSE_REASON_LEN {
+        let mut end = MAX_CLOSE_REASON_LEN;
+        while !reason.is_char_boundary(end) {
+            end -= 1;
+        }
+        reason.truncate(end);
+    }
+
+    let frame = CloseFrame {
+        code: UNSUPPORTED_PROTOCOL_CLOSE_CODE,
+        reason: reason.into(),
+    };
+    let _ = socket.send(Message::Close(Some(frame))).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn with_protocols(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            SEC_WEBSOCKET_PROTOCOL,
+            HeaderValue::from_str(value).unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn test_no_request_defaults_to_v1() {
+        let negotiated = negotiate(&HeaderMap::new(), None).unwrap();
+        assert_eq!(negotiated.version, ProtocolVersion::V1);
+        assert_eq!(negotiated.subprotocol, None);
+    }
+
+    #[test]
+    fn test_subprotocol_header_is_echoed() {
+        let negotiated = negotiate(&with_protocols("chat, stacks-wars.v1"), None).unwrap();
+        assert_eq!(negotiated.version, ProtocolVersion::V1);
+        assert_eq!(negotiated.subprotocol.as_deref(), Some("stacks-wars.v1"));
+    }
+
+    #[test]
+    fn test_highest_supported_subprotocol_wins() {
+        let negotiated =
+            negotiate(&with_protocols("stacks-wars.v99, stacks-wars.v1"), None).unwrap();
+        assert_eq!(negotiated.version, ProtocolVersion::V1);
+
+        let negotiated =
+            negotiate(&with_protocols("stacks-wars.v1, stacks-wars.v2"), None).unwrap();
+        assert_eq!(negotiated.version, ProtocolVersion::V2);
+        assert!(negotiated.version.uses_envelope());
+        assert!(!ProtocolVersion::DEFAULT.uses_envelope());
+    }
+
+    #[test]
+    fn test_unsupported_versions_are_refused() {
+        let err = negotiate(&with_protocols("stacks-wars.v99"), None).unwrap_err();
+        assert_eq!(err.subprotocol.as_deref(), Some("stacks-wars.v99"));
+        assert!(err.to_string().contains("supported: stacks-wars.v1"));
+
+        let err = negotiate(&HeaderMap::new(), Some("limit=5&protocol=7")).unwrap_err();
+        assert_eq!(err.requested, "7");
+        assert!(negotiate(&HeaderMap::new(), Some("protocol=v1")).is_ok());
+    }
+
+    #[test]
+    fn test_compression_is_negotiated_alongside_version() {
+        let negotiated =
+            negotiate(&with_protocols("stacks-wars.v1"), Some("compression=gzip")).unwrap();
+        assert_eq!(negotiated.compression, Compression::Gzip);
+
+        let negotiated = negotiate(&HeaderMap::new(), None).unwrap();
+        assert_eq!(negotiated.compression, Compression::None);
+    }
+}
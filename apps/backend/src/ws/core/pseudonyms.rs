@@ -0,0 +1,342 @@
+// Anonymized rosters: spectators see "Player A/B" until the game finishes
+//
+// In a lobby created with `anonymize_players`, every room message sent to a
+// spectator connection, and the lobby's public HTTP reads, have the players'
+// identities swapped for a pseudonym. A player's user id is masked wherever
+// it appears, as a string value or as an object key; their wallet, username
+// and display name only in the fields that carry them, so a played word that
+// happens to equal a username is left alone. Players and the creator always
+// see real identities in the room.
+//
+// Pseudonyms are handed out in join order when the room is first
+// bootstrapped and as players join, and a player keeps theirs for the rest of
+// the game even if someone else leaves. Tables are kept in Redis as well as in
+// memory, so a restart doesn't rename anyone. When the game finishes the
+// lobby's table is dropped just before `FinalStanding`, which reveals who
+// played. The room sequencer captures the table when a message is published,
+// so messages queued before the reveal still go out masked.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::{
+    db::pseudonym::PseudonymRepository, errors::AppError, models::PlayerState, state::RedisClient,
+};
+
+/// Fields holding a player's wallet or name rather than their id
+const IDENTITY_FIELDS: [&str; 3] = ["walletAddress", "username", "displayName"];
+
+/// A player's pseudonym and the identities it stands in for.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Alias {
+    pub name: String,
+    /// Wallet, username and display name
+    pub identities: Vec<String>,
+}
+
+/// One lobby's players and what spectators call them.
+#[derive(Debug, Clone, Default)]
+pub struct Pseudonyms {
+    players: HashMap<Uuid, Alias>,
+    /// Every wallet and name of a player, mapped to their pseudonym
+    identities: HashMap<String, String>,
+}
+
+impl Pseudonyms {
+    /// Give each of `players` without a pseudonym the next one, in join
+    /// order. Returns the players newly named.
+    pub fn assign(&mut self, players: &[PlayerState]) -> Vec<Uuid> {
+        let mut players: Vec<&PlayerState> = players.iter().collect();
+        players.sort_by_key(|player| player.joined_at);
+
+        let mut named = Vec::new();
+        for player in players {
+            if self.players.contains_key(&player.user_id) {
+                continue;
+            }
+            let identities = [
+                Some(player.wallet_address.clone()),
+                player.username.clone(),
+                player.display_name.clone(),
+            ];
+            let alias = Alias {
+                name: pseudonym(self.players.len()),
+                identities: identities
+                    .into_iter()
+                    .flatten()
+                    .filter(|identity| !identity.is_empty())
+                    .collect(),
+            };
+            self.insert(player.user_id, alias);
+            named.push(player.user_id);
+        }
+        named
+    }
+
+    /// Add a stored pseudonym to the table
+    pub fn insert(&mut self, user_id: Uuid, alias: Alias) {
+        for identity in &alias.identities {
+            self.identities
+                .entry(identity.clone())
+                .or_insert_with(|| alias.name.clone());
+        }
+        self.players.insert(user_id, alias);
+    }
+
+    /// The pseudonym `user_id` goes by, if they have one
+    pub fn name_of(&self, user_id: Uuid) -> Option<&str> {
+        self.players.get(&user_id).map(|alias| alias.name.as_str())
+    }
+
+    /// `user_id`'s entry, as stored
+    pub fn alias_of(&self, user_id: Uuid) -> Option<&Alias> {
+        self.players.get(&user_id)
+    }
+
+    /// Replace every player identity in `value` with the player's pseudonym.
+    pub fn mask(&self, value: &mut Value) {
+        self.mask_field("", value);
+    }
+
+    fn mask_field(&self, field: &str, value: &mut Value) {
+        match value {
+            Value::String(text) => {
+                let name = match Uuid::parse_str(text) {
+                    Ok(user_id) => self.name_of(user_id),
+                    Err(_) if IDENTITY_FIELDS.contains(&field) => {
+                        self.identities.get(text.as_str()).map(String::as_str)
+                    }
+                    Err(_) => None,
+                };
+                if let Some(name) = name {
+                    *text = name.to_string();
+                }
+            }
+            Value::Array(items) => items
+                .iter_mut()
+                .for_each(|item| self.mask_field(field, item)),
+            Value::Object(fields) => {
+                *fields = std::mem::take(fields)
+                    .into_iter()
+                    .map(|(key, mut value)| {
+                        self.mask_field(&key, &mut value);
+                        let key = Uuid::parse_str(&key)
+                            .ok()
+                            .and_then(|user_id| self.name_of(user_id))
+                            .map(str::to_string)
+                            .unwrap_or(key);
+                        (key, value)
+                    })
+                    .collect();
+            }
+            _ => {}
+        }
+    }
+
+    /// A masked copy of `value`
+    pub fn masked(&self, value: &Value) -> Value {
+        let mut masked = value.clone();
+        self.mask(&mut masked);
+        masked
+    }
+}
+
+/// "Player A" to "Player Z", then "Player AA", "Player AB", ...
+fn pseudonym(index: usize) -> String {
+    let mut letters = Vec::new();
+    let mut n = index + 1;
+    while n > 0 {
+        n -= 1;
+        letters.push(char::from(b'A' + (n % 26) as u8));
+        n /= 26;
+    }
+    format!("Player {}", letters.iter().rev().collect::<String>())
+}
+
+/// Pseudonym tables for the anonymized lobbies whose games haven't finished.
+///
+/// Tables are read from memory on the send paths; Redis holds them across
+/// restarts and is read back the first time a lobby is touched after one.
+#[derive(Clone, Default)]
+pub struct RoomPseudonyms {
+    lobbies: Arc<Mutex<HashMap<Uuid, Arc<Pseudonyms>>>>,
+}
+
+impl RoomPseudonyms {
+    /// Start (or keep) masking the lobby's players from spectators. Players
+    /// without a pseudonym get one in the order they joined.
+    pub async fn track(
+        &self,
+        redis: &RedisClient,
+        lobby_id: Uuid,
+        players: &[PlayerState],
+    ) -> Result<Arc<Pseudonyms>, AppError> {
+        let table = self.load(redis, lobby_id).await?.unwrap_or_default();
+        self.name(redis, lobby_id, table, players).await
+    }
+
+    /// Name a player who joined a lobby that is already being masked
+    pub async fn add(
+        &self,
+        redis: &RedisClient,
+        lobby_id: Uuid,
+        player: &PlayerState,
+    ) -> Result<(), AppError> {
+        if let Some(table) = self.load(redis, lobby_id).await? {
+            self.name(redis, lobby_id, table, std::slice::from_ref(player))
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn name(
+        &self,
+        redis: &RedisClient,
+        lobby_id: Uuid,
+        mut table: Arc<Pseudonyms>,
+        players: &[PlayerState],
+    ) -> Result<Arc<Pseudonyms>, AppError> {
+        let named = Arc::make_mut(&mut table).assign(players);
+        PseudonymRepository::new(redis.clone())
+            .save(lobby_id, &table, &named)
+            .await?;
+        self.lobbies.lock().unwrap().insert(lobby_id, table.clone());
+        Ok(table)
+    }
+
+    /// The lobby's current table, if spectators there see pseudonyms
+    pub fn get(&self, lobby_id: Uuid) -> Option<Arc<Pseudonyms>> {
+        self.lobbies.lock().unwrap().get(&lobby_id).cloned()
+    }
+
+    /// The lobby's table, read back from Redis if this process hasn't seen it
+    pub async fn load(
+        &self,
+        redis: &RedisClient,
+        lobby_id: Uuid,
+    ) -> Result<Option<Arc<Pseudonyms>>, AppError> {
+        if let Some(table) = self.get(lobby_id) {
+            return Ok(Some(table));
+        }
+        let Some(table) = PseudonymRepository::new(redis.clone())
+            .get_table(lobby_id)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let table = Arc::new(table);
+        self.lobbies
+            .lock()
+            .unwrap()
+            .entry(lobby_id)
+            .or_insert_with(|| table.clone());
+        Ok(Some(table))
+    }
+
+    /// Stop masking: spectators see real identities from here on
+    pub async fn reveal(&self, redis: &RedisClient, lobby_id: Uuid) {
+        self.lobbies.lock().unwrap().remove(&lobby_id);
+        if let Err(e) = PseudonymRepository::new(redis.clone())
+            .delete(lobby_id)
+            .await
+        {
+            tracing::warn!("Failed to drop pseudonyms of {}: {}", lobby_id, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn player(username: &str, joined_at: i64) -> PlayerState {
+        let mut player = PlayerState::new(
+            Uuid::new_v4(),
+            Uuid::nil(),
+            format!("SP{}", username.to_uppercase()),
+            Some(username.to_string()),
+            None,
+            10.0,
+            None,
+            false,
+        );
+        player.joined_at = joined_at;
+        player
+    }
+
+    #[test]
+    fn test_pseudonyms_follow_join_order_and_stay_put() {
+        let mut table = Pseudonyms::default();
+        let (alice, bob, carol) = (player("alice", 1), player("bob", 2), player("carol", 3));
+
+        assert_eq!(
+            table.assign(&[bob.clone(), alice.clone()]),
+            vec![alice.user_id, bob.user_id]
+        );
+        assert_eq!(table.assign(&[carol.clone()]), vec![carol.user_id]);
+        // Bob leaves; nobody else is renamed
+        assert!(table.assign(&[alice.clone(), carol.clone()]).is_empty());
+
+        assert_eq!(table.name_of(alice.user_id), Some("Player A"));
+        assert_eq!(table.name_of(bob.user_id), Some("Player B"));
+        assert_eq!(table.name_of(carol.user_id), Some("Player C"));
+
+        // A table rebuilt from its stored entries masks the same way
+        let mut restored = Pseudonyms::default();
+        for user_id in [alice.user_id, bob.user_id, carol.user_id] {
+            restored.insert(user_id, table.alias_of(user_id).unwrap().clone());
+        }
+        let event = json!({ "player": { "userId": bob.user_id, "username": "bob" } });
+        assert_eq!(restored.masked(&event), table.masked(&event));
+    }
+
+    #[test]
+    fn test_mask_replaces_identities_in_id_and_identity_fields() {
+        let mut table = Pseudonyms::default();
+        let (alice, bob) = (player("alice", 1), player("bob", 2));
+        table.assign(&[alice.clone(), bob.clone()]);
+
+        let event = json!({
+            "type": "turn",
+            "player": {
+                "userId": alice.user_id,
+                "walletAddress": alice.wallet_address,
+                "username": "alice",
+                "displayName": null,
+            },
+            "scores": { bob.user_id.to_string(): 3 },
+            "winners": [bob.user_id],
+            "word": "alice",
+        });
+        assert_eq!(
+            table.masked(&event),
+            json!({
+                "type": "turn",
+                "player": {
+                    "userId": "Player A",
+                    "walletAddress": "Player A",
+                    "username": "Player A",
+                    "displayName": null,
+                },
+                "scores": { "Player B": 3 },
+                "winners": ["Player B"],
+                "word": "alice",
+            })
+        );
+    }
+
+    #[test]
+    fn test_pseudonym_letters_roll_over() {
+        assert_eq!(pseudonym(0), "Player A");
+        assert_eq!(pseudonym(25), "Player Z");
+        assert_eq!(pseudonym(26), "Player AA");
+        assert_eq!(pseudonym(27), "Player AB");
+    }
+}
@@ -0,0 +1,252 @@
+// Ordered, sequence-numbered delivery of room events
+//
+// Every event fanned out to a lobby room goes through one task per lobby,
+// which stamps it with the next `seq` (1, 2, 3, ...) and writes it to every
+// connection in the room before taking the next one:
+//
+// { "type": "playerUpdated", "players": [...], "seq": 42 }
+//
+// Room snapshots sent to a single connection (bootstrap, game state,
+// standings) carry the `seq` of the last event before them without using one
+// up. A client applies the snapshot and then expects `seq + 1`; events at or
+// below the snapshot's `seq` are already part of it. On a gap the client sends
+// `resync` and gets fresh snapshots. Messages addressed to one user or
+// connection (acks, errors, GameOver) are not sequenced, nor are events for
+// only the players or only the spectators: they keep their place in the
+// queue but don't use up a `seq` the rest of the room would miss.
+//
+// With a spectator delay configured, deliveries to spectator connections are
+// handed to `SpectatorDelay` instead of being written, keeping their order.
+// In lobbies that anonymize their players, spectators get a copy masked with
+// the pseudonyms in force when the message was published.
+//
+// A lobby's task and counter are dropped when its last connection leaves, so
+// `seq` restarts for the next client, which starts from a snapshot anyway.
+// Set `ORDERED_ROOM_BROADCASTS=false` to fall back to unordered fan-out.
+
+use std::{collections::HashMap, sync::Arc};
+
+use axum::extract::ws::Message;
+use futures::{SinkExt, future::join_all};
+use serde_json::Value;
+use tokio::sync::{Mutex, mpsc};
+use uuid::Uuid;
+
+use crate::games::Audience;
+use crate::state::{AppState, ConnectionIndices, ConnectionInfo, Connections};
+use crate::ws::core::envelope::{Payload, encode_snapshot_for};
+use crate::ws::core::pseudonyms::Pseudonyms;
+use crate::ws::core::spectator_delay::SpectatorDelay;
+
+enum Outbound {
+    /// Event for the room's connections in `audience`, optionally skipping one user
+    Event {
+        event: Value,
+        audience: Audience,
+        except_user: Option<Uuid>,
+        pseudonyms: Option<Arc<Pseudonyms>>,
+    },
+    /// Snapshot for one connection
+    Snapshot {
+        connection_id: Uuid,
+        snapshot: Value,
+        pseudonyms: Option<Arc<Pseudonyms>>,
+    },
+}
+
+/// Per-lobby delivery queues for room events.
+#[derive(Clone, Default)]
+pub struct RoomSequencer {
+    lobbies: Arc<Mutex<HashMap<Uuid, mpsc::UnboundedSender<Outbound>>>>,
+}
+
+impl RoomSequencer {
+    /// Queue `event` for `audience` in the lobby's room (except `except_user`'s connections).
+    pub async fn publish(
+        &self,
+        state: &AppState,
+        lobby_id: Uuid,
+        event: Value,
+        audience: Audience,
+        except_user: Option<Uuid>,
+    ) {
+        let outbound = Outbound::Event {
+            event,
+            audience,
+            except_user,
+            pseudonyms: state.room_pseudonyms.get(lobby_id),
+        };
+        self.enqueue(state, lobby_id, outbound).await;
+    }
+
+    /// Queue a snapshot for one connection, in order with the room's events.
+    pub async fn publish_snapshot(
+        &self,
+        state: &AppState,
+        lobby_id: Uuid,
+        connection_id: Uuid,
+        snapshot: Value,
+    ) {
+        let outbound = Outbound::Snapshot {
+            connection_id,
+            snapshot,
+            pseudonyms: state.room_pseudonyms.get(lobby_id),
+        };
+        self.enqueue(state, lobby_id, outbound).await;
+    }
+
+    /// Drop the lobby's queue; its task exits once the queue drains.
+    pub async fn close(&self, lobby_id: Uuid) {
+        self.lobbies.lock().await.remove(&lobby_id);
+    }
+
+    async fn enqueue(&self, state: &AppState, lobby_id: Uuid, outbound: Outbound) {
+        // Nobody to deliver to, and no task worth starting
+        if state
+            .indices
+            .lock()
+            .await
+            .get_lobby_connections(&lobby_id)
+            .is_none()
+        {
+            return;
+        }
+
+        let mut lobbies = self.lobbies.lock().await;
+        let sender = lobbies.entry(lobby_id).or_insert_with(|| {
+            let (tx, rx) = mpsc::unbounded_channel();
+            tokio::spawn(run_lobby_queue(
+                lobby_id,
+                rx,
+                state.connections.clone(),
+                state.indices.clone(),
+                state.spectator_delay.clone(),
+            ));
+            tx
+        });
+        let _ = sender.send(outbound);
+    }
+}
+
+/// Deliver a lobby's queue in order; each message reaches every recipient
+/// before the next one is sent.
+async fn run_lobby_queue(
+    lobby_id: Uuid,
+    mut rx: mpsc::UnboundedReceiver<Outbound>,
+    connections: Connections,
+    indices: Arc<Mutex<ConnectionIndices>>,
+    spectator_delay: SpectatorDelay,
+) {
+    let mut seq: u64 = 0;
+
+    while let Some(outbound) = rx.recv().await {
+        let deliveries: Vec<(Arc<ConnectionInfo>, String)> = match outbound {
+            Outbound::Event {
+                event,
+                audience,
+                except_user,
+                pseudonyms,
+            } => {
+                let masked = pseudonyms.map(|table| table.masked(&event));
+                let (json, masked) = if audience == Audience::All {
+                    seq += 1;
+                    (stamp(event, seq), masked.map(|event| stamp(event, seq)))
+                } else {
+                    (event.to_string(), masked.map(|event| event.to_string()))
+                };
+                let (json, masked) = (Payload::new(json), masked.map(Payload::new));
+                room_connections(&connections, &indices, lobby_id, except_user)
+                    .await
+                    .into_iter()
+                    .filter(|conn| audience.includes(conn))
+                    .map(|conn| {
+                        let json = match &masked {
+                            Some(masked) if Audience::Spectators.includes(&conn) => {
+                                masked.text_for(conn.protocol)
+                            }
+                            _ => json.text_for(conn.protocol),
+                        };
+                        (conn, json)
+                    })
+                    .collect()
+            }
+            Outbound::Snapshot {
+                connection_id,
+                mut snapshot,
+                pseudonyms,
+            } => connections
+                .lock()
+                .await
+                .get(&connection_id)
+                .cloned()
+                .map(|conn| {
+                    if let Some(table) = pseudonyms
+                        && Audience::Spectators.includes(&conn)
+                    {
+                        table.mask(&mut snapshot);
+                    }
+                    let json =
+                        encode_snapshot_for(stamp(snapshot, seq), conn.protocol, conn.compression);
+                    vec![(conn, json)]
+                })
+                .unwrap_or_default(),
+        };
+
+        let (delayed, deliveries): (Vec<_>, Vec<_>) = deliveries
+            .into_iter()
+            .partition(|(conn, _)| spectator_delay.applies_to(conn));
+        for (conn, json) in delayed {
+            spectator_delay.hold(conn, json);
+        }
+
+        join_all(deliveries.into_iter().map(|(conn, json)| async move {
+            let mut s = conn.sender.lock().await;
+            let _ = s.send(Message::Text(json.into())).await;
+        }))
+        .await;
+    }
+}
+
+/// Connections currently in the lobby's room
+async fn room_connections(
+    connections: &Connections,
+    indices: &Arc<Mutex<ConnectionIndices>>,
+    lobby_id: Uuid,
+    except_user: Option<Uuid>,
+) -> Vec<Arc<ConnectionInfo>> {
+    let indices = indices.lock().await;
+    let Some(conn_ids) = indices.get_lobby_connections(&lobby_id) else {
+        return Vec::new();
+    };
+    let conns = connections.lock().await;
+
+    conn_ids
+        .iter()
+        .filter_map(|conn_id| conns.get(conn_id))
+        .filter(|conn| except_user.is_none() || conn.user_id != except_user)
+        .cloned()
+        .collect()
+}
+
+/// Serialize `message` with `seq` added to its top-level object
+fn stamp(mut message: Value, seq: u64) -> String {
+    if let Value::Object(fields) = &mut message {
+        fields.insert("seq".to_string(), seq.into());
+    }
+    message.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_stamp_adds_seq_to_objects_only() {
+        let stamped: Value =
+            serde_json::from_str(&stamp(json!({ "type": "gameStarted" }), 7)).unwrap();
+        assert_eq!(stamped, json!({ "type": "gameStarted", "seq": 7 }));
+
+        assert_eq!(stamp(json!([1, 2]), 7), "[1,2]");
+    }
+}
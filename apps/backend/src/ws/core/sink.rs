@@ -0,0 +1,85 @@
+// Outgoing half of a connection: a WebSocket, or a server-sent event stream
+//
+// Broadcasts write `Message`s to `ConnectionInfo::sender` without knowing
+// which transport is behind it. SSE subscribers (see `ws::sse`) hold a
+// bounded channel whose receiving end is their response body. Text arrives
+// already rendered for the connection's protocol (see `envelope::Payload`).
+//
+// A WebSocket applies backpressure to the sender. An SSE channel doesn't wait:
+// once a subscriber falls a full buffer behind, the channel is closed and its
+// stream ends, so a stalled client can't hold messages without limit.
+
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use axum::extract::ws::{Message, WebSocket};
+use futures::{Sink, channel::mpsc::Sender, stream::SplitSink};
+
+#[derive(Debug)]
+pub enum ConnectionSink {
+    WebSocket(SplitSink<WebSocket, Message>),
+    /// Text messages become events on an SSE response; others are dropped
+    EventStream(Sender<Message>),
+}
+
+impl Sink<Message> for ConnectionSink {
+    type Error = axum::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        match self.get_mut() {
+            Self::WebSocket(sink) => Pin::new(sink).poll_ready(cx),
+            Self::EventStream(_) => Poll::Ready(Ok(())),
+        }
+    }
+
+    fn start_send(self: Pin<&mut Self>, message: Message) -> Result<(), Self::Error> {
+        match self.get_mut() {
+            Self::WebSocket(sink) => Pin::new(sink).start_send(message),
+            Self::EventStream(tx) => tx.try_send(message).map_err(|e| {
+                tx.close_channel();
+                axum::Error::new(e.into_send_error())
+            }),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        match self.get_mut() {
+            Self::WebSocket(sink) => Pin::new(sink).poll_flush(cx),
+            Self::EventStream(_) => Poll::Ready(Ok(())),
+        }
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        match self.get_mut() {
+            Self::WebSocket(sink) => Pin::new(sink).poll_close(cx),
+            Self::EventStream(tx) => Pin::new(tx).poll_close(cx).map_err(axum::Error::new),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{SinkExt, StreamExt, channel::mpsc};
+
+    #[tokio::test]
+    async fn test_event_stream_closes_once_the_buffer_is_full() {
+        let (tx, mut rx) = mpsc::channel(1);
+        let mut sink = ConnectionSink::EventStream(tx);
+
+        // Capacity is the buffer plus one slot for the sender
+        for i in 0..2 {
+            sink.send(Message::Text(i.to_string().into()))
+                .await
+                .unwrap();
+        }
+        assert!(sink.send(Message::Text("2".into())).await.is_err());
+
+        // What was queued still arrives, then the stream ends
+        assert_eq!(rx.next().await, Some(Message::Text("0".into())));
+        assert_eq!(rx.next().await, Some(Message::Text("1".into())));
+        assert_eq!(rx.next().await, None);
+    }
+}
@@ -0,0 +1,230 @@
+// Spectator delay: room events reach spectators a fixed time after players
+//
+// In paid games a spectator watching the live feed could coach a player. With
+// SPECTATOR_DELAY_SECS set, the room sequencer hands each event bound for a
+// spectator connection to a queue instead of sending it, stamped with when it
+// becomes due. A flusher task ticks every SPECTATOR_FLUSH_INTERVAL and sends
+// whatever is due, oldest first. Players (and the creator) are never queued.
+//
+// Snapshots for spectators go through the same queue, so a spectator who
+// reconnects still sees the room as it was `delay` ago and their `seq`s
+// stay in order.
+//
+// With ORDERED_ROOM_BROADCASTS=false the unordered fan-out holds spectators'
+// copies in the same queue. The public words and replay reads leave out words
+// played within the last `delay`, so they can't be used to see ahead either.
+
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::extract::ws::Message;
+use futures::SinkExt;
+
+use crate::state::ConnectionInfo;
+
+/// How often due spectator events are sent
+pub const SPECTATOR_FLUSH_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Source of the current time, so delays can be tested without sleeping.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+}
+
+/// The real clock
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// A clock that only moves when told to.
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    now: Arc<Mutex<Instant>>,
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self {
+            now: Arc::new(Mutex::new(Instant::now())),
+        }
+    }
+}
+
+impl MockClock {
+    pub fn advance(&self, by: Duration) {
+        *self.now.lock().unwrap() += by;
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        *self.now.lock().unwrap()
+    }
+}
+
+/// Items released `delay` after they were pushed, in push order.
+pub struct DelayQueue<T> {
+    delay: Duration,
+    clock: Arc<dyn Clock>,
+    items: VecDeque<(Instant, T)>,
+}
+
+impl<T> DelayQueue<T> {
+    pub fn new(delay: Duration, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            delay,
+            clock,
+            items: VecDeque::new(),
+        }
+    }
+
+    /// Queue `item`; returns when it becomes due.
+    pub fn push(&mut self, item: T) -> Instant {
+        let due = self.clock.now() + self.delay;
+        self.items.push_back((due, item));
+        due
+    }
+
+    /// Remove and return every item that is due.
+    ///
+    /// The delay is the same for every item, so due times never decrease and
+    /// the first item not yet due ends the scan.
+    pub fn take_due(&mut self) -> Vec<T> {
+        let now = self.clock.now();
+        let mut due = Vec::new();
+        while self.items.front().is_some_and(|(at, _)| *at <= now) {
+            if let Some((_, item)) = self.items.pop_front() {
+                due.push(item);
+            }
+        }
+        due
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+/// A message and the connection it's for
+type Held = (Arc<ConnectionInfo>, String);
+
+/// Room messages held back from spectator connections.
+#[derive(Clone)]
+pub struct SpectatorDelay {
+    delay: Duration,
+    queue: Arc<Mutex<DelayQueue<Held>>>,
+}
+
+impl Default for SpectatorDelay {
+    /// No delay: spectators get events as they happen
+    fn default() -> Self {
+        Self::new(Duration::ZERO, Arc::new(SystemClock))
+    }
+}
+
+impl SpectatorDelay {
+    pub fn new(delay: Duration, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            delay,
+            queue: Arc::new(Mutex::new(DelayQueue::new(delay, clock))),
+        }
+    }
+
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    /// Whether messages for `conn` should be held back
+    pub fn applies_to(&self, conn: &ConnectionInfo) -> bool {
+        !self.delay.is_zero()
+            && conn
+                .room_context()
+                .is_some_and(|context| !context.is_participant())
+    }
+
+    /// Queue `json` for `conn` until the delay has passed.
+    pub fn hold(&self, conn: Arc<ConnectionInfo>, json: String) {
+        self.queue.lock().unwrap().push((conn, json));
+    }
+
+    /// Send every held message that is due.
+    pub async fn flush(&self) {
+        let due = self.queue.lock().unwrap().take_due();
+        for (conn, json) in due {
+            let mut s = conn.sender.lock().await;
+            let _ = s.send(Message::Text(json.into())).await;
+        }
+    }
+
+    /// Flush due messages every SPECTATOR_FLUSH_INTERVAL. Does nothing when
+    /// there is no delay.
+    pub fn spawn_flusher(&self) {
+        if self.delay.is_zero() {
+            return;
+        }
+        let delay = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(SPECTATOR_FLUSH_INTERVAL);
+            loop {
+                interval.tick().await;
+                delay.flush().await;
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spectator_copy_arrives_a_full_delay_after_the_players() {
+        let clock = MockClock::default();
+        let delay = Duration::from_secs(8);
+        let mut spectators = DelayQueue::new(delay, Arc::new(clock.clone()));
+
+        // Players are sent the event as it's published; spectators' copy waits
+        let published = clock.now();
+        let due = spectators.push("turn");
+        assert_eq!(due - published, delay);
+        assert!(spectators.take_due().is_empty());
+
+        clock.advance(delay - Duration::from_millis(1));
+        assert!(spectators.take_due().is_empty());
+
+        clock.advance(Duration::from_millis(1));
+        assert_eq!(spectators.take_due(), vec!["turn"]);
+        assert_eq!(clock.now() - published, delay);
+        assert!(spectators.is_empty());
+    }
+
+    #[test]
+    fn test_due_events_come_out_in_order() {
+        let clock = MockClock::default();
+        let mut spectators = DelayQueue::new(Duration::from_secs(5), Arc::new(clock.clone()));
+
+        spectators.push(1);
+        clock.advance(Duration::from_secs(1));
+        spectators.push(2);
+        clock.advance(Duration::from_secs(1));
+        spectators.push(3);
+
+        clock.advance(Duration::from_secs(4));
+        assert_eq!(spectators.take_due(), vec![1, 2]);
+        assert_eq!(spectators.len(), 1);
+
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(spectators.take_due(), vec![3]);
+    }
+}
@@ -0,0 +1,271 @@
+// Live leaderboard deltas
+//
+// Leaderboard clients get a `leaderboardSnapshot` of the current season's top
+// LIVE_LEADERBOARD_SIZE when they connect, then `leaderboardUpdated` carrying
+// only the entries whose rank or points moved, plus anyone pushed off the
+// board. Each game has its own board besides the overall one.
+//
+// Points changes are reported through `LeaderboardDeltas::changed`, which
+// marks the overall board and the game's board. Reports for a board within
+// LEADERBOARD_DELTA_WINDOW are merged into one reload and diff, so a finished
+// game scoring all its players sends one update rather than one per player.
+// A board nobody follows isn't reloaded and forgets what it last sent; the
+// next update for it carries every entry.
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use axum::extract::ws::Message;
+use futures::{SinkExt, future::join_all};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::db::{season::SeasonRepository, user_wars_points::UserWarsPointsRepository};
+use crate::errors::AppError;
+use crate::models::LeaderboardEntry;
+use crate::state::AppState;
+use crate::ws::broadcast::leaderboard_connections;
+use crate::ws::core::{envelope::Payload, message::BroadcastMessage};
+use crate::ws::leaderboard::LeaderboardServerMessage;
+
+/// How long points changes are collected before a board's delta is sent
+const LEADERBOARD_DELTA_WINDOW: Duration = Duration::from_millis(500);
+
+/// Entries on a live board
+pub const LIVE_LEADERBOARD_SIZE: i64 = 50;
+
+/// Entries as last sent, by user, and the season they were for
+type SentBoard = (i32, HashMap<Uuid, LeaderboardEntry>);
+
+#[derive(Default)]
+struct BoardEntry {
+    /// The board as followers last saw it
+    sent: Option<SentBoard>,
+    /// A flush task is running for this board
+    flushing: bool,
+    /// Changed since the running flush last loaded the board
+    dirty: bool,
+}
+
+/// Coalesces wars points changes into leaderboard deltas, per board.
+///
+/// Boards are keyed by game; None is the overall board.
+#[derive(Clone, Default)]
+pub struct LeaderboardDeltas {
+    boards: Arc<Mutex<HashMap<Option<Uuid>, BoardEntry>>>,
+}
+
+impl LeaderboardDeltas {
+    /// Wars points changed, earned in `game_id` if known; followers of the
+    /// overall board and that game's board get the delta shortly.
+    pub async fn changed(&self, state: &AppState, game_id: Option<Uuid>) {
+        let mut boards = self.boards.lock().await;
+        self.schedule(&mut boards, state, None);
+        if game_id.is_some() {
+            self.schedule(&mut boards, state, game_id);
+        }
+    }
+
+    fn schedule(
+        &self,
+        boards: &mut HashMap<Option<Uuid>, BoardEntry>,
+        state: &AppState,
+        board: Option<Uuid>,
+    ) {
+        let entry = boards.entry(board).or_default();
+        entry.dirty = true;
+        if !entry.flushing {
+            entry.flushing = true;
+            tokio::spawn(self.clone().flush(state.clone(), board));
+        }
+    }
+
+    /// Send the board's delta once the window has passed, repeating while
+    /// changes keep arriving during a flush.
+    async fn flush(self, state: AppState, board: Option<Uuid>) {
+        loop {
+            tokio::time::sleep(LEADERBOARD_DELTA_WINDOW).await;
+
+            match self.boards.lock().await.get_mut(&board) {
+                Some(entry) => entry.dirty = false,
+                None => return,
+            }
+
+            let followers = leaderboard_connections(&state, board).await;
+            if followers.is_empty() {
+                self.boards.lock().await.remove(&board);
+                return;
+            }
+
+            let loaded = load_leaderboard(&state, board).await;
+
+            let delta = {
+                let mut boards = self.boards.lock().await;
+                let Some(entry) = boards.get_mut(&board) else {
+                    return;
+                };
+                match loaded {
+                    Ok(Some((season_id, entries))) => {
+                        // A new season starts a new board
+                        let previous = entry
+                            .sent
+                            .take()
+                            .filter(|(sent_season, _)| *sent_season == season_id)
+                            .map(|(_, sent)| sent);
+                        let delta =
+                            leaderboard_delta(season_id, board, previous.as_ref(), &entries);
+                        entry.sent = Some((
+                            season_id,
+                            entries.into_iter().map(|e| (e.user_id, e)).collect(),
+                        ));
+                        delta
+                    }
+                    Ok(None) => {
+                        entry.sent = None;
+                        None
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to load leaderboard {:?}: {}", board, e);
+                        None
+                    }
+                }
+            };
+
+            // Only this task flushes the board, so its deltas stay in order
+            // without holding the boards lock while sending
+            if let Some(json) = delta.and_then(|delta| delta.to_json().ok().map(Payload::new)) {
+                let sends = followers.iter().map(|conn| {
+                    let json = json.text_for(conn.protocol);
+                    async move {
+                        let _ = conn
+                            .sender
+                            .lock()
+                            .await
+                            .send(Message::Text(json.into()))
+                            .await;
+                    }
+                });
+                join_all(sends).await;
+            }
+
+            let mut boards = self.boards.lock().await;
+            let Some(entry) = boards.get_mut(&board) else {
+                return;
+            };
+            if !entry.dirty {
+                entry.flushing = false;
+                return;
+            }
+        }
+    }
+}
+
+/// The current season's id and the top of its board, overall or for `game_id`.
+///
+/// None when no season is running.
+pub async fn load_leaderboard(
+    state: &AppState,
+    game_id: Option<Uuid>,
+) -> Result<Option<(i32, Vec<LeaderboardEntry>)>, AppError> {
+    let season = match SeasonRepository::new(state.postgres.clone())
+        .with_cache(state.redis.clone())
+        .current()
+        .await
+    {
+        Ok(season) => season,
+        Err(AppError::NotFound(_)) => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    let entries = UserWarsPointsRepository::new(state.postgres.clone())
+        .get_ranked_leaderboard(season.id(), game_id, LIVE_LEADERBOARD_SIZE)
+        .await?;
+    Ok(Some((season.id(), entries)))
+}
+
+/// The update taking followers from `previous` to `current`, if anything moved.
+///
+/// Without a `previous` every entry counts as changed.
+fn leaderboard_delta(
+    season_id: i32,
+    game_id: Option<Uuid>,
+    previous: Option<&HashMap<Uuid, LeaderboardEntry>>,
+    current: &[LeaderboardEntry],
+) -> Option<LeaderboardServerMessage> {
+    let changed: Vec<LeaderboardEntry> = current
+        .iter()
+        .filter(|entry| previous.and_then(|p| p.get(&entry.user_id)) != Some(*entry))
+        .cloned()
+        .collect();
+    let removed: Vec<Uuid> = previous
+        .into_iter()
+        .flat_map(|p| p.keys())
+        .filter(|user_id| !current.iter().any(|entry| entry.user_id == **user_id))
+        .copied()
+        .collect();
+
+    if changed.is_empty() && removed.is_empty() {
+        return None;
+    }
+    Some(LeaderboardServerMessage::LeaderboardUpdated {
+        season_id,
+        game_id,
+        changed,
+        removed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(user_id: Uuid, rank: i32, points: f64) -> LeaderboardEntry {
+        LeaderboardEntry {
+            user_id,
+            wallet_address: format!("wallet-{}", user_id),
+            rank,
+            points,
+        }
+    }
+
+    fn by_user(entries: &[LeaderboardEntry]) -> HashMap<Uuid, LeaderboardEntry> {
+        entries.iter().map(|e| (e.user_id, e.clone())).collect()
+    }
+
+    #[test]
+    fn test_delta_carries_only_moved_entries() {
+        let (a, b, c, d) = (
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+        );
+        let previous = by_user(&[entry(a, 1, 30.0), entry(b, 2, 20.0), entry(c, 3, 10.0)]);
+        // c jumps to the top, d enters, and b drops off the board
+        let current = vec![entry(c, 1, 50.0), entry(a, 2, 30.0), entry(d, 3, 25.0)];
+
+        let Some(LeaderboardServerMessage::LeaderboardUpdated {
+            changed, removed, ..
+        }) = leaderboard_delta(1, None, Some(&previous), &current)
+        else {
+            panic!("expected an update");
+        };
+        assert_eq!(changed, current);
+        assert_eq!(removed, vec![b]);
+
+        let unchanged = by_user(&current);
+        assert!(leaderboard_delta(1, None, Some(&unchanged), &current).is_none());
+    }
+
+    #[test]
+    fn test_delta_without_previous_sends_every_entry() {
+        let current = vec![entry(Uuid::new_v4(), 1, 5.0)];
+        let Some(LeaderboardServerMessage::LeaderboardUpdated {
+            changed, removed, ..
+        }) = leaderboard_delta(1, None, None, &current)
+        else {
+            panic!("expected an update");
+        };
+        assert_eq!(changed, current);
+        assert!(removed.is_empty());
+    }
+}
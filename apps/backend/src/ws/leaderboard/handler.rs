@@ -0,0 +1,88 @@
+// Leaderboard WebSocket handler - snapshot on connect, then deltas as points change
+use axum::{
+    extract::{
+        Query, State, WebSocketUpgrade,
+        ws::{Message, WebSocket},
+    },
+    response::IntoResponse,
+};
+use futures::stream::StreamExt;
+use serde::Deserialize;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    state::{AppState, ConnectionContext, ConnectionInfo},
+    ws::{
+        core::{ConnectionSink, Negotiated, WsProtocol, manager},
+        leaderboard::{LeaderboardServerMessage, deltas::load_leaderboard},
+    },
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeaderboardQueryParams {
+    /// Follow one game's leaderboard instead of the overall one
+    pub game_id: Option<Uuid>,
+}
+
+/// WebSocket handler for live leaderboard connections
+pub async fn leaderboard_handler(
+    ws: WebSocketUpgrade,
+    Query(params): Query<LeaderboardQueryParams>,
+    State(state): State<AppState>,
+    protocol: WsProtocol,
+) -> impl IntoResponse {
+    protocol.upgrade(ws, move |socket, negotiated| {
+        handle_socket(socket, params.game_id, state, negotiated)
+    })
+}
+
+async fn handle_socket(
+    socket: WebSocket,
+    game_id: Option<Uuid>,
+    state: AppState,
+    negotiated: Negotiated,
+) {
+    let (sender, mut receiver) = socket.split();
+    let connection_id = Uuid::new_v4();
+
+    // Registered before the snapshot is read, so no change falls between the two
+    let conn = Arc::new(ConnectionInfo {
+        connection_id,
+        user_id: None, // The leaderboard is public
+        context: ConnectionContext::Leaderboard(game_id),
+        protocol: negotiated.version,
+        compression: negotiated.compression,
+        sender: Arc::new(tokio::sync::Mutex::new(ConnectionSink::WebSocket(sender))),
+        room_context: Default::default(),
+    });
+    manager::register_connection(&state, connection_id, Arc::clone(&conn)).await;
+
+    let snapshot = match load_leaderboard(&state, game_id).await {
+        Ok(Some((season_id, entries))) => LeaderboardServerMessage::LeaderboardSnapshot {
+            season_id: Some(season_id),
+            game_id,
+            entries,
+        },
+        Ok(None) => LeaderboardServerMessage::LeaderboardSnapshot {
+            season_id: None,
+            game_id,
+            entries: Vec::new(),
+        },
+        Err(e) => LeaderboardServerMessage::Error {
+            code: "FETCH_FAILED".to_string(),
+            message: e.to_string(),
+        },
+    };
+    let _ = manager::send_snapshot_to_connection(&conn, &snapshot).await;
+
+    // Nothing to handle from the client; wait for it to go away
+    while let Some(msg) = receiver.next().await {
+        if matches!(msg, Ok(Message::Close(_)) | Err(_)) {
+            break;
+        }
+    }
+
+    manager::unregister_connection(&state, &connection_id).await;
+}
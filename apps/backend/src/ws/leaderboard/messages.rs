@@ -0,0 +1,32 @@
+// Leaderboard message types (server -> client)
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::LeaderboardEntry;
+
+/// Messages sent by the live leaderboard server to connected clients
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum LeaderboardServerMessage {
+    /// The leaderboard as it stands; `season_id` is None between seasons
+    #[serde(rename_all = "camelCase")]
+    LeaderboardSnapshot {
+        season_id: Option<i32>,
+        game_id: Option<Uuid>,
+        entries: Vec<LeaderboardEntry>,
+    },
+
+    /// Entries whose rank or points changed, and users who dropped off the board
+    #[serde(rename_all = "camelCase")]
+    LeaderboardUpdated {
+        season_id: i32,
+        game_id: Option<Uuid>,
+        changed: Vec<LeaderboardEntry>,
+        removed: Vec<Uuid>,
+    },
+
+    Error {
+        code: String,
+        message: String,
+    },
+}
@@ -0,0 +1,8 @@
+// Leaderboard WebSocket module - live season leaderboard, overall or per game
+pub mod deltas;
+pub mod handler;
+pub mod messages;
+
+pub use deltas::LeaderboardDeltas;
+pub use handler::leaderboard_handler;
+pub use messages::LeaderboardServerMessage;
@@ -0,0 +1,323 @@
+// Lobby-list deltas
+//
+// Lobby-list clients get a `lobbyList` snapshot when they connect and patch it
+// with deltas afterwards:
+//
+// - lobbyCreated            full LobbyInfo for a new lobby
+// - lobbyPlayerCountChanged the lobby's new participant count
+// - lobbyUpdated            only the lobby fields that changed, e.g. { "status": "finished" }
+// - lobbyRemoved            the lobby is gone
+//
+// Changes are reported through `LobbyListDeltas`. Reports for the same lobby
+// within LOBBY_DELTA_WINDOW are merged: one flush reloads the lobby from
+// Postgres and Redis, diffs it against what subscribers last saw and sends
+// only what changed. A lobby has at most one flush running, so its deltas
+// reach subscribers in the order the changes happened. Finished lobbies stop
+// being tracked once their final delta is out.
+//
+// Each subscriber only hears about lobbies its `LobbyFilter` matches. A lobby
+// that starts matching is sent whole as `lobbyCreated`; one that stops
+// matching (say it left "waiting") is sent as `lobbyRemoved`.
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use axum::extract::ws::Message;
+use futures::{SinkExt, future::join_all};
+use serde_json::{Map, Value};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::db::{
+    game::GameRepository, lobby::LobbyRepository, lobby_state::LobbyStateRepository,
+    user::UserRepository,
+};
+use crate::models::{LobbyExtended, LobbyFilter, LobbyInfo, LobbyStatus};
+use crate::state::{AppState, ConnectionContext};
+use crate::ws::broadcast::lobby_list_connections;
+use crate::ws::core::{envelope::Payload, message::BroadcastMessage};
+use crate::ws::lobby::LobbyServerMessage;
+
+/// How long changes to one lobby are collected before a delta is sent
+const LOBBY_DELTA_WINDOW: Duration = Duration::from_millis(100);
+
+/// Lobby fields that change too often to be worth a delta
+const UNTRACKED_FIELDS: &[&str] = &["updatedAt", "creatorLastPing"];
+
+#[derive(Default)]
+struct LobbyEntry {
+    /// The lobby as subscribers last saw it
+    sent: Option<LobbyExtended>,
+    /// Created since startup, so subscribers haven't seen it at all
+    created: bool,
+    /// A flush task is running for this lobby
+    flushing: bool,
+    /// Changed since the running flush last loaded the lobby
+    dirty: bool,
+}
+
+/// Coalesces lobby changes into lobby-list deltas.
+#[derive(Clone, Default)]
+pub struct LobbyListDeltas {
+    lobbies: Arc<Mutex<HashMap<Uuid, LobbyEntry>>>,
+}
+
+impl LobbyListDeltas {
+    /// A lobby was created; subscribers get `lobbyCreated` once it settles.
+    pub async fn created(&self, state: &AppState, lobby_id: Uuid) {
+        let mut lobbies = self.lobbies.lock().await;
+        lobbies.entry(lobby_id).or_default().created = true;
+        self.schedule(&mut lobbies, state, lobby_id);
+    }
+
+    /// A lobby changed in Postgres or Redis; subscribers get the delta shortly.
+    pub async fn changed(&self, state: &AppState, lobby_id: Uuid) {
+        let mut lobbies = self.lobbies.lock().await;
+        self.schedule(&mut lobbies, state, lobby_id);
+    }
+
+    /// A lobby was deleted; subscribers drop it right away.
+    pub async fn removed(&self, state: &AppState, lobby_id: Uuid) {
+        // Held while sending so a running flush can't send after this
+        let mut lobbies = self.lobbies.lock().await;
+        lobbies.remove(&lobby_id);
+        let removed = LobbyServerMessage::LobbyRemoved { lobby_id };
+        deliver(state, |_| vec![&removed]).await;
+    }
+
+    fn schedule(&self, lobbies: &mut HashMap<Uuid, LobbyEntry>, state: &AppState, lobby_id: Uuid) {
+        let entry = lobbies.entry(lobby_id).or_default();
+        entry.dirty = true;
+        if !entry.flushing {
+            entry.flushing = true;
+            tokio::spawn(self.clone().flush(state.clone(), lobby_id));
+        }
+    }
+
+    /// Send the lobby's deltas once the window has passed, repeating while
+    /// changes keep arriving during a flush.
+    async fn flush(self, state: AppState, lobby_id: Uuid) {
+        loop {
+            tokio::time::sleep(LOBBY_DELTA_WINDOW).await;
+
+            match self.lobbies.lock().await.get_mut(&lobby_id) {
+                Some(entry) => entry.dirty = false,
+                None => return,
+            }
+
+            let info = load_lobby_info(&state, lobby_id).await;
+
+            let mut lobbies = self.lobbies.lock().await;
+            // Removed while loading
+            let Some(entry) = lobbies.get_mut(&lobby_id) else {
+                return;
+            };
+
+            let mut finished = false;
+            if let Some(info) = info {
+                finished = info.lobby.status == LobbyStatus::Finished;
+                let previous = entry.sent.replace(info.lobby.clone());
+                send_change(&state, previous.as_ref(), entry.created, info).await;
+            }
+
+            if !entry.dirty {
+                if finished {
+                    lobbies.remove(&lobby_id);
+                } else {
+                    entry.flushing = false;
+                }
+                return;
+            }
+        }
+    }
+}
+
+/// Load a lobby with its game, creator and runtime state
+async fn load_lobby_info(state: &AppState, lobby_id: Uuid) -> Option<LobbyInfo> {
+    let lobby = LobbyRepository::new(state.postgres.clone())
+        .find_by_id(lobby_id)
+        .await
+        .ok()?;
+
+    let game_repo = GameRepository::new(state.postgres.clone());
+    let user_repo = UserRepository::new(state.postgres.clone());
+    let lobby_state_repo = LobbyStateRepository::new(state.redis.clone());
+
+    let (game, creator, lobby_state) = tokio::join!(
+        game_repo.find_by_id(lobby.game_id),
+        user_repo.find_by_id(lobby.creator_id),
+        lobby_state_repo.get_state(lobby_id),
+    );
+
+    Some(LobbyInfo {
+        lobby: LobbyExtended::from_parts(lobby, lobby_state.ok()?),
+        game: game.ok()?,
+        creator: creator.ok()?,
+    })
+}
+
+/// Send each subscriber what the change from `previous` to `info` means under its filter
+///
+/// With no `previous`, a lobby created since startup is new to everyone; an
+/// older one is assumed to be in the lists of subscribers it matches.
+async fn send_change(
+    state: &AppState,
+    previous: Option<&LobbyExtended>,
+    created: bool,
+    info: LobbyInfo,
+) {
+    let lobby_id = info.lobby.id;
+    let as_map = |lobby: &LobbyExtended| match serde_json::to_value(lobby) {
+        Ok(Value::Object(fields)) => fields,
+        _ => Map::new(),
+    };
+    let deltas = lobby_deltas(
+        lobby_id,
+        &previous.map(as_map).unwrap_or_default(),
+        &as_map(&info.lobby),
+    );
+    let current = info.lobby.clone();
+    let appeared = LobbyServerMessage::LobbyCreated {
+        lobby_info: Box::new(info),
+    };
+    let removed = LobbyServerMessage::LobbyRemoved { lobby_id };
+
+    deliver(state, |filter| {
+        let was = previous.map_or(!created, |lobby| filter.matches(lobby));
+        match (was, filter.matches(&current)) {
+            (true, true) => deltas.iter().collect(),
+            (false, true) => vec![&appeared],
+            (true, false) => vec![&removed],
+            (false, false) => Vec::new(),
+        }
+    })
+    .await;
+}
+
+/// Send every lobby-list subscriber the messages `select` picks for its filter,
+/// in order, waiting until all are written
+async fn deliver<'a>(
+    state: &AppState,
+    select: impl Fn(&LobbyFilter) -> Vec<&'a LobbyServerMessage>,
+) {
+    // Each message is serialized once, however many subscribers it goes to
+    let mut payloads: Vec<(&'a LobbyServerMessage, Payload)> = Vec::new();
+    let conns: Vec<_> = lobby_list_connections(state)
+        .await
+        .into_iter()
+        .filter_map(|conn| {
+            let ConnectionContext::Lobby(filter) = &conn.context else {
+                return None;
+            };
+            let json: Vec<String> = select(filter)
+                .into_iter()
+                .filter_map(|msg| {
+                    let cached = payloads.iter().position(|(m, _)| std::ptr::eq(*m, msg));
+                    let index = match cached {
+                        Some(index) => index,
+                        None => {
+                            payloads.push((msg, Payload::new(msg.to_json().ok()?)));
+                            payloads.len() - 1
+                        }
+                    };
+                    Some(payloads[index].1.text_for(conn.protocol))
+                })
+                .collect();
+            (!json.is_empty()).then_some((conn, json))
+        })
+        .collect();
+    let sends = conns.into_iter().map(|(conn, json)| async move {
+        let mut sender = conn.sender.lock().await;
+        for text in json {
+            let _ = sender.send(Message::Text(text.into())).await;
+        }
+    });
+    join_all(sends).await;
+}
+
+/// Deltas that take subscribers from `previous` to `current`
+fn lobby_deltas(
+    lobby_id: Uuid,
+    previous: &Map<String, Value>,
+    current: &Map<String, Value>,
+) -> Vec<LobbyServerMessage> {
+    let mut messages = Vec::new();
+
+    let mut fields: Map<String, Value> = current
+        .iter()
+        .filter(|(key, value)| {
+            !UNTRACKED_FIELDS.contains(&key.as_str()) && previous.get(*key) != Some(*value)
+        })
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+
+    if let Some(count) = fields.remove("participantCount") {
+        messages.push(LobbyServerMessage::LobbyPlayerCountChanged {
+            lobby_id,
+            participant_count: count.as_u64().unwrap_or_default() as usize,
+        });
+    }
+    if !fields.is_empty() {
+        messages.push(LobbyServerMessage::LobbyUpdated { lobby_id, fields });
+    }
+
+    messages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn lobby(fields: Value) -> Map<String, Value> {
+        let Value::Object(map) = fields else {
+            unreachable!()
+        };
+        map
+    }
+
+    #[test]
+    fn test_deltas_carry_only_changed_fields() {
+        let lobby_id = Uuid::new_v4();
+        let previous = lobby(json!({
+            "name": "Lobby",
+            "status": "waiting",
+            "participantCount": 1,
+            "updatedAt": "2026-10-15T10:00:00",
+        }));
+        let current = lobby(json!({
+            "name": "Lobby",
+            "status": "finished",
+            "participantCount": 2,
+            "updatedAt": "2026-10-15T10:05:00",
+        }));
+
+        let deltas: Vec<Value> = lobby_deltas(lobby_id, &previous, &current)
+            .iter()
+            .map(|m| serde_json::to_value(m).unwrap())
+            .collect();
+
+        assert_eq!(
+            deltas,
+            vec![
+                json!({
+                    "type": "lobbyPlayerCountChanged",
+                    "lobbyId": lobby_id,
+                    "participantCount": 2,
+                }),
+                json!({
+                    "type": "lobbyUpdated",
+                    "lobbyId": lobby_id,
+                    "fields": { "status": "finished" },
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_no_deltas_for_untracked_changes() {
+        let previous = lobby(json!({ "status": "waiting", "creatorLastPing": 1 }));
+        let current = lobby(json!({ "status": "waiting", "creatorLastPing": 2 }));
+
+        assert!(lobby_deltas(Uuid::new_v4(), &previous, &current).is_empty());
+    }
+}
@@ -7,6 +7,7 @@ use axum::{
     response::IntoResponse,
 };
 use futures::stream::StreamExt;
+use rust_decimal::Decimal;
 use serde::Deserialize;
 use std::{
     collections::{HashMap, HashSet},
@@ -15,14 +16,15 @@ use std::{
 use uuid::Uuid;
 
 use crate::{
+    announcements,
     db::{
         game::GameRepository, lobby::LobbyRepository, lobby_state::LobbyStateRepository,
         user::UserRepository,
     },
-    models::{LobbyExtended, LobbyInfo, LobbyState, LobbyStatus},
+    models::{LobbyExtended, LobbyFilter, LobbyInfo, LobbyState},
     state::{AppState, ConnectionContext, ConnectionInfo},
     ws::{
-        core::manager,
+        core::{ConnectionSink, Negotiated, WsProtocol, manager},
         lobby::{LobbyClientMessage, LobbyError, LobbyServerMessage},
     },
 };
@@ -31,152 +33,185 @@ use crate::{
 pub struct LobbyQueryParams {
     #[serde(default)]
     pub status: Option<String>, // Comma-separated: "waiting,starting"
+    pub game_id: Option<Uuid>,
+    pub min_stake: Option<Decimal>,
+    pub max_stake: Option<Decimal>,
     pub limit: Option<usize>,
 }
 
+impl LobbyQueryParams {
+    /// Subscription filter from the query string; unknown statuses are ignored
+    fn filter(&self) -> LobbyFilter {
+        LobbyFilter {
+            statuses: self
+                .status
+                .as_deref()
+                .map(|s| {
+                    s.split(',')
+                        .filter_map(|part| part.trim().to_lowercase().parse().ok())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            game_id: self.game_id,
+            min_stake: self.min_stake,
+            max_stake: self.max_stake,
+        }
+    }
+}
+
 /// WebSocket handler for lobby list connections
 pub async fn lobby_handler(
     ws: WebSocketUpgrade,
     Query(params): Query<LobbyQueryParams>,
     State(state): State<AppState>,
+    protocol: WsProtocol,
 ) -> impl IntoResponse {
-    ws.on_upgrade(move |socket| handle_socket(socket, params, state))
+    protocol.upgrade(ws, move |socket, negotiated| {
+        handle_socket(socket, params, state, negotiated)
+    })
 }
 
-async fn handle_socket(socket: WebSocket, params: LobbyQueryParams, state: AppState) {
+async fn handle_socket(
+    socket: WebSocket,
+    params: LobbyQueryParams,
+    state: AppState,
+    negotiated: Negotiated,
+) {
     let (sender, mut receiver) = socket.split();
-    let connection_id = Uuid::new_v4();
+    let mut conn = open_lobby_connection(
+        &state,
+        &params,
+        ConnectionSink::WebSocket(sender),
+        &negotiated,
+    )
+    .await;
+    let connection_id = conn.connection_id;
+
+    let lobby_repo = LobbyRepository::new(state.postgres.clone());
+    let lobby_state_repo = LobbyStateRepository::new(state.redis.clone());
+
+    // Message loop
+    while let Some(msg) = receiver.next().await {
+        match msg {
+            Ok(Message::Text(text)) => {
+                if let Ok(lobby_msg) = serde_json::from_str::<LobbyClientMessage>(&text) {
+                    handle_message(lobby_msg, &mut conn, &state, &lobby_repo, &lobby_state_repo)
+                        .await;
+                }
+            }
+            Ok(Message::Close(_)) => break,
+            _ => {}
+        }
+    }
+
+    // Cleanup
+    manager::unregister_connection(&state, &connection_id).await;
+}
 
-    // Parse status filter from query params
-    let status_strings = parse_status_filter(&params.status);
+/// Register a lobby list connection and send it the first page and any
+/// active announcements. Shared with the SSE stream.
+pub(crate) async fn open_lobby_connection(
+    state: &AppState,
+    params: &LobbyQueryParams,
+    sender: ConnectionSink,
+    negotiated: &Negotiated,
+) -> Arc<ConnectionInfo> {
+    let connection_id = Uuid::new_v4();
 
-    // Register connection with status-based context
+    // Register connection with its subscription filter as context
     let conn = Arc::new(ConnectionInfo {
         connection_id,
         user_id: None, // Lobby browsing doesn't require authentication
-        context: ConnectionContext::Lobby(status_strings.clone()),
+        context: ConnectionContext::Lobby(params.filter()),
+        protocol: negotiated.version,
+        compression: negotiated.compression,
         sender: Arc::new(tokio::sync::Mutex::new(sender)),
+        room_context: Default::default(),
     });
 
-    manager::register_connection(&state, connection_id, Arc::clone(&conn)).await;
+    manager::register_connection(state, connection_id, Arc::clone(&conn)).await;
 
     // Send initial lobby list
     let lobby_repo = LobbyRepository::new(state.postgres.clone());
     let lobby_state_repo = LobbyStateRepository::new(state.redis.clone());
-    let status_filter = parse_status_enum(&status_strings);
-    let status_filter_opt = if status_filter.is_empty() {
-        None
-    } else {
-        Some(status_filter)
-    };
     send_lobby_list(
         &conn,
         &lobby_repo,
         &lobby_state_repo,
-        &status_filter_opt,
         0,
         params.limit.unwrap_or(6),
     )
     .await;
-
-    // Message loop
-    while let Some(msg) = receiver.next().await {
-        match msg {
-            Ok(Message::Text(text)) => {
-                if let Ok(lobby_msg) = serde_json::from_str::<LobbyClientMessage>(&text) {
-                    handle_message(
-                        lobby_msg,
-                        &conn,
-                        &state,
-                        &lobby_repo,
-                        &lobby_state_repo,
-                        connection_id,
-                    )
-                    .await;
-                }
-            }
-            Ok(Message::Close(_)) => break,
-            _ => {}
-        }
+    for announcement in announcements::active_for_new_connection(state).await {
+        let _ = manager::send_to_connection(&conn, &LobbyServerMessage::Announcement(announcement))
+            .await;
     }
 
-    // Cleanup
-    manager::unregister_connection(&state, &connection_id).await;
+    conn
 }
 
 async fn handle_message(
     msg: LobbyClientMessage,
-    conn: &Arc<ConnectionInfo>,
+    conn: &mut Arc<ConnectionInfo>,
     state: &AppState,
     lobby_repo: &LobbyRepository,
     lobby_state_repo: &LobbyStateRepository,
-    connection_id: Uuid,
 ) {
     match msg {
         LobbyClientMessage::Subscribe { status, limit } => {
-            // User wants to change their status filter
-            if let Some(new_statuses) = status {
-                // Parse new status filter
-                let status_strings: Vec<String> =
-                    new_statuses.iter().map(|s| status_to_string(s)).collect();
-
-                // Unregister old connection
-                manager::unregister_connection(state, &connection_id).await;
-
-                // Create new connection with updated context
-                let new_conn = Arc::new(ConnectionInfo {
-                    connection_id,
-                    user_id: conn.user_id,
-                    context: ConnectionContext::Lobby(Some(status_strings.clone())),
-                    sender: conn.sender.clone(),
-                });
-
-                // Register with new context
-                manager::register_connection(state, connection_id, new_conn).await;
-
-                // Send updated lobby list
-                let status_filter = Some(new_statuses);
-                send_lobby_list(conn, lobby_repo, lobby_state_repo, &status_filter, 0, limit).await;
-            } else {
-                // No filter - send all lobbies
-                send_lobby_list(conn, lobby_repo, lobby_state_repo, &None, 0, limit).await;
-            }
+            // Change the status filter, keeping the rest of the subscription
+            let filter = LobbyFilter {
+                statuses: status.unwrap_or_default(),
+                ..current_filter(conn)
+            };
+            resubscribe(conn, state, filter).await;
+            send_lobby_list(conn, lobby_repo, lobby_state_repo, 0, limit).await;
+        }
+        LobbyClientMessage::UpdateFilter { filter, limit } => {
+            resubscribe(conn, state, filter).await;
+            send_lobby_list(conn, lobby_repo, lobby_state_repo, 0, limit).await;
         }
         LobbyClientMessage::LoadMore { offset, limit } => {
-            // Get current filter from connection context
-            let status_filter_vec = match &conn.context {
-                ConnectionContext::Lobby(opt_strings) => parse_status_enum(opt_strings),
-                _ => vec![],
-            };
-            let status_filter_opt = if status_filter_vec.is_empty() {
-                None
-            } else {
-                Some(status_filter_vec)
-            };
-            send_lobby_list(
-                conn,
-                lobby_repo,
-                lobby_state_repo,
-                &status_filter_opt,
-                offset,
-                limit,
-            )
-            .await;
+            send_lobby_list(conn, lobby_repo, lobby_state_repo, offset, limit).await;
         }
     }
 }
 
+fn current_filter(conn: &ConnectionInfo) -> LobbyFilter {
+    match &conn.context {
+        ConnectionContext::Lobby(filter) => filter.clone(),
+        _ => LobbyFilter::default(),
+    }
+}
+
+/// Re-register the connection under a new subscription filter
+async fn resubscribe(conn: &mut Arc<ConnectionInfo>, state: &AppState, filter: LobbyFilter) {
+    manager::unregister_connection(state, &conn.connection_id).await;
+
+    *conn = Arc::new(ConnectionInfo {
+        connection_id: conn.connection_id,
+        user_id: conn.user_id,
+        context: ConnectionContext::Lobby(filter),
+        protocol: conn.protocol,
+        compression: conn.compression,
+        sender: conn.sender.clone(),
+        room_context: Default::default(),
+    });
+
+    manager::register_connection(state, conn.connection_id, Arc::clone(conn)).await;
+}
+
 async fn send_lobby_list(
     conn: &Arc<ConnectionInfo>,
     lobby_repo: &LobbyRepository,
     lobby_state_repo: &LobbyStateRepository,
-    status_filter: &Option<Vec<LobbyStatus>>,
     offset: usize,
     limit: usize,
 ) {
-    match fetch_lobbies(lobby_repo, lobby_state_repo, status_filter, offset, limit).await {
+    let filter = current_filter(conn);
+    match fetch_lobbies(lobby_repo, lobby_state_repo, &filter, offset, limit).await {
         Ok((lobby_info, total)) => {
-            let _ = manager::send_to_connection(
+            let _ = manager::send_snapshot_to_connection(
                 conn,
                 &LobbyServerMessage::LobbyList { lobby_info, total },
             )
@@ -192,22 +227,15 @@ async fn send_lobby_list(
 async fn fetch_lobbies(
     lobby_repo: &LobbyRepository,
     lobby_state_repo: &LobbyStateRepository,
-    status_filter: &Option<Vec<LobbyStatus>>,
+    filter: &LobbyFilter,
     offset: usize,
     limit: usize,
 ) -> Result<(Vec<LobbyInfo>, usize), LobbyError> {
     // Fetch lobbies with total count using optimized query
-    let (lobbies, total) = if let Some(statuses) = status_filter {
-        lobby_repo
-            .find_by_statuses(statuses, offset, limit)
-            .await
-            .map_err(|e| LobbyError::FetchFailed(e.to_string()))?
-    } else {
-        lobby_repo
-            .find_all(offset, limit)
-            .await
-            .map_err(|e| LobbyError::FetchFailed(e.to_string()))?
-    };
+    let (lobbies, total) = lobby_repo
+        .find_by_filter(filter, offset, limit)
+        .await
+        .map_err(|e| LobbyError::FetchFailed(e.to_string()))?;
 
     tracing::debug!(
         "Fetched {} lobbies with total count: {}",
@@ -276,44 +304,3 @@ async fn fetch_lobbies(
     tracing::debug!("Constructed {} lobby info objects", lobby_info_list.len());
     Ok((lobby_info_list, total as usize))
 }
-
-fn parse_status_filter(param: &Option<String>) -> Option<Vec<String>> {
-    param.as_ref().map(|s| {
-        s.split(',')
-            .map(|part| part.trim().to_lowercase())
-            .filter(|part| {
-                matches!(
-                    part.as_str(),
-                    "waiting" | "starting" | "in_progress" | "inprogress" | "finished"
-                )
-            })
-            .collect()
-    })
-}
-
-fn parse_status_enum(strings: &Option<Vec<String>>) -> Vec<LobbyStatus> {
-    strings
-        .as_ref()
-        .map(|strings| {
-            strings
-                .iter()
-                .filter_map(|s| match s.as_str() {
-                    "waiting" => Some(LobbyStatus::Waiting),
-                    "starting" => Some(LobbyStatus::Starting),
-                    "in_progress" | "inprogress" => Some(LobbyStatus::InProgress),
-                    "finished" => Some(LobbyStatus::Finished),
-                    _ => None,
-                })
-                .collect()
-        })
-        .unwrap_or_default()
-}
-
-fn status_to_string(status: &LobbyStatus) -> String {
-    match status {
-        LobbyStatus::Waiting => "waiting".to_string(),
-        LobbyStatus::Starting => "starting".to_string(),
-        LobbyStatus::InProgress => "in_progress".to_string(),
-        LobbyStatus::Finished => "finished".to_string(),
-    }
-}
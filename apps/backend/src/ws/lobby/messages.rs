@@ -1,6 +1,8 @@
 // Lobby list message types (client -> server, server -> client)
-use crate::models::{LobbyInfo, LobbyStatus};
+use crate::announcements::Announcement;
+use crate::models::{LobbyFilter, LobbyInfo, LobbyStatus};
 use crate::ws::lobby::error::LobbyError;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
 /// Messages sent from clients to the lobby list websocket
@@ -14,6 +16,13 @@ pub enum LobbyClientMessage {
         #[serde(default = "default_limit")]
         limit: usize,
     },
+    /// Replace the whole subscription filter; the list is resent under it
+    UpdateFilter {
+        #[serde(flatten)]
+        filter: LobbyFilter,
+        #[serde(default = "default_limit")]
+        limit: usize,
+    },
     /// Request next page of lobbies
     LoadMore { offset: usize, limit: usize },
 }
@@ -26,6 +35,9 @@ fn default_limit() -> usize {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", tag = "type")]
 pub enum LobbyServerMessage {
+    /// Platform-wide notice from the admins
+    Announcement(Announcement),
+
     /// Initial list of lobbies
     #[serde(rename_all = "camelCase")]
     LobbyList {
@@ -36,20 +48,51 @@ pub enum LobbyServerMessage {
     /// New lobby created
     #[serde(rename_all = "camelCase")]
     LobbyCreated {
-        lobby_info: LobbyInfo,
+        lobby_info: Box<LobbyInfo>,
     },
 
-    /// Lobby status changed
+    /// Lobby fields that changed, keyed as in `LobbyExtended`
+    #[serde(rename_all = "camelCase")]
     LobbyUpdated {
-        lobby: LobbyInfo,
+        lobby_id: uuid::Uuid,
+        fields: serde_json::Map<String, serde_json::Value>,
     },
 
-    /// Lobby deleted/finished
+    /// Players joined or left the lobby
+    #[serde(rename_all = "camelCase")]
+    LobbyPlayerCountChanged {
+        lobby_id: uuid::Uuid,
+        participant_count: usize,
+    },
+
+    /// Lobby deleted
     #[serde(rename_all = "camelCase")]
     LobbyRemoved {
         lobby_id: uuid::Uuid,
     },
 
+    /// Quick-play found a match; the player should join the lobby room
+    #[serde(rename_all = "camelCase")]
+    MatchFound {
+        lobby_id: uuid::Uuid,
+        lobby_path: String,
+        game_id: uuid::Uuid,
+    },
+
+    /// Lobby was cancelled before starting; `refund` is the deposit owed back, if any
+    #[serde(rename_all = "camelCase")]
+    LobbyCancelled {
+        lobby_id: uuid::Uuid,
+        #[serde(with = "rust_decimal::serde::float_option")]
+        refund: Option<Decimal>,
+    },
+
+    /// Player waited too long in the quick-play queue and was removed
+    #[serde(rename_all = "camelCase")]
+    MatchmakingTimedOut {
+        game_id: uuid::Uuid,
+    },
+
     Error {
         code: String,
         message: String,
@@ -1,8 +1,10 @@
 // Lobby WebSocket module - handles lobby list browsing
+pub mod deltas;
 pub mod error;
 pub mod handler;
 pub mod messages;
 
+pub use deltas::LobbyListDeltas;
 pub use error::LobbyError;
 pub use handler::lobby_handler;
 pub use messages::{LobbyClientMessage, LobbyServerMessage};
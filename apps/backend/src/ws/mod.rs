@@ -2,9 +2,11 @@
 // WebSocket module - organized by feature
 pub mod broadcast;
 pub mod core;
+pub mod leaderboard;
 pub mod lobby;
 pub mod room;
 pub mod routes;
+pub mod sse;
 
 // Re-export commonly used items
 pub use broadcast::*;
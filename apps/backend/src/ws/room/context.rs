@@ -0,0 +1,168 @@
+// Room context: what a room connection's user may do, resolved once
+//
+// The context is read from the user's player state when the connection opens
+// and cached on the connection, so handlers authorize actions without going
+// back to Redis. Anything that changes a user's role in a lobby (joining,
+// leaving, being kicked) calls `refresh_room_context`, which re-reads it for
+// every connection the user has open in that room.
+
+use uuid::Uuid;
+
+use crate::db::player_state::PlayerStateRepository;
+use crate::models::PlayerState;
+use crate::state::AppState;
+use crate::ws::room::RoomError;
+
+/// A user's standing in a lobby room.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoomRole {
+    Creator,
+    Player,
+    /// Watching only: not joined, or not signed in
+    Spectator,
+}
+
+/// Capabilities of one room connection.
+///
+/// Not to be confused with `state::ConnectionContext`, which says which
+/// socket (lobby list or room) a connection belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoomContext {
+    pub lobby_id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub role: RoomRole,
+    pub is_creator: bool,
+}
+
+impl RoomContext {
+    /// Context for `user_id` given their player state in the lobby, if any.
+    pub fn new(lobby_id: Uuid, user_id: Option<Uuid>, player: Option<&PlayerState>) -> Self {
+        let role = match (user_id, player) {
+            (Some(_), Some(p)) if p.is_creator => RoomRole::Creator,
+            (Some(_), Some(_)) => RoomRole::Player,
+            _ => RoomRole::Spectator,
+        };
+        Self {
+            lobby_id,
+            user_id,
+            role,
+            is_creator: role == RoomRole::Creator,
+        }
+    }
+
+    /// Read the user's current role from their player state.
+    pub async fn load(
+        player_repo: &PlayerStateRepository,
+        lobby_id: Uuid,
+        user_id: Option<Uuid>,
+    ) -> Self {
+        let player = match user_id {
+            Some(user_id) => player_repo.get_state(lobby_id, user_id).await.ok(),
+            None => None,
+        };
+        Self::new(lobby_id, user_id, player.as_ref())
+    }
+
+    /// Whether the user has joined the lobby (the creator has)
+    pub fn is_participant(&self) -> bool {
+        self.role != RoomRole::Spectator
+    }
+
+    /// The creator's user id, for actions only the creator may take.
+    pub fn require_creator(&self) -> Result<Uuid, RoomError> {
+        let user_id = self.user_id.ok_or(RoomError::NotAuthenticated)?;
+        if !self.is_creator {
+            return Err(RoomError::NotCreator);
+        }
+        Ok(user_id)
+    }
+}
+
+/// Re-read `user_id`'s role and update it on each of their connections in the room.
+///
+/// A player joining a lobby that hides its players from spectators is given
+/// their pseudonym here.
+pub async fn refresh_room_context(state: &AppState, lobby_id: Uuid, user_id: Uuid) {
+    let player = PlayerStateRepository::new(state.redis.clone())
+        .get_state(lobby_id, user_id)
+        .await
+        .ok();
+    if let Some(player) = &player
+        && let Err(e) = state
+            .room_pseudonyms
+            .add(&state.redis, lobby_id, player)
+            .await
+    {
+        tracing::warn!("Failed to name {} in {}: {}", user_id, lobby_id, e);
+    }
+    let context = RoomContext::new(lobby_id, Some(user_id), player.as_ref());
+
+    let indices = state.indices.lock().await;
+    let Some(conn_ids) = indices.get_user_connections(&user_id) else {
+        return;
+    };
+    let conns = state.connections.lock().await;
+    for conn in conn_ids.iter().filter_map(|id| conns.get(id)) {
+        if conn.lobby_id() == Some(lobby_id) {
+            conn.set_room_context(context);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player(lobby_id: Uuid, user_id: Uuid, is_creator: bool) -> PlayerState {
+        PlayerState::new(
+            user_id,
+            lobby_id,
+            "SP000".into(),
+            None,
+            None,
+            10.0,
+            None,
+            is_creator,
+        )
+    }
+
+    #[test]
+    fn test_creator_context_passes_creator_checks() {
+        let (lobby_id, user_id) = (Uuid::new_v4(), Uuid::new_v4());
+        let creator = player(lobby_id, user_id, true);
+
+        let context = RoomContext::new(lobby_id, Some(user_id), Some(&creator));
+        assert_eq!(context.role, RoomRole::Creator);
+        assert!(context.is_participant());
+        assert_eq!(context.require_creator().unwrap(), user_id);
+    }
+
+    #[test]
+    fn test_player_context_fails_creator_checks() {
+        let (lobby_id, user_id) = (Uuid::new_v4(), Uuid::new_v4());
+        let joined = player(lobby_id, user_id, false);
+
+        let context = RoomContext::new(lobby_id, Some(user_id), Some(&joined));
+        assert_eq!(context.role, RoomRole::Player);
+        assert!(context.is_participant());
+        assert!(matches!(
+            context.require_creator(),
+            Err(RoomError::NotCreator)
+        ));
+    }
+
+    #[test]
+    fn test_spectators_are_not_participants() {
+        let lobby_id = Uuid::new_v4();
+
+        let signed_in = RoomContext::new(lobby_id, Some(Uuid::new_v4()), None);
+        assert_eq!(signed_in.role, RoomRole::Spectator);
+        assert!(!signed_in.is_participant());
+
+        let anonymous = RoomContext::new(lobby_id, None, None);
+        assert!(matches!(
+            anonymous.require_creator(),
+            Err(RoomError::NotAuthenticated)
+        ));
+    }
+}
@@ -4,23 +4,49 @@ use std::time::Duration;
 use tokio::time::sleep;
 use uuid::Uuid;
 
+use crate::auth::invite::decode_invite_token;
+use crate::contract_events::has_deposited;
+use crate::db::expiry;
 use crate::db::join_request::{JoinRequestRepository, JoinRequestState};
 use crate::db::lobby::LobbyRepository;
 use crate::db::lobby_chat::LobbyChatRepository;
+use crate::db::lobby_invite::LobbyInviteRepository;
 use crate::db::lobby_state::LobbyStateRepository;
 use crate::db::player_state::PlayerStateRepository;
+use crate::db::prediction::PredictionRepository;
 use crate::db::user::UserRepository;
-use crate::http::handlers::stacks::has_joined;
+use crate::errors::AppError;
+use crate::feature_flags::{Feature, FeatureFlags};
+use crate::games::{Audience, deadline_ms, server_time_ms};
+use crate::maintenance::MaintenanceMode;
+use crate::middleware::{StrictRateLimit, check_rate_limit};
 use crate::models::player_state::ClaimState;
-use crate::models::{LobbyStatus, PlayerState, WalletAddress};
+use crate::models::{ChatCursor, InviteError, LobbyStatus, PlayerState, WalletAddress};
 use crate::state::{AppState, ConnectionInfo};
 use crate::ws::room::{
-    RoomError,
+    RoomContext, RoomError, context,
+    handler::{close_spectator_connections, send_room_bootstrap},
     messages::{RoomClientMessage, RoomServerMessage},
+    ready, typing,
+};
+use crate::ws::{
+    broadcast,
+    core::{manager, message::JsonMessage},
 };
-use crate::ws::{broadcast, core::manager};
 use chrono::Utc;
 
+/// Seconds between the creator starting the game and the game engine starting
+const START_COUNTDOWN_SECS: u64 = 5;
+
+/// How often a running game's `tick` is called
+const GAME_TICK_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Join refusal for practice lobbies, which are played against the bot alone
+const PRACTICE_LOBBY_CLOSED: &str = "Practice lobbies can't be joined";
+
+/// Chat messages per `LoadChatHistory` page when the client doesn't say
+const CHAT_HISTORY_PAGE: usize = 50;
+
 /// Helper to require authentication for a lobby action
 async fn require_auth(conn: &Arc<ConnectionInfo>, auth_user_id: Option<Uuid>) -> Result<Uuid, ()> {
     match auth_user_id {
@@ -34,6 +60,80 @@ async fn require_auth(conn: &Arc<ConnectionInfo>, auth_user_id: Option<Uuid>) ->
     }
 }
 
+/// Force-finish the lobby's game if it's still running after `max`
+///
+/// Spawned when a game starts. Players keep their current standings and the
+/// room is told the game timed out.
+pub async fn enforce_max_duration(state: AppState, lobby_id: Uuid, max: Duration) {
+    sleep(max).await;
+
+    let timed_out = {
+        let Some(engine) = state.active_game(lobby_id).await else {
+            return;
+        };
+        let mut engine = engine.lock().await;
+        if engine.is_finished() {
+            return;
+        }
+        match engine.force_finish().await {
+            Ok(finished) => finished,
+            Err(e) => {
+                tracing::error!("Failed to force-finish game in lobby {}: {}", lobby_id, e);
+                return;
+            }
+        }
+    };
+
+    if timed_out {
+        tracing::warn!(
+            "Game in lobby {} ran past {}s and was force-finished",
+            lobby_id,
+            max.as_secs()
+        );
+        let _ = broadcast::broadcast_room(
+            &state,
+            lobby_id,
+            &RoomServerMessage::GameTimedOut {
+                max_duration_secs: max.as_secs(),
+            },
+        )
+        .await;
+    }
+}
+
+/// Call the lobby game's `tick` every `GAME_TICK_INTERVAL` until it finishes
+///
+/// Returned events are broadcast to the room wrapped in the "game" object,
+/// the same way action events are.
+pub async fn drive_game_ticks(state: AppState, lobby_id: Uuid) {
+    loop {
+        sleep(GAME_TICK_INTERVAL).await;
+
+        let events = {
+            let Some(engine) = state.active_game(lobby_id).await else {
+                return;
+            };
+            let mut engine = engine.lock().await;
+            if engine.is_finished() {
+                return;
+            }
+            match engine.tick().await {
+                Ok(events) => events,
+                Err(e) => {
+                    tracing::error!("Game tick failed for lobby {}: {}", lobby_id, e);
+                    continue;
+                }
+            }
+        };
+
+        for mut event in events {
+            let audience = Audience::take(&mut event);
+            let game_msg = JsonMessage::from(serde_json::json!({ "game": event }));
+            let _ = broadcast::broadcast_room_to(&state, lobby_id, audience, &game_msg).await;
+        }
+    }
+}
+
 /// Handle an individual lobby message
 pub async fn handle_room_message(
     room_msg: RoomClientMessage,
@@ -49,13 +149,17 @@ pub async fn handle_room_message(
         Ok(ls) => ls.status,
         Err(_) => return, // Can't process without status
     };
+    let room_context = conn
+        .room_context()
+        .unwrap_or_else(|| RoomContext::new(lobby_id, auth_user_id, None));
 
     match room_msg {
         RoomClientMessage::Ping { ts } => {
             let now_ms = Utc::now().timestamp_millis() as u64;
             let elapsed = now_ms.saturating_sub(ts);
 
-            let _ = manager::send_to_connection(
+            let _ = broadcast::send_room_message(
+                state,
                 conn,
                 &RoomServerMessage::Pong {
                     elapsed_ms: elapsed,
@@ -68,14 +172,47 @@ pub async fn handle_room_message(
                     let _ = player_repo.update_ping(lobby_id, user_id).await;
                 }
             }
+
+            // Anyone still connected keeps the lobby's keys alive
+            let _ = expiry::refresh_lobby_expiry(&state.redis, lobby_id).await;
+        }
+
+        RoomClientMessage::ServerTime { ts } => {
+            let _ = broadcast::send_room_message(
+                state,
+                conn,
+                &RoomServerMessage::ServerTime {
+                    server_time_ms: server_time_ms(),
+                    client_ts: ts,
+                },
+            )
+            .await;
+        }
+
+        RoomClientMessage::Resync => {
+            let lobby = match LobbyRepository::new(state.postgres.clone())
+                .find_by_id(lobby_id)
+                .await
+            {
+                Ok(lobby) => lobby,
+                Err(_) => {
+                    let msg = RoomServerMessage::from(RoomError::NotFound);
+                    let _ = broadcast::send_room_message(state, conn, &msg).await;
+                    return;
+                }
+            };
+            if let Err(err) = send_room_bootstrap(state, conn, lobby, auth_user_id).await {
+                let _ =
+                    broadcast::send_room_message(state, conn, &RoomServerMessage::from(err)).await;
+            }
         }
 
         // LOBBY-ONLY: Block if game is in progress (i guess ...)
-        RoomClientMessage::Join => {
+        RoomClientMessage::Join { invite_token } => {
             if lobby_status == LobbyStatus::InProgress {
                 let err = RoomError::JoinFailed("Cannot join during active game".to_string());
                 let msg = RoomServerMessage::from(err);
-                let _ = manager::send_to_connection(conn, &msg).await;
+                let _ = broadcast::send_room_message(state, conn, &msg).await;
                 return;
             }
 
@@ -83,7 +220,8 @@ pub async fn handle_room_message(
             let user_id = match require_auth(conn, auth_user_id).await {
                 Ok(uid) => uid,
                 Err(_) => {
-                    let _ = manager::send_to_connection(
+                    let _ = broadcast::send_room_message(
+                        state,
                         conn,
                         &RoomServerMessage::from(RoomError::JoinFailed(
                             "not authenticated".to_string(),
@@ -94,8 +232,51 @@ pub async fn handle_room_message(
                 }
             };
 
-            // Check join request (for private lobbies) or allow direct join (public lobbies)
-            let join_request = jr_repo.get(lobby_id, user_id).await;
+            let lobby = match LobbyRepository::new(state.postgres.clone())
+                .find_by_id(lobby_id)
+                .await
+            {
+                Ok(lobby) => lobby,
+                Err(e) => {
+                    let msg = RoomServerMessage::from(RoomError::JoinFailed(e.to_string()));
+                    let _ = broadcast::send_room_message(state, conn, &msg).await;
+                    return;
+                }
+            };
+            if lobby.practice_bot.is_some() {
+                let msg = RoomServerMessage::from(RoomError::JoinFailed(
+                    PRACTICE_LOBBY_CLOSED.to_string(),
+                ));
+                let _ = broadcast::send_room_message(state, conn, &msg).await;
+                return;
+            }
+
+            // Verify invite up front; the use is only consumed once all join checks pass
+            let invite_id = match invite_token {
+                Some(token) => match decode_invite_token(&token, &state.config.invite_secret) {
+                    Ok(claims) if claims.lobby_id == lobby_id => Some(claims.invite_id),
+                    Ok(_) => {
+                        let msg = RoomServerMessage::from(RoomError::JoinFailed(
+                            InviteError::WrongLobby.to_string(),
+                        ));
+                        let _ = broadcast::send_room_message(state, conn, &msg).await;
+                        return;
+                    }
+                    Err(e) => {
+                        let msg = RoomServerMessage::from(RoomError::JoinFailed(e.to_string()));
+                        let _ = broadcast::send_room_message(state, conn, &msg).await;
+                        return;
+                    }
+                },
+                None => None,
+            };
+
+            // Check join request (for private lobbies) or allow direct join (public lobbies).
+            // Invite bearers skip the approval queue entirely.
+            let join_request = match invite_id {
+                Some(_) => None,
+                None => jr_repo.get(lobby_id, user_id).await,
+            };
 
             let allowed = match &join_request {
                 Some(jr) => matches!(jr.state, JoinRequestState::Accepted),
@@ -118,7 +299,7 @@ pub async fn handle_room_message(
                             Err(e) => {
                                 let msg =
                                     RoomServerMessage::from(RoomError::JoinFailed(e.to_string()));
-                                let _ = manager::send_to_connection(conn, &msg).await;
+                                let _ = broadcast::send_room_message(state, conn, &msg).await;
                                 return;
                             }
                         };
@@ -137,20 +318,31 @@ pub async fn handle_room_message(
                         let msg = RoomServerMessage::from(RoomError::JoinFailed(
                             "Invalid wallet address".to_string(),
                         ));
-                        let _ = manager::send_to_connection(conn, &msg).await;
+                        let _ = broadcast::send_room_message(state, conn, &msg).await;
                         return;
                     }
                 };
 
+                // Paid lobbies may ask for a minimum trust rating
+                let min_trust = &state.config.paid_lobby_trust;
+                if min_trust.join > 0.0
+                    && lobby.is_paid()
+                    && let Err(e) = min_trust.check_join(trust_rating)
+                {
+                    let msg = RoomServerMessage::from(RoomError::JoinFailed(e.to_string()));
+                    let _ = broadcast::send_room_message(state, conn, &msg).await;
+                    return;
+                }
+
                 // Check if player has joined the vault contract if present
                 if let Some(contract_addr) = contract_address {
-                    match has_joined(contract_addr, &wallet_address_obj, state).await {
+                    match has_deposited(state, lobby_id, contract_addr, &wallet_address_obj).await {
                         Ok(true) => {} // Proceed
                         Ok(false) => {
                             let msg = RoomServerMessage::from(RoomError::JoinFailed(
                                 "Player has not joined the vault contract".to_string(),
                             ));
-                            let _ = manager::send_to_connection(conn, &msg).await;
+                            let _ = broadcast::send_room_message(state, conn, &msg).await;
                             return;
                         }
                         Err(e) => {
@@ -158,12 +350,21 @@ pub async fn handle_room_message(
                                 "Failed to check contract join: {}",
                                 e
                             )));
-                            let _ = manager::send_to_connection(conn, &msg).await;
+                            let _ = broadcast::send_room_message(state, conn, &msg).await;
                             return;
                         }
                     }
                 }
 
+                if let Some(invite_id) = invite_id {
+                    let invite_repo = LobbyInviteRepository::new(state.redis.clone());
+                    if let Err(e) = invite_repo.redeem(lobby_id, invite_id).await {
+                        let msg = RoomServerMessage::from(RoomError::JoinFailed(e.to_string()));
+                        let _ = broadcast::send_room_message(state, conn, &msg).await;
+                        return;
+                    }
+                }
+
                 // Create or upsert player state with user data
                 let pstate = PlayerState::new(
                     user_id,
@@ -178,13 +379,20 @@ pub async fn handle_room_message(
                 let _ = player_repo
                     .upsert_state(pstate.clone(), Some(state.clone()))
                     .await;
+                context::refresh_room_context(state, lobby_id, user_id).await;
 
-                let participant_count = lobby_state_repo
-                    .increment_participants(lobby_id)
+                // A player can't bet on their own game
+                if let Err(e) = PredictionRepository::new(state.redis.clone())
+                    .void(lobby_id, user_id)
                     .await
-                    .unwrap_or(0);
+                {
+                    tracing::warn!("Failed to void {}'s prediction: {}", user_id, e);
+                }
+
+                let _ = lobby_state_repo.increment_participants(lobby_id).await;
+                // The roster, count and pot reach the room as one room state
+                broadcast::broadcast_lobby_update(state.clone(), lobby_id).await;
 
-                // broadcast joined and updated player list
                 let _ = broadcast::broadcast_room(
                     state,
                     lobby_id,
@@ -192,32 +400,9 @@ pub async fn handle_room_message(
                 )
                 .await;
 
-                if let Ok(players) = player_repo.get_all_in_lobby(lobby_id).await {
-                    let _ = broadcast::broadcast_room(
-                        state,
-                        lobby_id,
-                        &RoomServerMessage::PlayerUpdated { players },
-                    )
-                    .await;
-                }
-
                 let lobby_repo = LobbyRepository::new(state.postgres.clone());
                 let db_lobby = lobby_repo.find_by_id(lobby_id).await.ok();
 
-                let current_amount = db_lobby.as_ref().and_then(|l| l.current_amount);
-
-                // Broadcast lobby status change with updated participant count and current amount
-                let _ = broadcast::broadcast_room(
-                    state,
-                    lobby_id,
-                    &RoomServerMessage::LobbyStatusChanged {
-                        status: lobby_status,
-                        participant_count,
-                        current_amount,
-                    },
-                )
-                .await;
-
                 // Handle private lobby join request cleanup
                 if let Some(lobby) = db_lobby {
                     if lobby.is_private {
@@ -236,7 +421,7 @@ pub async fn handle_room_message(
             } else {
                 let err = RoomError::JoinFailed("join request not accepted".to_string());
                 let msg = RoomServerMessage::from(err);
-                let _ = manager::send_to_connection(conn, &msg).await;
+                let _ = broadcast::send_room_message(state, conn, &msg).await;
             }
         }
 
@@ -244,14 +429,15 @@ pub async fn handle_room_message(
             if lobby_status == LobbyStatus::InProgress {
                 let err = RoomError::LeaveFailed("Cannot leave during active game".to_string());
                 let msg = RoomServerMessage::from(err);
-                let _ = manager::send_to_connection(conn, &msg).await;
+                let _ = broadcast::send_room_message(state, conn, &msg).await;
                 return;
             }
 
             let user_id = match require_auth(conn, auth_user_id).await {
                 Ok(uid) => uid,
                 Err(_) => {
-                    let _ = manager::send_to_connection(
+                    let _ = broadcast::send_room_message(
+                        state,
                         conn,
                         &RoomServerMessage::from(RoomError::LeaveFailed(
                             "not authenticated".to_string(),
@@ -262,13 +448,7 @@ pub async fn handle_room_message(
                 }
             };
 
-            // Check if user is the creator
-            let is_creator = player_repo
-                .is_creator(lobby_id, user_id)
-                .await
-                .unwrap_or(false);
-
-            if is_creator {
+            if room_context.is_creator {
                 // Creator can only leave if they are the only participant
                 let participant_count = player_repo.count_players(lobby_id).await.unwrap_or(0);
 
@@ -301,7 +481,7 @@ pub async fn handle_room_message(
                         "Creator cannot leave while other players are in the lobby".to_string(),
                     );
                     let msg = RoomServerMessage::from(err);
-                    let _ = manager::send_to_connection(conn, &msg).await;
+                    let _ = broadcast::send_room_message(state, conn, &msg).await;
                     return;
                 }
             }
@@ -314,11 +494,10 @@ pub async fn handle_room_message(
                 .delete_state(lobby_id, user_id, Some(state.clone()))
                 .await
                 .ok();
+            context::refresh_room_context(state, lobby_id, user_id).await;
 
-            let participant_count = lobby_state_repo
-                .decrement_participants(lobby_id)
-                .await
-                .unwrap_or(0);
+            let _ = lobby_state_repo.decrement_participants(lobby_id).await;
+            broadcast::broadcast_lobby_update(state.clone(), lobby_id).await;
 
             if let Some(player) = player {
                 let _ = broadcast::broadcast_room(
@@ -328,33 +507,6 @@ pub async fn handle_room_message(
                 )
                 .await;
             }
-            if let Ok(players) = player_repo.get_all_in_lobby(lobby_id).await {
-                let _ = broadcast::broadcast_room(
-                    state,
-                    lobby_id,
-                    &RoomServerMessage::PlayerUpdated { players },
-                )
-                .await;
-            }
-
-            // Broadcast lobby status change with updated participant count and current amount
-            let lobby_repo = LobbyRepository::new(state.postgres.clone());
-            let current_amount = lobby_repo
-                .find_by_id(lobby_id)
-                .await
-                .ok()
-                .and_then(|l| l.current_amount);
-
-            let _ = broadcast::broadcast_room(
-                state,
-                lobby_id,
-                &RoomServerMessage::LobbyStatusChanged {
-                    status: lobby_status,
-                    participant_count,
-                    current_amount,
-                },
-            )
-            .await;
         }
 
         RoomClientMessage::UpdateLobbyStatus { status } => {
@@ -363,39 +515,50 @@ pub async fn handle_room_message(
                     "Cannot change status during active/finished game".to_string(),
                 );
                 let msg = RoomServerMessage::from(err);
-                let _ = manager::send_to_connection(conn, &msg).await;
+                let _ = broadcast::send_room_message(state, conn, &msg).await;
                 return;
             }
 
-            let user_id = match require_auth(conn, auth_user_id).await {
-                Ok(uid) => uid,
-                Err(_) => {
-                    let _ = manager::send_to_connection(
-                        conn,
-                        &RoomServerMessage::from(RoomError::LobbyStatusFailed(
-                            "not authenticated".to_string(),
-                        )),
-                    )
-                    .await;
-                    return;
-                }
-            };
+            if require_auth(conn, auth_user_id).await.is_err() {
+                let _ = broadcast::send_room_message(
+                    state,
+                    conn,
+                    &RoomServerMessage::from(RoomError::LobbyStatusFailed(
+                        "not authenticated".to_string(),
+                    )),
+                )
+                .await;
+                return;
+            }
 
             // Only the lobby creator can change lobby status
-            let is_creator = player_repo
-                .is_creator(lobby_id, user_id)
-                .await
-                .unwrap_or(false);
-
-            if !is_creator {
+            if !room_context.is_creator {
                 let err = RoomError::LobbyStatusFailed(
                     "Only creator can change lobby status".to_string(),
                 );
                 let msg = RoomServerMessage::from(err);
-                let _ = manager::send_to_connection(conn, &msg).await;
+                let _ = broadcast::send_room_message(state, conn, &msg).await;
                 return;
             }
 
+            // Games already running are unaffected, but none start during maintenance
+            if matches!(status, LobbyStatus::Starting) {
+                let maintenance = MaintenanceMode::new(state.redis.clone()).status().await;
+                let refusal = match maintenance {
+                    Ok(m) if m.enabled => {
+                        Some(RoomError::Maintenance(m.player_message().to_string()))
+                    }
+                    Ok(_) => None,
+                    Err(e) => Some(RoomError::LobbyStatusFailed(e.to_string())),
+                };
+                if let Some(err) = refusal {
+                    let _ =
+                        broadcast::send_room_message(state, conn, &RoomServerMessage::from(err))
+                            .await;
+                    return;
+                }
+            }
+
             let _ = lobby_state_repo
                 .update_status(lobby_id, status.clone())
                 .await;
@@ -406,8 +569,18 @@ pub async fn handle_room_message(
                 tokio::spawn(async move {
                     let spawn_repo = LobbyStateRepository::new(spawn_redis.clone());
 
+                    // Players ready up first, if configured
+                    let ready_up = spawn_state.config.ready_up.clone();
+                    if ready_up.is_enabled()
+                        && !ready::run_ready_check(&spawn_state, spawn_lobby, &ready_up).await
+                    {
+                        return;
+                    }
+
+                    let countdown_deadline = deadline_ms(server_time_ms(), START_COUNTDOWN_SECS);
+
                     // Countdown from 5 down to 0
-                    for sec in (0..=5).rev() {
+                    for sec in (0..=START_COUNTDOWN_SECS).rev() {
                         let _ = spawn_repo.set_countdown(spawn_lobby, sec as u8).await.ok();
 
                         if sec == 0 {
@@ -425,6 +598,8 @@ pub async fn handle_room_message(
                                     spawn_lobby,
                                     &RoomServerMessage::StartCountdown {
                                         seconds_remaining: None,
+                                        deadline_ms: None,
+                                        server_time_ms: server_time_ms(),
                                     },
                                 )
                                 .await;
@@ -439,6 +614,8 @@ pub async fn handle_room_message(
                             spawn_lobby,
                             &RoomServerMessage::StartCountdown {
                                 seconds_remaining: Some(sec as u8),
+                                deadline_ms: Some(countdown_deadline),
+                                server_time_ms: server_time_ms(),
                             },
                         )
                         .await;
@@ -460,11 +637,8 @@ pub async fn handle_room_message(
                         .map(|s| s.participant_count)
                         .unwrap_or(0);
 
-                    let current_amount = lobby_repo_spawn
-                        .find_by_id(spawn_lobby)
-                        .await
-                        .ok()
-                        .and_then(|l| l.current_amount);
+                    let lobby = lobby_repo_spawn.find_by_id(spawn_lobby).await.ok();
+                    let current_amount = lobby.as_ref().and_then(|l| l.current_amount);
 
                     let _ = broadcast::broadcast_room(
                         &spawn_state,
@@ -477,9 +651,13 @@ pub async fn handle_room_message(
                     )
                     .await;
 
+                    if lobby.is_some_and(|l| !l.spectators_allowed) {
+                        close_spectator_connections(&spawn_state, spawn_lobby).await;
+                    }
+
                     let lobby_repo = LobbyRepository::new(spawn_state.postgres.clone());
-                    let game_id = match lobby_repo.find_by_id(spawn_lobby).await {
-                        Ok(db_lobby) => db_lobby.game_id,
+                    let db_lobby = match lobby_repo.find_by_id(spawn_lobby).await {
+                        Ok(db_lobby) => db_lobby,
                         _ => {
                             tracing::error!(
                                 "Failed to fetch lobby metadata for game initialization"
@@ -488,22 +666,53 @@ pub async fn handle_room_message(
                         }
                     };
 
+                    let (game_id, practice_bot) = (db_lobby.game_id, db_lobby.practice_bot);
+
                     if let Some(factory) = spawn_state.game_registry.get(&game_id) {
                         // Create engine with state (state is now required at creation time)
                         let mut engine = factory(spawn_lobby, spawn_state.clone());
+                        engine.set_lobby(&db_lobby).await;
 
                         // Get all player IDs in the lobby
                         let player_repo = PlayerStateRepository::new(spawn_state.redis.clone());
-                        let player_ids = match player_repo.get_all_in_lobby(spawn_lobby).await {
-                            Ok(players) => players.into_iter().map(|p| p.user_id).collect(),
-                            Err(e) => {
-                                tracing::error!(
-                                    "Failed to fetch players for game initialization: {}",
-                                    e
-                                );
-                                return;
+                        let mut player_ids: Vec<Uuid> =
+                            match player_repo.list_players(spawn_lobby).await {
+                                Ok(players) => players.into_iter().map(|p| p.user_id).collect(),
+                                Err(e) => {
+                                    tracing::error!(
+                                        "Failed to fetch players for game initialization: {}",
+                                        e
+                                    );
+                                    return;
+                                }
+                            };
+
+                        // Practice lobbies: the bot takes the second seat
+                        if let Some(difficulty) = practice_bot {
+                            match engine.add_bot(difficulty).await {
+                                Ok(bot_id) => player_ids.push(bot_id),
+                                Err(e) => {
+                                    let _ = broadcast::broadcast_room(
+                                        &spawn_state,
+                                        spawn_lobby,
+                                        &RoomServerMessage::GameStartFailed {
+                                            reason: e.to_string(),
+                                        },
+                                    )
+                                    .await;
+                                    return;
+                                }
                             }
-                        };
+                        }
+
+                        // The seating seed is revealed once the game starts; the
+                        // gameplay seed only once it ends
+                        engine
+                            .set_seeds(
+                                spawn_state.config.game_seed(),
+                                spawn_state.config.game_seed(),
+                            )
+                            .await;
 
                         // Initialize the game engine
                         match engine.initialize(player_ids).await {
@@ -521,18 +730,30 @@ pub async fn handle_room_message(
                                 // Store the active game engine
                                 {
                                     let mut active_games = spawn_state.active_games.lock().await;
-                                    active_games.insert(spawn_lobby, engine);
+                                    active_games.insert(
+                                        spawn_lobby,
+                                        Arc::new(tokio::sync::Mutex::new(engine)),
+                                    );
                                 }
 
+                                let limits = spawn_state.config.game_durations.for_game(game_id);
+                                tokio::spawn(enforce_max_duration(
+                                    spawn_state.clone(),
+                                    spawn_lobby,
+                                    limits.max(),
+                                ));
+                                tokio::spawn(drive_game_ticks(spawn_state.clone(), spawn_lobby));
+
                                 // Broadcast initialization events to room
                                 // These are RoomServerMessage variants (GameStarted, GameStartFailed)
                                 // which should be broadcast directly without game wrapper
-                                for event in events {
-                                    let game_msg =
-                                        crate::ws::core::message::JsonMessage::from(event);
-                                    let _ = broadcast::broadcast_room(
+                                for mut event in events {
+                                    let audience = Audience::take(&mut event);
+                                    let game_msg = JsonMessage::from(event);
+                                    let _ = broadcast::broadcast_room_to(
                                         &spawn_state,
                                         spawn_lobby,
+                                        audience,
                                         &game_msg,
                                     )
                                     .await;
@@ -579,14 +800,15 @@ pub async fn handle_room_message(
                 let err =
                     RoomError::JoinFailed("Cannot request to join during active game".to_string());
                 let msg = RoomServerMessage::from(err);
-                let _ = manager::send_to_connection(conn, &msg).await;
+                let _ = broadcast::send_room_message(state, conn, &msg).await;
                 return;
             }
 
             let user_id = match require_auth(conn, auth_user_id).await {
                 Ok(uid) => uid,
                 Err(_) => {
-                    let _ = manager::send_to_connection(
+                    let _ = broadcast::send_room_message(
+                        state,
                         conn,
                         &RoomServerMessage::from(RoomError::JoinFailed(
                             "not authenticated".to_string(),
@@ -597,29 +819,67 @@ pub async fn handle_room_message(
                 }
             };
 
+            let lobby = match LobbyRepository::new(state.postgres.clone())
+                .find_by_id(lobby_id)
+                .await
+            {
+                Ok(lobby) => lobby,
+                Err(e) => {
+                    let msg = RoomServerMessage::from(RoomError::JoinFailed(e.to_string()));
+                    let _ = broadcast::send_room_message(state, conn, &msg).await;
+                    return;
+                }
+            };
+            if lobby.practice_bot.is_some() {
+                let msg = RoomServerMessage::from(RoomError::JoinFailed(
+                    PRACTICE_LOBBY_CLOSED.to_string(),
+                ));
+                let _ = broadcast::send_room_message(state, conn, &msg).await;
+                return;
+            }
+
             // Fetch user profile to include in join request
             let user_repo = UserRepository::new(state.postgres.clone());
             let user = match user_repo.find_by_id(user_id).await {
                 Ok(u) => u,
                 Err(e) => {
                     let msg = RoomServerMessage::from(RoomError::JoinFailed(e.to_string()));
-                    let _ = manager::send_to_connection(conn, &msg).await;
+                    let _ = broadcast::send_room_message(state, conn, &msg).await;
                     return;
                 }
             };
 
+            // Trusted enough users skip the creator's queue in private lobbies
+            let auto_approve_threshold = lobby
+                .auto_approve_trust_threshold
+                .filter(|_| lobby.auto_approves(user.trust_rating));
+
             let jr_repo = JoinRequestRepository::new(state.redis.clone());
-            let _ = jr_repo
-                .create_pending(
-                    lobby_id,
+            if let Some(threshold) = auto_approve_threshold {
+                let _ = jr_repo
+                    .create_auto_approved(lobby_id, &user, threshold)
+                    .await;
+                let _ = broadcast::broadcast_user(
+                    state,
                     user_id,
-                    user.wallet_address.to_string(),
-                    user.username,
-                    user.display_name,
-                    user.trust_rating,
-                    15 * 60,
+                    &RoomServerMessage::JoinRequestStatus {
+                        user_id,
+                        accepted: true,
+                    },
                 )
                 .await;
+            } else {
+                let _ = jr_repo
+                    .create_pending(
+                        lobby_id,
+                        user_id,
+                        user.wallet_address.to_string(),
+                        user.username,
+                        user.display_name,
+                        user.trust_rating,
+                    )
+                    .await;
+            }
             if let Ok(list) = jr_repo.list(lobby_id).await {
                 let _ = broadcast::broadcast_room(
                     state,
@@ -639,35 +899,28 @@ pub async fn handle_room_message(
                 let err =
                     RoomError::ApproveFailed("Cannot approve joins during active game".to_string());
                 let msg = RoomServerMessage::from(err);
-                let _ = manager::send_to_connection(conn, &msg).await;
+                let _ = broadcast::send_room_message(state, conn, &msg).await;
                 return;
             }
 
-            let user_id = match require_auth(conn, auth_user_id).await {
-                Ok(uid) => uid,
-                Err(_) => {
-                    let _ = manager::send_to_connection(
-                        conn,
-                        &RoomServerMessage::from(RoomError::ApproveFailed(
-                            "not authenticated".to_string(),
-                        )),
-                    )
-                    .await;
-                    return;
-                }
-            };
+            if require_auth(conn, auth_user_id).await.is_err() {
+                let _ = broadcast::send_room_message(
+                    state,
+                    conn,
+                    &RoomServerMessage::from(RoomError::ApproveFailed(
+                        "not authenticated".to_string(),
+                    )),
+                )
+                .await;
+                return;
+            }
 
             // Only creator can approve join requests
-            let is_creator = player_repo
-                .is_creator(lobby_id, user_id)
-                .await
-                .unwrap_or(false);
-
-            if !is_creator {
+            if !room_context.is_creator {
                 let err =
                     RoomError::ApproveFailed("Only creator can approve join request".to_string());
                 let msg = RoomServerMessage::from(err);
-                let _ = manager::send_to_connection(conn, &msg).await;
+                let _ = broadcast::send_room_message(state, conn, &msg).await;
                 return;
             }
 
@@ -703,35 +956,28 @@ pub async fn handle_room_message(
                 let err =
                     RoomError::RejectFailed("Cannot reject joins during active game".to_string());
                 let msg = RoomServerMessage::from(err);
-                let _ = manager::send_to_connection(conn, &msg).await;
+                let _ = broadcast::send_room_message(state, conn, &msg).await;
                 return;
             }
 
-            let user_id = match require_auth(conn, auth_user_id).await {
-                Ok(uid) => uid,
-                Err(_) => {
-                    let _ = manager::send_to_connection(
-                        conn,
-                        &RoomServerMessage::from(RoomError::RejectFailed(
-                            "not authenticated".to_string(),
-                        )),
-                    )
-                    .await;
-                    return;
-                }
-            };
+            if require_auth(conn, auth_user_id).await.is_err() {
+                let _ = broadcast::send_room_message(
+                    state,
+                    conn,
+                    &RoomServerMessage::from(RoomError::RejectFailed(
+                        "not authenticated".to_string(),
+                    )),
+                )
+                .await;
+                return;
+            }
 
             // Only creator can reject join requests
-            let is_creator = player_repo
-                .is_creator(lobby_id, user_id)
-                .await
-                .unwrap_or(false);
-
-            if !is_creator {
+            if !room_context.is_creator {
                 let err =
                     RoomError::RejectFailed("Only creator can reject join request".to_string());
                 let msg = RoomServerMessage::from(err);
-                let _ = manager::send_to_connection(conn, &msg).await;
+                let _ = broadcast::send_room_message(state, conn, &msg).await;
                 return;
             }
 
@@ -767,14 +1013,15 @@ pub async fn handle_room_message(
                 let err =
                     RoomError::KickFailed("Cannot kick players during active game".to_string());
                 let msg = RoomServerMessage::from(err);
-                let _ = manager::send_to_connection(conn, &msg).await;
+                let _ = broadcast::send_room_message(state, conn, &msg).await;
                 return;
             }
 
             let user_id = match require_auth(conn, auth_user_id).await {
                 Ok(uid) => uid,
                 Err(_) => {
-                    let _ = manager::send_to_connection(
+                    let _ = broadcast::send_room_message(
+                        state,
                         conn,
                         &RoomServerMessage::from(RoomError::KickFailed(
                             "not authenticated".to_string(),
@@ -786,22 +1033,17 @@ pub async fn handle_room_message(
             };
 
             // Only creator can kick players
-            let is_creator = player_repo
-                .is_creator(lobby_id, user_id)
-                .await
-                .unwrap_or(false);
-
-            if !is_creator {
+            if !room_context.is_creator {
                 let err = RoomError::KickFailed("Only lobby creator can kick player".to_string());
                 let msg = RoomServerMessage::from(err);
-                let _ = manager::send_to_connection(conn, &msg).await;
+                let _ = broadcast::send_room_message(state, conn, &msg).await;
                 return;
             }
 
             if user_id == kicked_user_id {
                 let err = RoomError::KickFailed("Creator cannot kick themselves".to_string());
                 let msg = RoomServerMessage::from(err);
-                let _ = manager::send_to_connection(conn, &msg).await;
+                let _ = broadcast::send_room_message(state, conn, &msg).await;
                 return;
             }
 
@@ -813,11 +1055,10 @@ pub async fn handle_room_message(
                 .delete_state(lobby_id, kicked_user_id, Some(state.clone()))
                 .await
                 .ok();
+            context::refresh_room_context(state, lobby_id, kicked_user_id).await;
 
-            let participant_count = lobby_state_repo
-                .decrement_participants(lobby_id)
-                .await
-                .unwrap_or(0);
+            let _ = lobby_state_repo.decrement_participants(lobby_id).await;
+            broadcast::broadcast_lobby_update(state.clone(), lobby_id).await;
 
             if let Some(ref player) = kicked_player {
                 let _ = broadcast::broadcast_room(
@@ -829,32 +1070,6 @@ pub async fn handle_room_message(
                 )
                 .await;
             }
-            if let Ok(players) = player_repo.get_all_in_lobby(lobby_id).await {
-                let _ = broadcast::broadcast_room(
-                    state,
-                    lobby_id,
-                    &RoomServerMessage::PlayerUpdated { players },
-                )
-                .await;
-            }
-
-            let lobby_repo = LobbyRepository::new(state.postgres.clone());
-            let current_amount = lobby_repo
-                .find_by_id(lobby_id)
-                .await
-                .ok()
-                .and_then(|l| l.current_amount);
-
-            let _ = broadcast::broadcast_room(
-                state,
-                lobby_id,
-                &RoomServerMessage::LobbyStatusChanged {
-                    status: lobby_status,
-                    participant_count,
-                    current_amount,
-                },
-            )
-            .await;
         }
 
         RoomClientMessage::SendMessage { content, reply_to } => {
@@ -864,14 +1079,22 @@ pub async fn handle_room_message(
             };
 
             // Only participants (players + spectators) can send messages
-            let is_participant = player_repo.exists(lobby_id, user_id).await.unwrap_or(false);
-
-            if !is_participant {
+            if !room_context.is_participant() {
                 let err = RoomError::SendMessageFailed(
                     "Only lobby participants can send message".to_string(),
                 );
                 let msg = RoomServerMessage::from(err);
-                let _ = manager::send_to_connection(conn, &msg).await;
+                let _ = broadcast::send_room_message(state, conn, &msg).await;
+                return;
+            }
+
+            if let Some(word) = state.config.chat_moderation.blocked_word(&content) {
+                let err = RoomError::SendMessageFailed(format!(
+                    "Message contains the blocked word '{}'",
+                    word
+                ));
+                let msg = RoomServerMessage::from(err);
+                let _ = broadcast::send_room_message(state, conn, &msg).await;
                 return;
             }
 
@@ -881,6 +1104,8 @@ pub async fn handle_room_message(
                 .await
             {
                 Ok(message) => {
+                    // The message is out, so they're done typing it
+                    typing::set_typing(state, lobby_id, user_id, false).await;
                     let _ = broadcast::broadcast_room(
                         state,
                         lobby_id,
@@ -892,7 +1117,7 @@ pub async fn handle_room_message(
                     let err =
                         RoomError::SendMessageFailed(format!("Failed to create message: {}", e));
                     let msg = RoomServerMessage::from(err);
-                    let _ = manager::send_to_connection(conn, &msg).await;
+                    let _ = broadcast::send_room_message(state, conn, &msg).await;
                 }
             }
         }
@@ -901,7 +1126,8 @@ pub async fn handle_room_message(
             let user_id = match require_auth(conn, auth_user_id).await {
                 Ok(uid) => uid,
                 Err(_) => {
-                    let _ = manager::send_to_connection(
+                    let _ = broadcast::send_room_message(
+                        state,
                         conn,
                         &RoomServerMessage::from(RoomError::ReactionFailed(
                             "not authenticated".to_string(),
@@ -918,7 +1144,7 @@ pub async fn handle_room_message(
             if !is_participant {
                 let err = RoomError::ReactionFailed("Not in lobby".to_string());
                 let msg = RoomServerMessage::from(err);
-                let _ = manager::send_to_connection(conn, &msg).await;
+                let _ = broadcast::send_room_message(state, conn, &msg).await;
                 return;
             }
 
@@ -926,7 +1152,9 @@ pub async fn handle_room_message(
                 .add_reaction(lobby_id, message_id, user_id, &emoji)
                 .await
             {
-                Ok(_) => {
+                // Already there: nothing to tell the room
+                Ok((_, false)) => {}
+                Ok((_, true)) => {
                     let _ = broadcast::broadcast_room(
                         state,
                         lobby_id,
@@ -941,7 +1169,7 @@ pub async fn handle_room_message(
                 Err(e) => {
                     let err = RoomError::ReactionFailed(format!("Failed to add reaction: {}", e));
                     let msg = RoomServerMessage::from(err);
-                    let _ = manager::send_to_connection(conn, &msg).await;
+                    let _ = broadcast::send_room_message(state, conn, &msg).await;
                 }
             }
         }
@@ -950,7 +1178,8 @@ pub async fn handle_room_message(
             let user_id = match require_auth(conn, auth_user_id).await {
                 Ok(uid) => uid,
                 Err(_) => {
-                    let _ = manager::send_to_connection(
+                    let _ = broadcast::send_room_message(
+                        state,
                         conn,
                         &RoomServerMessage::from(RoomError::ReactionFailed(
                             "not authenticated".to_string(),
@@ -967,7 +1196,7 @@ pub async fn handle_room_message(
             if !is_participant {
                 let err = RoomError::ReactionFailed("Not in lobby".to_string());
                 let msg = RoomServerMessage::from(err);
-                let _ = manager::send_to_connection(conn, &msg).await;
+                let _ = broadcast::send_room_message(state, conn, &msg).await;
                 return;
             }
 
@@ -975,7 +1204,8 @@ pub async fn handle_room_message(
                 .remove_reaction(lobby_id, message_id, user_id, &emoji)
                 .await
             {
-                Ok(_) => {
+                Ok((_, false)) => {}
+                Ok((_, true)) => {
                     let _ = broadcast::broadcast_room(
                         state,
                         lobby_id,
@@ -991,22 +1221,213 @@ pub async fn handle_room_message(
                     let err =
                         RoomError::ReactionFailed(format!("Failed to remove reaction: {}", e));
                     let msg = RoomServerMessage::from(err);
-                    let _ = manager::send_to_connection(conn, &msg).await;
+                    let _ = broadcast::send_room_message(state, conn, &msg).await;
                 }
             }
         }
 
+        RoomClientMessage::MarkRead { message_id } => {
+            let user_id = match require_auth(conn, auth_user_id).await {
+                Ok(uid) => uid,
+                Err(_) => return,
+            };
+
+            match LobbyChatRepository::new(state.redis.clone())
+                .mark_read(lobby_id, user_id, message_id)
+                .await
+            {
+                Ok(false) => {}
+                Ok(true) => {
+                    let _ = broadcast::broadcast_room(
+                        state,
+                        lobby_id,
+                        &RoomServerMessage::ReadReceipt {
+                            user_id,
+                            message_id,
+                        },
+                    )
+                    .await;
+                }
+                Err(e) => {
+                    let err = RoomError::MarkReadFailed(e);
+                    let _ =
+                        broadcast::send_room_message(state, conn, &RoomServerMessage::from(err))
+                            .await;
+                }
+            }
+        }
+
+        RoomClientMessage::Typing { is_typing } => {
+            // Anonymous spectators have nothing to announce
+            let Some(user_id) = auth_user_id else {
+                return;
+            };
+            // Only those who can send messages can be typing one
+            if is_typing && !room_context.is_participant() {
+                return;
+            }
+            typing::set_typing(state, lobby_id, user_id, is_typing).await;
+        }
+
+        RoomClientMessage::LoadChatHistory {
+            before,
+            after,
+            limit,
+        } => {
+            let cursor = match (before, after) {
+                (Some(_), Some(_)) => {
+                    let err = RoomError::ChatHistoryFailed(
+                        "Pass either before or after, not both".to_string(),
+                    );
+                    let _ =
+                        broadcast::send_room_message(state, conn, &RoomServerMessage::from(err))
+                            .await;
+                    return;
+                }
+                (Some(id), None) => Some(ChatCursor::Before(id)),
+                (None, Some(id)) => Some(ChatCursor::After(id)),
+                (None, None) => None,
+            };
+
+            // Anyone in the room sees the chat, as in the bootstrap
+            let msg = match LobbyChatRepository::new(state.redis.clone())
+                .get_history_page(
+                    lobby_id,
+                    cursor,
+                    limit.unwrap_or(CHAT_HISTORY_PAGE),
+                    auth_user_id,
+                )
+                .await
+            {
+                Ok(page) => RoomServerMessage::ChatHistory {
+                    messages: page.messages,
+                    next_cursor: page.next_cursor,
+                    has_more: page.has_more,
+                },
+                Err(e) => RoomServerMessage::from(RoomError::ChatHistoryFailed(e)),
+            };
+            let _ = broadcast::send_room_message(state, conn, &msg).await;
+        }
+
+        RoomClientMessage::Ready => {
+            let user_id = match require_auth(conn, auth_user_id).await {
+                Ok(uid) => uid,
+                Err(_) => return,
+            };
+
+            let refusal = if lobby_status != LobbyStatus::Starting {
+                Some("no ready check in progress")
+            } else if !room_context.is_participant() {
+                Some("only players can ready up")
+            } else {
+                None
+            };
+            if let Some(reason) = refusal {
+                let err = RoomError::ReadyFailed(reason.to_string());
+                let _ =
+                    broadcast::send_room_message(state, conn, &RoomServerMessage::from(err)).await;
+                return;
+            }
+
+            if let Err(e) = lobby_state_repo.mark_ready(lobby_id, user_id).await {
+                let err = RoomError::ReadyFailed(e.to_string());
+                let _ =
+                    broadcast::send_room_message(state, conn, &RoomServerMessage::from(err)).await;
+                return;
+            }
+
+            // Players who left after readying up don't count
+            let ready = lobby_state_repo
+                .get_ready(lobby_id)
+                .await
+                .unwrap_or_default();
+            let player_ids = player_repo
+                .get_player_ids(lobby_id)
+                .await
+                .unwrap_or_default();
+            let ready_count = player_ids.iter().filter(|id| ready.contains(id)).count();
+            let player_count = player_ids.len();
+            let _ = broadcast::broadcast_room(
+                state,
+                lobby_id,
+                &RoomServerMessage::PlayerReady {
+                    user_id,
+                    ready_count,
+                    player_count,
+                },
+            )
+            .await;
+        }
+
+        RoomClientMessage::PredictWinner { user_id: winner_id } => {
+            let user_id = match require_auth(conn, auth_user_id).await {
+                Ok(uid) => uid,
+                Err(_) => return,
+            };
+
+            let predictions_on = FeatureFlags::from_state(state)
+                .enabled(Feature::Predictions)
+                .await
+                .unwrap_or(false);
+            let refusal = if state.config.prediction_points == 0 || !predictions_on {
+                Some("predictions are turned off")
+            } else if room_context.is_participant()
+                || player_repo.get_state(lobby_id, user_id).await.is_ok()
+            {
+                Some("only spectators can predict")
+            } else if player_repo.get_state(lobby_id, winner_id).await.is_err() {
+                Some("that user isn't playing in this lobby")
+            } else {
+                None
+            };
+            if let Some(reason) = refusal {
+                let err = RoomError::PredictionFailed(reason.to_string());
+                let _ =
+                    broadcast::send_room_message(state, conn, &RoomServerMessage::from(err)).await;
+                return;
+            }
+
+            let reply = match PredictionRepository::new(state.redis.clone())
+                .record(lobby_id, user_id, winner_id)
+                .await
+            {
+                Ok(true) => RoomServerMessage::PredictionRecorded { winner_id },
+                Ok(false) => RoomServerMessage::from(RoomError::PredictionFailed(
+                    "predictions are locked once the game starts".to_string(),
+                )),
+                Err(AppError::Forbidden(reason)) => {
+                    RoomServerMessage::from(RoomError::PredictionFailed(reason))
+                }
+                Err(e) => RoomServerMessage::from(RoomError::PredictionFailed(e.to_string())),
+            };
+            let _ = broadcast::send_room_message(state, conn, &reply).await;
+        }
+
         RoomClientMessage::ClaimReward { tx_id } => {
             let user_id = match require_auth(conn, auth_user_id).await {
                 Ok(uid) => uid,
                 Err(_) => return,
             };
 
+            // Claims share the strict quota with HTTP contract calls, keyed by user
+            if let Err((_, msg)) =
+                check_rate_limit::<StrictRateLimit>(state, "unknown", Some(user_id)).await
+            {
+                let _ = broadcast::send_room_message(
+                    state,
+                    conn,
+                    &RoomServerMessage::from(RoomError::ClaimFailed(msg)),
+                )
+                .await;
+                return;
+            }
+
             // Get player state
             let player_state = match player_repo.get_state(lobby_id, user_id).await {
                 Ok(ps) => ps,
                 Err(_) => {
-                    let _ = manager::send_to_connection(
+                    let _ = broadcast::send_room_message(
+                        state,
                         conn,
                         &RoomServerMessage::from(RoomError::ClaimFailed(
                             "Player not found in lobby".to_string(),
@@ -1018,11 +1439,9 @@ pub async fn handle_room_message(
             };
 
             // Check if has prize and not claimed
-            if player_state.prize.is_none()
-                || player_state.prize.unwrap() <= 0.0
-                || player_state.has_claimed()
-            {
-                let _ = manager::send_to_connection(
+            if !player_state.has_prize() || player_state.has_claimed() {
+                let _ = broadcast::send_room_message(
+                    state,
                     conn,
                     &RoomServerMessage::from(RoomError::ClaimFailed(
                         "No prize available to claim".to_string(),
@@ -1045,7 +1464,8 @@ pub async fn handle_room_message(
                 )
                 .await
             {
-                let _ = manager::send_to_connection(
+                let _ = broadcast::send_room_message(
+                    state,
                     conn,
                     &RoomServerMessage::from(RoomError::ClaimFailed(
                         "Failed to update claim state".to_string(),
@@ -1060,7 +1480,8 @@ pub async fn handle_room_message(
                 .subtract_current_amount(lobby_id, prize)
                 .await
             {
-                let _ = manager::send_to_connection(
+                let _ = broadcast::send_room_message(
+                    state,
                     conn,
                     &RoomServerMessage::from(RoomError::ClaimFailed(
                         "Failed to update lobby amount".to_string(),
@@ -1071,7 +1492,8 @@ pub async fn handle_room_message(
             }
 
             // Send success
-            let _ = manager::send_to_connection(conn, &RoomServerMessage::ClaimSuccess).await;
+            let _ =
+                broadcast::send_room_message(state, conn, &RoomServerMessage::ClaimSuccess).await;
         }
     }
 }
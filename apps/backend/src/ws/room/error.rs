@@ -16,7 +16,15 @@ pub enum RoomError {
     KickFailed(String),
     SendMessageFailed(String),
     ReactionFailed(String),
+    ChatHistoryFailed(String),
+    MarkReadFailed(String),
     ClaimFailed(String),
+    PredictionFailed(String),
+    ReadyFailed(String),
+    /// The lobby doesn't allow spectators and its game has started.
+    SpectatorsNotAllowed,
+    /// New games are paused for maintenance; carries the player-facing message.
+    Maintenance(String),
     /// Postgres metadata for the lobby is missing.
     MetadataMissing,
     /// Lobby runtime state or lobby itself was not found.
@@ -43,11 +51,19 @@ impl fmt::Display for RoomError {
             RoomError::KickFailed(s) => write!(f, "kick failed: {}", s),
             RoomError::SendMessageFailed(s) => write!(f, "send message failed: {}", s),
             RoomError::ReactionFailed(s) => write!(f, "reaction failed: {}", s),
+            RoomError::ChatHistoryFailed(s) => write!(f, "chat history failed: {}", s),
+            RoomError::MarkReadFailed(s) => write!(f, "mark read failed: {}", s),
             RoomError::MetadataMissing => write!(f, "lobby metadata missing from database"),
             RoomError::NotFound => write!(f, "lobby not found"),
             RoomError::InvalidMessage => write!(f, "invalid message"),
             RoomError::Internal(s) => write!(f, "internal error: {}", s),
             RoomError::ClaimFailed(s) => write!(f, "claim reward failed: {}", s),
+            RoomError::PredictionFailed(s) => write!(f, "prediction failed: {}", s),
+            RoomError::ReadyFailed(s) => write!(f, "ready failed: {}", s),
+            RoomError::SpectatorsNotAllowed => {
+                write!(f, "spectators are not allowed in this lobby")
+            }
+            RoomError::Maintenance(s) => write!(f, "{}", s),
         }
     }
 }
@@ -67,12 +83,18 @@ impl RoomError {
             RoomError::KickFailed(_) => "KICK_FAILED",
             RoomError::SendMessageFailed(_) => "SEND_MESSAGE_FAILED",
             RoomError::ReactionFailed(_) => "REACTION_FAILED",
+            RoomError::ChatHistoryFailed(_) => "CHAT_HISTORY_FAILED",
+            RoomError::MarkReadFailed(_) => "MARK_READ_FAILED",
             RoomError::NotAuthenticated => "NOT_AUTHENTICATED",
             RoomError::MetadataMissing => "METADATA_MISSING",
             RoomError::NotFound => "NOT_FOUND",
             RoomError::InvalidMessage => "INVALID_MESSAGE",
             RoomError::Internal(_) => "INTERNAL_ERROR",
             RoomError::ClaimFailed(_) => "CLAIM_FAILED",
+            RoomError::PredictionFailed(_) => "PREDICTION_FAILED",
+            RoomError::ReadyFailed(_) => "READY_FAILED",
+            RoomError::SpectatorsNotAllowed => "SPECTATORS_NOT_ALLOWED",
+            RoomError::Maintenance(_) => "MAINTENANCE",
         }
     }
 }
@@ -11,15 +11,23 @@ use axum::{
     extract::{ConnectInfo, Path, State, WebSocketUpgrade, ws::Message},
     response::IntoResponse,
 };
-use futures::StreamExt;
+use futures::{SinkExt, StreamExt};
 use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::sync::Mutex as TokioMutex;
 use uuid::Uuid;
 
-use crate::ws::{broadcast_room, broadcast_user, core::manager};
+use crate::announcements;
+use crate::ws::{
+    broadcast_room_to, broadcast_user,
+    core::{ConnectionSink, Negotiated, WsProtocol, manager},
+    send_room_message, send_room_snapshot,
+};
 use crate::{auth::extractors::WsAuth, db::lobby_chat::LobbyChatRepository};
-use crate::{db::lobby::LobbyRepository, models::LobbyInfo};
+use crate::{
+    db::lobby::LobbyRepository,
+    models::{ChatMessageView, Lobby, LobbyInfo},
+};
 use crate::{
     db::{game::GameRepository, user::UserRepository},
     middleware::{ApiRateLimit, check_rate_limit},
@@ -27,26 +35,34 @@ use crate::{
 use crate::{
     db::{
         join_request::JoinRequestRepository, lobby_state::LobbyStateRepository,
-        player_state::PlayerStateRepository,
+        player_state::PlayerStateRepository, signed_result::SignedResultRepository,
+        spectator::SpectatorRepository,
     },
     models::LobbyExtended,
     state::{AppState, ConnectionContext, ConnectionInfo},
 };
 use crate::{
-    models::LobbyStatus,
-    ws::room::{RoomError, engine::handle_room_message, messages::RoomServerMessage},
+    errors::AppError,
+    games::{Audience, GameError},
+    lobby_service::LobbyService,
+    models::{LobbyStatus, player_state::PlayerStatus},
+    ws::room::{RoomContext, RoomError, engine::handle_room_message, messages::RoomServerMessage},
 };
 
 /// HTTP endpoint: Upgrades an HTTP request to a WebSocket connection for lobby/game communication.
 ///
 /// This is the entry point for all WebSocket connections. After rate limiting and authentication,
 /// it upgrades the connection and hands off to `handle_socket` for message handling.
+/// Clients asking for an unsupported protocol version are closed right after the upgrade.
+/// Connections the lobby won't take (see `authorize_room_connection`) and
+/// wallets the access lists keep out are refused with 403.
 pub async fn room_handler(
     ws: WebSocketUpgrade,
     Path(lobby_path): Path<String>,
     State(state): State<AppState>,
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
     WsAuth(auth): WsAuth,
+    protocol: WsProtocol,
 ) -> Result<impl IntoResponse, (axum::http::StatusCode, String)> {
     // Determine optional user id from auth claims
     let auth_user_id = auth.and_then(|claims| claims.user_id().ok());
@@ -57,7 +73,87 @@ pub async fn room_handler(
         return Err((code, msg));
     }
 
-    Ok(ws.on_upgrade(move |socket| handle_socket(socket, lobby_path, auth_user_id, state)))
+    let lobby = LobbyRepository::new(state.postgres.clone())
+        .find_by_path(&lobby_path)
+        .await
+        .ok();
+    if let Some(lobby) = &lobby {
+        authorize_room_connection(&state, lobby, auth_user_id)
+            .await
+            .map_err(|e| e.to_response())?;
+    }
+
+    Ok(protocol.upgrade(ws, move |socket, negotiated| {
+        handle_socket(socket, lobby_path, lobby, auth_user_id, state, negotiated)
+    }))
+}
+
+/// Check that a connection may attach to the lobby's room.
+///
+/// Members (the creator and anyone with a player state) always may. Anyone
+/// may watch a finished lobby's replay. Otherwise signed-in users may come in
+/// to join while the lobby is waiting, and everyone else is a spectator: the
+/// lobby must allow them, and a new one must fit within its game's spectator
+/// ratio. A user with a spectator connection still open is already counted
+/// and may reattach regardless.
+pub(crate) async fn authorize_room_connection(
+    state: &AppState,
+    lobby: &Lobby,
+    user_id: Option<Uuid>,
+) -> Result<(), AppError> {
+    let player_repo = PlayerStateRepository::new(state.redis.clone());
+
+    if let Some(user_id) = user_id {
+        if user_id == lobby.creator_id || player_repo.exists(lobby.id(), user_id).await? {
+            return Ok(());
+        }
+        if lobby.status == LobbyStatus::Waiting {
+            return Ok(());
+        }
+    }
+    if lobby.status == LobbyStatus::Finished {
+        return Ok(());
+    }
+
+    if !lobby.spectators_allowed {
+        return Err(AppError::Forbidden(
+            "Spectators are not allowed in this lobby".into(),
+        ));
+    }
+    if state
+        .config
+        .spectator_ratios
+        .for_game(lobby.game_id)
+        .is_none()
+    {
+        return Ok(());
+    }
+    if let Some(user_id) = user_id
+        && SpectatorRepository::new(state.redis.clone())
+            .is_watching(user_id, lobby.id())
+            .await?
+    {
+        return Ok(());
+    }
+
+    let players = player_repo.list_players(lobby.id()).await?;
+    let active = players
+        .iter()
+        .filter(|p| p.status == PlayerStatus::Joined)
+        .count();
+    let spectators = LobbyService::new(state.clone())
+        .spectator_count(lobby.id(), &players)
+        .await;
+    if !state
+        .config
+        .spectator_ratios
+        .admits(lobby.game_id, active, spectators)
+    {
+        return Err(AppError::Forbidden(
+            "This lobby has reached its spectator limit".into(),
+        ));
+    }
+    Ok(())
 }
 
 /// Core WebSocket handler: Manages connection lifecycle and routes messages.
@@ -74,18 +170,17 @@ pub async fn room_handler(
 async fn handle_socket(
     socket: axum::extract::ws::WebSocket,
     lobby_path: String,
+    lobby: Option<Lobby>,
     auth_user_id: Option<Uuid>,
     state: AppState,
+    negotiated: Negotiated,
 ) {
     let (sender, mut receiver) = socket.split();
-    let connection_id = Uuid::new_v4();
 
-    let lobby_repo = LobbyRepository::new(state.postgres.clone());
-
-    // Fetch lobby by path with joined user and game data
-    let lobby = match lobby_repo.find_by_path(&lobby_path).await {
-        Ok(l) => l,
-        Err(_) => {
+    // Lobby was looked up by path before the upgrade
+    let lobby = match lobby {
+        Some(l) => l,
+        None => {
             let err = RoomError::NotFound;
             tracing::error!("Lobby not found for path {}: {:?}", lobby_path, err);
             return;
@@ -93,17 +188,239 @@ async fn handle_socket(
     };
 
     let lobby_id = lobby.id;
+    let contract_address = lobby.contract_address.clone();
+
+    let Some(room) = open_room_connection(
+        &state,
+        lobby,
+        auth_user_id,
+        ConnectionSink::WebSocket(sender),
+        &negotiated,
+    )
+    .await
+    else {
+        return;
+    };
+    let conn = Arc::clone(&room.conn);
+
+    // Main message loop
+    while let Some(msg) = receiver.next().await {
+        match msg {
+            Ok(Message::Text(text)) => {
+                // Parse message as JSON first
+                let parsed_msg: serde_json::Value = match serde_json::from_str(&text) {
+                    Ok(msg) => msg,
+                    Err(_) => {
+                        tracing::warn!("Invalid JSON message received");
+                        continue;
+                    }
+                };
+
+                // Try parsing as RoomClientMessage
+                if let Ok(room_msg) = serde_json::from_str(&text) {
+                    let player_repo = PlayerStateRepository::new(state.redis.clone());
+                    let lobby_state_repo = LobbyStateRepository::new(state.redis.clone());
+                    handle_room_message(
+                        room_msg,
+                        lobby_id,
+                        auth_user_id,
+                        &conn,
+                        &state,
+                        &player_repo,
+                        &lobby_state_repo,
+                        contract_address.as_ref(),
+                    )
+                    .await;
+                    continue;
+                }
+
+                // Try parsing as game action message wrapped in "game" object
+                // Format: { "game": { "type": "submitWord", "word": "hello" }, "clientActionId": "a1" }
+                if let Some(game_action) = parsed_msg.get("game") {
+                    let client_action_id = parsed_msg
+                        .get("clientActionId")
+                        .and_then(|id| id.as_str())
+                        .map(str::to_string);
+
+                    let result = if let Some(user_id) = auth_user_id {
+                        handle_game_action(
+                            &state,
+                            &conn,
+                            lobby_id,
+                            user_id,
+                            game_action.clone(),
+                            client_action_id.clone(),
+                        )
+                        .await
+                    } else {
+                        tracing::warn!("Game action from unauthenticated user");
+                        Err(RoomError::NotAuthenticated.code())
+                    };
+
+                    // Successful actions are acked before their events go out
+                    if let (Some(client_action_id), Err(code)) = (client_action_id, result) {
+                        send_action_ack(&conn, client_action_id, Some(code)).await;
+                    }
+                    continue;
+                }
+
+                // Unknown message type - log and ignore
+                tracing::warn!(
+                    "Unknown message type received: {:?}",
+                    parsed_msg.get("type")
+                );
+            }
+
+            Ok(Message::Binary(_)) => {}
+            Ok(Message::Close(_)) | Ok(Message::Pong(_)) | Ok(Message::Ping(_)) => {}
+            Err(e) => {
+                tracing::warn!("ws recv err: {}", e);
+                break;
+            }
+        }
+    }
+
+    close_room_connection(&state, room).await;
+}
+
+/// A registered room connection, torn down by `close_room_connection`
+pub(crate) struct RoomConnection {
+    pub conn: Arc<ConnectionInfo>,
+    lobby_id: Uuid,
+    /// Signed-in spectator tracked for this connection
+    spectating: Option<Uuid>,
+}
+
+/// Register a room connection and send its bootstrap and any active
+/// announcements. Shared with the SSE stream.
+///
+/// Returns None when the bootstrap fails; the error has been sent and the
+/// connection is already unregistered.
+pub(crate) async fn open_room_connection(
+    state: &AppState,
+    lobby: Lobby,
+    auth_user_id: Option<Uuid>,
+    sender: ConnectionSink,
+    negotiated: &Negotiated,
+) -> Option<RoomConnection> {
+    let connection_id = Uuid::new_v4();
+    let lobby_id = lobby.id;
+
+    let player_repo = PlayerStateRepository::new(state.redis.clone());
+    let room_context = RoomContext::load(&player_repo, lobby_id, auth_user_id).await;
 
     let conn = Arc::new(ConnectionInfo {
         connection_id,
         user_id: auth_user_id,
         context: ConnectionContext::Room(lobby_id),
+        protocol: negotiated.version,
+        compression: negotiated.compression,
         sender: Arc::new(TokioMutex::new(sender)),
+        room_context: std::sync::RwLock::new(Some(room_context)),
     });
 
     // Register the connection
-    manager::register_connection(&state, connection_id, conn.clone()).await;
+    manager::register_connection(state, connection_id, conn.clone()).await;
+
+    // Signed-in spectators are tracked so they can find their way back
+    let spectating = auth_user_id.filter(|_| !room_context.is_participant());
+    if let Some(user_id) = spectating
+        && let Err(e) = SpectatorRepository::new(state.redis.clone())
+            .add(user_id, lobby_id)
+            .await
+    {
+        tracing::warn!(
+            "Failed to track spectator {} in {}: {}",
+            user_id,
+            lobby_id,
+            e
+        );
+    }
+
+    if let Err(err) = send_room_bootstrap(state, &conn, lobby, auth_user_id).await {
+        tracing::error!("Lobby state not found for id {}: {:?}", lobby_id, err);
+        let msg = RoomServerMessage::from(err);
+        let _ = send_room_message(state, &conn, &msg).await;
+        manager::unregister_connection(state, &connection_id).await;
+        untrack_spectator(state, spectating, lobby_id).await;
+        return None;
+    }
+    for announcement in announcements::active_for_new_connection(state).await {
+        let msg = RoomServerMessage::Announcement(announcement);
+        let _ = send_room_message(state, &conn, &msg).await;
+    }
+
+    Some(RoomConnection {
+        conn,
+        lobby_id,
+        spectating,
+    })
+}
+
+/// Cleanup on disconnect
+pub(crate) async fn close_room_connection(state: &AppState, room: RoomConnection) {
+    manager::unregister_connection(state, &room.conn.connection_id).await;
+    untrack_spectator(state, room.spectating, room.lobby_id).await;
+
+    // The room gets the roster as it stands after this connection
+    state.room_state.changed(state, room.lobby_id).await;
+}
+
+/// Close the spectators' connections to a lobby that doesn't allow them,
+/// once its game starts. Signed-in users may come in while the lobby is
+/// waiting; those who didn't join are spectators by now.
+///
+/// Each gets an error and then its transport is closed; the connection is
+/// torn down by `close_room_connection` as the socket or stream ends.
+pub(crate) async fn close_spectator_connections(state: &AppState, lobby_id: Uuid) {
+    let spectators: Vec<Arc<ConnectionInfo>> = {
+        let indices = state.indices.lock().await;
+        let Some(conn_ids) = indices.get_lobby_connections(&lobby_id) else {
+            return;
+        };
+        let conns = state.connections.lock().await;
+        conn_ids
+            .iter()
+            .filter_map(|conn_id| conns.get(conn_id))
+            .filter(|conn| !conn.room_context().is_some_and(|c| c.is_participant()))
+            .cloned()
+            .collect()
+    };
 
+    let msg = RoomServerMessage::from(RoomError::SpectatorsNotAllowed);
+    for conn in spectators {
+        let _ = send_room_message(state, &conn, &msg).await;
+        let _ = conn.sender.lock().await.close().await;
+    }
+}
+
+async fn untrack_spectator(state: &AppState, spectating: Option<Uuid>, lobby_id: Uuid) {
+    let Some(user_id) = spectating else {
+        return;
+    };
+    if let Err(e) = SpectatorRepository::new(state.redis.clone())
+        .remove(user_id, lobby_id)
+        .await
+    {
+        tracing::warn!(
+            "Failed to untrack spectator {} in {}: {}",
+            user_id,
+            lobby_id,
+            e
+        );
+    }
+}
+
+/// Send a room connection its snapshots: the lobby bootstrap, plus the game
+/// state of a running game or the standings of a finished one.
+///
+/// Used on connect and when a client asks to resync after missing events.
+pub(crate) async fn send_room_bootstrap(
+    state: &AppState,
+    conn: &Arc<ConnectionInfo>,
+    lobby: Lobby,
+    auth_user_id: Option<Uuid>,
+) -> Result<(), RoomError> {
     let game_repo = GameRepository::new(state.postgres.clone());
     let user_repo = UserRepository::new(state.postgres.clone());
     let lobby_state_repo = LobbyStateRepository::new(state.redis.clone());
@@ -111,8 +428,7 @@ async fn handle_socket(
     let jr_repo = JoinRequestRepository::new(state.redis.clone());
 
     let chat_repo = LobbyChatRepository::new(state.redis.clone());
-
-    let contract_address = lobby.contract_address.clone();
+    let lobby_id = lobby.id;
 
     let (
         game,
@@ -121,27 +437,50 @@ async fn handle_socket(
         players_result,
         join_requests_result,
         chat_history_result,
+        read_receipts_result,
     ) = tokio::join!(
         game_repo.find_by_id(lobby.game_id),
         user_repo.find_by_id(lobby.creator_id),
         lobby_state_repo.get_state(lobby_id),
-        player_repo.get_all_in_lobby(lobby_id),
+        player_repo.list_players(lobby_id),
         jr_repo.list(lobby_id),
-        chat_repo.get_history(lobby_id, Some(50))
+        chat_repo.get_history(lobby_id, Some(50)),
+        chat_repo.get_read_receipts(lobby_id)
     );
 
     // Validate we have the minimum required data
     match (state_info_result, game, creator) {
         (Ok(state_info), Ok(game), Ok(creator)) => {
+            let ranked = lobby.practice_bot.is_none();
             let lobby_ext = LobbyExtended::from_parts(lobby, state_info);
             let lobby_status = lobby_ext.status;
             let players = players_result.unwrap_or_default();
+            if lobby_ext.anonymize_players {
+                let finished = lobby_status == LobbyStatus::Finished
+                    || match state.active_game(lobby_id).await {
+                        Some(engine) => engine.lock().await.is_finished(),
+                        None => false,
+                    };
+                if !finished
+                    && let Err(e) = state
+                        .room_pseudonyms
+                        .track(&state.redis, lobby_id, &players)
+                        .await
+                {
+                    tracing::warn!("Failed to name players of {}: {}", lobby_id, e);
+                }
+            }
             let join_requests = join_requests_result
                 .unwrap_or_default()
                 .into_iter()
                 .map(Into::into)
                 .collect();
-            let chat_history = chat_history_result.unwrap_or_default();
+            let chat_history = chat_history_result
+                .unwrap_or_default()
+                .into_iter()
+                .map(|message| ChatMessageView::new(message, auth_user_id))
+                .collect();
+            let read_receipts = read_receipts_result.unwrap_or_default();
 
             let lobby_info = LobbyInfo {
                 lobby: lobby_ext,
@@ -149,35 +488,45 @@ async fn handle_socket(
                 creator,
             };
 
-            let _ = manager::send_to_connection(
-                &conn,
+            let _ = send_room_snapshot(
+                state,
+                lobby_id,
+                conn,
                 &RoomServerMessage::LobbyBootstrap {
                     lobby_info,
                     players,
                     join_requests,
                     chat_history,
+                    read_receipts,
                 },
             )
             .await;
 
             // If game is in progress, send GameState for reconnecting user
-            if lobby_status == LobbyStatus::InProgress {
-                let active_games = state.active_games.lock().await;
-                if let Some(game_engine) = active_games.get(&lobby_id) {
-                    if let Ok(game_state) = game_engine.get_game_state(auth_user_id).await {
-                        let _ = manager::send_to_connection(
-                            &conn,
-                            &RoomServerMessage::GameState { game_state },
-                        )
-                        .await;
-                    }
+            if lobby_status == LobbyStatus::InProgress
+                && let Some(game_engine) = state.active_game(lobby_id).await
+            {
+                // Spectators get the state without any player's private fields
+                let viewer = auth_user_id.filter(|_| {
+                    conn.room_context()
+                        .is_some_and(|context| context.is_participant())
+                });
+                let game_state = game_engine.lock().await.get_game_state(viewer).await;
+                if let Ok(game_state) = game_state {
+                    let _ = send_room_snapshot(
+                        state,
+                        lobby_id,
+                        conn,
+                        &RoomServerMessage::GameState { game_state },
+                    )
+                    .await;
                 }
             }
 
             // If game is finished, send FinalStanding and GameOver for authenticated users
             if lobby_status == LobbyStatus::Finished {
                 // Get all players sorted by rank for standings
-                if let Ok(mut standings) = player_repo.get_all_in_lobby(lobby_id).await {
+                if let Ok(mut standings) = player_repo.list_players(lobby_id).await {
                     // Sort by rank (players with rank come first, sorted ascending)
                     standings.sort_by(|a, b| match (&a.rank, &b.rank) {
                         (Some(ra), Some(rb)) => ra.cmp(rb),
@@ -186,10 +535,13 @@ async fn handle_socket(
                         (None, None) => std::cmp::Ordering::Equal,
                     });
 
-                    let _ = manager::send_to_connection(
-                        &conn,
+                    let _ = send_room_snapshot(
+                        state,
+                        lobby_id,
+                        conn,
                         &RoomServerMessage::FinalStanding {
                             standings: standings.clone(),
+                            ranked,
                         },
                     )
                     .await;
@@ -198,8 +550,21 @@ async fn handle_socket(
                     if let Some(user_id) = auth_user_id {
                         if let Some(player) = standings.iter().find(|p| p.user_id == user_id) {
                             if let Some(rank) = player.rank {
-                                let _ = manager::send_to_connection(
-                                    &conn,
+                                let signed_results =
+                                    SignedResultRepository::new(state.postgres.clone())
+                                        .find_by_lobby(lobby_id)
+                                        .await
+                                        .unwrap_or_else(|e| {
+                                            tracing::error!(
+                                                "Failed to load signed results for {}: {}",
+                                                lobby_id,
+                                                e
+                                            );
+                                            None
+                                        });
+                                let _ = send_room_message(
+                                    state,
+                                    conn,
                                     &RoomServerMessage::GameOver {
                                         rank,
                                         prize: if player.has_claimed() {
@@ -208,6 +573,7 @@ async fn handle_socket(
                                             player.prize
                                         },
                                         wars_point: player.wars_point.unwrap_or(0.0),
+                                        signed_results,
                                     },
                                 )
                                 .await;
@@ -217,87 +583,10 @@ async fn handle_socket(
                 }
             }
         }
-        _ => {
-            let err = RoomError::NotFound;
-            tracing::error!("Lobby state not found for id {}: {:?}", lobby_id, err);
-            let msg = RoomServerMessage::from(err);
-            let _ = manager::send_to_connection(&conn, &msg).await;
-            manager::unregister_connection(&state, &connection_id).await;
-            return;
-        }
-    }
-
-    // Main message loop
-    while let Some(msg) = receiver.next().await {
-        match msg {
-            Ok(Message::Text(text)) => {
-                // Parse message as JSON first
-                let parsed_msg: serde_json::Value = match serde_json::from_str(&text) {
-                    Ok(msg) => msg,
-                    Err(_) => {
-                        tracing::warn!("Invalid JSON message received");
-                        continue;
-                    }
-                };
-
-                // Try parsing as RoomClientMessage
-                if let Ok(room_msg) = serde_json::from_str(&text) {
-                    let player_repo = PlayerStateRepository::new(state.redis.clone());
-                    let lobby_state_repo = LobbyStateRepository::new(state.redis.clone());
-                    handle_room_message(
-                        room_msg,
-                        lobby_id,
-                        auth_user_id,
-                        &conn,
-                        &state,
-                        &player_repo,
-                        &lobby_state_repo,
-                        contract_address.as_ref(),
-                    )
-                    .await;
-                    continue;
-                }
-
-                // Try parsing as game action message wrapped in "game" object
-                // Format: { "game": { "type": "submitWord", "word": "hello" } }
-                if let Some(game_action) = parsed_msg.get("game") {
-                    if let Some(user_id) = auth_user_id {
-                        handle_game_action(&state, lobby_id, user_id, game_action.clone()).await;
-                    } else {
-                        tracing::warn!("Game action from unauthenticated user");
-                    }
-                    continue;
-                }
-
-                // Unknown message type - log and ignore
-                tracing::warn!(
-                    "Unknown message type received: {:?}",
-                    parsed_msg.get("type")
-                );
-            }
-
-            Ok(Message::Binary(_)) => {}
-            Ok(Message::Close(_)) | Ok(Message::Pong(_)) | Ok(Message::Ping(_)) => {}
-            Err(e) => {
-                tracing::warn!("ws recv err: {}", e);
-                break;
-            }
-        }
+        _ => return Err(RoomError::NotFound),
     }
 
-    // Cleanup on disconnect
-    manager::unregister_connection(&state, &connection_id).await;
-
-    // Broadcast final player list to lobby
-    let player_repo = PlayerStateRepository::new(state.redis.clone());
-    if let Ok(players) = player_repo.get_all_in_lobby(lobby_id).await {
-        crate::ws::broadcast::broadcast_room(
-            &state,
-            lobby_id,
-            &RoomServerMessage::PlayerUpdated { players },
-        )
-        .await;
-    }
+    Ok(())
 }
 
 /// Handle a game action message from a player
@@ -307,44 +596,78 @@ async fn handle_socket(
 ///
 /// Response events are wrapped back in the "game" format:
 /// { "game": { "type": "...", ...fields } }
+///
+/// If the client tagged the action, an accepted `ActionAck` goes to `conn`
+/// before the events are broadcast. Returns the error code on rejection.
 async fn handle_game_action(
     state: &AppState,
+    conn: &Arc<ConnectionInfo>,
     lobby_id: Uuid,
     user_id: Uuid,
     action: serde_json::Value,
-) {
+    client_action_id: Option<String>,
+) -> Result<(), &'static str> {
     // Get the active game engine for this lobby
-    let mut active_games = state.active_games.lock().await;
-    if let Some(game_engine) = active_games.get_mut(&lobby_id) {
-        // Handle the action and get response events
-        match game_engine.handle_action(user_id, action).await {
-            Ok(events) => {
-                // Broadcast all response events wrapped in "game" object to room
-                for event in events {
-                    // Wrap event in "game" object: { "game": { "type": "...", ...fields } }
-                    let wrapped_msg = serde_json::json!({
-                        "game": event
-                    });
+    let Some(game_engine) = state.active_game(lobby_id).await else {
+        tracing::warn!("No active game found for lobby {}", lobby_id);
+        return Err(GameError::GameNotStarted.code());
+    };
+    let mut game_engine = game_engine.lock().await;
 
-                    let game_msg = crate::ws::core::message::JsonMessage::from(wrapped_msg);
-                    let _ = broadcast_room(state, lobby_id, &game_msg).await;
-                }
+    // Handle the action and get response events
+    match game_engine.handle_action(user_id, action).await {
+        Ok(events) => {
+            if let Some(client_action_id) = client_action_id {
+                send_action_ack(conn, client_action_id, None).await;
             }
-            Err(e) => {
-                tracing::error!("Game action handling failed for lobby {}: {}", lobby_id, e);
 
-                // Send error message back to the specific user
-                let wrapped_error = serde_json::json!({
-                    "game": {
-                        "type": "error",
-                        "message": e.to_string()
-                    }
+            // Broadcast all response events wrapped in "game" object to their audience
+            for mut event in events {
+                let audience = Audience::take(&mut event);
+                // Wrap event in "game" object: { "game": { "type": "...", ...fields } }
+                let wrapped_msg = serde_json::json!({
+                    "game": event
                 });
-                let game_error = crate::ws::core::message::JsonMessage::from(wrapped_error);
-                let _ = broadcast_user(state, user_id, &game_error).await;
+
+                let game_msg = crate::ws::core::message::JsonMessage::from(wrapped_msg);
+                let _ = broadcast_room_to(state, lobby_id, audience, &game_msg).await;
             }
+            Ok(())
         }
-    } else {
-        tracing::warn!("No active game found for lobby {}", lobby_id);
+        Err(e) => {
+            tracing::error!("Game action handling failed for lobby {}: {}", lobby_id, e);
+
+            // Send error message back to the specific user
+            let wrapped_error = serde_json::json!({
+                "game": {
+                    "type": "error",
+                    "message": e.to_string()
+                }
+            });
+            let game_error = crate::ws::core::message::JsonMessage::from(wrapped_error);
+            let _ = broadcast_user(state, user_id, &game_error).await;
+
+            Err(action_error_code(&e))
+        }
+    }
+}
+
+/// Error code reported in a rejected `ActionAck`
+fn action_error_code(error: &AppError) -> &'static str {
+    match error {
+        AppError::GameError(e) => e.code(),
+        AppError::BadRequest(_) | AppError::Deserialization(_) => "INVALID_ACTION",
+        AppError::Forbidden(_) | AppError::Unauthorized(_) => "FORBIDDEN",
+        AppError::RateLimited(_) => "RATE_LIMITED",
+        _ => "INTERNAL_ERROR",
     }
 }
+
+async fn send_action_ack(conn: &Arc<ConnectionInfo>, client_action_id: String, code: Option<&str>) {
+    let ack = RoomServerMessage::ActionAck {
+        client_action_id,
+        accepted: code.is_none(),
+        code: code.map(str::to_string),
+    };
+    let _ = manager::send_to_connection(conn, &ack).await;
+}
@@ -1,15 +1,24 @@
 // Room message types (client -> server, server -> client)
+use crate::announcements::Announcement;
 use crate::db::join_request::JoinRequest;
 use crate::models::lobby_state::LobbyStatus;
-use crate::models::{ChatMessage, LobbyInfo, PlayerState};
+use crate::models::{
+    ChatMessage, ChatMessageView, LobbyInfo, PlayerState, PredictionOutcome, SignedResults,
+};
 use crate::ws::room::error::RoomError;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
 use uuid::Uuid;
 
 /// Messages sent from clients to the lobby websocket.
 #[derive(Debug, Clone, serde::Deserialize)]
 #[serde(tag = "type", rename_all = "camelCase")]
 pub enum RoomClientMessage {
-    Join,
+    /// Join the lobby; a valid `invite_token` admits the bearer without a join request
+    #[serde(rename_all = "camelCase")]
+    Join {
+        invite_token: Option<String>,
+    },
     Leave,
     UpdateLobbyStatus {
         status: LobbyStatus,
@@ -49,6 +58,31 @@ pub enum RoomClientMessage {
         message_id: Uuid,
         emoji: String,
     },
+    /// Load a page of older (`before`) or newer (`after`) chat messages;
+    /// with neither, the newest page
+    #[serde(rename_all = "camelCase")]
+    LoadChatHistory {
+        before: Option<Uuid>,
+        after: Option<Uuid>,
+        limit: Option<usize>,
+    },
+    /// The user has read chat up to `message_id`
+    #[serde(rename_all = "camelCase")]
+    MarkRead {
+        message_id: Uuid,
+    },
+    /// The user started or stopped typing in chat
+    #[serde(rename_all = "camelCase")]
+    Typing {
+        is_typing: bool,
+    },
+    /// Player is ready for the game to start; only during a ready check
+    Ready,
+    /// Spectator predicts who will win; allowed until the game starts
+    #[serde(rename_all = "camelCase")]
+    PredictWinner {
+        user_id: Uuid,
+    },
     /// Request to claim a prize reward
     #[serde(rename_all = "camelCase")]
     ClaimReward {
@@ -58,6 +92,13 @@ pub enum RoomClientMessage {
     Ping {
         ts: u64,
     },
+    /// Clock sync probe; the server echoes `ts` back with its own time
+    ServerTime {
+        #[serde(default)]
+        ts: Option<u64>,
+    },
+    /// Client saw a gap in room event `seq`s; the server resends the room snapshots
+    Resync,
 }
 
 /// Messages broadcast by the lobby server to connected clients.
@@ -72,7 +113,19 @@ pub enum RoomServerMessage {
         lobby_info: LobbyInfo,
         players: Vec<PlayerState>,
         join_requests: Vec<JoinRequest>,
-        chat_history: Vec<ChatMessage>,
+        chat_history: Vec<ChatMessageView>,
+        /// Last-read message per user
+        read_receipts: HashMap<Uuid, Uuid>,
+    },
+
+    /// Platform-wide notice from the admins
+    Announcement(Announcement),
+
+    /// The creator left for good and the lobby passed to another player
+    #[serde(rename_all = "camelCase")]
+    CreatorChanged {
+        previous_creator_id: Uuid,
+        creator_id: Uuid,
     },
 
     /// Generic lobby state change
@@ -80,13 +133,56 @@ pub enum RoomServerMessage {
     LobbyStatusChanged {
         status: LobbyStatus,
         participant_count: usize,
-        current_amount: Option<f64>,
+        #[serde(with = "rust_decimal::serde::float_option")]
+        current_amount: Option<Decimal>,
+    },
+
+    /// The lobby's runtime state, roster and pot after a burst of changes
+    #[serde(rename_all = "camelCase")]
+    RoomStateUpdated {
+        status: LobbyStatus,
+        participant_count: usize,
+        #[serde(with = "rust_decimal::serde::float_option")]
+        current_amount: Option<Decimal>,
+        players: Vec<PlayerState>,
+        started_at: Option<i64>,
+        finished_at: Option<i64>,
+        updated_at: i64,
     },
 
-    /// Countdown updates
+    /// Countdown updates; `deadline_ms` is when the game starts (None = cancelled)
     #[serde(rename_all = "camelCase")]
     StartCountdown {
         seconds_remaining: Option<u8>,
+        deadline_ms: Option<u64>,
+        server_time_ms: u64,
+    },
+
+    /// Ready check opened: players have until `deadline_ms` to send `ready`
+    #[serde(rename_all = "camelCase")]
+    ReadyCheck {
+        timeout_secs: u64,
+        deadline_ms: u64,
+        server_time_ms: u64,
+    },
+
+    /// A player readied up
+    #[serde(rename_all = "camelCase")]
+    PlayerReady {
+        user_id: Uuid,
+        ready_count: usize,
+        player_count: usize,
+    },
+
+    /// Every player is ready; the start countdown follows
+    AllReady,
+
+    /// The ready check ran out with enough players ready; the start countdown
+    /// follows. `unready` were removed from the lobby if `dropped`.
+    #[serde(rename_all = "camelCase")]
+    ReadyCheckTimedOut {
+        unready: Vec<Uuid>,
+        dropped: bool,
     },
 
     #[serde(rename_all = "camelCase")]
@@ -138,12 +234,42 @@ pub enum RoomServerMessage {
         emoji: String,
     },
 
+    /// Someone else in the room started or stopped typing (unsequenced)
+    #[serde(rename_all = "camelCase")]
+    UserTyping {
+        user_id: Uuid,
+        is_typing: bool,
+    },
+
+    /// A user's read pointer moved forward
+    #[serde(rename_all = "camelCase")]
+    ReadReceipt {
+        user_id: Uuid,
+        message_id: Uuid,
+    },
+
+    /// Personal reply to `LoadChatHistory`, oldest message first
+    #[serde(rename_all = "camelCase")]
+    ChatHistory {
+        messages: Vec<ChatMessageView>,
+        next_cursor: Option<Uuid>,
+        has_more: bool,
+    },
+
     /// Personal pong response; elapsed_ms = now.saturating_sub(client_ts)
     #[serde(rename_all = "camelCase")]
     Pong {
         elapsed_ms: u64,
     },
 
+    /// Reply to `ServerTime`; clients estimate their offset as
+    /// `server_time_ms - (client_ts + rtt / 2)`
+    #[serde(rename_all = "camelCase")]
+    ServerTime {
+        server_time_ms: u64,
+        client_ts: Option<u64>,
+    },
+
     PlayerUpdated {
         players: Vec<PlayerState>,
     },
@@ -159,7 +285,16 @@ pub enum RoomServerMessage {
     // Shared Game Events (used across all games)
     // ========================================================================
     /// Game has started - broadcast to room
-    GameStarted,
+    /// `seats` is the turn order; with `seating_seed` anyone can redraw it
+    /// using `seat_players`
+    #[serde(rename_all = "camelCase")]
+    GameStarted {
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        seats: Vec<Uuid>,
+        /// The seed as a decimal string (too big for a JSON number)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        seating_seed: Option<String>,
+    },
 
     /// Game failed to start - broadcast to room
     GameStartFailed {
@@ -167,8 +302,10 @@ pub enum RoomServerMessage {
     },
 
     /// Final standings when game ends - broadcast to room
+    /// `ranked` is false for practice games, which award nothing
     FinalStanding {
         standings: Vec<PlayerState>,
+        ranked: bool,
     },
 
     /// Game over for a specific user - sent to individual user
@@ -176,13 +313,49 @@ pub enum RoomServerMessage {
     #[serde(rename_all = "camelCase")]
     GameOver {
         rank: usize,
-        prize: Option<f64>,
+        #[serde(with = "rust_decimal::serde::float_option")]
+        prize: Option<Decimal>,
         wars_point: f64,
+        /// The signed final results, once the game is over for everyone
+        #[serde(skip_serializing_if = "Option::is_none")]
+        signed_results: Option<SignedResults>,
+    },
+
+    /// Game ran past its maximum duration and was force-finished
+    /// Broadcast to room after the FinalStanding it was cut short with
+    #[serde(rename_all = "camelCase")]
+    GameTimedOut {
+        max_duration_secs: u64,
+    },
+
+    /// Personal confirmation that the user's prediction was stored
+    #[serde(rename_all = "camelCase")]
+    PredictionRecorded {
+        winner_id: Uuid,
+    },
+
+    /// How spectators' predictions fared - broadcast to room after FinalStanding
+    #[serde(rename_all = "camelCase")]
+    PredictionsSettled {
+        #[serde(flatten)]
+        outcome: PredictionOutcome,
+        /// Awarded to each correct predictor
+        points_awarded: u32,
     },
 
     /// Claim reward success
     ClaimSuccess,
 
+    /// Receipt for a game action sent with a `clientActionId`, to the sending
+    /// connection only and before any events the action produced.
+    /// `code` is set when the action was rejected.
+    #[serde(rename_all = "camelCase")]
+    ActionAck {
+        client_action_id: String,
+        accepted: bool,
+        code: Option<String>,
+    },
+
     Error {
         code: String,
         message: String,
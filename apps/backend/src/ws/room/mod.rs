@@ -1,10 +1,16 @@
 // Room WebSocket module - handles lobby room connections (game + chat)
+pub mod context;
 pub mod engine;
 pub mod error;
 pub mod handler;
 pub mod messages;
+pub mod ready;
+pub mod state_sync;
+pub mod typing;
 
+pub use context::{RoomContext, RoomRole};
 pub use engine::handle_room_message;
 pub use error::RoomError;
 pub use handler::room_handler;
 pub use messages::{RoomClientMessage, RoomServerMessage};
+pub use state_sync::RoomStateBroadcasts;
@@ -0,0 +1,270 @@
+// Ready check: players confirm they're there before the start countdown
+//
+// With READY_UP_TIMEOUT_SECS set, a lobby moving to Starting first opens a
+// ready check. The creator, who asked for the start, counts as ready. Each
+// `ready` is broadcast as `PlayerReady`; once every player is ready the room
+// gets `AllReady` and the countdown begins. At the timeout the countdown
+// begins anyway if a quorum is ready, with the rest dropped or kept as
+// configured; short of a quorum the lobby goes back to Waiting.
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use tokio::time::{Instant, sleep};
+use uuid::Uuid;
+
+use crate::db::lobby::LobbyRepository;
+use crate::db::lobby_state::LobbyStateRepository;
+use crate::db::player_state::PlayerStateRepository;
+use crate::games::{deadline_ms, server_time_ms};
+use crate::models::{LobbyStatus, PlayerState};
+use crate::state::{AppState, ReadyUpConfig, UnreadyPolicy};
+use crate::ws::broadcast;
+use crate::ws::room::context;
+use crate::ws::room::messages::RoomServerMessage;
+
+/// How often a running ready check looks at who's ready
+const READY_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Where a ready check stands.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReadyOutcome {
+    /// Still waiting on players
+    Pending,
+    /// Everyone is ready
+    AllReady,
+    /// Timed out with a quorum; `unready` are the players who weren't
+    Quorum { unready: Vec<Uuid> },
+    /// Timed out short of a quorum
+    NoQuorum { ready: usize, needed: usize },
+}
+
+/// Judge a ready check over `players` with `ready` of them ready.
+pub fn assess(
+    config: &ReadyUpConfig,
+    players: &[Uuid],
+    ready: &HashSet<Uuid>,
+    timed_out: bool,
+) -> ReadyOutcome {
+    let unready: Vec<Uuid> = players
+        .iter()
+        .filter(|id| !ready.contains(id))
+        .copied()
+        .collect();
+
+    if !players.is_empty() && unready.is_empty() {
+        return ReadyOutcome::AllReady;
+    }
+    if !timed_out {
+        return ReadyOutcome::Pending;
+    }
+
+    let ready = players.len() - unready.len();
+    let needed = config.quorum(players.len());
+    if ready >= needed {
+        ReadyOutcome::Quorum { unready }
+    } else {
+        ReadyOutcome::NoQuorum { ready, needed }
+    }
+}
+
+/// Run a ready check for a Starting lobby.
+///
+/// Returns true when the countdown should go ahead. Returns false if the
+/// check failed (the lobby is put back to Waiting) or the lobby left Starting
+/// some other way meanwhile.
+pub async fn run_ready_check(state: &AppState, lobby_id: Uuid, config: &ReadyUpConfig) -> bool {
+    let lobby_state_repo = LobbyStateRepository::new(state.redis.clone());
+    let player_repo = PlayerStateRepository::new(state.redis.clone());
+
+    let _ = lobby_state_repo.clear_ready(lobby_id).await;
+    if let Ok(players) = player_repo.list_players(lobby_id).await {
+        for creator in players.iter().filter(|p| p.is_creator) {
+            let _ = lobby_state_repo.mark_ready(lobby_id, creator.user_id).await;
+        }
+    }
+
+    let started_ms = server_time_ms();
+    let deadline = Instant::now() + Duration::from_secs(config.timeout_secs);
+    let _ = broadcast::broadcast_room(
+        state,
+        lobby_id,
+        &RoomServerMessage::ReadyCheck {
+            timeout_secs: config.timeout_secs,
+            deadline_ms: deadline_ms(started_ms, config.timeout_secs),
+            server_time_ms: started_ms,
+        },
+    )
+    .await;
+
+    loop {
+        match lobby_state_repo.get_status(lobby_id).await {
+            Ok(LobbyStatus::Starting) => {}
+            _ => return false,
+        }
+
+        let players = match player_repo.list_players(lobby_id).await {
+            Ok(players) => players,
+            Err(e) => {
+                tracing::error!("Ready check for lobby {} failed: {}", lobby_id, e);
+                return false;
+            }
+        };
+        let player_ids: Vec<Uuid> = players.iter().map(|p| p.user_id).collect();
+        let ready = lobby_state_repo
+            .get_ready(lobby_id)
+            .await
+            .unwrap_or_default();
+
+        match assess(config, &player_ids, &ready, Instant::now() >= deadline) {
+            ReadyOutcome::Pending => sleep(READY_POLL_INTERVAL).await,
+            ReadyOutcome::AllReady => {
+                let _ =
+                    broadcast::broadcast_room(state, lobby_id, &RoomServerMessage::AllReady).await;
+                return true;
+            }
+            ReadyOutcome::Quorum { unready } => {
+                let dropped = config.unready == UnreadyPolicy::Drop;
+                if dropped {
+                    let unready_players: Vec<PlayerState> = players
+                        .into_iter()
+                        .filter(|p| unready.contains(&p.user_id))
+                        .collect();
+                    drop_unready(state, lobby_id, unready_players).await;
+                }
+                let _ = broadcast::broadcast_room(
+                    state,
+                    lobby_id,
+                    &RoomServerMessage::ReadyCheckTimedOut { unready, dropped },
+                )
+                .await;
+                return true;
+            }
+            ReadyOutcome::NoQuorum { ready, needed } => {
+                call_off_start(
+                    state,
+                    lobby_id,
+                    format!(
+                        "Only {} players readied up in time; {} were needed",
+                        ready, needed
+                    ),
+                )
+                .await;
+                return false;
+            }
+        }
+    }
+}
+
+/// Remove players who didn't ready up, as if the creator had kicked them
+async fn drop_unready(state: &AppState, lobby_id: Uuid, unready: Vec<PlayerState>) {
+    let lobby_state_repo = LobbyStateRepository::new(state.redis.clone());
+    let player_repo = PlayerStateRepository::new(state.redis.clone());
+
+    for player in unready {
+        let _ = player_repo
+            .delete_state(lobby_id, player.user_id, Some(state.clone()))
+            .await;
+        context::refresh_room_context(state, lobby_id, player.user_id).await;
+        let _ = lobby_state_repo.decrement_participants(lobby_id).await;
+        let _ =
+            broadcast::broadcast_room(state, lobby_id, &RoomServerMessage::PlayerKicked { player })
+                .await;
+    }
+
+    broadcast::broadcast_lobby_update(state.clone(), lobby_id).await;
+}
+
+/// Put the lobby back to Waiting and tell the room why
+async fn call_off_start(state: &AppState, lobby_id: Uuid, reason: String) {
+    let lobby_state_repo = LobbyStateRepository::new(state.redis.clone());
+    let _ = lobby_state_repo
+        .update_status(lobby_id, LobbyStatus::Waiting)
+        .await;
+    let _ = lobby_state_repo.clear_ready(lobby_id).await;
+
+    let _ = broadcast::broadcast_room(
+        state,
+        lobby_id,
+        &RoomServerMessage::GameStartFailed { reason },
+    )
+    .await;
+
+    let participant_count = lobby_state_repo
+        .get_participant_count(lobby_id)
+        .await
+        .unwrap_or(0);
+    let current_amount = LobbyRepository::new(state.postgres.clone())
+        .find_by_id(lobby_id)
+        .await
+        .ok()
+        .and_then(|l| l.current_amount);
+    let _ = broadcast::broadcast_room(
+        state,
+        lobby_id,
+        &RoomServerMessage::LobbyStatusChanged {
+            status: LobbyStatus::Waiting,
+            participant_count,
+            current_amount,
+        },
+    )
+    .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(quorum_percent: u8) -> ReadyUpConfig {
+        ReadyUpConfig {
+            timeout_secs: 10,
+            quorum_percent,
+            unready: UnreadyPolicy::Drop,
+        }
+    }
+
+    #[test]
+    fn test_all_ready_starts_before_the_timeout() {
+        let players = vec![Uuid::new_v4(), Uuid::new_v4()];
+        let mut ready = HashSet::from([players[0]]);
+        assert_eq!(
+            assess(&config(100), &players, &ready, false),
+            ReadyOutcome::Pending
+        );
+
+        ready.insert(players[1]);
+        assert_eq!(
+            assess(&config(100), &players, &ready, false),
+            ReadyOutcome::AllReady
+        );
+    }
+
+    #[test]
+    fn test_quorum_decides_at_the_timeout() {
+        let players = vec![Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+        let ready = HashSet::from([players[0], players[1]]);
+
+        // 2 of 3 ready: enough for half, not for all
+        assert_eq!(
+            assess(&config(50), &players, &ready, true),
+            ReadyOutcome::Quorum {
+                unready: vec![players[2]]
+            }
+        );
+        assert_eq!(
+            assess(&config(100), &players, &ready, true),
+            ReadyOutcome::NoQuorum {
+                ready: 2,
+                needed: 3
+            }
+        );
+    }
+
+    #[test]
+    fn test_quorum_rounds_up() {
+        assert_eq!(config(50).quorum(3), 2);
+        assert_eq!(config(50).quorum(4), 2);
+        assert_eq!(config(100).quorum(5), 5);
+        assert_eq!(config(1).quorum(0), 1);
+    }
+}
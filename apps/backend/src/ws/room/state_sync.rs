@@ -0,0 +1,93 @@
+// Consolidated room state
+//
+// Lobby changes reported through `broadcast_lobby_update` also refresh the
+// lobby's room. Changes within the configured window become one
+// `roomStateUpdated` carrying the lobby's runtime state, roster and pot as they
+// stand then, so a burst of joins, leaves and kicks costs one message per
+// connection instead of a roster and a status message per change. Events
+// about one player (`playerJoined`, `playerLeft`, ...) and game-flow status
+// changes still go out as they happen.
+//
+// The state is broadcast to the room like any other room event: sequenced,
+// held back from spectators by the spectator delay and masked in anonymized
+// lobbies. Each flush starts after a random delay of up to the configured
+// jitter, so rooms changed by one event (a reaper pass, a season rollover)
+// don't all send at once. The jitter never exceeds the window.
+
+use std::time::Duration;
+
+use rand::Rng;
+use uuid::Uuid;
+
+use crate::db::{
+    lobby::LobbyRepository, lobby_state::LobbyStateRepository, player_state::PlayerStateRepository,
+};
+use crate::state::AppState;
+use crate::ws::broadcast;
+use crate::ws::core::coalesce::Coalescer;
+use crate::ws::room::RoomServerMessage;
+
+/// Sends rooms their consolidated state after lobby changes.
+#[derive(Clone, Default)]
+pub struct RoomStateBroadcasts {
+    coalescer: Coalescer,
+}
+
+impl RoomStateBroadcasts {
+    /// The lobby changed; its room gets the consolidated state shortly.
+    pub async fn changed(&self, state: &AppState, lobby_id: Uuid) {
+        let window = Duration::from_millis(state.config.room_state_window_ms);
+        let state = state.clone();
+        self.coalescer
+            .schedule(lobby_id, window, move || {
+                send_room_state(state.clone(), lobby_id)
+            })
+            .await;
+    }
+}
+
+async fn send_room_state(state: AppState, lobby_id: Uuid) {
+    let jitter_ms = state.config.room_state_jitter_ms;
+    if jitter_ms > 0 {
+        let delay = rand::rng().random_range(0..=jitter_ms);
+        tokio::time::sleep(Duration::from_millis(delay)).await;
+    }
+
+    let lobby_state_repo = LobbyStateRepository::new(state.redis.clone());
+    let player_repo = PlayerStateRepository::new(state.redis.clone());
+    let lobby_repo = LobbyRepository::new(state.postgres.clone());
+    let (lobby_state, players, lobby) = tokio::join!(
+        lobby_state_repo.get_state(lobby_id),
+        player_repo.list_players(lobby_id),
+        lobby_repo.find_by_id(lobby_id),
+    );
+    let lobby_state = match lobby_state {
+        Ok(lobby_state) => lobby_state,
+        Err(e) => {
+            tracing::debug!("No room state to send for {}: {}", lobby_id, e);
+            return;
+        }
+    };
+    let players = match players {
+        Ok(players) => players,
+        Err(e) => {
+            tracing::warn!("Roster of {} unavailable for room state: {}", lobby_id, e);
+            return;
+        }
+    };
+
+    broadcast::broadcast_room(
+        &state,
+        lobby_id,
+        &RoomServerMessage::RoomStateUpdated {
+            status: lobby_state.status,
+            participant_count: lobby_state.participant_count,
+            current_amount: lobby.ok().and_then(|lobby| lobby.current_amount),
+            players,
+            started_at: lobby_state.started_at,
+            finished_at: lobby_state.finished_at,
+            updated_at: lobby_state.updated_at,
+        },
+    )
+    .await;
+}
@@ -0,0 +1,146 @@
+// Typing indicators: who is typing in each lobby's chat, kept in memory only
+//
+// Clients send `typing { isTyping: true }` while the user types, repeating it
+// every couple of seconds. Only changes reach the room: the first `true`
+// broadcasts `userTyping`, repeats just push the expiry out, and `false` (or
+// sending the message) clears it. A user who goes quiet for TYPING_TIMEOUT
+// is cleared by a timer. Typing events are sent unsequenced and never stored.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::state::AppState;
+use crate::ws::broadcast::broadcast_room_ephemeral;
+use crate::ws::room::messages::RoomServerMessage;
+
+/// How long a typing state lasts without a refresh
+pub const TYPING_TIMEOUT: Duration = Duration::from_secs(4);
+
+/// Users currently typing, by lobby and user.
+///
+/// Each update stamps the entry with a new generation, so an expiry timer
+/// only clears the entry it was started for.
+#[derive(Clone)]
+pub struct TypingTracker {
+    timeout: Duration,
+    typing: Arc<Mutex<HashMap<(Uuid, Uuid), u64>>>,
+    generation: Arc<AtomicU64>,
+}
+
+impl Default for TypingTracker {
+    fn default() -> Self {
+        Self::new(TYPING_TIMEOUT)
+    }
+}
+
+impl TypingTracker {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            typing: Default::default(),
+            generation: Default::default(),
+        }
+    }
+
+    /// Mark the user typing. Returns whether they weren't already, and the
+    /// generation an expiry timer should check.
+    pub async fn start(&self, lobby_id: Uuid, user_id: Uuid) -> (bool, u64) {
+        let generation = self.generation.fetch_add(1, Ordering::Relaxed) + 1;
+        let previous = self
+            .typing
+            .lock()
+            .await
+            .insert((lobby_id, user_id), generation);
+        (previous.is_none(), generation)
+    }
+
+    /// Clear the user's typing state. Returns whether they were typing.
+    pub async fn stop(&self, lobby_id: Uuid, user_id: Uuid) -> bool {
+        self.typing
+            .lock()
+            .await
+            .remove(&(lobby_id, user_id))
+            .is_some()
+    }
+
+    /// Clear the state if nothing refreshed it since `generation`.
+    pub async fn expire(&self, lobby_id: Uuid, user_id: Uuid, generation: u64) -> bool {
+        let mut typing = self.typing.lock().await;
+        if typing.get(&(lobby_id, user_id)) != Some(&generation) {
+            return false;
+        }
+        typing.remove(&(lobby_id, user_id));
+        true
+    }
+}
+
+/// Apply a typing update from `user_id` and tell the rest of the room if it changed.
+pub async fn set_typing(state: &AppState, lobby_id: Uuid, user_id: Uuid, is_typing: bool) {
+    let tracker = &state.typing;
+    if !is_typing {
+        if tracker.stop(lobby_id, user_id).await {
+            announce(state, lobby_id, user_id, false).await;
+        }
+        return;
+    }
+
+    let (started, generation) = tracker.start(lobby_id, user_id).await;
+    if started {
+        announce(state, lobby_id, user_id, true).await;
+    }
+
+    let state = state.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(state.typing.timeout).await;
+        if state.typing.expire(lobby_id, user_id, generation).await {
+            announce(&state, lobby_id, user_id, false).await;
+        }
+    });
+}
+
+async fn announce(state: &AppState, lobby_id: Uuid, user_id: Uuid, is_typing: bool) {
+    let msg = RoomServerMessage::UserTyping { user_id, is_typing };
+    broadcast_room_ephemeral(state, lobby_id, Some(user_id), &msg).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_repeated_typing_only_starts_once() {
+        let tracker = TypingTracker::default();
+        let (lobby, user) = (Uuid::new_v4(), Uuid::new_v4());
+
+        let (started, _) = tracker.start(lobby, user).await;
+        assert!(started);
+        let (started, _) = tracker.start(lobby, user).await;
+        assert!(!started);
+
+        assert!(tracker.stop(lobby, user).await);
+        assert!(!tracker.stop(lobby, user).await);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_outlives_older_expiry() {
+        let tracker = TypingTracker::default();
+        let (lobby, user) = (Uuid::new_v4(), Uuid::new_v4());
+
+        let (_, first) = tracker.start(lobby, user).await;
+        let (_, second) = tracker.start(lobby, user).await;
+
+        // The first timer fires after the refresh and leaves the state alone
+        assert!(!tracker.expire(lobby, user, first).await);
+        assert!(tracker.expire(lobby, user, second).await);
+        assert!(!tracker.stop(lobby, user).await);
+    }
+}
@@ -1,19 +1,36 @@
 use crate::{
     state::AppState,
-    ws::{lobby::lobby_handler, room::room_handler},
+    ws::{
+        leaderboard::leaderboard_handler,
+        lobby::lobby_handler,
+        room::room_handler,
+        sse::{lobby_events, room_events},
+    },
 };
 use axum::{Router, routing::get};
 
-/// Create WebSocket routes (grouped under `/ws`).
+/// Create WebSocket routes (grouped under `/ws`), plus their read-only
+/// server-sent event fallbacks (under `/sse`).
 ///
 /// Routes:
 /// - GET `/ws/room/{lobby_path}` - Connect to a specific lobby room (game + chat)
 /// - GET `/ws/lobbies?status=waiting,starting` - Browse lobbies with optional status filter
+/// - GET `/ws/leaderboard?gameId=...` - Live season leaderboard, optionally for one game
+/// - GET `/sse/room/{lobby_path}` - Room broadcasts as an event stream
+/// - GET `/sse/lobbies?status=...` - Lobby list updates as an event stream
 pub fn create_ws_routes(state: AppState) -> Router {
     let ws_router = Router::new()
         .route("/room/{lobby_path}", get(room_handler))
         .route("/lobbies", get(lobby_handler))
+        .route("/leaderboard", get(leaderboard_handler))
+        .with_state(state.clone());
+
+    let sse_router = Router::new()
+        .route("/room/{lobby_path}", get(room_events))
+        .route("/lobbies", get(lobby_events))
         .with_state(state);
 
-    Router::new().nest("/ws", ws_router)
+    Router::new()
+        .nest("/ws", ws_router)
+        .nest("/sse", sse_router)
 }
@@ -0,0 +1,138 @@
+// Server-sent event streams - read-only fallback for networks that block WebSockets
+//
+// `GET /sse/lobbies` and `GET /sse/room/{lobby_path}` take the same query
+// parameters and auth as their `/ws` counterparts and register a connection
+// the same way, so every broadcast reaches them unchanged: each message is one
+// `data:` event carrying the JSON a WebSocket client would receive. The
+// streams only listen; room actions go through the HTTP API.
+
+use std::{convert::Infallible, net::SocketAddr};
+
+use axum::{
+    extract::{ConnectInfo, Path, Query, State, ws::Message},
+    http::StatusCode,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures::{
+    FutureExt, Stream, StreamExt,
+    channel::mpsc::{self, Receiver},
+    future::{self, BoxFuture},
+};
+
+use crate::{
+    auth::extractors::WsAuth,
+    db::lobby::LobbyRepository,
+    middleware::{ApiRateLimit, check_rate_limit},
+    state::AppState,
+    ws::{
+        core::{Compression, ConnectionSink, Negotiated, WsProtocol, manager},
+        lobby::handler::{LobbyQueryParams, open_lobby_connection},
+        room::handler::{authorize_room_connection, close_room_connection, open_room_connection},
+    },
+};
+
+/// Messages held for an SSE subscriber; one that falls this far behind is
+/// disconnected
+const EVENT_STREAM_BUFFER: usize = 256;
+
+type EventStream = Sse<Box<dyn Stream<Item = Result<Event, Infallible>> + Send + Unpin>>;
+
+/// SSE counterpart of `lobby_handler`
+pub async fn lobby_events(
+    Query(params): Query<LobbyQueryParams>,
+    State(state): State<AppState>,
+    WsProtocol(negotiated): WsProtocol,
+) -> Result<EventStream, (StatusCode, String)> {
+    let negotiated = uncompressed(negotiated)?;
+    let (sender, receiver) = mpsc::channel(EVENT_STREAM_BUFFER);
+
+    let connection_id = open_lobby_connection(
+        &state,
+        &params,
+        ConnectionSink::EventStream(sender),
+        &negotiated,
+    )
+    .await
+    .connection_id;
+    let on_disconnect = OnDisconnect(Some(
+        async move { manager::unregister_connection(&state, &connection_id).await }.boxed(),
+    ));
+
+    Ok(event_stream(receiver, on_disconnect))
+}
+
+/// SSE counterpart of `room_handler`, with the same rate limit and admission
+/// checks. An unknown lobby is a 404 here rather than a dropped socket.
+pub async fn room_events(
+    Path(lobby_path): Path<String>,
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    WsAuth(auth): WsAuth,
+    WsProtocol(negotiated): WsProtocol,
+) -> Result<EventStream, (StatusCode, String)> {
+    let negotiated = uncompressed(negotiated)?;
+    let auth_user_id = auth.and_then(|claims| claims.user_id().ok());
+
+    let ip = addr.ip().to_string();
+    check_rate_limit::<ApiRateLimit>(&state, &ip, auth_user_id).await?;
+
+    let lobby = LobbyRepository::new(state.postgres.clone())
+        .find_by_path(&lobby_path)
+        .await
+        .map_err(|e| e.to_response())?;
+    authorize_room_connection(&state, &lobby, auth_user_id)
+        .await
+        .map_err(|e| e.to_response())?;
+
+    let (sender, receiver) = mpsc::channel(EVENT_STREAM_BUFFER);
+    // On a failed bootstrap the stream carries the error and then ends
+    let room = open_room_connection(
+        &state,
+        lobby,
+        auth_user_id,
+        ConnectionSink::EventStream(sender),
+        &negotiated,
+    )
+    .await;
+    let on_disconnect = OnDisconnect(
+        room.map(|room| async move { close_room_connection(&state, room).await }.boxed()),
+    );
+
+    Ok(event_stream(receiver, on_disconnect))
+}
+
+/// The negotiated protocol without snapshot compression: compressed snapshots
+/// go out as binary frames, which an event stream can't carry.
+fn uncompressed(
+    negotiated: Result<Negotiated, impl ToString>,
+) -> Result<Negotiated, (StatusCode, String)> {
+    let negotiated = negotiated.map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+    Ok(Negotiated {
+        compression: Compression::None,
+        ..negotiated
+    })
+}
+
+/// Text messages as `data:` events, until the client goes away
+fn event_stream(receiver: Receiver<Message>, on_disconnect: OnDisconnect) -> EventStream {
+    let stream = receiver.filter_map(move |message| {
+        // Held by the stream so the cleanup runs once axum drops it
+        let _ = &on_disconnect;
+        future::ready(match message {
+            Message::Text(text) => Some(Ok(Event::default().data(text.as_str()))),
+            _ => None,
+        })
+    });
+    Sse::new(Box::new(stream) as Box<_>).keep_alive(KeepAlive::default())
+}
+
+/// Spawns its cleanup when dropped
+struct OnDisconnect(Option<BoxFuture<'static, ()>>);
+
+impl Drop for OnDisconnect {
+    fn drop(&mut self) {
+        if let Some(cleanup) = self.0.take() {
+            tokio::spawn(cleanup);
+        }
+    }
+}
@@ -91,12 +91,37 @@ impl TestApp {
         Ok(token)
     }
 
+    /// Body for `POST /api/user` registering the mainnet wallet of `secret`,
+    /// signed over a fresh challenge. Returns the wallet address and the body.
+    pub async fn registration(
+        &self,
+        secret: [u8; 32],
+        email: Option<&str>,
+    ) -> (String, serde_json::Value) {
+        use stacks_wars_be::auth::{challenge, wallet_signature};
+
+        let key = wallet_signature::public_key(&secret);
+        let wallet = wallet_signature::stacks_address(&hex::decode(&key).unwrap(), 22);
+        let address = stacks_wars_be::models::WalletAddress::new(&wallet).unwrap();
+        let issued = challenge::issue(&self.state.redis, &address)
+            .await
+            .expect("issue challenge");
+        let body = serde_json::json!({
+            "walletAddress": wallet,
+            "emailAddress": email,
+            "nonce": issued.nonce,
+            "signature": wallet_signature::sign_message(&secret, &issued.message),
+            "publicKey": key,
+        });
+        (wallet, body)
+    }
+
     /// Return a TestFactory tied to this TestApp instance.
     pub fn factory(&self) -> TestFactory {
         TestFactory {
             pg_pool: self.pg_pool.clone(),
             jwt_secret: self.state.config.jwt_secret.clone(),
-            redis: self.state.redis.clone(),
+            redis: self.state.redis.pool().clone(),
         }
     }
 }
@@ -107,6 +132,39 @@ impl TestApp {
 /// Coin Flip game ID from registry
 pub const COINFLIP_GAME_ID: Uuid = uuid::uuid!("05f920e9-6b71-471e-a98a-2e5fe9402c00");
 
+/// Wallet listed in the test config's admins
+#[allow(dead_code)]
+pub const TEST_ADMIN_WALLET: &str = "SP00000000000000000000000000000000000ADMN";
+
+/// Secret key the test config signs results with
+#[allow(dead_code)]
+pub const TEST_RESULT_SIGNING_KEY: &str =
+    "0707070707070707070707070707070707070707070707070707070707070707";
+
+/// Violations that earn an IP a ban in the test config
+#[allow(dead_code)]
+pub const TEST_ABUSE_THRESHOLD: u32 = 10;
+
+/// Length of a first ban in the test config
+#[allow(dead_code)]
+pub const TEST_ABUSE_BAN_SECS: u64 = 2;
+
+/// Key id test auth tokens are signed under
+pub const TEST_JWT_KEY_ID: &str = "current";
+
+/// A rotated-out auth token key the test config still accepts
+pub const TEST_PREVIOUS_JWT_KEY_ID: &str = "previous";
+pub const TEST_PREVIOUS_JWT_SECRET: &str = "stacks_wars_previous_signing_secret";
+
+/// Tokens accepted for lobbies in the test config; STX has no minimum
+#[allow(dead_code)]
+pub const TEST_LOBBY_TOKENS: &str =
+    "STX=0,WELSH@SP3NE50GEXFG9SZGTT51P40X2CKYSZ5CC4ZTZ7A2G.welshcorgicoin-token=100";
+
+/// Only origin allowed by the test config's CORS policy
+#[allow(dead_code)]
+pub const TEST_ALLOWED_ORIGIN: &str = "http://localhost:3000";
+
 #[allow(dead_code)]
 pub struct TestFactory {
     pub pg_pool: PgPool,
@@ -272,7 +330,7 @@ impl TestFactory {
         // Omit `status` (enum) column so DB default ('waiting') is used. Binding text for
         // enum columns can cause type mismatches depending on Postgres settings.
         // Also omit `path` to let the trigger generate a unique value
-        sqlx::query(
+        let inserted = sqlx::query(
             "INSERT INTO lobbies (id, name, game_id, game_path, creator_id, entry_amount, current_amount)
              SELECT $1, $2, $3, games.path, $4, $5, $6
              FROM games
@@ -282,12 +340,16 @@ impl TestFactory {
             .bind(&lname)
             .bind(game_id)
             .bind(creator_id)
-            .bind(0_f64)
-            .bind(0_f64)
+            .bind(rust_decimal::Decimal::ZERO)
+            .bind(rust_decimal::Decimal::ZERO)
             .execute(&self.pg_pool)
             .await
             .map_err(|e| -> Box<dyn Error> { Box::new(e) })?;
 
+        if inserted.rows_affected() == 0 {
+            return Err(format!("game {} does not exist", game_id).into());
+        }
+
         // Fetch the auto-generated lobby path
         let lobby_path: String = sqlx::query_scalar("SELECT path FROM lobbies WHERE id = $1")
             .bind(lobby_id)
@@ -303,7 +365,6 @@ impl TestFactory {
 
         // Build canonical keys using RedisKey helper
         let lobby_key = stacks_wars_be::models::RedisKey::lobby_state(lobby_id);
-        let player_key = stacks_wars_be::models::RedisKey::lobby_player(lobby_id, creator_id);
 
         let lstate = stacks_wars_be::models::LobbyState::new(lobby_id);
         let lhash = lstate.to_redis_hash();
@@ -312,24 +373,45 @@ impl TestFactory {
             .await
             .map_err(|e| -> Box<dyn Error> { Box::new(e) })?;
 
-        let creator: stacks_wars_be::models::User = sqlx::query_as(
+        drop(conn);
+        self.add_test_player(lobby_id, creator_id, true).await?;
+
+        Ok((lobby_id, lobby_path))
+    }
+
+    /// Write a joined player's state for `user_id` into the lobby's Redis state
+    pub async fn add_test_player(
+        &self,
+        lobby_id: Uuid,
+        user_id: Uuid,
+        is_creator: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        let mut conn = self
+            .redis
+            .get()
+            .await
+            .map_err(|e| -> Box<dyn Error> { Box::new(e) })?;
+
+        let player_key = stacks_wars_be::models::RedisKey::lobby_player(lobby_id, user_id);
+
+        let user: stacks_wars_be::models::User = sqlx::query_as(
             "SELECT id, wallet_address, username, display_name, email, email_verified, trust_rating, created_at, updated_at
              FROM users WHERE id = $1"
         )
-            .bind(creator_id)
+            .bind(user_id)
             .fetch_one(&self.pg_pool)
             .await
             .map_err(|e| -> Box<dyn Error> { Box::new(e) })?;
 
         let pstate = stacks_wars_be::models::PlayerState::new(
-            creator_id,
+            user_id,
             lobby_id,
-            creator.wallet_address.to_string(),
-            creator.username,
-            creator.display_name,
-            creator.trust_rating,
+            user.wallet_address.to_string(),
+            user.username,
+            user.display_name,
+            user.trust_rating,
             None,
-            true,
+            is_creator,
         );
         let phash_map = pstate.to_redis_hash();
         let phash: Vec<(String, String)> = phash_map.into_iter().collect();
@@ -338,7 +420,7 @@ impl TestFactory {
             .await
             .map_err(|e| -> Box<dyn Error> { Box::new(e) })?;
 
-        Ok((lobby_id, lobby_path))
+        Ok(())
     }
 
     /// Insert a season directly and return the season id (integer SERIAL)
@@ -491,11 +573,79 @@ pub async fn spawn_app_with_containers() -> TestApp {
     // Build AppState manually using the pools we created
     let bot = Bot::new("test-bot-token");
     let config = stacks_wars_be::state::AppConfig {
+        environment: Default::default(),
         jwt_secret: "stacks_wars_deep_and_hidden_secret".to_string(),
+        invite_secret: "stacks_wars_invite_secret_kept_apart".to_string(),
+        jwt_keys: stacks_wars_be::auth::jwt::JwtKeys::hs256(
+            TEST_JWT_KEY_ID,
+            "stacks_wars_deep_and_hidden_secret",
+        )
+        .and_then(|keys| {
+            keys.with_previous_hs256(TEST_PREVIOUS_JWT_KEY_ID, TEST_PREVIOUS_JWT_SECRET)
+        })
+        .expect("valid jwt keys"),
         redis_url: redis_url.clone(),
         database_url: database_url.clone(),
         telegram_bot_token: "test-bot-token".to_string(),
         telegram_chat_id: "test-chat-id".to_string(),
+        admins: vec![
+            stacks_wars_be::models::WalletAddress::new(TEST_ADMIN_WALLET)
+                .expect("valid admin wallet"),
+        ],
+        network: Default::default(),
+        hiro_api_key: String::new(),
+        lobby_idle_timeout_secs: stacks_wars_be::reaper::DEFAULT_LOBBY_IDLE_TIMEOUT_SECS,
+        creator_grace_secs: stacks_wars_be::reaper::DEFAULT_CREATOR_GRACE_SECS,
+        streak_day_offset_secs: 0,
+        cors: stacks_wars_be::state::CorsConfig::parse(
+            TEST_ALLOWED_ORIGIN,
+            stacks_wars_be::state::CorsConfig::DEFAULT_METHODS,
+            None,
+            false,
+            &Default::default(),
+        )
+        .expect("valid cors config"),
+        rate_limits: Default::default(),
+        // Short bans so tests can watch one expire
+        abuse: stacks_wars_be::state::AbuseConfig {
+            threshold: TEST_ABUSE_THRESHOLD,
+            window_secs: 60,
+            ban_secs: TEST_ABUSE_BAN_SECS,
+            max_ban_secs: 8,
+            allowlist: Vec::new(),
+        },
+        lobby_tokens: stacks_wars_be::state::TokenPolicy::parse(TEST_LOBBY_TOKENS)
+            .expect("valid token policy"),
+        lobby_names: Default::default(),
+        chat_moderation: Default::default(),
+        game_durations: Default::default(),
+        spectator_ratios: Default::default(),
+        lexi_wars_max_word_length: stacks_wars_be::games::lexi_wars::DEFAULT_MAX_WORD_LENGTH,
+        lexi_wars_dictionary: Default::default(),
+        lexi_wars: Default::default(),
+        game_rng_seed: None,
+        ordered_room_broadcasts: true,
+        spectator_delay_secs: 0,
+        room_state_window_ms: 100,
+        room_state_jitter_ms: 25,
+        prediction_points: stacks_wars_be::models::prediction::DEFAULT_PREDICTION_POINTS,
+        slow_query_threshold_ms: stacks_wars_be::db::timing::DEFAULT_SLOW_QUERY_THRESHOLD_MS,
+        feature_defaults: Default::default(),
+        ready_up: Default::default(),
+        season_rollover: Default::default(),
+        rank_snapshot_interval_secs: 0,
+        contract_event_interval_secs: 0,
+        seat_separation: Default::default(),
+        chat_retention: Default::default(),
+        paid_lobby_trust: stacks_wars_be::state::PaidLobbyTrust {
+            create: 5.0,
+            join: 5.0,
+        },
+        wallet_access: Default::default(),
+        result_signing_key: Some(
+            stacks_wars_be::games::signing::ResultSigningKey::from_hex(TEST_RESULT_SIGNING_KEY)
+                .expect("valid signing key"),
+        ),
     };
 
     let state = stacks_wars_be::state::AppState {
@@ -504,7 +654,15 @@ pub async fn spawn_app_with_containers() -> TestApp {
         indices: Default::default(),
         game_registry: Arc::new(stacks_wars_be::games::create_game_registry()),
         active_games: Arc::new(tokio::sync::Mutex::new(std::collections::HashMap::new())),
-        redis: redis_pool,
+        room_sequencer: Default::default(),
+        spectator_delay: Default::default(),
+        room_pseudonyms: Default::default(),
+        lobby_deltas: Default::default(),
+        leaderboard_deltas: Default::default(),
+        room_state: Default::default(),
+        typing: Default::default(),
+        join_request_changes: Default::default(),
+        redis: stacks_wars_be::state::RedisClient::new(redis_pool),
         postgres: pg_pool.clone(),
         bot,
     };
@@ -528,7 +686,7 @@ pub async fn spawn_app_with_containers() -> TestApp {
     // middleware on nested routers (rate-limiter) can read State<AppState>
     let app = stacks_wars_be::http::create_http_routes(state.clone())
         .merge(stacks_wars_be::ws::create_ws_routes(state.clone()))
-        .layer(stacks_wars_be::cors_layer())
+        .layer(stacks_wars_be::cors_layer(&state.config.cors))
         .fallback(|| async { "404 Not Found" });
 
     // Bind to ephemeral port
@@ -2,6 +2,7 @@
 /// Provides connection wrappers for:
 /// - /ws/room/:lobby_id
 /// - /ws/lobbies
+/// - /ws/leaderboard
 use futures_util::{SinkExt, StreamExt};
 use serde_json::Value;
 use tokio_tungstenite::{connect_async, tungstenite::Message};
@@ -62,6 +63,16 @@ impl WsConnection {
         base_url: &str,
         token: Option<&str>,
         status_filter: Option<&[&str]>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::connect_to_lobby_with_protocol(base_url, token, status_filter, None).await
+    }
+
+    /// Connect to the lobby list, offering `protocol` as the WebSocket subprotocol
+    pub async fn connect_to_lobby_with_protocol(
+        base_url: &str,
+        token: Option<&str>,
+        status_filter: Option<&[&str]>,
+        protocol: Option<&str>,
     ) -> Result<Self, Box<dyn std::error::Error>> {
         let ws_url = base_url.replace("http://", "ws://");
         let mut url = format!("{}/ws/lobbies", ws_url);
@@ -77,8 +88,38 @@ impl WsConnection {
             url.push_str(&query_params.join("&"));
         }
 
+        Self::connect_to_lobby_url(&url, token, protocol).await
+    }
+
+    /// Connect to the lobby list with a raw query string, e.g. `gameId=...&minStake=1`
+    pub async fn connect_to_lobby_with_query(
+        base_url: &str,
+        query: &str,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let ws_url = base_url.replace("http://", "ws://");
+        Self::connect_to_lobby_url(&format!("{}/ws/lobbies?{}", ws_url, query), None, None).await
+    }
+
+    /// Connect to the live leaderboard, overall or for one game
+    pub async fn connect_to_leaderboard(
+        base_url: &str,
+        game_id: Option<uuid::Uuid>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let ws_url = base_url.replace("http://", "ws://");
+        let url = match game_id {
+            Some(game_id) => format!("{}/ws/leaderboard?gameId={}", ws_url, game_id),
+            None => format!("{}/ws/leaderboard", ws_url),
+        };
+        Self::connect_to_lobby_url(&url, None, None).await
+    }
+
+    async fn connect_to_lobby_url(
+        url: &str,
+        token: Option<&str>,
+        protocol: Option<&str>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
         let mut request_builder = tokio_tungstenite::tungstenite::http::Request::builder()
-            .uri(&url)
+            .uri(url)
             .header(
                 "Sec-WebSocket-Key",
                 tokio_tungstenite::tungstenite::handshake::client::generate_key(),
@@ -97,6 +138,9 @@ impl WsConnection {
         if let Some(tok) = token {
             request_builder = request_builder.header("Cookie", format!("auth_token={}", tok));
         }
+        if let Some(protocol) = protocol {
+            request_builder = request_builder.header("Sec-WebSocket-Protocol", protocol);
+        }
 
         let request = request_builder
             .body(())
@@ -138,6 +182,26 @@ impl WsConnection {
             .map_err(|_| Box::<dyn std::error::Error>::from("Timeout waiting for message"))?
     }
 
+    /// Wait for the server to close the connection; returns the close code and reason
+    pub async fn recv_close_timeout(
+        &mut self,
+        timeout: std::time::Duration,
+    ) -> Result<(u16, String), Box<dyn std::error::Error>> {
+        let wait = async {
+            while let Some(msg) = self.receiver.next().await {
+                if let Message::Close(frame) = msg? {
+                    return Ok(frame
+                        .map(|f| (u16::from(f.code), f.reason.to_string()))
+                        .unwrap_or((1005, String::new())));
+                }
+            }
+            Err::<_, Box<dyn std::error::Error>>("Connection ended without a close frame".into())
+        };
+        tokio::time::timeout(timeout, wait)
+            .await
+            .map_err(|_| Box::<dyn std::error::Error>::from("Timeout waiting for close"))?
+    }
+
     /// Close the WebSocket connection
     pub async fn close(mut self) -> Result<(), Box<dyn std::error::Error>> {
         self.sender.close().await?;
@@ -4,5 +4,11 @@
 #[path = "common/mod.rs"]
 mod common;
 
+#[path = "games/replay.rs"]
+mod replay;
+
 #[path = "games/coinflip.rs"]
 mod coinflip;
+
+#[path = "games/lexi_wars.rs"]
+mod lexi_wars;
@@ -0,0 +1,192 @@
+// Coin Flip engine tests
+// Run with: `cargo test --test games coinflip`
+
+use std::time::Duration;
+
+use serde_json::json;
+use stacks_wars_be::games::COIN_FLIP_GAME_ID;
+
+use crate::common;
+
+#[tokio::test]
+async fn coin_flip_round_knocks_out_the_wrong_call() {
+    let app = common::spawn_app_with_containers().await;
+    let factory = app.factory();
+
+    factory
+        .create_test_season(None)
+        .await
+        .expect("create season failed");
+    factory
+        .ensure_coinflip_game()
+        .await
+        .expect("ensure coin flip failed");
+    let (alice, _) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let (bob, _) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let (lobby_id, _) = factory
+        .create_test_lobby(alice, COIN_FLIP_GAME_ID, Some("flip lobby"))
+        .await
+        .expect("create lobby failed");
+    factory
+        .add_test_player(lobby_id, bob, false)
+        .await
+        .expect("add player failed");
+
+    let create_engine = app.state.game_registry[&COIN_FLIP_GAME_ID];
+    let mut engine = create_engine(lobby_id, app.state.clone());
+    engine
+        .initialize(vec![alice, bob])
+        .await
+        .expect("initialize failed");
+
+    let heads = json!({ "type": "call", "side": "heads" });
+    let tails = json!({ "type": "call", "side": "tails" });
+    assert!(
+        engine.handle_action(alice, heads.clone()).await.is_err(),
+        "no calls before round one"
+    );
+
+    // The first tick starts round one
+    engine.tick().await.unwrap();
+    engine.handle_action(alice, heads).await.unwrap();
+    assert!(
+        engine.handle_action(alice, tails.clone()).await.is_err(),
+        "one call a round"
+    );
+    engine.handle_action(bob, tails).await.unwrap();
+
+    // Both called opposite sides, so the flip settles it
+    engine.tick().await.unwrap();
+    assert!(engine.is_finished());
+
+    let results = engine.get_results().await.unwrap().expect("no results");
+    assert!(results.ranked);
+    assert_eq!(results.rankings.len(), 2);
+    assert_eq!(results.rankings[0].rank, 1);
+    assert_eq!(results.rankings[0].score, Some(1));
+    assert_eq!(results.rankings[1].score, Some(0));
+    assert_ne!(results.rankings[0].user_id, results.rankings[1].user_id);
+
+    let awards: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM wars_point_awards WHERE lobby_id = $1")
+            .bind(lobby_id)
+            .fetch_one(&app.state.postgres)
+            .await
+            .unwrap();
+    assert_eq!(awards, 2, "both players are awarded points");
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn coin_flip_practice_game_is_unranked_and_leaves_no_history() {
+    use stacks_wars_be::db::player_state::PlayerStateRepository;
+    use stacks_wars_be::models::BotDifficulty;
+
+    let app = common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+    let factory = app.factory();
+
+    factory
+        .create_test_season(None)
+        .await
+        .expect("create season failed");
+    factory
+        .ensure_coinflip_game()
+        .await
+        .expect("ensure coin flip failed");
+    let (user_id, token) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let (lobby_id, _) = factory
+        .create_test_lobby(user_id, COIN_FLIP_GAME_ID, Some("practice flips"))
+        .await
+        .expect("create lobby failed");
+    sqlx::query("UPDATE lobbies SET practice_bot = 'hard' WHERE id = $1")
+        .bind(lobby_id)
+        .execute(&app.state.postgres)
+        .await
+        .unwrap();
+
+    let create_engine = app.state.game_registry[&COIN_FLIP_GAME_ID];
+    let mut engine = create_engine(lobby_id, app.state.clone());
+    let bot_id = engine.add_bot(BotDifficulty::Hard).await.unwrap();
+    engine
+        .initialize(vec![user_id, bot_id])
+        .await
+        .expect("initialize failed");
+
+    engine.tick().await.unwrap();
+    engine
+        .handle_action(user_id, json!({ "type": "call", "side": "heads" }))
+        .await
+        .unwrap();
+
+    // The bot calls within its think time, then the coin is flipped
+    let mut flipped = false;
+    for _ in 0..40 {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        engine.tick().await.unwrap();
+        let bootstrap = engine.get_bootstrap().await.unwrap();
+        if engine.is_finished() || bootstrap["round"] != 1 {
+            flipped = true;
+            break;
+        }
+    }
+    assert!(flipped, "the bot never called");
+    if !engine.is_finished() {
+        assert!(engine.force_finish().await.unwrap());
+    }
+
+    let results = engine.get_results().await.unwrap().expect("no results");
+    assert!(!results.ranked);
+    assert_eq!(results.rankings.len(), 2);
+    assert!(results.rankings.iter().any(|r| r.user_id == bot_id));
+
+    for (table, what) in [
+        ("user_wars_points", "wars points"),
+        ("wars_point_awards", "point awards"),
+        ("skill_ratings", "skill ratings"),
+    ] {
+        let rows: i64 = sqlx::query_scalar(&format!(
+            "SELECT COUNT(*) FROM {} WHERE user_id = $1",
+            table
+        ))
+        .bind(user_id)
+        .fetch_one(&app.state.postgres)
+        .await
+        .unwrap();
+        assert_eq!(rows, 0, "practice games write no {}", what);
+    }
+    let stored: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM lobby_results WHERE lobby_id = $1")
+        .bind(lobby_id)
+        .fetch_one(&app.state.postgres)
+        .await
+        .unwrap();
+    assert_eq!(stored, 0, "practice results aren't kept for reprocessing");
+
+    let player = PlayerStateRepository::new(app.state.redis.clone())
+        .get_state(lobby_id, user_id)
+        .await
+        .unwrap();
+    assert_eq!(player.rank, None, "no result is saved on the player");
+
+    let resp = client
+        .get(format!("{}/api/users/me/export", app.base_url))
+        .header("Cookie", factory.create_auth_cookie(&token))
+        .send()
+        .await
+        .expect("request failed");
+    assert!(resp.status().is_success());
+    let body: serde_json::Value = resp.json().await.expect("invalid json");
+    assert_eq!(body["matchHistory"], json!([]), "no match-history row");
+
+    app.stop().await;
+}
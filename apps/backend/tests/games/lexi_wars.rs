@@ -0,0 +1,269 @@
+// Lexi Wars engine tests
+// Run with: `cargo test --test games lexi_wars`
+
+use crate::common;
+use crate::replay::run_replay;
+
+#[tokio::test]
+async fn lexi_wars_opening_turn_replay() {
+    let app = common::spawn_app_with_containers().await;
+
+    run_replay(&app, "lexi_wars_opening_turn").await;
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn lexi_wars_practice_game_is_unranked_and_saves_nothing() {
+    use stacks_wars_be::games::LEXI_WARS_GAME_ID;
+    use stacks_wars_be::models::BotDifficulty;
+
+    let app = common::spawn_app_with_containers().await;
+    let factory = app.factory();
+
+    factory
+        .create_test_season(None)
+        .await
+        .expect("create season failed");
+    let (user_id, _) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let game_row = factory
+        .create_test_game(user_id, Some("practice"))
+        .await
+        .expect("create game failed");
+    let (lobby_id, _) = factory
+        .create_test_lobby(user_id, game_row, Some("practice lobby"))
+        .await
+        .expect("create lobby failed");
+
+    let create_engine = app.state.game_registry.get(&LEXI_WARS_GAME_ID).unwrap();
+    let mut engine = create_engine(lobby_id, app.state.clone());
+    let bot_id = engine.add_bot(BotDifficulty::Easy).await.unwrap();
+    engine
+        .initialize(vec![user_id, bot_id])
+        .await
+        .expect("initialize failed");
+
+    assert!(engine.force_finish().await.unwrap());
+    let results = engine.get_results().await.unwrap().expect("no results");
+    assert!(!results.ranked);
+    assert_eq!(results.rankings.len(), 2);
+
+    let points: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM user_wars_points WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(&app.state.postgres)
+            .await
+            .unwrap();
+    assert_eq!(points, 0, "practice games award no wars points");
+
+    let ratings: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM skill_ratings WHERE user_id = $1")
+        .bind(user_id)
+        .fetch_one(&app.state.postgres)
+        .await
+        .unwrap();
+    assert_eq!(ratings, 0, "practice games leave skill ratings alone");
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn lexi_wars_checks_words_with_its_validator() {
+    use std::sync::Arc;
+
+    use async_trait::async_trait;
+    use serde_json::json;
+    use stacks_wars_be::errors::AppError;
+    use stacks_wars_be::games::GameEngine;
+    use stacks_wars_be::games::lexi_wars::{InMemoryWordValidator, LexiWarsEngine, WordValidator};
+
+    /// A dictionary service that's down
+    struct Unreachable;
+
+    #[async_trait]
+    impl WordValidator for Unreachable {
+        async fn is_valid(&self, _word: &str) -> Result<bool, AppError> {
+            Err(AppError::FetchError("connection refused".to_string()))
+        }
+    }
+
+    let app = common::spawn_app_with_containers().await;
+    let factory = app.factory();
+
+    let (alice, _) = factory.create_test_user(None).await.expect("alice");
+    let (bob, _) = factory.create_test_user(None).await.expect("bob");
+    let game_row = factory
+        .create_test_game(alice, Some("validator"))
+        .await
+        .expect("create game failed");
+    let (lobby_id, _) = factory
+        .create_test_lobby(alice, game_row, Some("validator lobby"))
+        .await
+        .expect("create lobby failed");
+
+    let submit = |word: &str| json!({ "type": "submitWord", "word": word });
+    let reason = |events: Vec<serde_json::Value>| {
+        events
+            .iter()
+            .find(|e| e["type"] == "invalid")
+            .map(|e| e["reason"].as_str().unwrap_or_default().to_string())
+    };
+
+    // Only "qzxv" is a word here; "banana" isn't, bundled dictionary or not
+    let validator = Arc::new(InMemoryWordValidator::new(["qzxv"]));
+    let mut engine = LexiWarsEngine::with_validator(lobby_id, app.state.clone(), validator);
+    engine.initialize(vec![alice, bob]).await.unwrap();
+
+    let rejected = engine.handle_action(alice, submit("banana")).await.unwrap();
+    assert_eq!(
+        reason(rejected).as_deref(),
+        Some("'banana' is not in the dictionary")
+    );
+    let accepted = engine.handle_action(alice, submit("qzxv")).await.unwrap();
+    assert!(
+        !reason(accepted).is_some_and(|r| r.contains("dictionary")),
+        "qzxv gets past the dictionary"
+    );
+
+    // A failing backend rejects the word without ending the turn
+    let mut engine =
+        LexiWarsEngine::with_validator(lobby_id, app.state.clone(), Arc::new(Unreachable));
+    engine.initialize(vec![alice, bob]).await.unwrap();
+    let events = engine.handle_action(alice, submit("apple")).await.unwrap();
+    assert_eq!(
+        reason(events).as_deref(),
+        Some("Couldn't check 'apple' right now, try again")
+    );
+
+    // Junk is refused before the dictionary is asked
+    let err = engine
+        .handle_action(alice, submit("app1e"))
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(&err, AppError::GameError(e) if e.code() == "INVALID_WORD"),
+        "{:?}",
+        err
+    );
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn lexi_wars_scoring_mode_ranks_by_points() {
+    use std::sync::Arc;
+
+    use serde_json::json;
+    use stacks_wars_be::games::GameEngine;
+    use stacks_wars_be::games::lexi_wars::{
+        InMemoryWordValidator, LexiWarsConfig, LexiWarsEngine, LexiWarsMode, word_points,
+    };
+
+    let app = common::spawn_app_with_containers().await;
+    let factory = app.factory();
+
+    let (alice, _) = factory.create_test_user(None).await.expect("alice");
+    let (bob, _) = factory.create_test_user(None).await.expect("bob");
+    let game_row = factory
+        .create_test_game(alice, Some("scoring"))
+        .await
+        .expect("create game failed");
+    let (lobby_id, _) = factory
+        .create_test_lobby(alice, game_row, Some("scoring lobby"))
+        .await
+        .expect("create lobby failed");
+
+    let mut state = app.state.clone();
+    state.config.lexi_wars = LexiWarsConfig {
+        mode: LexiWarsMode::Scoring {
+            time_limit_secs: 60,
+        },
+        ..Default::default()
+    };
+    let validator = Arc::new(InMemoryWordValidator::new(["quiz", "apple"]));
+    let mut engine = LexiWarsEngine::with_validator(lobby_id, state, validator);
+    engine.initialize(vec![alice, bob]).await.unwrap();
+
+    let bootstrap = engine.get_bootstrap().await.unwrap();
+    assert_eq!(bootstrap["mode"], "scoring");
+    assert_eq!(bootstrap["timeLimitSecs"], 60);
+
+    // The loop isn't running, so the turn stays with alice
+    let mut total = 0;
+    for word in ["quiz", "apple"] {
+        let events = engine
+            .handle_action(alice, json!({ "type": "submitWord", "word": word }))
+            .await
+            .unwrap();
+        let update = events
+            .iter()
+            .find(|e| e["type"] == "scoreUpdate")
+            .expect("scoreUpdate");
+        total += word_points(word);
+        assert_eq!(update["points"], word_points(word));
+        assert_eq!(update["score"], total);
+    }
+
+    let state = engine.get_game_state(Some(bob)).await.unwrap();
+    assert_eq!(state["scores"][alice.to_string()], total);
+    assert_eq!(state["scores"][bob.to_string()], 0);
+
+    assert!(engine.force_finish().await.unwrap());
+    let results = engine.get_results().await.unwrap().expect("no results");
+    let order: Vec<_> = results
+        .rankings
+        .iter()
+        .map(|r| (r.user_id, r.score))
+        .collect();
+    assert_eq!(order, vec![(alice, Some(total)), (bob, Some(0))]);
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn lexi_wars_engines_with_the_same_seed_draw_alike() {
+    use stacks_wars_be::games::LEXI_WARS_GAME_ID;
+
+    let app = common::spawn_app_with_containers().await;
+    let factory = app.factory();
+
+    let mut players = Vec::new();
+    for _ in 0..5 {
+        let (user_id, _) = factory.create_test_user(None).await.expect("user");
+        players.push(user_id);
+    }
+    let game_row = factory
+        .create_test_game(players[0], Some("seeded"))
+        .await
+        .expect("create game failed");
+    let (lobby_id, _) = factory
+        .create_test_lobby(players[0], game_row, Some("seeded lobby"))
+        .await
+        .expect("create lobby failed");
+
+    // A configured seed stands in for the fresh one each game would draw
+    let mut state = app.state.clone();
+    state.config.game_rng_seed = Some(42);
+    let create_engine = app.state.game_registry[&LEXI_WARS_GAME_ID];
+
+    let mut draws = Vec::new();
+    for listed in [players.clone(), players.iter().rev().copied().collect()] {
+        let mut engine = create_engine(lobby_id, state.clone());
+        engine
+            .set_seeds(state.config.game_seed(), state.config.game_seed())
+            .await;
+        let events = engine.initialize(listed).await.expect("initialize");
+        let started = events
+            .into_iter()
+            .find(|e| e["type"] == "gameStarted")
+            .expect("gameStarted event");
+        let bootstrap = engine.get_bootstrap().await.expect("bootstrap");
+        draws.push((started["seats"].clone(), bootstrap["currentPlayer"].clone()));
+    }
+
+    assert_eq!(draws[0], draws[1]);
+
+    app.stop().await;
+}
@@ -0,0 +1,246 @@
+// Event-replay harness for GameEngine implementations
+//
+// A fixture is a JSON file under tests/games/fixtures describing the players,
+// the events `initialize` should emit, and a sequence of actions with the
+// events (or error) each one should produce:
+//
+// {
+//   "gameId": "<registry id>",
+//   "players": ["alice", "bob"],
+//   "initialEvents": [ ... ],
+//   "steps": [
+//     { "player": "alice", "action": { ... }, "events": [ ... ] },
+//     { "player": "bob", "action": { ... }, "error": "Bad request: Not your turn" }
+//   ]
+// }
+//
+// Emitted events are normalized before comparison: player ids become their
+// alias ("alice"), player wallets become "alice:wallet", any other UUID
+// becomes "<uuid>" and timestamp fields (keys ending in `At` or `Ms`, and
+// `lastPing`) become "<ts>". Expected events only need to list the fields a
+// fixture cares about; extra fields in the emitted events are ignored, but
+// arrays must match in length.
+//
+// Set UPDATE_GOLDEN=1 to rewrite a fixture's expectations from a run.
+
+use std::{collections::HashMap, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::common::TestApp;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayFixture {
+    pub game_id: Uuid,
+    pub players: Vec<String>,
+    #[serde(default)]
+    pub initial_events: Vec<Value>,
+    pub steps: Vec<ReplayStep>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayStep {
+    pub player: String,
+    pub action: Value,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<Value>,
+    /// Expected `AppError` display string, if the action should be rejected
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+fn fixture_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/games/fixtures")
+        .join(format!("{}.json", name))
+}
+
+/// Replay the named fixture against a fresh engine and assert every step.
+///
+/// Players are created as users and joined to a new lobby (the first one as
+/// creator) before the engine is built from the app's game registry.
+pub async fn run_replay(app: &TestApp, name: &str) {
+    let path = fixture_path(name);
+    let raw = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("failed to read fixture {}: {}", path.display(), e));
+    let mut fixture: ReplayFixture = serde_json::from_str(&raw)
+        .unwrap_or_else(|e| panic!("invalid fixture {}: {}", path.display(), e));
+
+    let factory = app.factory();
+    let mut normalizer = Normalizer::default();
+    let mut ids = HashMap::new();
+
+    for alias in &fixture.players {
+        let (user_id, _) = factory
+            .create_test_user(None)
+            .await
+            .expect("create user failed");
+        let user = stacks_wars_be::db::user::UserRepository::new(app.state.postgres.clone())
+            .find_by_id(user_id)
+            .await
+            .expect("user lookup failed");
+        normalizer
+            .aliases
+            .insert(user_id.to_string(), alias.clone());
+        normalizer
+            .aliases
+            .insert(user.wallet_address.to_string(), format!("{}:wallet", alias));
+        ids.insert(alias.clone(), user_id);
+    }
+
+    let player_ids: Vec<Uuid> = fixture.players.iter().map(|a| ids[a]).collect();
+    let creator = player_ids[0];
+
+    let game_row = factory
+        .create_test_game(creator, Some(&format!("replay {}", Uuid::new_v4())))
+        .await
+        .expect("create game failed");
+    let (lobby_id, _) = factory
+        .create_test_lobby(creator, game_row, Some("replay lobby"))
+        .await
+        .expect("create lobby failed");
+    for &player in &player_ids[1..] {
+        factory
+            .add_test_player(lobby_id, player, false)
+            .await
+            .expect("add player failed");
+    }
+
+    let create_engine = app
+        .state
+        .game_registry
+        .get(&fixture.game_id)
+        .unwrap_or_else(|| panic!("game {} is not registered", fixture.game_id));
+    let mut engine = create_engine(lobby_id, app.state.clone());
+
+    let update = std::env::var("UPDATE_GOLDEN").is_ok_and(|v| v == "1");
+
+    let initial = engine
+        .initialize(player_ids)
+        .await
+        .expect("initialize failed");
+    let initial = normalizer.normalize_all(initial);
+    if update {
+        fixture.initial_events = initial;
+    } else {
+        assert_events(name, "initialize", &fixture.initial_events, &initial);
+    }
+
+    for (i, step) in fixture.steps.iter_mut().enumerate() {
+        let user_id = *ids
+            .get(&step.player)
+            .unwrap_or_else(|| panic!("step {} uses unknown player {}", i, step.player));
+        let context = format!("step {} ({})", i, step.player);
+
+        match engine.handle_action(user_id, step.action.clone()).await {
+            Ok(events) => {
+                let events = normalizer.normalize_all(events);
+                if update {
+                    step.events = events;
+                    step.error = None;
+                    continue;
+                }
+                assert!(
+                    step.error.is_none(),
+                    "{} {}: expected error {:?}, got events {:#}",
+                    name,
+                    context,
+                    step.error,
+                    Value::Array(events)
+                );
+                assert_events(name, &context, &step.events, &events);
+            }
+            Err(e) => {
+                if update {
+                    step.events.clear();
+                    step.error = Some(e.to_string());
+                    continue;
+                }
+                assert_eq!(
+                    step.error.as_deref(),
+                    Some(e.to_string().as_str()),
+                    "{} {}: unexpected error",
+                    name,
+                    context
+                );
+            }
+        }
+    }
+
+    if update {
+        let json = serde_json::to_string_pretty(&fixture).expect("serialize fixture");
+        std::fs::write(&path, json + "\n").expect("write fixture");
+    }
+}
+
+fn assert_events(name: &str, context: &str, expected: &[Value], actual: &[Value]) {
+    let expected = Value::Array(expected.to_vec());
+    let actual = Value::Array(actual.to_vec());
+    assert!(
+        matches_subset(&expected, &actual),
+        "{} {}: events differ\nexpected: {:#}\nactual:   {:#}",
+        name,
+        context,
+        expected,
+        actual
+    );
+}
+
+/// Whether `actual` has everything in `expected`; objects may carry extra keys.
+fn matches_subset(expected: &Value, actual: &Value) -> bool {
+    match (expected, actual) {
+        (Value::Object(e), Value::Object(a)) => e
+            .iter()
+            .all(|(k, v)| a.get(k).is_some_and(|av| matches_subset(v, av))),
+        (Value::Array(e), Value::Array(a)) => {
+            e.len() == a.len() && e.iter().zip(a).all(|(ev, av)| matches_subset(ev, av))
+        }
+        _ => expected == actual,
+    }
+}
+
+#[derive(Default)]
+struct Normalizer {
+    /// Known ids and wallets mapped to readable names
+    aliases: HashMap<String, String>,
+}
+
+impl Normalizer {
+    fn normalize_all(&self, values: Vec<Value>) -> Vec<Value> {
+        values.into_iter().map(|v| self.normalize(v)).collect()
+    }
+
+    fn normalize(&self, value: Value) -> Value {
+        match value {
+            Value::Object(map) => Value::Object(
+                map.into_iter()
+                    .map(|(k, v)| {
+                        let v = if is_timestamp_key(&k) && !v.is_null() {
+                            Value::String("<ts>".into())
+                        } else {
+                            self.normalize(v)
+                        };
+                        (k, v)
+                    })
+                    .collect(),
+            ),
+            Value::Array(items) => {
+                Value::Array(items.into_iter().map(|v| self.normalize(v)).collect())
+            }
+            Value::String(s) => match self.aliases.get(&s) {
+                Some(alias) => Value::String(alias.clone()),
+                None if Uuid::parse_str(&s).is_ok() => Value::String("<uuid>".into()),
+                None => Value::String(s),
+            },
+            other => other,
+        }
+    }
+}
+
+fn is_timestamp_key(key: &str) -> bool {
+    key.ends_with("At") || key.ends_with("Ms") || key == "lastPing"
+}
@@ -18,3 +18,36 @@ mod user;
 
 #[path = "http_routes/platform_rating.rs"]
 mod platform_rating;
+
+#[path = "http_routes/matchmaking.rs"]
+mod matchmaking;
+
+#[path = "http_routes/admin.rs"]
+mod admin;
+
+#[path = "http_routes/report.rs"]
+mod report;
+
+#[path = "http_routes/cors.rs"]
+mod cors;
+
+#[path = "http_routes/stacks.rs"]
+mod stacks;
+
+#[path = "http_routes/redis_lock.rs"]
+mod redis_lock;
+
+#[path = "http_routes/hydration.rs"]
+mod hydration;
+
+#[path = "http_routes/consistency.rs"]
+mod consistency;
+
+#[path = "http_routes/chat.rs"]
+mod chat;
+
+#[path = "http_routes/stats.rs"]
+mod stats;
+
+#[path = "http_routes/contract_calls.rs"]
+mod contract_calls;
@@ -0,0 +1,806 @@
+use serde_json::json;
+
+use crate::common::TEST_ADMIN_WALLET;
+
+async fn count(pool: &sqlx::PgPool, sql: &str, name: &str) -> i64 {
+    sqlx::query_scalar(sql)
+        .bind(name)
+        .fetch_one(pool)
+        .await
+        .expect("count query failed")
+}
+
+#[tokio::test]
+async fn seed_bundle_inserts_and_skips_existing() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+    let factory = app.factory();
+
+    let (_, token) = factory
+        .create_test_user(Some(TEST_ADMIN_WALLET))
+        .await
+        .expect("create admin failed");
+
+    let bundle = json!({
+        "seasons": [
+            { "name": "seed-season-1", "startDate": "2026-01-01 00:00:00", "endDate": "2026-02-01 00:00:00" },
+            { "name": "seed-season-2", "startDate": "2026-02-01 00:00:00", "endDate": "2026-03-01 00:00:00" },
+        ],
+        "games": [{
+            "name": "Seeded Game",
+            "path": "seeded-game",
+            "description": "seeded",
+            "imageUrl": "https://example.com/seeded.png",
+            "minPlayers": 2,
+            "maxPlayers": 6,
+        }],
+    });
+
+    let resp = client
+        .post(format!("{}/api/admin/seed", app.base_url))
+        .header("Cookie", factory.create_auth_cookie(&token))
+        .json(&bundle)
+        .send()
+        .await
+        .expect("request failed");
+    assert!(resp.status().is_success());
+    let body: serde_json::Value = resp.json().await.expect("invalid json");
+    assert_eq!(body["seasons"]["inserted"], 2);
+    assert_eq!(body["games"]["inserted"], 1);
+
+    // Re-seeding the same bundle skips everything by default
+    let resp = client
+        .post(format!("{}/api/admin/seed", app.base_url))
+        .header("Cookie", factory.create_auth_cookie(&token))
+        .json(&bundle)
+        .send()
+        .await
+        .expect("request failed");
+    let body: serde_json::Value = resp.json().await.expect("invalid json");
+    assert_eq!(body["seasons"]["skipped"], 2);
+    assert_eq!(body["games"]["skipped"], 1);
+
+    let seasons = count(
+        &factory.pg_pool,
+        "SELECT COUNT(*) FROM seasons WHERE name = $1",
+        "seed-season-1",
+    )
+    .await;
+    assert_eq!(seasons, 1);
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn seed_bundle_with_invalid_entry_inserts_nothing() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+    let factory = app.factory();
+
+    let (_, token) = factory
+        .create_test_user(Some(TEST_ADMIN_WALLET))
+        .await
+        .expect("create admin failed");
+
+    let bundle = json!({
+        "seasons": [
+            { "name": "rollback-season", "startDate": "2026-01-01 00:00:00", "endDate": "2026-02-01 00:00:00" },
+        ],
+        "games": [{
+            "name": "Broken Game",
+            "path": "broken-game",
+            "description": "max below min",
+            "imageUrl": "https://example.com/broken.png",
+            "minPlayers": 4,
+            "maxPlayers": 2,
+        }],
+    });
+
+    let resp = client
+        .post(format!("{}/api/admin/seed", app.base_url))
+        .header("Cookie", factory.create_auth_cookie(&token))
+        .json(&bundle)
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), reqwest::StatusCode::BAD_REQUEST);
+    let body = resp.text().await.expect("invalid body");
+    assert!(body.contains("games[0]"));
+
+    let seasons = count(
+        &factory.pg_pool,
+        "SELECT COUNT(*) FROM seasons WHERE name = $1",
+        "rollback-season",
+    )
+    .await;
+    assert_eq!(seasons, 0);
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn seed_requires_admin() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+    let factory = app.factory();
+
+    let (_, token) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+
+    let resp = client
+        .post(format!("{}/api/admin/seed", app.base_url))
+        .header("Cookie", factory.create_auth_cookie(&token))
+        .json(&json!({ "seasons": [], "games": [] }))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), reqwest::StatusCode::FORBIDDEN);
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn toggling_a_feature_flag_gates_its_endpoint_without_restart() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+    let factory = app.factory();
+
+    let (_, admin_token) = factory
+        .create_test_user(Some(TEST_ADMIN_WALLET))
+        .await
+        .expect("create admin failed");
+    let (creator_id, _) = factory.create_test_user(None).await.expect("create user");
+    let game_id = factory
+        .create_game_with_players(creator_id, Some("flagged-quick-play"), 2, 4)
+        .await
+        .expect("create game failed");
+    let (_, player_token) = factory.create_test_user(None).await.expect("create user");
+
+    let set_flag = |enabled: serde_json::Value| {
+        client
+            .put(format!("{}/api/admin/features/matchmaking", app.base_url))
+            .header("Cookie", factory.create_auth_cookie(&admin_token))
+            .json(&json!({ "enabled": enabled }))
+            .send()
+    };
+    let enqueue = || {
+        client
+            .post(format!("{}/api/matchmaking", app.base_url))
+            .header("Cookie", factory.create_auth_cookie(&player_token))
+            .json(&json!({ "gameId": game_id }))
+            .send()
+    };
+
+    // Switched off: the queue refuses players
+    let resp = set_flag(json!(false)).await.expect("request failed");
+    assert!(resp.status().is_success());
+    let body: serde_json::Value = resp.json().await.expect("invalid json");
+    assert_eq!(body["enabled"], false);
+    assert_eq!(body["overridden"], true);
+
+    let resp = enqueue().await.expect("request failed");
+    assert_eq!(resp.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+    let body = resp.text().await.expect("invalid body");
+    assert!(
+        body.contains("Matchmaking is currently disabled"),
+        "{}",
+        body
+    );
+
+    // Back to the default (on): the same request is accepted
+    let resp = set_flag(serde_json::Value::Null)
+        .await
+        .expect("request failed");
+    let body: serde_json::Value = resp.json().await.expect("invalid json");
+    assert_eq!(body["enabled"], true);
+    assert_eq!(body["overridden"], false);
+
+    let resp = enqueue().await.expect("request failed");
+    assert_eq!(resp.status(), reqwest::StatusCode::OK);
+
+    let resp = client
+        .get(format!("{}/api/admin/features", app.base_url))
+        .header("Cookie", factory.create_auth_cookie(&admin_token))
+        .send()
+        .await
+        .expect("request failed");
+    let flags: serde_json::Value = resp.json().await.expect("invalid json");
+    assert!(
+        flags
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|flag| flag["feature"] == "matchmaking" && flag["enabled"] == true)
+    );
+
+    // Only admins may flip flags
+    let resp = client
+        .put(format!("{}/api/admin/features/matchmaking", app.base_url))
+        .header("Cookie", factory.create_auth_cookie(&player_token))
+        .json(&json!({ "enabled": false }))
+        .send()
+        .await
+        .expect("request failed");
+    assert!(resp.status().is_client_error());
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn lobby_summary_counts_by_status_and_game() {
+    use stacks_wars_be::db::lobby_state::LobbyStateRepository;
+    use stacks_wars_be::models::LobbyStatus;
+
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+    let factory = app.factory();
+
+    let (admin_id, token) = factory
+        .create_test_user(Some(TEST_ADMIN_WALLET))
+        .await
+        .expect("create admin failed");
+    let lexi = factory
+        .create_test_game(admin_id, Some("summary-a"))
+        .await
+        .expect("create game");
+    let other = factory
+        .create_test_game(admin_id, Some("summary-b"))
+        .await
+        .expect("create game");
+
+    let lobby_state = LobbyStateRepository::new(app.state.redis.clone());
+    let set_status = |lobby_id: uuid::Uuid, status: LobbyStatus| {
+        let pool = app.state.postgres.clone();
+        let lobby_state = lobby_state.clone();
+        async move {
+            sqlx::query("UPDATE lobbies SET status = $1 WHERE id = $2")
+                .bind(status)
+                .bind(lobby_id)
+                .execute(&pool)
+                .await
+                .expect("update lobby status");
+            lobby_state
+                .update_status(lobby_id, status)
+                .await
+                .expect("update runtime status");
+        }
+    };
+
+    let mut lexi_lobbies = Vec::new();
+    for i in 0..4 {
+        let (lobby_id, _) = factory
+            .create_test_lobby(admin_id, lexi, Some(&format!("summary {}", i)))
+            .await
+            .expect("create lobby");
+        lexi_lobbies.push(lobby_id);
+    }
+    set_status(lexi_lobbies[3], LobbyStatus::Finished).await;
+    let (other_lobby, _) = factory
+        .create_test_lobby(admin_id, other, Some("summary other"))
+        .await
+        .expect("create lobby");
+    set_status(other_lobby, LobbyStatus::InProgress).await;
+
+    // Started in Redis, row not yet written
+    lobby_state
+        .update_status(lexi_lobbies[0], LobbyStatus::InProgress)
+        .await
+        .expect("update runtime status");
+
+    let resp = client
+        .get(format!("{}/api/admin/lobbies/summary", app.base_url))
+        .header("Cookie", factory.create_auth_cookie(&token))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status().as_u16(), 200);
+    let body: serde_json::Value = resp.json().await.expect("invalid json");
+
+    let count = |game_id: uuid::Uuid, status: &str| {
+        body["counts"]
+            .as_array()
+            .expect("counts")
+            .iter()
+            .find(|c| c["gameId"] == game_id.to_string() && c["status"] == status)
+            .map(|c| c["count"].as_i64().unwrap())
+    };
+    assert_eq!(count(lexi, "waiting"), Some(2));
+    assert_eq!(count(lexi, "inProgress"), Some(1));
+    assert_eq!(count(lexi, "finished"), Some(1));
+    assert_eq!(count(other, "inProgress"), Some(1));
+    assert_eq!(count(other, "waiting"), None);
+    assert!(body["corrected"].as_u64().unwrap() >= 1);
+
+    // Players don't get the dashboard
+    let (_, player_token) = factory.create_test_user(None).await.expect("create user");
+    let resp = client
+        .get(format!("{}/api/admin/lobbies/summary", app.base_url))
+        .header("Cookie", factory.create_auth_cookie(&player_token))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status().as_u16(), 403);
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn denied_wallet_is_refused_on_requests_and_connections() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+    let factory = app.factory();
+
+    const DENIED: &str = "SP3DENYD00000000000000000000000000000000A";
+    let (_, admin_token) = factory
+        .create_test_user(Some(TEST_ADMIN_WALLET))
+        .await
+        .expect("create admin failed");
+    let (user_id, token) = factory
+        .create_test_user(Some(DENIED))
+        .await
+        .expect("create user failed");
+    let game_id = factory
+        .create_test_game(user_id, None)
+        .await
+        .expect("create game failed");
+    let (_, lobby_path) = factory
+        .create_test_lobby(user_id, game_id, None)
+        .await
+        .expect("create lobby failed");
+
+    let resp = client
+        .put(format!(
+            "{}/api/admin/wallet-access/deny/{}",
+            app.base_url, DENIED
+        ))
+        .header("Cookie", factory.create_auth_cookie(&admin_token))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), reqwest::StatusCode::OK);
+    let body: serde_json::Value = resp.json().await.expect("invalid json");
+    assert_eq!(body["deny"], json!([DENIED]));
+
+    let resp = client
+        .get(format!("{}/api/me", app.base_url))
+        .header("Cookie", factory.create_auth_cookie(&token))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), reqwest::StatusCode::FORBIDDEN);
+    let body = resp.text().await.expect("invalid body");
+    assert!(body.contains("denied access"), "{}", body);
+
+    // Signing in again doesn't get round it
+    let resp = client
+        .post(format!("{}/api/user", app.base_url))
+        .json(&json!({
+            "walletAddress": DENIED,
+            "nonce": "00",
+            "signature": "00",
+            "publicKey": "00"
+        }))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), reqwest::StatusCode::FORBIDDEN);
+
+    assert!(
+        crate::common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &token)
+            .await
+            .is_err(),
+        "denied wallet should not get a room connection"
+    );
+
+    // Lifting the denial lets the wallet back in
+    let resp = client
+        .delete(format!(
+            "{}/api/admin/wallet-access/deny/{}",
+            app.base_url, DENIED
+        ))
+        .header("Cookie", factory.create_auth_cookie(&admin_token))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), reqwest::StatusCode::OK);
+
+    let resp = client
+        .get(format!("{}/api/me", app.base_url))
+        .header("Cookie", factory.create_auth_cookie(&token))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), reqwest::StatusCode::OK);
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn allowlist_only_mode_admits_listed_wallets() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+    let factory = app.factory();
+
+    const INVITED: &str = "SP3NV1TED0000000000000000000000000000000A";
+    let (_, admin_token) = factory
+        .create_test_user(Some(TEST_ADMIN_WALLET))
+        .await
+        .expect("create admin failed");
+    let (_, invited_token) = factory
+        .create_test_user(Some(INVITED))
+        .await
+        .expect("create user failed");
+    let (_, other_token) = factory.create_test_user(None).await.expect("create user");
+
+    let me = |token: &str| {
+        client
+            .get(format!("{}/api/me", app.base_url))
+            .header("Cookie", factory.create_auth_cookie(token))
+            .send()
+    };
+
+    let resp = client
+        .put(format!("{}/api/admin/wallet-access/mode", app.base_url))
+        .header("Cookie", factory.create_auth_cookie(&admin_token))
+        .json(&json!({ "allowlistOnly": true }))
+        .send()
+        .await
+        .expect("request failed");
+    let body: serde_json::Value = resp.json().await.expect("invalid json");
+    assert_eq!(body["allowlistOnly"], true);
+    assert_eq!(body["modeOverridden"], true);
+
+    // Nobody is listed yet; only the admin still gets through
+    let resp = me(&invited_token).await.expect("request failed");
+    assert_eq!(resp.status(), reqwest::StatusCode::FORBIDDEN);
+    let resp = me(&admin_token).await.expect("request failed");
+    assert_eq!(resp.status(), reqwest::StatusCode::OK);
+
+    let resp = client
+        .put(format!(
+            "{}/api/admin/wallet-access/allow/{}",
+            app.base_url, INVITED
+        ))
+        .header("Cookie", factory.create_auth_cookie(&admin_token))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), reqwest::StatusCode::OK);
+
+    let resp = me(&invited_token).await.expect("request failed");
+    assert_eq!(resp.status(), reqwest::StatusCode::OK);
+    let resp = me(&other_token).await.expect("request failed");
+    assert_eq!(resp.status(), reqwest::StatusCode::FORBIDDEN);
+
+    // Back to the configured mode, where everyone gets in
+    let resp = client
+        .put(format!("{}/api/admin/wallet-access/mode", app.base_url))
+        .header("Cookie", factory.create_auth_cookie(&admin_token))
+        .json(&json!({ "allowlistOnly": null }))
+        .send()
+        .await
+        .expect("request failed");
+    let body: serde_json::Value = resp.json().await.expect("invalid json");
+    assert_eq!(body["allowlistOnly"], false);
+
+    let resp = me(&other_token).await.expect("request failed");
+    assert_eq!(resp.status(), reqwest::StatusCode::OK);
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn moderators_work_reports_but_not_admin_tools() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+    let factory = app.factory();
+
+    let (_, admin_token) = factory
+        .create_test_user(Some(TEST_ADMIN_WALLET))
+        .await
+        .expect("create admin failed");
+    let (moderator_id, moderator_token) = factory
+        .create_test_user(None)
+        .await
+        .expect("create moderator failed");
+    let (_, user_token) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+
+    let resp = client
+        .put(format!(
+            "{}/api/admin/users/{}/role",
+            app.base_url, moderator_id
+        ))
+        .header("Cookie", factory.create_auth_cookie(&admin_token))
+        .json(&json!({ "role": "moderator" }))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), reqwest::StatusCode::NO_CONTENT);
+
+    let reports = |token: &str| {
+        client
+            .get(format!("{}/api/admin/reports", app.base_url))
+            .header("Cookie", factory.create_auth_cookie(token))
+            .send()
+    };
+    let seed = |token: &str| {
+        client
+            .post(format!("{}/api/admin/seed", app.base_url))
+            .header("Cookie", factory.create_auth_cookie(token))
+            .json(&json!({ "seasons": [], "games": [] }))
+            .send()
+    };
+
+    // Moderators see the report queue but can't reach admin-only tools
+    let resp = reports(&moderator_token).await.expect("request failed");
+    assert_eq!(resp.status(), reqwest::StatusCode::OK);
+    let resp = seed(&moderator_token).await.expect("request failed");
+    assert_eq!(resp.status(), reqwest::StatusCode::FORBIDDEN);
+    assert!(resp.text().await.unwrap().contains("Admin access required"));
+    let resp = client
+        .put(format!(
+            "{}/api/admin/users/{}/role",
+            app.base_url, moderator_id
+        ))
+        .header("Cookie", factory.create_auth_cookie(&moderator_token))
+        .json(&json!({ "role": "admin" }))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), reqwest::StatusCode::FORBIDDEN);
+
+    // Admins can do both; plain users neither
+    let resp = reports(&admin_token).await.expect("request failed");
+    assert_eq!(resp.status(), reqwest::StatusCode::OK);
+    let resp = seed(&admin_token).await.expect("request failed");
+    assert_eq!(resp.status(), reqwest::StatusCode::OK);
+    let resp = reports(&user_token).await.expect("request failed");
+    assert_eq!(resp.status(), reqwest::StatusCode::FORBIDDEN);
+    assert!(
+        resp.text()
+            .await
+            .unwrap()
+            .contains("Moderator access required")
+    );
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn reprocessing_points_corrects_awards_once() {
+    use stacks_wars_be::{
+        db::lobby_result::LobbyResultRepository,
+        games::{
+            GameResults,
+            common::{WarsPointContext, save_player_result},
+        },
+    };
+
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+    let factory = app.factory();
+    let pool = app.state.postgres.clone();
+
+    let season_id = factory.create_test_season(None).await.unwrap() as i32;
+    let (_, token) = factory
+        .create_test_user(Some(TEST_ADMIN_WALLET))
+        .await
+        .expect("create admin failed");
+    let (alice, _) = factory.create_test_user(None).await.unwrap();
+    let (bob, _) = factory.create_test_user(None).await.unwrap();
+    let game_id = factory.create_test_game(bob, None).await.unwrap();
+    let (lobby_id, _) = factory
+        .create_test_lobby(bob, game_id, Some("reprocess"))
+        .await
+        .unwrap();
+
+    for (user_id, rank) in [(bob, 2), (alice, 1)] {
+        save_player_result(
+            &app.state,
+            lobby_id,
+            &WarsPointContext {
+                user_id,
+                game_id,
+                rank,
+                prize: None,
+                participants: 2,
+                entry_amount: None,
+                current_amount: None,
+                is_sponsored: false,
+                creator_id: Some(bob),
+                active_players: 1,
+            },
+        )
+        .await
+        .expect("save result");
+    }
+    LobbyResultRepository::new(pool.clone())
+        .store(
+            lobby_id,
+            &GameResults::from_ordered_players(vec![alice, bob]),
+        )
+        .await
+        .expect("store results");
+
+    // Alice's win was awarded 10 points under an older formula; today it's 4
+    for sql in [
+        "UPDATE wars_point_awards SET points = 10 WHERE user_id = $1",
+        "UPDATE user_wars_points SET points = 10 WHERE user_id = $1",
+        "UPDATE user_game_wars_points SET points = 10 WHERE user_id = $1",
+    ] {
+        sqlx::query(sql).bind(alice).execute(&pool).await.unwrap();
+    }
+
+    let totals = |user_id: uuid::Uuid| {
+        let pool = pool.clone();
+        async move {
+            let season: f64 = sqlx::query_scalar(
+                "SELECT points FROM user_wars_points WHERE user_id = $1 AND season_id = $2",
+            )
+            .bind(user_id)
+            .bind(season_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            let game: f64 = sqlx::query_scalar(
+                "SELECT points FROM user_game_wars_points WHERE user_id = $1 AND game_id = $2",
+            )
+            .bind(user_id)
+            .bind(game_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            (season, game)
+        }
+    };
+
+    let reprocess = || {
+        client
+            .post(format!(
+                "{}/api/admin/lobbies/{}/reprocess-points",
+                app.base_url, lobby_id
+            ))
+            .header("Cookie", factory.create_auth_cookie(&token))
+            .send()
+    };
+
+    let resp = reprocess().await.expect("request failed");
+    assert_eq!(resp.status().as_u16(), 200);
+    let body: serde_json::Value = resp.json().await.expect("invalid json");
+    assert_eq!(body["lobbyId"], lobby_id.to_string());
+    let award = |user_id: uuid::Uuid| {
+        body["awards"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|a| a["userId"] == user_id.to_string())
+            .cloned()
+            .expect("award for player")
+    };
+    assert_eq!(award(alice)["previous"], 10.0);
+    assert_eq!(award(alice)["points"], 4.0);
+    assert_eq!(award(bob)["previous"], 2.0);
+    assert_eq!(award(bob)["points"], 2.0);
+    assert_eq!(totals(alice).await, (4.0, 4.0));
+    assert_eq!(totals(bob).await, (2.0, 2.0));
+
+    // Running it again changes nothing
+    let resp = reprocess().await.expect("request failed");
+    assert_eq!(resp.status().as_u16(), 200);
+    let body: serde_json::Value = resp.json().await.expect("invalid json");
+    assert!(
+        body["awards"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|a| a["previous"] == a["points"])
+    );
+    assert_eq!(totals(alice).await, (4.0, 4.0));
+    assert_eq!(totals(bob).await, (2.0, 2.0));
+
+    // Bob sponsors a three-player lobby. Carol goes out first with two still
+    // in, then Bob with only Alice left, so his sponsor bonus counts one
+    let (carol, _) = factory.create_test_user(None).await.unwrap();
+    let (sponsored_lobby, _) = factory
+        .create_test_lobby(bob, game_id, Some("sponsored"))
+        .await
+        .unwrap();
+    sqlx::query("UPDATE lobbies SET is_sponsored = TRUE WHERE id = $1")
+        .bind(sponsored_lobby)
+        .execute(&pool)
+        .await
+        .unwrap();
+    for (user_id, rank, active_players) in [(carol, 3, 2), (bob, 2, 1), (alice, 1, 1)] {
+        save_player_result(
+            &app.state,
+            sponsored_lobby,
+            &WarsPointContext {
+                user_id,
+                game_id,
+                rank,
+                prize: None,
+                participants: 3,
+                entry_amount: None,
+                current_amount: None,
+                is_sponsored: true,
+                creator_id: Some(bob),
+                active_players,
+            },
+        )
+        .await
+        .expect("save result");
+    }
+    LobbyResultRepository::new(pool.clone())
+        .store(
+            sponsored_lobby,
+            &GameResults::from_ordered_players(vec![alice, bob, carol]),
+        )
+        .await
+        .expect("store results");
+
+    let reprocess_sponsored = || async {
+        let resp = client
+            .post(format!(
+                "{}/api/admin/lobbies/{}/reprocess-points",
+                app.base_url, sponsored_lobby
+            ))
+            .header("Cookie", factory.create_auth_cookie(&token))
+            .send()
+            .await
+            .expect("request failed");
+        assert_eq!(resp.status().as_u16(), 200);
+        let body: serde_json::Value = resp.json().await.expect("invalid json");
+        body["awards"].as_array().unwrap().clone()
+    };
+
+    // Nothing changed in the formula, so reprocessing corrects nothing
+    let awards = reprocess_sponsored().await;
+    assert_eq!(awards.len(), 3);
+    assert!(
+        awards.iter().all(|a| a["previous"] == a["points"]),
+        "no corrections: {:?}",
+        awards
+    );
+    let bob_award = awards
+        .iter()
+        .find(|a| a["userId"] == bob.to_string())
+        .unwrap();
+    assert_eq!(bob_award["points"], 6.5, "4 for second place + 2.5 bonus");
+
+    // Awards recorded before the active count was kept fall back to the
+    // count left once the player went out, which is the same here
+    sqlx::query("UPDATE wars_point_awards SET active_players = NULL WHERE lobby_id = $1")
+        .bind(sponsored_lobby)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let awards = reprocess_sponsored().await;
+    assert!(
+        awards.iter().all(|a| a["previous"] == a["points"]),
+        "no corrections: {:?}",
+        awards
+    );
+
+    // A lobby without stored results has nothing to reprocess
+    let (other_lobby, _) = factory
+        .create_test_lobby(bob, game_id, Some("no results"))
+        .await
+        .unwrap();
+    let resp = client
+        .post(format!(
+            "{}/api/admin/lobbies/{}/reprocess-points",
+            app.base_url, other_lobby
+        ))
+        .header("Cookie", factory.create_auth_cookie(&token))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status().as_u16(), 404);
+
+    app.stop().await;
+}
@@ -0,0 +1,298 @@
+use stacks_wars_be::db::lobby_chat::LobbyChatRepository;
+use stacks_wars_be::models::ChatCursor;
+use uuid::Uuid;
+
+/// Post `count` messages and return their ids in history order
+async fn seed_chat(chat: &LobbyChatRepository, lobby_id: Uuid, count: usize) -> Vec<Uuid> {
+    let mut messages = Vec::new();
+    for i in 0..count {
+        messages.push(
+            chat.create_message(lobby_id, Uuid::new_v4(), &format!("message {}", i), None)
+                .await
+                .expect("create message"),
+        );
+    }
+    messages.sort_by_key(|m| (m.created_at.timestamp(), m.message_id.to_string()));
+    messages.into_iter().map(|m| m.message_id).collect()
+}
+
+#[tokio::test]
+async fn chat_history_pages_backward_to_the_oldest_message() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let chat = LobbyChatRepository::new(app.state.redis.clone());
+    let lobby_id = Uuid::new_v4();
+    let ids = seed_chat(&chat, lobby_id, 7).await;
+
+    let newest = chat
+        .get_history_page(lobby_id, None, 3, None)
+        .await
+        .expect("newest page");
+    let page_ids: Vec<Uuid> = newest
+        .messages
+        .iter()
+        .map(|m| m.message.message_id)
+        .collect();
+    assert_eq!(page_ids, ids[4..]);
+    assert!(newest.has_more);
+    assert_eq!(newest.next_cursor, Some(ids[4]));
+
+    let mut seen = page_ids;
+    let mut cursor = newest.next_cursor;
+    while let Some(before) = cursor {
+        let page = chat
+            .get_history_page(lobby_id, Some(ChatCursor::Before(before)), 3, None)
+            .await
+            .expect("older page");
+        let mut older: Vec<Uuid> = page.messages.iter().map(|m| m.message.message_id).collect();
+        older.append(&mut seen);
+        seen = older;
+        cursor = page.next_cursor;
+    }
+    assert_eq!(seen, ids, "every message once, in order");
+
+    // And forward again from the oldest
+    let forward = chat
+        .get_history_page(lobby_id, Some(ChatCursor::After(ids[0])), 3, None)
+        .await
+        .expect("newer page");
+    let page_ids: Vec<Uuid> = forward
+        .messages
+        .iter()
+        .map(|m| m.message.message_id)
+        .collect();
+    assert_eq!(page_ids, ids[1..4]);
+    assert_eq!(forward.next_cursor, Some(ids[3]));
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn chat_history_has_more_is_exact_at_the_boundary() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let chat = LobbyChatRepository::new(app.state.redis.clone());
+    let lobby_id = Uuid::new_v4();
+    let ids = seed_chat(&chat, lobby_id, 6).await;
+
+    // Exactly the remaining messages: nothing more, no cursor
+    let page = chat
+        .get_history_page(lobby_id, Some(ChatCursor::Before(ids[3])), 3, None)
+        .await
+        .expect("last older page");
+    assert_eq!(page.messages.len(), 3);
+    assert!(!page.has_more);
+    assert_eq!(page.next_cursor, None);
+
+    // One short of the remaining messages: one more page
+    let page = chat
+        .get_history_page(lobby_id, Some(ChatCursor::Before(ids[3])), 2, None)
+        .await
+        .expect("older page");
+    assert!(page.has_more);
+    assert_eq!(page.next_cursor, Some(ids[1]));
+
+    let page = chat
+        .get_history_page(lobby_id, Some(ChatCursor::After(ids[2])), 3, None)
+        .await
+        .expect("last newer page");
+    assert_eq!(page.messages.len(), 3);
+    assert!(!page.has_more);
+
+    // Reactions come with each message
+    chat.add_reaction(lobby_id, ids[5], Uuid::new_v4(), "fire")
+        .await
+        .expect("add reaction");
+    let page = chat
+        .get_history_page(lobby_id, None, 1, None)
+        .await
+        .expect("newest page");
+    assert_eq!(page.messages[0].message.reactions.len(), 1);
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn chat_reactions_aggregate_per_viewer_and_are_idempotent() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let chat = LobbyChatRepository::new(app.state.redis.clone());
+    let lobby_id = Uuid::new_v4();
+    let ids = seed_chat(&chat, lobby_id, 1).await;
+    let (alice, bob, carol) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+    for (user, emoji) in [(alice, "fire"), (bob, "fire"), (carol, "heart")] {
+        let (_, changed) = chat
+            .add_reaction(lobby_id, ids[0], user, emoji)
+            .await
+            .expect("add reaction");
+        assert!(changed);
+    }
+
+    // Re-adding is a no-op
+    let (message, changed) = chat
+        .add_reaction(lobby_id, ids[0], alice, "fire")
+        .await
+        .expect("re-add reaction");
+    assert!(!changed);
+    assert_eq!(message.reactions.len(), 3);
+
+    let page = chat
+        .get_history_page(lobby_id, None, 10, Some(alice))
+        .await
+        .expect("history");
+    let summary = &page.messages[0].reaction_summary;
+    assert_eq!(summary.len(), 2);
+    assert_eq!((summary[0].emoji.as_str(), summary[0].count), ("fire", 2));
+    assert!(summary[0].reacted, "alice reacted with fire");
+    assert_eq!((summary[1].emoji.as_str(), summary[1].count), ("heart", 1));
+    assert!(!summary[1].reacted);
+
+    // Removing twice only removes once
+    let (_, changed) = chat
+        .remove_reaction(lobby_id, ids[0], bob, "fire")
+        .await
+        .expect("remove reaction");
+    assert!(changed);
+    let (message, changed) = chat
+        .remove_reaction(lobby_id, ids[0], bob, "fire")
+        .await
+        .expect("remove again");
+    assert!(!changed);
+    assert_eq!(message.reaction_summary(None)[0].count, 1);
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn mark_read_advances_the_read_pointer() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let chat = LobbyChatRepository::new(app.state.redis.clone());
+    let lobby_id = Uuid::new_v4();
+    let user_id = Uuid::new_v4();
+    let ids = seed_chat(&chat, lobby_id, 3).await;
+
+    assert!(chat.mark_read(lobby_id, user_id, ids[0]).await.unwrap());
+    assert!(chat.mark_read(lobby_id, user_id, ids[2]).await.unwrap());
+    // Marking the same message again changes nothing
+    assert!(!chat.mark_read(lobby_id, user_id, ids[2]).await.unwrap());
+
+    let receipts = chat.get_read_receipts(lobby_id).await.unwrap();
+    assert_eq!(receipts.get(&user_id), Some(&ids[2]));
+
+    assert!(
+        chat.mark_read(lobby_id, user_id, Uuid::new_v4())
+            .await
+            .is_err()
+    );
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn mark_read_ignores_older_messages() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let chat = LobbyChatRepository::new(app.state.redis.clone());
+    let lobby_id = Uuid::new_v4();
+    let user_id = Uuid::new_v4();
+    let ids = seed_chat(&chat, lobby_id, 3).await;
+
+    assert!(chat.mark_read(lobby_id, user_id, ids[1]).await.unwrap());
+    assert!(!chat.mark_read(lobby_id, user_id, ids[0]).await.unwrap());
+
+    let receipts = chat.get_read_receipts(lobby_id).await.unwrap();
+    assert_eq!(receipts.get(&user_id), Some(&ids[1]));
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn chat_retention_prunes_old_messages_and_keeps_finished_lobbies_longer() {
+    use redis::AsyncCommands;
+    use stacks_wars_be::chat_retention::prune_chat;
+    use stacks_wars_be::models::{LobbyStatus, RedisKey};
+    use stacks_wars_be::state::{ChatRetentionConfig, RetentionPolicy};
+
+    let app = crate::common::spawn_app_with_containers().await;
+    let factory = app.factory();
+    let chat = LobbyChatRepository::new(app.state.redis.clone());
+
+    let (creator_id, _) = factory.create_test_user(None).await.expect("user");
+    let game_id = factory
+        .create_test_game(creator_id, Some("retention-game"))
+        .await
+        .expect("game");
+    let (active, _) = factory
+        .create_test_lobby(creator_id, game_id, Some("active chat"))
+        .await
+        .expect("active lobby");
+    let (finished, _) = factory
+        .create_test_lobby(creator_id, game_id, Some("finished chat"))
+        .await
+        .expect("finished lobby");
+    stacks_wars_be::db::lobby::LobbyRepository::new(app.state.postgres.clone())
+        .update_status(finished, LobbyStatus::Finished, app.state.clone())
+        .await
+        .expect("finish lobby");
+
+    // The two oldest messages of each lobby were sent two hours ago
+    let two_hours_ago = chrono::Utc::now().timestamp() - 2 * 60 * 60;
+    let mut conn = app.state.redis.get().await.expect("redis");
+    let mut seeded = Vec::new();
+    for lobby_id in [active, finished] {
+        let ids = seed_chat(&chat, lobby_id, 4).await;
+        for id in &ids[..2] {
+            let _: () = conn
+                .zadd(
+                    RedisKey::lobby_chat(lobby_id),
+                    id.to_string(),
+                    two_hours_ago,
+                )
+                .await
+                .expect("backdate message");
+        }
+        seeded.push(ids);
+    }
+    drop(conn);
+
+    let retention = ChatRetentionConfig {
+        interval_secs: 60,
+        active: RetentionPolicy {
+            max_age_secs: 60 * 60,
+            max_messages: 0,
+        },
+        finished: RetentionPolicy {
+            max_age_secs: 3 * 60 * 60,
+            max_messages: 3,
+        },
+    };
+    let deleted = prune_chat(&app.state, &retention, chrono::Utc::now())
+        .await
+        .expect("prune chat");
+    assert_eq!(deleted, 3);
+
+    let remaining = |lobby_id| {
+        let chat = chat.clone();
+        async move {
+            let mut ids: Vec<Uuid> = chat
+                .get_history(lobby_id, Some(10))
+                .await
+                .expect("history")
+                .into_iter()
+                .map(|m| m.message_id)
+                .collect();
+            ids.reverse();
+            ids
+        }
+    };
+    // Active chat past the hour is gone; finished chat is only capped at three
+    assert_eq!(remaining(active).await, seeded[0][2..]);
+    assert_eq!(remaining(finished).await, seeded[1][1..]);
+
+    let mut conn = app.state.redis.get().await.expect("redis");
+    let pruned_data: bool = conn
+        .exists(RedisKey::lobby_chat_message(active, seeded[0][0]))
+        .await
+        .expect("exists");
+    assert!(!pruned_data);
+    drop(conn);
+
+    app.stop().await;
+}
@@ -0,0 +1,88 @@
+use redis::AsyncCommands;
+use stacks_wars_be::db::consistency::{self, LobbyDrift};
+use stacks_wars_be::db::lobby::LobbyRepository;
+use stacks_wars_be::models::RedisKey;
+
+#[tokio::test]
+async fn consistency_check_flags_and_repairs_drift() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let factory = app.factory();
+    let (creator_id, _t) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let game_id = factory
+        .create_test_game(creator_id, Some("consistency-game"))
+        .await
+        .expect("create game failed");
+    let (lobby_id, _path) = factory
+        .create_test_lobby(creator_id, game_id, Some("drifting"))
+        .await
+        .expect("create lobby failed");
+
+    // Start in sync, then let the Redis name drift
+    let repo = LobbyRepository::new(app.state.postgres.clone());
+    repo.sync_lobby_to_redis(lobby_id, &app.state.redis)
+        .await
+        .expect("sync");
+    let key = RedisKey::lobby_state(lobby_id);
+    let orphan = uuid::Uuid::new_v4();
+    {
+        let mut conn = app.state.redis.get().await.expect("redis conn");
+        let _: () = conn.hset(&key, "name", "stale").await.expect("redis hset");
+        let _: () = conn
+            .hset(RedisKey::lobby_state(orphan), "status", "Waiting")
+            .await
+            .expect("seed orphan state");
+    }
+
+    let report = consistency::check_lobbies(&app.state.redis, &app.state.postgres, false)
+        .await
+        .expect("check");
+    assert_eq!(report.checked, 1);
+    let drift = report
+        .mismatches
+        .iter()
+        .find(|m| m.lobby_id == Some(lobby_id))
+        .expect("name drift reported");
+    assert_eq!(
+        drift.drift,
+        LobbyDrift::FieldMismatch {
+            field: "name".into(),
+            postgres: Some("drifting".into()),
+            redis: Some("stale".into()),
+        }
+    );
+    assert!(!drift.repaired);
+    assert!(
+        report
+            .mismatches
+            .iter()
+            .any(|m| { m.lobby_id == Some(orphan) && m.drift == LobbyDrift::MissingInPostgres })
+    );
+
+    // Repair fixes the name but leaves the orphan for someone to look at
+    let report = consistency::check_lobbies(&app.state.redis, &app.state.postgres, true)
+        .await
+        .expect("check with repair");
+    let unrepaired: Vec<_> = report.unrepaired().collect();
+    assert_eq!(unrepaired.len(), 1);
+    assert_eq!(unrepaired[0].lobby_id, Some(orphan));
+
+    let mut conn = app.state.redis.get().await.expect("redis conn");
+    let name: Option<String> = conn.hget(&key, "name").await.expect("redis hget");
+    assert_eq!(name.as_deref(), Some("drifting"));
+    drop(conn);
+
+    let report = consistency::check_lobbies(&app.state.redis, &app.state.postgres, false)
+        .await
+        .expect("check after repair");
+    assert!(
+        report
+            .mismatches
+            .iter()
+            .all(|m| m.lobby_id != Some(lobby_id))
+    );
+
+    app.stop().await;
+}
@@ -0,0 +1,198 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use stacks_wars_be::{
+    contract_calls::{
+        BroadcastError, ContractCallError, MAX_SUBMIT_ATTEMPTS, NonceManager, StacksNode,
+    },
+    db::tx::TxRepository,
+    errors::AppError,
+};
+
+const SERVER_ADDRESS: &str = "SP3NE50GEXFG9SZGTT51P40X2CKYSZ5CC4ZTZ7A2G";
+
+#[derive(Default)]
+struct NodeState {
+    /// Next nonce the node expects
+    next_nonce: u64,
+    /// Broadcasts still to be turned down as conflicting, each one because
+    /// another sender took that nonce first
+    conflicts: usize,
+    /// Nonce of every broadcast, in order
+    broadcasts: Vec<u64>,
+}
+
+/// Node that takes transactions carrying the next nonce, as the real one does.
+/// A "transaction" is just its nonce's bytes.
+struct MockNode(Mutex<NodeState>);
+
+impl MockNode {
+    fn new(next_nonce: u64, conflicts: usize) -> Arc<Self> {
+        Arc::new(Self(Mutex::new(NodeState {
+            next_nonce,
+            conflicts,
+            ..Default::default()
+        })))
+    }
+
+    /// Another sender's transaction used up the node's next nonce
+    fn external_tx(&self) {
+        self.0.lock().unwrap().next_nonce += 1;
+    }
+
+    fn broadcasts(&self) -> Vec<u64> {
+        self.0.lock().unwrap().broadcasts.clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl StacksNode for MockNode {
+    async fn account_nonce(&self, _address: &str) -> Result<u64, AppError> {
+        Ok(self.0.lock().unwrap().next_nonce)
+    }
+
+    async fn broadcast(&self, raw_tx: &[u8]) -> Result<String, BroadcastError> {
+        // Give concurrent callers a chance to race
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        let nonce = u64::from_be_bytes(raw_tx.try_into().expect("raw tx is a nonce"));
+        let mut state = self.0.lock().unwrap();
+        state.broadcasts.push(nonce);
+
+        if state.conflicts > 0 {
+            state.conflicts -= 1;
+            state.next_nonce = state.next_nonce.max(nonce + 1);
+            return Err(BroadcastError::NonceRejected(
+                "ConflictingNonceInMempool".to_string(),
+            ));
+        }
+        if nonce < state.next_nonce {
+            return Err(BroadcastError::NonceRejected("BadNonce".to_string()));
+        }
+        state.next_nonce = nonce + 1;
+        Ok(format!("0x{:064x}", nonce))
+    }
+}
+
+fn manager(node: Arc<MockNode>, pool: sqlx::PgPool) -> NonceManager {
+    NonceManager::new(node, SERVER_ADDRESS, TxRepository::new(pool))
+        .with_backoff(Duration::from_millis(5))
+}
+
+fn nonce_tx(nonce: u64) -> Result<Vec<u8>, AppError> {
+    Ok(nonce.to_be_bytes().to_vec())
+}
+
+#[tokio::test]
+async fn nonce_conflict_is_resolved_on_retry() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let node = MockNode::new(5, 0);
+    let manager = manager(node.clone(), app.state.postgres.clone());
+
+    let first = manager.submit("distribute-prize", nonce_tx).await.unwrap();
+    assert_eq!(first, format!("0x{:064x}", 5));
+
+    // Nonce 6 goes out from elsewhere, so the cached one is now too low
+    node.external_tx();
+    let second = manager.submit("distribute-prize", nonce_tx).await.unwrap();
+    assert_eq!(
+        second,
+        format!("0x{:064x}", 7),
+        "retried with the node's nonce"
+    );
+    assert_eq!(node.broadcasts(), vec![5, 6, 7]);
+
+    let rows: Vec<(String, Option<i64>, Option<String>, i32)> = sqlx::query_as(
+        "SELECT status, nonce, tx_id, attempts FROM server_transactions ORDER BY created_at",
+    )
+    .fetch_all(&app.state.postgres)
+    .await
+    .unwrap();
+    assert_eq!(
+        rows,
+        vec![
+            ("submitted".to_string(), Some(5), Some(first), 1),
+            ("submitted".to_string(), Some(7), Some(second), 2),
+        ]
+    );
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn calls_that_keep_conflicting_fail_typed_and_are_recorded() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let node = MockNode::new(0, usize::MAX);
+    let manager = manager(node.clone(), app.state.postgres.clone());
+
+    let error = manager
+        .submit("distribute-prize", nonce_tx)
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(
+            error,
+            ContractCallError::NonceConflict { attempts, .. } if attempts == MAX_SUBMIT_ATTEMPTS
+        ),
+        "unexpected error: {:?}",
+        error
+    );
+    assert_eq!(node.broadcasts().len(), MAX_SUBMIT_ATTEMPTS as usize);
+    assert!(matches!(
+        AppError::from(error),
+        AppError::ServiceUnavailable(_)
+    ));
+
+    let (status, nonce, tx_id, attempts, failure): (
+        String,
+        Option<i64>,
+        Option<String>,
+        i32,
+        Option<String>,
+    ) = sqlx::query_as(
+        "SELECT status, nonce, tx_id, attempts, error FROM server_transactions
+        WHERE function = 'distribute-prize'",
+    )
+    .fetch_one(&app.state.postgres)
+    .await
+    .unwrap();
+    assert_eq!(status, "failed");
+    assert_eq!(nonce, Some(MAX_SUBMIT_ATTEMPTS as i64 - 1));
+    assert_eq!(tx_id, None);
+    assert_eq!(attempts, MAX_SUBMIT_ATTEMPTS as i32);
+    assert!(failure.unwrap().contains("ConflictingNonceInMempool"));
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn concurrent_calls_get_consecutive_nonces() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let node = MockNode::new(3, 0);
+    let manager = Arc::new(manager(node.clone(), app.state.postgres.clone()));
+
+    let mut calls = tokio::task::JoinSet::new();
+    for _ in 0..5 {
+        let manager = manager.clone();
+        calls.spawn(async move { manager.submit("claim-reward", nonce_tx).await });
+    }
+    while let Some(result) = calls.join_next().await {
+        result.unwrap().expect("call failed");
+    }
+
+    assert_eq!(
+        node.broadcasts(),
+        vec![3, 4, 5, 6, 7],
+        "no nonce is used twice"
+    );
+    let submitted: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM server_transactions WHERE status = 'submitted'")
+            .fetch_one(&app.state.postgres)
+            .await
+            .unwrap();
+    assert_eq!(submitted, 5);
+
+    app.stop().await;
+}
@@ -0,0 +1,61 @@
+use crate::common::TEST_ALLOWED_ORIGIN;
+
+#[tokio::test]
+async fn configured_origin_is_allowed() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+
+    let resp = client
+        .get(format!("{}/api/season", app.base_url))
+        .header("Origin", TEST_ALLOWED_ORIGIN)
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(
+        resp.headers()
+            .get("access-control-allow-origin")
+            .and_then(|v| v.to_str().ok()),
+        Some(TEST_ALLOWED_ORIGIN)
+    );
+    assert_eq!(
+        resp.headers()
+            .get("access-control-allow-credentials")
+            .and_then(|v| v.to_str().ok()),
+        Some("true")
+    );
+
+    // Preflight for a configured method
+    let resp = client
+        .request(
+            reqwest::Method::OPTIONS,
+            format!("{}/api/season", app.base_url),
+        )
+        .header("Origin", TEST_ALLOWED_ORIGIN)
+        .header("Access-Control-Request-Method", "PATCH")
+        .send()
+        .await
+        .expect("request failed");
+    let methods = resp.headers()["access-control-allow-methods"]
+        .to_str()
+        .unwrap()
+        .to_string();
+    assert!(methods.contains("PATCH"));
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn unlisted_origin_is_rejected() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+
+    let resp = client
+        .get(format!("{}/api/season", app.base_url))
+        .header("Origin", "https://evil.example.com")
+        .send()
+        .await
+        .expect("request failed");
+    assert!(resp.headers().get("access-control-allow-origin").is_none());
+
+    app.stop().await;
+}
@@ -102,3 +102,99 @@ async fn list_games() {
 
     app.stop().await;
 }
+
+#[tokio::test]
+async fn list_games_supports_conditional_get() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+    let factory = app.factory();
+    let (creator_id, _token) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    factory
+        .create_test_game(creator_id, Some("etag-game-1"))
+        .await
+        .expect("create game failed");
+
+    let list = |etag: Option<&str>| {
+        let mut req = client.get(format!("{}/api/games", app.base_url));
+        if let Some(etag) = etag {
+            req = req.header(reqwest::header::IF_NONE_MATCH, etag);
+        }
+        req.send()
+    };
+    let etag_of = |resp: &reqwest::Response| {
+        resp.headers()
+            .get(reqwest::header::ETAG)
+            .expect("ETag header")
+            .to_str()
+            .unwrap()
+            .to_string()
+    };
+
+    let resp = list(None).await.expect("request failed");
+    assert_eq!(resp.status(), reqwest::StatusCode::OK);
+    let etag = etag_of(&resp);
+
+    // Unchanged: 304 with the same tag and no body
+    let resp = list(Some(&etag)).await.expect("request failed");
+    assert_eq!(resp.status(), reqwest::StatusCode::NOT_MODIFIED);
+    assert_eq!(etag_of(&resp), etag);
+    assert!(resp.bytes().await.unwrap().is_empty());
+
+    // A new game changes the list, so the old tag no longer matches
+    factory
+        .create_test_game(creator_id, Some("etag-game-2"))
+        .await
+        .expect("create game failed");
+    let resp = list(Some(&etag)).await.expect("request failed");
+    assert_eq!(resp.status(), reqwest::StatusCode::OK);
+    assert_ne!(etag_of(&resp), etag);
+    let games: Vec<serde_json::Value> = resp.json().await.expect("invalid json");
+    assert!(games.iter().any(|g| g["path"] == "etag-game-2"));
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn version_reports_build_and_registered_games() {
+    let app = crate::common::spawn_app_with_containers().await;
+
+    let resp = reqwest::get(format!("{}/api/version", app.base_url))
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status().as_u16(), 200);
+    let body: serde_json::Value = resp.json().await.expect("invalid json");
+
+    assert_eq!(body["version"], env!("CARGO_PKG_VERSION"));
+    assert!(body.get("gitSha").is_some());
+    assert_eq!(body["wsProtocols"], json!([1, 2]));
+
+    // Every game in the registry is described, and nothing else
+    let mut reported: Vec<String> = body["games"]
+        .as_array()
+        .expect("games array")
+        .iter()
+        .map(|g| g["id"].as_str().unwrap().to_string())
+        .collect();
+    let mut registered: Vec<String> = stacks_wars_be::games::create_game_registry()
+        .keys()
+        .map(|id| id.to_string())
+        .collect();
+    reported.sort();
+    registered.sort();
+    assert_eq!(reported, registered);
+
+    let lexi = &body["games"][0];
+    assert_eq!(lexi["name"], "Lexi Wars");
+    assert_eq!(lexi["configSchema"]["type"], "object");
+    assert_eq!(
+        lexi["configSchema"]["properties"]["maxWordLength"]["default"],
+        app.state.config.lexi_wars_max_word_length
+    );
+    assert!(lexi["durationLimits"]["maxSecs"].as_u64().unwrap() > 0);
+    assert_eq!(body["games"][1]["name"], "Coin Flip");
+
+    app.stop().await;
+}
@@ -0,0 +1,201 @@
+use redis::AsyncCommands;
+use stacks_wars_be::db::hydration::{
+    self, HydrationOptions,
+    progress::{HydrationPhase, load_checkpoint},
+};
+use stacks_wars_be::models::RedisKey;
+
+/// Write legacy `users:data:{id}` hashes and return their ids in key order
+async fn seed_legacy_users(app: &crate::common::TestApp, count: usize) -> Vec<uuid::Uuid> {
+    let mut conn = app.state.redis.get().await.expect("redis conn");
+    let mut ids: Vec<uuid::Uuid> = (0..count).map(|_| uuid::Uuid::new_v4()).collect();
+    for (i, id) in ids.iter().enumerate() {
+        let _: () = conn
+            .hset_multiple(
+                RedisKey::user(*id),
+                &[
+                    ("wallet_address", format!("SP{:0>38}", i)),
+                    ("username", format!("legacy{}", i)),
+                ],
+            )
+            .await
+            .expect("seed user");
+    }
+    ids.sort_by_key(|id| RedisKey::user(*id));
+    ids
+}
+
+async fn hydrated_user_count(app: &crate::common::TestApp, ids: &[uuid::Uuid]) -> i64 {
+    sqlx::query_scalar("SELECT COUNT(*) FROM users WHERE id = ANY($1)")
+        .bind(ids)
+        .fetch_one(&app.state.postgres)
+        .await
+        .expect("count users")
+}
+
+#[tokio::test]
+async fn interrupted_hydration_resumes_from_checkpoint() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let ids = seed_legacy_users(&app, 5).await;
+
+    // Stop after two records, as if the run had been killed
+    let first = hydration::hydrate_all_with_options(
+        &app.state.redis,
+        &app.state.postgres,
+        &HydrationOptions {
+            max_records: Some(2),
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("first run");
+    assert!(!first.complete);
+    assert_eq!(first.hydrated(), 2);
+    assert_eq!(hydrated_user_count(&app, &ids).await, 2);
+    assert_eq!(
+        load_checkpoint(&app.state.redis, HydrationPhase::Users)
+            .await
+            .expect("checkpoint"),
+        Some(RedisKey::user(ids[1]))
+    );
+
+    let second = hydration::hydrate_all_from_redis(&app.state.redis, &app.state.postgres)
+        .await
+        .expect("second run");
+    assert!(second.complete);
+    let users = second.phase(HydrationPhase::Users).expect("users phase");
+    assert_eq!(users.total, 5);
+    assert_eq!(users.resumed_past, 2, "finished keys are not redone");
+    assert_eq!(users.hydrated, 3);
+    assert_eq!(hydrated_user_count(&app, &ids).await, 5);
+
+    // A completed run leaves nothing to resume
+    assert_eq!(
+        load_checkpoint(&app.state.redis, HydrationPhase::Users)
+            .await
+            .expect("checkpoint"),
+        None
+    );
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn malformed_records_are_collected_without_aborting() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let ids = seed_legacy_users(&app, 2).await;
+
+    let no_wallet = uuid::Uuid::new_v4();
+    let nameless_game = uuid::Uuid::new_v4();
+    {
+        let mut conn = app.state.redis.get().await.expect("redis conn");
+        let _: () = conn
+            .hset("users:data:not-a-uuid", "wallet_address", "SP0")
+            .await
+            .expect("seed bad key");
+        let _: () = conn
+            .hset(RedisKey::user(no_wallet), "username", "walletless")
+            .await
+            .expect("seed user without wallet");
+        let _: () = conn
+            .hset(RedisKey::game(nameless_game), "min_players", "2")
+            .await
+            .expect("seed game without name");
+    }
+
+    let summary = hydration::hydrate_all_from_redis(&app.state.redis, &app.state.postgres)
+        .await
+        .expect("hydration run");
+
+    assert!(summary.complete);
+    assert_eq!(hydrated_user_count(&app, &ids).await, 2);
+
+    let mut failed: Vec<String> = summary.failures().map(|f| f.key.clone()).collect();
+    failed.sort();
+    let mut expected = vec![
+        "users:data:not-a-uuid".to_string(),
+        RedisKey::user(no_wallet),
+        RedisKey::game(nameless_game),
+    ];
+    expected.sort();
+    assert_eq!(failed, expected);
+    assert!(
+        summary
+            .failures()
+            .find(|f| f.key == RedisKey::user(no_wallet))
+            .is_some_and(|f| f.reason.contains("wallet_address"))
+    );
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn dry_run_plans_without_writing() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let ids = seed_legacy_users(&app, 3).await;
+
+    let summary = hydration::hydrate_all_with_options(
+        &app.state.redis,
+        &app.state.postgres,
+        &HydrationOptions {
+            dry_run: true,
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("dry run");
+
+    assert!(summary.dry_run);
+    assert!(summary.complete);
+    let users = summary.phase(HydrationPhase::Users).expect("users phase");
+    assert_eq!(users.hydrated, 3, "every user is planned for insert");
+    assert_eq!(hydrated_user_count(&app, &ids).await, 0, "nothing written");
+    assert_eq!(
+        load_checkpoint(&app.state.redis, HydrationPhase::Users)
+            .await
+            .expect("checkpoint"),
+        None,
+        "a dry run leaves no progress behind"
+    );
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn validation_surfaces_malformed_lobby() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let lobby_id = uuid::Uuid::new_v4();
+    let lobby_key = RedisKey::lobby(lobby_id);
+    {
+        let mut conn = app.state.redis.get().await.expect("redis conn");
+        let _: () = conn
+            .hset_multiple(
+                &lobby_key,
+                &[("name", "Broken"), ("creator_id", "not-a-uuid")],
+            )
+            .await
+            .expect("seed malformed lobby");
+    }
+
+    let invalid = hydration::validate_from_redis(&app.state.redis)
+        .await
+        .expect("validation");
+    assert_eq!(invalid.len(), 1);
+    assert_eq!(invalid[0].key, lobby_key);
+    assert!(invalid[0].reason.contains("creator_id"));
+
+    // A dry run reports the same issue up front
+    let summary = hydration::hydrate_all_with_options(
+        &app.state.redis,
+        &app.state.postgres,
+        &HydrationOptions {
+            dry_run: true,
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("dry run");
+    assert_eq!(summary.invalid, invalid);
+
+    app.stop().await;
+}
@@ -202,6 +202,343 @@ async fn list_my_lobbies() {
     app.stop().await;
 }
 
+#[tokio::test]
+async fn create_lobby_rejects_inactive_or_unknown_game() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+    let factory = app.factory();
+
+    let (user_id, token) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let game_id = factory
+        .create_test_game(user_id, Some("retired-game"))
+        .await
+        .expect("create game failed");
+    sqlx::query("UPDATE games SET is_active = FALSE WHERE id = $1")
+        .bind(game_id)
+        .execute(&app.pg_pool)
+        .await
+        .expect("deactivate game");
+
+    let payload = |game_id: uuid::Uuid| {
+        json!({
+            "name": "doomed lobby",
+            "entryAmount": 0.0,
+            "tokenSymbol": "STX",
+            "isSponsored": false,
+            "gameId": game_id,
+            "gamePath": "retired-game"
+        })
+    };
+
+    for game_id in [game_id, uuid::Uuid::new_v4()] {
+        let resp = client
+            .post(format!("{}/api/lobby", app.base_url))
+            .header("Cookie", factory.create_auth_cookie(&token))
+            .json(&payload(game_id))
+            .send()
+            .await
+            .expect("request failed");
+        assert_eq!(resp.status().as_u16(), 400);
+    }
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM lobbies WHERE creator_id = $1")
+        .bind(user_id)
+        .fetch_one(&app.pg_pool)
+        .await
+        .expect("count lobbies");
+    assert_eq!(count, 0);
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn create_lobby_enforces_token_allowlist_and_minimum() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+    let factory = app.factory();
+
+    let (user_id, token) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let game_id = factory
+        .create_test_game(user_id, Some("token-game"))
+        .await
+        .expect("create game failed");
+
+    let welsh = "SP3NE50GEXFG9SZGTT51P40X2CKYSZ5CC4ZTZ7A2G.welshcorgicoin-token";
+    let payload = |symbol: &str, contract: Option<&str>, entry: f64| {
+        json!({
+            "name": "token lobby",
+            "entryAmount": entry,
+            "tokenSymbol": symbol,
+            "tokenContractId": contract,
+            "isSponsored": false,
+            "gameId": game_id,
+            "gamePath": "token-game"
+        })
+    };
+
+    let cases = [
+        (payload("DOGE", None, 10.0), 400, "DOGE is not accepted"),
+        (
+            payload("WELSH", Some(welsh), 50.0),
+            400,
+            "below the minimum of 100 WELSH",
+        ),
+        (payload("WELSH", Some(welsh), 150.0), 201, ""),
+    ];
+
+    for (body, status, message) in cases {
+        let resp = client
+            .post(format!("{}/api/lobby", app.base_url))
+            .header("Cookie", factory.create_auth_cookie(&token))
+            .json(&body)
+            .send()
+            .await
+            .expect("request failed");
+        assert_eq!(resp.status().as_u16(), status, "payload {}", body);
+        let text = resp.text().await.expect("body");
+        assert!(text.contains(message), "unexpected response: {}", text);
+    }
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn create_lobby_sanitizes_and_checks_names() {
+    use stacks_wars_be::{db::lobby::LobbyRepository, errors::AppError, state::ChatModeration};
+
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+    let factory = app.factory();
+
+    let (user_id, token) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let game_id = factory
+        .create_test_game(user_id, Some("name-game"))
+        .await
+        .expect("create game failed");
+
+    let create = |name: String| {
+        client
+            .post(format!("{}/api/lobby", app.base_url))
+            .header("Cookie", factory.create_auth_cookie(&token))
+            .json(&json!({
+                "name": name,
+                "gameId": game_id,
+                "gamePath": "name-game"
+            }))
+            .send()
+    };
+
+    let resp = create("  Late\u{0007}  night \t\n lobby ".to_string())
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status().as_u16(), 201);
+    let body: serde_json::Value = resp.json().await.expect("invalid json");
+    assert_eq!(body["name"], "Late night lobby");
+
+    let resp = create("x".repeat(51)).await.expect("request failed");
+    assert_eq!(resp.status().as_u16(), 400);
+    let text = resp.text().await.expect("body");
+    assert!(
+        text.contains("51 characters long; the limit is 50"),
+        "unexpected response: {}",
+        text
+    );
+
+    // Duplicates are allowed until the policy asks for unique names
+    let resp = create("late NIGHT lobby".to_string())
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status().as_u16(), 201);
+
+    let mut state = app.state.clone();
+    state.config.lobby_names.unique_per_creator = true;
+    let create_direct = |name: &'static str| {
+        let state = state.clone();
+        async move {
+            LobbyRepository::new(state.postgres.clone())
+                .create_lobby(
+                    name,
+                    None,
+                    user_id,
+                    game_id,
+                    "name-game",
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    false,
+                    false,
+                    true,
+                    false,
+                    None,
+                    None,
+                    None,
+                    state.redis.clone(),
+                    state.clone(),
+                )
+                .await
+        }
+    };
+    let err = create_direct(" Late Night  Lobby").await.unwrap_err();
+    assert!(
+        matches!(&err, AppError::Conflict(msg) if msg.contains("'Late Night Lobby'")),
+        "unexpected error: {}",
+        err
+    );
+    let early = create_direct("Early lobby").await.expect("unique name");
+
+    // Renames are cleaned up and checked the same way
+    state.config.chat_moderation = ChatModeration::parse("heck");
+    let repo = LobbyRepository::new(state.postgres.clone());
+    let renamed = repo
+        .update_name(early.id(), "  Early \t bird ", state.clone())
+        .await
+        .expect("rename");
+    assert_eq!(renamed.name, "Early bird");
+    let err = repo
+        .update_name(early.id(), "What the heck", state.clone())
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(&err, AppError::BadRequest(msg) if msg.contains("blocked word 'heck'")),
+        "unexpected error: {}",
+        err
+    );
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn create_paid_lobby_needs_minimum_trust_rating() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+    let factory = app.factory();
+
+    let (user_id, token) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let game_id = factory
+        .create_test_game(user_id, Some("trust-game"))
+        .await
+        .expect("create game failed");
+    sqlx::query("UPDATE users SET trust_rating = 1 WHERE id = $1")
+        .bind(user_id)
+        .execute(&app.pg_pool)
+        .await
+        .expect("lower trust rating");
+
+    let min_trust = app.state.config.paid_lobby_trust.create;
+    let cases = [
+        (json!({ "entryAmount": 10 }), 403),
+        (json!({}), 201),
+        (json!({ "currentAmount": 10, "isSponsored": true }), 201),
+    ];
+
+    for (amounts, status) in cases {
+        let mut body = json!({
+            "name": "trust lobby",
+            "tokenSymbol": "STX",
+            "gameId": game_id,
+            "gamePath": "trust-game"
+        });
+        body.as_object_mut()
+            .unwrap()
+            .extend(amounts.as_object().unwrap().clone());
+        let resp = client
+            .post(format!("{}/api/lobby", app.base_url))
+            .header("Cookie", factory.create_auth_cookie(&token))
+            .json(&body)
+            .send()
+            .await
+            .expect("request failed");
+        assert_eq!(resp.status().as_u16(), status, "payload {}", body);
+        if status == 403 {
+            let text = resp.text().await.expect("body");
+            assert!(
+                text.contains(&format!("at least {}", min_trust)),
+                "unexpected response: {}",
+                text
+            );
+        }
+    }
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn create_lobby_keeps_amounts_exact() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+    let factory = app.factory();
+
+    let (user_id, token) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let game_id = factory
+        .create_test_game(user_id, Some("decimal-game"))
+        .await
+        .expect("create game failed");
+
+    // Amounts may arrive as strings or numbers and are stored to the micro-unit
+    let resp = client
+        .post(format!("{}/api/lobby", app.base_url))
+        .header("Cookie", factory.create_auth_cookie(&token))
+        .json(&json!({
+            "name": "decimal lobby",
+            "entryAmount": "0.1",
+            "currentAmount": 0.1,
+            "tokenSymbol": "STX",
+            "gameId": game_id,
+            "gamePath": "decimal-game"
+        }))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status().as_u16(), 201);
+    let lobby: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(lobby["entryAmount"], json!(0.1));
+    assert_eq!(lobby["currentAmount"], json!(0.1));
+
+    let lobby_id: uuid::Uuid = lobby["id"].as_str().unwrap().parse().unwrap();
+    let stored: String =
+        sqlx::query_scalar("SELECT current_amount::text FROM lobbies WHERE id = $1")
+            .bind(lobby_id)
+            .fetch_one(&app.pg_pool)
+            .await
+            .expect("query failed");
+    assert_eq!(stored, "0.100000");
+
+    // Anything finer than a micro-unit is rejected rather than rounded
+    let resp = client
+        .post(format!("{}/api/lobby", app.base_url))
+        .header("Cookie", factory.create_auth_cookie(&token))
+        .json(&json!({
+            "name": "dust lobby",
+            "entryAmount": "0.0000001",
+            "tokenSymbol": "STX",
+            "gameId": game_id,
+            "gamePath": "decimal-game"
+        }))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status().as_u16(), 400);
+
+    app.stop().await;
+}
+
 #[tokio::test]
 async fn delete_lobby() {
     let app = crate::common::spawn_app_with_containers().await;
@@ -265,3 +602,1450 @@ async fn delete_lobby() {
 
     app.stop().await;
 }
+
+#[tokio::test]
+async fn single_use_invite_admits_once() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+    let factory = app.factory();
+
+    let (creator_id, token) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let game_id = factory
+        .create_test_game(creator_id, Some("invite-game"))
+        .await
+        .expect("create game failed");
+    let (lobby_id, _path) = factory
+        .create_test_lobby(creator_id, game_id, Some("invite lobby"))
+        .await
+        .expect("create lobby failed");
+
+    let resp = client
+        .post(format!("{}/api/lobby/{}/invite", app.base_url, lobby_id))
+        .header("Cookie", factory.create_auth_cookie(&token))
+        .json(&json!({ "maxUses": 1 }))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status().as_u16(), 201);
+    let body: serde_json::Value = resp.json().await.expect("invalid json");
+    let invite_token = body
+        .get("token")
+        .and_then(|v| v.as_str())
+        .expect("missing token");
+
+    let claims = stacks_wars_be::auth::invite::decode_invite_token(
+        invite_token,
+        &app.state.config.invite_secret,
+    )
+    .expect("token should verify");
+    // Invites aren't signed with the auth secret
+    assert!(
+        stacks_wars_be::auth::invite::decode_invite_token(
+            invite_token,
+            &app.state.config.jwt_secret
+        )
+        .is_err()
+    );
+    assert_eq!(claims.lobby_id, lobby_id);
+
+    let repo =
+        stacks_wars_be::db::lobby_invite::LobbyInviteRepository::new(app.state.redis.clone());
+    let invite = repo
+        .redeem(lobby_id, claims.invite_id)
+        .await
+        .expect("first redemption should succeed");
+    assert_eq!(invite.uses, 1);
+
+    // Exhausted: second redemption of a single-use invite is rejected
+    let err = repo
+        .redeem(lobby_id, claims.invite_id)
+        .await
+        .expect_err("second redemption should fail");
+    assert!(matches!(
+        err,
+        stacks_wars_be::errors::AppError::InviteError(
+            stacks_wars_be::models::InviteError::Exhausted { max_uses: 1 }
+        )
+    ));
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn revoked_invite_is_rejected() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+    let factory = app.factory();
+
+    let (creator_id, token) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let (_other_id, other_token) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let game_id = factory
+        .create_test_game(creator_id, Some("revoke-invite-game"))
+        .await
+        .expect("create game failed");
+    let (lobby_id, _path) = factory
+        .create_test_lobby(creator_id, game_id, Some("revoke lobby"))
+        .await
+        .expect("create lobby failed");
+
+    let resp = client
+        .post(format!("{}/api/lobby/{}/invite", app.base_url, lobby_id))
+        .header("Cookie", factory.create_auth_cookie(&token))
+        .json(&json!({}))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status().as_u16(), 201);
+    let body: serde_json::Value = resp.json().await.expect("invalid json");
+    let invite_id = body
+        .get("inviteId")
+        .and_then(|v| v.as_str())
+        .expect("missing inviteId")
+        .to_string();
+
+    // Only the creator may revoke
+    let resp = client
+        .delete(format!(
+            "{}/api/lobby/{}/invite/{}",
+            app.base_url, lobby_id, invite_id
+        ))
+        .header("Cookie", factory.create_auth_cookie(&other_token))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status().as_u16(), 403);
+
+    let resp = client
+        .delete(format!(
+            "{}/api/lobby/{}/invite/{}",
+            app.base_url, lobby_id, invite_id
+        ))
+        .header("Cookie", factory.create_auth_cookie(&token))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status().as_u16(), 204);
+
+    let repo =
+        stacks_wars_be::db::lobby_invite::LobbyInviteRepository::new(app.state.redis.clone());
+    let err = repo
+        .redeem(lobby_id, invite_id.parse().unwrap())
+        .await
+        .expect_err("revoked invite should be rejected");
+    assert!(matches!(
+        err,
+        stacks_wars_be::errors::AppError::InviteError(stacks_wars_be::models::InviteError::Revoked)
+    ));
+
+    app.stop().await;
+}
+
+/// Push a lobby's (and its players') activity timestamps an hour into the past
+async fn backdate_lobby_activity(app: &crate::common::TestApp, lobby_id: uuid::Uuid) {
+    let mut conn = app.state.redis.get().await.expect("redis conn");
+    let stale = chrono::Utc::now().timestamp() - 3600;
+
+    let _: () = conn
+        .hset(
+            stacks_wars_be::models::RedisKey::lobby_state(lobby_id),
+            "updated_at",
+            stale,
+        )
+        .await
+        .expect("backdate lobby state");
+
+    let player_keys: Vec<String> = conn
+        .keys(stacks_wars_be::models::RedisKey::lobby_player(
+            lobby_id,
+            stacks_wars_be::models::KeyPart::Wildcard,
+        ))
+        .await
+        .expect("player keys");
+    for key in player_keys {
+        let _: () = conn
+            .hset_multiple(
+                &key,
+                &[
+                    ("updated_at", stale.to_string()),
+                    ("last_ping", (stale * 1000).to_string()),
+                ],
+            )
+            .await
+            .expect("backdate player state");
+    }
+}
+
+#[tokio::test]
+async fn idle_empty_lobby_is_reaped() {
+    let app = crate::common::spawn_app_with_containers().await;
+
+    let factory = app.factory();
+    let (creator_id, _) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let game_id = factory
+        .create_test_game(creator_id, Some("Reaper Game"))
+        .await
+        .expect("create game failed");
+    let (lobby_id, _) = factory
+        .create_test_lobby(creator_id, game_id, Some("Idle Lobby"))
+        .await
+        .expect("create lobby failed");
+
+    backdate_lobby_activity(&app, lobby_id).await;
+
+    let reaped = stacks_wars_be::reaper::reap_idle_lobbies(&app.state, 60)
+        .await
+        .expect("reap failed");
+    assert_eq!(reaped, vec![lobby_id]);
+
+    let lobby = stacks_wars_be::db::lobby::LobbyRepository::new(app.state.postgres.clone())
+        .find_by_id(lobby_id)
+        .await
+        .expect("lobby row should remain");
+    assert_eq!(lobby.status, stacks_wars_be::models::LobbyStatus::Cancelled);
+
+    let mut conn = app.state.redis.get().await.expect("redis conn");
+    let state_exists: bool = conn
+        .exists(stacks_wars_be::models::RedisKey::lobby_state(lobby_id))
+        .await
+        .expect("exists");
+    let player_exists: bool = conn
+        .exists(stacks_wars_be::models::RedisKey::lobby_player(
+            lobby_id, creator_id,
+        ))
+        .await
+        .expect("exists");
+    assert!(!state_exists);
+    assert!(!player_exists);
+    drop(conn);
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn idle_connected_lobby_is_spared() {
+    let app = crate::common::spawn_app_with_containers().await;
+
+    let factory = app.factory();
+    let (creator_id, _) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let game_id = factory
+        .create_test_game(creator_id, Some("Reaper Game"))
+        .await
+        .expect("create game failed");
+    let (lobby_id, _) = factory
+        .create_test_lobby(creator_id, game_id, Some("Watched Lobby"))
+        .await
+        .expect("create lobby failed");
+
+    backdate_lobby_activity(&app, lobby_id).await;
+
+    // An open room socket, even one that hasn't sent anything in a while
+    app.state
+        .indices
+        .lock()
+        .await
+        .by_lobby
+        .entry(lobby_id)
+        .or_default()
+        .insert(uuid::Uuid::new_v4());
+
+    let reaped = stacks_wars_be::reaper::reap_idle_lobbies(&app.state, 60)
+        .await
+        .expect("reap failed");
+    assert!(reaped.is_empty());
+
+    let lobby = stacks_wars_be::db::lobby::LobbyRepository::new(app.state.postgres.clone())
+        .find_by_id(lobby_id)
+        .await
+        .expect("lobby row");
+    assert_eq!(lobby.status, stacks_wars_be::models::LobbyStatus::Waiting);
+
+    let mut conn = app.state.redis.get().await.expect("redis conn");
+    let state_exists: bool = conn
+        .exists(stacks_wars_be::models::RedisKey::lobby_state(lobby_id))
+        .await
+        .expect("exists");
+    assert!(state_exists);
+    drop(conn);
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn idle_lobby_connected_elsewhere_is_spared() {
+    let app = crate::common::spawn_app_with_containers().await;
+
+    let factory = app.factory();
+    let (creator_id, _) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let game_id = factory
+        .create_test_game(creator_id, Some("Reaper Game"))
+        .await
+        .expect("create game failed");
+    let (lobby_id, _) = factory
+        .create_test_lobby(creator_id, game_id, Some("Remote Lobby"))
+        .await
+        .expect("create lobby failed");
+
+    backdate_lobby_activity(&app, lobby_id).await;
+
+    // A room socket held by another instance
+    let presence =
+        stacks_wars_be::db::lobby_presence::LobbyPresenceRepository::new(app.state.redis.clone());
+    let remote_conn = uuid::Uuid::new_v4();
+    presence
+        .touch(lobby_id, &[remote_conn])
+        .await
+        .expect("touch presence");
+
+    let reaped = stacks_wars_be::reaper::reap_idle_lobbies(&app.state, 60)
+        .await
+        .expect("reap failed");
+    assert!(reaped.is_empty());
+
+    // Presence its instance stopped refreshing no longer counts
+    let mut conn = app.state.redis.get().await.expect("redis conn");
+    let _: () = conn
+        .zadd(
+            stacks_wars_be::models::RedisKey::lobby_connections(lobby_id),
+            remote_conn.to_string(),
+            chrono::Utc::now().timestamp() - 3600,
+        )
+        .await
+        .expect("backdate presence");
+    drop(conn);
+
+    let reaped = stacks_wars_be::reaper::reap_idle_lobbies(&app.state, 60)
+        .await
+        .expect("reap failed");
+    assert_eq!(reaped, vec![lobby_id]);
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn abandoned_lobby_passes_to_earliest_joined_player() {
+    let app = crate::common::spawn_app_with_containers().await;
+
+    let factory = app.factory();
+    let (creator_id, _) = factory.create_test_user(None).await.expect("create user");
+    let (bob, _) = factory.create_test_user(None).await.expect("create user");
+    let (carol, _) = factory.create_test_user(None).await.expect("create user");
+    let game_id = factory
+        .create_test_game(creator_id, Some("Handoff Game"))
+        .await
+        .expect("create game failed");
+    let (lobby_id, _) = factory
+        .create_test_lobby(creator_id, game_id, Some("Handoff Lobby"))
+        .await
+        .expect("create lobby failed");
+    for player in [bob, carol] {
+        factory
+            .add_test_player(lobby_id, player, false)
+            .await
+            .expect("add player");
+    }
+
+    backdate_lobby_activity(&app, lobby_id).await;
+    // Carol joined before Bob
+    let mut conn = app.state.redis.get().await.expect("redis conn");
+    let joined_at = chrono::Utc::now().timestamp() - 7200;
+    let _: () = conn
+        .hset(
+            stacks_wars_be::models::RedisKey::lobby_player(lobby_id, carol),
+            "joined_at",
+            joined_at,
+        )
+        .await
+        .expect("backdate join");
+    drop(conn);
+
+    let handoffs = stacks_wars_be::reaper::hand_off_abandoned_lobbies(&app.state, 60)
+        .await
+        .expect("handoff failed");
+    assert_eq!(
+        handoffs,
+        vec![(
+            lobby_id,
+            stacks_wars_be::reaper::CreatorHandoff::Transferred {
+                from: creator_id,
+                to: carol
+            }
+        )]
+    );
+
+    let lobby = stacks_wars_be::db::lobby::LobbyRepository::new(app.state.postgres.clone())
+        .find_by_id(lobby_id)
+        .await
+        .expect("lobby row");
+    assert_eq!(lobby.creator_id, carol);
+    assert_eq!(lobby.status, stacks_wars_be::models::LobbyStatus::Waiting);
+
+    let player_repo =
+        stacks_wars_be::db::player_state::PlayerStateRepository::new(app.state.redis.clone());
+    assert!(player_repo.is_creator(lobby_id, carol).await.unwrap());
+    assert!(!player_repo.is_creator(lobby_id, creator_id).await.unwrap());
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn abandoned_lobby_with_nobody_left_is_cancelled() {
+    let app = crate::common::spawn_app_with_containers().await;
+
+    let factory = app.factory();
+    let (creator_id, _) = factory.create_test_user(None).await.expect("create user");
+    let game_id = factory
+        .create_test_game(creator_id, Some("Handoff Game"))
+        .await
+        .expect("create game failed");
+    let (lobby_id, _) = factory
+        .create_test_lobby(creator_id, game_id, Some("Empty Lobby"))
+        .await
+        .expect("create lobby failed");
+
+    backdate_lobby_activity(&app, lobby_id).await;
+    // Spectators watching don't keep the lobby alive without a creator
+    app.state
+        .indices
+        .lock()
+        .await
+        .by_lobby
+        .entry(lobby_id)
+        .or_default()
+        .insert(uuid::Uuid::new_v4());
+
+    let handoffs = stacks_wars_be::reaper::hand_off_abandoned_lobbies(&app.state, 60)
+        .await
+        .expect("handoff failed");
+    assert_eq!(
+        handoffs,
+        vec![(lobby_id, stacks_wars_be::reaper::CreatorHandoff::Cancelled)]
+    );
+
+    let lobby = stacks_wars_be::db::lobby::LobbyRepository::new(app.state.postgres.clone())
+        .find_by_id(lobby_id)
+        .await
+        .expect("lobby row should remain");
+    assert_eq!(lobby.status, stacks_wars_be::models::LobbyStatus::Cancelled);
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn lobby_words_are_listed_in_turn_order() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+    let factory = app.factory();
+
+    let (alice, _) = factory.create_test_user(None).await.expect("create user");
+    let (bob, _) = factory.create_test_user(None).await.expect("create user");
+    let game_id = factory
+        .create_test_game(alice, Some("words-game"))
+        .await
+        .expect("create game");
+    let (lobby_id, _) = factory
+        .create_test_lobby(alice, game_id, Some("words lobby"))
+        .await
+        .expect("create lobby");
+
+    // Written out of order, as background inserts may land
+    let repo = stacks_wars_be::db::game_word::GameWordRepository::new(app.state.postgres.clone());
+    for (user, word, turn) in [
+        (bob, "planets", 2),
+        (alice, "cats", 1),
+        (alice, "zebra", 3),
+        (bob, "ocean", 4),
+    ] {
+        repo.record_word(lobby_id, user, word, turn)
+            .await
+            .expect("record word");
+    }
+
+    let resp = client
+        .get(format!("{}/api/lobby/{}/words", app.base_url, lobby_id))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status().as_u16(), 200);
+    let body: serde_json::Value = resp.json().await.expect("invalid json");
+
+    let words: Vec<&str> = body["words"]
+        .as_array()
+        .expect("words array")
+        .iter()
+        .map(|w| w["word"].as_str().unwrap())
+        .collect();
+    assert_eq!(words, vec!["cats", "planets", "zebra", "ocean"]);
+
+    let stats = &body["stats"];
+    assert_eq!(stats["totalWords"], 4);
+    assert_eq!(stats["longestWord"]["word"], "planets");
+    assert_eq!(stats["longestWord"]["userId"], bob.to_string());
+    // Two each; alice played first
+    assert_eq!(stats["mostWords"]["userId"], alice.to_string());
+
+    let missing = client
+        .get(format!(
+            "{}/api/lobby/{}/words",
+            app.base_url,
+            uuid::Uuid::new_v4()
+        ))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(missing.status().as_u16(), 404);
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn lobby_replay_seeks_to_the_state_at_an_instant() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+    let factory = app.factory();
+
+    let (alice, _) = factory.create_test_user(None).await.expect("create user");
+    let (bob, _) = factory.create_test_user(None).await.expect("create user");
+    let game_id = factory
+        .create_test_game(alice, Some("replay-game"))
+        .await
+        .expect("create game");
+    let (lobby_id, _) = factory
+        .create_test_lobby(alice, game_id, Some("replay lobby"))
+        .await
+        .expect("create lobby");
+
+    // The start is read from Postgres, so it outlives the lobby's Redis state
+    let started_at = chrono::Utc::now().timestamp() - 60;
+    sqlx::query("UPDATE lobbies SET started_at = $1 WHERE id = $2")
+        .bind(
+            chrono::DateTime::from_timestamp(started_at, 0)
+                .unwrap()
+                .naive_utc(),
+        )
+        .bind(lobby_id)
+        .execute(&app.state.postgres)
+        .await
+        .expect("set started_at");
+    stacks_wars_be::db::lobby_state::LobbyStateRepository::new(app.state.redis.clone())
+        .delete_state(lobby_id)
+        .await
+        .expect("expire lobby state");
+    let lobby_repo = stacks_wars_be::db::lobby::LobbyRepository::new(app.state.postgres.clone());
+
+    // Words 3s, 8s and 15s into the game
+    let repo = stacks_wars_be::db::game_word::GameWordRepository::new(app.state.postgres.clone());
+    for (user, word, turn, secs) in [
+        (alice, "cats", 1, 3),
+        (bob, "zebra", 2, 8),
+        (alice, "planet", 3, 15),
+    ] {
+        let played = repo
+            .record_word(lobby_id, user, word, turn)
+            .await
+            .expect("record word");
+        let played_at = chrono::DateTime::from_timestamp(started_at + secs, 0)
+            .unwrap()
+            .naive_utc();
+        sqlx::query("UPDATE game_words SET played_at = $1 WHERE id = $2")
+            .bind(played_at)
+            .bind(played.id)
+            .execute(&app.state.postgres)
+            .await
+            .expect("set played_at");
+    }
+
+    let get = |path: String| {
+        let client = client.clone();
+        async move {
+            let resp = client.get(path).send().await.expect("request failed");
+            assert_eq!(resp.status().as_u16(), 200);
+            resp.json::<serde_json::Value>()
+                .await
+                .expect("invalid json")
+        }
+    };
+    let replay = format!("{}/api/lobbies/{}/replay", app.base_url, lobby_id);
+
+    let timeline = get(replay.clone()).await;
+    assert_eq!(timeline["durationMs"], 15_000);
+    assert!(timeline.get("gameSeed").is_none());
+    let frame_times: Vec<i64> = timeline["frames"]
+        .as_array()
+        .expect("frames")
+        .iter()
+        .map(|f| f["atMs"].as_i64().unwrap())
+        .collect();
+    assert_eq!(frame_times, vec![3_000, 8_000, 15_000]);
+
+    // Mid-game: two words in, bob's the latest
+    let mid = get(format!("{}/frame?at_ms=10000", replay)).await;
+    assert_eq!(mid["wordsPlayed"], 2);
+    assert_eq!(mid["usedWords"], serde_json::json!(["cats", "zebra"]));
+    assert_eq!(mid["lastWord"]["userId"], bob.to_string());
+    assert_eq!(mid["scores"][0]["userId"], alice.to_string());
+    assert_eq!(mid["scores"][0]["words"], 1);
+
+    // Before the first word, the game is as it started
+    let start = get(format!("{}/frame?at_ms=-1000", replay)).await;
+    assert_eq!(start["wordsPlayed"], 0);
+    assert!(start["lastWord"].is_null());
+
+    // The gameplay seed is published with the replay once the game has ended
+    lobby_repo
+        .record_game_seed(lobby_id, u64::MAX)
+        .await
+        .expect("record seed");
+    let timeline = get(replay.clone()).await;
+    assert_eq!(timeline["gameSeed"], u64::MAX.to_string());
+
+    let missing = client
+        .get(format!(
+            "{}/api/lobbies/{}/replay/frame?at_ms=0",
+            app.base_url,
+            uuid::Uuid::new_v4()
+        ))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(missing.status().as_u16(), 404);
+
+    app.stop().await;
+}
+
+/// Seed `count` joined players with distinct join times into a fresh lobby
+async fn seed_roster(
+    app: &crate::common::TestApp,
+    count: usize,
+) -> (uuid::Uuid, Vec<stacks_wars_be::models::PlayerState>) {
+    let lobby_id = uuid::Uuid::new_v4();
+    let repo =
+        stacks_wars_be::db::player_state::PlayerStateRepository::new(app.state.redis.clone());
+
+    let mut seeded = Vec::new();
+    for i in 0..count {
+        let mut player = stacks_wars_be::models::PlayerState::new(
+            uuid::Uuid::new_v4(),
+            lobby_id,
+            format!("SP{:0>38}", i),
+            Some(format!("player{}", i)),
+            None,
+            10.0 + i as f64,
+            None,
+            i == 0,
+        );
+        player.joined_at = 1_700_000_000 + i as i64;
+        repo.upsert_state(player.clone(), None)
+            .await
+            .expect("seed player");
+        seeded.push(player);
+    }
+    (lobby_id, seeded)
+}
+
+#[tokio::test]
+async fn list_players_assembles_full_roster() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let repo =
+        stacks_wars_be::db::player_state::PlayerStateRepository::new(app.state.redis.clone());
+
+    let (lobby_id, seeded) = seed_roster(&app, 6).await;
+    // Another lobby's players must not leak in
+    seed_roster(&app, 2).await;
+
+    let roster = repo.list_players(lobby_id).await.expect("list players");
+    assert_eq!(roster.len(), seeded.len());
+    for (got, want) in roster.iter().zip(&seeded) {
+        assert_eq!(got.user_id, want.user_id);
+        assert_eq!(got.wallet_address, want.wallet_address);
+        assert_eq!(got.username, want.username);
+        assert_eq!(got.trust_rating, want.trust_rating);
+        assert_eq!(got.is_creator, want.is_creator);
+    }
+    assert_eq!(repo.count_players(lobby_id).await.expect("count"), 6);
+    assert!(
+        repo.list_players(uuid::Uuid::new_v4())
+            .await
+            .expect("empty lobby")
+            .is_empty()
+    );
+
+    app.stop().await;
+}
+
+/// Compares one HGETALL round trip per player with the pipelined roster read.
+/// Run with `cargo test --test http_routes roster_read_round_trips -- --ignored --nocapture`
+#[tokio::test]
+#[ignore]
+async fn roster_read_round_trips() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let repo =
+        stacks_wars_be::db::player_state::PlayerStateRepository::new(app.state.redis.clone());
+    let (lobby_id, seeded) = seed_roster(&app, 100).await;
+    const RUNS: u32 = 20;
+
+    let started = std::time::Instant::now();
+    for _ in 0..RUNS {
+        let mut conn = app.state.redis.get().await.expect("redis conn");
+        for player in &seeded {
+            let _: std::collections::HashMap<String, String> = conn
+                .hgetall(stacks_wars_be::models::RedisKey::lobby_player(
+                    lobby_id,
+                    player.user_id,
+                ))
+                .await
+                .expect("hgetall");
+        }
+    }
+    let per_key = started.elapsed() / RUNS;
+
+    let started = std::time::Instant::now();
+    for _ in 0..RUNS {
+        let roster = repo.list_players(lobby_id).await.expect("list players");
+        assert_eq!(roster.len(), seeded.len());
+    }
+    let pipelined = started.elapsed() / RUNS;
+
+    println!(
+        "{} players: per-key reads {:?} ({} round trips), pipelined {:?}",
+        seeded.len(),
+        per_key,
+        seeded.len(),
+        pipelined
+    );
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn lobby_full_view_merges_config_and_runtime() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+
+    let factory = app.factory();
+    let (creator_id, _t) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let (player_id, _t) = factory
+        .create_test_user(None)
+        .await
+        .expect("create player failed");
+    let game_id = factory
+        .create_test_game(creator_id, Some("full-view-game"))
+        .await
+        .expect("create game failed");
+    let (lobby_id, _path) = factory
+        .create_test_lobby(creator_id, game_id, Some("full view"))
+        .await
+        .expect("create lobby failed");
+    factory
+        .add_test_player(lobby_id, player_id, false)
+        .await
+        .expect("add player failed");
+
+    let resp = client
+        .get(format!("{}/api/lobbies/{}", app.base_url, lobby_id))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status().as_u16(), 200);
+    let body: serde_json::Value = resp.json().await.expect("invalid json");
+
+    // Config from Postgres, runtime and roster from Redis
+    assert_eq!(body["config"]["name"], "full view");
+    assert_eq!(body["config"]["id"], lobby_id.to_string());
+    assert_eq!(body["runtime"]["lobbyId"], lobby_id.to_string());
+    assert_eq!(body["runtime"]["status"], "waiting");
+    assert_eq!(body["players"].as_array().map(Vec::len), Some(2));
+    assert_eq!(body["spectatorCount"], 0);
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn lobby_full_view_tolerates_missing_runtime() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+
+    let factory = app.factory();
+    let (creator_id, _t) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let game_id = factory
+        .create_test_game(creator_id, Some("full-view-miss-game"))
+        .await
+        .expect("create game failed");
+    let (lobby_id, _path) = factory
+        .create_test_lobby(creator_id, game_id, Some("no runtime"))
+        .await
+        .expect("create lobby failed");
+
+    // Drop the lobby's runtime state, keeping the creator's player hash
+    {
+        let mut conn = app.state.redis.get().await.expect("redis conn");
+        let _: () = conn
+            .del(stacks_wars_be::models::RedisKey::lobby_state(lobby_id))
+            .await
+            .expect("redis del");
+    }
+
+    let resp = client
+        .get(format!("{}/api/lobbies/{}", app.base_url, lobby_id))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status().as_u16(), 200);
+    let body: serde_json::Value = resp.json().await.expect("invalid json");
+
+    assert_eq!(body["config"]["name"], "no runtime");
+    assert_eq!(body["runtime"]["status"], "waiting");
+    assert_eq!(body["runtime"]["participantCount"], 1);
+    assert_eq!(body["config"]["participantCount"], 1);
+    assert_eq!(body["players"].as_array().map(Vec::len), Some(1));
+
+    // Unknown lobbies are still a 404
+    let resp = client
+        .get(format!(
+            "{}/api/lobbies/{}",
+            app.base_url,
+            uuid::Uuid::new_v4()
+        ))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status().as_u16(), 404);
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn lobby_state_keys_carry_policy_ttl() {
+    use stacks_wars_be::models::{ExpiryPolicy, LobbyState, RedisKey};
+
+    let app = crate::common::spawn_app_with_containers().await;
+    let lobby_id = uuid::Uuid::new_v4();
+
+    stacks_wars_be::db::lobby_state::LobbyStateRepository::new(app.state.redis.clone())
+        .create_state(LobbyState::new(lobby_id))
+        .await
+        .expect("create state");
+
+    let mut conn = app.state.redis.get().await.expect("redis conn");
+    let ttl: i64 = conn
+        .ttl(RedisKey::lobby_state(lobby_id))
+        .await
+        .expect("redis ttl");
+    assert!(
+        ttl > ExpiryPolicy::LOBBY_TTL_SECS - 10 && ttl <= ExpiryPolicy::LOBBY_TTL_SECS,
+        "lobby state ttl {}",
+        ttl
+    );
+
+    drop(conn);
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn lobby_activity_refreshes_key_ttls() {
+    use stacks_wars_be::db::expiry::refresh_lobby_expiry;
+    use stacks_wars_be::models::{ExpiryPolicy, LobbyState, RedisKey};
+
+    let app = crate::common::spawn_app_with_containers().await;
+    let (lobby_id, seeded) = seed_roster(&app, 2).await;
+    stacks_wars_be::db::lobby_state::LobbyStateRepository::new(app.state.redis.clone())
+        .create_state(LobbyState::new(lobby_id))
+        .await
+        .expect("create state");
+
+    // Fresh keys aren't due a refresh
+    assert!(
+        !refresh_lobby_expiry(&app.state.redis, lobby_id)
+            .await
+            .expect("refresh")
+    );
+
+    // Let the lobby's keys run low, as if it had been live for hours
+    let mut keys = vec![RedisKey::lobby_state(lobby_id)];
+    keys.extend(
+        seeded
+            .iter()
+            .map(|p| RedisKey::lobby_player(lobby_id, p.user_id)),
+    );
+    let mut conn = app.state.redis.get().await.expect("redis conn");
+    for key in &keys {
+        let _: bool = conn.expire(key, 60).await.expect("redis expire");
+    }
+
+    assert!(
+        refresh_lobby_expiry(&app.state.redis, lobby_id)
+            .await
+            .expect("refresh")
+    );
+    for key in &keys {
+        let ttl: i64 = conn.ttl(key).await.expect("redis ttl");
+        assert!(
+            ttl > ExpiryPolicy::LOBBY_TTL_SECS - 10,
+            "{} ttl {} after activity",
+            key,
+            ttl
+        );
+    }
+
+    drop(conn);
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn unclaimed_prizes_hold_lobby_keys_until_claimed() {
+    use stacks_wars_be::db::expiry::refresh_lobby_expiry;
+    use stacks_wars_be::db::player_state::PlayerStateRepository;
+    use stacks_wars_be::models::{ExpiryPolicy, LobbyState, RedisKey};
+
+    let app = crate::common::spawn_app_with_containers().await;
+    let (lobby_id, seeded) = seed_roster(&app, 2).await;
+    stacks_wars_be::db::lobby_state::LobbyStateRepository::new(app.state.redis.clone())
+        .create_state(LobbyState::new(lobby_id))
+        .await
+        .expect("create state");
+    let repo = PlayerStateRepository::new(app.state.redis.clone());
+
+    let mut keys = vec![RedisKey::lobby_state(lobby_id)];
+    keys.extend(
+        seeded
+            .iter()
+            .map(|p| RedisKey::lobby_player(lobby_id, p.user_id)),
+    );
+
+    // Both winners are owed a prize, so nothing expires
+    for player in &seeded {
+        repo.set_prize(lobby_id, player.user_id, rust_decimal::Decimal::from(5))
+            .await
+            .expect("set prize");
+    }
+    let mut conn = app.state.redis.get().await.expect("redis conn");
+    for key in &keys {
+        let ttl: i64 = conn.ttl(key).await.expect("redis ttl");
+        assert_eq!(ttl, -1, "{} held", key);
+    }
+
+    // Activity and later writes don't put a TTL back
+    assert!(
+        !refresh_lobby_expiry(&app.state.redis, lobby_id)
+            .await
+            .expect("refresh")
+    );
+    repo.upsert_state(seeded[1].clone(), None)
+        .await
+        .expect("rewrite player");
+    let ttl: i64 = conn.ttl(&keys[2]).await.expect("redis ttl");
+    assert_eq!(ttl, -1);
+
+    // One claim isn't enough
+    repo.mark_claimed(lobby_id, seeded[0].user_id)
+        .await
+        .expect("claim");
+    let ttl: i64 = conn.ttl(&keys[0]).await.expect("redis ttl");
+    assert_eq!(ttl, -1);
+
+    // The last claim releases the lobby
+    repo.mark_claimed(lobby_id, seeded[1].user_id)
+        .await
+        .expect("claim");
+    for key in &keys {
+        let ttl: i64 = conn.ttl(key).await.expect("redis ttl");
+        assert!(
+            ttl > ExpiryPolicy::LOBBY_TTL_SECS - 10,
+            "{} ttl {} after the last claim",
+            key,
+            ttl
+        );
+    }
+
+    drop(conn);
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn lobby_config_updates_write_through_to_redis() {
+    use stacks_wars_be::db::lobby::LobbyRepository;
+    use stacks_wars_be::models::RedisKey;
+
+    let app = crate::common::spawn_app_with_containers().await;
+    let factory = app.factory();
+    let (creator_id, _t) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let game_id = factory
+        .create_test_game(creator_id, Some("write-through-game"))
+        .await
+        .expect("create game failed");
+    let (lobby_id, _path) = factory
+        .create_test_lobby(creator_id, game_id, Some("before"))
+        .await
+        .expect("create lobby failed");
+
+    let repo = LobbyRepository::new(app.state.postgres.clone());
+    repo.update_name(lobby_id, "after", app.state.clone())
+        .await
+        .expect("update name");
+    repo.update_entry_amount(lobby_id, rust_decimal::Decimal::from(5), app.state.clone())
+        .await
+        .expect("update entry amount");
+
+    let mut conn = app.state.redis.get().await.expect("redis conn");
+    let key = RedisKey::lobby_state(lobby_id);
+    let name: Option<String> = conn.hget(&key, "name").await.expect("redis hget");
+    assert_eq!(name.as_deref(), Some("after"));
+    let entry: Option<String> = conn.hget(&key, "entry_amount").await.expect("redis hget");
+    assert_eq!(entry.as_deref(), Some("5"));
+
+    // An on-demand sync repairs a hash that drifted from Postgres
+    let _: () = conn.hset(&key, "name", "stale").await.expect("redis hset");
+    assert!(
+        repo.sync_lobby_to_redis(lobby_id, &app.state.redis)
+            .await
+            .expect("sync")
+    );
+    let name: Option<String> = conn.hget(&key, "name").await.expect("redis hget");
+    assert_eq!(name.as_deref(), Some("after"));
+
+    // Lobbies without runtime state are left alone rather than recreated
+    let _: () = conn.del(&key).await.expect("redis del");
+    assert!(
+        !repo
+            .sync_lobby_to_redis(lobby_id, &app.state.redis)
+            .await
+            .expect("sync")
+    );
+    let exists: bool = conn.exists(&key).await.expect("redis exists");
+    assert!(!exists);
+
+    drop(conn);
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn create_lobby_reports_every_invalid_field() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+    let factory = app.factory();
+
+    let (_, token) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+
+    let resp = client
+        .post(format!("{}/api/lobby", app.base_url))
+        .header("Cookie", factory.create_auth_cookie(&token))
+        .json(&json!({
+            "name": "  ",
+            "entryAmount": -5.0,
+            "currentAmount": -5.0,
+            "contractAddress": "not-a-principal",
+            "autoApproveTrustThreshold": -1.0,
+            "gameId": uuid::Uuid::new_v4(),
+            "gamePath": ""
+        }))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status().as_u16(), 400);
+
+    let body: serde_json::Value = resp.json().await.expect("json");
+    let fields: Vec<&str> = body["errors"]
+        .as_array()
+        .expect("errors array")
+        .iter()
+        .map(|e| e["field"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        fields,
+        [
+            "name",
+            "entryAmount",
+            "currentAmount",
+            "contractAddress",
+            "autoApproveTrustThreshold",
+            "gamePath"
+        ]
+    );
+    assert_eq!(body["errors"][1]["error"], "must be >= 0");
+
+    // A body that doesn't deserialize names the offending field
+    let resp = client
+        .post(format!("{}/api/lobby", app.base_url))
+        .header("Cookie", factory.create_auth_cookie(&token))
+        .json(&json!({
+            "name": "typed lobby",
+            "isPrivate": "yes",
+            "gameId": uuid::Uuid::new_v4(),
+            "gamePath": "typed-game"
+        }))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status().as_u16(), 400);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["errors"][0]["field"], "isPrivate");
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn auto_approve_threshold_needs_a_private_lobby() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+    let factory = app.factory();
+
+    let (alice, alice_token) = factory.create_test_user(None).await.expect("alice");
+    let (_, bob_token) = factory.create_test_user(None).await.expect("bob");
+    let game_id = factory
+        .create_test_game(alice, Some("auto-approve-http"))
+        .await
+        .expect("create game");
+
+    let resp = client
+        .post(format!("{}/api/lobby", app.base_url))
+        .header("Cookie", factory.create_auth_cookie(&alice_token))
+        .json(&json!({
+            "name": "open doors",
+            "isPrivate": false,
+            "autoApproveTrustThreshold": 50.0,
+            "gameId": game_id,
+            "gamePath": "auto-approve-http"
+        }))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status().as_u16(), 400);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["errors"][0]["field"], "autoApproveTrustThreshold");
+
+    let (lobby_id, _) = factory
+        .create_test_lobby(alice, game_id, Some("Public"))
+        .await
+        .expect("create lobby");
+    let url = format!("{}/api/lobby/{}/auto-approve", app.base_url, lobby_id);
+    let set_threshold = |token: &str| {
+        client
+            .patch(&url)
+            .header("Cookie", factory.create_auth_cookie(token))
+            .json(&json!({ "trustThreshold": 50.0 }))
+            .send()
+    };
+
+    let resp = set_threshold(&bob_token).await.expect("request failed");
+    assert_eq!(resp.status().as_u16(), 403);
+    let resp = set_threshold(&alice_token).await.expect("request failed");
+    assert_eq!(
+        resp.status().as_u16(),
+        400,
+        "public lobbies take no threshold"
+    );
+
+    sqlx::query("UPDATE lobbies SET is_private = true WHERE id = $1")
+        .bind(lobby_id)
+        .execute(&app.pg_pool)
+        .await
+        .expect("make lobby private");
+    let resp = set_threshold(&alice_token).await.expect("request failed");
+    assert_eq!(resp.status().as_u16(), 200);
+    let lobby: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(lobby["autoApproveTrustThreshold"], 50.0);
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn create_lobby_with_payout_table() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+    let factory = app.factory();
+
+    let (user_id, token) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let game_id = factory
+        .create_test_game(user_id, Some("payout-game"))
+        .await
+        .expect("create game failed");
+
+    let payload = |percents: serde_json::Value| {
+        json!({
+            "name": "payout lobby",
+            "tokenSymbol": "STX",
+            "payoutPercents": percents,
+            "payoutUnderfill": "pot",
+            "gameId": game_id,
+            "gamePath": "payout-game"
+        })
+    };
+
+    let resp = client
+        .post(format!("{}/api/lobby", app.base_url))
+        .header("Cookie", factory.create_auth_cookie(&token))
+        .json(&payload(json!([50, 30])))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status().as_u16(), 400);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["errors"][0]["field"], "payoutPercents");
+
+    let resp = client
+        .post(format!("{}/api/lobby", app.base_url))
+        .header("Cookie", factory.create_auth_cookie(&token))
+        .json(&payload(json!([50, 30, 20])))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status().as_u16(), 201);
+    let lobby: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(lobby["payoutPercents"], json!([50, 30, 20]));
+    assert_eq!(lobby["payoutUnderfill"], "pot");
+
+    app.stop().await;
+}
+
+/// Contract events served from a list, newest first, as the node would
+struct MockEvents(std::sync::Mutex<Vec<stacks_wars_be::contract_events::ContractEvent>>);
+
+impl MockEvents {
+    fn push(&self, tx_id: &str, sender: &str, recipient: &str, amount: u128) {
+        self.0.lock().unwrap().insert(
+            0,
+            stacks_wars_be::contract_events::ContractEvent {
+                id: format!("{}:0", tx_id),
+                tx_id: tx_id.to_string(),
+                sender: sender.to_string(),
+                recipient: recipient.to_string(),
+                asset_id: None,
+                amount,
+            },
+        );
+    }
+}
+
+#[async_trait::async_trait]
+impl stacks_wars_be::contract_events::ContractEventSource for MockEvents {
+    async fn events(
+        &self,
+        _contract: &str,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<stacks_wars_be::contract_events::ContractEvent>, stacks_wars_be::errors::AppError>
+    {
+        Ok(self
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .skip(offset)
+            .take(limit)
+            .cloned()
+            .collect())
+    }
+}
+
+#[tokio::test]
+async fn contract_events_advance_deposits_and_claims_once() {
+    use stacks_wars_be::contract_events::{ContractIndexer, indexed_deposit};
+    use stacks_wars_be::models::player_state::ClaimState;
+
+    let app = crate::common::spawn_app_with_containers().await;
+    let factory = app.factory();
+
+    const ALICE: &str = "SP3A11CE00000000000000000000000000000000A";
+    const BOB: &str = "SP3B0B0000000000000000000000000000000000B";
+    const FEES: &str = "SP3FEES000000000000000000000000000000000F";
+    const VAULT: &str = "SP3A11CE00000000000000000000000000000000A.stx-vault";
+
+    let (alice, _) = factory.create_test_user(Some(ALICE)).await.expect("alice");
+    let (bob, _) = factory.create_test_user(Some(BOB)).await.expect("bob");
+    let game_id = factory
+        .create_test_game(alice, Some("vault-game"))
+        .await
+        .expect("create game");
+    let (lobby_id, _) = factory
+        .create_test_lobby(alice, game_id, Some("Vault Lobby"))
+        .await
+        .expect("create lobby");
+    sqlx::query("UPDATE lobbies SET contract_address = $1, entry_amount = 5 WHERE id = $2")
+        .bind(VAULT)
+        .bind(lobby_id)
+        .execute(&app.pg_pool)
+        .await
+        .expect("set contract");
+    factory
+        .add_test_player(lobby_id, bob, false)
+        .await
+        .expect("add bob");
+
+    let source = std::sync::Arc::new(MockEvents(Default::default()));
+    let indexer = ContractIndexer::new(app.state.clone(), source.clone());
+    let players =
+        stacks_wars_be::db::player_state::PlayerStateRepository::new(app.state.redis.clone());
+
+    // Dust sent to the vault isn't an entry fee
+    source.push("0xdust", BOB, VAULT, 1);
+    assert_eq!(indexer.index_once().await.expect("index"), 1);
+    assert_eq!(
+        indexed_deposit(&app.state.redis, lobby_id, BOB)
+            .await
+            .expect("deposit"),
+        None
+    );
+
+    // Both entry fees land in the vault
+    source.push("0xa11ce", ALICE, VAULT, 5_000_000);
+    source.push("0xb0b", BOB, VAULT, 5_000_000);
+    assert_eq!(indexer.index_once().await.expect("index"), 2);
+    assert_eq!(
+        indexed_deposit(&app.state.redis, lobby_id, BOB)
+            .await
+            .expect("deposit"),
+        Some("0xb0b".to_string())
+    );
+    let bob_state = players.get_state(lobby_id, bob).await.expect("bob state");
+    assert_eq!(bob_state.tx_id.as_deref(), Some("0xb0b"));
+
+    // Nothing new: already applied events are skipped
+    assert_eq!(indexer.index_once().await.expect("index"), 0);
+
+    // Bob wins and claims; the fee transfer in the same call is ignored
+    stacks_wars_be::db::lobby::LobbyRepository::new(app.pg_pool.clone())
+        .update_status(
+            lobby_id,
+            stacks_wars_be::models::LobbyStatus::Finished,
+            app.state.clone(),
+        )
+        .await
+        .expect("finish lobby");
+    players
+        .set_prize(lobby_id, bob, rust_decimal::Decimal::from(8))
+        .await
+        .expect("set prize");
+    let mut conn = app.state.redis.get().await.expect("redis");
+    let _: () = conn
+        .hset(
+            stacks_wars_be::models::RedisKey::lobby_state(lobby_id),
+            "current_amount",
+            "10",
+        )
+        .await
+        .expect("set pot");
+
+    source.push("0xc1a1m", VAULT, FEES, 2_000_000);
+    source.push("0xc1a1m", VAULT, BOB, 8_000_000);
+    // Both transfers share a tx; give them distinct event ids
+    source.0.lock().unwrap()[0].id = "0xc1a1m:1".to_string();
+    assert_eq!(indexer.index_once().await.expect("index"), 2);
+
+    let bob_state = players.get_state(lobby_id, bob).await.expect("bob state");
+    assert_eq!(
+        bob_state.claim_state,
+        Some(ClaimState::Claimed {
+            tx_id: "0xc1a1m".to_string()
+        })
+    );
+    let pot: String = conn
+        .hget(
+            stacks_wars_be::models::RedisKey::lobby_state(lobby_id),
+            "current_amount",
+        )
+        .await
+        .expect("pot");
+    assert_eq!(pot, "2");
+
+    // Replaying the feed changes nothing
+    assert_eq!(indexer.index_once().await.expect("index"), 0);
+
+    // A backlog past one pass's cap is applied oldest first over later passes
+    let backlog = stacks_wars_be::contract_events::MAX_EVENTS_PER_PASS + 20;
+    for i in 0..backlog {
+        source.push(&format!("0xback{}", i), FEES, VAULT, 1);
+    }
+    assert_eq!(
+        indexer.index_once().await.expect("index"),
+        stacks_wars_be::contract_events::MAX_EVENTS_PER_PASS
+    );
+    assert_eq!(indexer.index_once().await.expect("index"), 20);
+    assert_eq!(indexer.index_once().await.expect("index"), 0);
+    drop(conn);
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn lobby_updates_advance_only_their_own_updated_at() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let factory = app.factory();
+
+    let (creator_id, _) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let game_id = factory
+        .create_test_game(creator_id, Some("updated-at-game"))
+        .await
+        .expect("create game failed");
+    let (edited, _) = factory
+        .create_test_lobby(creator_id, game_id, Some("edited"))
+        .await
+        .expect("create lobby failed");
+    let (untouched, _) = factory
+        .create_test_lobby(creator_id, game_id, Some("untouched"))
+        .await
+        .expect("create lobby failed");
+
+    // Backdate both so a same-second update still shows up
+    use chrono::SubsecRound;
+    let earlier = (chrono::Utc::now().naive_utc() - chrono::Duration::hours(1)).trunc_subsecs(0);
+    sqlx::query("UPDATE lobbies SET updated_at = $1 WHERE id = ANY($2)")
+        .bind(earlier)
+        .bind(vec![edited, untouched])
+        .execute(&app.pg_pool)
+        .await
+        .expect("backdate lobbies");
+
+    let repo = stacks_wars_be::db::lobby::LobbyRepository::new(app.state.postgres.clone());
+    let lobby = repo
+        .update_description(edited, "new description", app.state.clone())
+        .await
+        .expect("update description");
+    assert!(lobby.updated_at > earlier);
+    assert_eq!(
+        repo.find_by_id(edited).await.expect("edited").updated_at,
+        lobby.updated_at
+    );
+    assert_eq!(
+        repo.find_by_id(untouched)
+            .await
+            .expect("untouched")
+            .updated_at,
+        earlier
+    );
+
+    app.stop().await;
+}
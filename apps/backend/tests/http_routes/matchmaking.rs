@@ -0,0 +1,320 @@
+use serde_json::json;
+
+#[tokio::test]
+async fn compatible_enqueues_form_lobby() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+    let factory = app.factory();
+
+    let (creator_id, _t) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let game_id = factory
+        .create_game_with_players(creator_id, Some("quick-play-game"), 2, 4)
+        .await
+        .expect("create game failed");
+
+    let (first_id, first_token) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let (_second_id, second_token) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+
+    let resp = client
+        .post(format!("{}/api/matchmaking", app.base_url))
+        .header("Cookie", factory.create_auth_cookie(&first_token))
+        .json(&json!({ "gameId": game_id, "minStake": 0.0, "maxStake": 0.0 }))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status().as_u16(), 200);
+    let body: serde_json::Value = resp.json().await.expect("invalid json");
+    assert_eq!(body["status"], "queued");
+
+    let resp = client
+        .post(format!("{}/api/matchmaking", app.base_url))
+        .header("Cookie", factory.create_auth_cookie(&second_token))
+        .json(&json!({ "gameId": game_id, "minStake": 0.0, "maxStake": 5.0 }))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status().as_u16(), 200);
+    let body: serde_json::Value = resp.json().await.expect("invalid json");
+    assert_eq!(body["status"], "matched");
+    assert_eq!(
+        body["lobby"]["creatorId"].as_str(),
+        Some(first_id.to_string().as_str()),
+        "longest-waiting player should create the lobby"
+    );
+
+    let queue = stacks_wars_be::matchmaking::MatchmakingQueue::new(app.state.redis.clone());
+    let remaining = queue.list(game_id).await.expect("list queue");
+    assert!(remaining.is_empty(), "matched players should leave the queue");
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn incompatible_stakes_stay_queued() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+    let factory = app.factory();
+
+    let (creator_id, _t) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let game_id = factory
+        .create_game_with_players(creator_id, Some("quick-play-stakes"), 2, 4)
+        .await
+        .expect("create game failed");
+
+    let (_a, token_a) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let (_b, token_b) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+
+    for (token, min, max) in [(&token_a, 0.0, 1.0), (&token_b, 10.0, 20.0)] {
+        let resp = client
+            .post(format!("{}/api/matchmaking", app.base_url))
+            .header("Cookie", factory.create_auth_cookie(token))
+            .json(&json!({ "gameId": game_id, "minStake": min, "maxStake": max }))
+            .send()
+            .await
+            .expect("request failed");
+        assert_eq!(resp.status().as_u16(), 200);
+        let body: serde_json::Value = resp.json().await.expect("invalid json");
+        assert_eq!(body["status"], "queued");
+    }
+
+    let queue = stacks_wars_be::matchmaking::MatchmakingQueue::new(app.state.redis.clone());
+    assert_eq!(queue.list(game_id).await.expect("list queue").len(), 2);
+
+    // Leaving the queue removes the entry
+    let resp = client
+        .delete(format!("{}/api/matchmaking/{}", app.base_url, game_id))
+        .header("Cookie", factory.create_auth_cookie(&token_a))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status().as_u16(), 204);
+    assert_eq!(queue.list(game_id).await.expect("list queue").len(), 1);
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn recent_opponents_are_not_rematched_in_paid_play() {
+    use stacks_wars_be::matchmaking::{MatchHistory, MatchmakingQueue, StakeRange, run_matcher};
+
+    let app = crate::common::spawn_app_with_containers().await;
+    let factory = app.factory();
+
+    let (creator_id, _t) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let game_id = factory
+        .create_game_with_players(creator_id, Some("quick-play-separation"), 2, 2)
+        .await
+        .expect("create game failed");
+
+    let mut players = Vec::new();
+    for _ in 0..3 {
+        let (user_id, _token) = factory
+            .create_test_user(None)
+            .await
+            .expect("create user failed");
+        players.push(user_id);
+    }
+    let (a, b, c) = (players[0], players[1], players[2]);
+
+    // a and b just shared a paid match
+    let window = app.state.config.seat_separation.window_secs;
+    MatchHistory::new(app.state.redis.clone())
+        .record(
+            uuid::Uuid::new_v4(),
+            &[a, b],
+            chrono::Utc::now().timestamp(),
+            window,
+        )
+        .await
+        .expect("record history");
+
+    let queue = MatchmakingQueue::new(app.state.redis.clone());
+    let stake = StakeRange::new(1.0, 5.0).unwrap();
+    for user_id in [a, b, c] {
+        queue
+            .enqueue(user_id, game_id, stake, 1200.0)
+            .await
+            .expect("enqueue");
+    }
+
+    let (_lobby, group) = run_matcher(&app.state, game_id)
+        .await
+        .expect("matcher failed")
+        .expect("should match");
+    let matched: Vec<uuid::Uuid> = group.players.iter().map(|p| p.user_id).collect();
+    assert!(
+        matched.contains(&c),
+        "the unrelated player should be matched"
+    );
+    assert!(
+        !(matched.contains(&a) && matched.contains(&b)),
+        "recent opponents shouldn't be rematched straight away"
+    );
+
+    // Whichever of a and b wasn't matched is still waiting
+    let remaining = queue.list(game_id).await.expect("list queue");
+    assert_eq!(remaining.len(), 1);
+    assert!([a, b].contains(&remaining[0].user_id));
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn paid_play_needs_the_join_trust_rating() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+    let factory = app.factory();
+
+    let (creator_id, _t) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let game_id = factory
+        .create_game_with_players(creator_id, Some("quick-play-trust"), 2, 4)
+        .await
+        .expect("create game failed");
+    let (user_id, token) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    sqlx::query("UPDATE users SET trust_rating = 1 WHERE id = $1")
+        .bind(user_id)
+        .execute(&app.pg_pool)
+        .await
+        .expect("lower trust rating");
+
+    let enqueue = |max_stake: f64| {
+        client
+            .post(format!("{}/api/matchmaking", app.base_url))
+            .header("Cookie", factory.create_auth_cookie(&token))
+            .json(&json!({ "gameId": game_id, "minStake": 0.0, "maxStake": max_stake }))
+            .send()
+    };
+
+    let resp = enqueue(5.0).await.expect("request failed");
+    assert_eq!(resp.status().as_u16(), 403);
+    let queue = stacks_wars_be::matchmaking::MatchmakingQueue::new(app.state.redis.clone());
+    assert!(queue.list(game_id).await.expect("list queue").is_empty());
+
+    // Free play has no minimum
+    let resp = enqueue(0.0).await.expect("request failed");
+    assert_eq!(resp.status().as_u16(), 200);
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn unknown_games_cannot_be_queued() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+    let factory = app.factory();
+
+    let (_user_id, token) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let game_id = uuid::Uuid::new_v4();
+
+    let resp = client
+        .post(format!("{}/api/matchmaking", app.base_url))
+        .header("Cookie", factory.create_auth_cookie(&token))
+        .json(&json!({ "gameId": game_id, "minStake": 0.0, "maxStake": 0.0 }))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status().as_u16(), 404);
+
+    let queue = stacks_wars_be::matchmaking::MatchmakingQueue::new(app.state.redis.clone());
+    assert!(queue.list(game_id).await.expect("list queue").is_empty());
+    assert!(
+        !queue
+            .queued_games()
+            .await
+            .expect("list queued games")
+            .contains(&game_id)
+    );
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn sweep_drops_players_who_waited_too_long() {
+    use redis::AsyncCommands;
+    use stacks_wars_be::matchmaking::{
+        MatchmakingQueue, QUEUE_TIMEOUT_SECS, StakeRange, sweep_queue_timeouts,
+    };
+    use stacks_wars_be::models::RedisKey;
+
+    let app = crate::common::spawn_app_with_containers().await;
+    let factory = app.factory();
+
+    let (creator_id, _t) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let game_id = factory
+        .create_game_with_players(creator_id, Some("quick-play-sweep"), 2, 4)
+        .await
+        .expect("create game failed");
+    let (stale, _) = factory.create_test_user(None).await.unwrap();
+    let (fresh, _) = factory.create_test_user(None).await.unwrap();
+
+    let queue = MatchmakingQueue::new(app.state.redis.clone());
+    for (user_id, stake) in [(stale, 0.0), (fresh, 10.0)] {
+        queue
+            .enqueue(
+                user_id,
+                game_id,
+                StakeRange::new(stake, stake).unwrap(),
+                1200.0,
+            )
+            .await
+            .expect("enqueue");
+    }
+
+    // Nobody else enqueues, so no matching pass would ever expire the stale player
+    let mut conn = app.state.redis.get().await.expect("redis");
+    let waited_since = chrono::Utc::now().timestamp() - QUEUE_TIMEOUT_SECS - 1;
+    let _: () = conn
+        .zadd(
+            RedisKey::matchmaking_queue(game_id),
+            stale.to_string(),
+            waited_since,
+        )
+        .await
+        .expect("backdate entry");
+    drop(conn);
+
+    assert!(sweep_queue_timeouts(&app.state).await.expect("sweep") >= 1);
+    let remaining: Vec<uuid::Uuid> = queue
+        .list(game_id)
+        .await
+        .expect("list queue")
+        .iter()
+        .map(|entry| entry.user_id)
+        .collect();
+    assert_eq!(remaining, vec![fresh]);
+
+    app.stop().await;
+}
@@ -200,3 +200,103 @@ async fn delete_platform_rating() {
 
     app.stop().await;
 }
+
+#[tokio::test]
+async fn second_rating_by_same_user_updates() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+
+    let factory = app.factory();
+    let (user_id, token) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+
+    for (rating, comment) in [(2, "first"), (5, "changed my mind")] {
+        let resp = client
+            .post(format!("{}/api/platform-rating", app.base_url))
+            .header("Cookie", factory.create_auth_cookie(&token))
+            .json(&json!({ "rating": rating, "comment": comment }))
+            .send()
+            .await
+            .expect("request failed");
+        assert!(resp.status().is_success());
+    }
+
+    let rows: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM platform_ratings WHERE user_id = $1")
+        .bind(user_id)
+        .fetch_one(&factory.pg_pool)
+        .await
+        .expect("count failed");
+    assert_eq!(rows, 1);
+
+    let resp = client
+        .get(format!("{}/api/platform-rating/{}", app.base_url, user_id))
+        .send()
+        .await
+        .expect("request failed");
+    let body: serde_json::Value = resp.json().await.expect("invalid json");
+    assert_eq!(body["rating"], 5);
+    assert_eq!(body["comment"], "changed my mind");
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn ratings_summary_and_pagination() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+
+    let factory = app.factory();
+    for rating in [5, 5, 4, 1] {
+        let (user_id, _) = factory
+            .create_test_user(None)
+            .await
+            .expect("create user failed");
+        factory
+            .create_platform_rating(user_id, rating)
+            .await
+            .expect("create platform rating failed");
+    }
+
+    let resp = client
+        .get(format!("{}/api/platform/ratings/summary", app.base_url))
+        .send()
+        .await
+        .expect("request failed");
+    assert!(resp.status().is_success());
+    let summary: serde_json::Value = resp.json().await.expect("invalid json");
+    assert_eq!(summary["count"], 4);
+    assert_eq!(summary["average"], 3.75);
+    let counts: Vec<i64> = summary["distribution"]
+        .as_array()
+        .expect("distribution missing")
+        .iter()
+        .map(|b| b["count"].as_i64().unwrap())
+        .collect();
+    assert_eq!(counts, vec![1, 0, 0, 1, 2]);
+
+    let resp = client
+        .get(format!(
+            "{}/api/platform/ratings/summary?minRating=4",
+            app.base_url
+        ))
+        .send()
+        .await
+        .expect("request failed");
+    let filtered: serde_json::Value = resp.json().await.expect("invalid json");
+    assert_eq!(filtered["count"], 3);
+
+    let resp = client
+        .get(format!(
+            "{}/api/platform/ratings?limit=2&offset=1",
+            app.base_url
+        ))
+        .send()
+        .await
+        .expect("request failed");
+    let page: Vec<serde_json::Value> = resp.json().await.expect("invalid json");
+    assert_eq!(page.len(), 2);
+
+    app.stop().await;
+}
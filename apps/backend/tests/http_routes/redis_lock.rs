@@ -0,0 +1,131 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use redis::AsyncCommands;
+use stacks_wars_be::{
+    models::RedisKey,
+    redis_lock::{RedisLock, with_lock},
+};
+
+#[tokio::test]
+async fn lock_is_held_by_one_caller_at_a_time() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let key = RedisKey::lock("exclusive");
+
+    let first = RedisLock::try_acquire(&app.state.redis, &key, Duration::from_secs(5))
+        .await
+        .expect("acquire")
+        .expect("free lock is acquired");
+    assert!(
+        RedisLock::try_acquire(&app.state.redis, &key, Duration::from_secs(5))
+            .await
+            .expect("acquire")
+            .is_none(),
+        "held lock can't be taken"
+    );
+    assert!(first.release().await.expect("release"));
+
+    // Concurrent critical sections never overlap
+    let inside = Arc::new(AtomicBool::new(false));
+    let entered = Arc::new(AtomicUsize::new(0));
+    let tasks: Vec<_> = (0..8)
+        .map(|_| {
+            let redis = app.state.redis.clone();
+            let key = key.clone();
+            let inside = inside.clone();
+            let entered = entered.clone();
+            tokio::spawn(async move {
+                with_lock(&redis, &key, Duration::from_secs(5), || async {
+                    assert!(!inside.swap(true, Ordering::SeqCst), "sections overlapped");
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                    entered.fetch_add(1, Ordering::SeqCst);
+                    inside.store(false, Ordering::SeqCst);
+                    Ok(())
+                })
+                .await
+            })
+        })
+        .collect();
+    for task in tasks {
+        task.await.expect("task panicked").expect("with_lock");
+    }
+    assert_eq!(entered.load(Ordering::SeqCst), 8);
+
+    // Released afterwards, even though nobody called release
+    assert!(
+        RedisLock::try_acquire(&app.state.redis, &key, Duration::from_secs(5))
+            .await
+            .expect("acquire")
+            .is_some()
+    );
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn release_only_frees_the_holders_own_lock() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let key = RedisKey::lock("guarded");
+
+    let stale = RedisLock::try_acquire(&app.state.redis, &key, Duration::from_millis(100))
+        .await
+        .expect("acquire")
+        .expect("free lock is acquired");
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let current = RedisLock::try_acquire(&app.state.redis, &key, Duration::from_secs(5))
+        .await
+        .expect("acquire")
+        .expect("expired lock is free");
+
+    // The expired holder can't release the new holder's lock
+    assert!(!stale.release().await.expect("release"));
+    let mut conn = app.state.redis.get().await.expect("redis conn");
+    let holder: Option<String> = conn.get(&key).await.expect("redis get");
+    assert_eq!(holder.as_deref(), Some(current.token()));
+    drop(conn);
+
+    assert!(current.release().await.expect("release"));
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn crashed_holder_lock_expires() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let key = RedisKey::lock("crashed");
+
+    // Held and never released, as if the instance died mid-section
+    let crashed = RedisLock::try_acquire(&app.state.redis, &key, Duration::from_millis(200))
+        .await
+        .expect("acquire")
+        .expect("free lock is acquired");
+    drop(crashed);
+
+    let result = RedisLock::acquire(
+        &app.state.redis,
+        &key,
+        Duration::from_secs(5),
+        Duration::from_millis(50),
+    )
+    .await;
+    assert!(result.is_err(), "still held before the ttl runs out");
+
+    // Waiting past the ttl gets the lock
+    let lock = RedisLock::acquire(
+        &app.state.redis,
+        &key,
+        Duration::from_secs(5),
+        Duration::from_secs(1),
+    )
+    .await
+    .expect("lock acquired once the crashed holder's ttl ran out");
+    assert!(lock.release().await.expect("release"));
+
+    app.stop().await;
+}
@@ -0,0 +1,189 @@
+use serde_json::json;
+
+use crate::common::TEST_ADMIN_WALLET;
+
+async fn trust_rating(pool: &sqlx::PgPool, user_id: uuid::Uuid) -> f64 {
+    sqlx::query_scalar("SELECT trust_rating FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .expect("trust query failed")
+}
+
+#[tokio::test]
+async fn create_report_applies_provisional_penalty() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+    let factory = app.factory();
+
+    let (_, token) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let (reported_id, _) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let before = trust_rating(&factory.pg_pool, reported_id).await;
+
+    let resp = client
+        .post(format!("{}/api/report", app.base_url))
+        .header("Cookie", factory.create_auth_cookie(&token))
+        .json(&json!({
+            "reportedUserId": reported_id,
+            "category": "cheating",
+            "description": "used a word solver",
+        }))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), reqwest::StatusCode::CREATED);
+    let body: serde_json::Value = resp.json().await.expect("invalid json");
+    assert_eq!(body["status"], "open");
+    assert_eq!(body["category"], "cheating");
+
+    let after = trust_rating(&factory.pg_pool, reported_id).await;
+    assert!(after < before);
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn duplicate_reports_are_throttled() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+    let factory = app.factory();
+
+    let (_, token) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let (reported_id, _) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+
+    let report = || {
+        client
+            .post(format!("{}/api/report", app.base_url))
+            .header("Cookie", factory.create_auth_cookie(&token))
+            .json(&json!({ "reportedUserId": reported_id, "category": "abuse" }))
+            .send()
+    };
+
+    let first = report().await.expect("request failed");
+    assert_eq!(first.status(), reqwest::StatusCode::CREATED);
+
+    let second = report().await.expect("request failed");
+    assert_eq!(second.status(), reqwest::StatusCode::CONFLICT);
+
+    // Distinct targets still hit the hourly cap
+    let mut last = reqwest::StatusCode::CREATED;
+    for _ in 0..5 {
+        let (other_id, _) = factory
+            .create_test_user(None)
+            .await
+            .expect("create user failed");
+        last = client
+            .post(format!("{}/api/report", app.base_url))
+            .header("Cookie", factory.create_auth_cookie(&token))
+            .json(&json!({ "reportedUserId": other_id, "category": "spam" }))
+            .send()
+            .await
+            .expect("request failed")
+            .status();
+    }
+    assert_eq!(last, reqwest::StatusCode::TOO_MANY_REQUESTS);
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn resolving_report_transitions_state() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+    let factory = app.factory();
+
+    let (_, admin_token) = factory
+        .create_test_user(Some(TEST_ADMIN_WALLET))
+        .await
+        .expect("create admin failed");
+    let (_, token) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let (reported_id, reported_token) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+
+    let resp = client
+        .post(format!("{}/api/report", app.base_url))
+        .header("Cookie", factory.create_auth_cookie(&token))
+        .json(&json!({ "reportedUserId": reported_id, "category": "cheating" }))
+        .send()
+        .await
+        .expect("request failed");
+    let report: serde_json::Value = resp.json().await.expect("invalid json");
+    let report_id = report["id"].as_str().expect("missing id").to_string();
+
+    let resp = client
+        .get(format!("{}/api/admin/reports?status=open", app.base_url))
+        .header("Cookie", factory.create_auth_cookie(&admin_token))
+        .send()
+        .await
+        .expect("request failed");
+    let queue: Vec<serde_json::Value> = resp.json().await.expect("invalid json");
+    assert!(queue.iter().any(|r| r["id"] == report_id.as_str()));
+
+    let resp = client
+        .post(format!(
+            "{}/api/admin/reports/{}/assign",
+            app.base_url, report_id
+        ))
+        .header("Cookie", factory.create_auth_cookie(&admin_token))
+        .send()
+        .await
+        .expect("request failed");
+    let assigned: serde_json::Value = resp.json().await.expect("invalid json");
+    assert_eq!(assigned["status"], "assigned");
+
+    let resolve = || {
+        client
+            .post(format!(
+                "{}/api/admin/reports/{}/resolve",
+                app.base_url, report_id
+            ))
+            .header("Cookie", factory.create_auth_cookie(&admin_token))
+            .json(&json!({ "action": "ban", "note": "confirmed" }))
+            .send()
+    };
+
+    let resp = resolve().await.expect("request failed");
+    assert!(resp.status().is_success());
+    let resolved: serde_json::Value = resp.json().await.expect("invalid json");
+    assert_eq!(resolved["status"], "resolved");
+    assert_eq!(resolved["action"], "ban");
+
+    let banned: bool = sqlx::query_scalar("SELECT banned_at IS NOT NULL FROM users WHERE id = $1")
+        .bind(reported_id)
+        .fetch_one(&factory.pg_pool)
+        .await
+        .expect("ban query failed");
+    assert!(banned);
+
+    // The ban signs them out everywhere
+    let resp = client
+        .get(format!("{}/api/me", app.base_url))
+        .header("Cookie", factory.create_auth_cookie(&reported_token))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    // Closed reports can't be resolved again
+    let again = resolve().await.expect("request failed");
+    assert_eq!(again.status(), reqwest::StatusCode::CONFLICT);
+
+    app.stop().await;
+}
@@ -1,6 +1,8 @@
 use chrono::Utc;
+use redis::AsyncCommands;
 use reqwest;
 use serde_json::json;
+use stacks_wars_be::{db::season::SeasonRepository, errors::AppError, models::RedisKey};
 
 #[tokio::test]
 async fn create_season() {
@@ -82,3 +84,640 @@ async fn get_current_season_id() {
 
     app.stop().await;
 }
+
+async fn post_season(
+    app: &crate::common::TestApp,
+    token: &str,
+    name: &str,
+    start: &str,
+    end: &str,
+    allow_overlap: bool,
+) -> reqwest::Response {
+    reqwest::Client::new()
+        .post(format!("{}/api/season", app.base_url))
+        .header("Cookie", app.factory().create_auth_cookie(token))
+        .json(&json!({
+            "name": name,
+            "startDate": start,
+            "endDate": end,
+            "allowOverlap": allow_overlap,
+        }))
+        .send()
+        .await
+        .expect("request failed")
+}
+
+#[tokio::test]
+async fn overlapping_season_rejected() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let (_, token) = app
+        .factory()
+        .create_test_user(Some(crate::common::TEST_ADMIN_WALLET))
+        .await
+        .expect("create admin failed");
+
+    let resp = post_season(
+        &app,
+        &token,
+        "overlap-a",
+        "2030-01-01 00:00:00",
+        "2030-02-01 00:00:00",
+        false,
+    )
+    .await;
+    assert!(resp.status().is_success());
+
+    let resp = post_season(
+        &app,
+        &token,
+        "overlap-b",
+        "2030-01-15 00:00:00",
+        "2030-03-01 00:00:00",
+        false,
+    )
+    .await;
+    assert_eq!(resp.status(), reqwest::StatusCode::CONFLICT);
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn adjacent_season_accepted() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let (_, token) = app
+        .factory()
+        .create_test_user(Some(crate::common::TEST_ADMIN_WALLET))
+        .await
+        .expect("create admin failed");
+
+    let resp = post_season(
+        &app,
+        &token,
+        "adjacent-a",
+        "2030-01-01 00:00:00",
+        "2030-02-01 00:00:00",
+        false,
+    )
+    .await;
+    assert!(resp.status().is_success());
+
+    let resp = post_season(
+        &app,
+        &token,
+        "adjacent-b",
+        "2030-02-01 00:00:00",
+        "2030-03-01 00:00:00",
+        false,
+    )
+    .await;
+    assert!(resp.status().is_success());
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn overlapping_season_allowed_with_override() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let (_, token) = app
+        .factory()
+        .create_test_user(Some(crate::common::TEST_ADMIN_WALLET))
+        .await
+        .expect("create admin failed");
+
+    let resp = post_season(
+        &app,
+        &token,
+        "event-base",
+        "2030-01-01 00:00:00",
+        "2030-02-01 00:00:00",
+        false,
+    )
+    .await;
+    assert!(resp.status().is_success());
+
+    let resp = post_season(
+        &app,
+        &token,
+        "event-special",
+        "2030-01-10 00:00:00",
+        "2030-01-20 00:00:00",
+        true,
+    )
+    .await;
+    assert!(resp.status().is_success());
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn overlapping_season_update_rejected() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let (_, token) = app
+        .factory()
+        .create_test_user(Some(crate::common::TEST_ADMIN_WALLET))
+        .await
+        .expect("create admin failed");
+
+    let resp = post_season(
+        &app,
+        &token,
+        "update-a",
+        "2030-01-01 00:00:00",
+        "2030-02-01 00:00:00",
+        false,
+    )
+    .await;
+    assert!(resp.status().is_success());
+    let resp = post_season(
+        &app,
+        &token,
+        "update-b",
+        "2030-02-01 00:00:00",
+        "2030-03-01 00:00:00",
+        false,
+    )
+    .await;
+    let season_b: serde_json::Value = resp.json().await.expect("invalid json");
+    let season_b = season_b["id"].as_i64().expect("missing id");
+
+    let put_start = |start: &'static str, allow_overlap: bool| {
+        reqwest::Client::new()
+            .put(format!("{}/api/season/{}", app.base_url, season_b))
+            .header("Cookie", app.factory().create_auth_cookie(&token))
+            .json(&json!({ "startDate": start, "allowOverlap": allow_overlap }))
+            .send()
+    };
+
+    // Moving B's start into A would make both current at once
+    let resp = put_start("2030-01-20 00:00:00", false)
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), reqwest::StatusCode::CONFLICT);
+    let resp = put_start("2030-01-20 00:00:00", true)
+        .await
+        .expect("request failed");
+    assert!(resp.status().is_success());
+
+    // Extending A into B is refused the same way
+    let repo = SeasonRepository::new(app.pg_pool.clone());
+    let season_a: i32 = sqlx::query_scalar("SELECT id FROM seasons WHERE name = 'update-a'")
+        .fetch_one(&app.pg_pool)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE seasons SET start_date = '2030-02-01 00:00:00' WHERE name = 'update-b'")
+        .execute(&app.pg_pool)
+        .await
+        .unwrap();
+    assert!(matches!(
+        repo.extend_season(season_a, 7, false).await,
+        Err(AppError::Conflict(_))
+    ));
+    assert!(repo.extend_season(season_a, 7, true).await.is_ok());
+
+    app.stop().await;
+}
+
+fn cached_repo(app: &crate::common::TestApp) -> SeasonRepository {
+    SeasonRepository::new(app.pg_pool.clone()).with_cache(app.state.redis.clone())
+}
+
+#[tokio::test]
+async fn current_season_cache_miss_then_hit() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let repo = cached_repo(&app);
+
+    let mut conn = app.state.redis.get().await.expect("redis conn");
+    let cached: Option<String> = conn.get(RedisKey::current_season()).await.unwrap();
+    assert!(cached.is_none());
+
+    // Miss: loads from Postgres and populates the cache
+    let season = repo.current().await.expect("current season");
+    let cached: Option<String> = conn.get(RedisKey::current_season()).await.unwrap();
+    assert!(cached.is_some());
+
+    // Hit: a direct DB change isn't visible until the cache is invalidated
+    sqlx::query("UPDATE seasons SET name = 'renamed-behind-cache' WHERE id = $1")
+        .bind(season.id())
+        .execute(&app.pg_pool)
+        .await
+        .unwrap();
+    let hit = repo.current().await.expect("current season");
+    assert_eq!(hit.id(), season.id());
+    assert_eq!(hit.name, season.name);
+
+    drop(conn);
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn current_season_cache_invalidated_on_update() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let repo = cached_repo(&app);
+
+    let season = repo.current().await.expect("current season");
+    repo.update_name(season.id(), "renamed-season".to_string())
+        .await
+        .expect("update failed");
+
+    let mut conn = app.state.redis.get().await.expect("redis conn");
+    let cached: Option<String> = conn.get(RedisKey::current_season()).await.unwrap();
+    assert!(cached.is_none());
+
+    let fresh = repo.current().await.expect("current season");
+    assert_eq!(fresh.name, "renamed-season");
+
+    drop(conn);
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn current_season_not_found_when_none_active() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let repo = cached_repo(&app);
+
+    sqlx::query("DELETE FROM seasons")
+        .execute(&app.pg_pool)
+        .await
+        .unwrap();
+
+    let err = repo.current().await.expect_err("no season should be active");
+    assert!(matches!(err, AppError::NotFound(_)));
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn create_season_reports_every_invalid_field() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let factory = app.factory();
+    let client = reqwest::Client::new();
+    let (_, token) = factory
+        .create_test_user(Some(crate::common::TEST_ADMIN_WALLET))
+        .await
+        .expect("create admin failed");
+
+    let resp = client
+        .post(format!("{}/api/season", app.base_url))
+        .header("Cookie", factory.create_auth_cookie(&token))
+        .json(&json!({
+            "name": "",
+            "startDate": "tomorrow",
+            "endDate": "2026-13-01",
+        }))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), reqwest::StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = resp.json().await.expect("invalid json");
+    let fields: Vec<&str> = body["errors"]
+        .as_array()
+        .expect("errors array")
+        .iter()
+        .map(|e| e["field"].as_str().unwrap())
+        .collect();
+    assert_eq!(fields, ["name", "startDate", "endDate"]);
+
+    // Dates that parse are still checked against each other
+    let resp = post_season(
+        &app,
+        &token,
+        "backwards season",
+        "2026-06-01 00:00:00",
+        "2026-05-01 00:00:00",
+        false,
+    )
+    .await;
+    assert_eq!(resp.status(), reqwest::StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = resp.json().await.expect("invalid json");
+    assert_eq!(body["errors"][0]["field"], "endDate");
+
+    // Updates are checked the same way
+    let season = SeasonRepository::new(app.pg_pool.clone())
+        .current()
+        .await
+        .expect("current season");
+    let resp = client
+        .put(format!("{}/api/season/{}", app.base_url, season.id()))
+        .header("Cookie", factory.create_auth_cookie(&token))
+        .json(&json!({ "name": " ", "endDate": "later" }))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), reqwest::StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = resp.json().await.expect("invalid json");
+    assert_eq!(body["errors"][0]["field"], "name");
+    assert_eq!(body["errors"][1]["field"], "endDate");
+
+    // A body that isn't sent as JSON keeps axum's status
+    let resp = client
+        .post(format!("{}/api/season", app.base_url))
+        .header("Cookie", factory.create_auth_cookie(&token))
+        .body("{}")
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), reqwest::StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn ended_season_rolls_over_exactly_once() {
+    use stacks_wars_be::{
+        db::{season::SeasonRepository, user_wars_points::UserWarsPointsRepository},
+        season_rollover::roll_over_season,
+        state::SeasonRolloverConfig,
+    };
+
+    let app = crate::common::spawn_app_with_containers().await;
+    let repo = SeasonRepository::new(app.state.postgres.clone());
+
+    let ended = repo
+        .create_season(
+            "Season 1",
+            None,
+            &(chrono::Utc::now() - chrono::Duration::days(30))
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string(),
+            &(chrono::Utc::now() - chrono::Duration::minutes(1))
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string(),
+            false,
+        )
+        .await
+        .expect("create ended season");
+
+    // Alice ended on 40 points, Bob never scored
+    let factory = app.factory();
+    let points = UserWarsPointsRepository::new(app.state.postgres.clone());
+    let (alice, _) = factory.create_test_user(None).await.unwrap();
+    let (bob, _) = factory.create_test_user(None).await.unwrap();
+    points
+        .upsert_wars_points(alice, ended.id(), 40.0)
+        .await
+        .unwrap();
+    points
+        .upsert_wars_points(bob, ended.id(), 0.0)
+        .await
+        .unwrap();
+
+    let config = SeasonRolloverConfig {
+        duration_days: 14,
+        name_template: "Season {n}".to_string(),
+        carry_over: 0.25,
+    };
+
+    // Several instances run the job at once
+    let runs =
+        futures::future::join_all((0..5).map(|_| roll_over_season(&app.state, &config))).await;
+    let created: Vec<_> = runs
+        .into_iter()
+        .filter_map(|run| run.expect("rollover failed"))
+        .collect();
+    assert_eq!(created.len(), 1, "exactly one run creates the season");
+
+    let next = &created[0];
+    assert_eq!(next.name, "Season 2");
+    assert_eq!(next.start_date, ended.end_date);
+    assert_eq!(next.end_date - next.start_date, chrono::Duration::days(14));
+    assert_eq!(repo.count_seasons().await.unwrap(), 2);
+    assert_eq!(repo.get_current_season_id().await.unwrap(), next.id());
+
+    let finalized: bool =
+        sqlx::query_scalar("SELECT finalized_at IS NOT NULL FROM seasons WHERE id = $1")
+            .bind(ended.id())
+            .fetch_one(&app.state.postgres)
+            .await
+            .unwrap();
+    assert!(finalized, "the ended season is finalized");
+
+    // Points carry over once, however many runs raced
+    let carried = points.get_season_wars_points(next.id()).await.unwrap();
+    assert_eq!(carried.len(), 1);
+    assert_eq!(carried[0].user_id, alice);
+    assert_eq!(carried[0].points, 10.0);
+
+    // Running again while the new season is live does nothing
+    assert!(
+        roll_over_season(&app.state, &config)
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn failed_carry_over_leaves_no_season_behind() {
+    use stacks_wars_be::{
+        db::{season::SeasonRepository, user_wars_points::UserWarsPointsRepository},
+        season_rollover::roll_over_season,
+        state::SeasonRolloverConfig,
+    };
+
+    let app = crate::common::spawn_app_with_containers().await;
+    let repo = SeasonRepository::new(app.state.postgres.clone());
+
+    let ended = repo
+        .create_season(
+            "Season 1",
+            None,
+            &(chrono::Utc::now() - chrono::Duration::days(30))
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string(),
+            &(chrono::Utc::now() - chrono::Duration::minutes(1))
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string(),
+            false,
+        )
+        .await
+        .expect("create ended season");
+
+    let points = UserWarsPointsRepository::new(app.state.postgres.clone());
+    let (alice, _) = app.factory().create_test_user(None).await.unwrap();
+    points
+        .upsert_wars_points(alice, ended.id(), 40.0)
+        .await
+        .unwrap();
+
+    // Points can't be written while the trigger is in place
+    sqlx::raw_sql(
+        "CREATE FUNCTION refuse_points() RETURNS trigger AS $$
+        BEGIN RAISE EXCEPTION 'points are read-only'; END;
+        $$ LANGUAGE plpgsql;
+        CREATE TRIGGER refuse_points BEFORE INSERT OR UPDATE ON user_wars_points
+        FOR EACH ROW EXECUTE FUNCTION refuse_points();",
+    )
+    .execute(&app.state.postgres)
+    .await
+    .unwrap();
+
+    let config = SeasonRolloverConfig {
+        duration_days: 14,
+        name_template: "Season {n}".to_string(),
+        carry_over: 0.5,
+    };
+    assert!(roll_over_season(&app.state, &config).await.is_err());
+    assert_eq!(
+        repo.count_seasons().await.unwrap(),
+        1,
+        "the season isn't kept without its carry-over"
+    );
+
+    sqlx::raw_sql("DROP TRIGGER refuse_points ON user_wars_points; DROP FUNCTION refuse_points();")
+        .execute(&app.state.postgres)
+        .await
+        .unwrap();
+
+    // The next run starts the season, points and all
+    let next = roll_over_season(&app.state, &config)
+        .await
+        .unwrap()
+        .expect("season created on retry");
+    assert_eq!(next.name, "Season 2");
+    let carried = points.get_season_wars_points(next.id()).await.unwrap();
+    assert_eq!(carried.len(), 1);
+    assert_eq!(carried[0].points, 20.0);
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn rank_history_follows_snapshots_in_order() {
+    use stacks_wars_be::{
+        db::{rank_snapshot::RankSnapshotRepository, user_wars_points::UserWarsPointsRepository},
+        rank_snapshots::take_rank_snapshot,
+    };
+
+    let app = crate::common::spawn_app_with_containers().await;
+    let factory = app.factory();
+    let season_id = factory.create_test_season(None).await.unwrap() as i32;
+    let (alice, _) = factory.create_test_user(None).await.unwrap();
+    let (bob, _) = factory.create_test_user(None).await.unwrap();
+    let (late, _) = factory.create_test_user(None).await.unwrap();
+    let (unranked, _) = factory.create_test_user(None).await.unwrap();
+
+    let points = UserWarsPointsRepository::new(app.state.postgres.clone());
+    points
+        .upsert_wars_points(alice, season_id, 10.0)
+        .await
+        .unwrap();
+    points
+        .upsert_wars_points(bob, season_id, 20.0)
+        .await
+        .unwrap();
+
+    let period = std::time::Duration::from_secs(60);
+    assert_eq!(
+        take_rank_snapshot(&app.state, period).await.unwrap(),
+        Some(2)
+    );
+    // Another instance in the same interval skips it
+    assert_eq!(take_rank_snapshot(&app.state, period).await.unwrap(), None);
+
+    // Alice overtakes Bob, and a third player joins
+    points
+        .upsert_wars_points(alice, season_id, 30.0)
+        .await
+        .unwrap();
+    points
+        .upsert_wars_points(late, season_id, 5.0)
+        .await
+        .unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    RankSnapshotRepository::new(app.state.postgres.clone())
+        .record(season_id)
+        .await
+        .unwrap();
+
+    let history = |user_id: uuid::Uuid| {
+        let url = format!(
+            "{}/api/seasons/{}/users/{}/rank-history",
+            app.base_url, season_id, user_id
+        );
+        async move {
+            let resp = reqwest::get(url).await.expect("request failed");
+            assert_eq!(resp.status(), reqwest::StatusCode::OK);
+            resp.json::<Vec<serde_json::Value>>()
+                .await
+                .expect("invalid json")
+        }
+    };
+
+    let alice_history = history(alice).await;
+    assert_eq!(alice_history.len(), 2);
+    assert_eq!(alice_history[0]["rank"], 2);
+    assert_eq!(alice_history[0]["points"], 10.0);
+    assert_eq!(alice_history[1]["rank"], 1);
+    assert_eq!(alice_history[1]["points"], 30.0);
+    assert!(
+        alice_history[0]["takenAt"].as_str().unwrap()
+            < alice_history[1]["takenAt"].as_str().unwrap()
+    );
+
+    // Nothing from before the late joiner was ranked
+    let late_history = history(late).await;
+    assert_eq!(late_history.len(), 1);
+    assert_eq!(late_history[0]["rank"], 3);
+
+    assert!(history(unranked).await.is_empty());
+
+    let resp = reqwest::get(format!(
+        "{}/api/seasons/{}/users/{}/rank-history",
+        app.base_url,
+        season_id + 1000,
+        alice
+    ))
+    .await
+    .unwrap();
+    assert_eq!(resp.status(), reqwest::StatusCode::NOT_FOUND);
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn batch_awards_add_to_each_users_total() {
+    use stacks_wars_be::db::user_wars_points::UserWarsPointsRepository;
+
+    let app = crate::common::spawn_app_with_containers().await;
+    let factory = app.factory();
+    let season_id = factory.create_test_season(None).await.unwrap() as i32;
+    let points = UserWarsPointsRepository::new(app.state.postgres.clone());
+
+    // Some users start with points (so the award conflicts), some have no
+    // row yet
+    let starting = [Some(12.5), None, Some(0.0), None];
+    let awards = [[3.0, 4.0], [10.0, 0.5], [7.0, 7.0], [1.0, 2.0]];
+
+    let mut batch = Vec::new();
+    let mut expected = Vec::new();
+    for (start, user_awards) in starting.iter().zip(awards) {
+        let (user_id, _) = factory.create_test_user(None).await.unwrap();
+        if let Some(start) = start {
+            points
+                .upsert_wars_points(user_id, season_id, *start)
+                .await
+                .unwrap();
+        }
+        // Listing a user twice in one batch adds both awards
+        batch.extend(user_awards.map(|award| (user_id, award)));
+        expected.push((
+            user_id,
+            start.unwrap_or(0.0) + user_awards.iter().sum::<f64>(),
+        ));
+    }
+
+    let awarded = points.award_many(season_id, &batch).await.unwrap();
+    assert_eq!(awarded, expected.len() as u64, "each user counted once");
+
+    for (user_id, total) in expected {
+        let entry = points.get_wars_points(user_id, season_id).await.unwrap();
+        assert_eq!(entry.points, total);
+    }
+
+    // Empty batches write nothing
+    assert_eq!(points.award_many(season_id, &[]).await.unwrap(), 0);
+
+    app.stop().await;
+}
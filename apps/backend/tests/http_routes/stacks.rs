@@ -0,0 +1,78 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use stacks_wars_be::{db::token_info::TokenInfoCache, errors::AppError, models::stacks::TokenInfo};
+
+const CONTRACT: &str = "SP3NE50GEXFG9SZGTT51P40X2CKYSZ5CC4ZTZ7A2G.welshcorgicoin-token";
+
+/// Mock upstream that counts calls and reports the call number as the price
+async fn mock_upstream(calls: Arc<AtomicUsize>) -> Result<TokenInfo, AppError> {
+    let call = calls.fetch_add(1, Ordering::SeqCst) + 1;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    Ok(TokenInfo {
+        price: call as f64,
+        minimum_amount: 10.0 / call as f64,
+    })
+}
+
+async fn read(cache: &TokenInfoCache, calls: &Arc<AtomicUsize>) -> TokenInfo {
+    let calls = calls.clone();
+    cache
+        .get_or_fetch(CONTRACT, move || mock_upstream(calls))
+        .await
+        .expect("token info read failed")
+}
+
+#[tokio::test]
+async fn token_info_is_cached_until_ttl_expires() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let cache = TokenInfoCache::new(app.state.redis.clone())
+        .with_ttl(Duration::from_millis(500), Duration::from_millis(500));
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    let first = read(&cache, &calls).await;
+    let second = read(&cache, &calls).await;
+    assert_eq!(
+        calls.load(Ordering::SeqCst),
+        1,
+        "second read should hit cache"
+    );
+    assert_eq!(first.price, second.price);
+
+    // Past the fresh window the stale value is served while refreshing
+    tokio::time::sleep(Duration::from_millis(600)).await;
+    let stale = read(&cache, &calls).await;
+    assert_eq!(stale.price, 1.0);
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert_eq!(calls.load(Ordering::SeqCst), 2, "stale read should refresh");
+    let refreshed = read(&cache, &calls).await;
+    assert_eq!(refreshed.price, 2.0);
+
+    // Past both windows the entry is gone and the next read fetches inline
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    let expired = read(&cache, &calls).await;
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+    assert_eq!(expired.price, 3.0);
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn concurrent_token_info_misses_fetch_once() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let cache = TokenInfoCache::new(app.state.redis.clone());
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    let reads = (0..8).map(|_| read(&cache, &calls));
+    let results = futures::future::join_all(reads).await;
+
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    assert!(results.iter().all(|info| info.price == 1.0));
+
+    app.stop().await;
+}
@@ -0,0 +1,130 @@
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+async fn get_overview(app: &crate::common::TestApp) -> Value {
+    let resp = reqwest::get(format!("{}/api/stats/overview", app.base_url))
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status().as_u16(), 200);
+    resp.json().await.expect("invalid json")
+}
+
+/// Mark a lobby's game as played: `started_mins_ago` None leaves the start
+/// unrecorded
+async fn finish_lobby(
+    app: &crate::common::TestApp,
+    lobby_id: Uuid,
+    started_mins_ago: Option<i32>,
+    pot: f64,
+    token: &str,
+) {
+    sqlx::query(
+        "UPDATE lobbies
+         SET started_at = NOW() - make_interval(mins => $2),
+             finished_at = NOW() - INTERVAL '5 minutes',
+             current_amount = $3::NUMERIC,
+             token_symbol = $4
+         WHERE id = $1",
+    )
+    .bind(lobby_id)
+    .bind(started_mins_ago)
+    .bind(pot)
+    .bind(token)
+    .execute(&app.pg_pool)
+    .await
+    .expect("finish lobby");
+}
+
+async fn rate(app: &crate::common::TestApp, user_id: Uuid, game_id: Uuid, days_ago: i32) {
+    sqlx::query(
+        "INSERT INTO skill_ratings (user_id, game_id, rating, games_played, updated_at)
+         VALUES ($1, $2, 1200, 1, NOW() - make_interval(days => $3))",
+    )
+    .bind(user_id)
+    .bind(game_id)
+    .bind(days_ago)
+    .execute(&app.pg_pool)
+    .await
+    .expect("insert skill rating");
+}
+
+#[tokio::test]
+async fn stats_overview_aggregates_finished_games_and_is_cached() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let factory = app.factory();
+
+    // Nothing played yet
+    assert_eq!(
+        get_overview(&app).await,
+        json!({
+            "gamesPlayed": 0,
+            "potVolume": [],
+            "activePlayers": { "last24h": 0, "last7d": 0 },
+            "mostPopularGame": null,
+            "averageGameDurationSecs": 0.0,
+        })
+    );
+    let mut conn = app.state.redis.get().await.expect("redis");
+    let _: () = redis::AsyncCommands::del(
+        &mut *conn,
+        stacks_wars_be::models::RedisKey::stats_overview(),
+    )
+    .await
+    .expect("clear cache");
+    drop(conn);
+
+    let (alice, _) = factory.create_test_user(None).await.expect("user");
+    let (bob, _) = factory.create_test_user(None).await.expect("user");
+    let (carol, _) = factory.create_test_user(None).await.expect("user");
+    let alpha = factory
+        .create_test_game(alice, Some("stats-alpha"))
+        .await
+        .expect("game");
+    let beta = factory
+        .create_test_game(alice, Some("stats-beta"))
+        .await
+        .expect("game");
+
+    let lobby = |game_id, name| factory.create_test_lobby(alice, game_id, Some(name));
+    let (alpha_1, _) = lobby(alpha, "alpha one").await.expect("lobby");
+    let (alpha_2, _) = lobby(alpha, "alpha two").await.expect("lobby");
+    let (beta_1, _) = lobby(beta, "beta one").await.expect("lobby");
+    let (_alpha_live, _) = lobby(alpha, "alpha live").await.expect("lobby");
+
+    // Five and ten minute games; beta's start wasn't recorded
+    finish_lobby(&app, alpha_1, Some(10), 10.0, "STX").await;
+    finish_lobby(&app, alpha_2, Some(15), 5.5, "STX").await;
+    finish_lobby(&app, beta_1, None, 100.0, "WELSH").await;
+
+    rate(&app, alice, alpha, 0).await;
+    rate(&app, alice, beta, 0).await;
+    rate(&app, bob, alpha, 3).await;
+    rate(&app, carol, alpha, 10).await;
+
+    let overview = get_overview(&app).await;
+    assert_eq!(
+        overview,
+        json!({
+            "gamesPlayed": 3,
+            "potVolume": [
+                { "tokenSymbol": "WELSH", "volume": 100.0 },
+                { "tokenSymbol": "STX", "volume": 15.5 },
+            ],
+            "activePlayers": { "last24h": 1, "last7d": 2 },
+            "mostPopularGame": {
+                "gameId": alpha,
+                "name": "stats-alpha",
+                "path": "stats-alpha",
+                "gamesPlayed": 2,
+            },
+            "averageGameDurationSecs": 450.0,
+        })
+    );
+
+    // A game finishing within the cache window doesn't show until it lapses
+    let (beta_2, _) = lobby(beta, "beta two").await.expect("lobby");
+    finish_lobby(&app, beta_2, Some(6), 1.0, "STX").await;
+    assert_eq!(get_overview(&app).await, overview);
+
+    app.stop().await;
+}
@@ -6,7 +6,7 @@ async fn create_user() {
     let app = crate::common::spawn_app_with_containers().await;
     let client = reqwest::Client::new();
 
-    let payload = json!({ "walletAddress": "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7" });
+    let (wallet, payload) = app.registration([1u8; 32], None).await;
 
     let resp = client
         .post(format!("{}/api/user", app.base_url))
@@ -45,10 +45,7 @@ async fn create_user() {
         .get("email")
         .and_then(|v| v.as_str())
         .expect("missing email");
-    assert_eq!(
-        email,
-        "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7@stackswars.com"
-    );
+    assert_eq!(email, format!("{}@stackswars.com", wallet));
     let email_verified = body
         .get("emailVerified")
         .and_then(|v| v.as_bool())
@@ -63,10 +60,7 @@ async fn create_user_with_email() {
     let app = crate::common::spawn_app_with_containers().await;
     let client = reqwest::Client::new();
 
-    let payload = json!({
-        "walletAddress": "SP3K8BC0PPEVCV7NZ6QSRWPQ2JE9E5B6N3PA0KBR9",
-        "emailAddress": "test@example.com"
-    });
+    let (_, payload) = app.registration([2u8; 32], Some("test@example.com")).await;
 
     let resp = client
         .post(format!("{}/api/user", app.base_url))
@@ -104,10 +98,7 @@ async fn create_user_with_invalid_email() {
     let app = crate::common::spawn_app_with_containers().await;
     let client = reqwest::Client::new();
 
-    let payload = json!({
-        "walletAddress": "SP1HTBVD3JG9C05J7HBJTHGR0GGW7KXW28M5JS8QE",
-        "emailAddress": "invalid-email"
-    });
+    let (_, payload) = app.registration([3u8; 32], Some("invalid-email")).await;
 
     let resp = client
         .post(format!("{}/api/user", app.base_url))
@@ -123,6 +114,164 @@ async fn create_user_with_invalid_email() {
     app.stop().await;
 }
 
+#[tokio::test]
+async fn concurrent_registrations_for_one_wallet_create_one_user() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+
+    let (wallet, first) = app.registration([4u8; 32], None).await;
+    let (_, second) = app.registration([4u8; 32], None).await;
+    let register = |body: serde_json::Value| {
+        client
+            .post(format!("{}/api/user", app.base_url))
+            .json(&body)
+            .send()
+    };
+
+    let (first, second) = tokio::join!(register(first), register(second));
+    let (first, second) = (
+        first.expect("request failed"),
+        second.expect("request failed"),
+    );
+    assert_eq!(first.status(), 200);
+    assert_eq!(second.status(), 200);
+    let first: serde_json::Value = first.json().await.expect("json");
+    let second: serde_json::Value = second.json().await.expect("json");
+    assert_eq!(first["id"], second["id"]);
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users WHERE wallet_address = $1")
+        .bind(&wallet)
+        .fetch_one(&app.pg_pool)
+        .await
+        .expect("count users");
+    assert_eq!(count, 1);
+
+    // Another wallet can't take an email that's already in use
+    let email = format!("{}@stackswars.com", wallet);
+    let (_, other) = app.registration([5u8; 32], Some(&email)).await;
+    let resp = register(other).await.expect("request failed");
+    assert_eq!(resp.status(), 409);
+    assert!(
+        resp.text()
+            .await
+            .unwrap()
+            .contains("Email address already in use")
+    );
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn wallet_signature_login() {
+    use stacks_wars_be::auth::wallet_signature::{public_key, sign_message, stacks_address};
+
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+
+    let secret = [42u8; 32];
+    let key = public_key(&secret);
+    let wallet = stacks_address(&hex::decode(&key).unwrap(), 22);
+
+    let challenge = || async {
+        let resp = client
+            .get(format!("{}/api/auth/challenge", app.base_url))
+            .query(&[("wallet", wallet.as_str())])
+            .send()
+            .await
+            .expect("request failed");
+        assert_eq!(resp.status(), 200);
+        resp.json::<serde_json::Value>().await.expect("json")
+    };
+    let verify = |nonce: &str, signature: &str| {
+        client
+            .post(format!("{}/api/auth/verify", app.base_url))
+            .json(&json!({
+                "walletAddress": wallet,
+                "nonce": nonce,
+                "signature": signature,
+                "publicKey": key,
+            }))
+            .send()
+    };
+
+    // A valid signature signs the wallet in
+    let issued = challenge().await;
+    let nonce = issued["nonce"].as_str().unwrap();
+    let signature = sign_message(&secret, issued["message"].as_str().unwrap());
+    let resp = verify(nonce, &signature).await.expect("request failed");
+    assert_eq!(resp.status(), 200);
+    assert!(
+        resp.headers()["set-cookie"]
+            .to_str()
+            .unwrap()
+            .contains("auth_token=")
+    );
+    let user: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(user["walletAddress"], wallet);
+
+    // The same nonce can't be answered twice
+    let resp = verify(nonce, &signature).await.expect("request failed");
+    assert_eq!(resp.status(), 401);
+    assert!(resp.text().await.unwrap().contains("already used"));
+
+    // A signature over anything but the challenge is refused, and burns the nonce
+    let issued = challenge().await;
+    let nonce = issued["nonce"].as_str().unwrap();
+    let forged = sign_message(&secret, "Sign in to Stacks Wars");
+    let resp = verify(nonce, &forged).await.expect("request failed");
+    assert_eq!(resp.status(), 401);
+    assert!(resp.text().await.unwrap().contains("Invalid signature"));
+    let signature = sign_message(&secret, issued["message"].as_str().unwrap());
+    let resp = verify(nonce, &signature).await.expect("request failed");
+    assert_eq!(resp.status(), 401);
+
+    // Someone else's key can't sign for this wallet
+    let issued = challenge().await;
+    let other = [43u8; 32];
+    let resp = client
+        .post(format!("{}/api/auth/verify", app.base_url))
+        .json(&json!({
+            "walletAddress": wallet,
+            "nonce": issued["nonce"],
+            "signature": sign_message(&other, issued["message"].as_str().unwrap()),
+            "publicKey": public_key(&other),
+        }))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), 401);
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn create_user_requires_the_wallets_signature() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+    let register = |body: &serde_json::Value| {
+        client
+            .post(format!("{}/api/user", app.base_url))
+            .json(body)
+            .send()
+    };
+
+    // Naming a wallet alone no longer signs anyone in
+    let resp = register(&json!({ "walletAddress": "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7" }))
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), 400);
+
+    // Nor does another key's signature over the wallet's challenge
+    let (_, mut body) = app.registration([6u8; 32], None).await;
+    let (_, forged) = app.registration([7u8; 32], None).await;
+    body["publicKey"] = forged["publicKey"].clone();
+    let resp = register(&body).await.expect("request failed");
+    assert_eq!(resp.status(), 401);
+    assert!(resp.headers().get("set-cookie").is_none());
+
+    app.stop().await;
+}
+
 #[tokio::test]
 async fn update_or_set_username() {
     let app = crate::common::spawn_app_with_containers().await;
@@ -247,6 +396,76 @@ async fn update_user_profile() {
     app.stop().await;
 }
 
+#[tokio::test]
+async fn tokens_verify_by_key_id_across_rotation() {
+    use crate::common::{TEST_PREVIOUS_JWT_KEY_ID, TEST_PREVIOUS_JWT_SECRET};
+
+    let app = crate::common::spawn_app_with_containers().await;
+    let factory = app.factory();
+    let client = reqwest::Client::new();
+
+    let (user_id, _) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let now = chrono::Utc::now().timestamp();
+    let claims = stacks_wars_be::auth::jwt::Claims {
+        sub: user_id.to_string(),
+        wallet: "SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7".to_string(),
+        iat: now,
+        exp: now + 3600,
+        jti: uuid::Uuid::new_v4().to_string(),
+    };
+    let sign = |kid: &str, secret: &str| {
+        let header = jsonwebtoken::Header {
+            kid: Some(kid.to_string()),
+            ..Default::default()
+        };
+        jsonwebtoken::encode(
+            &header,
+            &claims,
+            &jsonwebtoken::EncodingKey::from_secret(secret.as_bytes()),
+        )
+        .expect("sign token")
+    };
+    let me = |token: String| {
+        client
+            .get(format!("{}/api/me", app.base_url))
+            .header("Cookie", factory.create_auth_cookie(&token))
+            .send()
+    };
+
+    // Signed before the rotation, still inside its lifetime
+    let resp = me(sign(TEST_PREVIOUS_JWT_KEY_ID, TEST_PREVIOUS_JWT_SECRET))
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), 200);
+
+    // A key id the server doesn't know is refused, whatever the signature
+    let resp = me(sign("retired-long-ago", TEST_PREVIOUS_JWT_SECRET))
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), 401);
+
+    // Tokens issued now carry the current key id
+    let (user, token) = stacks_wars_be::db::user::UserRepository::new(app.pg_pool.clone())
+        .create_user(
+            "SP1P72Z3704VMT3DMHPP2CB8TGQWGDBHD3RPR9GZS",
+            None,
+            &app.state.config.jwt_keys,
+        )
+        .await
+        .expect("create user");
+    let header = jsonwebtoken::decode_header(&token).expect("header");
+    assert_eq!(header.kid.as_deref(), Some(crate::common::TEST_JWT_KEY_ID));
+    let resp = me(token).await.expect("request failed");
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["id"], user.id().to_string());
+
+    app.stop().await;
+}
+
 #[tokio::test]
 async fn logout_user() {
     let app = crate::common::spawn_app_with_containers().await;
@@ -306,3 +525,374 @@ async fn logout_user() {
 
     app.stop().await;
 }
+
+#[tokio::test]
+async fn profile_includes_skill_ratings() {
+    use stacks_wars_be::db::skill_rating::SkillRatingRepository;
+
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+
+    let factory = app.factory();
+    let (winner_id, _) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let (loser_id, _) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let game_id = factory
+        .create_test_game(winner_id, Some("Rated Game"))
+        .await
+        .expect("create game failed");
+
+    let repo = SkillRatingRepository::new(factory.pg_pool.clone());
+    let updated = repo
+        .apply_results(game_id, &[(loser_id, 2), (winner_id, 1)])
+        .await
+        .expect("apply results failed");
+    assert!(updated[&winner_id] > updated[&loser_id]);
+
+    let resp = client
+        .get(format!("{}/api/user/{}", app.base_url, winner_id))
+        .send()
+        .await
+        .expect("request failed");
+    assert!(resp.status().is_success());
+    let body: serde_json::Value = resp.json().await.expect("invalid json");
+
+    let ratings = body["skillRatings"]
+        .as_array()
+        .expect("skillRatings missing");
+    assert_eq!(ratings.len(), 1);
+    assert_eq!(ratings[0]["gameId"], game_id.to_string());
+    assert_eq!(ratings[0]["gamesPlayed"], 1);
+    assert!(ratings[0]["rating"].as_f64().unwrap() > 1200.0);
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn points_badge_awarded_exactly_once() {
+    use stacks_wars_be::badges::{BadgeContext, BadgeEngine};
+    use stacks_wars_be::db::user_badge::UserBadgeRepository;
+
+    let app = crate::common::spawn_app_with_containers().await;
+    let factory = app.factory();
+    let (user_id, _) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let season_id = factory
+        .create_test_season(Some("badge-season"))
+        .await
+        .expect("create season failed") as i32;
+
+    let engine = BadgeEngine::default();
+    let repo = UserBadgeRepository::new(factory.pg_pool.clone());
+    let at = |season_points| BadgeContext {
+        season_points,
+        rank: 5,
+        win_streak: None,
+    };
+
+    let below = engine
+        .award(&repo, user_id, season_id, &at(90.0))
+        .await
+        .expect("award failed");
+    assert!(below.is_empty());
+
+    let crossed = engine
+        .award(&repo, user_id, season_id, &at(120.0))
+        .await
+        .expect("award failed");
+    assert_eq!(crossed, vec!["points-100".to_string()]);
+
+    // Re-evaluating above the threshold doesn't duplicate the badge
+    let again = engine
+        .award(&repo, user_id, season_id, &at(150.0))
+        .await
+        .expect("award failed");
+    assert!(again.is_empty());
+
+    let held = repo
+        .find_by_user(user_id, Some(season_id))
+        .await
+        .expect("read badges failed");
+    assert_eq!(held.len(), 1);
+    assert_eq!(held[0].badge_id, "points-100");
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn profile_includes_streaks() {
+    use stacks_wars_be::db::streak::StreakRepository;
+
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+    let factory = app.factory();
+    let (user_id, _) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+
+    let repo = StreakRepository::new(app.state.redis.clone());
+    let now = chrono::Utc::now().timestamp();
+    repo.record_game(user_id, true, now, 0).await.unwrap();
+    repo.record_game(user_id, true, now, 0).await.unwrap();
+    let streaks = repo.record_game(user_id, false, now, 0).await.unwrap();
+    assert_eq!(streaks.current_win_streak, 0);
+    assert_eq!(streaks.longest_win_streak, 2);
+
+    let resp = client
+        .get(format!("{}/api/user/{}", app.base_url, user_id))
+        .send()
+        .await
+        .expect("request failed");
+    assert!(resp.status().is_success());
+    let body: serde_json::Value = resp.json().await.expect("invalid json");
+
+    assert_eq!(body["streaks"]["currentWinStreak"], 0);
+    assert_eq!(body["streaks"]["longestWinStreak"], 2);
+    assert_eq!(body["streaks"]["dailyStreak"], 1);
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn export_includes_lobbies_and_chat() {
+    use stacks_wars_be::db::lobby_chat::LobbyChatRepository;
+
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+    let factory = app.factory();
+    let (user_id, token) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let game_id = factory
+        .create_test_game(user_id, None)
+        .await
+        .expect("create game failed");
+    let (lobby_id, _) = factory
+        .create_test_lobby(user_id, game_id, Some("export lobby"))
+        .await
+        .expect("create lobby failed");
+
+    LobbyChatRepository::new(app.state.redis.clone())
+        .create_message(lobby_id, user_id, "gg everyone", None)
+        .await
+        .expect("create message failed");
+
+    let resp = client
+        .get(format!("{}/api/users/me/export", app.base_url))
+        .header("Cookie", factory.create_auth_cookie(&token))
+        .send()
+        .await
+        .expect("request failed");
+    assert!(resp.status().is_success());
+    let disposition = resp.headers()["content-disposition"].to_str().unwrap();
+    assert!(disposition.starts_with("attachment"));
+
+    let body: serde_json::Value = resp.json().await.expect("invalid json");
+    assert_eq!(body["profile"]["id"], user_id.to_string());
+    assert_eq!(body["lobbiesCreated"][0]["id"], lobby_id.to_string());
+    assert_eq!(body["matchHistory"][0]["lobbyId"], lobby_id.to_string());
+    assert_eq!(body["chatMessages"][0]["content"], "gg everyone");
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn delete_account_anonymizes_but_keeps_standings() {
+    use stacks_wars_be::db::{
+        lobby_chat::LobbyChatRepository, player_state::PlayerStateRepository,
+    };
+
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+    let factory = app.factory();
+    let (user_id, token) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let game_id = factory
+        .create_test_game(user_id, None)
+        .await
+        .expect("create game failed");
+    let (lobby_id, _) = factory
+        .create_test_lobby(user_id, game_id, None)
+        .await
+        .expect("create lobby failed");
+
+    let player_repo = PlayerStateRepository::new(app.state.redis.clone());
+    player_repo
+        .set_result(
+            lobby_id,
+            user_id,
+            1,
+            Some(rust_decimal::Decimal::from(5)),
+            12.0,
+        )
+        .await
+        .expect("set result failed");
+    let chat_repo = LobbyChatRepository::new(app.state.redis.clone());
+    chat_repo
+        .create_message(lobby_id, user_id, "my wallet is SP123", None)
+        .await
+        .expect("create message failed");
+
+    let before: serde_json::Value = client
+        .get(format!("{}/api/user/{}", app.base_url, user_id))
+        .send()
+        .await
+        .expect("request failed")
+        .json()
+        .await
+        .expect("invalid json");
+
+    let resp = client
+        .delete(format!("{}/api/users/me", app.base_url))
+        .header("Cookie", factory.create_auth_cookie(&token))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), reqwest::StatusCode::NO_CONTENT);
+
+    // PII is gone from the user row
+    let after: serde_json::Value = client
+        .get(format!("{}/api/user/{}", app.base_url, user_id))
+        .send()
+        .await
+        .expect("request failed")
+        .json()
+        .await
+        .expect("invalid json");
+    assert_ne!(after["walletAddress"], before["walletAddress"]);
+    assert!(after["username"].is_null());
+    assert_eq!(after["displayName"], "[deleted user]");
+
+    // Past standing still resolves, now to the placeholder
+    let standing = player_repo
+        .get_state(lobby_id, user_id)
+        .await
+        .expect("player state should remain");
+    assert_eq!(standing.rank, Some(1));
+    assert_eq!(standing.wars_point, Some(12.0));
+    assert_eq!(standing.display_name.as_deref(), Some("[deleted user]"));
+    assert_eq!(standing.wallet_address, after["walletAddress"]);
+
+    let history = chat_repo
+        .get_history(lobby_id, None)
+        .await
+        .expect("history failed");
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].user_id, uuid::Uuid::nil());
+
+    // Every session is revoked
+    let resp = client
+        .get(format!("{}/api/me", app.base_url))
+        .header("Cookie", factory.create_auth_cookie(&token))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    // A second deletion finds the account already gone rather than failing
+    let mut conn = factory.pg_pool.acquire().await.expect("acquire connection");
+    let anonymized = stacks_wars_be::db::user::UserRepository::anonymize_user(&mut conn, user_id)
+        .await
+        .expect("repeat deletion should succeed");
+    assert!(!anonymized);
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn profile_stats_only_with_include() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+    let factory = app.factory();
+
+    let (user_id, _) = factory
+        .create_test_user(None)
+        .await
+        .expect("create user failed");
+    let game_id = factory
+        .create_test_game(user_id, Some("stats-game"))
+        .await
+        .expect("create game failed");
+
+    sqlx::query(
+        "INSERT INTO skill_ratings (user_id, game_id, rating, games_played, wins)
+        VALUES ($1, $2, 1250, 4, 1)",
+    )
+    .bind(user_id)
+    .bind(game_id)
+    .execute(&app.pg_pool)
+    .await
+    .expect("insert skill rating failed");
+
+    let resp = client
+        .get(format!("{}/api/user/{}", app.base_url, user_id))
+        .send()
+        .await
+        .expect("request failed");
+    assert!(resp.status().is_success());
+    let body: serde_json::Value = resp.json().await.expect("invalid json");
+    assert!(body.get("stats").is_none());
+
+    let resp = client
+        .get(format!(
+            "{}/api/user/{}?include=stats",
+            app.base_url, user_id
+        ))
+        .send()
+        .await
+        .expect("request failed");
+    assert!(resp.status().is_success());
+    let body: serde_json::Value = resp.json().await.expect("invalid json");
+    let stats = &body["stats"];
+    assert_eq!(stats["gamesPlayed"], 4);
+    assert_eq!(stats["wins"], 1);
+    assert_eq!(stats["winRate"], 0.25);
+    assert_eq!(
+        stats["avatarSeed"].as_str().expect("missing avatar seed"),
+        stacks_wars_be::models::user::avatar_seed(user_id)
+    );
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn create_user_reports_every_invalid_field() {
+    let app = crate::common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+
+    let resp = client
+        .post(format!("{}/api/user", app.base_url))
+        .json(&json!({
+            "walletAddress": "not-a-wallet",
+            "emailAddress": "invalid-email",
+            "nonce": "00",
+            "signature": "00",
+            "publicKey": "00"
+        }))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), 400);
+
+    let body: serde_json::Value = resp.json().await.expect("invalid json");
+    let fields: Vec<&str> = body["errors"]
+        .as_array()
+        .expect("errors array")
+        .iter()
+        .map(|e| e["field"].as_str().unwrap())
+        .collect();
+    assert_eq!(fields, ["walletAddress", "emailAddress"]);
+
+    app.stop().await;
+}
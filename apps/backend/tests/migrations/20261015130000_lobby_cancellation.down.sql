@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS lobby_refunds;
+
+-- Postgres can't drop an enum value; fold cancelled lobbies into finished instead
+UPDATE lobbies SET status = 'finished' WHERE status = 'cancelled';
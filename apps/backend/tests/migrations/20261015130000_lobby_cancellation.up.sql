@@ -0,0 +1,19 @@
+-- Idle lobby reaping: cancelled status and refund records for reaped lobbies
+ALTER TYPE lobby_status ADD VALUE IF NOT EXISTS 'cancelled';
+
+CREATE TABLE lobby_refunds (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    lobby_id UUID NOT NULL REFERENCES lobbies(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    wallet_address TEXT NOT NULL,
+    amount DOUBLE PRECISION NOT NULL CHECK (amount > 0),
+    token_symbol TEXT,
+    token_contract_id TEXT,
+    refund_tx_id TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    UNIQUE(lobby_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_lobby_refunds_user_id ON lobby_refunds(user_id);
+CREATE INDEX IF NOT EXISTS idx_lobby_refunds_pending ON lobby_refunds(created_at) WHERE refund_tx_id IS NULL;
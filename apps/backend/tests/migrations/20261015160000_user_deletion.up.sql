@@ -0,0 +1,3 @@
+-- Soft deletion: the row stays so season and game records keep resolving,
+-- but its personal data is overwritten with placeholders
+ALTER TABLE users ADD COLUMN deleted_at TIMESTAMP;
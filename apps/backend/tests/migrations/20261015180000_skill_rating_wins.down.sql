@@ -0,0 +1 @@
+ALTER TABLE skill_ratings DROP COLUMN IF EXISTS wins;
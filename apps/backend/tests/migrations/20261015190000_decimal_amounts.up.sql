@@ -0,0 +1,7 @@
+-- Store lobby stakes, pools and refunds as exact decimals (6 places = micro-units)
+ALTER TABLE lobbies
+    ALTER COLUMN entry_amount TYPE NUMERIC(30, 6) USING ROUND(entry_amount::NUMERIC, 6),
+    ALTER COLUMN current_amount TYPE NUMERIC(30, 6) USING ROUND(current_amount::NUMERIC, 6);
+
+ALTER TABLE lobby_refunds
+    ALTER COLUMN amount TYPE NUMERIC(30, 6) USING ROUND(amount::NUMERIC, 6);
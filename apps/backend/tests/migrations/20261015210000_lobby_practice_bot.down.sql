@@ -0,0 +1,2 @@
+ALTER TABLE lobbies DROP COLUMN IF EXISTS practice_bot;
+DROP TYPE IF EXISTS bot_difficulty;
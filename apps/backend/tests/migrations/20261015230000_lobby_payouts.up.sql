@@ -0,0 +1,4 @@
+-- Lobbies may pay their top finishers by their own table instead of the default
+CREATE TYPE payout_underfill AS ENUM ('top_finisher', 'pot');
+ALTER TABLE lobbies ADD COLUMN payout_percents INTEGER[];
+ALTER TABLE lobbies ADD COLUMN payout_underfill payout_underfill NOT NULL DEFAULT 'top_finisher';
@@ -0,0 +1,8 @@
+-- Canonical results of each ranked game and the server's signature over them
+CREATE TABLE signed_results (
+    lobby_id UUID PRIMARY KEY REFERENCES lobbies(id) ON DELETE CASCADE,
+    payload TEXT NOT NULL,
+    signature TEXT NOT NULL,
+    public_key TEXT NOT NULL,
+    signed_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,5 @@
+-- What a user may do beyond playing: moderators work the report queue,
+-- admins run the platform
+CREATE TYPE user_role AS ENUM ('user', 'moderator', 'admin');
+
+ALTER TABLE users ADD COLUMN role user_role NOT NULL DEFAULT 'user';
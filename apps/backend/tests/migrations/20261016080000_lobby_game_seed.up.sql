@@ -0,0 +1,2 @@
+-- Seed each lobby's game drew its randomness from, for replays and audits
+ALTER TABLE lobbies ADD COLUMN game_seed BIGINT;
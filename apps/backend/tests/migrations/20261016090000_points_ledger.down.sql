@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS wars_point_awards;
+DROP TABLE IF EXISTS lobby_results;
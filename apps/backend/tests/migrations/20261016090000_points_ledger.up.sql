@@ -0,0 +1,19 @@
+-- Final results of each ranked lobby, and the wars points each player was
+-- awarded for it, so awards can be recomputed and corrected later
+CREATE TABLE lobby_results (
+    lobby_id UUID PRIMARY KEY REFERENCES lobbies(id) ON DELETE CASCADE,
+    -- GameResults JSON, as the game produced it
+    results TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE wars_point_awards (
+    lobby_id UUID NOT NULL REFERENCES lobbies(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    season_id INT NOT NULL REFERENCES seasons(id) ON DELETE CASCADE,
+    game_id UUID REFERENCES games(id) ON DELETE SET NULL,
+    points DOUBLE PRECISION NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (lobby_id, user_id)
+);
@@ -125,7 +125,7 @@ async fn strict_rate_limit_applies_to_sensitive_routes() {
 
     app.reset_redis().await.unwrap();
     let mut prev: Option<usize> = None;
-    for i in 1..=31 {
+    for i in 1..=11 {
         let resp = client
             .post(format!("{}/api/user", app.base_url))
             .json(&json!({ "invalid": "payload" }))
@@ -133,16 +133,16 @@ async fn strict_rate_limit_applies_to_sensitive_routes() {
             .await
             .expect("request failed");
 
-        if i <= 30 {
+        if i <= 10 {
             // Handler may return client error for invalid payload; ensure headers present
             let (limit, remaining) = parse_headers(&resp);
-            assert_eq!(limit, 30);
+            assert_eq!(limit, 10);
             if let Some(p) = prev {
                 assert!(remaining <= p, "remaining did not decrease");
             }
             prev = Some(remaining);
         } else {
-            // 31st request should be rate limited
+            // 11th request should be rate limited
             assert_eq!(resp.status().as_u16(), 429, "expected 429 at request {}", i);
             let remaining: usize = resp
                 .headers()
@@ -153,9 +153,127 @@ async fn strict_rate_limit_applies_to_sensitive_routes() {
                 .parse()
                 .unwrap();
             assert_eq!(remaining, 0);
+            let retry_after: u64 = resp
+                .headers()
+                .get("retry-after")
+                .expect("missing retry-after header")
+                .to_str()
+                .unwrap()
+                .parse()
+                .unwrap();
+            assert!((1..=60).contains(&retry_after));
+        }
+    }
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn contract_routes_use_the_strict_tier() {
+    let app = common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+
+    app.reset_redis().await.unwrap();
+    for path in ["contract", "sponsored-contract"] {
+        let resp = client
+            .get(format!("{}/api/{}", app.base_url, path))
+            .send()
+            .await
+            .expect("request failed");
+        let (limit, _) = parse_headers(&resp);
+        assert_eq!(limit, 10, "/api/{} should be on the strict tier", path);
+    }
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn strict_tier_throttles_before_api_tier() {
+    let app = common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+
+    app.reset_redis().await.unwrap();
+
+    // Same client alternates between a strict route and a public read
+    let mut strict_limited_at = None;
+    for i in 1..=15 {
+        let strict = client
+            .post(format!("{}/api/user", app.base_url))
+            .json(&json!({ "invalid": "payload" }))
+            .send()
+            .await
+            .expect("request failed");
+        if strict.status().as_u16() == 429 && strict_limited_at.is_none() {
+            strict_limited_at = Some(i);
         }
+
+        let api = client
+            .get(format!("{}/api/games", app.base_url))
+            .send()
+            .await
+            .expect("request failed");
+        assert!(api.status().is_success(), "api tier throttled at {}", i);
+        let (limit, remaining) = parse_headers(&api);
+        assert_eq!(limit, 60);
+        assert_eq!(remaining, 60 - i, "tiers must count separately");
+    }
+
+    assert_eq!(strict_limited_at, Some(11));
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn repeated_auth_failures_earn_temporary_ban() {
+    let app = common::spawn_app_with_containers().await;
+    let client = reqwest::Client::new();
+    app.reset_redis().await.unwrap();
+
+    let bad_auth = || {
+        client
+            .get(format!("{}/api/me", app.base_url))
+            .header("Cookie", "auth_token=not-a-jwt")
+            .send()
+    };
+
+    for i in 1..=common::TEST_ABUSE_THRESHOLD {
+        let resp = bad_auth().await.expect("request failed");
+        assert_eq!(
+            resp.status().as_u16(),
+            401,
+            "request {} should reach auth",
+            i
+        );
     }
 
+    // Threshold crossed: the IP is banned from every route, even public ones
+    let resp = client
+        .get(format!("{}/api/games", app.base_url))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status().as_u16(), 403);
+    let retry_after: u64 = resp.headers()["retry-after"]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!((1..=common::TEST_ABUSE_BAN_SECS).contains(&retry_after));
+
+    // After the cooldown requests go through again
+    tokio::time::sleep(std::time::Duration::from_secs(
+        common::TEST_ABUSE_BAN_SECS + 1,
+    ))
+    .await;
+    let resp = bad_auth().await.expect("request failed");
+    assert_eq!(resp.status().as_u16(), 401);
+    let resp = client
+        .get(format!("{}/api/games", app.base_url))
+        .send()
+        .await
+        .expect("request failed");
+    assert!(resp.status().is_success());
+
     app.stop().await;
 }
 
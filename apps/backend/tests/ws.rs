@@ -7,6 +7,9 @@ mod common;
 #[path = "ws/message_wrapping_test.rs"]
 mod message_wrapping_test;
 
+#[path = "ws/leaderboard.rs"]
+mod leaderboard;
+
 #[path = "ws/lobby.rs"]
 mod lobby;
 
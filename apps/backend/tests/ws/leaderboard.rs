@@ -0,0 +1,112 @@
+// Live leaderboard WebSocket integration tests (/ws/leaderboard)
+// Run with: `cargo test --test ws leaderboard`
+
+use crate::common;
+
+use stacks_wars_be::{
+    db::user_wars_points::UserWarsPointsRepository,
+    games::common::{WarsPointContext, save_player_result},
+};
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_awarded_points_push_a_rank_update() {
+    let app = common::spawn_app_with_containers().await;
+    let factory = app.factory();
+    let season_id = factory.create_test_season(None).await.unwrap() as i32;
+    let (alice, _) = factory.create_test_user(None).await.unwrap();
+    let (bob, _) = factory.create_test_user(None).await.unwrap();
+    let game_id = factory.create_test_game(bob, None).await.unwrap();
+    let (lobby_id, _) = factory
+        .create_test_lobby(bob, game_id, Some("Leaderboard Lobby"))
+        .await
+        .unwrap();
+    factory.add_test_player(lobby_id, bob, true).await.unwrap();
+    factory
+        .add_test_player(lobby_id, alice, false)
+        .await
+        .unwrap();
+
+    UserWarsPointsRepository::new(app.state.postgres.clone())
+        .upsert_wars_points(bob, season_id, 1.0)
+        .await
+        .unwrap();
+
+    let mut overall = common::WsConnection::connect_to_leaderboard(&app.base_url, None)
+        .await
+        .expect("connect overall leaderboard");
+    let mut per_game = common::WsConnection::connect_to_leaderboard(&app.base_url, Some(game_id))
+        .await
+        .expect("connect game leaderboard");
+
+    let snapshot = overall
+        .recv_json_timeout(Duration::from_secs(5))
+        .await
+        .unwrap();
+    assert_eq!(snapshot["type"], "leaderboardSnapshot");
+    assert_eq!(snapshot["seasonId"], season_id);
+    assert_eq!(snapshot["entries"][0]["userId"], bob.to_string());
+    assert_eq!(snapshot["entries"][0]["rank"], 1);
+
+    let snapshot = per_game
+        .recv_json_timeout(Duration::from_secs(5))
+        .await
+        .unwrap();
+    assert_eq!(snapshot["type"], "leaderboardSnapshot");
+    assert_eq!(snapshot["gameId"], game_id.to_string());
+    assert_eq!(snapshot["entries"], serde_json::json!([]));
+
+    // Alice wins a game
+    let result = save_player_result(
+        &app.state,
+        lobby_id,
+        &WarsPointContext {
+            user_id: alice,
+            game_id,
+            rank: 1,
+            prize: None,
+            participants: 2,
+            entry_amount: None,
+            current_amount: None,
+            is_sponsored: false,
+            creator_id: Some(bob),
+            active_players: 2,
+        },
+    )
+    .await
+    .expect("save result");
+    assert!(
+        result.wars_point > 1.0,
+        "a win outscores bob's single point"
+    );
+
+    let update = overall
+        .recv_json_timeout(Duration::from_secs(5))
+        .await
+        .unwrap();
+    assert_eq!(update["type"], "leaderboardUpdated");
+    let changed = update["changed"].as_array().unwrap();
+    let alice_entry = changed
+        .iter()
+        .find(|e| e["userId"] == alice.to_string())
+        .expect("alice's entry is in the update");
+    assert_eq!(alice_entry["rank"], 1);
+    assert_eq!(alice_entry["points"], result.wars_point);
+    let bob_entry = changed
+        .iter()
+        .find(|e| e["userId"] == bob.to_string())
+        .expect("bob moved down a place");
+    assert_eq!(bob_entry["rank"], 2);
+
+    let update = per_game
+        .recv_json_timeout(Duration::from_secs(5))
+        .await
+        .unwrap();
+    assert_eq!(update["type"], "leaderboardUpdated");
+    assert_eq!(update["gameId"], game_id.to_string());
+    assert_eq!(update["changed"].as_array().unwrap().len(), 1);
+    assert_eq!(update["changed"][0]["userId"], alice.to_string());
+    assert_eq!(update["changed"][0]["rank"], 1);
+
+    app.stop().await;
+}
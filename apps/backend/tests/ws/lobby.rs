@@ -363,3 +363,452 @@ async fn test_lobby_list_unauthenticated_connection() {
     lobby_list_ws.close().await.ok();
     app.stop().await;
 }
+
+#[tokio::test]
+async fn test_protocol_version_negotiation() {
+    let app = common::spawn_app_with_containers().await;
+
+    // A supported subprotocol connects and gets the usual initial list
+    let mut supported = common::WsConnection::connect_to_lobby_with_protocol(
+        &app.base_url,
+        None,
+        None,
+        Some("stacks-wars.v1"),
+    )
+    .await
+    .expect("Failed to connect with a supported protocol");
+    let initial_list = supported
+        .recv_json_timeout(Duration::from_secs(2))
+        .await
+        .expect("Should receive initial lobby list");
+    assert_eq!(initial_list["type"], "lobbyList");
+
+    // An unsupported one is closed with a reason naming the supported versions
+    let mut unsupported = common::WsConnection::connect_to_lobby_with_protocol(
+        &app.base_url,
+        None,
+        None,
+        Some("stacks-wars.v99"),
+    )
+    .await
+    .expect("Handshake should complete before the close");
+    let (code, reason) = unsupported
+        .recv_close_timeout(Duration::from_secs(2))
+        .await
+        .expect("Should be closed");
+    assert_eq!(code, 4001);
+    assert!(reason.contains("stacks-wars.v99"), "reason: {}", reason);
+    assert!(
+        reason.contains("supported: stacks-wars.v1"),
+        "reason: {}",
+        reason
+    );
+
+    supported.close().await.ok();
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn test_protocol_v2_wraps_messages_in_an_envelope() {
+    let app = common::spawn_app_with_containers().await;
+
+    let mut ws = common::WsConnection::connect_to_lobby_with_protocol(
+        &app.base_url,
+        None,
+        None,
+        Some("stacks-wars.v2"),
+    )
+    .await
+    .expect("Failed to connect with protocol v2");
+    let initial_list = ws
+        .recv_json_timeout(Duration::from_secs(2))
+        .await
+        .expect("Should receive initial lobby list");
+
+    assert_eq!(initial_list["type"], "lobbyList");
+    assert!(initial_list["seq"].is_null());
+    assert!(initial_list["data"]["lobbyInfo"].is_array());
+    assert!(initial_list.get("lobbyInfo").is_none());
+
+    ws.close().await.ok();
+    app.stop().await;
+}
+
+/// Create a free public lobby through the API, returning its id and path
+async fn create_lobby_via_api(
+    app: &common::TestApp,
+    token: &str,
+    game_id: uuid::Uuid,
+    game_path: &str,
+) -> (String, String) {
+    let resp = reqwest::Client::new()
+        .post(format!("{}/api/lobby", app.base_url))
+        .header("Cookie", app.factory().create_auth_cookie(token))
+        .json(&json!({
+            "name": format!("{} lobby", game_path),
+            "entryAmount": 0.0,
+            "tokenSymbol": "STX",
+            "isPrivate": false,
+            "isSponsored": false,
+            "gameId": game_id,
+            "gamePath": game_path
+        }))
+        .send()
+        .await
+        .expect("create request failed");
+    assert_eq!(resp.status().as_u16(), 201);
+    let lobby: serde_json::Value = resp.json().await.expect("invalid json");
+    (
+        lobby["id"].as_str().expect("missing id").to_string(),
+        lobby["path"].as_str().expect("missing path").to_string(),
+    )
+}
+
+/// The lobby a lobby-list delta is about
+fn delta_lobby_id(msg: &serde_json::Value) -> Option<&str> {
+    msg.get("lobbyId")
+        .or_else(|| msg.pointer("/lobbyInfo/lobby/id"))
+        .and_then(|v| v.as_str())
+}
+
+/// Next lobby-list message about `lobby_id`, skipping ones about other lobbies
+async fn recv_lobby_delta(ws: &mut common::WsConnection, lobby_id: &str) -> serde_json::Value {
+    loop {
+        let msg = ws
+            .recv_json_timeout(Duration::from_secs(3))
+            .await
+            .expect("Should receive a lobby delta");
+        if delta_lobby_id(&msg) == Some(lobby_id) {
+            return msg;
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_lobby_list_receives_lifecycle_deltas_in_order() {
+    let app = common::spawn_app_with_containers().await;
+    let factory = app.factory();
+
+    let (creator_id, creator_token) = factory
+        .create_test_user(None)
+        .await
+        .expect("Failed to create creator");
+    let (_player_id, player_token) = factory
+        .create_test_user(None)
+        .await
+        .expect("Failed to create player");
+    let game_id = factory
+        .create_test_game(creator_id, Some("delta-game"))
+        .await
+        .expect("Failed to create game");
+
+    let mut lobby_list_ws = common::WsConnection::connect_to_lobby(&app.base_url, None, None)
+        .await
+        .expect("Failed to connect to lobby list");
+    let initial = lobby_list_ws
+        .recv_json_timeout(Duration::from_secs(2))
+        .await
+        .expect("Should receive initial lobby list");
+    assert_eq!(initial["type"], "lobbyList");
+
+    // Creating
+    let (lobby_id, lobby_path) =
+        create_lobby_via_api(&app, &creator_token, game_id, "delta-game").await;
+
+    let created = recv_lobby_delta(&mut lobby_list_ws, &lobby_id).await;
+    assert_eq!(created["type"], "lobbyCreated");
+    assert_eq!(created["lobbyInfo"]["lobby"]["participantCount"], 1);
+
+    // Filling
+    let mut player_ws =
+        common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &player_token)
+            .await
+            .expect("Player failed to connect");
+    player_ws
+        .send_json(&json!({ "type": "join" }))
+        .await
+        .expect("join failed");
+
+    let count = recv_lobby_delta(&mut lobby_list_ws, &lobby_id).await;
+    assert_eq!(count["type"], "lobbyPlayerCountChanged");
+    assert_eq!(count["participantCount"], 2);
+
+    // Finishing
+    stacks_wars_be::db::lobby::LobbyRepository::new(app.state.postgres.clone())
+        .update_status(
+            lobby_id.parse().unwrap(),
+            stacks_wars_be::models::LobbyStatus::Finished,
+            app.state.clone(),
+        )
+        .await
+        .expect("update status failed");
+
+    let updated = recv_lobby_delta(&mut lobby_list_ws, &lobby_id).await;
+    assert_eq!(updated["type"], "lobbyUpdated");
+    assert_eq!(updated["fields"], json!({ "status": "finished" }));
+
+    player_ws.close().await.ok();
+    lobby_list_ws.close().await.ok();
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn test_lobby_list_only_delivers_deltas_matching_the_filter() {
+    let app = common::spawn_app_with_containers().await;
+    let factory = app.factory();
+    let lobby_repo = stacks_wars_be::db::lobby::LobbyRepository::new(app.state.postgres.clone());
+
+    let (creator_id, creator_token) = factory
+        .create_test_user(None)
+        .await
+        .expect("Failed to create creator");
+    let game_a = factory
+        .create_test_game(creator_id, Some("filter-a"))
+        .await
+        .expect("Failed to create game A");
+    let game_b = factory
+        .create_test_game(creator_id, Some("filter-b"))
+        .await
+        .expect("Failed to create game B");
+
+    let mut ws = common::WsConnection::connect_to_lobby_with_query(
+        &app.base_url,
+        &format!("game_id={}", game_a),
+    )
+    .await
+    .expect("Failed to connect to lobby list");
+    let initial = ws
+        .recv_json_timeout(Duration::from_secs(2))
+        .await
+        .expect("Should receive initial lobby list");
+    assert_eq!(initial["type"], "lobbyList");
+
+    // A lobby for game B is created first, but only game A's reaches the client
+    let (lobby_b, _) = create_lobby_via_api(&app, &creator_token, game_b, "filter-b").await;
+    let (lobby_a, _) = create_lobby_via_api(&app, &creator_token, game_a, "filter-a").await;
+
+    let first = ws
+        .recv_json_timeout(Duration::from_secs(3))
+        .await
+        .expect("Should receive game A's lobby");
+    assert_eq!(first["type"], "lobbyCreated");
+    assert_eq!(first["lobbyInfo"]["lobby"]["id"], lobby_a.as_str());
+
+    // Switch to game B: the list is resent under the new filter
+    ws.send_json(&json!({ "type": "updateFilter", "gameId": game_b }))
+        .await
+        .expect("updateFilter failed");
+    let list = ws
+        .recv_json_timeout(Duration::from_secs(2))
+        .await
+        .expect("Should receive the refiltered list");
+    assert_eq!(list["type"], "lobbyList");
+    let ids: Vec<&str> = list["lobbyInfo"]
+        .as_array()
+        .expect("lobbyInfo array")
+        .iter()
+        .filter_map(|info| info["lobby"]["id"].as_str())
+        .collect();
+    assert_eq!(ids, vec![lobby_b.as_str()]);
+
+    // Now game A's changes are filtered out and game B's come through
+    lobby_repo
+        .update_name(lobby_a.parse().unwrap(), "renamed a", app.state.clone())
+        .await
+        .expect("rename A failed");
+    lobby_repo
+        .update_name(lobby_b.parse().unwrap(), "renamed b", app.state.clone())
+        .await
+        .expect("rename B failed");
+
+    let update = ws
+        .recv_json_timeout(Duration::from_secs(3))
+        .await
+        .expect("Should receive game B's update");
+    assert_eq!(update["type"], "lobbyUpdated");
+    assert_eq!(update["lobbyId"], lobby_b.as_str());
+    assert_eq!(update["fields"], json!({ "name": "renamed b" }));
+
+    ws.close().await.ok();
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn test_announcement_reaches_open_and_new_connections_until_it_expires() {
+    let app = common::spawn_app_with_containers().await;
+    let factory = app.factory();
+    let (_, admin_token) = factory
+        .create_test_user(Some(common::TEST_ADMIN_WALLET))
+        .await
+        .expect("create admin");
+
+    let mut open_ws = common::WsConnection::connect_to_lobby(&app.base_url, None, None)
+        .await
+        .expect("connect lobby list");
+    let initial = open_ws
+        .recv_json_timeout(Duration::from_secs(2))
+        .await
+        .expect("initial list");
+    assert_eq!(initial["type"], "lobbyList");
+
+    let expires_at = chrono::Utc::now() + chrono::Duration::seconds(3);
+    let resp = reqwest::Client::new()
+        .post(format!("{}/api/admin/announce", app.base_url))
+        .header("Cookie", factory.create_auth_cookie(&admin_token))
+        .json(&json!({
+            "level": "warning",
+            "message": "Maintenance at 18:00 UTC",
+            "expiresAt": expires_at,
+        }))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), reqwest::StatusCode::CREATED);
+
+    // Already connected
+    let pushed = open_ws
+        .recv_json_timeout(Duration::from_secs(2))
+        .await
+        .expect("announcement");
+    assert_eq!(pushed["type"], "announcement");
+    assert_eq!(pushed["level"], "warning");
+    assert_eq!(pushed["message"], "Maintenance at 18:00 UTC");
+
+    // Connecting afterwards, while it's active
+    let mut late_ws = common::WsConnection::connect_to_lobby(&app.base_url, None, None)
+        .await
+        .expect("connect lobby list");
+    let initial = late_ws
+        .recv_json_timeout(Duration::from_secs(2))
+        .await
+        .expect("initial list");
+    assert_eq!(initial["type"], "lobbyList");
+    let replayed = late_ws
+        .recv_json_timeout(Duration::from_secs(2))
+        .await
+        .expect("active announcement");
+    assert_eq!(replayed["type"], "announcement");
+    assert_eq!(replayed["id"], pushed["id"]);
+
+    // Once expired, new connections don't get it
+    tokio::time::sleep(Duration::from_secs(4)).await;
+    let mut expired_ws = common::WsConnection::connect_to_lobby(&app.base_url, None, None)
+        .await
+        .expect("connect lobby list");
+    let initial = expired_ws
+        .recv_json_timeout(Duration::from_secs(2))
+        .await
+        .expect("initial list");
+    assert_eq!(initial["type"], "lobbyList");
+    assert!(
+        expired_ws
+            .recv_json_timeout(Duration::from_millis(500))
+            .await
+            .is_err()
+    );
+
+    open_ws.close().await.ok();
+    late_ws.close().await.ok();
+    expired_ws.close().await.ok();
+    app.stop().await;
+}
+
+/// Minimal event stream reader: yields the JSON of each `data:` line
+struct SseStream {
+    response: reqwest::Response,
+    buffer: String,
+}
+
+impl SseStream {
+    async fn connect(url: String) -> Self {
+        let response = reqwest::get(url).await.expect("SSE request failed");
+        assert_eq!(response.status().as_u16(), 200);
+        assert!(
+            response.headers()["content-type"]
+                .to_str()
+                .unwrap()
+                .starts_with("text/event-stream")
+        );
+        Self {
+            response,
+            buffer: String::new(),
+        }
+    }
+
+    async fn recv_json(&mut self) -> serde_json::Value {
+        loop {
+            if let Some(end) = self.buffer.find('\n') {
+                let line: String = self.buffer.drain(..=end).collect();
+                if let Some(data) = line.trim_end().strip_prefix("data:") {
+                    return serde_json::from_str(data.trim_start()).expect("invalid event json");
+                }
+                continue;
+            }
+            let chunk = tokio::time::timeout(Duration::from_secs(3), self.response.chunk())
+                .await
+                .expect("Should receive an event")
+                .expect("SSE read failed")
+                .expect("SSE stream ended");
+            self.buffer.push_str(std::str::from_utf8(&chunk).unwrap());
+        }
+    }
+
+    async fn recv_lobby_delta(&mut self, lobby_id: &str) -> serde_json::Value {
+        loop {
+            let msg = self.recv_json().await;
+            if delta_lobby_id(&msg) == Some(lobby_id) {
+                return msg;
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_sse_lobby_list_mirrors_websocket_updates() {
+    let app = common::spawn_app_with_containers().await;
+    let factory = app.factory();
+
+    let (creator_id, _token) = factory
+        .create_test_user(None)
+        .await
+        .expect("Failed to create creator");
+    let game_id = factory
+        .create_test_game(creator_id, Some("sse-game"))
+        .await
+        .expect("Failed to create game");
+    let (lobby_id, _path) = factory
+        .create_test_lobby(creator_id, game_id, Some("sse lobby"))
+        .await
+        .expect("Failed to create lobby");
+    let lobby_id = lobby_id.to_string();
+
+    let mut ws = common::WsConnection::connect_to_lobby(&app.base_url, None, None)
+        .await
+        .expect("Failed to connect to lobby list");
+    let mut sse = SseStream::connect(format!("{}/sse/lobbies", app.base_url)).await;
+
+    let ws_list = ws
+        .recv_json_timeout(Duration::from_secs(2))
+        .await
+        .expect("Should receive initial lobby list");
+    let sse_list = sse.recv_json().await;
+    assert_eq!(sse_list["type"], "lobbyList");
+    assert_eq!(sse_list, ws_list);
+
+    stacks_wars_be::db::lobby::LobbyRepository::new(app.state.postgres.clone())
+        .update_status(
+            lobby_id.parse().unwrap(),
+            stacks_wars_be::models::LobbyStatus::Starting,
+            app.state.clone(),
+        )
+        .await
+        .expect("update status failed");
+
+    let ws_update = recv_lobby_delta(&mut ws, &lobby_id).await;
+    let sse_update = sse.recv_lobby_delta(&lobby_id).await;
+    assert_eq!(ws_update["type"], "lobbyUpdated");
+    assert_eq!(sse_update, ws_update);
+
+    ws.close().await.ok();
+    app.stop().await;
+}
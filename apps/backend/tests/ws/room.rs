@@ -331,3 +331,1809 @@ async fn test_lobby_need_at_least_min_players() {
     creator_ws.close().await.ok();
     app.stop().await;
 }
+
+/// Read messages until an `actionAck` arrives
+async fn recv_ack(ws: &mut common::WsConnection) -> serde_json::Value {
+    for _ in 0..10 {
+        let msg = ws
+            .recv_json_timeout(Duration::from_secs(2))
+            .await
+            .expect("Should receive action ack");
+        if msg.get("type").and_then(|v| v.as_str()) == Some("actionAck") {
+            return msg;
+        }
+    }
+    panic!("no actionAck received");
+}
+
+#[tokio::test]
+async fn test_game_actions_are_acked_to_sender() {
+    let app = common::spawn_app_with_containers().await;
+    let factory = app.factory();
+
+    let (alice, alice_token) = factory.create_test_user(None).await.expect("alice");
+    let (bob, bob_token) = factory.create_test_user(None).await.expect("bob");
+    let game_id = factory
+        .create_test_game(alice, Some("ack-game"))
+        .await
+        .expect("create game");
+    let (lobby_id, lobby_path) = factory
+        .create_test_lobby(alice, game_id, Some("Ack Test"))
+        .await
+        .expect("create lobby");
+    factory
+        .add_test_player(lobby_id, bob, false)
+        .await
+        .expect("add bob");
+
+    // Run a Lexi Wars engine for the lobby without the turn timer
+    let create_engine = app.state.game_registry[&stacks_wars_be::games::LEXI_WARS_GAME_ID];
+    let mut engine = create_engine(lobby_id, app.state.clone());
+    engine
+        .initialize(vec![alice, bob])
+        .await
+        .expect("initialize");
+    app.state.active_games.lock().await.insert(
+        lobby_id,
+        std::sync::Arc::new(tokio::sync::Mutex::new(engine)),
+    );
+
+    let mut alice_ws =
+        common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &alice_token)
+            .await
+            .expect("alice connect");
+    let mut bob_ws = common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &bob_token)
+        .await
+        .expect("bob connect");
+
+    // Alice goes first
+    alice_ws
+        .send_json(&json!({
+            "game": { "type": "submitWord", "word": "apple" },
+            "clientActionId": "alice-1"
+        }))
+        .await
+        .expect("send word");
+    let ack = recv_ack(&mut alice_ws).await;
+    assert_eq!(ack["clientActionId"], "alice-1");
+    assert_eq!(ack["accepted"], true);
+    assert!(ack["code"].is_null());
+
+    // Bob is out of turn
+    bob_ws
+        .send_json(&json!({
+            "game": { "type": "submitWord", "word": "river" },
+            "clientActionId": "bob-1"
+        }))
+        .await
+        .expect("send word");
+    let ack = recv_ack(&mut bob_ws).await;
+    assert_eq!(ack["clientActionId"], "bob-1");
+    assert_eq!(ack["accepted"], false);
+    assert_eq!(ack["code"], "NOT_YOUR_TURN");
+
+    alice_ws.close().await.ok();
+    bob_ws.close().await.ok();
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn test_game_past_max_duration_is_force_finished() {
+    let app = common::spawn_app_with_containers().await;
+    let factory = app.factory();
+
+    let (alice, alice_token) = factory.create_test_user(None).await.expect("alice");
+    let (bob, _) = factory.create_test_user(None).await.expect("bob");
+    let game_id = factory
+        .create_test_game(alice, Some("stalled-game"))
+        .await
+        .expect("create game");
+    let (lobby_id, lobby_path) = factory
+        .create_test_lobby(alice, game_id, Some("Stalled Game"))
+        .await
+        .expect("create lobby");
+    factory
+        .add_test_player(lobby_id, bob, false)
+        .await
+        .expect("add bob");
+
+    // No turn timer, so nothing ever ends the game on its own
+    let create_engine = app.state.game_registry[&stacks_wars_be::games::LEXI_WARS_GAME_ID];
+    let mut engine = create_engine(lobby_id, app.state.clone());
+    engine
+        .initialize(vec![alice, bob])
+        .await
+        .expect("initialize");
+    app.state.active_games.lock().await.insert(
+        lobby_id,
+        std::sync::Arc::new(tokio::sync::Mutex::new(engine)),
+    );
+
+    let mut alice_ws =
+        common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &alice_token)
+            .await
+            .expect("alice connect");
+
+    // Alice plays a word, so she leads when the clock runs out
+    alice_ws
+        .send_json(&json!({ "game": { "type": "submitWord", "word": "apple" } }))
+        .await
+        .expect("send word");
+
+    stacks_wars_be::ws::room::engine::enforce_max_duration(
+        app.state.clone(),
+        lobby_id,
+        Duration::from_secs(1),
+    )
+    .await;
+
+    let mut standings = None;
+    let mut timed_out = None;
+    while standings.is_none() || timed_out.is_none() {
+        let msg = alice_ws
+            .recv_json_timeout(Duration::from_secs(5))
+            .await
+            .expect("Should receive game end messages");
+        match msg.get("type").and_then(|v| v.as_str()) {
+            Some("finalStanding") => standings = Some(msg["standings"].clone()),
+            Some("gameTimedOut") => timed_out = Some(msg),
+            _ => {}
+        }
+    }
+    assert_eq!(timed_out.unwrap()["maxDurationSecs"], 1);
+
+    let standings = standings.unwrap();
+    let ranks: Vec<(String, u64)> = standings
+        .as_array()
+        .expect("standings array")
+        .iter()
+        .map(|p| {
+            (
+                p["userId"].as_str().unwrap().to_string(),
+                p["rank"].as_u64().expect("every player is ranked"),
+            )
+        })
+        .collect();
+    assert_eq!(
+        ranks,
+        vec![(alice.to_string(), 1), (bob.to_string(), 2)],
+        "Leader by score takes first place"
+    );
+
+    let engine = app.state.active_game(lobby_id).await.expect("engine");
+    let engine = engine.lock().await;
+    assert!(engine.is_finished());
+    let results = engine.get_results().await.expect("results").expect("final");
+    assert_eq!(results.rankings.len(), 2);
+    drop(engine);
+
+    alice_ws.close().await.ok();
+    app.stop().await;
+}
+
+/// Read until `count` messages of type `kind` arrive, returning them in order
+async fn recv_of_type(
+    ws: &mut common::WsConnection,
+    kind: &str,
+    count: usize,
+) -> Vec<serde_json::Value> {
+    let mut received = Vec::new();
+    while received.len() < count {
+        let msg = ws
+            .recv_json_timeout(Duration::from_secs(5))
+            .await
+            .expect("Should keep receiving room events");
+        if msg.get("type").and_then(|v| v.as_str()) == Some(kind) {
+            received.push(msg);
+        }
+    }
+    received
+}
+
+#[tokio::test]
+async fn test_claim_reward_is_held_to_the_strict_quota() {
+    let app = common::spawn_app_with_containers().await;
+    let factory = app.factory();
+
+    let (alice, alice_token) = factory.create_test_user(None).await.expect("alice");
+    let game_id = factory
+        .create_test_game(alice, Some("claim-limit-game"))
+        .await
+        .expect("create game");
+    let (_, lobby_path) = factory
+        .create_test_lobby(alice, game_id, Some("Claim Limit"))
+        .await
+        .expect("create lobby");
+
+    let mut alice_ws =
+        common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &alice_token)
+            .await
+            .expect("alice connects");
+    recv_of_type(&mut alice_ws, "lobbyBootstrap", 1).await;
+
+    // Alice has nothing to claim, but every attempt counts against the quota
+    let mut refusals = Vec::new();
+    for _ in 0..11 {
+        alice_ws
+            .send_json(&json!({ "type": "claimReward", "txId": "0xabc" }))
+            .await
+            .expect("alice claims");
+        refusals.extend(recv_of_type(&mut alice_ws, "error", 1).await);
+    }
+    assert!(refusals.iter().all(|e| e["code"] == "CLAIM_FAILED"));
+    let limited =
+        |e: &serde_json::Value| e["message"].as_str().unwrap_or("").contains("rate limit");
+    assert!(!refusals[..10].iter().any(limited), "{:?}", refusals);
+    assert!(limited(&refusals[10]), "{:?}", refusals[10]);
+
+    alice_ws.close().await.ok();
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn test_room_events_carry_increasing_seq_under_concurrent_broadcasts() {
+    let app = common::spawn_app_with_containers().await;
+    let factory = app.factory();
+
+    let (alice, alice_token) = factory.create_test_user(None).await.expect("alice");
+    let (bob, bob_token) = factory.create_test_user(None).await.expect("bob");
+    let game_id = factory
+        .create_test_game(alice, Some("seq-game"))
+        .await
+        .expect("create game");
+    let (lobby_id, lobby_path) = factory
+        .create_test_lobby(alice, game_id, Some("Seq Test"))
+        .await
+        .expect("create lobby");
+    factory
+        .add_test_player(lobby_id, bob, false)
+        .await
+        .expect("add bob");
+
+    let mut alice_ws =
+        common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &alice_token)
+            .await
+            .expect("alice connect");
+    let bootstrap = recv_of_type(&mut alice_ws, "lobbyBootstrap", 1).await;
+    assert_eq!(bootstrap[0]["seq"], 0);
+    let mut bob_ws = common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &bob_token)
+        .await
+        .expect("bob connect");
+    recv_of_type(&mut bob_ws, "lobbyBootstrap", 1).await;
+
+    // Fire events from many tasks at once
+    const EVENTS: u64 = 50;
+    let sends = (0..EVENTS).map(|n| {
+        let state = app.state.clone();
+        tokio::spawn(async move {
+            let event = stacks_wars_be::ws::core::message::JsonMessage::from(
+                json!({ "type": "seqProbe", "n": n }),
+            );
+            stacks_wars_be::ws::broadcast_room(&state, lobby_id, &event).await;
+        })
+    });
+    for send in sends.collect::<Vec<_>>() {
+        send.await.expect("broadcast task");
+    }
+
+    let alice_events = recv_of_type(&mut alice_ws, "seqProbe", EVENTS as usize).await;
+    let bob_events = recv_of_type(&mut bob_ws, "seqProbe", EVENTS as usize).await;
+
+    let seqs: Vec<u64> = alice_events
+        .iter()
+        .map(|e| e["seq"].as_u64().expect("every room event has a seq"))
+        .collect();
+    assert!(
+        seqs.windows(2).all(|w| w[1] > w[0]),
+        "seq must strictly increase: {:?}",
+        seqs
+    );
+
+    // Everyone sees the same events in the same order
+    let order = |events: &[serde_json::Value]| -> Vec<(u64, u64)> {
+        events
+            .iter()
+            .map(|e| (e["seq"].as_u64().unwrap(), e["n"].as_u64().unwrap()))
+            .collect()
+    };
+    assert_eq!(order(&alice_events), order(&bob_events));
+
+    // A resync snapshot picks up where the events left off
+    alice_ws
+        .send_json(&json!({ "type": "resync" }))
+        .await
+        .expect("send resync");
+    let resync = recv_of_type(&mut alice_ws, "lobbyBootstrap", 1).await;
+    assert_eq!(resync[0]["seq"].as_u64(), seqs.last().copied());
+
+    alice_ws.close().await.ok();
+    bob_ws.close().await.ok();
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn test_maintenance_blocks_new_lobbies_but_not_running_games() {
+    let app = common::spawn_app_with_containers().await;
+    let factory = app.factory();
+    let client = reqwest::Client::new();
+
+    let (_, admin_token) = factory
+        .create_test_user(Some(common::TEST_ADMIN_WALLET))
+        .await
+        .expect("create admin");
+    let (alice, alice_token) = factory.create_test_user(None).await.expect("alice");
+    let (bob, _) = factory.create_test_user(None).await.expect("bob");
+    let game_id = factory
+        .create_test_game(alice, Some("maintenance-game"))
+        .await
+        .expect("create game");
+    let (lobby_id, lobby_path) = factory
+        .create_test_lobby(alice, game_id, Some("Running Game"))
+        .await
+        .expect("create lobby");
+    factory
+        .add_test_player(lobby_id, bob, false)
+        .await
+        .expect("add bob");
+
+    let create_engine = app.state.game_registry[&stacks_wars_be::games::LEXI_WARS_GAME_ID];
+    let mut engine = create_engine(lobby_id, app.state.clone());
+    engine
+        .initialize(vec![alice, bob])
+        .await
+        .expect("initialize");
+    app.state.active_games.lock().await.insert(
+        lobby_id,
+        std::sync::Arc::new(tokio::sync::Mutex::new(engine)),
+    );
+
+    let resp = client
+        .put(format!("{}/api/admin/maintenance", app.base_url))
+        .header("Cookie", factory.create_auth_cookie(&admin_token))
+        .json(&json!({ "enabled": true, "message": "Back in 10 minutes" }))
+        .send()
+        .await
+        .expect("request failed");
+    assert!(resp.status().is_success());
+    let status: serde_json::Value = resp.json().await.expect("invalid json");
+    assert_eq!(status["enabled"], true);
+
+    // New lobbies are refused with the admin's message
+    let lobby_payload = json!({
+        "name": "new lobby",
+        "entryAmount": 0.0,
+        "isPrivate": false,
+        "isSponsored": false,
+        "gameId": game_id,
+        "gamePath": "maintenance-game"
+    });
+    let resp = client
+        .post(format!("{}/api/lobby", app.base_url))
+        .header("Cookie", factory.create_auth_cookie(&alice_token))
+        .json(&lobby_payload)
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status().as_u16(), 503);
+    assert_eq!(resp.text().await.expect("body"), "Back in 10 minutes");
+
+    // The game already running still accepts actions
+    let mut alice_ws =
+        common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &alice_token)
+            .await
+            .expect("alice connect");
+    alice_ws
+        .send_json(&json!({
+            "game": { "type": "submitWord", "word": "apple" },
+            "clientActionId": "alice-1"
+        }))
+        .await
+        .expect("send word");
+    let ack = recv_ack(&mut alice_ws).await;
+    assert_eq!(ack["accepted"], true);
+
+    // Turning it off lets lobbies through again
+    let resp = client
+        .put(format!("{}/api/admin/maintenance", app.base_url))
+        .header("Cookie", factory.create_auth_cookie(&admin_token))
+        .json(&json!({ "enabled": false }))
+        .send()
+        .await
+        .expect("request failed");
+    assert!(resp.status().is_success());
+    let resp = client
+        .post(format!("{}/api/lobby", app.base_url))
+        .header("Cookie", factory.create_auth_cookie(&alice_token))
+        .json(&lobby_payload)
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status().as_u16(), 201);
+
+    alice_ws.close().await.ok();
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn test_spectators_refused_when_lobby_disallows_them() {
+    let app = common::spawn_app_with_containers().await;
+    let factory = app.factory();
+
+    let (alice, alice_token) = factory.create_test_user(None).await.expect("alice");
+    let (bob, bob_token) = factory.create_test_user(None).await.expect("bob");
+    let (_, carol_token) = factory.create_test_user(None).await.expect("carol");
+    let game_id = factory
+        .create_test_game(alice, Some("spectator-game"))
+        .await
+        .expect("create game");
+    let (lobby_id, lobby_path) = factory
+        .create_test_lobby(alice, game_id, Some("No Spectators"))
+        .await
+        .expect("create lobby");
+    factory
+        .add_test_player(lobby_id, bob, false)
+        .await
+        .expect("add bob");
+
+    // Spectators are allowed by default, including anonymous ones
+    let mut anon_ws = common::WsConnection::connect_to_room(&app.base_url, &lobby_path, "")
+        .await
+        .expect("anonymous spectator should connect");
+    let bootstrap = anon_ws
+        .recv_json_timeout(Duration::from_secs(2))
+        .await
+        .expect("bootstrap");
+    assert_eq!(bootstrap["type"], "lobbyBootstrap");
+    assert_eq!(bootstrap["lobbyInfo"]["lobby"]["spectatorsAllowed"], true);
+    anon_ws.close().await.ok();
+
+    let repo = stacks_wars_be::db::lobby::LobbyRepository::new(app.pg_pool.clone());
+    repo.set_spectators_allowed(lobby_id, false, app.state.clone())
+        .await
+        .expect("disable spectators");
+
+    let refused = common::WsConnection::connect_to_room(&app.base_url, &lobby_path, "").await;
+    let err = refused
+        .err()
+        .expect("anonymous spectator should be refused");
+    assert!(err.to_string().contains("403"), "unexpected error: {}", err);
+
+    // While waiting, signed-in users may still come in to join
+    let carol_ws = common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &carol_token)
+        .await
+        .expect("carol connects while waiting");
+    carol_ws.close().await.ok();
+
+    repo.update_status(
+        lobby_id,
+        stacks_wars_be::models::LobbyStatus::InProgress,
+        app.state.clone(),
+    )
+    .await
+    .expect("start lobby");
+
+    let refused =
+        common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &carol_token).await;
+    assert!(
+        refused.is_err(),
+        "non-player should be refused once started"
+    );
+
+    for token in [&alice_token, &bob_token] {
+        let ws = common::WsConnection::connect_to_room(&app.base_url, &lobby_path, token)
+            .await
+            .expect("players still connect");
+        ws.close().await.ok();
+    }
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn test_spectators_closed_out_when_game_starts() {
+    let app = common::spawn_app_with_containers().await;
+    let factory = app.factory();
+    let client = reqwest::Client::new();
+    factory
+        .ensure_coinflip_game()
+        .await
+        .expect("Failed to ensure Coin Flip game");
+
+    let (alice, alice_token) = factory.create_test_user(None).await.expect("alice");
+    let (bob, _) = factory.create_test_user(None).await.expect("bob");
+    let (_, carol_token) = factory.create_test_user(None).await.expect("carol");
+    let (lobby_id, lobby_path) = factory
+        .create_test_lobby(alice, common::COINFLIP_GAME_ID, Some("Closed Doors"))
+        .await
+        .expect("create lobby");
+    factory
+        .add_test_player(lobby_id, bob, false)
+        .await
+        .expect("add bob");
+
+    // Only the creator may change the setting
+    let url = format!("{}/api/lobby/{}/spectators", app.base_url, lobby_id);
+    let resp = client
+        .patch(&url)
+        .header("Cookie", factory.create_auth_cookie(&carol_token))
+        .json(&json!({ "spectatorsAllowed": false }))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status().as_u16(), 403);
+
+    let resp = client
+        .patch(&url)
+        .header("Cookie", factory.create_auth_cookie(&alice_token))
+        .json(&json!({ "spectatorsAllowed": false }))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status().as_u16(), 200);
+    let lobby: serde_json::Value = resp.json().await.expect("lobby body");
+    assert_eq!(lobby["spectatorsAllowed"], false);
+
+    // Carol comes in while the lobby waits but never joins
+    let mut alice_ws =
+        common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &alice_token)
+            .await
+            .expect("alice connects");
+    let mut carol_ws =
+        common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &carol_token)
+            .await
+            .expect("carol connects while waiting");
+    let _ = recv_of_type(&mut carol_ws, "lobbyBootstrap", 1).await;
+
+    alice_ws
+        .send_json(&json!({ "type": "updateLobbyStatus", "status": "starting" }))
+        .await
+        .expect("start game");
+
+    let error = recv_of_type(&mut carol_ws, "error", 1).await.remove(0);
+    assert_eq!(error["code"], "SPECTATORS_NOT_ALLOWED");
+    carol_ws
+        .recv_close_timeout(Duration::from_secs(5))
+        .await
+        .expect("carol's connection should be closed");
+
+    alice_ws.close().await.ok();
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn test_reconnection_requires_membership() {
+    let app = common::spawn_app_with_containers().await;
+    let factory = app.factory();
+
+    let (alice, _alice_token) = factory.create_test_user(None).await.expect("alice");
+    let (bob, bob_token) = factory.create_test_user(None).await.expect("bob");
+    let (_, mallory_token) = factory.create_test_user(None).await.expect("mallory");
+    let game_id = factory
+        .create_test_game(alice, Some("reconnect-game"))
+        .await
+        .expect("create game");
+    let (lobby_id, lobby_path) = factory
+        .create_test_lobby(alice, game_id, Some("Members Only"))
+        .await
+        .expect("create lobby");
+    factory
+        .add_test_player(lobby_id, bob, false)
+        .await
+        .expect("add bob");
+
+    let repo = stacks_wars_be::db::lobby::LobbyRepository::new(app.pg_pool.clone());
+    repo.set_spectators_allowed(lobby_id, false, app.state.clone())
+        .await
+        .expect("disable spectators");
+    repo.update_status(
+        lobby_id,
+        stacks_wars_be::models::LobbyStatus::InProgress,
+        app.state.clone(),
+    )
+    .await
+    .expect("start lobby");
+
+    // A user who never joined can't attach to the running game
+    let refused =
+        common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &mallory_token).await;
+    let err = refused.err().expect("non-member should be refused");
+    assert!(err.to_string().contains("403"), "unexpected error: {}", err);
+
+    // A player dropping and coming back is attached again
+    for _ in 0..2 {
+        let mut bob_ws =
+            common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &bob_token)
+                .await
+                .expect("member reconnects");
+        let bootstrap = bob_ws
+            .recv_json_timeout(Duration::from_secs(2))
+            .await
+            .expect("bootstrap");
+        assert_eq!(bootstrap["type"], "lobbyBootstrap");
+        bob_ws.close().await.ok();
+    }
+
+    // Once finished, anyone may watch the replay
+    repo.update_status(
+        lobby_id,
+        stacks_wars_be::models::LobbyStatus::Finished,
+        app.state.clone(),
+    )
+    .await
+    .expect("finish lobby");
+    let mallory_ws =
+        common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &mallory_token)
+            .await
+            .expect("finished lobby can be spectated");
+    mallory_ws.close().await.ok();
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn test_rapid_lobby_changes_reach_the_room_once() {
+    let app = common::spawn_app_with_containers().await;
+    let factory = app.factory();
+
+    let (alice, alice_token) = factory.create_test_user(None).await.expect("alice");
+    let game_id = factory
+        .create_test_game(alice, Some("room-state-game"))
+        .await
+        .expect("create game");
+    let (lobby_id, lobby_path) = factory
+        .create_test_lobby(alice, game_id, Some("Busy Lobby"))
+        .await
+        .expect("create lobby");
+
+    let mut alice_ws =
+        common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &alice_token)
+            .await
+            .expect("alice connects");
+    let bootstrap = alice_ws
+        .recv_json_timeout(Duration::from_secs(2))
+        .await
+        .expect("bootstrap");
+    assert_eq!(bootstrap["type"], "lobbyBootstrap");
+
+    for _ in 0..10 {
+        stacks_wars_be::ws::broadcast_lobby_update(app.state.clone(), lobby_id).await;
+    }
+
+    let mut states = Vec::new();
+    while let Ok(msg) = alice_ws.recv_json_timeout(Duration::from_millis(500)).await {
+        if msg["type"] == "roomStateUpdated" {
+            states.push(msg);
+        }
+    }
+    assert_eq!(
+        states.len(),
+        1,
+        "expected one consolidated state: {:?}",
+        states
+    );
+    assert_eq!(states[0]["status"], "waiting");
+    // The roster rides along, and the state is ordered like any room event
+    assert!(states[0]["players"].is_array(), "{:?}", states[0]);
+    assert!(states[0]["seq"].is_u64(), "{:?}", states[0]);
+
+    alice_ws.close().await.ok();
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn test_typing_reaches_others_and_clears_when_stale() {
+    let app = common::spawn_app_with_containers().await;
+    let factory = app.factory();
+
+    let (alice, alice_token) = factory.create_test_user(None).await.expect("alice");
+    let (bob, bob_token) = factory.create_test_user(None).await.expect("bob");
+    let game_id = factory
+        .create_test_game(alice, Some("typing-game"))
+        .await
+        .expect("create game");
+    let (lobby_id, lobby_path) = factory
+        .create_test_lobby(alice, game_id, Some("Typing Test"))
+        .await
+        .expect("create lobby");
+    factory
+        .add_test_player(lobby_id, bob, false)
+        .await
+        .expect("add bob");
+
+    let mut alice_ws =
+        common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &alice_token)
+            .await
+            .expect("alice connect");
+    recv_of_type(&mut alice_ws, "lobbyBootstrap", 1).await;
+    let mut bob_ws = common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &bob_token)
+        .await
+        .expect("bob connect");
+    recv_of_type(&mut bob_ws, "lobbyBootstrap", 1).await;
+
+    alice_ws
+        .send_json(&json!({ "type": "typing", "isTyping": true }))
+        .await
+        .expect("send typing");
+
+    let started = recv_of_type(&mut bob_ws, "userTyping", 1).await;
+    assert_eq!(started[0]["userId"], alice.to_string());
+    assert_eq!(started[0]["isTyping"], true);
+    assert!(started[0].get("seq").is_none(), "typing is not sequenced");
+
+    // Alice goes quiet; the state clears on its own
+    let cleared = recv_of_type(&mut bob_ws, "userTyping", 1).await;
+    assert_eq!(cleared[0]["userId"], alice.to_string());
+    assert_eq!(cleared[0]["isTyping"], false);
+
+    // The sender never hears about their own typing
+    while let Ok(msg) = alice_ws.recv_json_timeout(Duration::from_millis(500)).await {
+        assert_ne!(msg["type"], "userTyping", "sender got {:?}", msg);
+    }
+
+    alice_ws.close().await.ok();
+    bob_ws.close().await.ok();
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn test_trusted_users_are_auto_approved_in_private_lobbies() {
+    let app = common::spawn_app_with_containers().await;
+    let factory = app.factory();
+
+    let (alice, alice_token) = factory.create_test_user(None).await.expect("alice");
+    let (bob, bob_token) = factory.create_test_user(None).await.expect("bob");
+    let (carol, carol_token) = factory.create_test_user(None).await.expect("carol");
+    let game_id = factory
+        .create_test_game(alice, Some("auto-approve-game"))
+        .await
+        .expect("create game");
+    let (lobby_id, lobby_path) = factory
+        .create_test_lobby(alice, game_id, Some("Auto Approve"))
+        .await
+        .expect("create lobby");
+
+    sqlx::query("UPDATE lobbies SET is_private = true WHERE id = $1")
+        .bind(lobby_id)
+        .execute(&app.pg_pool)
+        .await
+        .expect("make lobby private");
+    stacks_wars_be::db::lobby::LobbyRepository::new(app.pg_pool.clone())
+        .set_auto_approve_trust_threshold(lobby_id, Some(50.0), app.state.clone())
+        .await
+        .expect("set threshold");
+    // Bob is trusted; Carol keeps the default rating of 10
+    sqlx::query("UPDATE users SET trust_rating = 80 WHERE id = $1")
+        .bind(bob)
+        .execute(&app.pg_pool)
+        .await
+        .expect("raise bob's rating");
+
+    let mut alice_ws =
+        common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &alice_token)
+            .await
+            .expect("alice connect");
+    recv_of_type(&mut alice_ws, "lobbyBootstrap", 1).await;
+
+    let mut bob_ws = common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &bob_token)
+        .await
+        .expect("bob connect");
+    recv_of_type(&mut bob_ws, "lobbyBootstrap", 1).await;
+    bob_ws
+        .send_json(&json!({ "type": "joinRequest" }))
+        .await
+        .expect("bob requests");
+
+    let status = recv_of_type(&mut bob_ws, "joinRequestStatus", 1).await;
+    assert_eq!(status[0]["userId"], bob.to_string());
+    assert_eq!(status[0]["accepted"], true);
+
+    let jr_repo =
+        stacks_wars_be::db::join_request::JoinRequestRepository::new(app.state.redis.clone());
+    let bob_request = jr_repo.get(lobby_id, bob).await.expect("bob's request");
+    assert!(matches!(
+        bob_request.state,
+        stacks_wars_be::db::join_request::JoinRequestState::Accepted
+    ));
+    assert_eq!(
+        bob_request.reason,
+        Some(
+            stacks_wars_be::db::join_request::JoinRequestReason::TrustThreshold {
+                trust_rating: 80.0,
+                threshold: 50.0,
+            }
+        )
+    );
+
+    let mut carol_ws =
+        common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &carol_token)
+            .await
+            .expect("carol connect");
+    recv_of_type(&mut carol_ws, "lobbyBootstrap", 1).await;
+    carol_ws
+        .send_json(&json!({ "type": "joinRequest" }))
+        .await
+        .expect("carol requests");
+
+    // The creator sees Carol waiting in the queue
+    let updates = recv_of_type(&mut alice_ws, "joinRequestsUpdated", 2).await;
+    let requests = updates[1]["joinRequests"].as_array().expect("requests");
+    let carol_request = requests
+        .iter()
+        .find(|r| r["userId"] == carol.to_string())
+        .expect("carol listed");
+    assert_eq!(carol_request["state"], "pending");
+    assert!(carol_request.get("reason").is_none());
+
+    alice_ws.close().await.ok();
+    bob_ws.close().await.ok();
+    carol_ws.close().await.ok();
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn test_practice_lobby_refuses_joiners() {
+    let app = common::spawn_app_with_containers().await;
+    let factory = app.factory();
+
+    let (alice, _) = factory.create_test_user(None).await.expect("alice");
+    let (_, bob_token) = factory.create_test_user(None).await.expect("bob");
+    let game_id = factory
+        .create_test_game(alice, Some("practice-game"))
+        .await
+        .expect("create game");
+    let (lobby_id, lobby_path) = factory
+        .create_test_lobby(alice, game_id, Some("Practice"))
+        .await
+        .expect("create lobby");
+    sqlx::query("UPDATE lobbies SET practice_bot = 'easy' WHERE id = $1")
+        .bind(lobby_id)
+        .execute(&app.pg_pool)
+        .await
+        .expect("make lobby a practice lobby");
+
+    let mut bob_ws = common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &bob_token)
+        .await
+        .expect("bob connect");
+    recv_of_type(&mut bob_ws, "lobbyBootstrap", 1).await;
+
+    for kind in ["join", "joinRequest"] {
+        bob_ws
+            .send_json(&json!({ "type": kind }))
+            .await
+            .expect("bob asks to join");
+        let error = recv_of_type(&mut bob_ws, "error", 1).await.remove(0);
+        assert_eq!(error["code"], "JOIN_FAILED", "{}", kind);
+    }
+
+    let players =
+        stacks_wars_be::db::player_state::PlayerStateRepository::new(app.state.redis.clone())
+            .list_players(lobby_id)
+            .await
+            .expect("players");
+    assert_eq!(players.len(), 1, "only the creator is seated");
+
+    bob_ws.close().await.ok();
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn test_join_status_long_poll_resolves_on_approval() {
+    let app = common::spawn_app_with_containers().await;
+    let factory = app.factory();
+    let client = reqwest::Client::new();
+
+    let (alice, alice_token) = factory.create_test_user(None).await.expect("alice");
+    let (bob, bob_token) = factory.create_test_user(None).await.expect("bob");
+    let (_, carol_token) = factory.create_test_user(None).await.expect("carol");
+    let game_id = factory
+        .create_test_game(alice, Some("join-status-game"))
+        .await
+        .expect("create game");
+    let (lobby_id, lobby_path) = factory
+        .create_test_lobby(alice, game_id, Some("Join Status"))
+        .await
+        .expect("create lobby");
+    factory
+        .add_test_player(lobby_id, alice, true)
+        .await
+        .expect("add creator");
+    sqlx::query("UPDATE lobbies SET is_private = true WHERE id = $1")
+        .bind(lobby_id)
+        .execute(&app.pg_pool)
+        .await
+        .expect("make lobby private");
+
+    let join_status = |token: String, wait: Option<u64>| {
+        let request = client
+            .get(format!(
+                "{}/api/lobbies/{}/join-status",
+                app.base_url, lobby_id
+            ))
+            .header("Cookie", factory.create_auth_cookie(&token));
+        let request = match wait {
+            Some(wait) => request.query(&[("wait", wait)]),
+            None => request,
+        };
+        request.send()
+    };
+
+    let mut bob_ws = common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &bob_token)
+        .await
+        .expect("bob connect");
+    recv_of_type(&mut bob_ws, "lobbyBootstrap", 1).await;
+    bob_ws
+        .send_json(&json!({ "type": "joinRequest" }))
+        .await
+        .expect("bob requests");
+    let jr_repo =
+        stacks_wars_be::db::join_request::JoinRequestRepository::new(app.state.redis.clone());
+    for _ in 0..50 {
+        if jr_repo.get(lobby_id, bob).await.is_some() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    // Without `wait` the current state comes straight back
+    let resp = join_status(bob_token.clone(), None)
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status().as_u16(), 200);
+    let body: serde_json::Value = resp.json().await.expect("invalid json");
+    assert_eq!(body["lobbyId"], lobby_id.to_string());
+    assert_eq!(body["state"], "pending");
+
+    // Nobody without a request has a status
+    let resp = join_status(carol_token, None)
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status().as_u16(), 404);
+
+    // A long poll is held until Alice approves
+    let started = std::time::Instant::now();
+    let poll = tokio::spawn(join_status(bob_token.clone(), Some(20)));
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert!(!poll.is_finished(), "a pending request should be held");
+
+    let mut alice_ws =
+        common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &alice_token)
+            .await
+            .expect("alice connect");
+    recv_of_type(&mut alice_ws, "lobbyBootstrap", 1).await;
+    alice_ws
+        .send_json(&json!({ "type": "approveJoin", "userId": bob }))
+        .await
+        .expect("alice approves");
+
+    let resp = tokio::time::timeout(Duration::from_secs(10), poll)
+        .await
+        .expect("long poll should resolve on approval")
+        .expect("poll task")
+        .expect("request failed");
+    assert_eq!(resp.status().as_u16(), 200);
+    let body: serde_json::Value = resp.json().await.expect("invalid json");
+    assert_eq!(body["state"], "accepted");
+    assert!(started.elapsed() < Duration::from_secs(20));
+
+    // Decided requests aren't held
+    let resp = tokio::time::timeout(
+        Duration::from_secs(5),
+        join_status(bob_token.clone(), Some(20)),
+    )
+    .await
+    .expect("a decided request returns at once")
+    .expect("request failed");
+    let body: serde_json::Value = resp.json().await.expect("invalid json");
+    assert_eq!(body["state"], "accepted");
+
+    alice_ws.close().await.ok();
+    bob_ws.close().await.ok();
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn test_untrusted_users_cannot_join_paid_lobbies() {
+    let app = common::spawn_app_with_containers().await;
+    let factory = app.factory();
+
+    let (alice, _) = factory.create_test_user(None).await.expect("alice");
+    let (bob, bob_token) = factory.create_test_user(None).await.expect("bob");
+    let game_id = factory
+        .create_test_game(alice, Some("paid-trust-game"))
+        .await
+        .expect("create game");
+    let (paid_id, paid_path) = factory
+        .create_test_lobby(alice, game_id, Some("Paid"))
+        .await
+        .expect("create paid lobby");
+    let (free_id, free_path) = factory
+        .create_test_lobby(alice, game_id, Some("Free"))
+        .await
+        .expect("create free lobby");
+
+    sqlx::query("UPDATE lobbies SET entry_amount = 10, current_amount = 10 WHERE id = $1")
+        .bind(paid_id)
+        .execute(&app.pg_pool)
+        .await
+        .expect("set entry fee");
+    sqlx::query("UPDATE users SET trust_rating = 1 WHERE id = $1")
+        .bind(bob)
+        .execute(&app.pg_pool)
+        .await
+        .expect("lower bob's rating");
+
+    let players =
+        stacks_wars_be::db::player_state::PlayerStateRepository::new(app.state.redis.clone());
+
+    let mut paid_ws = common::WsConnection::connect_to_room(&app.base_url, &paid_path, &bob_token)
+        .await
+        .expect("bob connects to paid lobby");
+    recv_of_type(&mut paid_ws, "lobbyBootstrap", 1).await;
+    paid_ws
+        .send_json(&json!({ "type": "join" }))
+        .await
+        .expect("bob joins paid lobby");
+    let refused = recv_of_type(&mut paid_ws, "error", 1).await;
+    assert_eq!(refused[0]["code"], "JOIN_FAILED");
+    let min_trust = app.state.config.paid_lobby_trust.join;
+    assert!(
+        refused[0]["message"]
+            .as_str()
+            .unwrap()
+            .contains(&format!("at least {}", min_trust)),
+        "unexpected error: {}",
+        refused[0]
+    );
+    assert!(players.get_state(paid_id, bob).await.is_err());
+
+    let mut free_ws = common::WsConnection::connect_to_room(&app.base_url, &free_path, &bob_token)
+        .await
+        .expect("bob connects to free lobby");
+    recv_of_type(&mut free_ws, "lobbyBootstrap", 1).await;
+    free_ws
+        .send_json(&json!({ "type": "join" }))
+        .await
+        .expect("bob joins free lobby");
+    let joined = recv_of_type(&mut free_ws, "playerJoined", 1).await;
+    assert_eq!(joined[0]["player"]["userId"], bob.to_string());
+    assert!(players.get_state(free_id, bob).await.is_ok());
+
+    paid_ws.close().await.ok();
+    free_ws.close().await.ok();
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn test_active_lobbies_list_players_and_spectators() {
+    let app = common::spawn_app_with_containers().await;
+    let factory = app.factory();
+    let client = reqwest::Client::new();
+
+    let (alice, alice_token) = factory.create_test_user(None).await.expect("alice");
+    let (_, bob_token) = factory.create_test_user(None).await.expect("bob");
+    let game_id = factory
+        .create_test_game(alice, Some("active-game"))
+        .await
+        .expect("create game");
+    let (lobby_id, lobby_path) = factory
+        .create_test_lobby(alice, game_id, Some("Resume Me"))
+        .await
+        .expect("create lobby");
+
+    let active = |token: String| {
+        let client = client.clone();
+        let url = format!("{}/api/users/me/active", app.base_url);
+        let cookie = factory.create_auth_cookie(&token);
+        async move {
+            let resp = client
+                .get(url)
+                .header("Cookie", cookie)
+                .send()
+                .await
+                .expect("request failed");
+            assert_eq!(resp.status().as_u16(), 200);
+            resp.json::<serde_json::Value>().await.expect("json")
+        }
+    };
+
+    // Bob watches without joining
+    let mut bob_ws = common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &bob_token)
+        .await
+        .expect("bob connect");
+    recv_of_type(&mut bob_ws, "lobbyBootstrap", 1).await;
+
+    let alice_active = active(alice_token.clone()).await;
+    assert_eq!(alice_active.as_array().unwrap().len(), 1);
+    assert_eq!(alice_active[0]["lobby"]["id"], lobby_id.to_string());
+    assert_eq!(alice_active[0]["role"], "player");
+    assert_eq!(alice_active[0]["status"], "waiting");
+
+    let bob_active = active(bob_token.clone()).await;
+    assert_eq!(bob_active.as_array().unwrap().len(), 1);
+    assert_eq!(bob_active[0]["lobby"]["id"], lobby_id.to_string());
+    assert_eq!(bob_active[0]["role"], "spectator");
+
+    // Closing the socket stops the lobby being listed for Bob
+    bob_ws.close().await.ok();
+    let mut remaining = bob_active;
+    for _ in 0..20 {
+        remaining = active(bob_token.clone()).await;
+        if remaining.as_array().unwrap().is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert_eq!(remaining, json!([]));
+
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn test_spectator_predictions_lock_at_start_and_score_at_finish() {
+    let app = common::spawn_app_with_containers().await;
+    let factory = app.factory();
+
+    let (alice, alice_token) = factory.create_test_user(None).await.expect("alice");
+    let (bob, bob_token) = factory.create_test_user(None).await.expect("bob");
+    let (carol, carol_token) = factory.create_test_user(None).await.expect("carol");
+    let game_id = factory
+        .create_test_game(alice, Some("prediction-game"))
+        .await
+        .expect("create game");
+    let (lobby_id, lobby_path) = factory
+        .create_test_lobby(alice, game_id, Some("Call It"))
+        .await
+        .expect("create lobby");
+    let (dave, _) = factory.create_test_user(None).await.expect("dave");
+    factory
+        .add_test_player(lobby_id, dave, false)
+        .await
+        .expect("dave joins");
+
+    let mut alice_ws =
+        common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &alice_token)
+            .await
+            .expect("alice connect");
+    recv_of_type(&mut alice_ws, "lobbyBootstrap", 1).await;
+    let mut bob_ws = common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &bob_token)
+        .await
+        .expect("bob connect");
+    recv_of_type(&mut bob_ws, "lobbyBootstrap", 1).await;
+    let mut carol_ws =
+        common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &carol_token)
+            .await
+            .expect("carol connect");
+    recv_of_type(&mut carol_ws, "lobbyBootstrap", 1).await;
+
+    // Spectators predict; players can't
+    let predict = |winner: uuid::Uuid| json!({ "type": "predictWinner", "userId": winner });
+    bob_ws
+        .send_json(&predict(alice))
+        .await
+        .expect("bob predicts");
+    let recorded = recv_of_type(&mut bob_ws, "predictionRecorded", 1).await;
+    assert_eq!(recorded[0]["winnerId"], alice.to_string());
+    carol_ws
+        .send_json(&predict(dave))
+        .await
+        .expect("carol predicts");
+    recv_of_type(&mut carol_ws, "predictionRecorded", 1).await;
+
+    alice_ws
+        .send_json(&predict(alice))
+        .await
+        .expect("alice predicts");
+    let refused = recv_of_type(&mut alice_ws, "error", 1).await;
+    assert_eq!(refused[0]["code"], "PREDICTION_FAILED");
+
+    let repo = stacks_wars_be::db::prediction::PredictionRepository::new(app.state.redis.clone());
+    let predictions = repo.get_predictions(lobby_id).await.expect("predictions");
+    assert_eq!(predictions.len(), 2);
+    assert_eq!(predictions[&bob], alice);
+    assert_eq!(predictions[&carol], dave);
+
+    // Erin predicts, then takes a seat: her prediction no longer counts, and
+    // she can't make another
+    let (erin, erin_token) = factory.create_test_user(None).await.expect("erin");
+    let mut erin_ws =
+        common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &erin_token)
+            .await
+            .expect("erin connect");
+    recv_of_type(&mut erin_ws, "lobbyBootstrap", 1).await;
+    erin_ws
+        .send_json(&predict(dave))
+        .await
+        .expect("erin predicts");
+    recv_of_type(&mut erin_ws, "predictionRecorded", 1).await;
+    erin_ws
+        .send_json(&json!({ "type": "join" }))
+        .await
+        .expect("erin joins");
+    recv_of_type(&mut erin_ws, "playerJoined", 1).await;
+    let predictions = repo.get_predictions(lobby_id).await.expect("predictions");
+    assert!(!predictions.contains_key(&erin));
+
+    assert!(matches!(
+        repo.record(lobby_id, erin, dave).await,
+        Err(stacks_wars_be::errors::AppError::Forbidden(_))
+    ));
+    erin_ws
+        .send_json(&predict(dave))
+        .await
+        .expect("erin predicts again");
+    let refused = recv_of_type(&mut erin_ws, "error", 1).await;
+    assert_eq!(refused[0]["code"], "PREDICTION_FAILED");
+
+    // Once the game is under way, predictions are locked
+    stacks_wars_be::db::lobby_state::LobbyStateRepository::new(app.state.redis.clone())
+        .update_status(lobby_id, stacks_wars_be::models::LobbyStatus::InProgress)
+        .await
+        .expect("start game");
+    bob_ws
+        .send_json(&predict(dave))
+        .await
+        .expect("bob switches");
+    let locked = recv_of_type(&mut bob_ws, "error", 1).await;
+    assert!(
+        locked[0]["message"].as_str().unwrap().contains("locked"),
+        "unexpected error: {}",
+        locked[0]
+    );
+    let predictions = repo.get_predictions(lobby_id).await.expect("predictions");
+    assert_eq!(predictions[&bob], alice);
+
+    // Alice wins: Bob called it, Carol didn't
+    let results = stacks_wars_be::games::GameResults::from_ordered_players(vec![alice, dave]);
+    let outcome = stacks_wars_be::games::settle_predictions(&app.state, lobby_id, &results)
+        .await
+        .expect("settle");
+    assert_eq!(outcome.winner_id, Some(alice));
+    assert_eq!(outcome.correct, vec![bob]);
+    assert_eq!(outcome.accuracy, 0.5);
+
+    let settled = recv_of_type(&mut carol_ws, "predictionsSettled", 1).await;
+    assert_eq!(settled[0]["correct"], json!([bob.to_string()]));
+    assert_eq!(settled[0]["accuracy"], 0.5);
+
+    let points = app.state.config.prediction_points as u64;
+    assert_eq!(repo.get_points(bob).await.expect("bob points"), points);
+    assert_eq!(repo.get_points(carol).await.expect("carol points"), 0);
+
+    alice_ws.close().await.ok();
+    bob_ws.close().await.ok();
+    carol_ws.close().await.ok();
+    erin_ws.close().await.ok();
+    app.stop().await;
+}
+
+/// Game events received before the first `countdown`, which ends a turn's opening
+async fn game_events_until_countdown(ws: &mut common::WsConnection) -> Vec<serde_json::Value> {
+    let mut events = Vec::new();
+    loop {
+        let msg = ws
+            .recv_json_timeout(Duration::from_secs(5))
+            .await
+            .expect("Should receive the turn's events");
+        let Some(event) = msg.get("game") else {
+            continue;
+        };
+        if event["type"] == "countdown" {
+            return events;
+        }
+        events.push(event.clone());
+    }
+}
+
+#[tokio::test]
+async fn test_private_rule_reaches_its_player_but_not_spectators() {
+    let app = common::spawn_app_with_containers().await;
+    let factory = app.factory();
+
+    let (alice, alice_token) = factory.create_test_user(None).await.expect("alice");
+    let (bob, bob_token) = factory.create_test_user(None).await.expect("bob");
+    let (_, carol_token) = factory.create_test_user(None).await.expect("carol");
+    let game_id = factory
+        .create_test_game(alice, Some("private-rule-game"))
+        .await
+        .expect("create game");
+    let (lobby_id, lobby_path) = factory
+        .create_test_lobby(alice, game_id, Some("Private Rules"))
+        .await
+        .expect("create lobby");
+    factory
+        .add_test_player(lobby_id, bob, false)
+        .await
+        .expect("add bob");
+
+    let create_engine = app.state.game_registry[&stacks_wars_be::games::LEXI_WARS_GAME_ID];
+    let mut engine = create_engine(lobby_id, app.state.clone());
+    engine
+        .initialize(vec![alice, bob])
+        .await
+        .expect("initialize");
+    app.state.active_games.lock().await.insert(
+        lobby_id,
+        std::sync::Arc::new(tokio::sync::Mutex::new(engine)),
+    );
+    stacks_wars_be::db::lobby_state::LobbyStateRepository::new(app.state.redis.clone())
+        .update_status(lobby_id, stacks_wars_be::models::LobbyStatus::InProgress)
+        .await
+        .expect("start game");
+
+    // Joining mid-game: Alice (whose turn it is) gets her rule, Bob an empty
+    // one, and Carol, watching, no rule at all
+    let mut alice_ws =
+        common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &alice_token)
+            .await
+            .expect("alice connect");
+    let state = recv_of_type(&mut alice_ws, "gameState", 1).await;
+    assert!(state[0]["gameState"]["rule"]["rule"].is_object());
+    let mut bob_ws = common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &bob_token)
+        .await
+        .expect("bob connect");
+    let state = recv_of_type(&mut bob_ws, "gameState", 1).await;
+    assert!(state[0]["gameState"]["rule"]["rule"].is_null());
+    let mut carol_ws =
+        common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &carol_token)
+            .await
+            .expect("carol connect");
+    let state = recv_of_type(&mut carol_ws, "gameState", 1).await;
+    assert!(state[0]["gameState"]["turn"].is_object());
+    assert!(state[0]["gameState"].get("rule").is_none());
+
+    // First turn: only the players hear about the rule
+    app.state
+        .active_game(lobby_id)
+        .await
+        .expect("engine")
+        .lock()
+        .await
+        .start_loop(app.state.clone());
+
+    let is_rule = |event: &&serde_json::Value| event["type"] == "rule";
+    let alice_events = game_events_until_countdown(&mut alice_ws).await;
+    let rule = alice_events.iter().find(is_rule).expect("alice's rule");
+    assert!(rule["rule"].is_object());
+    let bob_events = game_events_until_countdown(&mut bob_ws).await;
+    let rule = bob_events.iter().find(is_rule).expect("bob's cleared rule");
+    assert!(rule["rule"].is_null());
+    let carol_events = game_events_until_countdown(&mut carol_ws).await;
+    assert!(carol_events.iter().any(|event| event["type"] == "turn"));
+    assert!(!carol_events.iter().any(|event| is_rule(&event)));
+
+    alice_ws.close().await.ok();
+    bob_ws.close().await.ok();
+    carol_ws.close().await.ok();
+    app.stop().await;
+}
+
+/// Put a lobby into Starting and run a ready check on it in the background
+fn start_ready_check(
+    app: &common::TestApp,
+    lobby_id: uuid::Uuid,
+    config: stacks_wars_be::state::ReadyUpConfig,
+) -> tokio::task::JoinHandle<bool> {
+    let state = app.state.clone();
+    tokio::spawn(async move {
+        stacks_wars_be::db::lobby_state::LobbyStateRepository::new(state.redis.clone())
+            .update_status(lobby_id, stacks_wars_be::models::LobbyStatus::Starting)
+            .await
+            .expect("starting");
+        stacks_wars_be::ws::room::ready::run_ready_check(&state, lobby_id, &config).await
+    })
+}
+
+#[tokio::test]
+async fn test_countdown_waits_until_every_player_is_ready() {
+    let app = common::spawn_app_with_containers().await;
+    let factory = app.factory();
+
+    let (alice, alice_token) = factory.create_test_user(None).await.expect("alice");
+    let (bob, bob_token) = factory.create_test_user(None).await.expect("bob");
+    let game_id = factory
+        .create_test_game(alice, Some("ready-up-game"))
+        .await
+        .expect("create game");
+    let (lobby_id, lobby_path) = factory
+        .create_test_lobby(alice, game_id, Some("Ready Up"))
+        .await
+        .expect("create lobby");
+    factory
+        .add_test_player(lobby_id, bob, false)
+        .await
+        .expect("add bob");
+
+    let mut alice_ws =
+        common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &alice_token)
+            .await
+            .expect("alice connect");
+    recv_of_type(&mut alice_ws, "lobbyBootstrap", 1).await;
+    let mut bob_ws = common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &bob_token)
+        .await
+        .expect("bob connect");
+    recv_of_type(&mut bob_ws, "lobbyBootstrap", 1).await;
+
+    let check = start_ready_check(
+        &app,
+        lobby_id,
+        stacks_wars_be::state::ReadyUpConfig {
+            timeout_secs: 30,
+            quorum_percent: 100,
+            unready: stacks_wars_be::state::UnreadyPolicy::Drop,
+        },
+    );
+    let opened = recv_of_type(&mut bob_ws, "readyCheck", 1).await;
+    assert_eq!(opened[0]["timeoutSecs"], 30);
+
+    // The creator asked for the start, so only Bob is outstanding
+    bob_ws
+        .send_json(&json!({ "type": "ready" }))
+        .await
+        .expect("bob readies");
+    let ready = recv_of_type(&mut alice_ws, "playerReady", 1).await;
+    assert_eq!(ready[0]["userId"], bob.to_string());
+    assert_eq!(ready[0]["readyCount"], 2);
+    assert_eq!(ready[0]["playerCount"], 2);
+
+    recv_of_type(&mut alice_ws, "allReady", 1).await;
+    let go_ahead = tokio::time::timeout(Duration::from_secs(5), check)
+        .await
+        .expect("check finishes well before the timeout")
+        .expect("check task");
+    assert!(go_ahead);
+
+    alice_ws.close().await.ok();
+    bob_ws.close().await.ok();
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn test_quorum_starts_after_timeout_and_drops_unready_players() {
+    let app = common::spawn_app_with_containers().await;
+    let factory = app.factory();
+
+    let (alice, alice_token) = factory.create_test_user(None).await.expect("alice");
+    let (bob, bob_token) = factory.create_test_user(None).await.expect("bob");
+    let (carol, _) = factory.create_test_user(None).await.expect("carol");
+    let game_id = factory
+        .create_test_game(alice, Some("ready-quorum-game"))
+        .await
+        .expect("create game");
+    let (lobby_id, lobby_path) = factory
+        .create_test_lobby(alice, game_id, Some("Ready Quorum"))
+        .await
+        .expect("create lobby");
+    for player in [bob, carol] {
+        factory
+            .add_test_player(lobby_id, player, false)
+            .await
+            .expect("add player");
+    }
+
+    let mut alice_ws =
+        common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &alice_token)
+            .await
+            .expect("alice connect");
+    recv_of_type(&mut alice_ws, "lobbyBootstrap", 1).await;
+    let mut bob_ws = common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &bob_token)
+        .await
+        .expect("bob connect");
+    recv_of_type(&mut bob_ws, "lobbyBootstrap", 1).await;
+
+    let check = start_ready_check(
+        &app,
+        lobby_id,
+        stacks_wars_be::state::ReadyUpConfig {
+            timeout_secs: 2,
+            quorum_percent: 50,
+            unready: stacks_wars_be::state::UnreadyPolicy::Drop,
+        },
+    );
+    recv_of_type(&mut bob_ws, "readyCheck", 1).await;
+    bob_ws
+        .send_json(&json!({ "type": "ready" }))
+        .await
+        .expect("bob readies");
+    recv_of_type(&mut alice_ws, "playerReady", 1).await;
+
+    // Carol never readies: 2 of 3 is a quorum, so the start goes ahead without her
+    let timed_out = recv_of_type(&mut alice_ws, "readyCheckTimedOut", 1).await;
+    assert_eq!(timed_out[0]["unready"], json!([carol.to_string()]));
+    assert_eq!(timed_out[0]["dropped"], true);
+    assert!(check.await.expect("check task"));
+
+    let players =
+        stacks_wars_be::db::player_state::PlayerStateRepository::new(app.state.redis.clone())
+            .get_player_ids(lobby_id)
+            .await
+            .expect("players");
+    assert_eq!(players.len(), 2);
+    assert!(!players.contains(&carol));
+
+    alice_ws.close().await.ok();
+    bob_ws.close().await.ok();
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn test_seeded_seating_is_announced_and_followed() {
+    let app = common::spawn_app_with_containers().await;
+    let factory = app.factory();
+
+    let (alice, _) = factory.create_test_user(None).await.expect("alice");
+    let (bob, _) = factory.create_test_user(None).await.expect("bob");
+    let (carol, _) = factory.create_test_user(None).await.expect("carol");
+    let game_id = factory
+        .create_test_game(alice, Some("seating-game"))
+        .await
+        .expect("create game");
+    let (lobby_id, _) = factory
+        .create_test_lobby(alice, game_id, Some("Seating Test"))
+        .await
+        .expect("create lobby");
+    for player in [bob, carol] {
+        factory
+            .add_test_player(lobby_id, player, false)
+            .await
+            .expect("add player");
+    }
+
+    let create_engine = app.state.game_registry[&stacks_wars_be::games::LEXI_WARS_GAME_ID];
+    let mut engine = create_engine(lobby_id, app.state.clone());
+    engine.set_seeds(7, 7).await;
+    let events = engine
+        .initialize(vec![alice, bob, carol])
+        .await
+        .expect("initialize");
+
+    // The seats come from the seed alone
+    let expected = stacks_wars_be::games::seat_players(vec![carol, bob, alice], 7);
+    let started = events
+        .iter()
+        .find(|e| e["type"] == "gameStarted")
+        .expect("gameStarted event");
+    assert_eq!(started["seatingSeed"], "7");
+    let seats: Vec<uuid::Uuid> = serde_json::from_value(started["seats"].clone()).expect("seats");
+    assert_eq!(seats, expected);
+
+    // ...and the game takes turns in that order
+    let bootstrap = engine.get_bootstrap().await.expect("bootstrap");
+    let turn_order: Vec<uuid::Uuid> = bootstrap["activePlayers"]
+        .as_array()
+        .expect("active players")
+        .iter()
+        .map(|p| serde_json::from_value(p["userId"].clone()).expect("user id"))
+        .collect();
+    assert_eq!(turn_order, expected);
+    assert_eq!(bootstrap["currentPlayer"]["userId"], json!(expected[0]));
+}
+
+#[tokio::test]
+async fn test_game_over_carries_the_stored_signed_results() {
+    use stacks_wars_be::auth::wallet_signature::stacks_address;
+
+    let app = common::spawn_app_with_containers().await;
+    let factory = app.factory();
+
+    // Settlement pays principals, so the players need real addresses
+    let (alice_wallet, bob_wallet) = (stacks_address(&[1; 33], 22), stacks_address(&[2; 33], 22));
+    let (alice, alice_token) = factory
+        .create_test_user(Some(&alice_wallet))
+        .await
+        .expect("alice");
+    let (bob, _) = factory
+        .create_test_user(Some(&bob_wallet))
+        .await
+        .expect("bob");
+    let game_id = factory
+        .create_test_game(alice, Some("signed-game"))
+        .await
+        .expect("create game");
+    let (lobby_id, lobby_path) = factory
+        .create_test_lobby(alice, game_id, Some("Settled Lobby"))
+        .await
+        .expect("create lobby");
+    factory
+        .add_test_player(lobby_id, bob, false)
+        .await
+        .expect("add bob");
+
+    let players =
+        stacks_wars_be::db::player_state::PlayerStateRepository::new(app.state.redis.clone());
+    players
+        .set_rank(lobby_id, alice, 1)
+        .await
+        .expect("rank alice");
+    players.set_rank(lobby_id, bob, 2).await.expect("rank bob");
+    stacks_wars_be::db::lobby::LobbyRepository::new(app.pg_pool.clone())
+        .update_status(
+            lobby_id,
+            stacks_wars_be::models::LobbyStatus::Finished,
+            app.state.clone(),
+        )
+        .await
+        .expect("finish lobby");
+
+    let results = stacks_wars_be::games::GameResults::from_ordered_players(vec![alice, bob]);
+    let wallets = [(alice, alice_wallet), (bob, bob_wallet)].into();
+    let signed = app
+        .state
+        .config
+        .result_signing_key
+        .as_ref()
+        .expect("test config signs results")
+        .sign_results(lobby_id, &results, &wallets)
+        .expect("sign results");
+    let repo = stacks_wars_be::db::signed_result::SignedResultRepository::new(app.pg_pool.clone());
+    repo.store(&signed).await.expect("store signed results");
+    assert_eq!(
+        repo.find_by_lobby(lobby_id).await.expect("load"),
+        Some(signed.clone())
+    );
+
+    let mut alice_ws =
+        common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &alice_token)
+            .await
+            .expect("alice connects");
+    let mut game_over = None;
+    while let Ok(msg) = alice_ws.recv_json_timeout(Duration::from_secs(2)).await {
+        if msg["type"] == "gameOver" {
+            game_over = Some(msg);
+            break;
+        }
+    }
+    let game_over = game_over.expect("gameOver on reconnecting to a finished game");
+    assert_eq!(game_over["rank"], 1);
+
+    let received: stacks_wars_be::models::SignedResults =
+        serde_json::from_value(game_over["signedResults"].clone()).expect("signed results");
+    assert_eq!(received, signed);
+    assert!(received.verify());
+
+    alice_ws.close().await.ok();
+    app.stop().await;
+}
+
+#[tokio::test]
+async fn test_anonymized_lobby_hides_players_from_spectators_until_finish() {
+    let app = common::spawn_app_with_containers().await;
+    let factory = app.factory();
+
+    let (alice, alice_token) = factory.create_test_user(None).await.expect("alice");
+    let (bob, _) = factory.create_test_user(None).await.expect("bob");
+    let (_, carol_token) = factory.create_test_user(None).await.expect("carol");
+    let game_id = factory
+        .create_test_game(alice, Some("anonymized-game"))
+        .await
+        .expect("create game");
+    let (lobby_id, lobby_path) = factory
+        .create_test_lobby(alice, game_id, Some("Masked Match"))
+        .await
+        .expect("create lobby");
+    factory
+        .add_test_player(lobby_id, bob, false)
+        .await
+        .expect("add bob");
+    sqlx::query("UPDATE lobbies SET anonymize_players = true WHERE id = $1")
+        .bind(lobby_id)
+        .execute(&app.pg_pool)
+        .await
+        .expect("anonymize lobby");
+
+    let create_engine = app.state.game_registry[&stacks_wars_be::games::LEXI_WARS_GAME_ID];
+    let mut engine = create_engine(lobby_id, app.state.clone());
+    engine
+        .initialize(vec![alice, bob])
+        .await
+        .expect("initialize");
+    app.state.active_games.lock().await.insert(
+        lobby_id,
+        std::sync::Arc::new(tokio::sync::Mutex::new(engine)),
+    );
+
+    let user_ids = |players: &serde_json::Value| -> std::collections::HashSet<String> {
+        players
+            .as_array()
+            .expect("players array")
+            .iter()
+            .map(|p| p["userId"].as_str().unwrap().to_string())
+            .collect()
+    };
+    let real_ids = [alice.to_string(), bob.to_string()];
+    let reveals_anyone = |msg: &serde_json::Value| {
+        let text = msg.to_string();
+        real_ids.iter().any(|id| text.contains(id))
+    };
+
+    let mut carol_ws =
+        common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &carol_token)
+            .await
+            .expect("carol connect");
+    let spectator_view = recv_of_type(&mut carol_ws, "lobbyBootstrap", 1).await;
+    assert_eq!(
+        user_ids(&spectator_view[0]["players"]),
+        ["Player A", "Player B"].map(String::from).into()
+    );
+    assert!(!reveals_anyone(&spectator_view[0]));
+
+    // Public reads mask the same way, from a table that outlives a restart
+    let base_url = app.base_url.clone();
+    let read = |path: String| {
+        let url = format!("{}/api/{}", base_url, path);
+        async move {
+            reqwest::get(url)
+                .await
+                .expect("public read")
+                .json::<serde_json::Value>()
+                .await
+                .expect("json body")
+        }
+    };
+    let public_view = read(format!("lobbies/{}", lobby_id)).await;
+    assert_eq!(
+        user_ids(&public_view["players"]),
+        ["Player A", "Player B"].map(String::from).into()
+    );
+    assert!(!reveals_anyone(&public_view));
+    let pseudonym_repo =
+        stacks_wars_be::db::pseudonym::PseudonymRepository::new(app.state.redis.clone());
+    let stored = pseudonym_repo
+        .get_table(lobby_id)
+        .await
+        .expect("read pseudonyms")
+        .expect("pseudonyms stored");
+    assert_eq!(stored.name_of(alice), Some("Player A"));
+    assert_eq!(stored.name_of(bob), Some("Player B"));
+
+    // Players see who they're up against
+    let mut alice_ws =
+        common::WsConnection::connect_to_room(&app.base_url, &lobby_path, &alice_token)
+            .await
+            .expect("alice connect");
+    let player_view = recv_of_type(&mut alice_ws, "lobbyBootstrap", 1).await;
+    assert_eq!(
+        user_ids(&player_view[0]["players"]),
+        real_ids.clone().into()
+    );
+
+    alice_ws
+        .send_json(&json!({ "game": { "type": "submitWord", "word": "apple" } }))
+        .await
+        .expect("send word");
+    assert!(!reveals_anyone(
+        &read(format!("lobby/{}/words", lobby_id)).await
+    ));
+    assert!(!reveals_anyone(
+        &read(format!("lobbies/{}/replay", lobby_id)).await
+    ));
+    stacks_wars_be::ws::room::engine::enforce_max_duration(
+        app.state.clone(),
+        lobby_id,
+        Duration::from_secs(1),
+    )
+    .await;
+
+    // Everything before the standings is masked; the standings reveal the players
+    let standings = loop {
+        let msg = carol_ws
+            .recv_json_timeout(Duration::from_secs(5))
+            .await
+            .expect("Should receive game events");
+        if msg["type"] == "finalStanding" {
+            break msg["standings"].clone();
+        }
+        assert!(!reveals_anyone(&msg), "spectator saw a player: {}", msg);
+    };
+    assert_eq!(user_ids(&standings), real_ids.clone().into());
+    assert!(
+        pseudonym_repo
+            .get_table(lobby_id)
+            .await
+            .expect("read pseudonyms")
+            .is_none()
+    );
+
+    alice_ws.close().await.ok();
+    carol_ws.close().await.ok();
+    app.stop().await;
+}